@@ -0,0 +1,46 @@
+//! Records the git commit and build time for `GET /api/version`.
+//!
+//! `GIT_COMMIT` and `BUILD_TIMESTAMP`, when set, take precedence, for builds
+//! outside a git checkout such as container images.
+
+use std::{env, path::Path, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=BUILD_TIMESTAMP");
+
+    let git_commit = env::var("GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.trim().is_empty())
+        .or_else(|| git(&["rev-parse", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let built_at = env::var("BUILD_TIMESTAMP")
+        .ok()
+        .filter(|timestamp| !timestamp.trim().is_empty())
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+
+    // Rebuild when a commit moves HEAD or the branch it points at
+    for name in ["HEAD", "refs/heads"] {
+        if let Some(path) = git(&["rev-parse", "--git-path", name]) {
+            if Path::new(&path).exists() {
+                println!("cargo:rerun-if-changed={}", path);
+            }
+        }
+    }
+
+    println!("cargo:rustc-env=EASM_GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=EASM_BUILD_TIMESTAMP={}", built_at);
+}
+
+/// Trimmed output of a successful git command run in this crate
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|out| out.trim().to_string())
+        .filter(|out| !out.is_empty())
+}
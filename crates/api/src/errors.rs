@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -10,6 +10,8 @@ use shared::errors::{
 };
 use tracing;
 
+use crate::middleware::current_request_id;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error(transparent)]
@@ -24,6 +26,9 @@ pub enum ApiError {
     #[error("Token expired")]
     TokenExpired,
 
+    #[error("Token revoked")]
+    TokenRevoked,
+
     #[error("Unauthorized")]
     Unauthorized,
 
@@ -39,9 +44,16 @@ pub enum ApiError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Request body too large")]
+    PayloadTooLarge,
+
     #[error("Rate limit exceeded")]
     RateLimited,
 
+    /// Too many failed logins; holds the seconds until the lockout ends
+    #[error("Too many failed login attempts. Try again in {0} seconds")]
+    AccountLocked(u64),
+
     #[error("Internal server error: {0}")]
     InternalServerError(String),
 }
@@ -160,6 +172,7 @@ impl IntoResponse for ApiError {
             ),
             ApiError::InvalidToken => (StatusCode::UNAUTHORIZED, self.to_string(), "INVALID_TOKEN"),
             ApiError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string(), "TOKEN_EXPIRED"),
+            ApiError::TokenRevoked => (StatusCode::UNAUTHORIZED, self.to_string(), "TOKEN_REVOKED"),
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string(), "UNAUTHORIZED"),
             ApiError::Forbidden => (StatusCode::FORBIDDEN, self.to_string(), "FORBIDDEN"),
             ApiError::NotFound(_msg) => (StatusCode::NOT_FOUND, self.to_string(), "NOT_FOUND"),
@@ -167,11 +180,21 @@ impl IntoResponse for ApiError {
                 (StatusCode::BAD_REQUEST, self.to_string(), "BAD_REQUEST")
             }
             ApiError::Conflict(_msg) => (StatusCode::CONFLICT, self.to_string(), "CONFLICT"),
+            ApiError::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                self.to_string(),
+                "PAYLOAD_TOO_LARGE",
+            ),
             ApiError::RateLimited => (
                 StatusCode::TOO_MANY_REQUESTS,
                 self.to_string(),
                 "RATE_LIMITED",
             ),
+            ApiError::AccountLocked(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                self.to_string(),
+                "ACCOUNT_LOCKED",
+            ),
             ApiError::InternalServerError(_) => {
                 tracing::error!(error = ?self, "Internal server error occurred");
                 (
@@ -188,15 +211,27 @@ impl IntoResponse for ApiError {
             message
         };
 
-        let body = Json(json!({
+        let mut body = json!({
             "error": {
                 "message": response_message,
                 "code": error_code,
                 "status": status.as_u16()
             }
-        }));
+        });
 
-        (status, body).into_response()
+        // Let clients quote the request ID when reporting the error
+        if let Some(request_id) = current_request_id() {
+            body["error"]["request_id"] = json!(request_id.0);
+        }
+        let body = Json(body);
+
+        let mut response = (status, body).into_response();
+        if let ApiError::AccountLocked(retry_after_secs) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
     }
 }
 
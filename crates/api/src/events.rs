@@ -0,0 +1,79 @@
+//! Fan resource events out to the server-sent event streams of this
+//! instance.
+//!
+//! Events arrive over Postgres `NOTIFY`, published by the repositories of
+//! whichever process made the change, and are rebroadcast to every open
+//! stream. Each stream only passes on its own organization's events.
+
+use std::time::Duration;
+
+use infrastructure::events::RESOURCE_EVENTS_CHANNEL;
+use shared::{errors::Result, types::ResourceEvent};
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::{sync::broadcast, task::JoinHandle};
+
+/// Events held for a stream that falls behind before it starts missing them
+const EVENT_BUFFER: usize = 256;
+
+/// Wait before listening again after the database connection drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Broadcast channel of resource events
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ResourceEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    /// Send an event to every current subscriber
+    pub fn publish(&self, event: ResourceEvent) {
+        // Only fails when nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ResourceEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Start publishing the events announced on the database. Listening has
+    /// begun by the time this returns, so no later change is missed.
+    pub async fn listen(&self, pool: &PgPool) -> Result<JoinHandle<()>> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(RESOURCE_EVENTS_CHANNEL).await?;
+
+        let bus = self.clone();
+        Ok(tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        match serde_json::from_str::<ResourceEvent>(notification.payload()) {
+                            Ok(event) => bus.publish(event),
+                            Err(e) => tracing::warn!(
+                                "Ignoring malformed resource event {:?}: {}",
+                                notification.payload(),
+                                e
+                            ),
+                        }
+                    }
+                    Err(e) => {
+                        // The next recv reconnects and listens again
+                        tracing::warn!("Lost the resource event listener: {}", e);
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        }))
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,194 @@
+//! Request extractors that reject bad input with the API's error body.
+//!
+//! axum's own `Json` and `Query` answer a body or query string that doesn't
+//! match the target type with a plain-text 400 or 422. These wrap them so
+//! the client gets a 400 `BAD_REQUEST` error naming the offending field; an
+//! unknown enum value such as a status or severity lists the allowed values.
+//! A body over the configured size limit is still a 413.
+//! `Pagination` reads a list endpoint's `limit` and `offset`, and `ClientIp`
+//! finds the address a request came from.
+
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{
+        rejection::JsonRejection, rejection::QueryRejection, ConnectInfo, FromRequest,
+        FromRequestParts,
+    },
+    http::{request::Parts, Extensions, HeaderMap, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use discovery::scope::IpRange;
+use serde::Deserialize;
+
+use crate::{errors::ApiError, state::AppState};
+
+/// JSON request body, or a JSON response
+#[derive(Debug, Clone, Copy, Default, FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiError))]
+pub struct Json<T>(pub T);
+
+impl<T> IntoResponse for Json<T>
+where
+    axum::Json<T>: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+/// Query string parameters
+#[derive(Debug, Clone, Copy, Default, FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+pub struct Query<T>(pub T);
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            return ApiError::PayloadTooLarge;
+        }
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+/// Page size of a list endpoint when the client doesn't ask for one
+pub const DEFAULT_PAGE_LIMIT: usize = 50;
+
+/// Largest page size a list endpoint returns; bigger requests are clamped
+pub const MAX_PAGE_LIMIT: usize = 200;
+
+/// `limit` and `offset` query parameters of a list endpoint. `limit`
+/// defaults to [`DEFAULT_PAGE_LIMIT`] and is clamped to [`MAX_PAGE_LIMIT`],
+/// `offset` defaults to 0, and a negative value for either is a 400.
+///
+/// Reads the same query string as the handler's own `Query`, which ignores
+/// these two parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_PAGE_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+/// Pagination parameters as sent, signed so a negative value gets a clear
+/// error rather than a parse failure
+#[derive(Debug, Deserialize)]
+struct RawPagination {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl Pagination {
+    fn from_raw(raw: RawPagination) -> Result<Self, ApiError> {
+        let non_negative = |name: &str, value: Option<i64>| match value {
+            Some(value) if value < 0 => Err(ApiError::BadRequest(format!(
+                "{} must not be negative",
+                name
+            ))),
+            value => Ok(value.map(|value| usize::try_from(value).unwrap_or(usize::MAX))),
+        };
+
+        let limit = non_negative("limit", raw.limit)?;
+        let offset = non_negative("offset", raw.offset)?;
+        Ok(Self {
+            limit: limit.map_or(DEFAULT_PAGE_LIMIT, |limit| limit.min(MAX_PAGE_LIMIT)),
+            offset: offset.unwrap_or(0),
+        })
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Pagination {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(raw) = Query::<RawPagination>::from_request_parts(parts, state).await?;
+        Pagination::from_raw(raw)
+    }
+}
+
+/// Header a reverse proxy puts the client's address in
+const FORWARDED_FOR_HEADER: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// Address the request came from, if known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    /// The peer address of the connection. When the peer is one of
+    /// `trusted_proxies`, `X-Forwarded-For` is followed back from its last
+    /// entry to the first address that isn't a trusted proxy, since a client
+    /// can put anything in the entries before those its proxies added.
+    pub fn from_request_parts(
+        headers: &HeaderMap,
+        extensions: &Extensions,
+        trusted_proxies: &[String],
+    ) -> Self {
+        let Some(peer) = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+        else {
+            return ClientIp(None);
+        };
+
+        let trusted: Vec<IpRange> = trusted_proxies
+            .iter()
+            .filter_map(|range| range.parse().ok())
+            .collect();
+        let is_trusted = |ip: IpAddr| trusted.iter().any(|range| range.contains(ip));
+
+        let mut client = peer;
+        if is_trusted(peer) {
+            let forwarded: Vec<&str> = headers
+                .get_all(FORWARDED_FOR_HEADER)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .collect();
+            for entry in forwarded.iter().rev() {
+                let Ok(ip) = entry.trim().parse::<IpAddr>() else {
+                    break;
+                };
+                client = ip;
+                if !is_trusted(ip) {
+                    break;
+                }
+            }
+        }
+
+        ClientIp(Some(client))
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp::from_request_parts(
+            &parts.headers,
+            &parts.extensions,
+            &state.config.trusted_proxies,
+        ))
+    }
+}
@@ -0,0 +1,495 @@
+//! GraphQL schema over the asset and vulnerability services
+//!
+//! Clients can fetch an asset together with its ports, technologies and
+//! vulnerabilities in one round-trip instead of one REST call each. Nested
+//! fields are only resolved when asked for. Mutations cover status changes
+//! and apply the same role checks as the equivalent REST routes, and are
+//! recorded in the audit log like them.
+
+use std::sync::Arc;
+
+use async_graphql::{Context, EmptySubscription, Enum, Json, Object, Result, Schema};
+use backend::models::{Asset, Port, Technology, Vulnerability};
+use chrono::{DateTime, Utc};
+use shared::types::{self, AuditAction, ID};
+
+use crate::{
+    errors::convert_result, errors::ApiError, extract::ClientIp,
+    middleware::audit::record_audit_entry, middleware::auth::Claims, state::AppState,
+};
+
+/// Most items returned for a nested list, e.g. an asset's ports
+const NESTED_LIST_LIMIT: usize = 500;
+
+/// Deepest query accepted, so nested lookups can't be chained endlessly
+const MAX_QUERY_DEPTH: usize = 10;
+
+/// The GraphQL schema served at `/graphql`
+pub type EasmSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Build the schema. Each request supplies the application state and the
+/// caller's claims as request data.
+pub fn build_schema() -> EasmSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+fn app_state<'a>(ctx: &Context<'a>) -> Result<&'a Arc<AppState>> {
+    ctx.data::<Arc<AppState>>()
+}
+
+/// Record a mutation of a resource in the audit log, as `audit_middleware`
+/// does for the REST routes
+async fn audit_update(ctx: &Context<'_>, resource_type: &str, resource_id: ID) -> Result<()> {
+    let ip_address = ctx.data_opt::<ClientIp>().and_then(|ClientIp(ip)| *ip);
+    record_audit_entry(
+        app_state(ctx)?,
+        ctx.data::<Claims>()?,
+        AuditAction::Update,
+        resource_type,
+        Some(resource_id),
+        ip_address,
+    )
+    .await;
+    Ok(())
+}
+
+/// Fail unless the caller's role passes `check`
+fn require_role(ctx: &Context<'_>, check: impl FnOnce(types::UserRole) -> bool) -> Result<()> {
+    let role = ctx.data::<Claims>()?.user_role()?;
+    if !check(role) {
+        return Err(ApiError::Forbidden.into());
+    }
+    Ok(())
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "AssetType", remote = "shared::types::AssetType")]
+enum AssetTypeValue {
+    Domain,
+    IPAddress,
+    WebApp,
+    Certificate,
+    CodeRepo,
+    CloudResource,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "AssetStatus", remote = "shared::types::AssetStatus")]
+enum AssetStatusValue {
+    Active,
+    Inactive,
+    Archived,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "Severity", remote = "shared::types::Severity")]
+enum SeverityValue {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(
+    name = "VulnerabilityStatus",
+    remote = "shared::types::VulnerabilityStatus"
+)]
+enum VulnerabilityStatusValue {
+    Open,
+    InProgress,
+    Closed,
+    AcceptedRisk,
+    FalsePositive,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "Protocol", remote = "shared::types::Protocol")]
+enum ProtocolValue {
+    TCP,
+    UDP,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "PortStatus", remote = "shared::types::PortStatus")]
+enum PortStatusValue {
+    Open,
+    Closed,
+    Filtered,
+}
+
+/// An asset, with its ports, technologies and vulnerabilities on request
+pub struct AssetObject(Asset);
+
+#[Object(name = "Asset")]
+impl AssetObject {
+    async fn id(&self) -> ID {
+        self.0.id
+    }
+
+    async fn organization_id(&self) -> ID {
+        self.0.organization_id
+    }
+
+    async fn asset_type(&self) -> AssetTypeValue {
+        self.0.asset_type.into()
+    }
+
+    async fn value(&self) -> &str {
+        &self.0.value
+    }
+
+    async fn status(&self) -> AssetStatusValue {
+        self.0.status.into()
+    }
+
+    async fn risk_score(&self) -> f32 {
+        self.0.risk_score
+    }
+
+    async fn attributes(&self) -> Json<&serde_json::Value> {
+        Json(&self.0.attributes)
+    }
+
+    async fn first_seen(&self) -> DateTime<Utc> {
+        self.0.first_seen
+    }
+
+    async fn last_seen(&self) -> DateTime<Utc> {
+        self.0.last_seen
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.updated_at
+    }
+
+    async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let state = app_state(ctx)?;
+        Ok(convert_result(
+            state.asset_service.get_tags(self.0.id).await,
+        )?)
+    }
+
+    async fn ports(&self, ctx: &Context<'_>) -> Result<Vec<PortObject>> {
+        let state = app_state(ctx)?;
+        let ports = convert_result(
+            state
+                .port_repository
+                .list_ports(Some(self.0.id), None, None, None, NESTED_LIST_LIMIT, 0)
+                .await,
+        )?;
+        Ok(ports.into_iter().map(PortObject).collect())
+    }
+
+    async fn technologies(&self, ctx: &Context<'_>) -> Result<Vec<TechnologyObject>> {
+        let state = app_state(ctx)?;
+        // The repository falls back to an organization lookup when nothing
+        // matches the ID, so keep only this asset's entries
+        let technologies = convert_result(
+            state
+                .technology_repository
+                .list_technologies(Some(self.0.id), None, None, NESTED_LIST_LIMIT, 0)
+                .await,
+        )?;
+        Ok(technologies
+            .into_iter()
+            .filter(|technology| technology.asset_id == self.0.id)
+            .map(TechnologyObject)
+            .collect())
+    }
+
+    async fn vulnerabilities(
+        &self,
+        ctx: &Context<'_>,
+        severity: Option<SeverityValue>,
+        status: Option<VulnerabilityStatusValue>,
+    ) -> Result<Vec<VulnerabilityObject>> {
+        let state = app_state(ctx)?;
+        let vulnerabilities = convert_result(
+            state
+                .vulnerability_service
+                .list_vulnerabilities(
+                    Some(self.0.id),
+                    None,
+                    severity.map(Into::into),
+                    status.map(Into::into),
+                    NESTED_LIST_LIMIT,
+                    0,
+                )
+                .await,
+        )?;
+        Ok(vulnerabilities
+            .into_iter()
+            .map(VulnerabilityObject)
+            .collect())
+    }
+}
+
+/// A port found open, closed or filtered on an asset
+pub struct PortObject(Port);
+
+#[Object(name = "Port")]
+impl PortObject {
+    async fn id(&self) -> ID {
+        self.0.id
+    }
+
+    async fn asset_id(&self) -> ID {
+        self.0.asset_id
+    }
+
+    async fn port_number(&self) -> i32 {
+        self.0.port_number
+    }
+
+    async fn protocol(&self) -> ProtocolValue {
+        self.0.protocol.into()
+    }
+
+    async fn service_name(&self) -> Option<&str> {
+        self.0.service_name.as_deref()
+    }
+
+    async fn banner(&self) -> Option<&str> {
+        self.0.banner.as_deref()
+    }
+
+    async fn status(&self) -> PortStatusValue {
+        self.0.status.into()
+    }
+
+    async fn first_seen(&self) -> DateTime<Utc> {
+        self.0.first_seen
+    }
+
+    async fn last_seen(&self) -> DateTime<Utc> {
+        self.0.last_seen
+    }
+}
+
+/// A technology detected on an asset
+pub struct TechnologyObject(Technology);
+
+#[Object(name = "Technology")]
+impl TechnologyObject {
+    async fn id(&self) -> ID {
+        self.0.id
+    }
+
+    async fn asset_id(&self) -> ID {
+        self.0.asset_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn version(&self) -> Option<&str> {
+        self.0.version.as_deref()
+    }
+
+    async fn category(&self) -> Option<&str> {
+        self.0.category.as_deref()
+    }
+}
+
+/// A vulnerability, with the asset it affects on request
+pub struct VulnerabilityObject(Vulnerability);
+
+#[Object(name = "Vulnerability")]
+impl VulnerabilityObject {
+    async fn id(&self) -> ID {
+        self.0.id
+    }
+
+    async fn asset_id(&self) -> ID {
+        self.0.asset_id
+    }
+
+    async fn port_id(&self) -> Option<ID> {
+        self.0.port_id
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn severity(&self) -> SeverityValue {
+        self.0.severity.into()
+    }
+
+    async fn status(&self) -> VulnerabilityStatusValue {
+        self.0.status.into()
+    }
+
+    async fn cve_id(&self) -> Option<&str> {
+        self.0.cve_id.as_deref()
+    }
+
+    async fn cvss_score(&self) -> Option<f64> {
+        self.0.cvss_score
+    }
+
+    async fn evidence(&self) -> Json<&serde_json::Value> {
+        Json(&self.0.evidence)
+    }
+
+    async fn remediation(&self) -> Option<&str> {
+        self.0.remediation.as_deref()
+    }
+
+    async fn first_seen(&self) -> DateTime<Utc> {
+        self.0.first_seen
+    }
+
+    async fn last_seen(&self) -> DateTime<Utc> {
+        self.0.last_seen
+    }
+
+    async fn resolved_at(&self) -> Option<DateTime<Utc>> {
+        self.0.resolved_at
+    }
+
+    async fn asset(&self, ctx: &Context<'_>) -> Result<AssetObject> {
+        let state = app_state(ctx)?;
+        let asset = convert_result(state.asset_service.get_asset(self.0.asset_id).await)?;
+        Ok(AssetObject(asset))
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A single asset by ID
+    async fn asset(&self, ctx: &Context<'_>, id: ID) -> Result<AssetObject> {
+        let state = app_state(ctx)?;
+        let asset = convert_result(state.asset_service.get_asset(id).await)?;
+        Ok(AssetObject(asset))
+    }
+
+    /// Assets matching the given filters
+    #[allow(clippy::too_many_arguments)]
+    async fn assets(
+        &self,
+        ctx: &Context<'_>,
+        organization_id: Option<ID>,
+        asset_type: Option<AssetTypeValue>,
+        status: Option<AssetStatusValue>,
+        tag: Option<String>,
+        #[graphql(default = 10)] limit: usize,
+        #[graphql(default = 0)] offset: usize,
+    ) -> Result<Vec<AssetObject>> {
+        let state = app_state(ctx)?;
+        let assets = convert_result(
+            state
+                .asset_service
+                .list_assets(
+                    organization_id,
+                    asset_type.map(Into::into),
+                    status.map(Into::into),
+                    tag,
+                    limit,
+                    offset,
+                )
+                .await,
+        )?;
+        Ok(assets.into_iter().map(AssetObject).collect())
+    }
+
+    /// A single vulnerability by ID
+    async fn vulnerability(&self, ctx: &Context<'_>, id: ID) -> Result<VulnerabilityObject> {
+        let state = app_state(ctx)?;
+        let vulnerability =
+            convert_result(state.vulnerability_service.get_vulnerability(id).await)?;
+        Ok(VulnerabilityObject(vulnerability))
+    }
+
+    /// Vulnerabilities matching the given filters
+    #[allow(clippy::too_many_arguments)]
+    async fn vulnerabilities(
+        &self,
+        ctx: &Context<'_>,
+        asset_id: Option<ID>,
+        severity: Option<SeverityValue>,
+        status: Option<VulnerabilityStatusValue>,
+        #[graphql(default = 10)] limit: usize,
+        #[graphql(default = 0)] offset: usize,
+    ) -> Result<Vec<VulnerabilityObject>> {
+        let state = app_state(ctx)?;
+        let vulnerabilities = convert_result(
+            state
+                .vulnerability_service
+                .list_vulnerabilities(
+                    asset_id,
+                    None,
+                    severity.map(Into::into),
+                    status.map(Into::into),
+                    limit,
+                    offset,
+                )
+                .await,
+        )?;
+        Ok(vulnerabilities
+            .into_iter()
+            .map(VulnerabilityObject)
+            .collect())
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Set an asset's status
+    async fn update_asset_status(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        status: AssetStatusValue,
+    ) -> Result<AssetObject> {
+        require_role(ctx, |role| role.can_modify_assets())?;
+        let state = app_state(ctx)?;
+
+        let mut asset = convert_result(state.asset_service.get_asset(id).await)?;
+        asset.status = status.into();
+        let asset = convert_result(state.asset_service.update_asset(&asset).await)?;
+        audit_update(ctx, "asset", asset.id).await?;
+        Ok(AssetObject(asset))
+    }
+
+    /// Set a vulnerability's status
+    async fn update_vulnerability_status(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        status: VulnerabilityStatusValue,
+    ) -> Result<VulnerabilityObject> {
+        require_role(ctx, |role| role.can_modify_vulnerabilities())?;
+        let state = app_state(ctx)?;
+
+        let mut vulnerability =
+            convert_result(state.vulnerability_service.get_vulnerability(id).await)?;
+        vulnerability.status = status.into();
+        let vulnerability = convert_result(
+            state
+                .vulnerability_service
+                .update_vulnerability(&vulnerability)
+                .await,
+        )?;
+        audit_update(ctx, "vulnerability", vulnerability.id).await?;
+        Ok(VulnerabilityObject(vulnerability))
+    }
+}
@@ -1,17 +1,22 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    Json,
+    extract::{Extension, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
-use backend::models::Asset;
+use backend::models::{Asset, AssetHistory, Port, Technology, Vulnerability};
 use serde::{Deserialize, Serialize};
 use shared::types::{AssetStatus, AssetType, ID};
-use std::{net::IpAddr, sync::Arc};
+use std::{collections::BTreeMap, net::IpAddr, sync::Arc};
 use url::Url;
 use uuid::Uuid;
 
 use crate::{
     errors::{convert_result, ApiError, Result},
+    extract::{Json, Pagination, Query},
+    handlers::{
+        target_organization, total_count_headers, BulkStatusUpdateRequest, BulkUpdateResponse,
+    },
+    middleware::auth::Claims,
     state::AppState,
 };
 
@@ -114,8 +119,8 @@ pub struct AssetQuery {
     organization_id: Option<Uuid>,
     asset_type: Option<AssetType>,
     status: Option<AssetStatus>,
-    limit: Option<usize>,
-    offset: Option<usize>,
+    /// Only list assets carrying this tag
+    tag: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -124,6 +129,78 @@ pub struct AssetListResponse {
     total: usize,
 }
 
+/// An asset linked to the requested one, with the kind of link
+#[derive(Debug, Serialize)]
+pub struct RelatedAssetResponse {
+    pub relationship_type: String,
+    pub asset: Asset,
+}
+
+/// Everything known about a single asset, for the detail view
+#[derive(Debug, Serialize)]
+pub struct AssetDetailsResponse {
+    pub asset: Asset,
+    pub related_assets: Vec<RelatedAssetResponse>,
+    pub ports: Vec<Port>,
+    pub technologies: Vec<Technology>,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+/// Request body for tagging an asset
+#[derive(Debug, Deserialize)]
+pub struct AssetTagsRequest {
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetTagsResponse {
+    pub tags: Vec<String>,
+}
+
+/// Upper bound on the number of ports, technologies and vulnerabilities
+/// returned for a single asset
+const ASSET_DETAILS_LIMIT: usize = 500;
+
+#[derive(Debug, Serialize)]
+pub struct AssetHistoryResponse {
+    history: Vec<AssetHistory>,
+    total: usize,
+}
+
+/// Output formats for the asset relationship graph
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// Node/edge JSON for the frontend
+    #[default]
+    Json,
+    /// Graphviz DOT
+    Dot,
+}
+
+/// Query parameters for exporting the asset relationship graph
+#[derive(Debug, Deserialize)]
+pub struct AssetGraphQuery {
+    /// Organization to export, admins only. Defaults to the caller's own.
+    organization_id: Option<ID>,
+    format: Option<GraphFormat>,
+}
+
+/// How many relationships of one type were found, and how many of those
+/// weren't already recorded
+#[derive(Debug, Serialize)]
+pub struct RelationshipTypeCounts {
+    discovered: usize,
+    created: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelationshipDiscoveryResponse {
+    discovered: usize,
+    created: usize,
+    by_type: BTreeMap<String, RelationshipTypeCounts>,
+}
+
 /// Request struct for creating a new asset without requiring an ID
 #[derive(Debug, Deserialize)]
 pub struct CreateAssetRequest {
@@ -141,10 +218,8 @@ pub struct CreateAssetRequest {
 pub async fn list_assets(
     State(state): State<Arc<AppState>>,
     Query(query): Query<AssetQuery>,
-) -> Result<Json<AssetListResponse>> {
-    let limit = query.limit.unwrap_or(10);
-    let offset = query.offset.unwrap_or(0);
-
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<AssetListResponse>)> {
     // Get assets from service
     let assets = convert_result(
         state
@@ -153,6 +228,7 @@ pub async fn list_assets(
                 query.organization_id,
                 query.asset_type,
                 query.status,
+                query.tag.clone(),
                 limit,
                 offset,
             )
@@ -163,11 +239,19 @@ pub async fn list_assets(
     let total = convert_result(
         state
             .asset_service
-            .count_assets(query.organization_id, query.asset_type, query.status)
+            .count_assets(
+                query.organization_id,
+                query.asset_type,
+                query.status,
+                query.tag,
+            )
             .await,
     )?;
 
-    Ok(Json(AssetListResponse { assets, total }))
+    Ok((
+        total_count_headers(total),
+        Json(AssetListResponse { assets, total }),
+    ))
 }
 
 /// Get a single asset by ID
@@ -179,6 +263,191 @@ pub async fn get_asset(
     Ok(Json(asset))
 }
 
+/// Get an asset together with its relationships, open ports, detected
+/// technologies and vulnerabilities
+pub async fn get_asset_details(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<Json<AssetDetailsResponse>> {
+    let asset = convert_result(state.asset_service.get_asset(id).await)?;
+
+    let related_assets = convert_result(
+        state
+            .asset_service
+            .get_related_assets(id, None, ASSET_DETAILS_LIMIT, 0)
+            .await,
+    )?
+    .into_iter()
+    .map(|(asset, relationship_type)| RelatedAssetResponse {
+        relationship_type,
+        asset,
+    })
+    .collect();
+
+    let ports = convert_result(
+        state
+            .port_repository
+            .list_ports(Some(id), None, None, None, ASSET_DETAILS_LIMIT, 0)
+            .await,
+    )?;
+
+    let technologies = convert_result(
+        state
+            .technology_repository
+            .list_asset_technologies(id, ASSET_DETAILS_LIMIT, 0)
+            .await,
+    )?;
+
+    let vulnerabilities = convert_result(
+        state
+            .vulnerability_service
+            .list_vulnerabilities(Some(id), None, None, None, ASSET_DETAILS_LIMIT, 0)
+            .await,
+    )?;
+
+    Ok(Json(AssetDetailsResponse {
+        asset,
+        related_assets,
+        ports,
+        technologies,
+        vulnerabilities,
+    }))
+}
+
+/// Get the recorded field changes for an asset, most recent first
+pub async fn get_asset_history(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<AssetHistoryResponse>)> {
+    // Make sure the asset exists so an unknown ID is a 404 rather than an empty list
+    convert_result(state.asset_service.get_asset(id).await)?;
+
+    let history = convert_result(
+        state
+            .asset_service
+            .get_asset_history(id, limit, offset)
+            .await,
+    )?;
+    let total = convert_result(state.asset_service.count_asset_history(id).await)?;
+
+    Ok((
+        total_count_headers(total),
+        Json(AssetHistoryResponse { history, total }),
+    ))
+}
+
+/// Get an asset's tags
+pub async fn get_asset_tags(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<Json<AssetTagsResponse>> {
+    let tags = convert_result(state.asset_service.get_tags(id).await)?;
+    Ok(Json(AssetTagsResponse { tags }))
+}
+
+/// Add tags to an asset, returning all of its tags
+pub async fn add_asset_tags(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+    Json(request): Json<AssetTagsRequest>,
+) -> Result<Json<AssetTagsResponse>> {
+    if request.tags.is_empty() {
+        return Err(ApiError::BadRequest("No tags given".to_string()));
+    }
+
+    let tags = convert_result(state.asset_service.add_tags(id, &request.tags).await)?;
+    Ok(Json(AssetTagsResponse { tags }))
+}
+
+/// Remove a tag from an asset
+pub async fn remove_asset_tag(
+    State(state): State<Arc<AppState>>,
+    Path((id, tag)): Path<(ID, String)>,
+) -> Result<StatusCode> {
+    let removed = convert_result(state.asset_service.remove_tag(id, &tag).await)?;
+    if !removed {
+        return Err(ApiError::NotFound(format!(
+            "Asset {} is not tagged '{}'",
+            id, tag
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Export the caller's organization's asset relationship graph as
+/// node/edge JSON or Graphviz DOT
+pub async fn get_asset_graph(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<AssetGraphQuery>,
+) -> Result<Response> {
+    let organization_id = target_organization(&claims, query.organization_id)?;
+    let graph = convert_result(state.asset_service.asset_graph(organization_id).await)?;
+
+    Ok(match query.format.unwrap_or_default() {
+        GraphFormat::Json => Json(graph).into_response(),
+        GraphFormat::Dot => (
+            [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+            graph.to_dot(),
+        )
+            .into_response(),
+    })
+}
+
+/// Discover relationships between the caller's organization's assets and
+/// record any that aren't already known
+pub async fn discover_relationships(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<RelationshipDiscoveryResponse>> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let relationships = convert_result(
+        state
+            .asset_service
+            .discover_asset_relationships(organization_id)
+            .await,
+    )?;
+    let discovered = relationships.len();
+
+    let mut grouped: BTreeMap<String, Vec<(ID, ID, String)>> = BTreeMap::new();
+    for relationship in relationships {
+        grouped
+            .entry(relationship.2.clone())
+            .or_default()
+            .push(relationship);
+    }
+
+    // Stored one type at a time so the response can say how many of each
+    // were new
+    let mut created = 0;
+    let mut by_type = BTreeMap::new();
+    for (relationship_type, relationships) in grouped {
+        let type_discovered = relationships.len();
+        let type_created = convert_result(
+            state
+                .asset_service
+                .create_asset_relationships(relationships)
+                .await,
+        )?;
+        created += type_created;
+        by_type.insert(
+            relationship_type,
+            RelationshipTypeCounts {
+                discovered: type_discovered,
+                created: type_created,
+            },
+        );
+    }
+
+    Ok(Json(RelationshipDiscoveryResponse {
+        discovered,
+        created,
+        by_type,
+    }))
+}
+
 /// Create a new asset
 pub async fn create_asset(
     State(state): State<Arc<AppState>>,
@@ -263,6 +532,26 @@ pub async fn update_asset(
     Ok(Json(updated_asset))
 }
 
+/// Set the status of many assets at once. IDs outside the caller's
+/// organization are ignored.
+pub async fn bulk_update_asset_status(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<BulkStatusUpdateRequest<AssetStatus>>,
+) -> Result<Json<BulkUpdateResponse>> {
+    request.validate()?;
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let updated = convert_result(
+        state
+            .asset_service
+            .bulk_update_asset_status(organization_id, request.ids, request.status)
+            .await,
+    )?;
+
+    Ok(Json(BulkUpdateResponse { updated }))
+}
+
 /// Delete an asset by ID
 pub async fn delete_asset(
     State(state): State<Arc<AppState>>,
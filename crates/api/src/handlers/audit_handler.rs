@@ -0,0 +1,63 @@
+use axum::{extract::State, http::HeaderMap};
+use backend::models::{AuditLogEntry, AuditLogFilter};
+use serde::{Deserialize, Serialize};
+use shared::types::{AuditAction, Timestamp, ID};
+use std::sync::Arc;
+
+use crate::{
+    errors::{convert_result, Result},
+    extract::{Json, Pagination, Query},
+    handlers::total_count_headers,
+    state::AppState,
+};
+
+/// Query parameters for searching the audit log
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    organization_id: Option<ID>,
+    user_id: Option<ID>,
+    action: Option<AuditAction>,
+    resource_type: Option<String>,
+    resource_id: Option<ID>,
+    /// Earliest entry to include, as RFC 3339
+    since: Option<Timestamp>,
+    /// Latest entry to include, as RFC 3339
+    until: Option<Timestamp>,
+}
+
+/// Response for searching the audit log
+#[derive(Debug, Serialize)]
+pub struct AuditLogResponse {
+    entries: Vec<AuditLogEntry>,
+    total: usize,
+}
+
+/// Search the audit log, most recent entries first
+pub async fn list_audit_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditLogQuery>,
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<AuditLogResponse>)> {
+    let filter = AuditLogFilter {
+        organization_id: query.organization_id,
+        user_id: query.user_id,
+        action: query.action,
+        resource_type: query.resource_type,
+        resource_id: query.resource_id,
+        since: query.since,
+        until: query.until,
+    };
+
+    let entries = convert_result(
+        state
+            .audit_log_repository
+            .list_entries(&filter, limit, offset)
+            .await,
+    )?;
+    let total = convert_result(state.audit_log_repository.count_entries(&filter).await)?;
+
+    Ok((
+        total_count_headers(total),
+        Json(AuditLogResponse { entries, total }),
+    ))
+}
@@ -2,15 +2,16 @@ use axum::{
     extract::{Extension, State},
     http::StatusCode,
     response::IntoResponse,
-    Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid;
 
 use crate::{
-    errors::{convert_result, ApiError, Result},
-    middleware::auth::{generate_token, revoke_token, Claims},
+    errors::{convert_backend_error, convert_result, ApiError, Result},
+    extract::{ClientIp, Json},
+    login_lockout::LoginKey,
+    middleware::auth::{generate_token, Claims},
     state::AppState,
 };
 
@@ -28,6 +29,12 @@ pub struct LoginUserDto {
     pub password: String,
 }
 
+#[derive(Deserialize)]
+pub struct ChangePasswordDto {
+    pub current_password: String,
+    pub new_password: String,
+}
+
 #[derive(Deserialize)]
 pub struct RefreshTokenDto {
     pub refresh_token: Option<String>,
@@ -63,7 +70,7 @@ pub async fn register(
     // 4. Generate JWT token
     let token = generate_token(
         &user.id.to_string(),
-        &format!("{:?}", user.role),
+        user.role,
         Some(&user.organization_id.to_string()),
         &state.config,
     )?;
@@ -79,9 +86,11 @@ pub async fn register(
     ))
 }
 
-/// Login a user
+/// Login a user. Repeated failures from one address, or many from any,
+/// lock the account out for a while, answered with a 429.
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    ClientIp(ip): ClientIp,
     Json(payload): Json<LoginUserDto>,
 ) -> Result<impl IntoResponse> {
     // 1. Validate input
@@ -91,26 +100,46 @@ pub async fn login(
         ));
     }
 
-    // 2. Call UserService to find user by email/username and verify password
-    let user = convert_result(
-        state
-            .user_service
-            .login_user(&payload.email, &payload.password)
-            .await,
-    )?;
+    // 2. Refuse while the account is locked out, without checking the password
+    let lockout_key = LoginKey::new(&payload.email, ip);
+    if let Some(remaining) = state.login_lockout.locked_for(&lockout_key) {
+        return Err(ApiError::AccountLocked(remaining.as_secs().max(1)));
+    }
+
+    // 3. Call UserService to find user by email/username and verify password
+    let user = match state
+        .user_service
+        .login_user(&payload.email, &payload.password)
+        .await
+    {
+        Ok(user) => {
+            state.login_lockout.record_success(&lockout_key);
+            user
+        }
+        Err(e) => {
+            // Unknown accounts count too, so probing for emails gets locked out
+            if matches!(
+                e,
+                backend::Error::Authentication(_) | backend::Error::NotFound(_)
+            ) {
+                state.login_lockout.record_failure(&lockout_key);
+            }
+            return Err(convert_backend_error(e));
+        }
+    };
 
-    // 3. Generate JWT token
+    // 4. Generate JWT token
     let token = generate_token(
         &user.id.to_string(),
-        &format!("{:?}", user.role),
+        user.role,
         Some(&user.organization_id.to_string()),
         &state.config,
     )?;
 
-    // 4. Generate refresh token (in a real implementation, this would be a separate token)
+    // 5. Generate refresh token (in a real implementation, this would be a separate token)
     let refresh_token = uuid::Uuid::new_v4().to_string();
 
-    // 5. Return token in response
+    // 6. Return token in response
     Ok(Json(AuthResponseDto {
         token,
         refresh_token: Some(refresh_token),
@@ -124,12 +153,48 @@ pub async fn logout(
     Extension(claims): Extension<Claims>,
 ) -> Result<impl IntoResponse> {
     // Revoke the current token
-    revoke_token(&claims.sub, &claims.jti, &state).await?;
+    state.token_denylist.revoke(&claims);
 
     // Return success response
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Log a user out of every session, revoking all tokens issued to them
+pub async fn logout_all(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse> {
+    state.token_denylist.revoke_all(&claims.sub);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Change the logged-in user's password
+pub async fn change_password(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ChangePasswordDto>,
+) -> Result<impl IntoResponse> {
+    if payload.current_password.is_empty() || payload.new_password.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Current and new password cannot be empty".to_string(),
+        ));
+    }
+
+    convert_result(
+        state
+            .user_service
+            .change_password(
+                claims.user_id()?,
+                &payload.current_password,
+                &payload.new_password,
+            )
+            .await,
+    )?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Refresh an access token
 pub async fn refresh_token(
     State(state): State<Arc<AppState>>,
@@ -137,16 +202,22 @@ pub async fn refresh_token(
     Json(payload): Json<RefreshTokenDto>,
 ) -> Result<impl IntoResponse> {
     // In a real implementation, validate the refresh token
-    // For this example, we'll just generate a new token based on the existing claims
+    // For this example, we'll just generate a new token for the same user
+
+    // Re-read the user so a changed role takes effect on refresh
+    let user = convert_result(state.user_service.get_user(claims.user_id()?).await)?;
+    if !user.active {
+        return Err(ApiError::Unauthorized);
+    }
 
     // Revoke the current token
-    revoke_token(&claims.sub, &claims.jti, &state).await?;
+    state.token_denylist.revoke(&claims);
 
     // Generate a new token
     let new_token = generate_token(
-        &claims.sub,
-        &claims.role,
-        claims.org.as_deref(),
+        &user.id.to_string(),
+        user.role,
+        Some(&user.organization_id.to_string()),
         &state.config,
     )?;
 
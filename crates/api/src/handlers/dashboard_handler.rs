@@ -0,0 +1,183 @@
+use axum::extract::{Extension, State};
+use backend::models::Asset;
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::{AssetType, Severity, VulnerabilityStatus};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    errors::{convert_result, ApiError, Result},
+    extract::{Json, Query},
+    handlers::report_handler::{AssetTypeCounts, SeverityCounts},
+    middleware::auth::Claims,
+    state::AppState,
+};
+
+/// Number of recently discovered assets listed on the dashboard
+const RECENT_DISCOVERIES_LIMIT: usize = 5;
+
+/// Days covered by the trend charts unless asked otherwise
+const DEFAULT_TREND_DAYS: u32 = 30;
+
+/// Longest period trends can be requested for
+const MAX_TREND_DAYS: u32 = 365;
+
+/// Organization-wide numbers shown on the dashboard
+#[derive(Debug, Serialize)]
+pub struct DashboardStats {
+    pub total_assets: usize,
+    pub assets_by_type: AssetTypeCounts,
+    pub open_vulnerabilities: usize,
+    pub open_vulnerabilities_by_severity: SeverityCounts,
+    pub technologies: usize,
+    pub recent_discoveries: Vec<Asset>,
+}
+
+/// Asset, vulnerability and technology counts for the caller's organization
+pub async fn get_dashboard_stats(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<DashboardStats>> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let count_assets = |asset_type| {
+        state
+            .asset_service
+            .count_assets(Some(organization_id), Some(asset_type), None, None)
+    };
+    let assets_by_type = AssetTypeCounts {
+        domain: convert_result(count_assets(AssetType::Domain).await)?,
+        ip_address: convert_result(count_assets(AssetType::IPAddress).await)?,
+        web_app: convert_result(count_assets(AssetType::WebApp).await)?,
+        certificate: convert_result(count_assets(AssetType::Certificate).await)?,
+        code_repo: convert_result(count_assets(AssetType::CodeRepo).await)?,
+        cloud_resource: convert_result(count_assets(AssetType::CloudResource).await)?,
+    };
+
+    let mut open_vulnerabilities_by_severity = SeverityCounts {
+        critical: 0,
+        high: 0,
+        medium: 0,
+        low: 0,
+        info: 0,
+    };
+    for (severity, count) in convert_result(
+        state
+            .vulnerability_service
+            .count_by_severity(organization_id, Some(VulnerabilityStatus::Open))
+            .await,
+    )? {
+        let slot = match severity {
+            Severity::Critical => &mut open_vulnerabilities_by_severity.critical,
+            Severity::High => &mut open_vulnerabilities_by_severity.high,
+            Severity::Medium => &mut open_vulnerabilities_by_severity.medium,
+            Severity::Low => &mut open_vulnerabilities_by_severity.low,
+            Severity::Info => &mut open_vulnerabilities_by_severity.info,
+        };
+        *slot += count;
+    }
+
+    let technologies = convert_result(
+        state
+            .technology_repository
+            .count_organization_technologies(organization_id)
+            .await,
+    )?;
+
+    let recent_discoveries = convert_result(
+        state
+            .asset_service
+            .list_recent_assets(organization_id, RECENT_DISCOVERIES_LIMIT)
+            .await,
+    )?;
+
+    Ok(Json(DashboardStats {
+        total_assets: assets_by_type.total(),
+        assets_by_type,
+        open_vulnerabilities: open_vulnerabilities_by_severity.total(),
+        open_vulnerabilities_by_severity,
+        technologies,
+        recent_discoveries,
+    }))
+}
+
+/// Query parameters for the dashboard trends
+#[derive(Debug, Deserialize)]
+pub struct TrendsQuery {
+    days: Option<u32>,
+}
+
+/// Number of assets or vulnerabilities first seen on a day
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+/// New assets and vulnerabilities per day, oldest day first and ending today
+#[derive(Debug, Serialize)]
+pub struct DashboardTrends {
+    pub days: u32,
+    pub assets: Vec<DailyCount>,
+    pub vulnerabilities: Vec<DailyCount>,
+}
+
+/// One entry per day from `start` through `end`, with zero for days that have
+/// no count
+fn daily_series(
+    start: NaiveDate,
+    end: NaiveDate,
+    counts: Vec<(NaiveDate, usize)>,
+) -> Vec<DailyCount> {
+    let counts: HashMap<NaiveDate, usize> = counts.into_iter().collect();
+    start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| DailyCount {
+            date,
+            count: counts.get(&date).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Assets and vulnerabilities discovered per day in the caller's organization
+/// over the last `days` days (30 by default), including today
+pub async fn get_dashboard_trends(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<TrendsQuery>,
+) -> Result<Json<DashboardTrends>> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let days = query.days.unwrap_or(DEFAULT_TREND_DAYS);
+    if days == 0 || days > MAX_TREND_DAYS {
+        return Err(ApiError::BadRequest(format!(
+            "days must be between 1 and {}",
+            MAX_TREND_DAYS
+        )));
+    }
+
+    let today = Utc::now().date_naive();
+    let start = today - Days::new(u64::from(days - 1));
+    let since = start.and_time(chrono::NaiveTime::MIN).and_utc();
+
+    let assets = convert_result(
+        state
+            .asset_service
+            .asset_counts_by_day(organization_id, since)
+            .await,
+    )?;
+    let vulnerabilities = convert_result(
+        state
+            .vulnerability_service
+            .vuln_counts_by_day(organization_id, since)
+            .await,
+    )?;
+
+    Ok(Json(DashboardTrends {
+        days,
+        assets: daily_series(start, today, assets),
+        vulnerabilities: daily_series(start, today, vulnerabilities),
+    }))
+}
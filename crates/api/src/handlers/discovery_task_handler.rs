@@ -1,17 +1,18 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
 };
-use backend::models::DiscoveryJob;
+use backend::models::{Asset, DiscoveryJob, DiscoveryJobFilter, JobResultSummary};
 use discovery::tasks::{DiscoveryTaskType, NucleiTaskParams};
-use serde::{Deserialize, Serialize};
-use shared::types::{JobStatus, JobType, ID};
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
+use shared::types::{JobSortField, JobStatus, JobType, SortOrder, Timestamp, ID};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     errors::{convert_result, ApiError, Result},
+    extract::{Json, Pagination, Query},
+    handlers::{sort_params, total_count_headers},
     state::AppState,
 };
 
@@ -20,15 +21,80 @@ use crate::{
 pub struct DiscoveryTaskQuery {
     organization_id: Option<Uuid>,
     job_type: Option<JobType>,
-    status: Option<JobStatus>,
-    limit: Option<usize>,
-    offset: Option<usize>,
+    /// One status, or several separated by commas, e.g. `RUNNING,PENDING`
+    status: Option<String>,
+    /// Earliest creation time to include, as RFC 3339
+    since: Option<Timestamp>,
+    /// Latest creation time to include, as RFC 3339
+    until: Option<Timestamp>,
+    /// `status` or `created_at`
+    sort_by: Option<JobSortField>,
+    /// `asc` or `desc`, ascending by default
+    sort_order: Option<SortOrder>,
+}
+
+/// A discovery task along with how long it has run
+#[derive(Debug, Serialize)]
+pub struct DiscoveryTaskResponse {
+    #[serde(flatten)]
+    task: DiscoveryJob,
+    /// Seconds from start to completion, or so far while running; `None`
+    /// before the task starts
+    duration_seconds: Option<i64>,
+}
+
+impl From<DiscoveryJob> for DiscoveryTaskResponse {
+    fn from(task: DiscoveryJob) -> Self {
+        Self {
+            duration_seconds: duration_seconds(&task),
+            task,
+        }
+    }
+}
+
+fn duration_seconds(task: &DiscoveryJob) -> Option<i64> {
+    task.duration().map(|duration| duration.num_seconds())
+}
+
+/// Parse a comma-separated list of job statuses, ignoring empty entries
+fn parse_statuses(status: Option<&str>) -> Result<Vec<JobStatus>> {
+    status
+        .into_iter()
+        .flat_map(|status| status.split(','))
+        .map(str::trim)
+        .filter(|status| !status.is_empty())
+        .map(|status| {
+            JobStatus::deserialize(status.into_deserializer()).map_err(
+                |_: serde::de::value::Error| {
+                    ApiError::BadRequest(format!("Unknown job status: {}", status))
+                },
+            )
+        })
+        .collect()
 }
 
 /// Response for listing discovery tasks
 #[derive(Debug, Serialize)]
 pub struct DiscoveryTaskListResponse {
-    tasks: Vec<DiscoveryJob>,
+    tasks: Vec<DiscoveryTaskResponse>,
+    total: usize,
+}
+
+/// What a discovery task found, and how long it ran
+#[derive(Debug, Serialize)]
+pub struct DiscoveryTaskSummaryResponse {
+    job_id: ID,
+    status: JobStatus,
+    #[serde(flatten)]
+    results: JobResultSummary,
+    /// As in [`DiscoveryTaskResponse`]
+    duration_seconds: Option<i64>,
+}
+
+/// Assets a discovery task found
+#[derive(Debug, Serialize)]
+pub struct DiscoveryTaskAssetsResponse {
+    assets: Vec<Asset>,
     total: usize,
 }
 
@@ -45,55 +111,101 @@ pub struct CreateDiscoveryTaskRequest {
     pub task_type: DiscoveryTaskType,
     /// Configuration for Nuclei scans (only used when task_type is VulnerabilityScanNuclei)
     pub nuclei_params: Option<NucleiTaskParams>,
+    /// Skip targets scanned within the freshness window
+    #[serde(default)]
+    pub incremental: bool,
+    /// How recent a scan must be for an incremental task to skip its
+    /// target, in hours (defaults to 24)
+    pub freshness_window_hours: Option<u32>,
+    /// Only work out the targets, ports and request volume the task would
+    /// scan, without scanning
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// List discovery tasks with filtering
 pub async fn list_discovery_tasks(
     State(state): State<Arc<AppState>>,
     Query(query): Query<DiscoveryTaskQuery>,
-) -> Result<Json<DiscoveryTaskListResponse>> {
-    let limit = query.limit.unwrap_or(10);
-    let offset = query.offset.unwrap_or(0);
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<DiscoveryTaskListResponse>)> {
+    let sort = sort_params(query.sort_by, query.sort_order)?;
+    let filter = DiscoveryJobFilter {
+        organization_id: query.organization_id,
+        statuses: parse_statuses(query.status.as_deref())?,
+        job_type: query.job_type,
+        since: query.since,
+        until: query.until,
+    };
 
     // Get discovery jobs
     let tasks = convert_result(
         state
             .discovery_job_repository
-            .list_jobs(
-                query.organization_id,
-                query.job_type,
-                query.status,
-                limit,
-                offset,
-            )
+            .list_jobs(&filter, sort, limit, offset)
             .await,
     )?;
 
     // Get total count for pagination
-    let total = convert_result(
-        state
-            .discovery_job_repository
-            .count_jobs(query.organization_id, query.job_type, query.status)
-            .await,
-    )?;
+    let total = convert_result(state.discovery_job_repository.count_jobs(&filter).await)?;
 
-    Ok(Json(DiscoveryTaskListResponse { tasks, total }))
+    Ok((
+        total_count_headers(total),
+        Json(DiscoveryTaskListResponse {
+            tasks: tasks.into_iter().map(Into::into).collect(),
+            total,
+        }),
+    ))
 }
 
 /// Get a single discovery task by ID
 pub async fn get_discovery_task(
     State(state): State<Arc<AppState>>,
     Path(id): Path<ID>,
-) -> Result<Json<DiscoveryJob>> {
+) -> Result<Json<DiscoveryTaskResponse>> {
+    let task = convert_result(state.discovery_job_repository.get_job(id).await)?;
+    Ok(Json(task.into()))
+}
+
+/// Count the assets, ports, technologies and vulnerabilities a discovery
+/// task found
+pub async fn get_discovery_task_summary(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<Json<DiscoveryTaskSummaryResponse>> {
     let task = convert_result(state.discovery_job_repository.get_job(id).await)?;
-    Ok(Json(task))
+    let results = convert_result(state.discovery_job_repository.get_job_summary(id).await)?;
+
+    Ok(Json(DiscoveryTaskSummaryResponse {
+        job_id: task.id,
+        status: task.status,
+        results,
+        duration_seconds: duration_seconds(&task),
+    }))
+}
+
+/// List the assets a discovery task created or updated
+pub async fn list_discovery_task_assets(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<(HeaderMap, Json<DiscoveryTaskAssetsResponse>)> {
+    // Make sure the task exists so an unknown ID is a 404 rather than an empty list
+    convert_result(state.discovery_job_repository.get_job(id).await)?;
+
+    let assets = convert_result(state.discovery_job_repository.get_job_assets(id).await)?;
+    let total = assets.len();
+
+    Ok((
+        total_count_headers(total),
+        Json(DiscoveryTaskAssetsResponse { assets, total }),
+    ))
 }
 
 /// Create a new discovery task for an asset
 pub async fn create_discovery_task(
     State(state): State<Arc<AppState>>,
     Json(request): Json<CreateDiscoveryTaskRequest>,
-) -> Result<(StatusCode, Json<DiscoveryJob>)> {
+) -> Result<(StatusCode, Json<DiscoveryTaskResponse>)> {
     // Validate request
     if request.asset_id.is_none() && request.target.is_none() {
         return Err(ApiError::BadRequest(
@@ -134,6 +246,16 @@ pub async fn create_discovery_task(
         );
     }
 
+    if request.incremental {
+        config.insert("incremental".to_string(), serde_json::Value::Bool(true));
+    }
+    if let Some(hours) = request.freshness_window_hours {
+        config.insert("freshness_window_hours".to_string(), hours.into());
+    }
+    if request.dry_run {
+        config.insert("dry_run".to_string(), serde_json::Value::Bool(true));
+    }
+
     // Create the discovery job
     let job = DiscoveryJob::new(
         request.organization_id,
@@ -160,14 +282,14 @@ pub async fn create_discovery_task(
         )?;
     }
 
-    Ok((StatusCode::CREATED, Json(created_job)))
+    Ok((StatusCode::CREATED, Json(created_job.into())))
 }
 
 /// Cancel a discovery task
 pub async fn cancel_discovery_task(
     State(state): State<Arc<AppState>>,
     Path(id): Path<ID>,
-) -> Result<Json<DiscoveryJob>> {
+) -> Result<Json<DiscoveryTaskResponse>> {
     // Get the job
     let mut job = convert_result(state.discovery_job_repository.get_job(id).await)?;
 
@@ -182,7 +304,7 @@ pub async fn cancel_discovery_task(
         )));
     }
 
-    Ok(Json(job))
+    Ok(Json(job.into()))
 }
 
 /// Delete a discovery task
@@ -0,0 +1,44 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::{Extension, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    errors::{ApiError, Result},
+    middleware::auth::Claims,
+    state::AppState,
+};
+
+/// Stream the caller's organization's asset and vulnerability changes as
+/// server-sent events, each carrying a `{type, org_id, resource}` payload
+pub async fn stream_events(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+    let receiver = state.events.subscribe();
+
+    let events = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.org_id == organization_id => {
+                    let Ok(event) = Event::default().json_data(event) else {
+                        continue;
+                    };
+                    return Some((Ok(event), receiver));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::debug!("Event stream fell behind, skipped {} events", missed);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,23 @@
+use axum::{
+    extract::{Extension, State},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{extract::ClientIp, graphql::EasmSchema, middleware::auth::Claims, state::AppState};
+
+/// Execute a GraphQL query or mutation as the authenticated caller. The
+/// client address is passed along for the audit log.
+pub async fn graphql(
+    State(state): State<Arc<AppState>>,
+    Extension(schema): Extension<EasmSchema>,
+    Extension(claims): Extension<Claims>,
+    client_ip: ClientIp,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(
+        schema
+            .execute(request.data(state).data(claims).data(client_ip))
+            .await,
+    )
+}
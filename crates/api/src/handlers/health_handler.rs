@@ -1,7 +1,16 @@
-use axum::{http::StatusCode, Json};
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, Json};
 use serde_json::json;
 
+use crate::state::{AppState, BuildInfo};
+
 /// Health check endpoint to verify API is running
 pub async fn health_check() -> (StatusCode, Json<serde_json::Value>) {
     (StatusCode::OK, Json(json!({ "status": "ok" })))
 }
+
+/// Version, commit and build time of the running API
+pub async fn version(State(state): State<Arc<AppState>>) -> Json<BuildInfo> {
+    Json(state.build_info)
+}
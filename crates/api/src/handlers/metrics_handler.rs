@@ -0,0 +1,10 @@
+use axum::{http::header, response::IntoResponse};
+use shared::metrics::{registry, render, METRICS_CONTENT_TYPE};
+
+/// Metrics endpoint for Prometheus to scrape
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)],
+        render(registry()),
+    )
+}
@@ -1,7 +1,92 @@
 pub mod asset_handler;
+pub mod audit_handler;
 pub mod auth_handler;
+pub mod dashboard_handler;
 pub mod discovery_task_handler;
+pub mod event_handler;
+pub mod graphql_handler;
 pub mod health_handler;
+pub mod metrics_handler;
 pub mod organization_handler;
 pub mod report_handler;
+pub mod scan_profile_handler;
+pub mod scan_schedule_handler;
+pub mod technology_handler;
+pub mod user_handler;
 pub mod vulnerability_handler;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use shared::types::{Sort, SortOrder, ID};
+
+use crate::errors::{ApiError, Result};
+use crate::middleware::auth::Claims;
+
+/// Response header carrying the unpaginated total for list endpoints
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+
+/// Build the headers advertising the total number of items for a paginated list
+pub fn total_count_headers(total: usize) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(total));
+    headers
+}
+
+/// Build a list endpoint's sort from its `sort_by` and `sort_order` query
+/// parameters. The order defaults to ascending and means nothing without a
+/// field, so giving it alone is rejected.
+pub fn sort_params<F>(
+    sort_by: Option<F>,
+    sort_order: Option<SortOrder>,
+) -> Result<Option<Sort<F>>> {
+    match (sort_by, sort_order) {
+        (Some(field), order) => Ok(Some(Sort::new(field, order.unwrap_or_default()))),
+        (None, Some(_)) => Err(ApiError::BadRequest(
+            "sort_order requires sort_by".to_string(),
+        )),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Most IDs accepted by a single bulk update
+pub const MAX_BULK_IDS: usize = 1000;
+
+/// Request body for setting the status of many items at once
+#[derive(Debug, Deserialize)]
+pub struct BulkStatusUpdateRequest<S> {
+    pub ids: Vec<ID>,
+    pub status: S,
+}
+
+impl<S> BulkStatusUpdateRequest<S> {
+    /// Reject empty and oversized ID lists
+    pub fn validate(&self) -> Result<()> {
+        if self.ids.is_empty() {
+            return Err(ApiError::BadRequest("No IDs given".to_string()));
+        }
+        if self.ids.len() > MAX_BULK_IDS {
+            return Err(ApiError::BadRequest(format!(
+                "At most {} IDs can be updated at once",
+                MAX_BULK_IDS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Number of items a bulk update changed
+#[derive(Debug, Serialize)]
+pub struct BulkUpdateResponse {
+    pub updated: usize,
+}
+
+/// The organization a request acts on: `requested` for admins, otherwise
+/// the caller's own, which is all a non-admin may name
+pub fn target_organization(claims: &Claims, requested: Option<ID>) -> Result<ID> {
+    let own = claims.organization_id()?;
+    match requested {
+        Some(org_id) if claims.user_role()?.can_admin() || own == Some(org_id) => Ok(org_id),
+        Some(_) => Err(ApiError::Forbidden),
+        None => own.ok_or(ApiError::Forbidden),
+    }
+}
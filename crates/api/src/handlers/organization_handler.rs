@@ -1,17 +1,21 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
-    Json,
+    Extension,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::{
     errors::{convert_result, ApiError, Result},
+    extract::{Json, Query},
+    handlers::total_count_headers,
+    middleware::auth::Claims,
     state::AppState,
 };
-use backend::models::Organization; // Use trait instead of impl
+use backend::{models::Organization, NotificationPeriod}; // Use trait instead of impl
+use discovery::scope::ScopeEntry;
 use shared::types::{PaginationParams, ID}; // Use ID alias
 
 // DTOs
@@ -23,6 +27,32 @@ pub struct CreateOrganizationDto {
 #[derive(Deserialize)]
 pub struct UpdateOrganizationDto {
     pub name: String,
+    /// How often to send the summary report, left unchanged if omitted
+    pub notification_period: Option<NotificationPeriod>,
+    /// Domains, IPs and CIDR ranges scans are restricted to, left unchanged
+    /// if omitted. An empty list lifts the restriction.
+    pub scan_scope: Option<Vec<String>>,
+}
+
+/// Admins reach every organization, anyone else only their own
+fn check_organization_access(claims: &Claims, org_id: ID) -> Result<()> {
+    if claims.user_role()?.can_admin() || claims.organization_id()? == Some(org_id) {
+        Ok(())
+    } else {
+        // Other organizations aren't acknowledged to exist
+        Err(ApiError::NotFound("Organization not found".to_string()))
+    }
+}
+
+/// Organization names are trimmed and must not be empty
+fn organization_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Organization name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name.to_string())
 }
 
 // Handlers
@@ -30,55 +60,71 @@ pub async fn create_organization(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<CreateOrganizationDto>,
 ) -> Result<impl IntoResponse> {
-    // TODO: Add authorization check (e.g., only Admins can create orgs?)
-    if payload.name.is_empty() {
-        return Err(ApiError::BadRequest(
-            "Organization name cannot be empty".to_string(),
-        ));
-    }
-    let org = Organization::new(payload.name);
+    let org = Organization::new(organization_name(&payload.name)?);
     let created_org = convert_result(state.organization_service.create_organization(&org).await)?;
     Ok((StatusCode::CREATED, Json(created_org)))
 }
 
 pub async fn get_organization(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Path(org_id): Path<ID>, // Use ID alias
 ) -> Result<impl IntoResponse> {
-    // TODO: Add authorization check (e.g., user belongs to this org or is admin)
+    check_organization_access(&claims, org_id)?;
     let org = convert_result(state.organization_service.get_organization(org_id).await)?;
     Ok(Json(org))
 }
 
+/// Every organization for admins; anyone else sees only their own
 pub async fn list_organizations(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Query(pagination): Query<PaginationParams>,
 ) -> Result<impl IntoResponse> {
-    // TODO: Add authorization check (e.g., only Admins can list all orgs?)
+    if !claims.user_role()?.can_admin() {
+        let orgs = match claims.organization_id()? {
+            Some(org_id) if pagination.offset() == 0 => vec![convert_result(
+                state.organization_service.get_organization(org_id).await,
+            )?],
+            _ => Vec::new(),
+        };
+        let total = usize::from(claims.organization_id()?.is_some());
+        return Ok((total_count_headers(total), Json(orgs)));
+    }
+
     let orgs = convert_result(
         state
             .organization_service
             .list_organizations(pagination.page_size as usize, pagination.offset() as usize)
             .await,
     )?;
-    Ok(Json(orgs))
+    let total = convert_result(state.organization_service.count_organizations().await)?;
+    Ok((total_count_headers(total), Json(orgs)))
 }
 
 pub async fn update_organization(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Path(org_id): Path<ID>, // Use ID alias
     Json(payload): Json<UpdateOrganizationDto>,
 ) -> Result<impl IntoResponse> {
-    // TODO: Add authorization check
-    if payload.name.is_empty() {
-        return Err(ApiError::BadRequest(
-            "Organization name cannot be empty".to_string(),
-        ));
-    }
+    check_organization_access(&claims, org_id)?;
+    let name = organization_name(&payload.name)?;
     // Get existing org first to update it
     let mut org = convert_result(state.organization_service.get_organization(org_id).await)?;
-    org.name = payload.name;
-    // Note: Need to handle updated_at timestamp, likely in service/repo
+    org.name = name;
+    if let Some(period) = payload.notification_period {
+        org.notification_period = period;
+    }
+    if let Some(scan_scope) = payload.scan_scope {
+        for entry in &scan_scope {
+            entry
+                .parse::<ScopeEntry>()
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        }
+        org.scan_scope = scan_scope;
+    }
+    org.updated_at = chrono::Utc::now();
     let updated_org = convert_result(state.organization_service.update_organization(&org).await)?;
     Ok(Json(updated_org))
 }
@@ -87,12 +133,10 @@ pub async fn delete_organization(
     State(state): State<Arc<AppState>>,
     Path(org_id): Path<ID>, // Use ID alias
 ) -> Result<impl IntoResponse> {
-    // TODO: Add authorization check
     let deleted = convert_result(state.organization_service.delete_organization(org_id).await)?;
     if deleted {
         Ok(StatusCode::NO_CONTENT)
     } else {
-        // This case might not happen if get_organization is checked first in a real scenario
         Err(ApiError::NotFound("Organization not found".to_string()))
     }
 }
@@ -1,8 +1,7 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
-    Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -10,7 +9,11 @@ use shared::types::{AssetType, Severity};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::{errors::ApiError, state::AppState};
+use crate::{
+    errors::ApiError,
+    extract::{Json, Query},
+    state::AppState,
+};
 
 /// Query parameters for report generation
 #[derive(Debug, Deserialize)]
@@ -71,6 +74,13 @@ pub struct SeverityCounts {
     pub info: usize,
 }
 
+impl SeverityCounts {
+    /// Sum over all severities
+    pub fn total(&self) -> usize {
+        self.critical + self.high + self.medium + self.low + self.info
+    }
+}
+
 /// Counts by status
 #[derive(Debug, Serialize)]
 pub struct StatusCounts {
@@ -88,6 +98,19 @@ pub struct AssetTypeCounts {
     pub web_app: usize,
     pub certificate: usize,
     pub code_repo: usize,
+    pub cloud_resource: usize,
+}
+
+impl AssetTypeCounts {
+    /// Sum over all asset types
+    pub fn total(&self) -> usize {
+        self.domain
+            + self.ip_address
+            + self.web_app
+            + self.certificate
+            + self.code_repo
+            + self.cloud_resource
+    }
 }
 
 /// Summary of an asset for reports
@@ -180,6 +203,7 @@ pub async fn generate_asset_report(
             web_app: 15,
             certificate: 5,
             code_repo: 1,
+            cloud_resource: 0,
         },
         recently_discovered_assets: vec![
             AssetSummary {
@@ -0,0 +1,215 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use backend::models::{DiscoveryJob, ScanProfile};
+use serde::{Deserialize, Serialize};
+use shared::types::{JobType, ID};
+use std::sync::Arc;
+
+use crate::{
+    errors::{convert_result, ApiError, Result},
+    extract::{Json, Query},
+    state::AppState,
+};
+
+/// Query parameters for listing scan profiles
+#[derive(Debug, Deserialize)]
+pub struct ScanProfileQuery {
+    organization_id: ID,
+}
+
+/// Response for listing scan profiles
+#[derive(Debug, Serialize)]
+pub struct ScanProfileListResponse {
+    profiles: Vec<ScanProfile>,
+    total: usize,
+}
+
+/// Request for creating a new scan profile
+#[derive(Debug, Deserialize)]
+pub struct CreateScanProfileRequest {
+    /// Organization ID
+    pub organization_id: ID,
+    /// Name to launch the profile by; reusing a built-in's name replaces it
+    /// for the organization
+    pub name: String,
+    /// Discovery methods to run
+    pub methods: Vec<JobType>,
+    /// How many levels deep to crawl and recurse into discovered assets
+    pub depth: u32,
+    /// Ports to scan
+    pub ports: Vec<u16>,
+    /// How long a job may run, in seconds
+    pub timeout_secs: u32,
+}
+
+/// Request for updating a scan profile
+#[derive(Debug, Deserialize)]
+pub struct UpdateScanProfileRequest {
+    pub name: Option<String>,
+    pub methods: Option<Vec<JobType>>,
+    pub depth: Option<u32>,
+    pub ports: Option<Vec<u16>>,
+    pub timeout_secs: Option<u32>,
+}
+
+/// Request for launching a discovery job from a scan profile
+#[derive(Debug, Deserialize)]
+pub struct LaunchScanProfileRequest {
+    /// Organization ID
+    pub organization_id: ID,
+    /// Name of the profile, ignoring case
+    pub profile: String,
+    /// Target to scan (domain, IP, URL)
+    pub target: String,
+}
+
+/// A scan profile by ID, built-in or stored
+async fn find_profile(state: &AppState, id: ID) -> Result<ScanProfile> {
+    match ScanProfile::built_ins()
+        .into_iter()
+        .find(|profile| profile.id == id)
+    {
+        Some(profile) => Ok(profile),
+        None => convert_result(state.scan_profile_repository.get_profile(id).await),
+    }
+}
+
+/// List the built-in profiles and an organization's own, built-ins first.
+/// A built-in the organization replaced with its own profile is left out.
+pub async fn list_scan_profiles(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ScanProfileQuery>,
+) -> Result<Json<ScanProfileListResponse>> {
+    let stored = convert_result(
+        state
+            .scan_profile_repository
+            .list_profiles(query.organization_id)
+            .await,
+    )?;
+
+    let mut profiles: Vec<ScanProfile> = ScanProfile::built_ins()
+        .into_iter()
+        .filter(|built_in| {
+            !stored
+                .iter()
+                .any(|profile| profile.name.eq_ignore_ascii_case(&built_in.name))
+        })
+        .collect();
+    profiles.extend(stored);
+
+    let total = profiles.len();
+    Ok(Json(ScanProfileListResponse { profiles, total }))
+}
+
+/// Get a single scan profile by ID
+pub async fn get_scan_profile(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<Json<ScanProfile>> {
+    Ok(Json(find_profile(&state, id).await?))
+}
+
+/// Create a new scan profile
+pub async fn create_scan_profile(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateScanProfileRequest>,
+) -> Result<(StatusCode, Json<ScanProfile>)> {
+    let profile = ScanProfile::new(
+        request.organization_id,
+        request.name.trim().to_string(),
+        request.methods,
+        request.depth,
+        request.ports,
+        request.timeout_secs,
+    );
+    convert_result(profile.validate())?;
+
+    let created = convert_result(state.scan_profile_repository.create_profile(&profile).await)?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update an existing scan profile. Built-in profiles can't be changed.
+pub async fn update_scan_profile(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+    Json(request): Json<UpdateScanProfileRequest>,
+) -> Result<Json<ScanProfile>> {
+    let mut profile = find_profile(&state, id).await?;
+    if profile.is_built_in() {
+        return Err(ApiError::BadRequest(
+            "Built-in scan profiles can't be changed".to_string(),
+        ));
+    }
+
+    if let Some(name) = request.name {
+        profile.name = name.trim().to_string();
+    }
+    if let Some(methods) = request.methods {
+        profile.methods = methods;
+    }
+    if let Some(depth) = request.depth {
+        profile.depth = depth;
+    }
+    if let Some(ports) = request.ports {
+        profile.ports = ports;
+    }
+    if let Some(timeout_secs) = request.timeout_secs {
+        profile.timeout_secs = timeout_secs;
+    }
+    convert_result(profile.validate())?;
+    profile.updated_at = chrono::Utc::now();
+
+    let updated = convert_result(state.scan_profile_repository.update_profile(&profile).await)?;
+
+    Ok(Json(updated))
+}
+
+/// Delete a scan profile. Built-in profiles can't be deleted.
+pub async fn delete_scan_profile(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<StatusCode> {
+    if ScanProfile::built_ins()
+        .iter()
+        .any(|profile| profile.id == id)
+    {
+        return Err(ApiError::BadRequest(
+            "Built-in scan profiles can't be deleted".to_string(),
+        ));
+    }
+
+    convert_result(state.scan_profile_repository.delete_profile(id).await)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Launch a discovery job from a scan profile, looked up by name among the
+/// organization's own profiles and then the built-ins
+pub async fn launch_scan_profile(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<LaunchScanProfileRequest>,
+) -> Result<(StatusCode, Json<DiscoveryJob>)> {
+    let target = request.target.trim();
+    if target.is_empty() {
+        return Err(ApiError::BadRequest("target must not be empty".to_string()));
+    }
+
+    let stored = convert_result(
+        state
+            .scan_profile_repository
+            .get_profile_by_name(request.organization_id, &request.profile)
+            .await,
+    )?;
+    let profile = stored
+        .or_else(|| ScanProfile::built_in(&request.profile))
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Scan profile '{}' not found", request.profile))
+        })?;
+
+    let job = profile.to_job(request.organization_id, target.to_string());
+    let created = convert_result(state.discovery_job_repository.create_job(&job).await)?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use backend::models::ScanSchedule;
+use serde::{Deserialize, Serialize};
+use shared::types::{JobType, ID};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    errors::{convert_result, ApiError, Result},
+    extract::{Json, Pagination, Query},
+    handlers::total_count_headers,
+    state::AppState,
+};
+
+/// Query parameters for listing scan schedules
+#[derive(Debug, Deserialize)]
+pub struct ScanScheduleQuery {
+    organization_id: Option<Uuid>,
+}
+
+/// Response for listing scan schedules
+#[derive(Debug, Serialize)]
+pub struct ScanScheduleListResponse {
+    schedules: Vec<ScanSchedule>,
+    total: usize,
+}
+
+/// Request for creating a new scan schedule
+#[derive(Debug, Deserialize)]
+pub struct CreateScanScheduleRequest {
+    /// Organization ID
+    pub organization_id: ID,
+    /// Target to scan on every run (domain, IP, URL)
+    pub target: String,
+    /// Type of job to enqueue
+    pub job_type: JobType,
+    /// Cron expression with a leading seconds field, e.g. `0 0 2 * * *`
+    pub cron_expression: String,
+    /// Whether the schedule starts enabled (defaults to true)
+    pub enabled: Option<bool>,
+}
+
+/// Request for updating a scan schedule
+#[derive(Debug, Deserialize)]
+pub struct UpdateScanScheduleRequest {
+    pub target: Option<String>,
+    pub job_type: Option<JobType>,
+    pub cron_expression: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// List scan schedules
+pub async fn list_scan_schedules(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ScanScheduleQuery>,
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<ScanScheduleListResponse>)> {
+    let schedules = convert_result(
+        state
+            .scan_schedule_repository
+            .list_schedules(query.organization_id, limit, offset)
+            .await,
+    )?;
+
+    // Get total count for pagination
+    let total = convert_result(
+        state
+            .scan_schedule_repository
+            .count_schedules(query.organization_id)
+            .await,
+    )?;
+
+    Ok((
+        total_count_headers(total),
+        Json(ScanScheduleListResponse { schedules, total }),
+    ))
+}
+
+/// Get a single scan schedule by ID
+pub async fn get_scan_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<Json<ScanSchedule>> {
+    let schedule = convert_result(state.scan_schedule_repository.get_schedule(id).await)?;
+    Ok(Json(schedule))
+}
+
+/// Create a new scan schedule
+pub async fn create_scan_schedule(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateScanScheduleRequest>,
+) -> Result<(StatusCode, Json<ScanSchedule>)> {
+    if request.target.trim().is_empty() {
+        return Err(ApiError::BadRequest("target must not be empty".to_string()));
+    }
+    convert_result(ScanSchedule::parse_cron(&request.cron_expression))?;
+
+    let mut schedule = ScanSchedule::new(
+        request.organization_id,
+        request.target,
+        request.job_type,
+        request.cron_expression,
+    );
+    if let Some(enabled) = request.enabled {
+        schedule.enabled = enabled;
+    }
+
+    let created = convert_result(
+        state
+            .scan_schedule_repository
+            .create_schedule(&schedule)
+            .await,
+    )?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update an existing scan schedule
+pub async fn update_scan_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+    Json(request): Json<UpdateScanScheduleRequest>,
+) -> Result<Json<ScanSchedule>> {
+    let mut schedule = convert_result(state.scan_schedule_repository.get_schedule(id).await)?;
+
+    if let Some(target) = request.target {
+        if target.trim().is_empty() {
+            return Err(ApiError::BadRequest("target must not be empty".to_string()));
+        }
+        schedule.target = target;
+    }
+    if let Some(job_type) = request.job_type {
+        schedule.job_type = job_type;
+    }
+    if let Some(cron_expression) = request.cron_expression {
+        convert_result(ScanSchedule::parse_cron(&cron_expression))?;
+        schedule.cron_expression = cron_expression;
+    }
+    if let Some(enabled) = request.enabled {
+        schedule.enabled = enabled;
+    }
+    schedule.updated_at = chrono::Utc::now();
+
+    let updated = convert_result(
+        state
+            .scan_schedule_repository
+            .update_schedule(&schedule)
+            .await,
+    )?;
+
+    Ok(Json(updated))
+}
+
+/// Delete a scan schedule
+pub async fn delete_scan_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<ID>,
+) -> Result<StatusCode> {
+    convert_result(state.scan_schedule_repository.delete_schedule(id).await)?;
+    Ok(StatusCode::NO_CONTENT)
+}
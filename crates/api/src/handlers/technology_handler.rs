@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Extension, State},
+    http::HeaderMap,
+};
+use backend::models::{DetectedTechnology, TechnologyDistribution};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{
+    errors::{convert_result, ApiError, Result},
+    extract::{Json, Pagination, Query},
+    handlers::total_count_headers,
+    middleware::auth::Claims,
+    state::AppState,
+};
+
+/// Query parameters for listing technologies
+#[derive(Debug, Deserialize)]
+pub struct TechnologyQuery {
+    /// Part of the technology name, ignoring case
+    search: Option<String>,
+    category: Option<String>,
+}
+
+/// Response for listing technologies
+#[derive(Debug, Serialize)]
+pub struct TechnologyListResponse {
+    technologies: Vec<DetectedTechnology>,
+    total: usize,
+}
+
+/// List the technologies detected on the caller's organization's assets
+pub async fn list_technologies(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<TechnologyQuery>,
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<TechnologyListResponse>)> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    // Empty filters come from cleared form fields and match everything
+    let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+
+    let page = convert_result(
+        state
+            .technology_repository
+            .list_organization_technologies(
+                organization_id,
+                non_empty(query.search),
+                non_empty(query.category),
+                limit,
+                offset,
+            )
+            .await,
+    )?;
+
+    Ok((
+        total_count_headers(page.total),
+        Json(TechnologyListResponse {
+            technologies: page.items,
+            total: page.total,
+        }),
+    ))
+}
+
+/// Technologies across the caller's organization, counted by name and by
+/// category
+pub async fn get_technology_distribution(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<TechnologyDistribution>> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let distribution = convert_result(
+        state
+            .technology_repository
+            .technology_distribution(organization_id)
+            .await,
+    )?;
+
+    Ok(Json(distribution))
+}
@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Extension,
+};
+use backend::models::User;
+use serde::{Deserialize, Serialize};
+use shared::types::{UserRole, ID};
+use std::sync::Arc;
+
+use crate::{
+    errors::{convert_result, ApiError, Result},
+    extract::{Json, Pagination, Query},
+    handlers::{target_organization, total_count_headers},
+    middleware::auth::Claims,
+    state::AppState,
+};
+
+/// Query parameters for listing users
+#[derive(Debug, Deserialize)]
+pub struct UserQuery {
+    /// Organization to list, admins only. Defaults to the caller's own.
+    organization_id: Option<ID>,
+}
+
+/// Response for listing users
+#[derive(Debug, Serialize)]
+pub struct UserListResponse {
+    users: Vec<User>,
+    total: usize,
+}
+
+/// Request for creating a user
+#[derive(Debug, Deserialize)]
+pub struct CreateUserRequest {
+    pub email: String,
+    pub password: String,
+    /// Defaults to analyst
+    pub role: Option<UserRole>,
+    /// Organization to add the user to, admins only. Defaults to the
+    /// caller's own.
+    pub organization_id: Option<ID>,
+}
+
+/// Request for updating a user, leaving omitted fields unchanged
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub role: Option<UserRole>,
+}
+
+/// Load a user the caller may manage. Users of other organizations are
+/// only visible to admins, and only admins may manage admins.
+async fn managed_user(state: &AppState, claims: &Claims, id: ID) -> Result<User> {
+    let user = convert_result(state.user_service.get_user(id).await)?;
+    let is_admin = claims.user_role()?.can_admin();
+
+    if !is_admin && claims.organization_id()? != Some(user.organization_id) {
+        return Err(ApiError::NotFound("User not found".to_string()));
+    }
+    if !is_admin && user.role.can_admin() {
+        return Err(ApiError::Forbidden);
+    }
+
+    Ok(user)
+}
+
+/// Only admins may grant the admin role
+fn check_role_grant(claims: &Claims, role: UserRole) -> Result<()> {
+    if role.can_admin() && !claims.user_role()?.can_admin() {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(())
+}
+
+/// List the users of the caller's organization
+pub async fn list_users(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<UserQuery>,
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<UserListResponse>)> {
+    let organization_id = target_organization(&claims, query.organization_id)?;
+    let users = convert_result(
+        state
+            .user_service
+            .list_users(organization_id, limit, offset)
+            .await,
+    )?;
+    let total = convert_result(state.user_service.count_users(organization_id).await)?;
+
+    Ok((
+        total_count_headers(total),
+        Json(UserListResponse { users, total }),
+    ))
+}
+
+/// Get a single user
+pub async fn get_user(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<ID>,
+) -> Result<Json<User>> {
+    Ok(Json(managed_user(&state, &claims, id).await?))
+}
+
+/// Create a user in the caller's organization
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateUserRequest>,
+) -> Result<(StatusCode, Json<User>)> {
+    let organization_id = target_organization(&claims, request.organization_id)?;
+    let role = request.role.unwrap_or(UserRole::Analyst);
+    check_role_grant(&claims, role)?;
+
+    if request.email.trim().is_empty() {
+        return Err(ApiError::BadRequest("Email cannot be empty".to_string()));
+    }
+
+    let user = convert_result(
+        state
+            .user_service
+            .create_user(
+                organization_id,
+                request.email.trim(),
+                &request.password,
+                role,
+            )
+            .await,
+    )?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+/// Update a user's username, email or role. A changed role applies to new
+/// tokens, so the user's current ones are revoked.
+pub async fn update_user(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<ID>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Result<Json<User>> {
+    let mut user = managed_user(&state, &claims, id).await?;
+
+    if let Some(username) = request.username {
+        user.username = username.trim().to_string();
+    }
+    if let Some(email) = request.email {
+        user.email = email.trim().to_string();
+    }
+    let role_changed = request.role.is_some_and(|role| role != user.role);
+    if let Some(role) = request.role {
+        check_role_grant(&claims, role)?;
+        user.role = role;
+    }
+    user.updated_at = chrono::Utc::now();
+
+    let user = convert_result(state.user_service.update_user(&user).await)?;
+    if role_changed {
+        state.token_denylist.revoke_all(&user.id.to_string());
+    }
+
+    Ok(Json(user))
+}
+
+/// Deactivate a user, who can no longer log in, and revoke their tokens
+pub async fn deactivate_user(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<ID>,
+) -> Result<Json<User>> {
+    if claims.user_id()? == id {
+        return Err(ApiError::BadRequest(
+            "You cannot deactivate your own account".to_string(),
+        ));
+    }
+
+    set_active(&state, &claims, id, false).await
+}
+
+/// Let a deactivated user log in again
+pub async fn activate_user(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<ID>,
+) -> Result<Json<User>> {
+    set_active(&state, &claims, id, true).await
+}
+
+async fn set_active(state: &AppState, claims: &Claims, id: ID, active: bool) -> Result<Json<User>> {
+    let mut user = managed_user(state, claims, id).await?;
+    user.active = active;
+    user.updated_at = chrono::Utc::now();
+
+    let user = convert_result(state.user_service.update_user(&user).await)?;
+    if !active {
+        state.token_denylist.revoke_all(&user.id.to_string());
+    }
+
+    Ok(Json(user))
+}
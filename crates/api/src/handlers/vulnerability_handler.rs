@@ -1,18 +1,20 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    Json,
+    extract::{Extension, Path, State},
+    http::{HeaderMap, StatusCode},
 };
-use backend::models::Vulnerability;
+use backend::models::{CorrelationGraph, Vulnerability, VulnerabilityGroup};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json;
-use shared::types::{Severity, VulnerabilityStatus, ID};
+use shared::types::{Severity, SortOrder, VulnerabilitySortField, VulnerabilityStatus, ID};
 use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 use crate::{
-    errors::{convert_result, Result},
+    errors::{convert_result, ApiError, Result},
+    extract::{Json, Pagination, Query},
+    handlers::{sort_params, total_count_headers, BulkStatusUpdateRequest, BulkUpdateResponse},
+    middleware::auth::Claims,
     state::AppState,
 };
 
@@ -22,8 +24,10 @@ pub struct VulnerabilityQuery {
     port_id: Option<Uuid>,
     severity: Option<Severity>,
     status: Option<VulnerabilityStatus>,
-    limit: Option<usize>,
-    offset: Option<usize>,
+    /// One of `severity`, `status` or `created_at`
+    sort_by: Option<VulnerabilitySortField>,
+    /// `asc` or `desc`, ascending by default
+    sort_order: Option<SortOrder>,
 }
 
 #[derive(Debug, Serialize)]
@@ -48,6 +52,20 @@ pub struct CorrelationQuery {
     min_severity: Option<Severity>,
 }
 
+/// Query parameters for vulnerability grouping
+#[derive(Debug, Deserialize)]
+pub struct VulnerabilityGroupQuery {
+    /// Organization ID to group vulnerabilities for
+    organization_id: Uuid,
+}
+
+/// Response for grouped vulnerabilities
+#[derive(Debug, Serialize)]
+pub struct VulnerabilityGroupResponse {
+    /// Groups of open vulnerabilities, most severe first
+    groups: Vec<VulnerabilityGroup>,
+}
+
 /// Query parameters for similar vulnerabilities
 #[derive(Debug, Deserialize)]
 pub struct SimilarVulnerabilityQuery {
@@ -87,18 +105,22 @@ pub struct CreateVulnerabilityDto {
 pub async fn list_vulnerabilities(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VulnerabilityQuery>,
-) -> Result<Json<VulnerabilityListResponse>> {
+    Pagination { limit, offset }: Pagination,
+) -> Result<(HeaderMap, Json<VulnerabilityListResponse>)> {
+    let sort = sort_params(query.sort_by, query.sort_order)?;
+
     // Get vulnerabilities from service
     let vulnerabilities = convert_result(
         state
             .vulnerability_service
-            .list_vulnerabilities(
+            .list_vulnerabilities_sorted(
                 query.asset_id,
                 query.port_id,
                 query.severity,
                 query.status,
-                query.limit.unwrap_or(20),
-                query.offset.unwrap_or(0),
+                sort,
+                limit,
+                offset,
             )
             .await,
     )?;
@@ -111,10 +133,13 @@ pub async fn list_vulnerabilities(
             .await,
     )?;
 
-    Ok(Json(VulnerabilityListResponse {
-        vulnerabilities,
-        total,
-    }))
+    Ok((
+        total_count_headers(total),
+        Json(VulnerabilityListResponse {
+            vulnerabilities,
+            total,
+        }),
+    ))
 }
 
 /// Get a single vulnerability by ID
@@ -184,6 +209,26 @@ pub async fn update_vulnerability(
     Ok(Json(updated_vulnerability))
 }
 
+/// Set the status of many vulnerabilities at once. IDs outside the caller's
+/// organization are ignored.
+pub async fn bulk_update_vulnerability_status(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<BulkStatusUpdateRequest<VulnerabilityStatus>>,
+) -> Result<Json<BulkUpdateResponse>> {
+    request.validate()?;
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let updated = convert_result(
+        state
+            .vulnerability_service
+            .bulk_update_vulnerability_status(organization_id, request.ids, request.status)
+            .await,
+    )?;
+
+    Ok(Json(BulkUpdateResponse { updated }))
+}
+
 /// Delete a vulnerability by ID
 pub async fn delete_vulnerability(
     State(state): State<Arc<AppState>>,
@@ -208,6 +253,50 @@ pub async fn correlate_vulnerabilities(
     Ok(Json(CorrelatedVulnerabilityResponse { correlations }))
 }
 
+/// Group open vulnerabilities shared across assets in an organization
+pub async fn group_vulnerabilities(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VulnerabilityGroupQuery>,
+) -> Result<Json<VulnerabilityGroupResponse>> {
+    let groups = convert_result(
+        state
+            .vulnerability_service
+            .correlate(query.organization_id)
+            .await,
+    )?;
+
+    Ok(Json(VulnerabilityGroupResponse { groups }))
+}
+
+/// Most assets placed in the correlation graph
+const CORRELATION_GRAPH_ASSET_LIMIT: usize = 1000;
+
+/// Graph of the caller's organization's open vulnerabilities and the assets
+/// they connect through a shared CVE, technology or title
+pub async fn get_correlation_graph(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<CorrelationGraph>> {
+    let organization_id = claims.organization_id()?.ok_or(ApiError::Forbidden)?;
+
+    let groups = convert_result(state.vulnerability_service.correlate(organization_id).await)?;
+    let assets = convert_result(
+        state
+            .asset_service
+            .list_assets(
+                Some(organization_id),
+                None,
+                None,
+                None,
+                CORRELATION_GRAPH_ASSET_LIMIT,
+                0,
+            )
+            .await,
+    )?;
+
+    Ok(Json(CorrelationGraph::new(&groups, &assets)))
+}
+
 /// Find similar vulnerabilities to a specific vulnerability
 pub async fn find_similar_vulnerabilities(
     State(state): State<Arc<AppState>>,
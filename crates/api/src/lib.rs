@@ -1,44 +1,71 @@
 pub mod errors;
+pub mod events;
+pub mod extract;
+pub mod graphql;
 pub mod handlers;
+pub mod login_lockout;
 pub mod middleware;
 pub mod routes;
 pub mod state;
 pub mod test_utils;
+pub mod tls;
+pub mod token_denylist;
 
 use std::net::SocketAddr;
 
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
 use shared::{config::Config, errors::Result};
-use tower_http::trace::{self, TraceLayer};
-use tracing::{info, Level};
+use tracing::info;
 
 use crate::routes::create_router;
 use crate::state::AppState;
 
 pub async fn run(config: Config) -> Result<()> {
+    // Certificates are checked before anything else is set up
+    let tls = tls::tls_config(&config)?;
+
     // Create the application state
     let state = AppState::new(&config).await?;
 
-    // Set up tracing middleware
-    let trace_layer = TraceLayer::new_for_http()
-        .make_span_with(trace::DefaultMakeSpan::new().level(Level::INFO))
-        .on_response(trace::DefaultOnResponse::new().level(Level::INFO));
-
     // Build the router with routes
-    let app = create_router(state).layer(trace_layer);
+    let app = create_router(state);
 
     // Build the server address
     let addr = SocketAddr::from((config.host, config.port));
 
     // Bind to the address
-    let listener = tokio::net::TcpListener::bind(&addr).await.map_err(|e| {
+    let listener = std::net::TcpListener::bind(addr).map_err(|e| {
         shared::errors::AppError::external_service(format!("Failed to bind to address: {}", e))
     })?;
 
-    info!("Listening on http://{}", addr);
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    info!("Listening on {}://{}", scheme, addr);
+
+    serve(listener, app, tls).await
+}
 
-    // Start the server
-    axum::serve(listener, app)
-        .await
+/// Serve `app` on `listener`, over HTTPS when `tls` is set and plain HTTP
+/// otherwise
+pub async fn serve(
+    listener: std::net::TcpListener,
+    app: Router,
+    tls: Option<RustlsConfig>,
+) -> Result<()> {
+    // The peer address is made available for the audit log
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+    let served = match tls {
+        Some(tls) => {
+            axum_server::from_tcp_rustls(listener, tls)
+                .serve(service)
+                .await
+        }
+        None => {
+            listener.set_nonblocking(true)?;
+            axum::serve(tokio::net::TcpListener::from_std(listener)?, service).await
+        }
+    };
+    served
         .map_err(|e| shared::errors::AppError::external_service(format!("Server error: {}", e)))?;
 
     Ok(())
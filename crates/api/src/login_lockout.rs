@@ -0,0 +1,164 @@
+//! Lock an account out of logging in after repeated failures.
+//!
+//! Failed logins are counted in memory per account and client address, so
+//! a credential stuffing run against an account is stopped without letting
+//! anyone lock a victim out from elsewhere. They are also counted per
+//! account alone, against a higher threshold, so guessing spread over many
+//! addresses is stopped too. Counts reset on a successful login or once the
+//! cooldown has passed since the last failure.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use shared::config::Config;
+
+/// Account and address a login attempt is counted against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoginKey {
+    email: String,
+    ip: Option<IpAddr>,
+}
+
+impl LoginKey {
+    /// Key for `email`, ignoring case, from `ip`
+    pub fn new(email: &str, ip: Option<IpAddr>) -> Self {
+        Self {
+            email: email.trim().to_lowercase(),
+            ip,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FailedLogins {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl FailedLogins {
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.locked_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+    }
+}
+
+/// Failed logins per account and address, and per account alone
+#[derive(Debug, Default)]
+struct Failures {
+    by_address: HashMap<LoginKey, FailedLogins>,
+    by_account: HashMap<String, FailedLogins>,
+}
+
+/// Failed login counts and the accounts currently locked
+#[derive(Debug)]
+pub struct LoginLockout {
+    threshold: u32,
+    account_threshold: u32,
+    cooldown: Duration,
+    failures: Mutex<Failures>,
+}
+
+impl LoginLockout {
+    /// Lock after `threshold` failures from one address, or
+    /// `account_threshold` from any, for `cooldown`
+    pub fn new(threshold: u32, account_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            account_threshold,
+            cooldown,
+            failures: Mutex::new(Failures::default()),
+        }
+    }
+
+    /// Lockout as configured by `LOGIN_LOCKOUT_THRESHOLD`,
+    /// `LOGIN_ACCOUNT_LOCKOUT_THRESHOLD` and `LOGIN_LOCKOUT_SECS`
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.login_lockout_threshold,
+            config.login_account_lockout_threshold,
+            Duration::from_secs(config.login_lockout_secs),
+        )
+    }
+
+    /// How much longer `key` is locked out, or `None` if it may log in
+    pub fn locked_for(&self, key: &LoginKey) -> Option<Duration> {
+        let now = Instant::now();
+        let failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+
+        let by_address = failures
+            .by_address
+            .get(key)
+            .and_then(|failed| failed.remaining(now));
+        let by_account = failures
+            .by_account
+            .get(&key.email)
+            .and_then(|failed| failed.remaining(now));
+        by_address.max(by_account)
+    }
+
+    /// Count a failed login, locking `key` once it reaches the threshold,
+    /// or its account once that reaches the account threshold
+    pub fn record_failure(&self, key: &LoginKey) {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        let failures = &mut *failures;
+
+        // Forget failures and lockouts that have run their course, so the
+        // maps only hold recent attempts
+        let recent =
+            |failed: &FailedLogins| now.duration_since(failed.last_failure) < self.cooldown;
+        failures.by_address.retain(|_, failed| recent(failed));
+        failures.by_account.retain(|_, failed| recent(failed));
+
+        count_failure(
+            failures
+                .by_address
+                .entry(key.clone())
+                .or_insert_with(|| new_failure(now)),
+            self.threshold,
+            self.cooldown,
+            now,
+        );
+        count_failure(
+            failures
+                .by_account
+                .entry(key.email.clone())
+                .or_insert_with(|| new_failure(now)),
+            self.account_threshold,
+            self.cooldown,
+            now,
+        );
+    }
+
+    /// Clear the failures counted against `key` and its account after it
+    /// logs in
+    pub fn record_success(&self, key: &LoginKey) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        failures.by_address.remove(key);
+        failures.by_account.remove(&key.email);
+    }
+}
+
+fn new_failure(now: Instant) -> FailedLogins {
+    FailedLogins {
+        count: 0,
+        last_failure: now,
+        locked_until: None,
+    }
+}
+
+/// Add a failure, locking for `cooldown` once `threshold` is reached
+fn count_failure(failed: &mut FailedLogins, threshold: u32, cooldown: Duration, now: Instant) {
+    failed.count += 1;
+    failed.last_failure = now;
+
+    if failed.count >= threshold {
+        failed.locked_until = Some(now + cooldown);
+    }
+}
@@ -1,5 +1,5 @@
 use api::run;
-use shared::config::Config;
+use shared::{config::Config, logging::init_tracing};
 use tracing::info;
 
 #[tokio::main]
@@ -8,9 +8,7 @@ async fn main() -> anyhow::Result<()> {
     let config = Config::from_env()?;
 
     // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(&config.log_level)
-        .init();
+    init_tracing(&config);
 
     info!("Starting API server on {}:{}", config.host, config.port);
 
@@ -0,0 +1,160 @@
+use std::{net::IpAddr, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use backend::models::AuditLogEntry;
+use shared::types::{AuditAction, ID};
+use uuid::Uuid;
+
+use crate::{extract::ClientIp, middleware::auth::Claims, state::AppState};
+
+/// Resources whose changes are audited, by their path segment under `/api`
+const AUDITED_RESOURCES: &[(&str, &str)] = &[
+    ("assets", "asset"),
+    ("vulnerabilities", "vulnerability"),
+    ("organizations", "organization"),
+    ("discovery-tasks", "discovery_task"),
+    ("scan-schedules", "scan_schedule"),
+    ("scan-profiles", "scan_profile"),
+];
+
+/// What a mutating request does, worked out from its method and path
+#[derive(Debug)]
+struct AuditTarget {
+    action: AuditAction,
+    resource_type: &'static str,
+    resource_id: Option<ID>,
+}
+
+impl AuditTarget {
+    /// `POST /assets` creates, `DELETE /assets/{id}` deletes and any other
+    /// mutating request under an audited resource updates it. Reads and
+    /// unaudited paths give `None`.
+    fn from_request(method: &Method, path: &str) -> Option<Self> {
+        let mut segments = path.trim_matches('/').split('/');
+        let first = segments.next()?;
+        let resource_type = AUDITED_RESOURCES
+            .iter()
+            .find(|(segment, _)| *segment == first)
+            .map(|(_, resource_type)| *resource_type)?;
+
+        let rest: Vec<&str> = segments.filter(|segment| !segment.is_empty()).collect();
+        let resource_id = rest
+            .first()
+            .and_then(|segment| Uuid::parse_str(segment).ok());
+
+        let action = match *method {
+            Method::POST if rest.is_empty() => AuditAction::Create,
+            Method::DELETE if rest.len() == 1 && resource_id.is_some() => AuditAction::Delete,
+            Method::POST | Method::PUT | Method::PATCH | Method::DELETE => AuditAction::Update,
+            _ => return None,
+        };
+
+        Some(Self {
+            action,
+            resource_type,
+            resource_id,
+        })
+    }
+}
+
+/// Read the `id` of a newly created resource from the response body, handing
+/// back an equivalent response. Bodies over `limit` bytes aren't read.
+async fn created_resource_id(response: Response, limit: usize) -> (Response, Option<ID>) {
+    let (parts, body) = response.into_parts();
+
+    match axum::body::to_bytes(body, limit).await {
+        Ok(bytes) => {
+            let id = serde_json::from_slice::<serde_json::Value>(&bytes)
+                .ok()
+                .and_then(|body| body.get("id")?.as_str()?.parse().ok());
+            (Response::from_parts(parts, Body::from(bytes)), id)
+        }
+        Err(e) => {
+            tracing::warn!("Couldn't read created resource from response: {}", e);
+            (Response::from_parts(parts, Body::empty()), None)
+        }
+    }
+}
+
+/// Record who created, changed or deleted a resource, once the request has
+/// succeeded. Must run inside `auth_middleware` so the user's claims are
+/// available. Failing to write the entry is logged, not returned to the user.
+pub async fn audit_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(target) = AuditTarget::from_request(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let Some(claims) = request.extensions().get::<Claims>().cloned() else {
+        return next.run(request).await;
+    };
+    let ClientIp(ip_address) = ClientIp::from_request_parts(
+        request.headers(),
+        request.extensions(),
+        &state.config.trusted_proxies,
+    );
+
+    let response = next.run(request).await;
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let (response, resource_id) = match target.resource_id {
+        None if target.action == AuditAction::Create => {
+            created_resource_id(response, state.config.max_request_body_bytes).await
+        }
+        resource_id => (response, resource_id),
+    };
+
+    record_audit_entry(
+        &state,
+        &claims,
+        target.action,
+        target.resource_type,
+        resource_id,
+        ip_address,
+    )
+    .await;
+
+    response
+}
+
+/// Write an audit log entry for a change made by `claims`' user, logging
+/// any failure. Shared by `audit_middleware` and the GraphQL mutations.
+pub(crate) async fn record_audit_entry(
+    state: &AppState,
+    claims: &Claims,
+    action: AuditAction,
+    resource_type: &str,
+    resource_id: Option<ID>,
+    ip_address: Option<IpAddr>,
+) {
+    let Ok(user_id) = claims.user_id() else {
+        return;
+    };
+    let entry = AuditLogEntry::new(
+        user_id,
+        claims.organization_id().ok().flatten(),
+        action,
+        resource_type.to_string(),
+        resource_id,
+        ip_address.map(|ip| ip.to_string()),
+    );
+
+    if let Err(e) = state.audit_log_repository.create_entry(&entry).await {
+        tracing::error!(
+            "Failed to record {:?} of {} in the audit log: {}",
+            entry.action,
+            entry.resource_type,
+            e
+        );
+    }
+}
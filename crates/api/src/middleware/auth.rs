@@ -17,7 +17,7 @@ use crate::{errors::ApiError, state::AppState};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String,            // Subject (user ID)
-    pub role: String,           // User role
+    pub role: String,           // User role, e.g. "ANALYST"
     pub org: Option<String>,    // Organization ID
     pub exp: usize,             // Expiration time
     pub iat: usize,             // Issued at
@@ -53,6 +53,9 @@ impl Claims {
     }
 }
 
+/// Seconds of leeway for clock skew when checking a token's expiry
+pub const TOKEN_LEEWAY_SECS: u64 = 5;
+
 /// Authentication middleware
 pub async fn auth_middleware(
     State(state): State<Arc<AppState>>,
@@ -64,20 +67,20 @@ pub async fn auth_middleware(
         return Ok(next.run(req).await);
     }
 
-    // Get authorization header
-    let auth_header = req
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|header| header.to_str().ok())
-        .ok_or(ApiError::Unauthorized)?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err(ApiError::Unauthorized);
-    }
-
-    // Extract the token
-    let token = &auth_header["Bearer ".len()..];
+    // Get authorization header, falling back to the query string for event
+    // streams since browsers can't set headers on an EventSource
+    let token = match req.headers().get(header::AUTHORIZATION) {
+        Some(auth_header) => {
+            let auth_header = auth_header.to_str().map_err(|_| ApiError::Unauthorized)?;
+
+            // Check if it's a Bearer token and extract it
+            auth_header
+                .strip_prefix("Bearer ")
+                .ok_or(ApiError::Unauthorized)?
+                .to_string()
+        }
+        None => event_stream_token(&req).ok_or(ApiError::Unauthorized)?,
+    };
 
     // Set up validation with more strict settings
     let mut validation = Validation::default();
@@ -85,11 +88,11 @@ pub async fn auth_middleware(
     validation.set_audience(&["easm-client"]);
     validation.validate_exp = true;
     validation.validate_nbf = true;
-    validation.leeway = 5; // 5 seconds of leeway for clock skew
+    validation.leeway = TOKEN_LEEWAY_SECS;
 
     // Validate token
     let token_data = match decode::<Claims>(
-        token,
+        &token,
         &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
         &validation,
     ) {
@@ -102,12 +105,10 @@ pub async fn auth_middleware(
         },
     };
 
-    // Check if token has been revoked (pseudocode - real implementation would check Redis or DB)
-    // if let Some(revoked_tokens) = state.revoked_tokens.read().await.get(&token_data.claims.sub) {
-    //     if revoked_tokens.contains(&token_data.claims.jti) {
-    //         return Err(ApiError::TokenRevoked);
-    //     }
-    // }
+    // Reject tokens revoked by logging out
+    if state.token_denylist.is_revoked(&token_data.claims) {
+        return Err(ApiError::TokenRevoked);
+    }
 
     // Add claims to request extensions
     req.extensions_mut().insert(token_data.claims.clone());
@@ -171,10 +172,29 @@ where
     Ok(next.run(req).await)
 }
 
+/// Query parameter an event stream may pass its access token in
+const ACCESS_TOKEN_PARAM: &str = "access_token";
+
+/// Access token given in the query string of a request for an event stream
+fn event_stream_token(req: &Request) -> Option<String> {
+    let accepts_event_stream = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+    if !accepts_event_stream {
+        return None;
+    }
+
+    url::form_urlencoded::parse(req.uri().query()?.as_bytes())
+        .find(|(name, _)| name == ACCESS_TOKEN_PARAM)
+        .map(|(_, token)| token.into_owned())
+}
+
 /// Generate JWT token for authenticated user
 pub fn generate_token(
     user_id: &str,
-    role: &str,
+    role: UserRole,
     organization_id: Option<&str>,
     config: &Config,
 ) -> Result<String, ApiError> {
@@ -216,20 +236,3 @@ pub fn generate_token(
     )
     .map_err(|e| ApiError::InternalServerError(format!("Token generation error: {}", e)))
 }
-
-/// Revoke a JWT token (pseudocode - would be implemented with Redis or DB)
-pub async fn revoke_token(
-    _user_id: &str,
-    _token_id: &str,
-    _state: &Arc<AppState>,
-) -> Result<(), ApiError> {
-    // Implementation would store the JTI in a Redis set or DB table
-    // with a TTL matching the token's original expiration
-
-    // Example with Redis:
-    // let key = format!("revoked:{}:{}", user_id, token_id);
-    // let _: () = state.redis.set_ex(key, "1", token_ttl_seconds).await
-    //    .map_err(|e| ApiError::InternalServerError(format!("Token revocation error: {}", e)))?;
-
-    Ok(())
-}
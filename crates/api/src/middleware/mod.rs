@@ -1,6 +1,12 @@
+pub mod audit;
 pub mod auth;
+pub mod request_id;
+pub mod security_headers;
 
+pub use audit::audit_middleware;
 pub use auth::{
     auth_middleware, require_admin, require_asset_modification, require_discovery_permission,
     require_user_management, require_vulnerability_modification,
 };
+pub use request_id::{current_request_id, request_id_middleware, RequestId, REQUEST_ID_HEADER};
+pub use security_headers::{security_headers, security_headers_layer};
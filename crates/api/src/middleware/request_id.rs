@@ -0,0 +1,88 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::trace::MakeSpan;
+use tracing::{Level, Span};
+use uuid::Uuid;
+
+/// Header carrying the request ID in both directions
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request ID we accept; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// ID correlating a request with its logs and its response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Use the client's `X-Request-Id` if it is usable, otherwise a new UUID
+    fn from_request(request: &Request) -> Self {
+        let provided = request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN);
+
+        match provided {
+            Some(id) => RequestId(id.to_string()),
+            None => RequestId(Uuid::new_v4().to_string()),
+        }
+    }
+}
+
+/// The ID of the request currently being handled, if any
+pub fn current_request_id() -> Option<RequestId> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Assign every request an ID, make it available to handlers, error
+/// responses and the `TraceLayer` span, and echo it in the response.
+/// Must wrap the `TraceLayer` so the ID exists when its span is created.
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId::from_request(&request);
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = CURRENT_REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+/// `TraceLayer` span for a request, tagged with its request ID
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestIdMakeSpan;
+
+impl<B> MakeSpan<B> for RequestIdMakeSpan {
+    fn make_span(&mut self, request: &axum::http::Request<B>) -> Span {
+        let request_id = request
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.0.as_str())
+            .unwrap_or_default();
+
+        tracing::span!(
+            Level::INFO,
+            "request",
+            method = %request.method(),
+            // Only the path, so tokens passed as query parameters stay out
+            // of the logs
+            uri = %request.uri().path(),
+            version = ?request.version(),
+            request_id = %request_id,
+        )
+    }
+}
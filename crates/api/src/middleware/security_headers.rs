@@ -0,0 +1,52 @@
+use axum::{
+    http::{header, HeaderName, HeaderValue},
+    response::Response,
+};
+use shared::config::Config;
+use tower::util::MapResponseLayer;
+
+/// Headers recommended by the OWASP Secure Headers Project, or none when
+/// security headers are disabled in the configuration
+pub fn security_headers(config: &Config) -> Vec<(HeaderName, HeaderValue)> {
+    if !config.security_headers {
+        return Vec::new();
+    }
+
+    let mut headers = vec![
+        (
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        ),
+        (header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        (
+            header::REFERRER_POLICY,
+            HeaderValue::from_static("no-referrer"),
+        ),
+    ];
+
+    // Config validation rejects policies that aren't valid header values
+    match HeaderValue::from_str(&config.content_security_policy) {
+        Ok(policy) => headers.push((header::CONTENT_SECURITY_POLICY, policy)),
+        Err(e) => tracing::warn!("Not sending invalid Content-Security-Policy: {}", e),
+    }
+
+    headers
+}
+
+/// Layer adding the configured security headers to every response. Headers
+/// a handler already set are left alone.
+pub fn security_headers_layer(
+    config: &Config,
+) -> MapResponseLayer<impl Fn(Response) -> Response + Clone + Send + Sync + 'static> {
+    let headers = security_headers(config);
+
+    MapResponseLayer::new(move |mut response: Response| {
+        for (name, value) in &headers {
+            response
+                .headers_mut()
+                .entry(name)
+                .or_insert_with(|| value.clone());
+        }
+        response
+    })
+}
@@ -1,37 +1,73 @@
 use axum::{
-    middleware::from_fn_with_state,
-    routing::{get, post},
-    Router,
+    extract::DefaultBodyLimit,
+    http::StatusCode,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{get, patch, post},
+    Extension, Router,
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tower_http::{
+    compression::CompressionLayer,
     cors::{Any, CorsLayer},
-    trace::TraceLayer,
+    limit::RequestBodyLimitLayer,
+    timeout::TimeoutLayer,
+    trace::{DefaultOnResponse, TraceLayer},
 };
+use tracing::Level;
 
 use crate::{
+    graphql::build_schema,
     handlers::{
-        asset_handler::{create_asset, delete_asset, get_asset, list_assets, update_asset},
-        auth_handler::{login, logout, refresh_token, register},
+        asset_handler::{
+            add_asset_tags, bulk_update_asset_status, create_asset, delete_asset,
+            discover_relationships, get_asset, get_asset_details, get_asset_graph,
+            get_asset_history, get_asset_tags, list_assets, remove_asset_tag, update_asset,
+        },
+        audit_handler::list_audit_log,
+        auth_handler::{change_password, login, logout, logout_all, refresh_token, register},
+        dashboard_handler::{get_dashboard_stats, get_dashboard_trends},
         discovery_task_handler::{
             cancel_discovery_task, create_discovery_task, delete_discovery_task,
-            get_discovery_task, list_discovery_tasks,
+            get_discovery_task, get_discovery_task_summary, list_discovery_task_assets,
+            list_discovery_tasks,
         },
-        health_handler::health_check,
+        event_handler::stream_events,
+        graphql_handler::graphql,
+        health_handler::{health_check, version},
+        metrics_handler::metrics,
         organization_handler::{
             create_organization, delete_organization, get_organization, list_organizations,
             update_organization,
         },
         report_handler,
+        scan_profile_handler::{
+            create_scan_profile, delete_scan_profile, get_scan_profile, launch_scan_profile,
+            list_scan_profiles, update_scan_profile,
+        },
+        scan_schedule_handler::{
+            create_scan_schedule, delete_scan_schedule, get_scan_schedule, list_scan_schedules,
+            update_scan_schedule,
+        },
+        technology_handler::{get_technology_distribution, list_technologies},
+        user_handler::{
+            activate_user, create_user, deactivate_user, get_user, list_users, update_user,
+        },
         vulnerability_handler::{
-            correlate_vulnerabilities, create_vulnerability, delete_vulnerability,
-            find_similar_vulnerabilities, get_vulnerability, list_vulnerabilities,
-            update_vulnerability,
+            bulk_update_vulnerability_status, correlate_vulnerabilities, create_vulnerability,
+            delete_vulnerability, find_similar_vulnerabilities, get_correlation_graph,
+            get_vulnerability, group_vulnerabilities, list_vulnerabilities, update_vulnerability,
         },
+        TOTAL_COUNT_HEADER,
     },
-    middleware::auth::{
-        auth_middleware, require_admin, require_asset_modification, require_user_management,
-        require_vulnerability_modification,
+    middleware::{
+        audit::audit_middleware,
+        auth::{
+            auth_middleware, require_admin, require_asset_modification,
+            require_discovery_permission, require_user_management,
+            require_vulnerability_modification,
+        },
+        request_id::{request_id_middleware, RequestIdMakeSpan, REQUEST_ID_HEADER},
+        security_headers::security_headers_layer,
     },
     state::AppState,
 };
@@ -42,7 +78,24 @@ pub fn create_router(state: AppState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([TOTAL_COUNT_HEADER, REQUEST_ID_HEADER]);
+
+    // Security headers, as configured, for every response
+    let security_headers = security_headers_layer(&state.config);
+
+    // gzip or brotli, whichever the client prefers, unless disabled
+    let compression = CompressionLayer::new()
+        .gzip(state.config.compression)
+        .br(state.config.compression);
+
+    // Bounded bodies and handler time, so a slow or huge upload can't tie
+    // up the server. The body limit replaces axum's own 2 MiB default.
+    let body_limit = RequestBodyLimitLayer::new(state.config.max_request_body_bytes);
+    let timeout = TimeoutLayer::with_status_code(
+        StatusCode::REQUEST_TIMEOUT,
+        Duration::from_secs(state.config.request_timeout_secs),
+    );
 
     // Wrap the state in an Arc
     let state = Arc::new(state);
@@ -51,15 +104,27 @@ pub fn create_router(state: AppState) -> Router {
     Router::new()
         // Health check endpoint (no auth)
         .route("/health", get(health_check))
+        // Metrics for Prometheus to scrape (no auth)
+        .route("/metrics", get(metrics))
         // Auth routes (NO middleware)
         .route("/api/auth/register", post(register))
         .route("/api/auth/login", post(login))
+        // GraphQL, behind the same authentication as the REST API
+        .route(
+            "/graphql",
+            post(graphql)
+                .layer(Extension(build_schema()))
+                .route_layer(from_fn_with_state(state.clone(), auth_middleware)),
+        )
         .nest(
             "/api",
             Router::new()
                 // Auth routes that require authentication
                 .route("/auth/logout", post(logout))
+                .route("/auth/logout-all", post(logout_all))
                 .route("/auth/refresh", post(refresh_token))
+                .route("/auth/change-password", post(change_password))
+                .route("/version", get(version))
                 // Protected routes with authentication
                 // Organization management - admin or manager only
                 .route("/organizations", get(list_organizations))
@@ -79,6 +144,29 @@ pub fn create_router(state: AppState) -> Router {
                     axum::routing::delete(delete_organization)
                         .route_layer(from_fn_with_state(state.clone(), require_admin)),
                 )
+                // User management within an organization - admin or manager only
+                .route(
+                    "/users",
+                    get(list_users)
+                        .post(create_user)
+                        .route_layer(from_fn_with_state(state.clone(), require_user_management)),
+                )
+                .route(
+                    "/users/{id}",
+                    get(get_user)
+                        .put(update_user)
+                        .route_layer(from_fn_with_state(state.clone(), require_user_management)),
+                )
+                .route(
+                    "/users/{id}/deactivate",
+                    post(deactivate_user)
+                        .route_layer(from_fn_with_state(state.clone(), require_user_management)),
+                )
+                .route(
+                    "/users/{id}/activate",
+                    post(activate_user)
+                        .route_layer(from_fn_with_state(state.clone(), require_user_management)),
+                )
                 // Assets API - different permissions for different actions
                 .route("/assets", get(list_assets))
                 .route(
@@ -88,7 +176,39 @@ pub fn create_router(state: AppState) -> Router {
                         require_asset_modification,
                     )),
                 )
+                .route(
+                    "/assets/bulk",
+                    patch(bulk_update_asset_status).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                .route("/assets/graph", get(get_asset_graph))
+                .route(
+                    "/assets/discover-relationships",
+                    post(discover_relationships).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_discovery_permission,
+                    )),
+                )
                 .route("/assets/{id}", get(get_asset))
+                .route("/assets/{id}/details", get(get_asset_details))
+                .route("/assets/{id}/history", get(get_asset_history))
+                .route("/assets/{id}/tags", get(get_asset_tags))
+                .route(
+                    "/assets/{id}/tags",
+                    post(add_asset_tags).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                .route(
+                    "/assets/{id}/tags/{tag}",
+                    axum::routing::delete(remove_asset_tag).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
                 .route(
                     "/assets/{id}",
                     axum::routing::put(update_asset).route_layer(from_fn_with_state(
@@ -109,20 +229,84 @@ pub fn create_router(state: AppState) -> Router {
                     "/discovery-tasks",
                     post(create_discovery_task).route_layer(from_fn_with_state(
                         state.clone(),
-                        require_asset_modification,
+                        require_discovery_permission,
                     )),
                 )
                 .route("/discovery-tasks/{id}", get(get_discovery_task))
+                .route("/discovery/jobs", get(list_discovery_tasks))
+                .route(
+                    "/discovery/jobs/{id}/summary",
+                    get(get_discovery_task_summary),
+                )
+                .route(
+                    "/discovery/jobs/{id}/assets",
+                    get(list_discovery_task_assets),
+                )
                 .route(
                     "/discovery-tasks/{id}/cancel",
                     post(cancel_discovery_task).route_layer(from_fn_with_state(
                         state.clone(),
-                        require_asset_modification,
+                        require_discovery_permission,
                     )),
                 )
                 .route(
                     "/discovery-tasks/{id}",
                     axum::routing::delete(delete_discovery_task).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_discovery_permission,
+                    )),
+                )
+                // Scan Schedules API
+                .route("/scan-schedules", get(list_scan_schedules))
+                .route(
+                    "/scan-schedules",
+                    post(create_scan_schedule).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                .route("/scan-schedules/{id}", get(get_scan_schedule))
+                .route(
+                    "/scan-schedules/{id}",
+                    axum::routing::put(update_scan_schedule).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                .route(
+                    "/scan-schedules/{id}",
+                    axum::routing::delete(delete_scan_schedule).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                // Scan Profiles API
+                .route("/scan-profiles", get(list_scan_profiles))
+                .route(
+                    "/scan-profiles",
+                    post(create_scan_profile).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                .route(
+                    "/scan-profiles/launch",
+                    post(launch_scan_profile).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_discovery_permission,
+                    )),
+                )
+                .route("/scan-profiles/{id}", get(get_scan_profile))
+                .route(
+                    "/scan-profiles/{id}",
+                    axum::routing::put(update_scan_profile).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_asset_modification,
+                    )),
+                )
+                .route(
+                    "/scan-profiles/{id}",
+                    axum::routing::delete(delete_scan_profile).route_layer(from_fn_with_state(
                         state.clone(),
                         require_asset_modification,
                     )),
@@ -136,6 +320,13 @@ pub fn create_router(state: AppState) -> Router {
                         require_vulnerability_modification,
                     )),
                 )
+                .route(
+                    "/vulnerabilities/bulk",
+                    patch(bulk_update_vulnerability_status).route_layer(from_fn_with_state(
+                        state.clone(),
+                        require_vulnerability_modification,
+                    )),
+                )
                 .route("/vulnerabilities/{id}", get(get_vulnerability))
                 .route(
                     "/vulnerabilities/{id}",
@@ -153,10 +344,26 @@ pub fn create_router(state: AppState) -> Router {
                 )
                 // Vulnerability correlation endpoints - available to all authenticated users
                 .route("/vulnerabilities/correlate", get(correlate_vulnerabilities))
+                .route("/vulnerabilities/groups", get(group_vulnerabilities))
+                .route(
+                    "/vulnerabilities/correlation-graph",
+                    get(get_correlation_graph),
+                )
                 .route(
                     "/vulnerabilities/{id}/similar",
                     get(find_similar_vulnerabilities),
                 )
+                // Technologies
+                .route("/technologies", get(list_technologies))
+                .route(
+                    "/technologies/distribution",
+                    get(get_technology_distribution),
+                )
+                // Live asset and vulnerability changes
+                .route("/events", get(stream_events))
+                // Dashboard
+                .route("/dashboard/stats", get(get_dashboard_stats))
+                .route("/dashboard/trends", get(get_dashboard_trends))
                 // Reports
                 .route(
                     "/reports/vulnerabilities",
@@ -167,13 +374,33 @@ pub fn create_router(state: AppState) -> Router {
                     get(report_handler::generate_asset_report),
                 )
                 .route("/reports/{report_id}", get(report_handler::download_report))
+                // Audit log - admin only
+                .route(
+                    "/audit",
+                    get(list_audit_log)
+                        .route_layer(from_fn_with_state(state.clone(), require_admin)),
+                )
+                // Record changes in the audit log, inside authentication so
+                // the acting user is known
+                .route_layer(from_fn_with_state(state.clone(), audit_middleware))
                 // Apply authentication middleware to all routes under /api
                 .route_layer(from_fn_with_state(state.clone(), auth_middleware)),
         )
         // Add state
         .with_state(state)
+        .layer(timeout)
+        .layer(body_limit)
+        .layer(DefaultBodyLimit::disable())
         // Add middleware (cors applies to all routes, including /health)
-        // TraceLayer also applies to all routes
-        .layer(TraceLayer::new_for_http())
+        // TraceLayer also applies to all routes, with spans tagged by request ID
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(RequestIdMakeSpan)
+                .on_response(DefaultOnResponse::new().level(Level::INFO)),
+        )
         .layer(cors)
+        .layer(security_headers)
+        .layer(compression)
+        // Outermost, so the request ID is assigned before anything else runs
+        .layer(from_fn(request_id_middleware))
 }
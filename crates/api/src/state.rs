@@ -5,14 +5,43 @@ use backend::{
         AssetServiceImpl, DiscoveryServiceImpl, OrganizationServiceImpl, UserServiceImpl,
         VulnerabilityServiceImpl,
     },
-    AssetService, DiscoveryJobRepository, DiscoveryService, OrganizationService, UserService,
-    VulnerabilityService,
+    AssetService, AuditLogRepository, DiscoveryJobRepository, DiscoveryService,
+    OrganizationService, PortRepository, ScanProfileRepository, ScanScheduleRepository,
+    TechnologyRepository, UserService, VulnerabilityService,
+};
+use infrastructure::{
+    database::{Database, DatabaseOptions},
+    repositories::RepositoryFactory,
 };
-use infrastructure::{database::Database, repositories::RepositoryFactory};
 use redis::Client as RedisClient;
+use serde::Serialize;
 use shared::{config::Config, errors::Result};
 use sqlx::PgPool;
 
+use crate::{events::EventBus, login_lockout::LoginLockout, token_denylist::TokenDenylist};
+
+/// What build of the API is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    /// Crate version
+    pub version: &'static str,
+    /// Commit the binary was built from, `unknown` outside a git checkout
+    pub git_commit: &'static str,
+    /// When the binary was built, as RFC 3339
+    pub built_at: &'static str,
+}
+
+impl BuildInfo {
+    /// This binary's build, as recorded by the build script
+    pub const fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_commit: env!("EASM_GIT_COMMIT"),
+            built_at: env!("EASM_BUILD_TIMESTAMP"),
+        }
+    }
+}
+
 /// Application state shared across all routes
 #[derive(Clone)]
 pub struct AppState {
@@ -23,14 +52,24 @@ pub struct AppState {
     pub vulnerability_service: Arc<dyn VulnerabilityService>,
     pub discovery_service: Arc<dyn DiscoveryService>,
     pub discovery_job_repository: Arc<dyn DiscoveryJobRepository>,
+    pub port_repository: Arc<dyn PortRepository>,
+    pub technology_repository: Arc<dyn TechnologyRepository>,
+    pub scan_schedule_repository: Arc<dyn ScanScheduleRepository>,
+    pub scan_profile_repository: Arc<dyn ScanProfileRepository>,
+    pub audit_log_repository: Arc<dyn AuditLogRepository>,
     pub user_service: Arc<dyn UserService>,
     pub organization_service: Arc<dyn OrganizationService>,
+    pub login_lockout: Arc<LoginLockout>,
+    pub token_denylist: Arc<TokenDenylist>,
+    pub events: EventBus,
+    pub build_info: BuildInfo,
 }
 
 impl AppState {
     pub async fn new(config: &Config) -> Result<Self> {
         // Connect to the database
-        let db = Database::new(&config.database_url, 10).await?;
+        let db =
+            Database::connect(&config.database_url, &DatabaseOptions::from_config(config)).await?;
         let db_pool = db.pool;
 
         // Create repository factory
@@ -52,12 +91,19 @@ impl AppState {
         let discovery_asset_repo = repo_factory.asset_repository();
         let discovery_job_repo = repo_factory.discovery_job_repository();
         let organization_repo = repo_factory.organization_repository();
+        let port_repo = repo_factory.port_repository();
+        let technology_repo = repo_factory.technology_repository();
+        let scan_schedule_repo = repo_factory.scan_schedule_repository();
+        let scan_profile_repo = repo_factory.scan_profile_repository();
+        let audit_log_repo = repo_factory.audit_log_repository();
 
         // Create services
         let user_service: Arc<dyn UserService> =
             Arc::new(UserServiceImpl::new(user_repo, organization_repo.clone()));
-        let asset_service: Arc<dyn AssetService> =
-            Arc::new(AssetServiceImpl::new(asset_repo.clone()));
+        let asset_service: Arc<dyn AssetService> = Arc::new(
+            AssetServiceImpl::new(asset_repo.clone(), repo_factory.asset_history_repository())
+                .with_vulnerability_repository(vulnerability_repo.clone()),
+        );
         let vulnerability_service: Arc<dyn VulnerabilityService> = Arc::new(
             VulnerabilityServiceImpl::new(vulnerability_repo, asset_repo),
         );
@@ -68,6 +114,10 @@ impl AppState {
         let organization_service: Arc<dyn OrganizationService> =
             Arc::new(OrganizationServiceImpl::new(organization_repo));
 
+        // Relay changes made by any process to the live event streams
+        let events = EventBus::new();
+        events.listen(&db_pool).await?;
+
         Ok(Self {
             config: config.clone(),
             db_pool,
@@ -76,8 +126,17 @@ impl AppState {
             vulnerability_service,
             discovery_service,
             discovery_job_repository: discovery_job_repo,
+            port_repository: port_repo,
+            technology_repository: technology_repo,
+            scan_schedule_repository: scan_schedule_repo,
+            scan_profile_repository: scan_profile_repo,
+            audit_log_repository: audit_log_repo,
             user_service,
             organization_service,
+            login_lockout: Arc::new(LoginLockout::from_config(config)),
+            token_denylist: Arc::new(TokenDenylist::from_config(config)),
+            events,
+            build_info: BuildInfo::current(),
         })
     }
 }
@@ -10,19 +10,36 @@ use tower::ServiceExt;
 
 use crate::{handlers::auth_handler::AuthResponseDto, state::AppState};
 use backend::{
-    models::{Asset, Organization, User, Vulnerability},
+    models::{
+        Asset, AssetGraph, AssetHistory, DiscoveryJob, DiscoveryJobFilter, JobAssetLink,
+        JobResultSummary, Organization, User, Vulnerability, VulnerabilityGroup,
+    },
     Result,
 };
 use shared::{
     config::Config,
     types::{
-        AssetStatus, AssetType, JobStatus, JobType, Severity, UserRole, VulnerabilityStatus, ID,
+        AssetStatus, AssetType, JobStatus, JobType, Page, Severity, Sort, SortOrder, UserRole,
+        VulnerabilitySortField, VulnerabilityStatus, ID,
     },
 };
 use uuid::Uuid;
 
-#[derive(Clone)]
-pub struct MockAssetService;
+/// ID of the `test1.example.com` asset returned by `MockAssetService::list_assets`
+pub const MOCK_SUBDOMAIN_ASSET_ID: Uuid = Uuid::from_u128(0x1);
+
+/// ID of the `example.com` asset returned by `MockAssetService::list_assets`
+pub const MOCK_PARENT_DOMAIN_ASSET_ID: Uuid = Uuid::from_u128(0x2);
+
+/// The one password `MockUserService::login_user` rejects
+pub const MOCK_WRONG_PASSWORD: &str = "wrong-password";
+
+/// Relationships created through it are remembered, so creating the same one
+/// twice only counts it once
+#[derive(Clone, Default)]
+pub struct MockAssetService {
+    relationships: std::sync::Arc<std::sync::Mutex<std::collections::HashSet<(ID, ID, String)>>>,
+}
 
 #[derive(Clone)]
 pub struct MockUserService;
@@ -53,12 +70,19 @@ impl backend::UserService for MockUserService {
             email: email.to_string(),
             password_hash: "hashed_password".to_string(), // Mock hash
             role: UserRole::Analyst,                      // Default role
+            active: true,
             created_at: now,
             updated_at: now,
         })
     }
 
-    async fn login_user(&self, email: &str, _password: &str) -> Result<User> {
+    async fn login_user(&self, email: &str, password: &str) -> Result<User> {
+        if password == MOCK_WRONG_PASSWORD {
+            return Err(backend::Error::Authentication(
+                "Invalid credentials".to_string(),
+            ));
+        }
+
         // Return a mock user, assuming login is successful for the test email
         let now = chrono::Utc::now();
         Ok(User {
@@ -68,10 +92,68 @@ impl backend::UserService for MockUserService {
             email: email.to_string(),
             password_hash: "hashed_password".to_string(),
             role: UserRole::Analyst,
+            active: true,
             created_at: now,
             updated_at: now,
         })
     }
+
+    async fn get_user(&self, id: ID) -> Result<User> {
+        let now = chrono::Utc::now();
+        Ok(User {
+            id,
+            organization_id: Uuid::new_v4(),
+            username: "testuser".to_string(),
+            email: "testuser@example.com".to_string(),
+            password_hash: "hashed_password".to_string(),
+            role: UserRole::Analyst,
+            active: true,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    async fn change_password(
+        &self,
+        _id: ID,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<()> {
+        // Every test user's password is "password123"
+        if current_password != "password123" {
+            return Err(backend::Error::Authentication(
+                "Invalid credentials".to_string(),
+            ));
+        }
+        User::validate_password(new_password)
+    }
+
+    async fn list_users(
+        &self,
+        _organization_id: ID,
+        _limit: usize,
+        _offset: usize,
+    ) -> Result<Vec<User>> {
+        unimplemented!()
+    }
+
+    async fn count_users(&self, _organization_id: ID) -> Result<usize> {
+        unimplemented!()
+    }
+
+    async fn create_user(
+        &self,
+        _organization_id: ID,
+        _email: &str,
+        _password: &str,
+        _role: UserRole,
+    ) -> Result<User> {
+        unimplemented!()
+    }
+
+    async fn update_user(&self, _user: &User) -> Result<User> {
+        unimplemented!()
+    }
 }
 
 #[async_trait]
@@ -115,6 +197,7 @@ impl backend::AssetService for MockAssetService {
             created_at: now,
             updated_at: now,
             attributes: asset.attributes.clone(),
+            risk_score: asset.risk_score,
         })
     }
 
@@ -134,6 +217,7 @@ impl backend::AssetService for MockAssetService {
             attributes: serde_json::json!({
                 "hostname": "test",
             }),
+            risk_score: 42.5,
         })
     }
 
@@ -151,6 +235,7 @@ impl backend::AssetService for MockAssetService {
             created_at: asset.created_at,
             updated_at: now,
             attributes: asset.attributes.clone(),
+            risk_score: asset.risk_score,
         })
     }
 
@@ -164,15 +249,21 @@ impl backend::AssetService for MockAssetService {
         _organization_id: Option<ID>,
         _asset_type: Option<AssetType>,
         _status: Option<AssetStatus>,
+        tag: Option<String>,
         _limit: usize,
         _offset: usize,
     ) -> Result<Vec<Asset>> {
+        // Every test asset is tagged "production" and nothing else
+        if tag.is_some_and(|tag| tag != "production") {
+            return Ok(Vec::new());
+        }
+
         // Return a list of test assets
         let now = chrono::Utc::now();
 
         Ok(vec![
             Asset {
-                id: Uuid::new_v4(),
+                id: MOCK_SUBDOMAIN_ASSET_ID,
                 organization_id: Uuid::new_v4(),
                 asset_type: AssetType::Domain,
                 value: "test1.example.com".to_string(),
@@ -184,20 +275,22 @@ impl backend::AssetService for MockAssetService {
                 attributes: serde_json::json!({
                     "hostname": "test1",
                 }),
+                risk_score: 0.0,
             },
             Asset {
-                id: Uuid::new_v4(),
+                id: MOCK_PARENT_DOMAIN_ASSET_ID,
                 organization_id: Uuid::new_v4(),
-                asset_type: AssetType::IPAddress,
-                value: "192.168.1.1".to_string(),
+                asset_type: AssetType::Domain,
+                value: "example.com".to_string(),
                 status: AssetStatus::Active,
                 first_seen: now,
                 last_seen: now,
                 created_at: now,
                 updated_at: now,
                 attributes: serde_json::json!({
-                    "ip_address": "192.168.1.1",
+                    "hostname": "example",
                 }),
+                risk_score: 0.0,
             },
         ])
     }
@@ -205,13 +298,48 @@ impl backend::AssetService for MockAssetService {
     async fn count_assets(
         &self,
         _organization_id: Option<ID>,
-        _asset_type: Option<AssetType>,
+        asset_type: Option<AssetType>,
         _status: Option<AssetStatus>,
+        tag: Option<String>,
     ) -> Result<usize> {
+        // Both test assets are domains tagged "production"
+        if tag.is_some_and(|tag| tag != "production")
+            || asset_type.is_some_and(|asset_type| asset_type != AssetType::Domain)
+        {
+            return Ok(0);
+        }
+
         // Return a fixed count
         Ok(2)
     }
 
+    async fn add_tags(&self, _asset_id: ID, tags: &[String]) -> Result<Vec<String>> {
+        // The asset already carries the "production" tag
+        let mut all = vec!["production".to_string()];
+        all.extend(tags.iter().map(|tag| tag.trim().to_lowercase()));
+        all.sort();
+        all.dedup();
+        Ok(all)
+    }
+
+    async fn remove_tag(&self, _asset_id: ID, tag: &str) -> Result<bool> {
+        Ok(tag == "production")
+    }
+
+    async fn get_tags(&self, _asset_id: ID) -> Result<Vec<String>> {
+        Ok(vec!["production".to_string()])
+    }
+
+    async fn bulk_update_asset_status(
+        &self,
+        _organization_id: ID,
+        asset_ids: Vec<ID>,
+        _status: AssetStatus,
+    ) -> Result<usize> {
+        // Every listed asset belongs to the caller
+        Ok(asset_ids.len())
+    }
+
     async fn create_asset_relationship(
         &self,
         _source_asset_id: ID,
@@ -232,10 +360,34 @@ impl backend::AssetService for MockAssetService {
         Ok(true)
     }
 
+    async fn create_asset_relationships(
+        &self,
+        relationships: Vec<(ID, ID, String)>,
+    ) -> Result<usize> {
+        let mut recorded = self.relationships.lock().unwrap();
+        Ok(relationships
+            .into_iter()
+            .filter(|relationship| recorded.insert(relationship.clone()))
+            .count())
+    }
+
     async fn get_related_assets(
         &self,
         _asset_id: ID,
         _relationship_type: Option<String>,
+        _limit: usize,
+        _offset: usize,
+    ) -> Result<Vec<(Asset, String)>> {
+        // Mock implementation - return empty list
+        Ok(Vec::new())
+    }
+
+    async fn get_referring_assets(
+        &self,
+        _asset_id: ID,
+        _relationship_type: Option<String>,
+        _limit: usize,
+        _offset: usize,
     ) -> Result<Vec<(Asset, String)>> {
         // Mock implementation - return empty list
         Ok(Vec::new())
@@ -245,8 +397,20 @@ impl backend::AssetService for MockAssetService {
         &self,
         _organization_id: ID,
     ) -> Result<Vec<(ID, ID, String)>> {
-        // Mock implementation - return empty list
-        Ok(Vec::new())
+        // The listed subdomain belongs to the listed parent domain
+        Ok(vec![(
+            MOCK_SUBDOMAIN_ASSET_ID,
+            MOCK_PARENT_DOMAIN_ASSET_ID,
+            "subdomain".to_string(),
+        )])
+    }
+
+    async fn asset_graph(&self, organization_id: ID) -> Result<AssetGraph> {
+        let assets = self
+            .list_assets(Some(organization_id), None, None, None, usize::MAX, 0)
+            .await?;
+        let relationships = self.discover_asset_relationships(organization_id).await?;
+        Ok(AssetGraph::new(&assets, &relationships))
     }
 
     async fn analyze_dependency_chain(
@@ -269,6 +433,60 @@ impl backend::AssetService for MockAssetService {
         // Mock implementation - return success
         Ok(())
     }
+
+    async fn get_asset_history(
+        &self,
+        asset_id: ID,
+        _limit: usize,
+        _offset: usize,
+    ) -> Result<Vec<AssetHistory>> {
+        // A single status change from active to inactive
+        Ok(vec![AssetHistory::new(
+            asset_id,
+            "status".to_string(),
+            json!("ACTIVE"),
+            json!("INACTIVE"),
+            chrono::Utc::now(),
+        )])
+    }
+
+    async fn count_asset_history(&self, _asset_id: ID) -> Result<usize> {
+        Ok(1)
+    }
+
+    async fn compute_risk_score(&self, _asset_id: ID) -> Result<f32> {
+        Ok(0.0)
+    }
+
+    async fn list_recent_assets(&self, organization_id: ID, _limit: usize) -> Result<Vec<Asset>> {
+        // The most recent of the two test assets
+        let now = chrono::Utc::now();
+        Ok(vec![Asset {
+            id: MOCK_SUBDOMAIN_ASSET_ID,
+            organization_id,
+            asset_type: AssetType::Domain,
+            value: "test1.example.com".to_string(),
+            status: AssetStatus::Active,
+            first_seen: now,
+            last_seen: now,
+            created_at: now,
+            updated_at: now,
+            attributes: serde_json::json!({
+                "hostname": "test1",
+            }),
+            risk_score: 0.0,
+        }])
+    }
+
+    async fn asset_counts_by_day(
+        &self,
+        _organization_id: ID,
+        _since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+        // One test asset found two days ago, the other today
+        let today = chrono::Utc::now().date_naive();
+        Ok(vec![(today - chrono::Days::new(2), 1), (today, 1)])
+    }
 }
 
 // Mock vulnerability service for testing
@@ -411,6 +629,35 @@ impl backend::VulnerabilityService for MockVulnerabilityService {
         ])
     }
 
+    async fn list_vulnerabilities_sorted(
+        &self,
+        asset_id: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        sort: Option<Sort<VulnerabilitySortField>>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = self
+            .list_vulnerabilities(asset_id, port_id, severity, status, limit, offset)
+            .await?;
+
+        if let Some(sort) = sort {
+            vulnerabilities.sort_by(|a, b| match sort.field {
+                VulnerabilitySortField::Severity => a.severity.cmp(&b.severity),
+                VulnerabilitySortField::Status => {
+                    format!("{:?}", a.status).cmp(&format!("{:?}", b.status))
+                }
+                VulnerabilitySortField::CreatedAt => a.created_at.cmp(&b.created_at),
+            });
+            if sort.order == SortOrder::Desc {
+                vulnerabilities.reverse();
+            }
+        }
+        Ok(vulnerabilities)
+    }
+
     async fn count_vulnerabilities(
         &self,
         _asset_id: Option<ID>,
@@ -440,6 +687,19 @@ impl backend::VulnerabilityService for MockVulnerabilityService {
         Ok(correlations)
     }
 
+    async fn correlate(&self, _organization_id: ID) -> Result<Vec<VulnerabilityGroup>> {
+        // Return one vulnerability shared by two assets
+        Ok(vec![VulnerabilityGroup {
+            title: "Test Vulnerability".to_string(),
+            cve_id: Some("CVE-2023-1234".to_string()),
+            technology: Some("nginx".to_string()),
+            affected_asset_count: 2,
+            vulnerability_count: 2,
+            highest_severity: Severity::High,
+            asset_ids: vec![Uuid::new_v4(), Uuid::new_v4()],
+        }])
+    }
+
     async fn find_similar_vulnerabilities(
         &self,
         _vulnerability_id: ID,
@@ -474,11 +734,12 @@ impl backend::VulnerabilityService for MockVulnerabilityService {
 
     async fn bulk_update_vulnerability_status(
         &self,
-        _vulnerability_ids: Vec<ID>,
+        _organization_id: ID,
+        vulnerability_ids: Vec<ID>,
         _status: VulnerabilityStatus,
     ) -> Result<usize> {
-        // Mock implementation - return count of "updated" vulnerabilities
-        Ok(2)
+        // Every listed vulnerability belongs to the caller
+        Ok(vulnerability_ids.len())
     }
 
     async fn get_vulnerability_statistics(
@@ -494,6 +755,26 @@ impl backend::VulnerabilityService for MockVulnerabilityService {
         stats.insert("low".to_string(), 1);
         Ok(stats)
     }
+
+    async fn count_by_severity(
+        &self,
+        _organization_id: ID,
+        _status: Option<VulnerabilityStatus>,
+    ) -> Result<std::collections::HashMap<Severity, usize>> {
+        Ok(std::collections::HashMap::from([
+            (Severity::Critical, 1),
+            (Severity::High, 2),
+            (Severity::Medium, 4),
+        ]))
+    }
+
+    async fn vuln_counts_by_day(
+        &self,
+        _organization_id: ID,
+        _since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+        Ok(vec![(chrono::Utc::now().date_naive(), 3)])
+    }
 }
 
 #[async_trait]
@@ -545,6 +826,384 @@ impl backend::DiscoveryService for MockDiscoveryService {
     }
 }
 
+/// Organizations kept in memory, enforcing unique names as the database does
+#[derive(Default)]
+pub struct InMemoryOrganizationRepository {
+    organizations: std::sync::Mutex<Vec<Organization>>,
+}
+
+#[async_trait]
+impl backend::OrganizationRepository for InMemoryOrganizationRepository {
+    async fn create_organization(&self, organization: &Organization) -> Result<Organization> {
+        let mut organizations = self.organizations.lock().unwrap();
+        if organizations
+            .iter()
+            .any(|org| org.name == organization.name)
+        {
+            return Err(backend::Error::Conflict(
+                "Record already exists".to_string(),
+            ));
+        }
+        organizations.push(organization.clone());
+        Ok(organization.clone())
+    }
+
+    async fn get_organization(&self, id: ID) -> Result<Organization> {
+        self.organizations
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|org| org.id == id)
+            .cloned()
+            .ok_or_else(|| backend::Error::NotFound("Organization not found".to_string()))
+    }
+
+    async fn get_organization_by_name(&self, name: &str) -> Result<Option<Organization>> {
+        Ok(self
+            .organizations
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|org| org.name == name)
+            .cloned())
+    }
+
+    async fn update_organization(&self, organization: &Organization) -> Result<Organization> {
+        let mut organizations = self.organizations.lock().unwrap();
+        let existing = organizations
+            .iter_mut()
+            .find(|org| org.id == organization.id)
+            .ok_or_else(|| backend::Error::NotFound("Organization not found".to_string()))?;
+        *existing = organization.clone();
+        Ok(organization.clone())
+    }
+
+    async fn delete_organization(&self, id: ID) -> Result<bool> {
+        let mut organizations = self.organizations.lock().unwrap();
+        let before = organizations.len();
+        organizations.retain(|org| org.id != id);
+        Ok(organizations.len() < before)
+    }
+
+    async fn list_organizations(&self, limit: usize, offset: usize) -> Result<Vec<Organization>> {
+        let mut organizations = self.organizations.lock().unwrap().clone();
+        organizations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(organizations.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn count_organizations(&self) -> Result<usize> {
+        Ok(self.organizations.lock().unwrap().len())
+    }
+
+    async fn record_report_sent(
+        &self,
+        _id: ID,
+        _sent_at: shared::types::Timestamp,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Users kept in memory, enforcing unique emails as the database does
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: std::sync::Mutex<Vec<User>>,
+}
+
+impl InMemoryUserRepository {
+    fn find(&self, matches: impl Fn(&User) -> bool) -> Option<User> {
+        self.users
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|user| matches(user))
+            .cloned()
+    }
+
+    fn filtered(&self, organization_id: Option<ID>, role: Option<UserRole>) -> Vec<User> {
+        let mut users: Vec<User> = self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|user| organization_id.is_none_or(|id| user.organization_id == id))
+            .filter(|user| role.is_none_or(|role| user.role == role))
+            .cloned()
+            .collect();
+        users.sort_by(|a, b| a.username.cmp(&b.username));
+        users
+    }
+}
+
+#[async_trait]
+impl backend::UserRepository for InMemoryUserRepository {
+    async fn create_user(&self, user: &User) -> Result<User> {
+        self.users.lock().unwrap().push(user.clone());
+        Ok(user.clone())
+    }
+
+    async fn get_user(&self, id: ID) -> Result<User> {
+        self.find(|user| user.id == id)
+            .ok_or_else(|| backend::Error::NotFound("User not found".to_string()))
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
+        Ok(self.find(|user| user.username == username))
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        Ok(self.find(|user| user.email == email))
+    }
+
+    async fn update_user(&self, user: &User) -> Result<User> {
+        let mut users = self.users.lock().unwrap();
+        let existing = users
+            .iter_mut()
+            .find(|existing| existing.id == user.id)
+            .ok_or_else(|| backend::Error::NotFound("User not found".to_string()))?;
+        *existing = user.clone();
+        Ok(user.clone())
+    }
+
+    async fn delete_user(&self, id: ID) -> Result<bool> {
+        let mut users = self.users.lock().unwrap();
+        let before = users.len();
+        users.retain(|user| user.id != id);
+        Ok(users.len() < before)
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
+        Ok(self.find(|user| user.email == email))
+    }
+
+    async fn list_users(
+        &self,
+        organization_id: Option<ID>,
+        role: Option<UserRole>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<User>> {
+        Ok(self
+            .filtered(organization_id, role)
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+
+    async fn count_users(
+        &self,
+        organization_id: Option<ID>,
+        role: Option<UserRole>,
+    ) -> Result<usize> {
+        Ok(self.filtered(organization_id, role).len())
+    }
+
+    async fn atomic_register_user(&self, email: &str, user: &User) -> Result<User> {
+        let mut users = self.users.lock().unwrap();
+        if users.iter().any(|existing| existing.email == email) {
+            return Err(backend::Error::Conflict("Email already exists".to_string()));
+        }
+        users.push(user.clone());
+        Ok(user.clone())
+    }
+}
+
+/// Discovery jobs and their asset links kept in memory. The ports,
+/// technologies and vulnerabilities on an asset, which the database would
+/// count, are given with `add_asset`.
+#[derive(Default)]
+pub struct InMemoryDiscoveryJobRepository {
+    jobs: std::sync::Mutex<Vec<DiscoveryJob>>,
+    links: std::sync::Mutex<Vec<JobAssetLink>>,
+    assets: std::sync::Mutex<Vec<(Asset, JobResultSummary)>>,
+}
+
+impl InMemoryDiscoveryJobRepository {
+    /// Make `asset` known, with the number of ports, technologies and
+    /// vulnerabilities found on it
+    pub fn add_asset(
+        &self,
+        asset: Asset,
+        ports: usize,
+        technologies: usize,
+        vulnerabilities: usize,
+    ) {
+        self.assets.lock().unwrap().push((
+            asset,
+            JobResultSummary {
+                assets: 1,
+                ports,
+                technologies,
+                vulnerabilities,
+            },
+        ));
+    }
+
+    fn linked_assets(&self, job_id: ID) -> Vec<(Asset, JobResultSummary)> {
+        let links = self.links.lock().unwrap();
+        self.assets
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(asset, _)| {
+                links
+                    .iter()
+                    .any(|link| link.job_id == job_id && link.asset_id == asset.id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn matching_jobs(&self, filter: &DiscoveryJobFilter) -> Vec<DiscoveryJob> {
+        let mut jobs: Vec<_> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|job| filter.matches(job))
+            .cloned()
+            .collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        jobs
+    }
+}
+
+#[async_trait]
+impl backend::DiscoveryJobRepository for InMemoryDiscoveryJobRepository {
+    async fn create_job(&self, job: &DiscoveryJob) -> Result<DiscoveryJob> {
+        self.jobs.lock().unwrap().push(job.clone());
+        Ok(job.clone())
+    }
+
+    async fn get_job(&self, id: ID) -> Result<DiscoveryJob> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+            .ok_or_else(|| backend::Error::NotFound("Discovery job not found".to_string()))
+    }
+
+    async fn update_job(&self, job: &DiscoveryJob) -> Result<DiscoveryJob> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let existing = jobs
+            .iter_mut()
+            .find(|existing| existing.id == job.id)
+            .ok_or_else(|| backend::Error::NotFound("Discovery job not found".to_string()))?;
+        *existing = job.clone();
+        Ok(job.clone())
+    }
+
+    async fn delete_job(&self, id: ID) -> Result<bool> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let before = jobs.len();
+        jobs.retain(|job| job.id != id);
+        Ok(jobs.len() < before)
+    }
+
+    async fn list_jobs(
+        &self,
+        filter: &DiscoveryJobFilter,
+        _sort: Option<Sort<shared::types::JobSortField>>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<DiscoveryJob>> {
+        Ok(self
+            .matching_jobs(filter)
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+
+    async fn count_jobs(&self, filter: &DiscoveryJobFilter) -> Result<usize> {
+        Ok(self.matching_jobs(filter).len())
+    }
+
+    async fn list_jobs_by_status(
+        &self,
+        status: JobStatus,
+        limit: usize,
+    ) -> Result<Vec<DiscoveryJob>> {
+        let mut jobs = self.matching_jobs(&DiscoveryJobFilter {
+            statuses: vec![status],
+            ..Default::default()
+        });
+        jobs.truncate(limit);
+        Ok(jobs)
+    }
+
+    async fn create_job_asset_link(&self, link: &JobAssetLink) -> Result<JobAssetLink> {
+        let mut links = self.links.lock().unwrap();
+        if links
+            .iter()
+            .any(|existing| existing.job_id == link.job_id && existing.asset_id == link.asset_id)
+        {
+            return Err(backend::Error::Conflict(
+                "Record already exists".to_string(),
+            ));
+        }
+        links.push(link.clone());
+        Ok(link.clone())
+    }
+
+    async fn link_job_to_assets(&self, job_id: ID, asset_ids: &[ID]) -> Result<usize> {
+        let mut links = self.links.lock().unwrap();
+        let mut created = 0;
+        for &asset_id in asset_ids {
+            if !links
+                .iter()
+                .any(|link| link.job_id == job_id && link.asset_id == asset_id)
+            {
+                links.push(JobAssetLink::new(job_id, asset_id));
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
+
+    async fn get_job_assets(&self, job_id: ID) -> Result<Vec<Asset>> {
+        Ok(self
+            .linked_assets(job_id)
+            .into_iter()
+            .map(|(asset, _)| asset)
+            .collect())
+    }
+
+    async fn get_job_summary(&self, job_id: ID) -> Result<JobResultSummary> {
+        Ok(self.linked_assets(job_id).iter().fold(
+            JobResultSummary::default(),
+            |total, (_, found)| JobResultSummary {
+                assets: total.assets + found.assets,
+                ports: total.ports + found.ports,
+                technologies: total.technologies + found.technologies,
+                vulnerabilities: total.vulnerabilities + found.vulnerabilities,
+            },
+        ))
+    }
+
+    async fn record_target_scans(
+        &self,
+        _organization_id: ID,
+        _job_type: JobType,
+        _targets: &[String],
+        _scanned_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn last_target_scans(
+        &self,
+        _organization_id: ID,
+        _job_type: JobType,
+        _targets: &[String],
+    ) -> Result<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>> {
+        Ok(std::collections::HashMap::new())
+    }
+}
+
 pub fn create_test_app_state() -> AppState {
     // Load config first to get DB URL if needed for lazy pool
     let config = Config::from_env().expect("Failed to load config for test state");
@@ -593,9 +1252,8 @@ pub fn create_test_app_state() -> AppState {
 
         async fn list_jobs(
             &self,
-            _organization_id: Option<ID>,
-            _job_type: Option<shared::types::JobType>,
-            _status: Option<shared::types::JobStatus>,
+            _filter: &backend::models::DiscoveryJobFilter,
+            _sort: Option<shared::types::Sort<shared::types::JobSortField>>,
             _limit: usize,
             _offset: usize,
         ) -> backend::Result<Vec<backend::models::DiscoveryJob>> {
@@ -604,9 +1262,7 @@ pub fn create_test_app_state() -> AppState {
 
         async fn count_jobs(
             &self,
-            _organization_id: Option<ID>,
-            _job_type: Option<shared::types::JobType>,
-            _status: Option<shared::types::JobStatus>,
+            _filter: &backend::models::DiscoveryJobFilter,
         ) -> backend::Result<usize> {
             Ok(0)
         }
@@ -631,6 +1287,14 @@ pub fn create_test_app_state() -> AppState {
             })
         }
 
+        async fn link_job_to_assets(
+            &self,
+            _job_id: ID,
+            asset_ids: &[ID],
+        ) -> backend::Result<usize> {
+            Ok(asset_ids.len())
+        }
+
         async fn get_job_assets(
             &self,
             _job_id: ID,
@@ -638,6 +1302,33 @@ pub fn create_test_app_state() -> AppState {
             Ok(vec![])
         }
 
+        async fn get_job_summary(
+            &self,
+            _job_id: ID,
+        ) -> backend::Result<backend::models::JobResultSummary> {
+            Ok(backend::models::JobResultSummary::default())
+        }
+
+        async fn record_target_scans(
+            &self,
+            _organization_id: ID,
+            _job_type: shared::types::JobType,
+            _targets: &[String],
+            _scanned_at: chrono::DateTime<chrono::Utc>,
+        ) -> backend::Result<()> {
+            Ok(())
+        }
+
+        async fn last_target_scans(
+            &self,
+            _organization_id: ID,
+            _job_type: shared::types::JobType,
+            _targets: &[String],
+        ) -> backend::Result<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>>
+        {
+            Ok(std::collections::HashMap::new())
+        }
+
         async fn list_jobs_by_status(
             &self,
             _status: shared::types::JobStatus,
@@ -647,16 +1338,412 @@ pub fn create_test_app_state() -> AppState {
         }
     }
 
+    struct StubPortRepository;
+
+    #[async_trait::async_trait]
+    impl backend::PortRepository for StubPortRepository {
+        async fn create_port(
+            &self,
+            port: &backend::models::Port,
+        ) -> backend::Result<backend::models::Port> {
+            Ok(port.clone())
+        }
+
+        async fn get_port(&self, id: ID) -> backend::Result<backend::models::Port> {
+            let mut port = backend::models::Port::new(
+                Uuid::new_v4(),
+                443,
+                shared::types::Protocol::TCP,
+                Some("https".to_string()),
+                None,
+            );
+            port.id = id;
+            Ok(port)
+        }
+
+        async fn update_port(
+            &self,
+            port: &backend::models::Port,
+        ) -> backend::Result<backend::models::Port> {
+            Ok(port.clone())
+        }
+
+        async fn delete_port(&self, _id: ID) -> backend::Result<bool> {
+            Ok(true)
+        }
+
+        async fn upsert_ports_bulk(
+            &self,
+            asset_id: ID,
+            ports: &[backend::models::Port],
+        ) -> backend::Result<Vec<backend::models::Port>> {
+            Ok(ports
+                .iter()
+                .cloned()
+                .map(|mut port| {
+                    port.asset_id = asset_id;
+                    port
+                })
+                .collect())
+        }
+
+        async fn list_ports(
+            &self,
+            asset_id: Option<ID>,
+            _port_number: Option<i32>,
+            _protocol: Option<shared::types::Protocol>,
+            _status: Option<shared::types::PortStatus>,
+            _limit: usize,
+            _offset: usize,
+        ) -> backend::Result<Vec<backend::models::Port>> {
+            // Return a single open HTTPS port for the requested asset
+            Ok(vec![backend::models::Port::new(
+                asset_id.unwrap_or_else(Uuid::new_v4),
+                443,
+                shared::types::Protocol::TCP,
+                Some("https".to_string()),
+                None,
+            )])
+        }
+
+        async fn count_ports(
+            &self,
+            _asset_id: Option<ID>,
+            _port_number: Option<i32>,
+            _protocol: Option<shared::types::Protocol>,
+            _status: Option<shared::types::PortStatus>,
+        ) -> backend::Result<usize> {
+            Ok(1)
+        }
+    }
+
+    struct StubTechnologyRepository;
+
+    #[async_trait::async_trait]
+    impl backend::TechnologyRepository for StubTechnologyRepository {
+        async fn create_technology(
+            &self,
+            technology: &backend::models::Technology,
+        ) -> backend::Result<backend::models::Technology> {
+            Ok(technology.clone())
+        }
+
+        async fn upsert_technology(
+            &self,
+            technology: &backend::models::Technology,
+        ) -> backend::Result<backend::models::Technology> {
+            Ok(technology.clone())
+        }
+
+        async fn get_technology(&self, id: ID) -> backend::Result<backend::models::Technology> {
+            let mut technology = backend::models::Technology::new(
+                Uuid::new_v4(),
+                "nginx".to_string(),
+                Some("1.25".to_string()),
+                Some("Web Server".to_string()),
+            );
+            technology.id = id;
+            Ok(technology)
+        }
+
+        async fn update_technology(
+            &self,
+            technology: &backend::models::Technology,
+        ) -> backend::Result<backend::models::Technology> {
+            Ok(technology.clone())
+        }
+
+        async fn delete_technology(&self, _id: ID) -> backend::Result<bool> {
+            Ok(true)
+        }
+
+        async fn list_technologies(
+            &self,
+            id_filter: Option<ID>,
+            _name: Option<String>,
+            _category: Option<String>,
+            _limit: usize,
+            _offset: usize,
+        ) -> backend::Result<Vec<backend::models::Technology>> {
+            // Return one technology for the requested asset plus one belonging
+            // to another asset, mimicking the repository's organization fallback
+            Ok(vec![
+                backend::models::Technology::new(
+                    id_filter.unwrap_or_else(Uuid::new_v4),
+                    "nginx".to_string(),
+                    Some("1.25".to_string()),
+                    Some("Web Server".to_string()),
+                ),
+                backend::models::Technology::new(
+                    Uuid::new_v4(),
+                    "PHP".to_string(),
+                    None,
+                    Some("Programming Language".to_string()),
+                ),
+            ])
+        }
+
+        async fn list_asset_technologies(
+            &self,
+            asset_id: ID,
+            _limit: usize,
+            _offset: usize,
+        ) -> backend::Result<Vec<backend::models::Technology>> {
+            Ok(vec![backend::models::Technology::new(
+                asset_id,
+                "nginx".to_string(),
+                Some("1.25".to_string()),
+                Some("Web Server".to_string()),
+            )])
+        }
+
+        async fn count_technologies(
+            &self,
+            _asset_id: Option<ID>,
+            _name: Option<String>,
+            _category: Option<String>,
+        ) -> backend::Result<usize> {
+            Ok(1)
+        }
+
+        async fn count_organization_technologies(
+            &self,
+            _organization_id: ID,
+        ) -> backend::Result<usize> {
+            // nginx and PHP, as listed above
+            Ok(2)
+        }
+
+        async fn list_organization_technologies(
+            &self,
+            _organization_id: ID,
+            search: Option<String>,
+            category: Option<String>,
+            limit: usize,
+            offset: usize,
+        ) -> backend::Result<Page<backend::models::DetectedTechnology>> {
+            let items: Vec<_> = self
+                .list_technologies(None, None, None, limit, offset)
+                .await?
+                .into_iter()
+                .filter(|technology| {
+                    search.as_ref().is_none_or(|search| {
+                        technology
+                            .name
+                            .to_lowercase()
+                            .contains(&search.to_lowercase())
+                    }) && category.as_ref().is_none_or(|category| {
+                        technology
+                            .category
+                            .as_ref()
+                            .is_some_and(|c| c.eq_ignore_ascii_case(category))
+                    })
+                })
+                .map(|technology| backend::models::DetectedTechnology {
+                    technology,
+                    asset_value: "test1.example.com".to_string(),
+                })
+                .collect();
+            Ok(Page {
+                total: items.len(),
+                items,
+                limit,
+                offset,
+            })
+        }
+
+        async fn technology_distribution(
+            &self,
+            _organization_id: ID,
+        ) -> backend::Result<backend::models::TechnologyDistribution> {
+            let count = |name: &str| backend::models::TechnologyCount {
+                name: name.to_string(),
+                count: 1,
+            };
+            Ok(backend::models::TechnologyDistribution {
+                by_name: vec![count("PHP"), count("nginx")],
+                by_category: vec![count("Programming Language"), count("Web Server")],
+            })
+        }
+    }
+
+    struct StubScanScheduleRepository;
+
+    #[async_trait::async_trait]
+    impl backend::ScanScheduleRepository for StubScanScheduleRepository {
+        async fn create_schedule(
+            &self,
+            schedule: &backend::models::ScanSchedule,
+        ) -> backend::Result<backend::models::ScanSchedule> {
+            Ok(schedule.clone())
+        }
+
+        async fn get_schedule(&self, id: ID) -> backend::Result<backend::models::ScanSchedule> {
+            let mut schedule = backend::models::ScanSchedule::new(
+                Uuid::new_v4(),
+                "example.com".to_string(),
+                JobType::DnsEnum,
+                "0 0 2 * * *".to_string(),
+            );
+            schedule.id = id;
+            Ok(schedule)
+        }
+
+        async fn update_schedule(
+            &self,
+            schedule: &backend::models::ScanSchedule,
+        ) -> backend::Result<backend::models::ScanSchedule> {
+            Ok(schedule.clone())
+        }
+
+        async fn delete_schedule(&self, _id: ID) -> backend::Result<bool> {
+            Ok(true)
+        }
+
+        async fn list_schedules(
+            &self,
+            _organization_id: Option<ID>,
+            _limit: usize,
+            _offset: usize,
+        ) -> backend::Result<Vec<backend::models::ScanSchedule>> {
+            Ok(vec![])
+        }
+
+        async fn count_schedules(&self, _organization_id: Option<ID>) -> backend::Result<usize> {
+            Ok(0)
+        }
+
+        async fn list_enabled_schedules(
+            &self,
+        ) -> backend::Result<Vec<backend::models::ScanSchedule>> {
+            Ok(vec![])
+        }
+    }
+
+    struct StubScanProfileRepository;
+
+    #[async_trait::async_trait]
+    impl backend::ScanProfileRepository for StubScanProfileRepository {
+        async fn create_profile(
+            &self,
+            profile: &backend::models::ScanProfile,
+        ) -> backend::Result<backend::models::ScanProfile> {
+            Ok(profile.clone())
+        }
+
+        async fn get_profile(&self, id: ID) -> backend::Result<backend::models::ScanProfile> {
+            let mut profile = backend::models::ScanProfile::new(
+                Uuid::new_v4(),
+                "Web only".to_string(),
+                vec![JobType::WebCrawl],
+                2,
+                vec![80, 443],
+                600,
+            );
+            profile.id = id;
+            Ok(profile)
+        }
+
+        async fn get_profile_by_name(
+            &self,
+            _organization_id: ID,
+            _name: &str,
+        ) -> backend::Result<Option<backend::models::ScanProfile>> {
+            Ok(None)
+        }
+
+        async fn update_profile(
+            &self,
+            profile: &backend::models::ScanProfile,
+        ) -> backend::Result<backend::models::ScanProfile> {
+            Ok(profile.clone())
+        }
+
+        async fn delete_profile(&self, _id: ID) -> backend::Result<bool> {
+            Ok(true)
+        }
+
+        async fn list_profiles(
+            &self,
+            _organization_id: ID,
+        ) -> backend::Result<Vec<backend::models::ScanProfile>> {
+            Ok(vec![])
+        }
+    }
+
+    /// Keeps entries in memory so tests can read back what was audited
+    #[derive(Default)]
+    struct StubAuditLogRepository {
+        entries: std::sync::Mutex<Vec<backend::models::AuditLogEntry>>,
+    }
+
+    #[async_trait::async_trait]
+    impl backend::AuditLogRepository for StubAuditLogRepository {
+        async fn create_entry(
+            &self,
+            entry: &backend::models::AuditLogEntry,
+        ) -> backend::Result<backend::models::AuditLogEntry> {
+            self.entries.lock().unwrap().push(entry.clone());
+            Ok(entry.clone())
+        }
+
+        async fn list_entries(
+            &self,
+            filter: &backend::models::AuditLogFilter,
+            limit: usize,
+            offset: usize,
+        ) -> backend::Result<Vec<backend::models::AuditLogEntry>> {
+            Ok(self
+                .entries
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|entry| filter.matches(entry))
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn count_entries(
+            &self,
+            filter: &backend::models::AuditLogFilter,
+        ) -> backend::Result<usize> {
+            Ok(self
+                .entries
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|entry| filter.matches(entry))
+                .count())
+        }
+    }
+
+    let login_lockout =
+        std::sync::Arc::new(crate::login_lockout::LoginLockout::from_config(&config));
+    let token_denylist =
+        std::sync::Arc::new(crate::token_denylist::TokenDenylist::from_config(&config));
+
     AppState {
         config,
         db_pool,
         redis_client: None,
-        asset_service: std::sync::Arc::new(MockAssetService),
+        asset_service: std::sync::Arc::new(MockAssetService::default()),
         vulnerability_service: std::sync::Arc::new(MockVulnerabilityService),
         organization_service: std::sync::Arc::new(MockOrganizationService),
         discovery_service: std::sync::Arc::new(MockDiscoveryService),
         user_service: std::sync::Arc::new(MockUserService),
         discovery_job_repository: std::sync::Arc::new(StubDiscoveryJobRepository),
+        port_repository: std::sync::Arc::new(StubPortRepository),
+        technology_repository: std::sync::Arc::new(StubTechnologyRepository),
+        scan_schedule_repository: std::sync::Arc::new(StubScanScheduleRepository),
+        scan_profile_repository: std::sync::Arc::new(StubScanProfileRepository),
+        audit_log_repository: std::sync::Arc::new(StubAuditLogRepository::default()),
+        login_lockout,
+        token_denylist,
+        events: crate::events::EventBus::new(),
+        build_info: crate::state::BuildInfo::current(),
     }
 }
 
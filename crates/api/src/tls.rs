@@ -0,0 +1,74 @@
+//! HTTPS for the API server.
+//!
+//! When a certificate chain and private key are configured the API serves
+//! HTTPS itself, negotiating HTTP/2 or HTTP/1.1 over ALPN, instead of
+//! relying on a reverse proxy to terminate TLS.
+
+use std::{fs::File, io::BufReader, path::Path, sync::Arc};
+
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::ServerConfig;
+use shared::{
+    config::Config,
+    errors::{AppError, Result},
+};
+
+/// TLS settings from the certificate and key in `config`, if both are set
+pub fn tls_config(config: &Config) -> Result<Option<RustlsConfig>> {
+    match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => load_tls_config(cert_path, key_path).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// TLS settings serving the PEM certificate chain at `cert_path` with the
+/// PEM private key at `key_path`
+pub fn load_tls_config(
+    cert_path: impl AsRef<Path>,
+    key_path: impl AsRef<Path>,
+) -> Result<RustlsConfig> {
+    let (cert_path, key_path) = (cert_path.as_ref(), key_path.as_ref());
+
+    let certs = rustls_pemfile::certs(&mut open_pem(cert_path)?)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| invalid_pem(cert_path, e))?;
+    if certs.is_empty() {
+        return Err(AppError::configuration(format!(
+            "No certificates found in {}",
+            cert_path.display()
+        )));
+    }
+    let key = rustls_pemfile::private_key(&mut open_pem(key_path)?)
+        .map_err(|e| invalid_pem(key_path, e))?
+        .ok_or_else(|| {
+            AppError::configuration(format!("No private key found in {}", key_path.display()))
+        })?;
+
+    let mut server_config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| AppError::configuration(format!("Invalid TLS settings: {}", e)))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| {
+                AppError::configuration(format!(
+                    "Certificate {} doesn't match key {}: {}",
+                    cert_path.display(),
+                    key_path.display(),
+                    e
+                ))
+            })?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(RustlsConfig::from_config(Arc::new(server_config)))
+}
+
+fn open_pem(path: &Path) -> Result<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| AppError::configuration(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn invalid_pem(path: &Path, error: std::io::Error) -> AppError {
+    AppError::configuration(format!("Invalid PEM in {}: {}", path.display(), error))
+}
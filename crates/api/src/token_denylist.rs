@@ -0,0 +1,88 @@
+//! Reject access tokens that were revoked before they expired.
+//!
+//! Logging out revokes the token by its JWT ID, and logging out of every
+//! session revokes all tokens the user was issued up to that moment. Both
+//! are held in memory, only for as long as the tokens they cover could still
+//! pass validation.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::Utc;
+use shared::config::Config;
+
+use crate::middleware::auth::{Claims, TOKEN_LEEWAY_SECS};
+
+#[derive(Debug, Default)]
+struct Revoked {
+    /// Expiry of each revoked token, by JWT ID
+    tokens: HashMap<String, u64>,
+    /// When each user last logged out of every session, by user ID
+    users: HashMap<String, u64>,
+}
+
+/// Tokens and users whose tokens are no longer accepted
+#[derive(Debug)]
+pub struct TokenDenylist {
+    token_lifetime: u64,
+    revoked: Mutex<Revoked>,
+}
+
+impl TokenDenylist {
+    /// Denylist for tokens valid for at most `token_lifetime` seconds
+    pub fn new(token_lifetime: u64) -> Self {
+        Self {
+            token_lifetime,
+            revoked: Mutex::new(Revoked::default()),
+        }
+    }
+
+    /// Denylist for tokens issued for `JWT_EXPIRATION`
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.jwt_expiration.max(0) as u64)
+    }
+
+    /// Whether the token with `claims` has been revoked
+    pub fn is_revoked(&self, claims: &Claims) -> bool {
+        let revoked = self.revoked.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Tokens issued in the same second as a logout of every session are
+        // revoked too, as `iat` can't tell which came first
+        revoked.tokens.contains_key(&claims.jti)
+            || revoked
+                .users
+                .get(&claims.sub)
+                .is_some_and(|&logged_out| claims.iat as u64 <= logged_out)
+    }
+
+    /// Revoke the token with `claims`
+    pub fn revoke(&self, claims: &Claims) {
+        let mut revoked = self.lock_pruned();
+        revoked.tokens.insert(claims.jti.clone(), claims.exp as u64);
+    }
+
+    /// Revoke every token issued to `user_id` so far
+    pub fn revoke_all(&self, user_id: &str) {
+        let mut revoked = self.lock_pruned();
+        revoked.users.insert(user_id.to_string(), now());
+    }
+
+    /// Lock the denylist, first forgetting revocations of tokens that have
+    /// expired since, so it only holds tokens that would still be accepted
+    fn lock_pruned(&self) -> std::sync::MutexGuard<'_, Revoked> {
+        let now = now();
+        let mut revoked = self.revoked.lock().unwrap_or_else(|e| e.into_inner());
+
+        revoked
+            .tokens
+            .retain(|_, expires| *expires + TOKEN_LEEWAY_SECS >= now);
+        revoked
+            .users
+            .retain(|_, logged_out| *logged_out + self.token_lifetime + TOKEN_LEEWAY_SECS >= now);
+
+        revoked
+    }
+}
+
+fn now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
+}
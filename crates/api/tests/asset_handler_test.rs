@@ -42,6 +42,29 @@ async fn test_list_assets() {
     assert_eq!(body["assets"].as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_list_assets_sets_total_count_header() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/assets?limit=1&offset=1")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The mock service always reports two assets in total
+    let total = response
+        .headers()
+        .get("x-total-count")
+        .expect("X-Total-Count header should be present");
+    assert_eq!(total, "2");
+}
+
 #[tokio::test]
 async fn test_create_asset() {
     // Create the router with mock services
@@ -70,8 +93,8 @@ async fn test_create_asset() {
     // Send the request to the router
     let response = router.oneshot(request).await.unwrap();
 
-    // Check that the response has a 422 status (since MockAssetService::create_asset is unimplemented)
-    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    // Asset types are uppercase, so "Domain" is rejected before reaching the service
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -104,6 +127,185 @@ async fn test_get_asset() {
     // Check that the response contains an asset with the correct ID
     assert_eq!(body["id"], asset_id.to_string());
     assert_eq!(body["value"], "test.example.com");
+    assert_eq!(body["risk_score"], 42.5);
+}
+
+#[tokio::test]
+async fn test_get_asset_details() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let asset_id = Uuid::new_v4();
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/{}/details", asset_id))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body["asset"]["id"], asset_id.to_string());
+    assert!(body["related_assets"].as_array().unwrap().is_empty());
+    assert_eq!(body["ports"].as_array().unwrap().len(), 1);
+    assert_eq!(body["ports"][0]["port_number"], 443);
+
+    // Only the asset's own technologies, looked up by the repository
+    let technologies = body["technologies"].as_array().unwrap();
+    assert_eq!(technologies.len(), 1);
+    assert_eq!(technologies[0]["asset_id"], asset_id.to_string());
+
+    assert!(body["vulnerabilities"].is_array());
+}
+
+#[tokio::test]
+async fn test_get_asset_history() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let asset_id = Uuid::new_v4();
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/{}/history", asset_id))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("x-total-count").unwrap(), "1");
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let history = body["history"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["asset_id"], asset_id.to_string());
+    assert_eq!(history[0]["field"], "status");
+    assert_eq!(history[0]["old_value"], "ACTIVE");
+    assert_eq!(history[0]["new_value"], "INACTIVE");
+}
+
+#[tokio::test]
+async fn test_get_asset_graph_json() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/assets/graph?format=json")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let nodes = body["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert!(nodes
+        .iter()
+        .any(|n| n["value"] == "test1.example.com" && n["asset_type"] == "DOMAIN"));
+
+    // The subdomain relationship shows up as an edge to its parent domain
+    let edges = body["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["source"], MOCK_SUBDOMAIN_ASSET_ID.to_string());
+    assert_eq!(edges[0]["target"], MOCK_PARENT_DOMAIN_ASSET_ID.to_string());
+    assert_eq!(edges[0]["relationship_type"], "subdomain");
+}
+
+#[tokio::test]
+async fn test_get_asset_graph_dot() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/assets/graph?format=dot")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/vnd.graphviz"));
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let dot = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(dot.starts_with("digraph assets {"));
+    assert!(dot.contains(&format!(
+        "\"{}\" -> \"{}\" [label=\"subdomain\"];",
+        MOCK_SUBDOMAIN_ASSET_ID, MOCK_PARENT_DOMAIN_ASSET_ID
+    )));
+}
+
+#[tokio::test]
+async fn test_get_asset_graph_refuses_other_organizations() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/graph?organization_id={}", Uuid::new_v4()))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_discover_relationships() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let discover = || {
+        Request::builder()
+            .uri("/api/assets/discover-relationships")
+            .method("POST")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // The subdomain is linked to its parent domain
+    let response = router.clone().oneshot(discover()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["discovered"], 1);
+    assert_eq!(body["created"], 1);
+    assert_eq!(body["by_type"]["subdomain"]["discovered"], 1);
+    assert_eq!(body["by_type"]["subdomain"]["created"], 1);
+
+    // Running it again finds the same relationship without recording it twice
+    let response = router.oneshot(discover()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["discovered"], 1);
+    assert_eq!(body["created"], 0);
+    assert_eq!(body["by_type"]["subdomain"]["created"], 0);
 }
 
 #[tokio::test]
@@ -139,8 +341,8 @@ async fn test_update_asset() {
     // Send the request to the router
     let response = router.oneshot(request).await.unwrap();
 
-    // Check that the response has a 422 status (since MockAssetService::update_asset is unimplemented)
-    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    // Asset types are uppercase, so "Domain" is rejected before reaching the service
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -166,3 +368,257 @@ async fn test_delete_asset() {
     // Check that the response has a 204 No Content status
     assert_eq!(response.status(), StatusCode::NO_CONTENT);
 }
+
+#[tokio::test]
+async fn test_list_assets_filters_by_tag() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    // The mock assets are all tagged "production"
+    for (tag, expected) in [("production", 2), ("pci-scope", 0)] {
+        let request = Request::builder()
+            .uri(format!("/api/assets?tag={}", tag))
+            .method("GET")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["assets"].as_array().unwrap().len(), expected);
+        assert_eq!(body["total"], expected);
+    }
+}
+
+#[tokio::test]
+async fn test_get_asset_tags() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/{}/tags", Uuid::new_v4()))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["tags"], json!(["production"]));
+}
+
+#[tokio::test]
+async fn test_add_asset_tags() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/{}/tags", Uuid::new_v4()))
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "tags": ["PCI-Scope", "production"] }).to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The response lists all of the asset's tags, including existing ones
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["tags"], json!(["pci-scope", "production"]));
+}
+
+#[tokio::test]
+async fn test_add_asset_tags_requires_tags() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/{}/tags", Uuid::new_v4()))
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "tags": [] }).to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_remove_asset_tag() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+    let asset_id = Uuid::new_v4();
+
+    // Removing a tag the asset has succeeds, an unknown one is a 404
+    for (tag, expected) in [
+        ("production", StatusCode::NO_CONTENT),
+        ("pci-scope", StatusCode::NOT_FOUND),
+    ] {
+        let request = Request::builder()
+            .uri(format!("/api/assets/{}/tags/{}", asset_id, tag))
+            .method("DELETE")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_bulk_update_asset_status() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/assets/bulk")
+        .method("PATCH")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "ids": [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()],
+                "status": "ARCHIVED"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["updated"], 3);
+}
+
+#[tokio::test]
+async fn test_bulk_update_asset_status_rejects_bad_requests() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let too_many: Vec<Uuid> = (0..1001).map(|_| Uuid::new_v4()).collect();
+    for (payload, expected) in [
+        (
+            json!({ "ids": [], "status": "ARCHIVED" }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            json!({ "ids": too_many, "status": "ARCHIVED" }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            json!({ "ids": [Uuid::new_v4()], "status": "DESTROYED" }),
+            StatusCode::BAD_REQUEST,
+        ),
+    ] {
+        let request = Request::builder()
+            .uri("/api/assets/bulk")
+            .method("PATCH")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap();
+
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_asset_status_lists_allowed_values() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let payload = json!({ "ids": [Uuid::new_v4()], "status": "DESTROYED" });
+    let request = Request::builder()
+        .uri("/api/assets/bulk")
+        .method("PATCH")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "BAD_REQUEST");
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(message.contains("unknown variant `DESTROYED`"), "{message}");
+    assert!(
+        message.contains("`ACTIVE`, `INACTIVE`, `ARCHIVED`"),
+        "{message}"
+    );
+
+    // The same goes for filters in the query string
+    let request = Request::builder()
+        .uri("/api/assets?status=gone")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("`ACTIVE`, `INACTIVE`, `ARCHIVED`"),
+        "{message}"
+    );
+}
+
+async fn list_assets_accepting_gzip(state: api::state::AppState) -> axum::response::Response {
+    let router = api::routes::create_router(state);
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/assets")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::ACCEPT_ENCODING, "gzip")
+        .body(Body::empty())
+        .unwrap();
+
+    router.oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_list_assets_is_compressed_when_accepted() {
+    let response = list_assets_accepting_gzip(create_test_app_state()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+
+    // The body is no longer plain JSON
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..2], &[0x1f, 0x8b]);
+}
+
+#[tokio::test]
+async fn test_list_assets_compression_can_be_disabled() {
+    let mut state = create_test_app_state();
+    state.config.compression = false;
+
+    let response = list_assets_accepting_gzip(state).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["assets"].as_array().unwrap().len(), 2);
+}
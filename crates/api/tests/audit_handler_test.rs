@@ -0,0 +1,142 @@
+use std::net::SocketAddr;
+
+use api::{middleware::auth::generate_token, test_utils::*};
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use serde_json::json;
+use shared::types::UserRole;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Router behind trusted proxies, with a token for an analyst making changes
+/// and one for an admin reading the audit log
+fn setup() -> (Router, Uuid, String, String) {
+    let mut state = create_test_app_state();
+    state.config.trusted_proxies = vec!["127.0.0.1".to_string(), "10.0.0.0/8".to_string()];
+    let config = state.config.clone();
+    let router = api::routes::create_router(state);
+
+    let analyst_id = Uuid::new_v4();
+    let org_id = Uuid::new_v4().to_string();
+    let analyst_token = generate_token(
+        &analyst_id.to_string(),
+        UserRole::Analyst,
+        Some(&org_id),
+        &config,
+    )
+    .unwrap();
+    let admin_token = generate_token(
+        &Uuid::new_v4().to_string(),
+        UserRole::Admin,
+        Some(&org_id),
+        &config,
+    )
+    .unwrap();
+
+    (router, analyst_id, analyst_token, admin_token)
+}
+
+async fn audit_entries(router: &Router, token: &str, query: &str) -> serde_json::Value {
+    let request = Request::builder()
+        .uri(format!("/api/audit?{}", query))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("x-total-count"));
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_asset_is_audited() {
+    let (router, analyst_id, analyst_token, admin_token) = setup();
+
+    let asset_data = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "asset_type": "DOMAIN",
+        "value": "audited.example.com"
+    });
+
+    let request = Request::builder()
+        .uri("/api/assets")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", analyst_token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Forwarded-For", "198.51.100.9, 203.0.113.7, 10.0.0.1")
+        .extension(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))))
+        .body(Body::from(asset_data.to_string()))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // The response body still reaches the client after the audit reads it
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let asset: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(asset["value"], "audited.example.com");
+
+    let body = audit_entries(&router, &admin_token, &format!("user_id={}", analyst_id)).await;
+    assert_eq!(body["total"], 1);
+
+    let entry = &body["entries"][0];
+    assert_eq!(entry["user_id"], analyst_id.to_string());
+    assert_eq!(entry["action"], "CREATE");
+    assert_eq!(entry["resource_type"], "asset");
+    assert_eq!(entry["resource_id"], asset["id"]);
+    assert_eq!(entry["ip_address"], "203.0.113.7");
+    assert!(entry["organization_id"].is_string());
+    assert!(entry["created_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_delete_asset_is_audited() {
+    let (router, analyst_id, analyst_token, admin_token) = setup();
+    let asset_id = Uuid::new_v4();
+
+    let request = Request::builder()
+        .uri(format!("/api/assets/{}", asset_id))
+        .method("DELETE")
+        .header(header::AUTHORIZATION, format!("Bearer {}", analyst_token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert!(response.status().is_success());
+
+    let body = audit_entries(&router, &admin_token, "action=DELETE&resource_type=asset").await;
+    assert_eq!(body["total"], 1);
+
+    let entry = &body["entries"][0];
+    assert_eq!(entry["user_id"], analyst_id.to_string());
+    assert_eq!(entry["action"], "DELETE");
+    assert_eq!(entry["resource_id"], asset_id.to_string());
+
+    // Reads aren't audited
+    let body = audit_entries(&router, &admin_token, "").await;
+    assert_eq!(body["total"], 1);
+}
+
+#[tokio::test]
+async fn test_audit_log_requires_admin() {
+    let (router, _, analyst_token, _) = setup();
+
+    let request = Request::builder()
+        .uri("/api/audit")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", analyst_token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
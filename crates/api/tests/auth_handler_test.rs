@@ -198,3 +198,67 @@ async fn test_invalid_token() {
     // Check that the response has a 401 Unauthorized status
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 }
+
+#[tokio::test]
+async fn test_change_password() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/auth/change-password")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "current_password": "password123",
+                "new_password": "a much longer passphrase"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_change_password_rejects_wrong_current_password() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/auth/change-password")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "current_password": "not my password",
+                "new_password": "a much longer passphrase"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // A new password that is too short is a bad request
+    let request = Request::builder()
+        .uri("/api/auth/change-password")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "current_password": "password123",
+                "new_password": "short"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
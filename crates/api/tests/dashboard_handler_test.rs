@@ -0,0 +1,110 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_get_dashboard_stats() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/dashboard/stats")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    // The mock services hold two domains, seven open vulnerabilities and
+    // two distinct technologies
+    assert_eq!(body["total_assets"], 2);
+    assert_eq!(body["assets_by_type"]["domain"], 2);
+    assert_eq!(body["assets_by_type"]["ip_address"], 0);
+    assert_eq!(body["open_vulnerabilities"], 7);
+    assert_eq!(body["open_vulnerabilities_by_severity"]["critical"], 1);
+    assert_eq!(body["open_vulnerabilities_by_severity"]["high"], 2);
+    assert_eq!(body["open_vulnerabilities_by_severity"]["medium"], 4);
+    assert_eq!(body["open_vulnerabilities_by_severity"]["low"], 0);
+    assert_eq!(body["technologies"], 2);
+
+    let recent = body["recent_discoveries"].as_array().unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0]["value"], "test1.example.com");
+}
+
+#[tokio::test]
+async fn test_get_dashboard_stats_requires_authentication() {
+    let router = api::routes::create_router(create_test_app_state());
+
+    let request = Request::builder()
+        .uri("/api/dashboard/stats")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_get_dashboard_trends() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/dashboard/trends?days=7")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    // Every day in the window is listed, including those without discoveries
+    let today = chrono::Utc::now().date_naive();
+    let assets = body["assets"].as_array().unwrap();
+    assert_eq!(body["days"], 7);
+    assert_eq!(assets.len(), 7);
+    assert_eq!(assets[6]["date"], today.to_string());
+    let asset_counts: Vec<u64> = assets
+        .iter()
+        .map(|a| a["count"].as_u64().unwrap())
+        .collect();
+    assert_eq!(asset_counts, vec![0, 0, 0, 0, 1, 0, 1]);
+
+    let vulnerabilities = body["vulnerabilities"].as_array().unwrap();
+    assert_eq!(vulnerabilities.len(), 7);
+    assert_eq!(vulnerabilities[0]["count"], 0);
+    assert_eq!(vulnerabilities[6]["count"], 3);
+}
+
+#[tokio::test]
+async fn test_get_dashboard_trends_rejects_invalid_period() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    for days in ["0", "366"] {
+        let request = Request::builder()
+            .uri(format!("/api/dashboard/trends?days={}", days))
+            .method("GET")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,234 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use backend::{
+    models::{Asset, DiscoveryJob, JobAssetLink},
+    DiscoveryJobRepository,
+};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use shared::types::{AssetType, JobStatus, JobType};
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn get(router: &Router, uri: &str) -> (StatusCode, Value) {
+    let token = authenticate_test_user(router).await;
+    let request = Request::builder()
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_discovery_task_summary_counts_linked_findings() {
+    let jobs = Arc::new(InMemoryDiscoveryJobRepository::default());
+    let organization_id = Uuid::new_v4();
+
+    let mut job = DiscoveryJob::new(
+        organization_id,
+        JobType::PortScan,
+        Some("example.com".to_string()),
+        None,
+    );
+    let started_at = chrono::Utc::now() - chrono::Duration::minutes(45);
+    job.status = JobStatus::Completed;
+    job.started_at = Some(started_at);
+    job.completed_at = Some(started_at + chrono::Duration::seconds(2712));
+    jobs.create_job(&job).await.unwrap();
+
+    // Two assets found by the job, one found by something else
+    for (value, ports, technologies, vulnerabilities, linked) in [
+        ("1.1.1.1", 3, 2, 4, true),
+        ("2.2.2.2", 1, 0, 1, true),
+        ("3.3.3.3", 5, 5, 5, false),
+    ] {
+        let asset = Asset::new(
+            organization_id,
+            AssetType::IPAddress,
+            value.to_string(),
+            None,
+        );
+        if linked {
+            jobs.create_job_asset_link(&JobAssetLink::new(job.id, asset.id))
+                .await
+                .unwrap();
+        }
+        jobs.add_asset(asset, ports, technologies, vulnerabilities);
+    }
+
+    let mut state = create_test_app_state();
+    state.discovery_job_repository = jobs;
+    let router = api::routes::create_router(state);
+
+    let (status, summary) = get(&router, &format!("/api/discovery/jobs/{}/summary", job.id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(summary["job_id"], job.id.to_string());
+    assert_eq!(summary["status"], "COMPLETED");
+    assert_eq!(summary["assets"], 2);
+    assert_eq!(summary["ports"], 4);
+    assert_eq!(summary["technologies"], 2);
+    assert_eq!(summary["vulnerabilities"], 5);
+    assert_eq!(summary["duration_seconds"], 2712);
+}
+
+#[tokio::test]
+async fn test_discovery_task_summary_for_pending_and_unknown_jobs() {
+    let jobs = Arc::new(InMemoryDiscoveryJobRepository::default());
+    let job = DiscoveryJob::new(Uuid::new_v4(), JobType::DnsEnum, None, None);
+    jobs.create_job(&job).await.unwrap();
+
+    let mut state = create_test_app_state();
+    state.discovery_job_repository = jobs;
+    let router = api::routes::create_router(state);
+
+    // Nothing found yet, and no time spent
+    let (status, summary) = get(&router, &format!("/api/discovery/jobs/{}/summary", job.id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(summary["assets"], 0);
+    assert!(summary["duration_seconds"].is_null());
+
+    let uri = format!("/api/discovery/jobs/{}/summary", Uuid::new_v4());
+    let (status, _) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_discovery_task_assets_lists_linked_assets() {
+    let jobs = Arc::new(InMemoryDiscoveryJobRepository::default());
+    let organization_id = Uuid::new_v4();
+    let job = DiscoveryJob::new(organization_id, JobType::DnsEnum, None, None);
+    jobs.create_job(&job).await.unwrap();
+
+    let mut linked = Vec::new();
+    for value in ["a.example.com", "b.example.com", "c.example.com"] {
+        let asset = Asset::new(organization_id, AssetType::Domain, value.to_string(), None);
+        if value != "c.example.com" {
+            linked.push(asset.id);
+        }
+        jobs.add_asset(asset, 0, 0, 0);
+    }
+    jobs.link_job_to_assets(job.id, &linked).await.unwrap();
+
+    let mut state = create_test_app_state();
+    state.discovery_job_repository = jobs;
+    let router = api::routes::create_router(state);
+
+    let (status, body) = get(&router, &format!("/api/discovery/jobs/{}/assets", job.id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+    let values: Vec<_> = body["assets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|asset| asset["value"].as_str().unwrap())
+        .collect();
+    assert_eq!(values, vec!["a.example.com", "b.example.com"]);
+
+    let uri = format!("/api/discovery/jobs/{}/assets", Uuid::new_v4());
+    let (status, _) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_discovery_task_reports_duration() {
+    let jobs = Arc::new(InMemoryDiscoveryJobRepository::default());
+    let organization_id = Uuid::new_v4();
+
+    let started_at = chrono::Utc::now() - chrono::Duration::hours(2);
+    let mut completed = DiscoveryJob::new(organization_id, JobType::PortScan, None, None);
+    completed.status = JobStatus::Completed;
+    completed.started_at = Some(started_at);
+    completed.completed_at = Some(started_at + chrono::Duration::seconds(90));
+    jobs.create_job(&completed).await.unwrap();
+
+    let mut running = DiscoveryJob::new(organization_id, JobType::DnsEnum, None, None);
+    running.status = JobStatus::Running;
+    running.started_at = Some(started_at);
+    jobs.create_job(&running).await.unwrap();
+
+    let pending = DiscoveryJob::new(organization_id, JobType::DnsEnum, None, None);
+    jobs.create_job(&pending).await.unwrap();
+
+    let mut state = create_test_app_state();
+    state.discovery_job_repository = jobs;
+    let router = api::routes::create_router(state);
+
+    let (status, task) = get(&router, &format!("/api/discovery-tasks/{}", completed.id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(task["id"], completed.id.to_string());
+    assert_eq!(task["duration_seconds"], 90);
+
+    let (_, task) = get(&router, &format!("/api/discovery-tasks/{}", running.id)).await;
+    assert!(task["duration_seconds"].as_i64().unwrap() >= 2 * 3600);
+
+    let (_, task) = get(&router, &format!("/api/discovery-tasks/{}", pending.id)).await;
+    assert!(task["duration_seconds"].is_null());
+
+    let (_, list) = get(&router, "/api/discovery-tasks").await;
+    let durations: Vec<_> = list["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["duration_seconds"].clone())
+        .collect();
+    assert_eq!(durations.len(), 3);
+    assert!(durations.contains(&serde_json::json!(90)));
+}
+
+#[tokio::test]
+async fn test_list_discovery_jobs_by_several_statuses() {
+    let jobs = Arc::new(InMemoryDiscoveryJobRepository::default());
+    let organization_id = Uuid::new_v4();
+    for (job_type, status) in [
+        (JobType::DnsEnum, JobStatus::Pending),
+        (JobType::PortScan, JobStatus::Running),
+        (JobType::PortScan, JobStatus::Completed),
+        (JobType::DnsEnum, JobStatus::Failed),
+    ] {
+        let mut job = DiscoveryJob::new(organization_id, job_type, None, None);
+        job.status = status;
+        jobs.create_job(&job).await.unwrap();
+    }
+
+    let mut state = create_test_app_state();
+    state.discovery_job_repository = jobs;
+    let router = api::routes::create_router(state);
+
+    let statuses = |body: &Value| {
+        let mut statuses: Vec<String> = body["tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["status"].as_str().unwrap().to_string())
+            .collect();
+        statuses.sort();
+        statuses
+    };
+
+    // The discovery page asks for active and finished jobs this way
+    let (status, body) = get(&router, "/api/discovery/jobs?status=RUNNING,PENDING").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+    assert_eq!(statuses(&body), vec!["PENDING", "RUNNING"]);
+
+    let uri = "/api/discovery-tasks?status=COMPLETED,%20FAILED&job_type=PORTSCAN";
+    let (_, body) = get(&router, uri).await;
+    assert_eq!(statuses(&body), vec!["COMPLETED"]);
+
+    let (_, body) = get(&router, "/api/discovery-tasks?status=").await;
+    assert_eq!(body["total"], 4);
+
+    let (status, body) = get(&router, "/api/discovery/jobs?status=RUNNING,BOGUS").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]["message"].as_str().unwrap().contains("BOGUS"));
+}
@@ -0,0 +1,111 @@
+use api::{middleware::auth::generate_token, routes::create_router, test_utils::*};
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use backend::services::AssetServiceImpl;
+use http_body_util::BodyExt;
+use infrastructure::{
+    database::migrations::Migrator, repositories::RepositoryFactory,
+    utils::testing::create_test_organization,
+};
+use serde_json::json;
+use shared::types::{ResourceEvent, ResourceEventType, ResourceKind, UserRole};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+// Helper to create an asset through the API
+async fn create_asset(router: &Router, token: &str, organization_id: Uuid, value: &str) {
+    let request = Request::builder()
+        .uri("/api/assets")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "organization_id": organization_id,
+                "asset_type": "DOMAIN",
+                "value": value
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[sqlx::test]
+async fn test_creating_asset_emits_event(pool: PgPool) {
+    Migrator::new(pool.clone()).run_migrations().await.unwrap();
+
+    let factory = RepositoryFactory::new(pool.clone());
+    let org = create_test_organization(&factory, "Live Org")
+        .await
+        .unwrap();
+    let other_org = create_test_organization(&factory, "Other Live Org")
+        .await
+        .unwrap();
+
+    let mut state = create_test_app_state();
+    state.asset_service = Arc::new(AssetServiceImpl::new(
+        factory.asset_repository(),
+        factory.asset_history_repository(),
+    ));
+    state.events.listen(&pool).await.unwrap();
+    let token = generate_token(
+        &Uuid::new_v4().to_string(),
+        UserRole::Analyst,
+        Some(&org.id.to_string()),
+        &state.config,
+    )
+    .unwrap();
+    let router = create_router(state);
+
+    // Subscribe the way a browser's EventSource does, with the token in the
+    // query string
+    let request = Request::builder()
+        .uri(format!("/api/events?access_token={}", token))
+        .method("GET")
+        .header(header::ACCEPT, "text/event-stream")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/event-stream"));
+    let mut stream = response.into_body();
+
+    // Another organization's asset isn't sent to this subscriber
+    create_asset(&router, &token, other_org.id, "other-live.example.com").await;
+    create_asset(&router, &token, org.id, "live.example.com").await;
+
+    let frame = tokio::time::timeout(Duration::from_secs(5), stream.frame())
+        .await
+        .expect("no event before the timeout")
+        .unwrap()
+        .unwrap();
+    let chunk = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+    let data = chunk
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .expect("event has no data");
+
+    let event: ResourceEvent = serde_json::from_str(data).unwrap();
+    assert_eq!(
+        event,
+        ResourceEvent {
+            event_type: ResourceEventType::Created,
+            org_id: org.id,
+            resource: ResourceKind::Asset,
+        }
+    );
+}
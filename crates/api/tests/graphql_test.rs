@@ -0,0 +1,152 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use backend::models::AuditLogFilter;
+use http_body_util::BodyExt;
+use serde_json::json;
+use shared::types::AuditAction;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Send a GraphQL request as `token` and return the response status and body
+async fn execute(
+    router: &Router,
+    token: Option<&str>,
+    query: &str,
+    variables: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let mut request = Request::builder()
+        .uri("/graphql")
+        .method("POST")
+        .header("Content-Type", "application/json");
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let request = request
+        .body(Body::from(
+            json!({ "query": query, "variables": variables }).to_string(),
+        ))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_graphql_asset_with_nested_vulnerabilities() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+    let asset_id = Uuid::new_v4();
+
+    let query = r#"
+        query Asset($id: UUID!) {
+            asset(id: $id) {
+                id
+                value
+                assetType
+                tags
+                ports { portNumber protocol }
+                vulnerabilities(severity: HIGH) { title severity cveId }
+            }
+        }
+    "#;
+    let (status, body) = execute(&router, Some(&token), query, json!({ "id": asset_id })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("errors").is_none(), "unexpected errors: {}", body);
+
+    let asset = &body["data"]["asset"];
+    assert_eq!(asset["id"], asset_id.to_string());
+    assert_eq!(asset["value"], "test.example.com");
+    assert_eq!(asset["assetType"], "DOMAIN");
+    assert_eq!(asset["tags"], json!(["production"]));
+    assert_eq!(
+        asset["ports"],
+        json!([{ "portNumber": 443, "protocol": "TCP" }])
+    );
+
+    // Fields that weren't asked for aren't returned
+    assert!(asset.get("riskScore").is_none());
+
+    let vulnerabilities = asset["vulnerabilities"].as_array().unwrap();
+    assert_eq!(vulnerabilities.len(), 2);
+    assert_eq!(vulnerabilities[0]["title"], "Test Vulnerability 1");
+    assert_eq!(vulnerabilities[0]["severity"], "HIGH");
+    assert_eq!(vulnerabilities[0]["cveId"], "CVE-2023-1234");
+}
+
+#[tokio::test]
+async fn test_graphql_update_vulnerability_status() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+    let vulnerability_id = Uuid::new_v4();
+
+    let mutation = r#"
+        mutation Close($id: UUID!) {
+            updateVulnerabilityStatus(id: $id, status: FALSE_POSITIVE) { id status }
+        }
+    "#;
+    let (status, body) = execute(
+        &router,
+        Some(&token),
+        mutation,
+        json!({ "id": vulnerability_id }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("errors").is_none(), "unexpected errors: {}", body);
+    let updated = &body["data"]["updateVulnerabilityStatus"];
+    assert_eq!(updated["id"], vulnerability_id.to_string());
+    assert_eq!(updated["status"], "FALSE_POSITIVE");
+}
+
+#[tokio::test]
+async fn test_graphql_mutations_are_audited() {
+    let state = create_test_app_state();
+    let audit_log = state.audit_log_repository.clone();
+    let router = api::routes::create_router(state);
+    let token = authenticate_test_user(&router).await;
+    let asset_id = Uuid::new_v4();
+
+    let mutation = r#"
+        mutation Archive($id: UUID!) {
+            updateAssetStatus(id: $id, status: ARCHIVED) { id }
+        }
+    "#;
+    let (status, body) = execute(&router, Some(&token), mutation, json!({ "id": asset_id })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("errors").is_none(), "unexpected errors: {}", body);
+
+    let entries = audit_log
+        .list_entries(&AuditLogFilter::default(), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, AuditAction::Update);
+    assert_eq!(entries[0].resource_type, "asset");
+    assert_eq!(entries[0].resource_id, Some(asset_id));
+
+    // Queries aren't audited
+    let query = r#"query Asset($id: UUID!) { asset(id: $id) { id } }"#;
+    execute(&router, Some(&token), query, json!({ "id": asset_id })).await;
+    let total = audit_log
+        .count_entries(&AuditLogFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+}
+
+#[tokio::test]
+async fn test_graphql_requires_authentication() {
+    let router = api::routes::create_router(create_test_app_state());
+
+    let (status, _) = execute(&router, None, "{ assets { id } }", json!({})).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -30,3 +30,49 @@ async fn test_health_endpoint() {
     // Check that the response contains the expected data
     assert_eq!(body["status"], "ok");
 }
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let router = api::routes::create_router(create_test_app_state());
+
+    // Scraped without authentication
+    let request = Request::builder()
+        .uri("/metrics")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[axum::http::header::CONTENT_TYPE],
+        shared::metrics::METRICS_CONTENT_TYPE
+    );
+}
+
+#[tokio::test]
+async fn test_version_endpoint() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/version")
+        .method("GET")
+        .header(
+            axum::http::header::AUTHORIZATION,
+            format!("Bearer {}", token),
+        )
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!body["git_commit"].as_str().unwrap().is_empty());
+    let built_at = body["built_at"].as_str().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(built_at).is_ok());
+}
@@ -0,0 +1,146 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use api::{login_lockout::LoginLockout, test_utils::*};
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use http_body_util::BodyExt;
+use serde_json::json;
+use tower::ServiceExt;
+
+const EMAIL: &str = "locked@example.com";
+const CLIENT_IP: &str = "203.0.113.7";
+const PROXY: [u8; 4] = [127, 0, 0, 1];
+
+/// Router behind a trusted proxy that locks an account after three failures
+/// from one address, or five from any, for `cooldown`
+fn setup(cooldown: Duration) -> Router {
+    let mut state = create_test_app_state();
+    state.config.trusted_proxies = vec!["127.0.0.1".to_string()];
+    state.login_lockout = Arc::new(LoginLockout::new(3, 5, cooldown));
+    api::routes::create_router(state)
+}
+
+async fn login_from(router: &Router, password: &str, peer: [u8; 4], ip: &str) -> Response {
+    let request = Request::builder()
+        .uri("/api/auth/login")
+        .method("POST")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Forwarded-For", ip)
+        .extension(ConnectInfo(SocketAddr::from((peer, 40000))))
+        .body(Body::from(
+            json!({ "email": EMAIL, "password": password }).to_string(),
+        ))
+        .unwrap();
+
+    router.clone().oneshot(request).await.unwrap()
+}
+
+/// Login through the trusted proxy, forwarded for `ip`
+async fn login(router: &Router, password: &str, ip: &str) -> Response {
+    login_from(router, password, PROXY, ip).await
+}
+
+async fn fail_logins(router: &Router, times: usize) {
+    for _ in 0..times {
+        let response = login(router, MOCK_WRONG_PASSWORD, CLIENT_IP).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn test_repeated_failed_logins_lock_account() {
+    let router = setup(Duration::from_secs(900));
+    fail_logins(&router, 3).await;
+
+    // Locked, even with the right password
+    let response = login(&router, "password123", CLIENT_IP).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 900);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "ACCOUNT_LOCKED");
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Too many failed login attempts"));
+}
+
+#[tokio::test]
+async fn test_lockout_clears_after_cooldown() {
+    let router = setup(Duration::from_millis(300));
+    fail_logins(&router, 3).await;
+
+    let response = login(&router, "password123", CLIENT_IP).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    let response = login(&router, "password123", CLIENT_IP).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_successful_login_resets_failed_attempts() {
+    let router = setup(Duration::from_secs(900));
+    fail_logins(&router, 2).await;
+
+    let response = login(&router, "password123", CLIENT_IP).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The count starts over, so two more failures don't lock the account
+    fail_logins(&router, 2).await;
+    let response = login(&router, "password123", CLIENT_IP).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_lockout_is_per_address() {
+    let router = setup(Duration::from_secs(900));
+    fail_logins(&router, 3).await;
+
+    let response = login(&router, "password123", "198.51.100.1").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_forwarded_for_is_ignored_from_untrusted_peer() {
+    let router = setup(Duration::from_secs(900));
+    let peer = [198, 51, 100, 9];
+
+    // A client talking to us directly can't pick a fresh address per attempt
+    for attempt in 0..3 {
+        let forwarded = format!("192.0.2.{}", attempt);
+        let response = login_from(&router, MOCK_WRONG_PASSWORD, peer, &forwarded).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = login_from(&router, "password123", peer, "192.0.2.200").await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_failures_across_addresses_lock_account() {
+    let router = setup(Duration::from_secs(900));
+
+    for attempt in 0..5 {
+        let ip = format!("192.0.2.{}", attempt);
+        let response = login(&router, MOCK_WRONG_PASSWORD, &ip).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // No single address reached its threshold, but the account did
+    let response = login(&router, "password123", "198.51.100.1").await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
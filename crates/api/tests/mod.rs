@@ -1,4 +1,22 @@
 pub mod asset_handler_test;
+pub mod audit_handler_test;
 pub mod auth_handler_test;
+pub mod dashboard_handler_test;
+pub mod discovery_task_handler_test;
+pub mod event_handler_test;
+pub mod graphql_test;
 pub mod health_test;
+pub mod login_lockout_test;
+pub mod organization_handler_test;
+pub mod pagination_test;
+pub mod request_id_test;
+pub mod request_limits_test;
+pub mod role_guard_test;
+pub mod scan_profile_handler_test;
+pub mod scan_schedule_handler_test;
+pub mod security_headers_test;
+pub mod technology_handler_test;
+pub mod tls_test;
+pub mod token_revocation_test;
+pub mod user_handler_test;
 pub mod vulnerability_handler_test;
@@ -0,0 +1,241 @@
+use api::{middleware::auth::generate_token, test_utils::*};
+use axum::{
+    body::Body,
+    http::{header, Method, Request, Response, StatusCode},
+    Router,
+};
+use backend::services::OrganizationServiceImpl;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use shared::types::{UserRole, ID};
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+struct Setup {
+    router: Router,
+    admin_token: String,
+    state: api::state::AppState,
+}
+
+fn setup() -> Setup {
+    let mut state = create_test_app_state();
+    state.organization_service = Arc::new(OrganizationServiceImpl::new(Arc::new(
+        InMemoryOrganizationRepository::default(),
+    )));
+    let admin_token = token_for(&state, UserRole::Admin, None);
+    Setup {
+        router: api::routes::create_router(state.clone()),
+        admin_token,
+        state,
+    }
+}
+
+fn token_for(state: &api::state::AppState, role: UserRole, organization_id: Option<ID>) -> String {
+    generate_token(
+        &Uuid::new_v4().to_string(),
+        role,
+        organization_id.map(|id| id.to_string()).as_deref(),
+        &state.config,
+    )
+    .unwrap()
+}
+
+async fn send(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> Response<Body> {
+    let request = Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn json_body(response: Response<Body>) -> Value {
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+async fn create(router: &Router, token: &str, name: &str) -> Response<Body> {
+    send(
+        router,
+        Method::POST,
+        "/api/organizations",
+        token,
+        Some(json!({ "name": name })),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_create_and_list_organizations() {
+    let Setup {
+        router,
+        admin_token,
+        ..
+    } = setup();
+
+    let response = create(&router, &admin_token, "  Acme Corp ").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = json_body(response).await;
+    assert_eq!(created["name"], "Acme Corp");
+
+    let response = create(&router, &admin_token, "Globex").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = send(
+        &router,
+        Method::GET,
+        "/api/organizations",
+        &admin_token,
+        None,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "2");
+    let names: Vec<Value> = json_body(response)
+        .await
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|org| org["name"].clone())
+        .collect();
+    assert_eq!(names, vec![json!("Acme Corp"), json!("Globex")]);
+}
+
+#[tokio::test]
+async fn test_create_organization_rejects_duplicate_and_empty_names() {
+    let Setup {
+        router,
+        admin_token,
+        ..
+    } = setup();
+
+    assert_eq!(
+        create(&router, &admin_token, "Acme Corp").await.status(),
+        StatusCode::CREATED
+    );
+
+    let response = create(&router, &admin_token, "Acme Corp").await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body = json_body(response).await;
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Acme Corp"));
+
+    assert_eq!(
+        create(&router, &admin_token, "   ").await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_rename_to_taken_name_is_rejected() {
+    let Setup {
+        router,
+        admin_token,
+        ..
+    } = setup();
+
+    create(&router, &admin_token, "Acme Corp").await;
+    let globex = json_body(create(&router, &admin_token, "Globex").await).await;
+    let uri = format!("/api/organizations/{}", globex["id"].as_str().unwrap());
+
+    let response = send(
+        &router,
+        Method::PUT,
+        &uri,
+        &admin_token,
+        Some(json!({ "name": "Acme Corp" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Keeping its own name isn't a clash
+    let response = send(
+        &router,
+        Method::PUT,
+        &uri,
+        &admin_token,
+        Some(json!({ "name": "Globex", "notification_period": "WEEKLY" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_delete_organization() {
+    let Setup {
+        router,
+        admin_token,
+        ..
+    } = setup();
+
+    let created = json_body(create(&router, &admin_token, "Acme Corp").await).await;
+    let uri = format!("/api/organizations/{}", created["id"].as_str().unwrap());
+
+    let response = send(&router, Method::DELETE, &uri, &admin_token, None).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = send(&router, Method::DELETE, &uri, &admin_token, None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = send(&router, Method::GET, &uri, &admin_token, None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_non_admins_are_scoped_to_their_organization() {
+    let Setup {
+        router,
+        admin_token,
+        state,
+    } = setup();
+
+    let own = json_body(create(&router, &admin_token, "Acme Corp").await).await;
+    let other = json_body(create(&router, &admin_token, "Globex").await).await;
+    let own_id: ID = own["id"].as_str().unwrap().parse().unwrap();
+    let manager = token_for(&state, UserRole::Manager, Some(own_id));
+
+    // Only admins create or delete organizations
+    assert_eq!(
+        create(&router, &manager, "Initech").await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let own_uri = format!("/api/organizations/{}", own_id);
+    let response = send(&router, Method::DELETE, &own_uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Listing shows only the caller's organization
+    let response = send(&router, Method::GET, "/api/organizations", &manager, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "1");
+    let listed = json_body(response).await;
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["id"], own["id"]);
+
+    let response = send(&router, Method::GET, &own_uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Other organizations can't be read or changed
+    let other_uri = format!("/api/organizations/{}", other["id"].as_str().unwrap());
+    let response = send(&router, Method::GET, &other_uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = send(
+        &router,
+        Method::PUT,
+        &other_uri,
+        &manager,
+        Some(json!({ "name": "Taken Over" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,76 @@
+use api::{
+    errors::ApiError,
+    extract::{Pagination, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT},
+    test_utils::*,
+};
+use axum::{
+    body::Body,
+    extract::FromRequestParts,
+    http::{header, Request, StatusCode},
+};
+use tower::ServiceExt;
+
+/// Pagination extracted from a request for `uri`
+async fn pagination(uri: &str) -> Result<Pagination, ApiError> {
+    let (mut parts, _) = Request::builder().uri(uri).body(()).unwrap().into_parts();
+    Pagination::from_request_parts(&mut parts, &()).await
+}
+
+#[tokio::test]
+async fn test_pagination_defaults() {
+    let page = pagination("/api/assets?status=active").await.unwrap();
+    assert_eq!(page.limit, DEFAULT_PAGE_LIMIT);
+    assert_eq!(page.offset, 0);
+
+    let page = pagination("/api/assets?limit=25&offset=100").await.unwrap();
+    assert_eq!((page.limit, page.offset), (25, 100));
+}
+
+#[tokio::test]
+async fn test_pagination_clamps_over_cap_limit() {
+    let page = pagination("/api/assets?limit=1000000").await.unwrap();
+    assert_eq!(page.limit, MAX_PAGE_LIMIT);
+
+    let page = pagination("/api/assets?limit=200").await.unwrap();
+    assert_eq!(page.limit, 200);
+}
+
+#[tokio::test]
+async fn test_pagination_rejects_negative_values() {
+    for uri in ["/api/assets?offset=-1", "/api/assets?limit=-5"] {
+        match pagination(uri).await {
+            Err(ApiError::BadRequest(message)) => assert!(message.contains("must not be negative")),
+            other => panic!("expected a bad request for {}, got {:?}", uri, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_list_endpoints_reject_negative_offset() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    for path in [
+        "/api/assets",
+        "/api/vulnerabilities",
+        "/api/technologies",
+        "/api/discovery-tasks",
+    ] {
+        let request = Request::builder()
+            .uri(format!("{}?offset=-1", path))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", path);
+
+        // An over-cap limit is clamped rather than refused
+        let request = Request::builder()
+            .uri(format!("{}?limit=1000000", path))
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+    }
+}
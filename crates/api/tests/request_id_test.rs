@@ -0,0 +1,72 @@
+use api::{middleware::REQUEST_ID_HEADER, test_utils::*};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_provided_request_id_is_echoed() {
+    let router = api::routes::create_router(create_test_app_state());
+    let request = Request::builder()
+        .uri("/health")
+        .method("GET")
+        .header(REQUEST_ID_HEADER, "client-request-42")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(REQUEST_ID_HEADER).unwrap(),
+        "client-request-42"
+    );
+}
+
+#[tokio::test]
+async fn test_request_id_is_generated_when_absent() {
+    let router = api::routes::create_router(create_test_app_state());
+    let request = Request::builder()
+        .uri("/health")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+
+    let request_id = response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .expect("response should carry a request ID")
+        .to_str()
+        .unwrap();
+    assert!(Uuid::parse_str(request_id).is_ok());
+}
+
+#[tokio::test]
+async fn test_error_body_includes_request_id() {
+    let router = api::routes::create_router(create_test_app_state());
+
+    // No token, so the auth middleware rejects the request
+    let request = Request::builder()
+        .uri("/api/assets")
+        .method("GET")
+        .header(REQUEST_ID_HEADER, "client-request-43")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response.headers().get(REQUEST_ID_HEADER).unwrap(),
+        "client-request-43"
+    );
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["request_id"], "client-request-43");
+}
@@ -0,0 +1,103 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use backend::models::ScanSchedule;
+use shared::types::{JobType, ID};
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Schedule repository whose listing never finishes in time
+struct SlowScanScheduleRepository;
+
+#[async_trait::async_trait]
+impl backend::ScanScheduleRepository for SlowScanScheduleRepository {
+    async fn create_schedule(&self, schedule: &ScanSchedule) -> backend::Result<ScanSchedule> {
+        Ok(schedule.clone())
+    }
+
+    async fn get_schedule(&self, _id: ID) -> backend::Result<ScanSchedule> {
+        Ok(ScanSchedule::new(
+            Uuid::new_v4(),
+            "example.com".to_string(),
+            JobType::DnsEnum,
+            "0 0 2 * * *".to_string(),
+        ))
+    }
+
+    async fn update_schedule(&self, schedule: &ScanSchedule) -> backend::Result<ScanSchedule> {
+        Ok(schedule.clone())
+    }
+
+    async fn delete_schedule(&self, _id: ID) -> backend::Result<bool> {
+        Ok(true)
+    }
+
+    async fn list_schedules(
+        &self,
+        _organization_id: Option<ID>,
+        _limit: usize,
+        _offset: usize,
+    ) -> backend::Result<Vec<ScanSchedule>> {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        Ok(vec![])
+    }
+
+    async fn count_schedules(&self, _organization_id: Option<ID>) -> backend::Result<usize> {
+        Ok(0)
+    }
+
+    async fn list_enabled_schedules(&self) -> backend::Result<Vec<ScanSchedule>> {
+        Ok(vec![])
+    }
+}
+
+#[tokio::test]
+async fn test_oversized_body_is_rejected() {
+    let mut state = create_test_app_state();
+    state.config.max_request_body_bytes = 1024;
+    let router = api::routes::create_router(state);
+    let token = authenticate_test_user(&router).await;
+
+    let body = serde_json::json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "asset_type": "DOMAIN",
+        "value": "a".repeat(4096),
+    })
+    .to_string();
+
+    let request = Request::builder()
+        .uri("/api/assets")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_slow_request_times_out() {
+    let mut state = create_test_app_state();
+    state.config.request_timeout_secs = 1;
+    state.scan_schedule_repository = Arc::new(SlowScanScheduleRepository);
+    let router = api::routes::create_router(state);
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/scan-schedules")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = tokio::time::timeout(Duration::from_secs(10), router.oneshot(request))
+        .await
+        .expect("the API should answer before the handler finishes")
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+}
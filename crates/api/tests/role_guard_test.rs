@@ -0,0 +1,162 @@
+use api::{
+    middleware::auth::{generate_token, Claims},
+    test_utils::*,
+};
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use shared::{config::Config, types::UserRole};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn setup() -> (Router, Config) {
+    let state = create_test_app_state();
+    let config = state.config.clone();
+    (api::routes::create_router(state), config)
+}
+
+fn token_for(role: UserRole, config: &Config) -> String {
+    generate_token(
+        &Uuid::new_v4().to_string(),
+        role,
+        Some(&Uuid::new_v4().to_string()),
+        config,
+    )
+    .unwrap()
+}
+
+async fn send(router: &Router, method: Method, uri: &str, token: &str) -> StatusCode {
+    let body = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "asset_type": "DOMAIN",
+        "value": "roles.example.com"
+    });
+
+    let request = Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    router.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn test_every_role_can_read() {
+    let (router, config) = setup();
+
+    for role in [
+        UserRole::Admin,
+        UserRole::Manager,
+        UserRole::Analyst,
+        UserRole::ReadOnly,
+    ] {
+        let token = token_for(role, &config);
+        let status = send(&router, Method::GET, "/api/assets", &token).await;
+        assert_eq!(status, StatusCode::OK, "{} listing assets", role);
+    }
+}
+
+#[tokio::test]
+async fn test_create_asset_by_role() {
+    let (router, config) = setup();
+
+    for (role, expected) in [
+        (UserRole::Admin, StatusCode::CREATED),
+        (UserRole::Manager, StatusCode::CREATED),
+        (UserRole::Analyst, StatusCode::CREATED),
+        (UserRole::ReadOnly, StatusCode::FORBIDDEN),
+    ] {
+        let token = token_for(role, &config);
+        let status = send(&router, Method::POST, "/api/assets", &token).await;
+        assert_eq!(status, expected, "{} creating an asset", role);
+    }
+}
+
+#[tokio::test]
+async fn test_admin_route_by_role() {
+    let (router, config) = setup();
+
+    for (role, expected) in [
+        (UserRole::Admin, StatusCode::OK),
+        (UserRole::Manager, StatusCode::FORBIDDEN),
+        (UserRole::Analyst, StatusCode::FORBIDDEN),
+        (UserRole::ReadOnly, StatusCode::FORBIDDEN),
+    ] {
+        let token = token_for(role, &config);
+        let status = send(&router, Method::GET, "/api/audit", &token).await;
+        assert_eq!(status, expected, "{} reading the audit log", role);
+    }
+}
+
+#[tokio::test]
+async fn test_read_only_is_blocked_from_mutating_routes() {
+    let (router, config) = setup();
+    let token = token_for(UserRole::ReadOnly, &config);
+    let id = Uuid::new_v4();
+
+    let routes = [
+        (Method::POST, "/api/organizations".to_string()),
+        (Method::PUT, format!("/api/organizations/{}", id)),
+        (Method::DELETE, format!("/api/organizations/{}", id)),
+        (Method::POST, "/api/assets".to_string()),
+        (Method::PATCH, "/api/assets/bulk".to_string()),
+        (Method::PUT, format!("/api/assets/{}", id)),
+        (Method::DELETE, format!("/api/assets/{}", id)),
+        (Method::POST, format!("/api/assets/{}/tags", id)),
+        (Method::DELETE, format!("/api/assets/{}/tags/prod", id)),
+        (Method::POST, "/api/discovery-tasks".to_string()),
+        (Method::POST, format!("/api/discovery-tasks/{}/cancel", id)),
+        (Method::DELETE, format!("/api/discovery-tasks/{}", id)),
+        (Method::POST, "/api/scan-schedules".to_string()),
+        (Method::PUT, format!("/api/scan-schedules/{}", id)),
+        (Method::DELETE, format!("/api/scan-schedules/{}", id)),
+        (Method::POST, "/api/scan-profiles".to_string()),
+        (Method::POST, "/api/scan-profiles/launch".to_string()),
+        (Method::PUT, format!("/api/scan-profiles/{}", id)),
+        (Method::DELETE, format!("/api/scan-profiles/{}", id)),
+        (Method::POST, "/api/vulnerabilities".to_string()),
+        (Method::PATCH, "/api/vulnerabilities/bulk".to_string()),
+        (Method::PUT, format!("/api/vulnerabilities/{}", id)),
+        (Method::DELETE, format!("/api/vulnerabilities/{}", id)),
+    ];
+
+    for (method, uri) in routes {
+        let status = send(&router, method.clone(), &uri, &token).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "READONLY {} {}", method, uri);
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_role_is_rejected() {
+    let (router, config) = setup();
+
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = Claims {
+        sub: Uuid::new_v4().to_string(),
+        role: "SUPERUSER".to_string(),
+        org: None,
+        exp: now + 3600,
+        iat: now,
+        iss: "easm-api".to_string(),
+        aud: "easm-client".to_string(),
+        jti: Uuid::new_v4().to_string(),
+        device: None,
+        scope: None,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )
+    .unwrap();
+
+    let status = send(&router, Method::POST, "/api/assets", &token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
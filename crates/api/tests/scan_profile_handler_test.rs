@@ -0,0 +1,143 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_launch_quick_scan_profile() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let launch_data = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "profile": "quick",
+        "target": "example.com"
+    });
+
+    let request = Request::builder()
+        .uri("/api/scan-profiles/launch")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(launch_data.to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["target"], "example.com");
+    assert_eq!(body["status"], "PENDING");
+    assert_eq!(body["job_type"], "DNSENUM");
+    assert_eq!(body["configuration"]["profile"], "Quick");
+    assert_eq!(
+        body["configuration"]["methods"],
+        json!(["DNSENUM", "PORTSCAN"])
+    );
+    assert_eq!(body["configuration"]["depth"], 1);
+    assert_eq!(
+        body["configuration"]["ports"],
+        json!([22, 80, 443, 8080, 8443])
+    );
+}
+
+#[tokio::test]
+async fn test_launch_unknown_scan_profile() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let launch_data = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "profile": "Exhaustive",
+        "target": "example.com"
+    });
+
+    let request = Request::builder()
+        .uri("/api/scan-profiles/launch")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(launch_data.to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_scan_profiles_includes_built_ins() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!(
+            "/api/scan-profiles?organization_id={}",
+            Uuid::new_v4()
+        ))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let names: Vec<&str> = body["profiles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|profile| profile["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Quick", "Standard", "Deep"]);
+    assert_eq!(body["total"], 3);
+}
+
+#[tokio::test]
+async fn test_create_scan_profile_rejects_empty_methods() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let profile_data = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "name": "Nothing",
+        "methods": [],
+        "depth": 1,
+        "ports": [443],
+        "timeout_secs": 60
+    });
+
+    let request = Request::builder()
+        .uri("/api/scan-profiles")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(profile_data.to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_built_in_scan_profile_cannot_be_deleted() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!("/api/scan-profiles/{}", Uuid::from_u128(1)))
+        .method("DELETE")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,85 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_create_scan_schedule() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let schedule_data = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "target": "example.com",
+        "job_type": "DNSENUM",
+        "cron_expression": "0 0 2 * * *"
+    });
+
+    let request = Request::builder()
+        .uri("/api/scan-schedules")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(schedule_data.to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["target"], "example.com");
+    assert_eq!(body["cron_expression"], "0 0 2 * * *");
+    assert_eq!(body["enabled"], true);
+    assert!(body["last_run_at"].is_null());
+}
+
+#[tokio::test]
+async fn test_create_scan_schedule_rejects_invalid_cron() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let schedule_data = json!({
+        "organization_id": Uuid::new_v4().to_string(),
+        "target": "example.com",
+        "job_type": "DNSENUM",
+        "cron_expression": "every night"
+    });
+
+    let request = Request::builder()
+        .uri("/api/scan-schedules")
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(schedule_data.to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_disable_scan_schedule() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!("/api/scan-schedules/{}", Uuid::new_v4()))
+        .method("PUT")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "enabled": false }).to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["enabled"], false);
+}
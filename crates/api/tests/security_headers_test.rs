@@ -0,0 +1,64 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, Response, StatusCode},
+    Router,
+};
+use tower::ServiceExt;
+
+async fn get_health(router: Router) -> Response<Body> {
+    let request = Request::builder()
+        .uri("/health")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    router.oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_security_headers_are_set() {
+    let router = api::routes::create_router(create_test_app_state());
+
+    let response = get_health(router).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+    assert_eq!(headers[header::REFERRER_POLICY], "no-referrer");
+    assert_eq!(
+        headers[header::CONTENT_SECURITY_POLICY],
+        "default-src 'none'; frame-ancestors 'none'"
+    );
+}
+
+#[tokio::test]
+async fn test_content_security_policy_is_configurable() {
+    let mut state = create_test_app_state();
+    state.config.content_security_policy = "default-src 'self'".to_string();
+    let router = api::routes::create_router(state);
+
+    let response = get_health(router).await;
+
+    assert_eq!(
+        response.headers()[header::CONTENT_SECURITY_POLICY],
+        "default-src 'self'"
+    );
+}
+
+#[tokio::test]
+async fn test_security_headers_can_be_disabled() {
+    let mut state = create_test_app_state();
+    state.config.security_headers = false;
+    let router = api::routes::create_router(state);
+
+    let response = get_health(router).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert!(!headers.contains_key(header::X_CONTENT_TYPE_OPTIONS));
+    assert!(!headers.contains_key(header::X_FRAME_OPTIONS));
+    assert!(!headers.contains_key(header::REFERRER_POLICY));
+    assert!(!headers.contains_key(header::CONTENT_SECURITY_POLICY));
+}
@@ -0,0 +1,100 @@
+use api::test_utils::*;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use serde_json::json;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_get_technology_distribution() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/technologies/distribution")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(
+        body["by_name"],
+        json!([{"name": "PHP", "count": 1}, {"name": "nginx", "count": 1}])
+    );
+    assert_eq!(
+        body["by_category"],
+        json!([
+            {"name": "Programming Language", "count": 1},
+            {"name": "Web Server", "count": 1}
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_get_technology_distribution_requires_authentication() {
+    let router = api::routes::create_router(create_test_app_state());
+
+    let request = Request::builder()
+        .uri("/api/technologies/distribution")
+        .method("GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_technologies_filters_by_search_and_category() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/technologies?search=NGI&category=web%20server")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "1");
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body["total"], 1);
+    let technologies = body["technologies"].as_array().unwrap();
+    assert_eq!(technologies.len(), 1);
+    assert_eq!(technologies[0]["name"], "nginx");
+    assert_eq!(technologies[0]["version"], "1.25");
+    assert_eq!(technologies[0]["asset_value"], "test1.example.com");
+}
+
+#[tokio::test]
+async fn test_list_technologies_ignores_empty_filters() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/technologies?search=&category=")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["total"], 2);
+}
@@ -0,0 +1,91 @@
+use api::test_utils::*;
+use api::tls::load_tls_config;
+use std::fs;
+use std::net::TcpListener;
+
+/// A self-signed certificate for `localhost` and its key, written as PEM to
+/// `dir`, returning the certificate PEM and both paths
+fn write_self_signed_cert(dir: &std::path::Path) -> (String, String, String) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_pem = certified.cert.pem();
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    fs::write(&cert_path, &cert_pem).unwrap();
+    fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+    (
+        cert_pem,
+        cert_path.to_string_lossy().into_owned(),
+        key_path.to_string_lossy().into_owned(),
+    )
+}
+
+#[tokio::test]
+async fn test_server_negotiates_tls_with_configured_certificate() {
+    let dir = tempfile::tempdir().unwrap();
+    let (cert_pem, cert_path, key_path) = write_self_signed_cert(dir.path());
+
+    let tls = load_tls_config(&cert_path, &key_path).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let router = api::routes::create_router(create_test_app_state());
+    tokio::spawn(api::serve(listener, router, Some(tls)));
+
+    // A client trusting the self-signed certificate completes the handshake
+    let client = reqwest::Client::builder()
+        .add_root_certificate(reqwest::Certificate::from_pem(cert_pem.as_bytes()).unwrap())
+        .https_only(true)
+        .build()
+        .unwrap();
+    let response = client
+        .get(format!("https://localhost:{}/health", port))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // One that doesn't trust it refuses the connection
+    let result = reqwest::Client::new()
+        .get(format!("https://localhost:{}/health", port))
+        .send()
+        .await;
+    assert!(result.is_err());
+
+    // Plain HTTP isn't served on the TLS port
+    let result = reqwest::Client::new()
+        .get(format!("http://localhost:{}/health", port))
+        .send()
+        .await;
+    assert!(result.is_err() || !result.unwrap().status().is_success());
+}
+
+#[tokio::test]
+async fn test_server_serves_http_without_tls() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let router = api::routes::create_router(create_test_app_state());
+    tokio::spawn(api::serve(listener, router, None));
+
+    let response = reqwest::get(format!("http://127.0.0.1:{}/health", port))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+#[test]
+fn test_tls_is_off_without_both_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, cert_path, key_path) = write_self_signed_cert(dir.path());
+
+    let mut config = shared::config::Config::from_env().unwrap();
+    config.tls_cert_path = None;
+    config.tls_key_path = None;
+    assert!(api::tls::tls_config(&config).unwrap().is_none());
+
+    config.tls_cert_path = Some(cert_path);
+    config.tls_key_path = Some(key_path.clone());
+    assert!(api::tls::tls_config(&config).unwrap().is_some());
+
+    // A key where the certificate should be is reported
+    config.tls_cert_path = Some(key_path);
+    assert!(api::tls::tls_config(&config).is_err());
+}
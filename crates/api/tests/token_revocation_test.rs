@@ -0,0 +1,148 @@
+use api::{
+    middleware::auth::{generate_token, Claims},
+    test_utils::*,
+};
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use http_body_util::BodyExt;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use shared::{config::Config, types::UserRole};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn setup() -> (Router, Config) {
+    let state = create_test_app_state();
+    let config = state.config.clone();
+    (api::routes::create_router(state), config)
+}
+
+fn token_for(user_id: &str, config: &Config) -> String {
+    generate_token(
+        user_id,
+        UserRole::Analyst,
+        Some(&Uuid::new_v4().to_string()),
+        config,
+    )
+    .unwrap()
+}
+
+/// Token for `user_id` issued `secs` seconds from now
+fn token_issued_in(user_id: &str, secs: usize, config: &Config) -> String {
+    let iat = chrono::Utc::now().timestamp() as usize + secs;
+    let claims = Claims {
+        sub: user_id.to_string(),
+        role: UserRole::Analyst.to_string(),
+        org: Some(Uuid::new_v4().to_string()),
+        exp: iat + 3600,
+        iat,
+        iss: "easm-api".to_string(),
+        aud: "easm-client".to_string(),
+        jti: Uuid::new_v4().to_string(),
+        device: None,
+        scope: None,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn post(router: &Router, uri: &str, token: &str) -> Response {
+    let request = Request::builder()
+        .uri(uri)
+        .method("POST")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn list_assets(router: &Router, token: &str) -> Response {
+    let request = Request::builder()
+        .uri("/api/assets")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn assert_revoked(response: Response) {
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "TOKEN_REVOKED");
+}
+
+#[tokio::test]
+async fn test_logged_out_token_is_rejected() {
+    let (router, config) = setup();
+    let token = token_for(&Uuid::new_v4().to_string(), &config);
+
+    let response = list_assets(&router, &token).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = post(&router, "/api/auth/logout", &token).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    assert_revoked(list_assets(&router, &token).await).await;
+
+    // Logging out again is rejected the same way
+    assert_revoked(post(&router, "/api/auth/logout", &token).await).await;
+}
+
+#[tokio::test]
+async fn test_logout_keeps_other_sessions() {
+    let (router, config) = setup();
+    let user_id = Uuid::new_v4().to_string();
+    let token = token_for(&user_id, &config);
+    let other_session = token_for(&user_id, &config);
+
+    let response = post(&router, "/api/auth/logout", &token).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = list_assets(&router, &other_session).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_logout_all_revokes_every_session() {
+    let (router, config) = setup();
+    let user_id = Uuid::new_v4().to_string();
+    let token = token_for(&user_id, &config);
+    let other_session = token_for(&user_id, &config);
+    let other_user = token_for(&Uuid::new_v4().to_string(), &config);
+
+    let response = post(&router, "/api/auth/logout-all", &token).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    assert_revoked(list_assets(&router, &token).await).await;
+    assert_revoked(list_assets(&router, &other_session).await).await;
+
+    let response = list_assets(&router, &other_user).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_logout_all_accepts_later_logins() {
+    let (router, config) = setup();
+    let user_id = Uuid::new_v4().to_string();
+    let token = token_for(&user_id, &config);
+
+    let response = post(&router, "/api/auth/logout-all", &token).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let later = token_issued_in(&user_id, 2, &config);
+    let response = list_assets(&router, &later).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,321 @@
+use api::{middleware::auth::generate_token, state::AppState, test_utils::*};
+use axum::{
+    body::Body,
+    http::{header, Method, Request, Response, StatusCode},
+    Router,
+};
+use backend::{
+    models::{Organization, User},
+    services::UserServiceImpl,
+    OrganizationRepository, UserRepository,
+};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use shared::types::{UserRole, ID};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Two organizations with users, served by the real user service
+struct Setup {
+    router: Router,
+    state: AppState,
+    users: Arc<InMemoryUserRepository>,
+    acme: ID,
+    globex: ID,
+}
+
+async fn setup() -> Setup {
+    let organizations = Arc::new(InMemoryOrganizationRepository::default());
+    let acme = organizations
+        .create_organization(&Organization::new("Acme Corp".to_string()))
+        .await
+        .unwrap()
+        .id;
+    let globex = organizations
+        .create_organization(&Organization::new("Globex".to_string()))
+        .await
+        .unwrap()
+        .id;
+
+    let users = Arc::new(InMemoryUserRepository::default());
+    for (org, name, role) in [
+        (acme, "alice", UserRole::Manager),
+        (acme, "bob", UserRole::Analyst),
+        (globex, "carol", UserRole::Admin),
+        (globex, "dave", UserRole::Analyst),
+    ] {
+        add_user(&users, org, name, role).await;
+    }
+
+    let mut state = create_test_app_state();
+    state.user_service = Arc::new(UserServiceImpl::new(users.clone(), organizations));
+    Setup {
+        router: api::routes::create_router(state.clone()),
+        state,
+        users,
+        acme,
+        globex,
+    }
+}
+
+async fn add_user(users: &InMemoryUserRepository, org: ID, name: &str, role: UserRole) -> User {
+    let user = User::new(
+        org,
+        name.to_string(),
+        format!("{}@example.com", name),
+        User::hash_password("password123").unwrap(),
+        Some(role),
+    );
+    users.create_user(&user).await.unwrap()
+}
+
+async fn user_named(users: &InMemoryUserRepository, name: &str) -> User {
+    users.get_user_by_username(name).await.unwrap().unwrap()
+}
+
+/// A token for `user`, as logging in would issue
+fn token_for(state: &AppState, user: &User) -> String {
+    generate_token(
+        &user.id.to_string(),
+        user.role,
+        Some(&user.organization_id.to_string()),
+        &state.config,
+    )
+    .unwrap()
+}
+
+async fn send(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    token: &str,
+    body: Option<Value>,
+) -> Response<Body> {
+    let request = Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn json_body(response: Response<Body>) -> Value {
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn usernames(body: &Value) -> Vec<&str> {
+    body["users"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["username"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_users_is_scoped_to_callers_organization() {
+    let Setup {
+        router,
+        state,
+        users,
+        globex,
+        ..
+    } = setup().await;
+    let manager = token_for(&state, &user_named(&users, "alice").await);
+
+    let response = send(&router, Method::GET, "/api/users", &manager, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "2");
+    let body = json_body(response).await;
+    assert_eq!(usernames(&body), vec!["alice", "bob"]);
+
+    // Password hashes never leave the server
+    for user in body["users"].as_array().unwrap() {
+        assert!(user.get("password_hash").is_none());
+        assert_eq!(user["active"], true);
+    }
+
+    // A manager can't look into another organization
+    let uri = format!("/api/users?organization_id={}", globex);
+    let response = send(&router, Method::GET, &uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // An admin can
+    let admin = token_for(&state, &user_named(&users, "carol").await);
+    let response = send(&router, Method::GET, "/api/users", &admin, None).await;
+    assert_eq!(usernames(&json_body(response).await), vec!["carol", "dave"]);
+}
+
+#[tokio::test]
+async fn test_user_management_requires_manager_or_admin() {
+    let Setup {
+        router,
+        state,
+        users,
+        ..
+    } = setup().await;
+    let analyst = token_for(&state, &user_named(&users, "bob").await);
+    let alice = user_named(&users, "alice").await;
+
+    let response = send(&router, Method::GET, "/api/users", &analyst, None).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/users",
+        &analyst,
+        Some(json!({ "email": "eve@example.com", "password": "password123" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let uri = format!("/api/users/{}/deactivate", alice.id);
+    let response = send(&router, Method::POST, &uri, &analyst, None).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(user_named(&users, "alice").await.active);
+}
+
+#[tokio::test]
+async fn test_create_and_update_user() {
+    let Setup {
+        router,
+        state,
+        users,
+        acme,
+        ..
+    } = setup().await;
+    let manager = token_for(&state, &user_named(&users, "alice").await);
+
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/users",
+        &manager,
+        Some(json!({ "email": "erin@example.com", "password": "password123", "role": "READONLY" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = json_body(response).await;
+    assert!(created.get("password_hash").is_none());
+    assert_eq!(created["organization_id"], acme.to_string());
+    assert_eq!(created["role"], "READONLY");
+
+    let uri = format!("/api/users/{}", created["id"].as_str().unwrap());
+    let response = send(
+        &router,
+        Method::PUT,
+        &uri,
+        &manager,
+        Some(json!({ "role": "ANALYST", "username": "erin.s" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated = json_body(response).await;
+    assert_eq!(updated["role"], "ANALYST");
+    assert_eq!(updated["username"], "erin.s");
+
+    // An email that's taken, or a role a manager can't grant, is refused
+    let response = send(
+        &router,
+        Method::PUT,
+        &uri,
+        &manager,
+        Some(json!({ "email": "bob@example.com" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = send(
+        &router,
+        Method::PUT,
+        &uri,
+        &manager,
+        Some(json!({ "role": "ADMIN" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_managers_cannot_reach_other_organizations_users() {
+    let Setup {
+        router,
+        state,
+        users,
+        globex,
+        ..
+    } = setup().await;
+    let manager = token_for(&state, &user_named(&users, "alice").await);
+    let dave = user_named(&users, "dave").await;
+
+    let uri = format!("/api/users/{}", dave.id);
+    let response = send(&router, Method::GET, &uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let uri = format!("/api/users/{}/deactivate", dave.id);
+    let response = send(&router, Method::POST, &uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send(
+        &router,
+        Method::POST,
+        "/api/users",
+        &manager,
+        Some(json!({
+            "email": "mallory@example.com",
+            "password": "password123",
+            "organization_id": globex,
+        })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_deactivated_user_cannot_log_in_or_use_tokens() {
+    let Setup {
+        router,
+        state,
+        users,
+        ..
+    } = setup().await;
+    let alice = user_named(&users, "alice").await;
+    let manager = token_for(&state, &alice);
+    let bob = user_named(&users, "bob").await;
+    let bob_token = token_for(&state, &bob);
+
+    let uri = format!("/api/users/{}/deactivate", bob.id);
+    let response = send(&router, Method::POST, &uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await["active"], false);
+
+    // Tokens issued before deactivation stop working
+    let response = send(&router, Method::GET, "/api/assets", &bob_token, None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let login = Request::builder()
+        .uri("/api/auth/login")
+        .method("POST")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "email": "bob@example.com", "password": "password123" }).to_string(),
+        ))
+        .unwrap();
+    let response = router.clone().oneshot(login).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Managers can't deactivate themselves
+    let uri = format!("/api/users/{}/deactivate", alice.id);
+    let response = send(&router, Method::POST, &uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Reactivating restores access
+    let uri = format!("/api/users/{}/activate", bob.id);
+    let response = send(&router, Method::POST, &uri, &manager, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(user_named(&users, "bob").await.active);
+}
@@ -1,10 +1,23 @@
-use api::test_utils::*;
+use api::{middleware::auth::generate_token, test_utils::*};
 use axum::{
     body::Body,
     http::{header, Request, StatusCode},
+    Router,
+};
+use backend::{
+    models::Vulnerability,
+    services::{AssetServiceImpl, VulnerabilityServiceImpl},
 };
 use http_body_util::BodyExt;
+use infrastructure::{
+    database::migrations::Migrator,
+    repositories::RepositoryFactory,
+    utils::testing::{create_test_asset, create_test_organization},
+};
 use serde_json::json;
+use shared::types::{AssetType, Severity, UserRole};
+use sqlx::PgPool;
+use std::sync::Arc;
 use tower::ServiceExt;
 use uuid::Uuid;
 
@@ -74,10 +87,8 @@ async fn test_create_vulnerability() {
     // Send the request to the router
     let response = router.oneshot(request).await.unwrap();
 
-    // Check that the response has a 422 Unprocessable Entity status
-    // Our implementation doesn't use unimplemented! so it would actually return 201 Created
-    // in a production environment
-    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    // Severities are uppercase, so "High" is rejected before reaching the service
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -151,10 +162,9 @@ async fn test_update_vulnerability() {
     // Send the request to the router
     let response = router.oneshot(request).await.unwrap();
 
-    // Check that the response has a 422 Unprocessable Entity status
-    // Our implementation doesn't use unimplemented! so it would actually return 200 OK
-    // in a production environment
-    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    // Severities and statuses are uppercase, so "Critical" is rejected before
+    // reaching the service
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -180,3 +190,353 @@ async fn test_delete_vulnerability() {
     // Check that the response has a 204 No Content status
     assert_eq!(response.status(), StatusCode::NO_CONTENT);
 }
+
+#[tokio::test]
+async fn test_group_vulnerabilities() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri(format!(
+            "/api/vulnerabilities/groups?organization_id={}",
+            Uuid::new_v4()
+        ))
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let groups = body["groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["cve_id"], "CVE-2023-1234");
+    assert_eq!(groups[0]["affected_asset_count"], 2);
+    assert_eq!(groups[0]["highest_severity"], "HIGH");
+}
+
+#[tokio::test]
+async fn test_bulk_update_vulnerability_status() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/vulnerabilities/bulk")
+        .method("PATCH")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "ids": [Uuid::new_v4(), Uuid::new_v4()],
+                "status": "CLOSED"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["updated"], 2);
+}
+
+#[tokio::test]
+async fn test_bulk_update_vulnerability_status_requires_ids() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let request = Request::builder()
+        .uri("/api/vulnerabilities/bulk")
+        .method("PATCH")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "ids": [], "status": "CLOSED" }).to_string(),
+        ))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_unknown_vulnerability_status_lists_allowed_values() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    let payload = json!({ "ids": [Uuid::new_v4()], "status": "FIXED" });
+    let request = Request::builder()
+        .uri("/api/vulnerabilities/bulk")
+        .method("PATCH")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(message.contains("unknown variant `FIXED`"), "{message}");
+    assert!(
+        message.contains("`OPEN`, `INPROGRESS`, `CLOSED`, `ACCEPTEDRISK`, `FALSEPOSITIVE`"),
+        "{message}"
+    );
+
+    // Severity filters are checked the same way
+    let request = Request::builder()
+        .uri("/api/vulnerabilities?severity=SEVERE")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("`INFO`, `LOW`, `MEDIUM`, `HIGH`, `CRITICAL`"),
+        "{message}"
+    );
+}
+
+// Helper to list vulnerabilities and return their titles in response order
+async fn list_titles(router: &Router, token: &str, uri: &str) -> Vec<String> {
+    let request = Request::builder()
+        .uri(uri)
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    body["vulnerabilities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_list_vulnerabilities_sorted_by_severity_desc(pool: PgPool) {
+    Migrator::new(pool.clone()).run_migrations().await.unwrap();
+
+    let factory = RepositoryFactory::new(pool.clone());
+    let org = create_test_organization(&factory, "Sorted Org")
+        .await
+        .unwrap();
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "sorted.example.com")
+        .await
+        .unwrap();
+
+    // Titles that sort differently from severity, which the database stores
+    // as text
+    for (title, severity) in [
+        ("a-low", Severity::Low),
+        ("b-critical", Severity::Critical),
+        ("c-medium", Severity::Medium),
+        ("d-high", Severity::High),
+        ("e-info", Severity::Info),
+    ] {
+        let vulnerability = Vulnerability::new(
+            asset.id,
+            None,
+            title.to_string(),
+            None,
+            severity,
+            None,
+            None,
+            None,
+        );
+        factory
+            .vulnerability_repository()
+            .create_vulnerability(&vulnerability)
+            .await
+            .unwrap();
+    }
+
+    let mut state = create_test_app_state();
+    state.vulnerability_service = Arc::new(VulnerabilityServiceImpl::new(
+        factory.vulnerability_repository(),
+        factory.asset_repository(),
+    ));
+    let router = api::routes::create_router(state);
+    let token = authenticate_test_user(&router).await;
+
+    let uri = format!(
+        "/api/vulnerabilities?asset_id={}&sort_by=severity&sort_order=desc",
+        asset.id
+    );
+    assert_eq!(
+        list_titles(&router, &token, &uri).await,
+        vec!["b-critical", "d-high", "c-medium", "a-low", "e-info"]
+    );
+
+    // Pages of the sorted list follow on from each other
+    assert_eq!(
+        list_titles(&router, &token, &format!("{}&limit=2&offset=2", uri)).await,
+        vec!["c-medium", "a-low"]
+    );
+
+    // Ascending is the default order
+    let uri = format!(
+        "/api/vulnerabilities?asset_id={}&sort_by=severity",
+        asset.id
+    );
+    assert_eq!(
+        list_titles(&router, &token, &uri).await,
+        vec!["e-info", "a-low", "c-medium", "d-high", "b-critical"]
+    );
+}
+
+#[tokio::test]
+async fn test_list_vulnerabilities_rejects_unknown_sort() {
+    let router = api::routes::create_router(create_test_app_state());
+    let token = authenticate_test_user(&router).await;
+
+    // Only allowlisted fields are accepted, so nothing else reaches the query
+    let request = Request::builder()
+        .uri("/api/vulnerabilities?sort_by=title;DROP%20TABLE%20vulnerabilities")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("`severity`, `status`, `created_at`"),
+        "{message}"
+    );
+
+    // A direction needs a field to sort on
+    let request = Request::builder()
+        .uri("/api/vulnerabilities?sort_order=desc")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_correlation_graph_links_assets_sharing_cve(pool: PgPool) {
+    Migrator::new(pool.clone()).run_migrations().await.unwrap();
+
+    let factory = RepositoryFactory::new(pool.clone());
+    let org = create_test_organization(&factory, "Graph Org")
+        .await
+        .unwrap();
+    let mut assets = Vec::new();
+    for value in ["a.graph.com", "b.graph.com", "c.graph.com"] {
+        assets.push(
+            create_test_asset(&factory, org.id, AssetType::Domain, value)
+                .await
+                .unwrap(),
+        );
+    }
+
+    // The first two assets share a CVE, the third only has an unrelated finding
+    for (asset, title, cve_id) in [
+        (&assets[0], "Path traversal", Some("CVE-2021-41773")),
+        (&assets[1], "Path traversal", Some("CVE-2021-41773")),
+        (&assets[2], "Directory listing", None),
+    ] {
+        let vulnerability = Vulnerability::new(
+            asset.id,
+            None,
+            title.to_string(),
+            None,
+            Severity::Critical,
+            cve_id.map(str::to_string),
+            None,
+            None,
+        );
+        factory
+            .vulnerability_repository()
+            .create_vulnerability(&vulnerability)
+            .await
+            .unwrap();
+    }
+
+    let mut state = create_test_app_state();
+    state.vulnerability_service = Arc::new(VulnerabilityServiceImpl::new(
+        factory.vulnerability_repository(),
+        factory.asset_repository(),
+    ));
+    state.asset_service = Arc::new(AssetServiceImpl::new(
+        factory.asset_repository(),
+        factory.asset_history_repository(),
+    ));
+    let token = generate_token(
+        &Uuid::new_v4().to_string(),
+        UserRole::Analyst,
+        Some(&org.id.to_string()),
+        &state.config,
+    )
+    .unwrap();
+    let router = api::routes::create_router(state);
+
+    let request = Request::builder()
+        .uri("/api/vulnerabilities/correlation-graph")
+        .method("GET")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let nodes = body["nodes"].as_array().unwrap();
+    assert_eq!(
+        nodes.iter().filter(|n| n["kind"] == "asset").count(),
+        3,
+        "{body}"
+    );
+    assert_eq!(
+        nodes
+            .iter()
+            .filter(|n| n["kind"] == "vulnerability")
+            .count(),
+        2,
+        "{body}"
+    );
+
+    let shared: Vec<_> = body["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["kind"] != "affects")
+        .collect();
+    assert_eq!(shared.len(), 1, "{body}");
+    let mut endpoints = [
+        shared[0]["source"].as_str().unwrap().to_string(),
+        shared[0]["target"].as_str().unwrap().to_string(),
+    ];
+    endpoints.sort();
+    let mut expected = [assets[0].id.to_string(), assets[1].id.to_string()];
+    expected.sort();
+    assert_eq!(endpoints, expected);
+    assert_eq!(shared[0]["kind"], "shared_cve");
+    assert_eq!(shared[0]["label"], "CVE-2021-41773");
+}
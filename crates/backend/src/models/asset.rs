@@ -1,6 +1,5 @@
 use serde::{Deserialize, Serialize};
 use shared::types::{AssetStatus, AssetType, Timestamp, ID};
-use std::collections::HashMap;
 use std::str::FromStr;
 
 /// Asset model representing internet-facing assets
@@ -35,6 +34,11 @@ pub struct Asset {
 
     /// Additional attributes specific to asset type
     pub attributes: serde_json::Value,
+
+    /// Risk score from 0 to 100, kept up to date by the asset and
+    /// vulnerability services (see `services::risk_score`)
+    #[serde(default)]
+    pub risk_score: f32,
 }
 
 impl Asset {
@@ -62,6 +66,7 @@ impl Asset {
             updated_at: now,
             attributes: attributes
                 .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new())),
+            risk_score: 0.0,
         }
     }
 
@@ -70,73 +75,32 @@ impl Asset {
         AssetBuilder::new(organization_id, asset_type, value)
     }
 
-    /// Get asset relationship type from attributes
-    pub fn get_relationships(&self) -> HashMap<String, Vec<ID>> {
-        if let Some(relationships) = self.attributes.get("relationships") {
-            if let Ok(parsed) =
-                serde_json::from_value::<HashMap<String, Vec<ID>>>(relationships.clone())
-            {
-                return parsed;
-            }
-        }
-        HashMap::new()
+    /// Entries of the asset's `ports` attribute recorded as open, or with no
+    /// status at all
+    pub fn open_port_attributes(&self) -> Vec<&serde_json::Value> {
+        self.attributes
+            .get("ports")
+            .and_then(|ports| ports.as_array())
+            .map(|ports| {
+                ports
+                    .iter()
+                    .filter(|port| {
+                        port.get("status")
+                            .and_then(|status| status.as_str())
+                            .is_none_or(|status| status.eq_ignore_ascii_case("open"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
-    /// Add a relationship to another asset
-    pub fn add_relationship(&mut self, relationship_type: &str, related_asset_id: ID) -> &mut Self {
-        let mut relationships = self.get_relationships();
-        relationships
-            .entry(relationship_type.to_string())
-            .or_default()
-            .push(related_asset_id);
-
-        // Update the attributes with the new relationships
-        let mut attributes = if let serde_json::Value::Object(map) = &self.attributes {
-            map.clone()
-        } else {
-            serde_json::Map::new()
-        };
-
-        attributes.insert(
-            "relationships".to_string(),
-            serde_json::to_value(relationships)
-                .unwrap_or(serde_json::Value::Object(serde_json::Map::new())),
-        );
-
-        self.attributes = serde_json::Value::Object(attributes);
-        self
-    }
-
-    /// Remove a relationship to another asset
-    pub fn remove_relationship(
-        &mut self,
-        relationship_type: &str,
-        related_asset_id: ID,
-    ) -> &mut Self {
-        let mut relationships = self.get_relationships();
-
-        if let Some(assets) = relationships.get_mut(relationship_type) {
-            assets.retain(|id| *id != related_asset_id);
-            if assets.is_empty() {
-                relationships.remove(relationship_type);
-            }
-        }
-
-        // Update the attributes with the new relationships
-        let mut attributes = if let serde_json::Value::Object(map) = &self.attributes {
-            map.clone()
-        } else {
-            serde_json::Map::new()
-        };
-
-        attributes.insert(
-            "relationships".to_string(),
-            serde_json::to_value(relationships)
-                .unwrap_or(serde_json::Value::Object(serde_json::Map::new())),
-        );
-
-        self.attributes = serde_json::Value::Object(attributes);
-        self
+    /// Port numbers recorded as open in the asset's `ports` attribute
+    pub fn open_ports(&self) -> Vec<u16> {
+        self.open_port_attributes()
+            .into_iter()
+            .filter_map(|port| port.get("port").and_then(|p| p.as_u64()))
+            .filter_map(|p| u16::try_from(p).ok())
+            .collect()
     }
 }
 
@@ -209,6 +173,7 @@ impl AssetBuilder {
             created_at: now,
             updated_at: now,
             attributes: self.attributes,
+            risk_score: 0.0,
         }
     }
 }
@@ -319,3 +284,25 @@ impl AssetRelationship {
         }
     }
 }
+
+/// Criteria for listing assets; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssetFilter {
+    pub organization_id: Option<ID>,
+    pub asset_type: Option<AssetType>,
+    pub status: Option<AssetStatus>,
+    /// Only assets carrying this tag
+    pub tag: Option<String>,
+    /// Include soft-deleted assets
+    pub include_deleted: bool,
+}
+
+/// Which end of its relationships an asset is looked up by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationshipDirection {
+    /// Relationships the asset is the source of
+    Outgoing,
+    /// Relationships pointing at the asset
+    Incoming,
+}
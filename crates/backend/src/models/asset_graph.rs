@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use shared::types::{AssetType, ID};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::Asset;
+
+/// An asset in the relationship graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssetGraphNode {
+    /// Asset ID
+    pub id: ID,
+
+    /// Type of asset
+    pub asset_type: AssetType,
+
+    /// The asset identifier (domain, IP, etc.)
+    pub value: String,
+}
+
+/// A directed relationship between two assets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssetGraphEdge {
+    /// Asset the relationship starts from
+    pub source: ID,
+
+    /// Asset the relationship points to
+    pub target: ID,
+
+    /// Type of relationship, e.g. `subdomain`
+    pub relationship_type: String,
+}
+
+/// Assets and the relationships between them, for visualization
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct AssetGraph {
+    pub nodes: Vec<AssetGraphNode>,
+    pub edges: Vec<AssetGraphEdge>,
+}
+
+impl AssetGraph {
+    /// Build a graph from assets and `(source, target, relationship_type)`
+    /// triples. Relationships with an endpoint missing from `assets` are left out.
+    pub fn new(assets: &[Asset], relationships: &[(ID, ID, String)]) -> Self {
+        let mut seen = HashSet::new();
+        let nodes: Vec<AssetGraphNode> = assets
+            .iter()
+            .filter(|asset| seen.insert(asset.id))
+            .map(|asset| AssetGraphNode {
+                id: asset.id,
+                asset_type: asset.asset_type,
+                value: asset.value.clone(),
+            })
+            .collect();
+
+        let edges = relationships
+            .iter()
+            .filter(|(source, target, _)| seen.contains(source) && seen.contains(target))
+            .map(|(source, target, relationship_type)| AssetGraphEdge {
+                source: *source,
+                target: *target,
+                relationship_type: relationship_type.clone(),
+            })
+            .collect();
+
+        Self { nodes, edges }
+    }
+
+    /// Render the graph in Graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph assets {\n");
+
+        for node in &self.nodes {
+            let asset_type = serde_json::to_value(node.asset_type)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\", asset_type=\"{}\"];",
+                node.id,
+                escape_dot(&node.value),
+                asset_type
+            );
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                edge.source,
+                edge.target,
+                escape_dot(&edge.relationship_type)
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escape a value for use inside a double-quoted DOT string
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
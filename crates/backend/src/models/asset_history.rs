@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use shared::types::{Timestamp, ID};
+
+use super::Asset;
+
+/// A single recorded change to one field of an asset
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssetHistory {
+    /// Unique identifier
+    pub id: ID,
+
+    /// Asset that changed
+    pub asset_id: ID,
+
+    /// Changed field, e.g. `status` or `attributes.open_ports`
+    pub field: String,
+
+    /// Value before the change (null if the field was added)
+    pub old_value: serde_json::Value,
+
+    /// Value after the change (null if the field was removed)
+    pub new_value: serde_json::Value,
+
+    /// When the change was recorded
+    pub changed_at: Timestamp,
+}
+
+impl AssetHistory {
+    /// Create a new history entry
+    pub fn new(
+        asset_id: ID,
+        field: String,
+        old_value: serde_json::Value,
+        new_value: serde_json::Value,
+        changed_at: Timestamp,
+    ) -> Self {
+        use uuid::Uuid;
+
+        Self {
+            id: Uuid::new_v4(),
+            asset_id,
+            field,
+            old_value,
+            new_value,
+            changed_at,
+        }
+    }
+
+    /// Compare two versions of an asset and return one entry per changed field.
+    /// Attributes are compared key by key so e.g. a bumped technology version
+    /// shows up as its own change; bookkeeping timestamps are ignored.
+    pub fn diff(old: &Asset, new: &Asset, changed_at: Timestamp) -> Vec<AssetHistory> {
+        let mut changes = Vec::new();
+        let mut record = |field: String, old_value: serde_json::Value, new_value| {
+            if old_value != new_value {
+                changes.push(AssetHistory::new(
+                    new.id, field, old_value, new_value, changed_at,
+                ));
+            }
+        };
+
+        record(
+            "asset_type".to_string(),
+            serde_json::json!(old.asset_type),
+            serde_json::json!(new.asset_type),
+        );
+        record(
+            "value".to_string(),
+            serde_json::json!(old.value),
+            serde_json::json!(new.value),
+        );
+        record(
+            "status".to_string(),
+            serde_json::json!(old.status),
+            serde_json::json!(new.status),
+        );
+
+        match (old.attributes.as_object(), new.attributes.as_object()) {
+            (Some(old_attributes), Some(new_attributes)) => {
+                let mut keys: Vec<&String> =
+                    old_attributes.keys().chain(new_attributes.keys()).collect();
+                keys.sort();
+                keys.dedup();
+
+                for key in keys {
+                    record(
+                        format!("attributes.{}", key),
+                        old_attributes
+                            .get(key)
+                            .cloned()
+                            .unwrap_or(serde_json::Value::Null),
+                        new_attributes
+                            .get(key)
+                            .cloned()
+                            .unwrap_or(serde_json::Value::Null),
+                    );
+                }
+            }
+            _ => record(
+                "attributes".to_string(),
+                old.attributes.clone(),
+                new.attributes.clone(),
+            ),
+        }
+
+        changes
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::types::{AuditAction, Timestamp, ID};
+use uuid::Uuid;
+
+/// A change a user made through the API, kept for compliance
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditLogEntry {
+    /// Unique identifier
+    pub id: ID,
+
+    /// User who made the change
+    pub user_id: ID,
+
+    /// Organization the user was acting for, if their token names one
+    pub organization_id: Option<ID>,
+
+    /// What was done to the resource
+    pub action: AuditAction,
+
+    /// Kind of resource changed, e.g. `asset` or `vulnerability`
+    pub resource_type: String,
+
+    /// Resource changed, unless the change covered several at once
+    pub resource_id: Option<ID>,
+
+    /// Address the request came from, if known
+    pub ip_address: Option<String>,
+
+    /// When the change was made
+    pub created_at: Timestamp,
+}
+
+impl AuditLogEntry {
+    /// Create a new entry timestamped now
+    pub fn new(
+        user_id: ID,
+        organization_id: Option<ID>,
+        action: AuditAction,
+        resource_type: String,
+        resource_id: Option<ID>,
+        ip_address: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            organization_id,
+            action,
+            resource_type,
+            resource_id,
+            ip_address,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Criteria for listing audit log entries; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditLogFilter {
+    pub organization_id: Option<ID>,
+    pub user_id: Option<ID>,
+    pub action: Option<AuditAction>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<ID>,
+    /// Earliest entry to include
+    pub since: Option<Timestamp>,
+    /// Latest entry to include
+    pub until: Option<Timestamp>,
+}
+
+impl AuditLogFilter {
+    /// Whether `entry` meets every criterion that is set
+    pub fn matches(&self, entry: &AuditLogEntry) -> bool {
+        self.organization_id
+            .is_none_or(|id| entry.organization_id == Some(id))
+            && self.user_id.is_none_or(|id| entry.user_id == id)
+            && self.action.is_none_or(|action| entry.action == action)
+            && self
+                .resource_type
+                .as_ref()
+                .is_none_or(|resource_type| &entry.resource_type == resource_type)
+            && self
+                .resource_id
+                .is_none_or(|id| entry.resource_id == Some(id))
+            && self.since.is_none_or(|since| entry.created_at >= since)
+            && self.until.is_none_or(|until| entry.created_at <= until)
+    }
+}
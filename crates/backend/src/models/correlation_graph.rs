@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use shared::types::{Severity, ID};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::{Asset, VulnerabilityGroup};
+
+/// What a [`CorrelationGraphNode`] stands for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrelationNodeKind {
+    /// A group of open vulnerabilities sharing a CVE or title
+    Vulnerability,
+    Asset,
+}
+
+/// Why two nodes of a [`CorrelationGraph`] are connected
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrelationEdgeKind {
+    /// The vulnerability was found on the asset
+    Affects,
+    SharedCve,
+    SharedTechnology,
+    /// The assets share a vulnerability with no CVE, matched by title
+    SharedTitle,
+}
+
+/// A vulnerability group or an asset in the correlation graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CorrelationGraphNode {
+    /// Asset ID, or the CVE (or title) and technology of a vulnerability group
+    pub id: String,
+
+    pub kind: CorrelationNodeKind,
+
+    /// Asset value or vulnerability title
+    pub label: String,
+
+    /// Highest severity in a vulnerability group, none for assets
+    pub severity: Option<Severity>,
+}
+
+/// An undirected link between two nodes of the correlation graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CorrelationGraphEdge {
+    pub source: String,
+
+    pub target: String,
+
+    pub kind: CorrelationEdgeKind,
+
+    /// The CVE, technology or title the nodes share, or the vulnerability
+    /// title for `affects` edges
+    pub label: String,
+}
+
+/// Open vulnerabilities and the assets they connect, for a force-directed
+/// chart
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CorrelationGraph {
+    pub nodes: Vec<CorrelationGraphNode>,
+    pub edges: Vec<CorrelationGraphEdge>,
+}
+
+impl CorrelationGraph {
+    /// Build a graph from vulnerability groups and the assets they were found
+    /// on. Each group links to its assets, and assets in the same group are
+    /// linked by the CVE or title they share. Assets are also linked when
+    /// groups record the same affected technology on them. Assets missing
+    /// from `assets` are left out.
+    pub fn new(groups: &[VulnerabilityGroup], assets: &[Asset]) -> Self {
+        let assets: HashMap<ID, &Asset> = assets.iter().map(|asset| (asset.id, asset)).collect();
+        let mut graph = Self::default();
+        let mut asset_nodes = HashSet::new();
+        let mut seen_edges = HashSet::new();
+        let mut by_technology: BTreeMap<&str, BTreeSet<ID>> = BTreeMap::new();
+
+        for group in groups {
+            let group_id = format!(
+                "{}@{}",
+                group.cve_id.as_deref().unwrap_or(&group.title),
+                group.technology.as_deref().unwrap_or_default()
+            );
+            let members: BTreeSet<ID> = group
+                .asset_ids
+                .iter()
+                .copied()
+                .filter(|id| assets.contains_key(id))
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+
+            graph.nodes.push(CorrelationGraphNode {
+                id: group_id.clone(),
+                kind: CorrelationNodeKind::Vulnerability,
+                label: group.title.clone(),
+                severity: Some(group.highest_severity),
+            });
+
+            for asset_id in &members {
+                if asset_nodes.insert(*asset_id) {
+                    graph.nodes.push(CorrelationGraphNode {
+                        id: asset_id.to_string(),
+                        kind: CorrelationNodeKind::Asset,
+                        label: assets[asset_id].value.clone(),
+                        severity: None,
+                    });
+                }
+                graph.push_edge(
+                    &mut seen_edges,
+                    group_id.clone(),
+                    asset_id.to_string(),
+                    CorrelationEdgeKind::Affects,
+                    &group.title,
+                );
+            }
+
+            let (kind, label) = match &group.cve_id {
+                Some(cve_id) => (CorrelationEdgeKind::SharedCve, cve_id),
+                None => (CorrelationEdgeKind::SharedTitle, &group.title),
+            };
+            graph.link_assets(&mut seen_edges, &members, kind, label);
+
+            if let Some(technology) = &group.technology {
+                by_technology
+                    .entry(technology)
+                    .or_default()
+                    .extend(&members);
+            }
+        }
+
+        for (technology, members) in by_technology {
+            graph.link_assets(
+                &mut seen_edges,
+                &members,
+                CorrelationEdgeKind::SharedTechnology,
+                technology,
+            );
+        }
+
+        graph
+    }
+
+    /// Link every pair of `members`
+    fn link_assets(
+        &mut self,
+        seen: &mut HashSet<(String, String, CorrelationEdgeKind, String)>,
+        members: &BTreeSet<ID>,
+        kind: CorrelationEdgeKind,
+        label: &str,
+    ) {
+        for (i, source) in members.iter().enumerate() {
+            for target in members.iter().skip(i + 1) {
+                self.push_edge(seen, source.to_string(), target.to_string(), kind, label);
+            }
+        }
+    }
+
+    /// Add an edge unless an identical one is already in the graph
+    fn push_edge(
+        &mut self,
+        seen: &mut HashSet<(String, String, CorrelationEdgeKind, String)>,
+        source: String,
+        target: String,
+        kind: CorrelationEdgeKind,
+        label: &str,
+    ) {
+        if seen.insert((source.clone(), target.clone(), kind, label.to_string())) {
+            self.edges.push(CorrelationGraphEdge {
+                source,
+                target,
+                kind,
+                label: label.to_string(),
+            });
+        }
+    }
+}
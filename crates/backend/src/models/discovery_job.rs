@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use shared::types::{JobStatus, JobType, Timestamp, ID};
 
+/// How long an incremental job treats a target's last scan as fresh when
+/// its configuration doesn't say
+pub const DEFAULT_FRESHNESS_WINDOW_HOURS: i64 = 24;
+
 /// Discovery Job model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveryJob {
@@ -38,6 +42,23 @@ pub struct DiscoveryJob {
     pub configuration: serde_json::Value,
 }
 
+/// What a discovery job found: the assets linked to it, and the ports,
+/// technologies and vulnerabilities on those assets
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct JobResultSummary {
+    /// Assets the job discovered or updated
+    pub assets: usize,
+
+    /// Ports on those assets
+    pub ports: usize,
+
+    /// Technologies detected on those assets
+    pub technologies: usize,
+
+    /// Vulnerabilities on those assets
+    pub vulnerabilities: usize,
+}
+
 impl DiscoveryJob {
     /// Create a new discovery job
     pub fn new(
@@ -66,4 +87,90 @@ impl DiscoveryJob {
                 .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new())),
         }
     }
+
+    /// Whether the job only scans targets not scanned within its freshness
+    /// window, set by `"incremental": true` in its configuration
+    pub fn incremental(&self) -> bool {
+        self.configuration
+            .get("incremental")
+            .and_then(|incremental| incremental.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Whether the job only works out what it would scan, without probing
+    /// anything, set by `"dry_run": true` in its configuration
+    pub fn dry_run(&self) -> bool {
+        self.configuration
+            .get("dry_run")
+            .and_then(|dry_run| dry_run.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Schedule that enqueued the job, from `schedule_id` in its
+    /// configuration; `None` for a job started by hand
+    pub fn schedule_id(&self) -> Option<ID> {
+        self.configuration
+            .get("schedule_id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| id.parse().ok())
+    }
+
+    /// How recent a target's last scan must be for an incremental job to
+    /// skip it, from `freshness_window_hours` in its configuration
+    pub fn freshness_window(&self) -> chrono::Duration {
+        let hours = self
+            .configuration
+            .get("freshness_window_hours")
+            .and_then(|hours| hours.as_i64())
+            .filter(|hours| *hours >= 0)
+            .unwrap_or(DEFAULT_FRESHNESS_WINDOW_HOURS);
+        chrono::Duration::hours(hours)
+    }
+
+    /// How long the job ran, from `started_at` until `completed_at`, or
+    /// until `now` while it's still running. `None` before it starts.
+    pub fn duration_at(&self, now: Timestamp) -> Option<chrono::Duration> {
+        let started_at = self.started_at?;
+        Some(self.completed_at.unwrap_or(now) - started_at)
+    }
+
+    /// How long the job ran, up to now while it's still running
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        self.duration_at(chrono::Utc::now())
+    }
+
+    /// Ports to scan, from `ports` in its configuration; `None` scans the
+    /// scanner's common ports
+    pub fn ports(&self) -> Option<Vec<u16>> {
+        self.configuration
+            .get("ports")
+            .and_then(|ports| serde_json::from_value(ports.clone()).ok())
+    }
+}
+
+/// Criteria for listing discovery jobs; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiscoveryJobFilter {
+    pub organization_id: Option<ID>,
+    /// Jobs in any of these statuses, or in any status when empty
+    pub statuses: Vec<JobStatus>,
+    pub job_type: Option<JobType>,
+    /// Earliest creation time to include
+    pub since: Option<Timestamp>,
+    /// Latest creation time to include
+    pub until: Option<Timestamp>,
+}
+
+impl DiscoveryJobFilter {
+    /// Whether `job` meets every criterion that is set
+    pub fn matches(&self, job: &DiscoveryJob) -> bool {
+        self.organization_id
+            .is_none_or(|id| job.organization_id == id)
+            && (self.statuses.is_empty() || self.statuses.contains(&job.status))
+            && self
+                .job_type
+                .is_none_or(|job_type| job.job_type == job_type)
+            && self.since.is_none_or(|since| job.created_at >= since)
+            && self.until.is_none_or(|until| job.created_at <= until)
+    }
 }
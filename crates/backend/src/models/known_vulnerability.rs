@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use shared::types::Severity;
+use std::cmp::Ordering;
+
+/// Versions of a product affected by a vulnerability. The bounds mirror those
+/// of an NVD CPE match, and all that are set must hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedVersions {
+    /// The one affected version, when the match names a single release
+    #[serde(default)]
+    pub version: Option<String>,
+
+    #[serde(default)]
+    pub version_start_including: Option<String>,
+
+    #[serde(default)]
+    pub version_start_excluding: Option<String>,
+
+    #[serde(default)]
+    pub version_end_including: Option<String>,
+
+    #[serde(default)]
+    pub version_end_excluding: Option<String>,
+}
+
+impl AffectedVersions {
+    /// Whether `version` falls within these bounds
+    pub fn contains(&self, version: &str) -> bool {
+        let bound = |other: &Option<String>, accept: fn(Ordering) -> bool| {
+            other
+                .as_deref()
+                .is_none_or(|other| accept(compare_versions(version, other)))
+        };
+
+        bound(&self.version, Ordering::is_eq)
+            && bound(&self.version_start_including, Ordering::is_ge)
+            && bound(&self.version_start_excluding, Ordering::is_gt)
+            && bound(&self.version_end_including, Ordering::is_le)
+            && bound(&self.version_end_excluding, Ordering::is_lt)
+    }
+}
+
+/// A published vulnerability and the versions of the product it affects
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KnownVulnerability {
+    /// CVE identifier
+    pub cve_id: String,
+
+    /// Short title
+    pub title: String,
+
+    /// Detailed description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Severity level
+    pub severity: Severity,
+
+    /// CVSS base score
+    #[serde(default)]
+    pub cvss_score: Option<f64>,
+
+    /// Recommended remediation steps
+    #[serde(default)]
+    pub remediation: Option<String>,
+
+    /// CPE 2.3 name of the affected product, e.g.
+    /// `cpe:2.3:a:apache:http_server`
+    pub cpe: String,
+
+    /// Other names the product is detected under, e.g. `Apache` for the
+    /// Apache HTTP Server
+    #[serde(default)]
+    pub aliases: Vec<String>,
+
+    /// Affected versions, any of which match
+    pub affected: Vec<AffectedVersions>,
+}
+
+impl KnownVulnerability {
+    /// Product part of the CPE name, e.g. `http_server`
+    pub fn product(&self) -> Option<&str> {
+        self.cpe
+            .split(':')
+            .nth(4)
+            .filter(|product| !product.is_empty() && *product != "*")
+    }
+
+    /// Whether `version` of the technology detected as `name` is affected.
+    /// Names are matched against the CPE product and the aliases, ignoring
+    /// case and separators.
+    pub fn affects(&self, name: &str, version: &str) -> bool {
+        let name = normalize_name(name);
+        let known_name = self
+            .product()
+            .into_iter()
+            .chain(self.aliases.iter().map(String::as_str))
+            .any(|candidate| normalize_name(candidate) == name);
+
+        known_name && self.affected.iter().any(|range| range.contains(version))
+    }
+}
+
+/// Lowercase a product name and treat `_`, `-` and runs of spaces alike
+fn normalize_name(name: &str) -> String {
+    name.split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Compare dotted version strings part by part, numerically where both
+/// parts are numbers. Missing trailing parts count as zero, so `2.4` and
+/// `2.4.0` are the same version.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |version: &str| -> Vec<String> {
+        version
+            .trim()
+            .split(['.', '-', '_'])
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).map(String::as_str).unwrap_or("0");
+        let y = b.get(i).map(String::as_str).unwrap_or("0");
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
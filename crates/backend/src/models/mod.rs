@@ -1,17 +1,38 @@
 mod asset;
+mod asset_graph;
+mod asset_history;
+mod audit_log;
+mod correlation_graph;
 mod discovery_job;
 mod job_asset_link;
+mod known_vulnerability;
 mod organization;
 mod port;
+mod scan_profile;
+mod scan_schedule;
 mod technology;
 mod user;
 mod vulnerability;
 
-pub use asset::{Asset, AssetRelationship, AssetRelationshipType};
-pub use discovery_job::DiscoveryJob;
+pub use asset::{
+    Asset, AssetFilter, AssetRelationship, AssetRelationshipType, RelationshipDirection,
+};
+pub use asset_graph::{AssetGraph, AssetGraphEdge, AssetGraphNode};
+pub use asset_history::AssetHistory;
+pub use audit_log::{AuditLogEntry, AuditLogFilter};
+pub use correlation_graph::{
+    CorrelationEdgeKind, CorrelationGraph, CorrelationGraphEdge, CorrelationGraphNode,
+    CorrelationNodeKind,
+};
+pub use discovery_job::{
+    DiscoveryJob, DiscoveryJobFilter, JobResultSummary, DEFAULT_FRESHNESS_WINDOW_HOURS,
+};
 pub use job_asset_link::JobAssetLink;
+pub use known_vulnerability::{compare_versions, AffectedVersions, KnownVulnerability};
 pub use organization::Organization;
 pub use port::Port;
-pub use technology::Technology;
-pub use user::User;
-pub use vulnerability::Vulnerability;
+pub use scan_profile::{ScanProfile, MAX_SCAN_DEPTH};
+pub use scan_schedule::ScanSchedule;
+pub use technology::{DetectedTechnology, Technology, TechnologyCount, TechnologyDistribution};
+pub use user::{User, MIN_PASSWORD_LENGTH};
+pub use vulnerability::{Vulnerability, VulnerabilityActivity, VulnerabilityGroup};
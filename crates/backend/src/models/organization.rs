@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use shared::types::{Timestamp, ID};
 
+use crate::traits::NotificationPeriod;
+
 /// Organization model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Organization {
@@ -10,6 +12,17 @@ pub struct Organization {
     /// Organization name
     pub name: String,
 
+    /// How often the organization is sent a summary report
+    pub notification_period: NotificationPeriod,
+
+    /// When the last summary report was sent
+    pub last_report_sent_at: Option<Timestamp>,
+
+    /// Domains, IPs and CIDR ranges scans are restricted to. Empty allows
+    /// any target that isn't globally blocked.
+    #[serde(default)]
+    pub scan_scope: Vec<String>,
+
     /// Creation timestamp
     pub created_at: Timestamp,
 
@@ -28,8 +41,18 @@ impl Organization {
         Self {
             id: Uuid::new_v4(),
             name,
+            notification_period: NotificationPeriod::default(),
+            last_report_sent_at: None,
+            scan_scope: Vec::new(),
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// Whether a summary report should be sent at `now`, a full period
+    /// after the last one
+    pub fn is_summary_report_due(&self, now: Timestamp) -> bool {
+        self.last_report_sent_at
+            .is_none_or(|sent_at| now - sent_at >= self.notification_period.window())
+    }
 }
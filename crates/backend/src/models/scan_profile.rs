@@ -0,0 +1,183 @@
+use crate::{models::DiscoveryJob, Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::{JobType, Timestamp, ID};
+use uuid::Uuid;
+
+/// Deepest a profile may crawl or recurse
+pub const MAX_SCAN_DEPTH: u32 = 5;
+
+/// Named preset of discovery methods and scan settings, launched as a
+/// `DiscoveryJob` against a target
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanProfile {
+    /// Unique identifier
+    pub id: ID,
+
+    /// Organization this profile belongs to, or `None` for the built-in
+    /// profiles every organization gets
+    pub organization_id: Option<ID>,
+
+    /// Name jobs are launched by, unique within an organization
+    pub name: String,
+
+    /// Discovery methods to run, the first being the job's type
+    pub methods: Vec<JobType>,
+
+    /// How many levels deep to crawl and recurse into discovered assets
+    pub depth: u32,
+
+    /// Ports to scan
+    pub ports: Vec<u16>,
+
+    /// How long a job may run, in seconds
+    pub timeout_secs: u32,
+
+    /// Creation timestamp
+    pub created_at: Timestamp,
+
+    /// Last updated timestamp
+    pub updated_at: Timestamp,
+}
+
+impl ScanProfile {
+    /// Create a new profile for an organization
+    pub fn new(
+        organization_id: ID,
+        name: String,
+        methods: Vec<JobType>,
+        depth: u32,
+        ports: Vec<u16>,
+        timeout_secs: u32,
+    ) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            organization_id: Some(organization_id),
+            name,
+            methods,
+            depth,
+            ports,
+            timeout_secs,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// The "Quick", "Standard" and "Deep" profiles, available to every
+    /// organization under fixed IDs
+    pub fn built_ins() -> Vec<ScanProfile> {
+        let built_in = |id: u128, name: &str, methods, depth, ports, timeout_secs| ScanProfile {
+            id: Uuid::from_u128(id),
+            organization_id: None,
+            name: name.to_string(),
+            methods,
+            depth,
+            ports,
+            timeout_secs,
+            created_at: DateTime::UNIX_EPOCH,
+            updated_at: DateTime::UNIX_EPOCH,
+        };
+
+        vec![
+            built_in(
+                1,
+                "Quick",
+                vec![JobType::DnsEnum, JobType::PortScan],
+                1,
+                vec![22, 80, 443, 8080, 8443],
+                15 * 60,
+            ),
+            built_in(
+                2,
+                "Standard",
+                vec![JobType::DnsEnum, JobType::PortScan, JobType::WebCrawl],
+                2,
+                vec![
+                    21, 22, 23, 25, 53, 80, 110, 143, 443, 445, 465, 587, 993, 995, 1433, 3306,
+                    3389, 5432, 6379, 8080, 8443,
+                ],
+                60 * 60,
+            ),
+            built_in(
+                3,
+                "Deep",
+                vec![
+                    JobType::DnsEnum,
+                    JobType::CertScan,
+                    JobType::PortScan,
+                    JobType::WebCrawl,
+                    JobType::VulnScan,
+                ],
+                3,
+                (1..=1024)
+                    .chain([
+                        1433, 1521, 2049, 3306, 3389, 5432, 5900, 6379, 8000, 8080, 8443, 8888,
+                        9200, 11211, 27017,
+                    ])
+                    .collect(),
+                4 * 60 * 60,
+            ),
+        ]
+    }
+
+    /// The built-in profile called `name`, ignoring case
+    pub fn built_in(name: &str) -> Option<ScanProfile> {
+        Self::built_ins()
+            .into_iter()
+            .find(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether this is one of the built-in profiles
+    pub fn is_built_in(&self) -> bool {
+        self.organization_id.is_none()
+    }
+
+    /// Check the profile can be launched, rejecting it as a validation error
+    /// if not
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(Error::Validation("name must not be empty".to_string()));
+        }
+        if self.methods.is_empty() {
+            return Err(Error::Validation(
+                "methods must include at least one discovery method".to_string(),
+            ));
+        }
+        if self.depth == 0 || self.depth > MAX_SCAN_DEPTH {
+            return Err(Error::Validation(format!(
+                "depth must be between 1 and {}",
+                MAX_SCAN_DEPTH
+            )));
+        }
+        if self.ports.is_empty() || self.ports.contains(&0) {
+            return Err(Error::Validation(
+                "ports must list at least one port between 1 and 65535".to_string(),
+            ));
+        }
+        if self.timeout_secs == 0 {
+            return Err(Error::Validation(
+                "timeout_secs must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// A pending job running this profile's methods against `target`, with
+    /// the profile's settings in its configuration
+    pub fn to_job(&self, organization_id: ID, target: String) -> DiscoveryJob {
+        DiscoveryJob::new(
+            organization_id,
+            self.methods.first().copied().unwrap_or(JobType::DnsEnum),
+            Some(target),
+            Some(serde_json::json!({
+                "profile": self.name,
+                "methods": self.methods,
+                "depth": self.depth,
+                "ports": self.ports,
+                "timeout_secs": self.timeout_secs,
+            })),
+        )
+    }
+}
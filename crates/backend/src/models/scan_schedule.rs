@@ -0,0 +1,87 @@
+use crate::{Error, Result};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use shared::types::{JobType, Timestamp, ID};
+use std::str::FromStr;
+
+/// Recurring discovery scan, enqueued as a `DiscoveryJob` whenever its cron
+/// expression comes due
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanSchedule {
+    /// Unique identifier
+    pub id: ID,
+
+    /// Organization this schedule belongs to
+    pub organization_id: ID,
+
+    /// Target passed to each job (e.g., a domain)
+    pub target: String,
+
+    /// Type of job to enqueue
+    pub job_type: JobType,
+
+    /// Cron expression with a leading seconds field, e.g. `0 0 2 * * *`
+    pub cron_expression: String,
+
+    /// Whether the scheduler should enqueue jobs for this schedule
+    pub enabled: bool,
+
+    /// When a job was last enqueued
+    pub last_run_at: Option<Timestamp>,
+
+    /// Creation timestamp
+    pub created_at: Timestamp,
+
+    /// Last updated timestamp
+    pub updated_at: Timestamp,
+}
+
+impl ScanSchedule {
+    /// Create a new, enabled scan schedule
+    pub fn new(
+        organization_id: ID,
+        target: String,
+        job_type: JobType,
+        cron_expression: String,
+    ) -> Self {
+        use chrono::Utc;
+        use uuid::Uuid;
+
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            organization_id,
+            target,
+            job_type,
+            cron_expression,
+            enabled: true,
+            last_run_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Parse a cron expression, rejecting it as a validation error if invalid
+    pub fn parse_cron(expression: &str) -> Result<Schedule> {
+        Schedule::from_str(expression).map_err(|e| {
+            Error::Validation(format!("Invalid cron expression '{}': {}", expression, e))
+        })
+    }
+
+    /// Next time a job is due, counting from the last run (or creation if it
+    /// has never run)
+    pub fn next_run(&self) -> Result<Option<Timestamp>> {
+        let schedule = Self::parse_cron(&self.cron_expression)?;
+        let since = self.last_run_at.unwrap_or(self.created_at);
+        Ok(schedule.after(&since).next())
+    }
+
+    /// Whether a job should be enqueued at `now`
+    pub fn is_due(&self, now: Timestamp) -> Result<bool> {
+        if !self.enabled {
+            return Ok(false);
+        }
+        Ok(self.next_run()?.is_some_and(|next| next <= now))
+    }
+}
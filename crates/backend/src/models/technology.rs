@@ -19,6 +19,9 @@ pub struct Technology {
     /// Technology category
     pub category: Option<String>,
 
+    /// When the technology was last detected on the asset
+    pub last_seen: Timestamp,
+
     /// Creation timestamp
     pub created_at: Timestamp,
 
@@ -45,8 +48,38 @@ impl Technology {
             name,
             version,
             category,
+            last_seen: now,
             created_at: now,
             updated_at: now,
         }
     }
 }
+
+/// A technology together with the asset it was detected on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DetectedTechnology {
+    #[serde(flatten)]
+    pub technology: Technology,
+
+    /// Value of the asset, e.g. its domain name
+    pub asset_value: String,
+}
+
+/// Number of assets running a technology, or a technology of a category
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TechnologyCount {
+    /// Technology name or category
+    pub name: String,
+
+    pub count: usize,
+}
+
+/// Technologies detected across an organization's assets, counted by name
+/// and by category, most common first
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TechnologyDistribution {
+    pub by_name: Vec<TechnologyCount>,
+
+    /// Technologies without a category are counted as `Uncategorized`
+    pub by_category: Vec<TechnologyCount>,
+}
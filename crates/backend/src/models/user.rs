@@ -1,6 +1,14 @@
+use crate::{Error, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
 use serde::{Deserialize, Serialize};
 use shared::types::{Timestamp, UserRole, ID};
 
+/// Shortest password accepted when registering or changing a password
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
 /// User model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -23,6 +31,10 @@ pub struct User {
     #[serde(skip_serializing)]
     pub password_hash: String,
 
+    /// Whether the user can log in. Deactivated users are kept rather than
+    /// deleted so their history stays attributed.
+    pub active: bool,
+
     /// Creation timestamp
     pub created_at: Timestamp,
 
@@ -51,8 +63,62 @@ impl User {
             email,
             role: role.unwrap_or(UserRole::Analyst),
             password_hash,
+            active: true,
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// Hash `password` with Argon2id and a fresh salt, as a PHC string
+    /// (`$argon2id$v=19$...`) to store in `password_hash`
+    pub fn hash_password(password: &str) -> Result<String> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| Error::Internal(format!("Password hashing failed: {}", e)))
+    }
+
+    /// Replace the user's password, storing only its hash
+    pub fn set_password(&mut self, password: &str) -> Result<()> {
+        self.password_hash = Self::hash_password(password)?;
+        Ok(())
+    }
+
+    /// Check `password` against the stored hash. The comparison is constant
+    /// time, so how long it takes doesn't reveal how much of the hash matched.
+    pub fn verify_password(&self, password: &str) -> Result<bool> {
+        let parsed_hash = PasswordHash::new(&self.password_hash)
+            .map_err(|e| Error::Internal(format!("Invalid password hash format: {}", e)))?;
+
+        match argon2()?.verify_password(password.as_bytes(), &parsed_hash) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(Error::Internal(format!(
+                "Password verification failed unexpectedly: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Reject passwords too weak to accept, as a validation error
+    pub fn validate_password(password: &str) -> Result<()> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(Error::Validation(format!(
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Argon2id with explicit parameters: 64 MiB of memory, 2 iterations, 1 lane
+/// and a 32-byte output. Existing hashes carry their own parameters, so these
+/// can be raised without breaking verification.
+fn argon2() -> Result<Argon2<'static>> {
+    let params = Params::new(65536, 2, 1, Some(32))
+        .map_err(|e| Error::Internal(format!("Invalid Argon2 parameters: {}", e)))?;
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
 }
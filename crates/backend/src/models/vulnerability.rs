@@ -102,3 +102,46 @@ impl Vulnerability {
         });
     }
 }
+
+/// Open vulnerabilities sharing a CVE (or title, when there is no CVE) and
+/// affected technology, aggregated across the assets they were found on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VulnerabilityGroup {
+    /// Title shared by the grouped vulnerabilities
+    pub title: String,
+
+    /// CVE identifier if applicable
+    pub cve_id: Option<String>,
+
+    /// Affected technology from the vulnerability evidence, if recorded
+    pub technology: Option<String>,
+
+    /// Number of distinct assets with a vulnerability in this group
+    pub affected_asset_count: usize,
+
+    /// Number of vulnerabilities in this group
+    pub vulnerability_count: usize,
+
+    /// Highest severity among the grouped vulnerabilities
+    pub highest_severity: Severity,
+
+    /// Assets with a vulnerability in this group
+    #[serde(default)]
+    pub asset_ids: Vec<ID>,
+}
+
+/// Vulnerability activity on an organization's assets since a point in time
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VulnerabilityActivity {
+    /// Vulnerabilities first seen in the window
+    pub new: usize,
+
+    /// Vulnerabilities resolved in the window
+    pub resolved: usize,
+
+    /// Critical vulnerabilities first seen in the window
+    pub critical: usize,
+
+    /// High severity vulnerabilities first seen in the window
+    pub high: usize,
+}
@@ -1,30 +1,223 @@
 use async_trait::async_trait;
 use shared::types::{AssetStatus, AssetType, ID};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tracing::{debug, info};
 use url;
 
 use crate::{
-    models::{Asset, AssetRelationshipType},
-    traits::{AssetRepository, AssetService},
-    Result,
+    models::{
+        Asset, AssetFilter, AssetGraph, AssetHistory, AssetRelationship, AssetRelationshipType,
+        RelationshipDirection,
+    },
+    services::{
+        attribute_schema::validate_attributes, relationships::discover_relationships,
+        risk::refresh_risk_score,
+    },
+    traits::{AssetHistoryRepository, AssetRepository, AssetService, VulnerabilityRepository},
+    Error, Result,
 };
 
 pub struct AssetServiceImpl {
     repository: Arc<dyn AssetRepository>,
+    history_repository: Arc<dyn AssetHistoryRepository>,
+    vulnerability_repository: Option<Arc<dyn VulnerabilityRepository>>,
 }
 
 impl AssetServiceImpl {
-    pub fn new(repository: Arc<dyn AssetRepository>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn AssetRepository>,
+        history_repository: Arc<dyn AssetHistoryRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            history_repository,
+            vulnerability_repository: None,
+        }
+    }
+
+    /// Score assets against their open vulnerabilities in
+    /// `vulnerability_repository`, keeping risk scores up to date as ports
+    /// change
+    pub fn with_vulnerability_repository(
+        mut self,
+        vulnerability_repository: Arc<dyn VulnerabilityRepository>,
+    ) -> Self {
+        self.vulnerability_repository = Some(vulnerability_repository);
+        self
+    }
+
+    /// Every asset of `organization_id`, loaded a page at a time
+    async fn organization_assets(&self, organization_id: ID) -> Result<Vec<Asset>> {
+        let mut assets = Vec::new();
+        let mut seen = HashSet::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .repository
+                .list_assets(
+                    &AssetFilter {
+                        organization_id: Some(organization_id),
+                        ..Default::default()
+                    },
+                    RELATIONSHIP_PAGE_SIZE,
+                    offset,
+                )
+                .await?;
+            let page_len = page.len();
+            offset += page_len;
+            // An asset can move between pages while they're loaded
+            assets.extend(page.into_iter().filter(|asset| seen.insert(asset.id)));
+            if page_len < RELATIONSHIP_PAGE_SIZE {
+                return Ok(assets);
+            }
+        }
+    }
+
+    /// Refresh the risk score of `asset` after its ports may have changed.
+    /// Without a vulnerability repository scores aren't maintained.
+    async fn refresh_risk_score(&self, asset: &mut Asset) -> Result<()> {
+        if let Some(vulnerability_repository) = &self.vulnerability_repository {
+            asset.risk_score = refresh_risk_score(
+                self.repository.as_ref(),
+                vulnerability_repository.as_ref(),
+                asset.id,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Assets at the other end of `asset_id`'s relationships in
+    /// `direction`, with the type of each relationship
+    async fn relationships_of(
+        &self,
+        asset_id: ID,
+        direction: RelationshipDirection,
+        relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<(Asset, String)>> {
+        // Make sure the asset itself exists
+        self.repository.get_asset(asset_id).await?;
+
+        Ok(self
+            .repository
+            .list_asset_relationships(asset_id, direction, relationship_type, limit, offset)
+            .await?
+            .into_iter()
+            .map(|(relationship, asset)| (asset, relationship.relationship_type))
+            .collect())
+    }
+}
+
+/// Longest tag accepted, matching the `asset_tags.tag` column
+const MAX_TAG_LEN: usize = 64;
+
+/// Assets loaded per query when discovering relationships or building the
+/// relationship graph
+const RELATIONSHIP_PAGE_SIZE: usize = 500;
+
+/// Tags are compared case-insensitively and without surrounding whitespace
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+fn validate_tag(tag: &str) -> Result<()> {
+    if tag.is_empty() {
+        return Err(Error::Validation("Tags cannot be empty".to_string()));
     }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(Error::Validation(format!(
+            "Tag '{}' is longer than {} characters",
+            tag, MAX_TAG_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Canonical form of an asset value, so the same asset entered in different
+/// ways is stored once:
+///
+/// - domains are lowercased without trailing dots, and a URL is reduced to
+///   its host (`http://Example.com/` becomes `example.com`)
+/// - IP addresses must parse, and are written in their standard form
+/// - web app URLs get a lowercase scheme and host, lose default ports,
+///   fragments and trailing slashes, and default to `https://` without a
+///   scheme
+/// - other values are trimmed
+pub fn canonicalize_value(asset_type: AssetType, raw: &str) -> Result<String> {
+    let value = raw.trim();
+    if value.is_empty() {
+        return Err(Error::Validation("Asset value cannot be empty".to_string()));
+    }
+
+    match asset_type {
+        AssetType::Domain => {
+            let host = if value.contains("://") {
+                parse_url(value)?
+                    .host_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| Error::Validation(format!("'{}' has no domain name", value)))?
+            } else {
+                value.to_string()
+            };
+            let domain = host.trim_end_matches('.').to_lowercase();
+            if domain.is_empty() {
+                return Err(Error::Validation(format!(
+                    "'{}' is not a domain name",
+                    value
+                )));
+            }
+            Ok(domain)
+        }
+        AssetType::IPAddress => value
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.to_string())
+            .map_err(|_| Error::Validation(format!("'{}' is not a valid IP address", value))),
+        AssetType::WebApp => {
+            let mut url = if value.contains("://") {
+                parse_url(value)?
+            } else {
+                parse_url(&format!("https://{}", value))?
+            };
+            url.set_fragment(None);
+            if url.query().is_none() {
+                let path = url.path().trim_end_matches('/').to_string();
+                url.set_path(&path);
+            }
+            let mut canonical = url.to_string();
+            // The root path always serializes as "/"
+            if url.query().is_none() && canonical.ends_with('/') {
+                canonical.pop();
+            }
+            Ok(canonical)
+        }
+        AssetType::Certificate | AssetType::CodeRepo | AssetType::CloudResource => {
+            Ok(value.to_string())
+        }
+    }
+}
+
+fn parse_url(value: &str) -> Result<url::Url> {
+    url::Url::parse(value)
+        .map_err(|e| Error::Validation(format!("'{}' is not a valid URL: {}", value, e)))
 }
 
 #[async_trait]
 impl AssetService for AssetServiceImpl {
     async fn create_asset(&self, asset: &Asset) -> Result<Asset> {
         debug!("Creating asset: {}", asset.value);
-        self.repository.create_asset(asset).await
+        validate_attributes(asset.asset_type, &asset.attributes)?;
+        let mut asset = asset.clone();
+        asset.value = canonicalize_value(asset.asset_type, &asset.value)?;
+
+        // An asset that already exists is seen again rather than duplicated
+        let mut created = self.repository.upsert_asset(&asset).await?;
+        if !created.open_ports().is_empty() {
+            self.refresh_risk_score(&mut created).await?;
+        }
+        Ok(created)
     }
 
     async fn get_asset(&self, id: ID) -> Result<Asset> {
@@ -34,7 +227,27 @@ impl AssetService for AssetServiceImpl {
 
     async fn update_asset(&self, asset: &Asset) -> Result<Asset> {
         debug!("Updating asset: {}", asset.value);
-        self.repository.update_asset(asset).await
+        validate_attributes(asset.asset_type, &asset.attributes)?;
+        let mut asset = asset.clone();
+        asset.value = canonicalize_value(asset.asset_type, &asset.value)?;
+
+        let previous = self.repository.get_asset(asset.id).await?;
+        let mut updated = self.repository.update_asset(&asset).await?;
+
+        // Record each changed field so monitoring can see what moved and when
+        let changes = AssetHistory::diff(&previous, &updated, updated.updated_at);
+        for change in &changes {
+            self.history_repository.create_history_entry(change).await?;
+        }
+        if !changes.is_empty() {
+            debug!("Recorded {} changes for asset {}", changes.len(), asset.id);
+        }
+
+        if previous.open_ports() != updated.open_ports() {
+            self.refresh_risk_score(&mut updated).await?;
+        }
+
+        Ok(updated)
     }
 
     async fn delete_asset(&self, id: ID) -> Result<bool> {
@@ -47,15 +260,27 @@ impl AssetService for AssetServiceImpl {
         organization_id: Option<ID>,
         asset_type: Option<AssetType>,
         status: Option<AssetStatus>,
+        tag: Option<String>,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<Asset>> {
         debug!(
-            "Listing assets with filters - organization_id: {:?}, asset_type: {:?}, status: {:?}, limit: {}, offset: {}",
-            organization_id, asset_type, status, limit, offset
+            "Listing assets with filters - organization_id: {:?}, asset_type: {:?}, status: {:?}, tag: {:?}, limit: {}, offset: {}",
+            organization_id, asset_type, status, tag, limit, offset
         );
+        let tag = tag.as_deref().map(normalize_tag);
         self.repository
-            .list_assets(organization_id, asset_type, status, limit, offset)
+            .list_assets(
+                &AssetFilter {
+                    organization_id,
+                    asset_type,
+                    status,
+                    tag,
+                    ..Default::default()
+                },
+                limit,
+                offset,
+            )
             .await
     }
 
@@ -64,38 +289,106 @@ impl AssetService for AssetServiceImpl {
         organization_id: Option<ID>,
         asset_type: Option<AssetType>,
         status: Option<AssetStatus>,
+        tag: Option<String>,
     ) -> Result<usize> {
         debug!(
-            "Counting assets with filters - organization_id: {:?}, asset_type: {:?}, status: {:?}",
-            organization_id, asset_type, status
+            "Counting assets with filters - organization_id: {:?}, asset_type: {:?}, status: {:?}, tag: {:?}",
+            organization_id, asset_type, status, tag
         );
+        let tag = tag.as_deref().map(normalize_tag);
         self.repository
-            .count_assets(organization_id, asset_type, status)
+            .count_assets(&AssetFilter {
+                organization_id,
+                asset_type,
+                status,
+                tag,
+                ..Default::default()
+            })
             .await
     }
 
+    async fn add_tags(&self, asset_id: ID, tags: &[String]) -> Result<Vec<String>> {
+        debug!("Tagging asset {} with {:?}", asset_id, tags);
+        let mut normalized = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = normalize_tag(tag);
+            validate_tag(&tag)?;
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+
+        // Fail with NotFound rather than a foreign key violation
+        self.repository.get_asset(asset_id).await?;
+        self.repository.add_asset_tags(asset_id, &normalized).await
+    }
+
+    async fn remove_tag(&self, asset_id: ID, tag: &str) -> Result<bool> {
+        debug!("Removing tag {} from asset {}", tag, asset_id);
+        self.repository
+            .remove_asset_tag(asset_id, &normalize_tag(tag))
+            .await
+    }
+
+    async fn get_tags(&self, asset_id: ID) -> Result<Vec<String>> {
+        debug!("Getting tags for asset {}", asset_id);
+        self.repository.get_asset(asset_id).await?;
+        self.repository.list_asset_tags(asset_id).await
+    }
+
+    async fn bulk_update_asset_status(
+        &self,
+        organization_id: ID,
+        asset_ids: Vec<ID>,
+        status: AssetStatus,
+    ) -> Result<usize> {
+        info!(
+            "Updating status to {:?} for {} assets in organization {}",
+            status,
+            asset_ids.len(),
+            organization_id
+        );
+
+        let updated_count = self
+            .repository
+            .bulk_update_status(organization_id, &asset_ids, status)
+            .await?;
+
+        info!("Successfully updated {} assets", updated_count);
+        Ok(updated_count)
+    }
+
     async fn create_asset_relationship(
         &self,
         source_asset_id: ID,
         target_asset_id: ID,
         relationship_type: String,
-        _metadata: Option<serde_json::Value>,
+        metadata: Option<serde_json::Value>,
     ) -> Result<bool> {
         info!(
             "Creating relationship: {} between assets {} and {}",
             relationship_type, source_asset_id, target_asset_id
         );
 
-        // Get the source asset
-        let mut source_asset = self.repository.get_asset(source_asset_id).await?;
-
-        // Add the relationship to the source asset
-        source_asset.add_relationship(&relationship_type, target_asset_id);
+        // Both ends must exist
+        self.repository.get_asset(source_asset_id).await?;
+        self.repository.get_asset(target_asset_id).await?;
 
-        // Update the source asset
-        self.repository.update_asset(&source_asset).await?;
+        let relationship = AssetRelationship {
+            source_asset_id,
+            target_asset_id,
+            relationship_type,
+            metadata,
+        };
+        self.repository.add_asset_relationship(&relationship).await
+    }
 
-        Ok(true)
+    async fn create_asset_relationships(
+        &self,
+        relationships: Vec<(ID, ID, String)>,
+    ) -> Result<usize> {
+        info!("Creating {} asset relationships", relationships.len());
+        self.repository.add_relationships_bulk(relationships).await
     }
 
     async fn delete_asset_relationship(
@@ -109,56 +402,53 @@ impl AssetService for AssetServiceImpl {
             relationship_type, source_asset_id, target_asset_id
         );
 
-        // Get the source asset
-        let mut source_asset = self.repository.get_asset(source_asset_id).await?;
-
-        // Remove the relationship from the source asset
-        source_asset.remove_relationship(&relationship_type, target_asset_id);
-
-        // Update the source asset
-        self.repository.update_asset(&source_asset).await?;
-
-        Ok(true)
+        self.repository
+            .remove_asset_relationship(source_asset_id, target_asset_id, &relationship_type)
+            .await
     }
 
     async fn get_related_assets(
         &self,
         asset_id: ID,
         relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
     ) -> Result<Vec<(Asset, String)>> {
         debug!(
             "Getting related assets for asset id: {} with relationship type: {:?}",
             asset_id, relationship_type
         );
 
-        // Get the source asset
-        let source_asset = self.repository.get_asset(asset_id).await?;
-
-        // Get the relationships from the asset
-        let relationships = source_asset.get_relationships();
-
-        // Filter by relationship type if specified
-        let filtered_relationships = if let Some(rel_type) = relationship_type {
-            relationships
-                .iter()
-                .filter(|(k, _)| **k == rel_type)
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect::<std::collections::HashMap<_, _>>()
-        } else {
-            relationships.clone()
-        };
+        self.relationships_of(
+            asset_id,
+            RelationshipDirection::Outgoing,
+            relationship_type,
+            limit,
+            offset,
+        )
+        .await
+    }
 
-        // Fetch the related assets
-        let mut related_assets = Vec::new();
-        for (rel_type, asset_ids) in filtered_relationships {
-            for target_id in asset_ids {
-                if let Ok(asset) = self.repository.get_asset(target_id).await {
-                    related_assets.push((asset, rel_type.clone()));
-                }
-            }
-        }
+    async fn get_referring_assets(
+        &self,
+        asset_id: ID,
+        relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<(Asset, String)>> {
+        debug!(
+            "Getting assets referring to asset id: {} with relationship type: {:?}",
+            asset_id, relationship_type
+        );
 
-        Ok(related_assets)
+        self.relationships_of(
+            asset_id,
+            RelationshipDirection::Incoming,
+            relationship_type,
+            limit,
+            offset,
+        )
+        .await
     }
 
     async fn discover_asset_relationships(
@@ -170,321 +460,22 @@ impl AssetService for AssetServiceImpl {
             organization_id
         );
 
-        // Get all assets for the organization
-        let assets = self
-            .repository
-            .list_assets(Some(organization_id), None, None, 1000, 0)
-            .await?;
-
-        let mut discovered_relationships = Vec::new();
-
-        // Group assets by type for easier processing
-        let domains: Vec<&Asset> = assets
-            .iter()
-            .filter(|a| a.asset_type == AssetType::Domain)
-            .collect();
-
-        let ips: Vec<&Asset> = assets
-            .iter()
-            .filter(|a| a.asset_type == AssetType::IPAddress)
-            .collect();
-
-        let web_apps: Vec<&Asset> = assets
-            .iter()
-            .filter(|a| a.asset_type == AssetType::WebApp)
-            .collect();
-
-        let certificates: Vec<&Asset> = assets
-            .iter()
-            .filter(|a| a.asset_type == AssetType::Certificate)
-            .collect();
-
-        let cloud_resources: Vec<&Asset> = assets
-            .iter()
-            .filter(|a| a.asset_type == AssetType::CloudResource)
-            .collect();
-
-        // Discover domain relationships (e.g., subdomains)
-        for domain_a in domains.iter() {
-            for domain_b in domains.iter() {
-                if domain_a.id == domain_b.id {
-                    continue;
-                }
-
-                // Check for subdomain relationship
-                let domain_a_value = domain_a.value.to_string();
-                let domain_b_value = domain_b.value.to_string();
-
-                // Check if domain_b is a subdomain of domain_a
-                // "sub.test-domain.com" should be identified as a subdomain of "test-domain.com"
-                if domain_b_value != domain_a_value {
-                    // Check if domain_b is a subdomain of domain_a using direct string comparison
-                    let is_subdomain = domain_b_value.ends_with(&format!(".{}", domain_a_value));
-
-                    if is_subdomain {
-                        discovered_relationships.push((
-                            domain_b.id,
-                            domain_a.id,
-                            AssetRelationshipType::Subdomain.as_str().to_string(),
-                        ));
-                    }
-                }
-
-                // Check for same registrar relationship (if info available)
-                if let (Some(info_a), Some(info_b)) = (
-                    domain_a.attributes.get("whois_info"),
-                    domain_b.attributes.get("whois_info"),
-                ) {
-                    if let (Some(registrar_a), Some(registrar_b)) =
-                        (info_a.get("registrar"), info_b.get("registrar"))
-                    {
-                        if registrar_a == registrar_b
-                            && registrar_a.as_str().is_some()
-                            && !registrar_a.as_str().unwrap().is_empty()
-                        {
-                            discovered_relationships.push((
-                                domain_a.id,
-                                domain_b.id,
-                                AssetRelationshipType::SameRegistrar.as_str().to_string(),
-                            ));
-                        }
-                    }
-                }
-            }
-        }
-
-        // Discover web app hosted on IP relationships
-        for web_app in web_apps.iter() {
-            if let Some(host_info) = web_app.attributes.get("host_info") {
-                // Check for IP relationship
-                if let Some(host_ip) = host_info.get("ip_address") {
-                    if let Some(ip_str) = host_ip.as_str() {
-                        // Find the corresponding IP asset
-                        for ip in ips.iter() {
-                            if ip.value == ip_str {
-                                discovered_relationships.push((
-                                    web_app.id,
-                                    ip.id,
-                                    AssetRelationshipType::HostedOn.as_str().to_string(),
-                                ));
-                                break;
-                            }
-                        }
-                    }
-                }
-
-                // Check for domain relationship
-                if let Some(host_domain) = host_info.get("domain") {
-                    if let Some(domain_str) = host_domain.as_str() {
-                        // Find the corresponding domain asset
-                        for domain in domains.iter() {
-                            if domain.value == domain_str
-                                || domain_str.ends_with(&format!(".{}", domain.value))
-                            {
-                                discovered_relationships.push((
-                                    web_app.id,
-                                    domain.id,
-                                    AssetRelationshipType::HostedOn.as_str().to_string(),
-                                ));
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Check for web app dependencies
-            if let Some(dependencies) = web_app.attributes.get("dependencies") {
-                if let Some(deps_array) = dependencies.as_array() {
-                    for dep in deps_array {
-                        if let Some(dep_url) = dep.get("url").and_then(|u| u.as_str()) {
-                            // Try to extract domain from URL
-                            if let Ok(url) = url::Url::parse(dep_url) {
-                                if let Some(host) = url.host_str() {
-                                    // Look for matching domain asset
-                                    for domain in domains.iter() {
-                                        if domain.value == host
-                                            || host.ends_with(&format!(".{}", domain.value))
-                                        {
-                                            discovered_relationships.push((
-                                                web_app.id,
-                                                domain.id,
-                                                AssetRelationshipType::DependsOn
-                                                    .as_str()
-                                                    .to_string(),
-                                            ));
-                                            break;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Discover certificate relationships
-        for cert in certificates.iter() {
-            if let Some(cert_info) = cert.attributes.get("certificate_info") {
-                if let Some(cert_domains) = cert_info.get("domains") {
-                    if let Some(domains_array) = cert_domains.as_array() {
-                        for domain_value in domains_array {
-                            if let Some(domain_str) = domain_value.as_str() {
-                                // Find the corresponding domain asset
-                                for domain in domains.iter() {
-                                    if domain.value == domain_str {
-                                        discovered_relationships.push((
-                                            cert.id,
-                                            domain.id,
-                                            AssetRelationshipType::Secures.as_str().to_string(),
-                                        ));
-                                    } else if domain_str.ends_with(&format!(".{}", domain.value)) {
-                                        // Wildcard certificate that includes this domain
-                                        discovered_relationships.push((
-                                            cert.id,
-                                            domain.id,
-                                            AssetRelationshipType::Secures.as_str().to_string(),
-                                        ));
-                                    }
-                                }
-
-                                // Also link to web apps on this domain
-                                for web_app in web_apps.iter() {
-                                    if let Some(host_info) = web_app.attributes.get("host_info") {
-                                        if let Some(app_domain) =
-                                            host_info.get("domain").and_then(|d| d.as_str())
-                                        {
-                                            if app_domain == domain_str
-                                                || app_domain.ends_with(&format!(".{}", domain_str))
-                                            {
-                                                discovered_relationships.push((
-                                                    cert.id,
-                                                    web_app.id,
-                                                    AssetRelationshipType::Secures
-                                                        .as_str()
-                                                        .to_string(),
-                                                ));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-
-                // Check for common issuers between certificates
-                if let Some(issuer) = cert_info.get("issuer").and_then(|i| i.as_str()) {
-                    for other_cert in certificates.iter() {
-                        if cert.id == other_cert.id {
-                            continue;
-                        }
-
-                        if let Some(other_info) = other_cert.attributes.get("certificate_info") {
-                            if let Some(other_issuer) =
-                                other_info.get("issuer").and_then(|i| i.as_str())
-                            {
-                                if issuer == other_issuer {
-                                    discovered_relationships.push((
-                                        cert.id,
-                                        other_cert.id,
-                                        AssetRelationshipType::SameIssuer.as_str().to_string(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Discover cloud resource relationships
-        for cloud_resource in cloud_resources.iter() {
-            if let Some(cloud_info) = cloud_resource.attributes.get("cloud_info") {
-                // Check for resources in the same VPC/Network
-                if let Some(vpc_id) = cloud_info.get("vpc_id").and_then(|v| v.as_str()) {
-                    for other_resource in cloud_resources.iter() {
-                        if cloud_resource.id == other_resource.id {
-                            continue;
-                        }
-
-                        if let Some(other_info) = other_resource.attributes.get("cloud_info") {
-                            if let Some(other_vpc) =
-                                other_info.get("vpc_id").and_then(|v| v.as_str())
-                            {
-                                if vpc_id == other_vpc {
-                                    discovered_relationships.push((
-                                        cloud_resource.id,
-                                        other_resource.id,
-                                        AssetRelationshipType::SameNetwork.as_str().to_string(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-
-                // Link cloud resources to their public IP addresses
-                if let Some(public_ip) = cloud_info.get("public_ip").and_then(|ip| ip.as_str()) {
-                    for ip in ips.iter() {
-                        if ip.value == public_ip {
-                            discovered_relationships.push((
-                                cloud_resource.id,
-                                ip.id,
-                                AssetRelationshipType::HasPublicIP.as_str().to_string(),
-                            ));
-                            break;
-                        }
-                    }
-                }
-
-                // Link cloud resources to hosted services/web apps
-                if let Some(resource_dns) = cloud_info.get("dns_name").and_then(|dns| dns.as_str())
-                {
-                    for web_app in web_apps.iter() {
-                        if let Some(host_info) = web_app.attributes.get("host_info") {
-                            if let Some(app_host) =
-                                host_info.get("hostname").and_then(|h| h.as_str())
-                            {
-                                if app_host == resource_dns {
-                                    discovered_relationships.push((
-                                        web_app.id,
-                                        cloud_resource.id,
-                                        AssetRelationshipType::HostedOn.as_str().to_string(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
-
-                    // Also link to domains
-                    for domain in domains.iter() {
-                        if resource_dns.ends_with(&format!(".{}", domain.value)) {
-                            discovered_relationships.push((
-                                cloud_resource.id,
-                                domain.id,
-                                AssetRelationshipType::BelongsTo.as_str().to_string(),
-                            ));
-                        }
-                    }
-                }
-            }
-        }
-
-        // Deduplicate relationships
-        let mut unique_relationships = Vec::new();
-        for rel in discovered_relationships {
-            if !unique_relationships.contains(&rel) {
-                unique_relationships.push(rel);
-            }
-        }
-
+        let assets = self.organization_assets(organization_id).await?;
+        let relationships = discover_relationships(&assets);
         debug!(
-            "Discovered {} unique asset relationships",
-            unique_relationships.len()
+            "Discovered {} unique asset relationships among {} assets",
+            relationships.len(),
+            assets.len()
         );
-        Ok(unique_relationships)
+        Ok(relationships)
+    }
+
+    async fn asset_graph(&self, organization_id: ID) -> Result<AssetGraph> {
+        debug!("Building asset graph for organization: {}", organization_id);
+
+        let assets = self.organization_assets(organization_id).await?;
+        let relationships = discover_relationships(&assets);
+        Ok(AssetGraph::new(&assets, &relationships))
     }
 
     // Helper function to identify direct and indirect dependencies between assets
@@ -541,6 +532,8 @@ impl AssetService for AssetServiceImpl {
             .get_related_assets(
                 current_id,
                 Some(AssetRelationshipType::DependsOn.as_str().to_string()),
+                usize::MAX,
+                0,
             )
             .await?;
 
@@ -560,4 +553,57 @@ impl AssetService for AssetServiceImpl {
 
         Ok(())
     }
+
+    async fn get_asset_history(
+        &self,
+        asset_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AssetHistory>> {
+        debug!("Getting history for asset id: {}", asset_id);
+        self.history_repository
+            .list_asset_history(asset_id, limit, offset)
+            .await
+    }
+
+    async fn count_asset_history(&self, asset_id: ID) -> Result<usize> {
+        self.history_repository.count_asset_history(asset_id).await
+    }
+
+    async fn list_recent_assets(&self, organization_id: ID, limit: usize) -> Result<Vec<Asset>> {
+        debug!(
+            "Listing {} most recent assets for organization: {}",
+            limit, organization_id
+        );
+        self.repository
+            .list_recent_assets(organization_id, limit)
+            .await
+    }
+
+    async fn asset_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+        debug!(
+            "Counting assets per day since {} for organization: {}",
+            since, organization_id
+        );
+        self.repository
+            .asset_counts_by_day(organization_id, since)
+            .await
+    }
+
+    async fn compute_risk_score(&self, asset_id: ID) -> Result<f32> {
+        debug!("Computing risk score for asset id: {}", asset_id);
+        let vulnerability_repository = self.vulnerability_repository.as_ref().ok_or_else(|| {
+            Error::Internal("Risk scoring needs a vulnerability repository".to_string())
+        })?;
+        refresh_risk_score(
+            self.repository.as_ref(),
+            vulnerability_repository.as_ref(),
+            asset_id,
+        )
+        .await
+    }
 }
@@ -0,0 +1,154 @@
+//! Validation of asset attributes against per-type JSON schemas
+//!
+//! Attributes stay free-form: keys the schemas don't know about are
+//! accepted as they are. The keys relationship discovery and risk scoring
+//! read, such as `host_info.ip_address` on web apps or `ports` on IPs, must
+//! have the expected shape, so a bad payload is rejected when it is stored
+//! rather than silently ignored later.
+
+use jsonschema::Validator;
+use serde_json::{json, Value};
+use shared::types::AssetType;
+use std::sync::LazyLock;
+
+use crate::{Error, Result};
+
+/// Open ports recorded by port scans, as read by `Asset::open_ports`
+fn ports_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "required": ["port"],
+            "properties": {
+                "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+                "protocol": { "type": "string" },
+                "status": { "type": "string" },
+                "service": { "type": ["string", "null"] }
+            }
+        }
+    })
+}
+
+/// Related asset IDs by relationship type, the form relationships took
+/// before they moved to the `asset_relationships` table
+fn relationships_schema() -> Value {
+    json!({
+        "type": "object",
+        "additionalProperties": {
+            "type": "array",
+            "items": { "type": "string", "format": "uuid" }
+        }
+    })
+}
+
+fn ip_address_schema() -> Value {
+    json!({
+        "type": "string",
+        "anyOf": [{ "format": "ipv4" }, { "format": "ipv6" }]
+    })
+}
+
+/// Schema the attributes of an asset of `asset_type` must match
+fn schema_for(asset_type: AssetType) -> Value {
+    let properties = match asset_type {
+        AssetType::Domain => json!({
+            "source": { "type": "string" },
+            "whois_info": { "type": "object" },
+            "ports": ports_schema(),
+            "relationships": relationships_schema()
+        }),
+        AssetType::IPAddress => json!({
+            "source": { "type": "string" },
+            "asn_info": { "type": "object" },
+            "ports": ports_schema(),
+            "relationships": relationships_schema()
+        }),
+        AssetType::WebApp => json!({
+            "host_info": {
+                "type": "object",
+                "properties": {
+                    "ip_address": ip_address_schema(),
+                    "domain": { "type": "string" },
+                    "hostname": { "type": "string" }
+                }
+            },
+            "dependencies": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": { "url": { "type": "string" } }
+                }
+            },
+            "content_hash": { "type": "string" },
+            "screenshot_path": { "type": "string" },
+            "relationships": relationships_schema()
+        }),
+        AssetType::Certificate => json!({
+            "certificate_info": {
+                "type": "object",
+                "properties": {
+                    "domains": { "type": "array", "items": { "type": "string" } },
+                    "issuer": { "type": "string" },
+                    "not_before": { "type": "string" },
+                    "not_after": { "type": "string" }
+                }
+            },
+            "relationships": relationships_schema()
+        }),
+        AssetType::CodeRepo | AssetType::CloudResource => json!({
+            "relationships": relationships_schema()
+        }),
+    };
+
+    json!({ "type": "object", "properties": properties })
+}
+
+fn compile(asset_type: AssetType) -> Validator {
+    jsonschema::options()
+        .should_validate_formats(true)
+        .build(&schema_for(asset_type))
+        .expect("built-in attribute schemas are valid")
+}
+
+static DOMAIN: LazyLock<Validator> = LazyLock::new(|| compile(AssetType::Domain));
+static IP_ADDRESS: LazyLock<Validator> = LazyLock::new(|| compile(AssetType::IPAddress));
+static WEB_APP: LazyLock<Validator> = LazyLock::new(|| compile(AssetType::WebApp));
+static CERTIFICATE: LazyLock<Validator> = LazyLock::new(|| compile(AssetType::Certificate));
+static CODE_REPO: LazyLock<Validator> = LazyLock::new(|| compile(AssetType::CodeRepo));
+static CLOUD_RESOURCE: LazyLock<Validator> = LazyLock::new(|| compile(AssetType::CloudResource));
+
+/// Check `attributes` against the schema for `asset_type`, returning a
+/// validation error that lists every mismatch
+pub fn validate_attributes(asset_type: AssetType, attributes: &Value) -> Result<()> {
+    let validator: &Validator = match asset_type {
+        AssetType::Domain => &DOMAIN,
+        AssetType::IPAddress => &IP_ADDRESS,
+        AssetType::WebApp => &WEB_APP,
+        AssetType::Certificate => &CERTIFICATE,
+        AssetType::CodeRepo => &CODE_REPO,
+        AssetType::CloudResource => &CLOUD_RESOURCE,
+    };
+
+    let problems: Vec<String> = validator
+        .iter_errors(attributes)
+        .map(|error| {
+            let path = error.instance_path.to_string();
+            if path.is_empty() {
+                error.to_string()
+            } else {
+                format!("{}: {}", path, error)
+            }
+        })
+        .collect();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Validation(format!(
+            "Invalid attributes for {:?} asset: {}",
+            asset_type,
+            problems.join("; ")
+        )))
+    }
+}
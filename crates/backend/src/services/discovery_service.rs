@@ -1,20 +1,45 @@
 use crate::models::JobAssetLink;
 use async_trait::async_trait;
 use chrono::Utc;
-use shared::types::{AssetType, JobStatus, JobType, ID};
+use discovery::results::{DiscoveredWebResource, DiscoveryResult};
+use discovery::web_crawl::content_hash::{detect_content_changes, ContentChange};
+use shared::types::{AssetStatus, AssetType, JobStatus, JobType, PortStatus, ID};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::{
-    models::{Asset, DiscoveryJob, Vulnerability},
-    traits::{AssetRepository, DiscoveryJobRepository, DiscoveryService},
+    models::{Asset, AssetFilter, DiscoveryJob, Vulnerability},
+    services::{canonicalize_value, CRITICAL_PORTS},
+    traits::{AssetRepository, DiscoveryJobRepository, DiscoveryService, NotificationService},
     Result,
 };
 
+/// Number of existing assets fetched per query when comparing against a
+/// result; all pages are read
+const RECONCILE_PAGE_SIZE: usize = 1000;
+
+/// Tag users put on web app assets, through `POST /assets/{id}/tags`, to be
+/// alerted when their content changes. Nothing applies it automatically.
+const CRITICAL_TAG: &str = "critical";
+
+/// Outcome of comparing a discovery run against the assets already known
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationReport {
+    /// Assets seen for the first time; created by the reconciliation
+    pub new: Vec<Asset>,
+    /// Previously known assets seen again; their `last_seen` is refreshed
+    pub unchanged: Vec<Asset>,
+    /// Previously known assets of a type covered by the run that were not
+    /// seen this time. They are reported but left untouched.
+    pub disappeared: Vec<Asset>,
+}
+
 pub struct DiscoveryServiceImpl {
     asset_repository: Arc<dyn AssetRepository>,
     discovery_job_repository: Arc<dyn DiscoveryJobRepository>,
+    notification_service: Option<Arc<dyn NotificationService>>,
 }
 
 impl DiscoveryServiceImpl {
@@ -25,6 +50,278 @@ impl DiscoveryServiceImpl {
         Self {
             asset_repository,
             discovery_job_repository,
+            notification_service: None,
+        }
+    }
+
+    /// Alert through `notification_service` when reconciliation finds new
+    /// critical assets, or when the content of a web app tagged `critical`
+    /// changes
+    pub fn with_notification_service(
+        mut self,
+        notification_service: Arc<dyn NotificationService>,
+    ) -> Self {
+        self.notification_service = Some(notification_service);
+        self
+    }
+
+    /// Every asset of `asset_type` in the organization, optionally only those
+    /// tagged `tag`, read a page at a time
+    async fn all_assets(
+        &self,
+        organization_id: ID,
+        asset_type: AssetType,
+        tag: Option<&str>,
+    ) -> Result<Vec<Asset>> {
+        let mut assets = Vec::new();
+        loop {
+            let page = self
+                .asset_repository
+                .list_assets(
+                    &AssetFilter {
+                        organization_id: Some(organization_id),
+                        asset_type: Some(asset_type),
+                        tag: tag.map(str::to_string),
+                        ..Default::default()
+                    },
+                    RECONCILE_PAGE_SIZE,
+                    assets.len(),
+                )
+                .await?;
+            let last_page = page.len() < RECONCILE_PAGE_SIZE;
+            assets.extend(page);
+            if last_page {
+                return Ok(assets);
+            }
+        }
+    }
+
+    /// Compare the domains, IPs and open ports in `result` against the
+    /// organization's existing assets. New assets are created, known ones get
+    /// their `last_seen` refreshed, and known assets of a type the result
+    /// covers that were not seen again are reported as disappeared. Domain
+    /// registration details in the result are stored as `whois_info` and IP
+    /// network ownership as `asn_info`.
+    pub async fn reconcile_results(
+        &self,
+        organization_id: ID,
+        result: &DiscoveryResult,
+    ) -> Result<ReconciliationReport> {
+        let now = Utc::now();
+        let mut report = ReconciliationReport::default();
+
+        // Discovered assets keyed by normalized value, with the attributes a
+        // newly created asset should carry
+        let mut domains: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+        let mut ips: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+
+        for domain in &result.domains {
+            domains
+                .entry(normalize_value(AssetType::Domain, &domain.domain_name))
+                .or_insert_with(|| result.domain_attributes(domain));
+        }
+        for ip in &result.ip_addresses {
+            ips.entry(ip.ip_address.to_string())
+                .or_insert_with(|| result.ip_attributes(ip.ip_address, &ip.source));
+        }
+
+        // Open ports are recorded on their IP asset, which counts as discovered
+        let mut open_ports: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+        for port in result
+            .ports
+            .iter()
+            .filter(|p| p.port_status() == Some(PortStatus::Open))
+        {
+            let ip = port.ip_address.to_string();
+            ips.entry(ip.clone())
+                .or_insert_with(|| result.ip_attributes(port.ip_address, &port.source));
+            open_ports.entry(ip).or_default().push(serde_json::json!({
+                "port": port.port,
+                "protocol": port.protocol,
+                "service": port.service_name,
+                "banner": port.banner,
+                "status": port.port_status()
+            }));
+        }
+
+        for (asset_type, values) in [(AssetType::Domain, domains), (AssetType::IPAddress, ips)] {
+            // Only types the result covers can have disappeared assets
+            if values.is_empty() {
+                continue;
+            }
+
+            let mut existing: HashMap<String, Asset> = self
+                .all_assets(organization_id, asset_type, None)
+                .await?
+                .into_iter()
+                .map(|asset| (normalize_value(asset_type, &asset.value), asset))
+                .collect();
+
+            for (value, attributes) in values {
+                let ports = open_ports.remove(&value);
+
+                match existing.remove(&value) {
+                    Some(mut asset) => {
+                        asset.last_seen = now;
+                        asset.updated_at = now;
+                        asset.status = AssetStatus::Active;
+                        if let (Some(ports), Some(attributes)) =
+                            (ports, asset.attributes.as_object_mut())
+                        {
+                            attributes.insert("ports".to_string(), ports.into());
+                        }
+                        // Refresh enrichment looked up during this run
+                        for key in ["whois_info", "asn_info"] {
+                            if let (Some(info), Some(asset_attributes)) =
+                                (attributes.get(key), asset.attributes.as_object_mut())
+                            {
+                                asset_attributes.insert(key.to_string(), info.clone());
+                            }
+                        }
+                        report
+                            .unchanged
+                            .push(self.asset_repository.update_asset(&asset).await?);
+                    }
+                    None => {
+                        let mut attributes = attributes;
+                        if let Some(ports) = ports {
+                            attributes["ports"] = ports.into();
+                        }
+                        let mut asset =
+                            Asset::new(organization_id, asset_type, value, Some(attributes));
+                        asset.first_seen = now;
+                        asset.last_seen = now;
+                        let asset = self.asset_repository.create_asset(&asset).await?;
+
+                        if is_critical_exposure(&asset) {
+                            self.notify_new_critical_asset(&asset).await;
+                        }
+                        report.new.push(asset);
+                    }
+                }
+            }
+
+            report.disappeared.extend(existing.into_values());
+        }
+
+        info!(
+            "Reconciled discovery results for organization {}: {} new, {} unchanged, {} disappeared",
+            organization_id,
+            report.new.len(),
+            report.unchanged.len(),
+            report.disappeared.len()
+        );
+
+        Ok(report)
+    }
+
+    /// Compare the content hashes of the web resources in `result` against
+    /// those stored on the organization's known web app assets, storing the
+    /// hashes that changed. Changes on web apps tagged `critical` are sent
+    /// to the notification service. Must run before the result is
+    /// persisted, which overwrites the stored hashes.
+    pub async fn detect_content_changes(
+        &self,
+        organization_id: ID,
+        result: &DiscoveryResult,
+    ) -> Result<Vec<ContentChange>> {
+        if result
+            .web_resources
+            .iter()
+            .all(|resource| resource.content_hash.is_none())
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut web_apps: HashMap<String, Asset> = self
+            .all_assets(organization_id, AssetType::WebApp, None)
+            .await?
+            .into_iter()
+            .map(|asset| (normalize_value(AssetType::WebApp, &asset.value), asset))
+            .collect();
+
+        let previous: HashMap<String, String> = web_apps
+            .iter()
+            .filter_map(|(url, asset)| {
+                let hash = asset.attributes.get("content_hash")?.as_str()?;
+                Some((url.clone(), hash.to_string()))
+            })
+            .collect();
+        // Crawled URLs are matched in the canonical form assets are stored in
+        let resources: Vec<_> = result
+            .web_resources
+            .iter()
+            .map(|resource| DiscoveredWebResource {
+                url: normalize_value(AssetType::WebApp, &resource.url),
+                ..resource.clone()
+            })
+            .collect();
+        let changes = detect_content_changes(&previous, &resources);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        let critical: Vec<ID> = self
+            .all_assets(organization_id, AssetType::WebApp, Some(CRITICAL_TAG))
+            .await?
+            .into_iter()
+            .map(|asset| asset.id)
+            .collect();
+
+        for change in &changes {
+            let Some(mut asset) = web_apps.remove(&change.url) else {
+                continue;
+            };
+            if let Some(attributes) = asset.attributes.as_object_mut() {
+                attributes.insert(
+                    "content_hash".to_string(),
+                    change.current_hash.clone().into(),
+                );
+            }
+            asset.updated_at = Utc::now();
+            let asset = self.asset_repository.update_asset(&asset).await?;
+
+            if critical.contains(&asset.id) {
+                self.notify_content_change(&asset, &change.previous_hash)
+                    .await;
+            }
+        }
+
+        info!(
+            "Detected content changes on {} web apps for organization {}",
+            changes.len(),
+            organization_id
+        );
+
+        Ok(changes)
+    }
+
+    /// Send a content change alert; failures are logged like those of new
+    /// critical asset alerts
+    async fn notify_content_change(&self, asset: &Asset, previous_hash: &str) {
+        if let Some(notification_service) = &self.notification_service {
+            if let Err(e) = notification_service
+                .notify_content_change(asset, previous_hash)
+                .await
+            {
+                warn!(
+                    "Failed to send content change notification for {}: {}",
+                    asset.value, e
+                );
+            }
+        }
+    }
+
+    /// Send a new critical asset alert; failures are logged, not propagated,
+    /// so a notification outage doesn't lose the reconciliation
+    async fn notify_new_critical_asset(&self, asset: &Asset) {
+        if let Some(notification_service) = &self.notification_service {
+            if let Err(e) = notification_service.notify_new_critical_asset(asset).await {
+                warn!(
+                    "Failed to send new critical asset notification for {}: {}",
+                    asset.value, e
+                );
+            }
         }
     }
 
@@ -39,6 +336,58 @@ impl DiscoveryServiceImpl {
             .await
     }
 
+    /// The targets `job` should scan. An incremental job leaves out targets
+    /// jobs of its type scanned within its freshness window.
+    pub async fn targets_to_scan(
+        &self,
+        job: &DiscoveryJob,
+        targets: Vec<String>,
+    ) -> Result<Vec<String>> {
+        if !job.incremental() || targets.is_empty() {
+            return Ok(targets);
+        }
+
+        let cutoff = Utc::now() - job.freshness_window();
+        let last_scans = self
+            .discovery_job_repository
+            .last_target_scans(job.organization_id, job.job_type, &targets)
+            .await?;
+        let (fresh, stale): (Vec<String>, Vec<String>) = targets
+            .into_iter()
+            .partition(|target| last_scans.get(target).is_some_and(|at| *at > cutoff));
+
+        if !fresh.is_empty() {
+            info!(
+                "Job {} skipping {} targets scanned since {}: {}",
+                job.id,
+                fresh.len(),
+                cutoff,
+                fresh.join(", ")
+            );
+        }
+        Ok(stale)
+    }
+
+    /// Record that `job` scanned `targets`, as of when it started, so later
+    /// incremental jobs can skip them
+    pub async fn record_scanned_targets(
+        &self,
+        job: &DiscoveryJob,
+        targets: &[String],
+    ) -> Result<()> {
+        if targets.is_empty() {
+            return Ok(());
+        }
+        self.discovery_job_repository
+            .record_target_scans(
+                job.organization_id,
+                job.job_type,
+                targets,
+                job.started_at.unwrap_or_else(Utc::now),
+            )
+            .await
+    }
+
     // Update job
     pub async fn update_job(&self, job: &DiscoveryJob) -> Result<DiscoveryJob> {
         self.discovery_job_repository.update_job(job).await
@@ -97,7 +446,15 @@ impl DiscoveryServiceImpl {
         // Get the parent domain asset if it exists
         let _parent_assets = self
             .asset_repository
-            .list_assets(Some(organization_id), Some(AssetType::Domain), None, 1, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(organization_id),
+                    asset_type: Some(AssetType::Domain),
+                    ..Default::default()
+                },
+                1,
+                0,
+            )
             .await?;
 
         // Create and save subdomain assets
@@ -234,14 +591,29 @@ impl DiscoveryService for DiscoveryServiceImpl {
     }
 }
 
+/// Normalize an asset value for comparison across runs. Values stored before
+/// canonicalization that don't parse are compared as they are.
+fn normalize_value(asset_type: AssetType, value: &str) -> String {
+    canonicalize_value(asset_type, value).unwrap_or_else(|_| value.trim().to_string())
+}
+
+/// Whether a newly discovered asset exposes a port from `CRITICAL_PORTS`
+fn is_critical_exposure(asset: &Asset) -> bool {
+    asset
+        .open_ports()
+        .iter()
+        .any(|port| CRITICAL_PORTS.contains(port))
+}
+
 // Basic tests for DiscoveryServiceImpl
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{AssetRelationship, RelationshipDirection};
     use crate::traits::{AssetRepository, DiscoveryJobRepository};
     use mockall::mock;
     use mockall::predicate::*;
-    use shared::types::{AssetStatus, AssetType, JobStatus, JobType};
+    use shared::types::{AssetStatus, JobStatus, JobType};
     use uuid::Uuid;
 
     mock! {
@@ -249,23 +621,55 @@ mod tests {
         #[async_trait]
         impl AssetRepository for AssetRepo {
             async fn create_asset(&self, asset: &Asset) -> Result<Asset>;
+            async fn upsert_asset(&self, asset: &Asset) -> Result<Asset>;
             async fn get_asset(&self, id: Uuid) -> Result<Asset>;
             async fn update_asset(&self, asset: &Asset) -> Result<Asset>;
             async fn delete_asset(&self, id: Uuid) -> Result<bool>;
+            async fn restore_asset(&self, id: Uuid) -> Result<bool>;
             async fn list_assets(
                 &self,
-                organization_id: Option<Uuid>,
-                asset_type: Option<AssetType>,
-                status: Option<AssetStatus>,
+                filter: &crate::models::AssetFilter,
                 limit: usize,
                 offset: usize,
             ) -> Result<Vec<Asset>>;
-            async fn count_assets(
+            async fn count_assets(&self, filter: &crate::models::AssetFilter) -> Result<usize>;
+            async fn add_asset_tags(&self, asset_id: Uuid, tags: &[String]) -> Result<Vec<String>>;
+            async fn remove_asset_tag(&self, asset_id: Uuid, tag: &str) -> Result<bool>;
+            async fn list_asset_tags(&self, asset_id: Uuid) -> Result<Vec<String>>;
+            async fn add_asset_relationship(&self, relationship: &AssetRelationship) -> Result<bool>;
+            async fn add_relationships_bulk(&self, relationships: Vec<(Uuid, Uuid, String)>) -> Result<usize>;
+            async fn remove_asset_relationship(
+                &self,
+                source_asset_id: Uuid,
+                target_asset_id: Uuid,
+                relationship_type: &str,
+            ) -> Result<bool>;
+            async fn list_asset_relationships(
+                &self,
+                asset_id: Uuid,
+                direction: RelationshipDirection,
+                relationship_type: Option<String>,
+                limit: usize,
+                offset: usize,
+            ) -> Result<Vec<(AssetRelationship, Asset)>>;
+            async fn bulk_update_status(
+                &self,
+                organization_id: Uuid,
+                asset_ids: &[Uuid],
+                status: AssetStatus,
+            ) -> Result<usize>;
+            async fn mark_stale(
                 &self,
                 organization_id: Option<Uuid>,
-                asset_type: Option<AssetType>,
-                status: Option<AssetStatus>,
+                older_than: chrono::Duration,
             ) -> Result<usize>;
+            async fn update_risk_score(&self, id: ID, risk_score: f32) -> Result<bool>;
+            async fn list_recent_assets(&self, organization_id: ID, limit: usize) -> Result<Vec<Asset>>;
+            async fn asset_counts_by_day(
+                &self,
+                organization_id: ID,
+                since: chrono::DateTime<chrono::Utc>,
+            ) -> Result<Vec<(chrono::NaiveDate, usize)>>;
         }
     }
 
@@ -279,25 +683,34 @@ mod tests {
             async fn delete_job(&self, id: Uuid) -> Result<bool>;
             async fn list_jobs(
                 &self,
-                organization_id: Option<Uuid>,
-                job_type: Option<JobType>,
-                status: Option<JobStatus>,
+                filter: &crate::models::DiscoveryJobFilter,
+                sort: Option<shared::types::Sort<shared::types::JobSortField>>,
                 limit: usize,
                 offset: usize,
             ) -> Result<Vec<DiscoveryJob>>;
-            async fn count_jobs(
-                &self,
-                organization_id: Option<Uuid>,
-                job_type: Option<JobType>,
-                status: Option<JobStatus>,
-            ) -> Result<usize>;
+            async fn count_jobs(&self, filter: &crate::models::DiscoveryJobFilter) -> Result<usize>;
             async fn list_jobs_by_status(
                 &self,
                 status: JobStatus,
                 limit: usize,
             ) -> Result<Vec<DiscoveryJob>>;
             async fn create_job_asset_link(&self, link: &JobAssetLink) -> Result<JobAssetLink>;
+            async fn link_job_to_assets(&self, job_id: Uuid, asset_ids: &[Uuid]) -> Result<usize>;
             async fn get_job_assets(&self, job_id: Uuid) -> Result<Vec<Asset>>;
+            async fn get_job_summary(&self, job_id: Uuid) -> Result<crate::models::JobResultSummary>;
+            async fn record_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+                scanned_at: chrono::DateTime<chrono::Utc>,
+            ) -> Result<()>;
+            async fn last_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+            ) -> Result<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>>;
         }
     }
 
@@ -1,15 +1,22 @@
 mod asset_service;
+mod attribute_schema;
 mod discovery_service;
 mod notification_service;
 mod organization_service;
+mod relationships;
+mod risk;
 pub mod technology_service;
 mod user_service;
+mod vulnerability_feed;
 mod vulnerability_service;
 
-pub use asset_service::AssetServiceImpl;
-pub use discovery_service::DiscoveryServiceImpl;
-pub use notification_service::NotificationServiceImpl;
+pub use asset_service::{canonicalize_value, AssetServiceImpl};
+pub use attribute_schema::validate_attributes;
+pub use discovery_service::{DiscoveryServiceImpl, ReconciliationReport};
+pub use notification_service::{NotificationServiceImpl, SummaryReport, SummaryStats};
 pub use organization_service::OrganizationServiceImpl;
+pub use risk::{risk_score, CRITICAL_PORTS, MAX_RISK_SCORE};
 pub use technology_service::TechnologyServiceImpl;
 pub use user_service::UserServiceImpl;
+pub use vulnerability_feed::StaticVulnerabilityFeed;
 pub use vulnerability_service::VulnerabilityServiceImpl;
@@ -1,13 +1,18 @@
 use async_trait::async_trait;
 use rand;
-use shared::types::{Severity, VulnerabilityStatus, ID};
-use std::collections::HashMap;
+use serde::Serialize;
+use shared::types::{Severity, Timestamp, VulnerabilityStatus, ID};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use tokio;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     models::{Asset, Vulnerability},
-    traits::{NotificationPeriod, NotificationService, NotificationSettings},
+    traits::{
+        AssetRepository, NotificationLogRepository, NotificationPeriod, NotificationService,
+        NotificationSettings, VulnerabilityRepository,
+    },
     Result,
 };
 
@@ -16,6 +21,35 @@ pub struct NotificationServiceImpl {
     email_client: Option<EmailClient>,
     webhook_client: Option<WebhookClient>,
     settings_cache: HashMap<ID, NotificationSettings>,
+    asset_repository: Arc<dyn AssetRepository>,
+    vulnerability_repository: Arc<dyn VulnerabilityRepository>,
+    notification_log: Option<Arc<dyn NotificationLogRepository>>,
+    suppression_window: chrono::Duration,
+}
+
+/// Counts covered by a summary report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SummaryStats {
+    pub new_vulnerabilities: usize,
+    pub resolved_vulnerabilities: usize,
+    pub critical_vulnerabilities: usize,
+    pub high_vulnerabilities: usize,
+    pub new_assets: usize,
+}
+
+/// A summary report rendered for email
+#[derive(Debug, Clone)]
+pub struct SummaryReport {
+    pub organization_id: ID,
+    pub period: NotificationPeriod,
+    /// Start of the window the report covers
+    pub since: Timestamp,
+    /// End of the window, when the report was generated
+    pub until: Timestamp,
+    pub stats: SummaryStats,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: String,
 }
 
 /// Simple email client for notifications
@@ -39,6 +73,24 @@ impl EmailClient {
         );
         Ok(true)
     }
+
+    async fn send_html_email(
+        &self,
+        to: &[String],
+        subject: &str,
+        text_body: &str,
+        html_body: &str,
+    ) -> Result<bool> {
+        // A real implementation would send both bodies as multipart/alternative
+        info!(
+            "Would send email to {}, subject: {}, text body: {}, html body: {}",
+            to.join(", "),
+            subject,
+            text_body,
+            html_body
+        );
+        Ok(true)
+    }
 }
 
 /// Simple webhook client for notifications
@@ -64,12 +116,162 @@ impl WebhookClient {
 }
 
 impl NotificationServiceImpl {
-    pub fn new() -> Self {
+    /// Summary reports are built from the assets and vulnerabilities in
+    /// these repositories
+    pub fn new(
+        asset_repository: Arc<dyn AssetRepository>,
+        vulnerability_repository: Arc<dyn VulnerabilityRepository>,
+    ) -> Self {
         Self {
             email_client: Some(EmailClient::new()),
             webhook_client: Some(WebhookClient::new()),
             settings_cache: HashMap::new(),
+            asset_repository,
+            vulnerability_repository,
+            notification_log: None,
+            suppression_window: chrono::Duration::zero(),
+        }
+    }
+
+    /// Hold back alerts identical to one an organization was sent less than
+    /// `window` ago, tracking sent alerts in `notification_log`
+    pub fn with_notification_log(
+        mut self,
+        notification_log: Arc<dyn NotificationLogRepository>,
+        window: chrono::Duration,
+    ) -> Self {
+        self.notification_log = Some(notification_log);
+        self.suppression_window = window;
+        self
+    }
+
+    /// Whether an alert should be sent, recording it as sent if so. An alert
+    /// is only held back when an identical one went to the organization
+    /// within the suppression window. If the log can't be checked the alert
+    /// is sent, since a duplicate beats a lost alert.
+    async fn claim_notification(
+        &self,
+        organization_id: ID,
+        fingerprint: &str,
+        event_type: &str,
+    ) -> bool {
+        let Some(notification_log) = &self.notification_log else {
+            return true;
+        };
+        if self.suppression_window <= chrono::Duration::zero() {
+            return true;
+        }
+
+        match notification_log
+            .claim_notification(
+                organization_id,
+                fingerprint,
+                event_type,
+                chrono::Utc::now(),
+                self.suppression_window,
+            )
+            .await
+        {
+            Ok(true) => true,
+            Ok(false) => {
+                debug!(
+                    "Suppressing {} notification already sent to organization {}",
+                    event_type, organization_id
+                );
+                false
+            }
+            Err(e) => {
+                warn!("Failed to check notification log, sending anyway: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Organization owning the asset a vulnerability was found on
+    async fn vulnerability_organization(&self, vulnerability: &Vulnerability) -> Result<ID> {
+        let asset = self
+            .asset_repository
+            .get_asset(vulnerability.asset_id)
+            .await?;
+        Ok(asset.organization_id)
+    }
+
+    /// Gather an organization's activity over `period`, ending now, and
+    /// render it as a text and an HTML email body
+    pub async fn build_summary_report(
+        &self,
+        organization_id: ID,
+        period: NotificationPeriod,
+    ) -> Result<SummaryReport> {
+        let until = chrono::Utc::now();
+        let since = until - period.window();
+
+        let activity = self
+            .vulnerability_repository
+            .activity_since(organization_id, since)
+            .await?;
+        let new_assets = self
+            .asset_repository
+            .asset_counts_by_day(organization_id, since)
+            .await?
+            .into_iter()
+            .map(|(_, count)| count)
+            .sum();
+
+        let stats = SummaryStats {
+            new_vulnerabilities: activity.new,
+            resolved_vulnerabilities: activity.resolved,
+            critical_vulnerabilities: activity.critical,
+            high_vulnerabilities: activity.high,
+            new_assets,
+        };
+
+        let period_str = period_label(period);
+        let subject = format!("[EASM] {} Security Summary Report", period_str);
+        let window = format!(
+            "{} to {}",
+            since.format("%Y-%m-%d %H:%M UTC"),
+            until.format("%Y-%m-%d %H:%M UTC")
+        );
+        let rows = [
+            ("New vulnerabilities", stats.new_vulnerabilities),
+            ("Resolved vulnerabilities", stats.resolved_vulnerabilities),
+            (
+                "New critical vulnerabilities",
+                stats.critical_vulnerabilities,
+            ),
+            ("New high vulnerabilities", stats.high_vulnerabilities),
+            ("New assets", stats.new_assets),
+        ];
+
+        let mut text_body = format!(
+            "{} Security Summary Report for Organization: {}\nPeriod: {}\n\n",
+            period_str, organization_id, window
+        );
+        for (label, count) in rows {
+            text_body.push_str(&format!("{}: {}\n", label, count));
+        }
+
+        let mut html_body = format!(
+            "<html><body><h1>{} Security Summary Report</h1>\
+             <p>Organization: {}<br>Period: {}</p><table>",
+            period_str, organization_id, window
+        );
+        for (label, count) in rows {
+            html_body.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>", label, count));
         }
+        html_body.push_str("</table></body></html>");
+
+        Ok(SummaryReport {
+            organization_id,
+            period,
+            since,
+            until,
+            stats,
+            subject,
+            text_body,
+            html_body,
+        })
     }
 
     /// Check if notification should be sent based on severity
@@ -164,6 +366,44 @@ impl NotificationServiceImpl {
             .unwrap_or_else(|| crate::Error::Internal("Unknown webhook error".to_string())))
     }
 
+    /// Group vulnerabilities by the organization owning their asset, so a
+    /// scan run produces one digest per organization. A vulnerability
+    /// reported more than once is only included once, and those on unknown
+    /// or deleted assets are left out.
+    pub async fn group_by_organization(
+        &self,
+        vulnerabilities: &[Vulnerability],
+    ) -> Result<HashMap<ID, Vec<Vulnerability>>> {
+        let mut asset_ids: Vec<ID> = vulnerabilities.iter().map(|v| v.asset_id).collect();
+        asset_ids.sort();
+        asset_ids.dedup();
+
+        let asset_orgs: HashMap<ID, ID> = self
+            .asset_repository
+            .get_assets(&asset_ids)
+            .await?
+            .into_iter()
+            .map(|asset| (asset.id, asset.organization_id))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut org_vulns: HashMap<ID, Vec<Vulnerability>> = HashMap::new();
+        for vuln in vulnerabilities {
+            if !seen.insert(vuln.id) {
+                continue;
+            }
+            match asset_orgs.get(&vuln.asset_id) {
+                Some(org_id) => org_vulns.entry(*org_id).or_default().push(vuln.clone()),
+                None => debug!(
+                    "Skipping vulnerability {} on unknown asset {}",
+                    vuln.id, vuln.asset_id
+                ),
+            }
+        }
+
+        Ok(org_vulns)
+    }
+
     /// Send batch notifications for multiple vulnerabilities
     async fn send_batch_notification(
         &self,
@@ -206,6 +446,24 @@ impl NotificationServiceImpl {
             return Ok(false);
         }
 
+        // Leave out vulnerabilities the organization was alerted about recently
+        let mut unsent_vulns = Vec::with_capacity(filtered_vulns.len());
+        for vuln in filtered_vulns {
+            let fingerprint = vulnerability_fingerprint(event_type, vuln);
+            if self
+                .claim_notification(organization_id, &fingerprint, event_type)
+                .await
+            {
+                unsent_vulns.push(vuln);
+            }
+        }
+        let filtered_vulns = unsent_vulns;
+
+        if filtered_vulns.is_empty() {
+            debug!("All vulnerabilities in batch were notified recently");
+            return Ok(false);
+        }
+
         // Count vulnerabilities by severity
         let mut severity_counts: HashMap<Severity, usize> = HashMap::new();
         for vuln in &filtered_vulns {
@@ -297,9 +555,23 @@ impl NotificationServiceImpl {
     }
 }
 
-impl Default for NotificationServiceImpl {
-    fn default() -> Self {
-        Self::new()
+/// Identifies an alert about a vulnerability by what was found and where,
+/// so a finding that's reported again under a new ID matches the original
+fn vulnerability_fingerprint(event_type: &str, vulnerability: &Vulnerability) -> String {
+    format!(
+        "{}:{}:{}:{}",
+        event_type,
+        vulnerability.asset_id,
+        vulnerability.cve_id.as_deref().unwrap_or_default(),
+        vulnerability.title
+    )
+}
+
+fn period_label(period: NotificationPeriod) -> &'static str {
+    match period {
+        NotificationPeriod::Daily => "Daily",
+        NotificationPeriod::Weekly => "Weekly",
+        NotificationPeriod::Monthly => "Monthly",
     }
 }
 
@@ -312,9 +584,8 @@ impl NotificationService for NotificationServiceImpl {
         );
 
         // Get notification settings
-        let settings = self
-            .get_notification_settings(vulnerability.asset_id)
-            .await?;
+        let organization_id = self.vulnerability_organization(vulnerability).await?;
+        let settings = self.get_notification_settings(organization_id).await?;
 
         // Check if we should notify based on settings
         if !settings.notify_on_new_vulnerability
@@ -324,6 +595,14 @@ impl NotificationService for NotificationServiceImpl {
             return Ok(false);
         }
 
+        let fingerprint = vulnerability_fingerprint("new_vulnerability", vulnerability);
+        if !self
+            .claim_notification(organization_id, &fingerprint, "new_vulnerability")
+            .await
+        {
+            return Ok(false);
+        }
+
         // Build email content
         let subject = format!(
             "[EASM] New {} vulnerability detected: {}",
@@ -391,7 +670,6 @@ impl NotificationService for NotificationServiceImpl {
         Ok(success)
     }
 
-    // New method to handle batch notifications for multiple new vulnerabilities
     async fn notify_new_vulnerabilities_batch(
         &self,
         vulnerabilities: &[Vulnerability],
@@ -400,18 +678,11 @@ impl NotificationService for NotificationServiceImpl {
             return Ok(true);
         }
 
-        // Group vulnerabilities by organization for more targeted notifications
-        let mut org_vulns: HashMap<ID, Vec<Vulnerability>> = HashMap::new();
-        for vuln in vulnerabilities {
-            org_vulns
-                .entry(vuln.asset_id)
-                .or_default()
-                .push(vuln.clone());
-        }
+        let org_vulns = self.group_by_organization(vulnerabilities).await?;
 
         let mut overall_success = true;
 
-        // Send batch notifications for each organization
+        // Send one digest per organization
         for (org_id, vulns) in org_vulns {
             let success = self
                 .send_batch_notification(&vulns, "new_vulnerability", org_id)
@@ -435,9 +706,8 @@ impl NotificationService for NotificationServiceImpl {
         );
 
         // Get notification settings
-        let settings = self
-            .get_notification_settings(vulnerability.asset_id)
-            .await?;
+        let organization_id = self.vulnerability_organization(vulnerability).await?;
+        let settings = self.get_notification_settings(organization_id).await?;
 
         // Check if we should notify based on settings
         if !settings.notify_on_status_change
@@ -447,6 +717,19 @@ impl NotificationService for NotificationServiceImpl {
             return Ok(false);
         }
 
+        let fingerprint = format!(
+            "{}:{:?}:{:?}",
+            vulnerability_fingerprint("vulnerability_status_change", vulnerability),
+            old_status,
+            vulnerability.status
+        );
+        if !self
+            .claim_notification(organization_id, &fingerprint, "vulnerability_status_change")
+            .await
+        {
+            return Ok(false);
+        }
+
         // Build email content
         let subject = format!(
             "[EASM] Vulnerability status changed: {}",
@@ -521,6 +804,14 @@ impl NotificationService for NotificationServiceImpl {
             return Ok(false);
         }
 
+        let fingerprint = format!("new_critical_asset:{:?}:{}", asset.asset_type, asset.value);
+        if !self
+            .claim_notification(asset.organization_id, &fingerprint, "new_critical_asset")
+            .await
+        {
+            return Ok(false);
+        }
+
         // Build email content
         let subject = format!("[EASM] New critical asset discovered: {}", asset.value);
 
@@ -567,6 +858,89 @@ impl NotificationService for NotificationServiceImpl {
         Ok(success)
     }
 
+    async fn notify_content_change(&self, asset: &Asset, previous_hash: &str) -> Result<bool> {
+        info!(
+            "Preparing notification for content change on critical asset: {} ({})",
+            asset.id, asset.value
+        );
+
+        // Get notification settings
+        let settings = self
+            .get_notification_settings(asset.organization_id)
+            .await?;
+
+        // Check if we should notify based on settings
+        if !settings.notify_on_content_change {
+            debug!("Notification skipped based on settings");
+            return Ok(false);
+        }
+
+        let current_hash = asset
+            .attributes
+            .get("content_hash")
+            .and_then(|hash| hash.as_str())
+            .unwrap_or_default();
+
+        let fingerprint = format!("content_change:{}:{}", asset.id, current_hash);
+        if !self
+            .claim_notification(asset.organization_id, &fingerprint, "content_change")
+            .await
+        {
+            return Ok(false);
+        }
+
+        // Build email content
+        let subject = format!("[EASM] Content changed on critical asset: {}", asset.value);
+
+        let body = format!(
+            "The content of a critical asset changed since the last scan:\n\nAsset: {}\nType: {:?}\nID: {}\nOrganization ID: {}\nPrevious hash: {}\nCurrent hash: {}",
+            asset.value,
+            asset.asset_type,
+            asset.id,
+            asset.organization_id,
+            previous_hash,
+            current_hash
+        );
+
+        // Build webhook payload
+        let payload = self.create_notification_payload(
+            "content_change",
+            &serde_json::json!({
+                "asset": asset,
+                "previous_hash": previous_hash,
+                "current_hash": current_hash,
+            }),
+        );
+
+        // Send notifications based on settings
+        let mut success = true;
+
+        if settings.email_notifications && !settings.email_recipients.is_empty() {
+            if let Some(client) = &self.email_client {
+                if let Err(e) = client
+                    .send_email(&settings.email_recipients, &subject, &body)
+                    .await
+                {
+                    debug!("Failed to send email notification: {:?}", e);
+                    success = false;
+                }
+            }
+        }
+
+        if settings.webhook_notifications {
+            if let Some(url) = &settings.webhook_url {
+                if let Some(client) = &self.webhook_client {
+                    if let Err(e) = client.send_webhook(url, &payload).await {
+                        debug!("Failed to send webhook notification: {:?}", e);
+                        success = false;
+                    }
+                }
+            }
+        }
+
+        Ok(success)
+    }
+
     async fn send_summary_report(
         &self,
         organization_id: ID,
@@ -585,36 +959,17 @@ impl NotificationService for NotificationServiceImpl {
             return Ok(false);
         }
 
-        // In a real implementation, we would generate a report based on recent data
-        // For now, we'll create a placeholder report
-
-        let period_str = match period {
-            NotificationPeriod::Daily => "Daily",
-            NotificationPeriod::Weekly => "Weekly",
-            NotificationPeriod::Monthly => "Monthly",
-        };
-
-        let subject = format!("[EASM] {} Security Summary Report", period_str);
-
-        let body = format!(
-            "{} Security Summary Report for Organization: {}\n\n",
-            period_str, organization_id
-        );
+        let report = self.build_summary_report(organization_id, period).await?;
+        let period_str = period_label(period);
 
         let payload = self.create_notification_payload(
             &format!("{}_summary_report", period_str.to_lowercase()),
             &serde_json::json!({
                 "organization_id": organization_id.to_string(),
                 "period": period_str,
-                "generated_at": chrono::Utc::now().to_rfc3339(),
-                // In a real implementation, this would include actual statistics
-                "stats": {
-                    "new_vulnerabilities": 0,
-                    "resolved_vulnerabilities": 0,
-                    "critical_vulnerabilities": 0,
-                    "high_vulnerabilities": 0,
-                    "new_assets": 0
-                }
+                "since": report.since.to_rfc3339(),
+                "generated_at": report.until.to_rfc3339(),
+                "stats": report.stats,
             }),
         );
 
@@ -624,7 +979,12 @@ impl NotificationService for NotificationServiceImpl {
         if settings.email_notifications && !settings.email_recipients.is_empty() {
             if let Some(client) = &self.email_client {
                 if let Err(e) = client
-                    .send_email(&settings.email_recipients, &subject, &body)
+                    .send_html_email(
+                        &settings.email_recipients,
+                        &report.subject,
+                        &report.text_body,
+                        &report.html_body,
+                    )
                     .await
                 {
                     debug!("Failed to send email notification: {:?}", e);
@@ -660,6 +1020,7 @@ impl NotificationService for NotificationServiceImpl {
             notify_on_new_vulnerability: true,
             notify_on_status_change: true,
             notify_on_new_critical_asset: true,
+            notify_on_content_change: true,
             minimum_severity_for_notification: Severity::Medium,
             additional_settings: None,
         };
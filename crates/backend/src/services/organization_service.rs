@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use crate::{
-    errors::Result,
+    errors::{Error, Result},
     models::Organization,
     traits::{OrganizationRepository, OrganizationService},
 };
@@ -17,12 +17,28 @@ impl OrganizationServiceImpl {
     pub fn new(repo: Arc<dyn OrganizationRepository>) -> Self {
         Self { repo }
     }
+
+    /// Reject an empty name, or one taken by an organization other than `id`
+    async fn check_name(&self, name: &str, id: Uuid) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(Error::Validation(
+                "Organization name cannot be empty".to_string(),
+            ));
+        }
+        match self.repo.get_organization_by_name(name).await? {
+            Some(existing) if existing.id != id => Err(Error::Conflict(format!(
+                "Organization '{}' already exists",
+                name
+            ))),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[async_trait]
 impl OrganizationService for OrganizationServiceImpl {
     async fn create_organization(&self, organization: &Organization) -> Result<Organization> {
-        // Add validation if needed
+        self.check_name(&organization.name, organization.id).await?;
         self.repo.create_organization(organization).await
     }
 
@@ -31,7 +47,7 @@ impl OrganizationService for OrganizationServiceImpl {
     }
 
     async fn update_organization(&self, organization: &Organization) -> Result<Organization> {
-        // Add validation if needed
+        self.check_name(&organization.name, organization.id).await?;
         self.repo.update_organization(organization).await
     }
 
@@ -0,0 +1,238 @@
+//! Inferring relationships between an organization's assets
+//!
+//! Relationships come from asset values and attributes: a domain is a
+//! subdomain of any domain its value ends in, a web app is hosted on the IP
+//! and domain in its `host_info`, a certificate secures the domains it
+//! names, and so on. Rather than comparing every asset with every other,
+//! the assets are first indexed by the values they are looked up by, so
+//! each asset only needs a handful of lookups. A domain's parents are found
+//! by looking up each of its suffixes: `a.b.example.com` checks
+//! `b.example.com`, `example.com` and `com`.
+//!
+//! Relationships between assets sharing an attribute (same registrar, same
+//! issuer, same network) are still produced for every pair in a group, as
+//! that is what they are, but only assets in the same group are paired.
+
+use std::collections::{HashMap, HashSet};
+
+use shared::types::{AssetType, ID};
+
+use crate::models::{Asset, AssetRelationshipType};
+
+/// A discovered relationship: source, target and relationship type
+pub(crate) type Relationship = (ID, ID, String);
+
+/// Parent domains of `domain`, nearest first
+fn parent_domains(domain: &str) -> impl Iterator<Item = &str> {
+    domain
+        .match_indices('.')
+        .map(move |(dot, _)| &domain[dot + 1..])
+        .filter(|parent| !parent.is_empty())
+}
+
+/// `domain` followed by its parent domains
+fn domain_and_parents(domain: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(domain).chain(parent_domains(domain))
+}
+
+/// String attribute `field` of the `object` attribute of `asset`
+fn nested_str<'a>(asset: &'a Asset, object: &str, field: &str) -> Option<&'a str> {
+    asset.attributes.get(object)?.get(field)?.as_str()
+}
+
+/// Assets grouped by value, keeping the order they were listed in
+fn index_by_value<'a>(assets: &[&'a Asset]) -> HashMap<&'a str, Vec<ID>> {
+    let mut index: HashMap<&str, Vec<ID>> = HashMap::new();
+    for asset in assets {
+        index
+            .entry(asset.value.as_str())
+            .or_default()
+            .push(asset.id);
+    }
+    index
+}
+
+/// Every ordered pair of distinct assets that share a key
+fn pair_within_groups<'a>(
+    keyed: impl Iterator<Item = (&'a str, ID)>,
+    relationship_type: AssetRelationshipType,
+    relationships: &mut Vec<Relationship>,
+) {
+    let mut groups: HashMap<&str, Vec<ID>> = HashMap::new();
+    let mut order = Vec::new();
+    for (key, id) in keyed {
+        let group = groups.entry(key).or_default();
+        if group.is_empty() {
+            order.push(key);
+        }
+        group.push(id);
+    }
+
+    let relationship_type = relationship_type.as_str();
+    for key in order {
+        let group = &groups[key];
+        for a in group {
+            for b in group {
+                if a != b {
+                    relationships.push((*a, *b, relationship_type.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// Relationships between `assets`, without duplicates
+pub(crate) fn discover_relationships(assets: &[Asset]) -> Vec<Relationship> {
+    let of_type = |asset_type: AssetType| -> Vec<&Asset> {
+        assets
+            .iter()
+            .filter(|asset| asset.asset_type == asset_type)
+            .collect()
+    };
+    let domains = of_type(AssetType::Domain);
+    let ips = of_type(AssetType::IPAddress);
+    let web_apps = of_type(AssetType::WebApp);
+    let certificates = of_type(AssetType::Certificate);
+    let cloud_resources = of_type(AssetType::CloudResource);
+
+    let domains_by_value = index_by_value(&domains);
+    let ips_by_value = index_by_value(&ips);
+
+    // Web apps by their domain and each of its parents, and by hostname
+    let mut web_apps_by_domain: HashMap<&str, Vec<ID>> = HashMap::new();
+    let mut web_apps_by_hostname: HashMap<&str, Vec<ID>> = HashMap::new();
+    for web_app in &web_apps {
+        if let Some(domain) = nested_str(web_app, "host_info", "domain") {
+            for key in domain_and_parents(domain) {
+                web_apps_by_domain.entry(key).or_default().push(web_app.id);
+            }
+        }
+        if let Some(hostname) = nested_str(web_app, "host_info", "hostname") {
+            web_apps_by_hostname
+                .entry(hostname)
+                .or_default()
+                .push(web_app.id);
+        }
+    }
+
+    // The domain asset for `host`: an exact match, else the nearest parent
+    let domain_for_host = |host: &str| -> Option<ID> {
+        domain_and_parents(host)
+            .find_map(|candidate| domains_by_value.get(candidate))
+            .map(|ids| ids[0])
+    };
+    let first_ip = |value: &str| ips_by_value.get(value).map(|ids| ids[0]);
+
+    let mut relationships = Vec::new();
+
+    // Subdomains, and domains sharing a registrar
+    let subdomain = AssetRelationshipType::Subdomain.as_str();
+    for domain in &domains {
+        for parent in parent_domains(&domain.value) {
+            for parent_id in domains_by_value.get(parent).into_iter().flatten() {
+                relationships.push((domain.id, *parent_id, subdomain.clone()));
+            }
+        }
+    }
+    pair_within_groups(
+        domains.iter().filter_map(|domain| {
+            let registrar = nested_str(domain, "whois_info", "registrar")?;
+            (!registrar.is_empty()).then_some((registrar, domain.id))
+        }),
+        AssetRelationshipType::SameRegistrar,
+        &mut relationships,
+    );
+
+    // Web apps hosted on IPs and domains, and the domains they load from
+    let hosted_on = AssetRelationshipType::HostedOn.as_str();
+    let depends_on = AssetRelationshipType::DependsOn.as_str();
+    for web_app in &web_apps {
+        if let Some(ip_id) = nested_str(web_app, "host_info", "ip_address").and_then(first_ip) {
+            relationships.push((web_app.id, ip_id, hosted_on.clone()));
+        }
+        if let Some(domain_id) =
+            nested_str(web_app, "host_info", "domain").and_then(domain_for_host)
+        {
+            relationships.push((web_app.id, domain_id, hosted_on.clone()));
+        }
+
+        let dependencies = web_app
+            .attributes
+            .get("dependencies")
+            .and_then(|dependencies| dependencies.as_array());
+        for dependency in dependencies.into_iter().flatten() {
+            let Some(url) = dependency
+                .get("url")
+                .and_then(|url| url.as_str())
+                .and_then(|url| url::Url::parse(url).ok())
+            else {
+                continue;
+            };
+            if let Some(domain_id) = url.host_str().and_then(domain_for_host) {
+                relationships.push((web_app.id, domain_id, depends_on.clone()));
+            }
+        }
+    }
+
+    // Certificates secure the domains they name, including through their
+    // parents, and the web apps on those domains
+    let secures = AssetRelationshipType::Secures.as_str();
+    for certificate in &certificates {
+        let names = certificate
+            .attributes
+            .get("certificate_info")
+            .and_then(|info| info.get("domains"))
+            .and_then(|domains| domains.as_array());
+        for name in names.into_iter().flatten().filter_map(|name| name.as_str()) {
+            for candidate in domain_and_parents(name) {
+                for domain_id in domains_by_value.get(candidate).into_iter().flatten() {
+                    relationships.push((certificate.id, *domain_id, secures.clone()));
+                }
+            }
+            for web_app_id in web_apps_by_domain.get(name).into_iter().flatten() {
+                relationships.push((certificate.id, *web_app_id, secures.clone()));
+            }
+        }
+    }
+    pair_within_groups(
+        certificates.iter().filter_map(|certificate| {
+            Some((
+                nested_str(certificate, "certificate_info", "issuer")?,
+                certificate.id,
+            ))
+        }),
+        AssetRelationshipType::SameIssuer,
+        &mut relationships,
+    );
+
+    // Cloud resources in the same network, and the IPs, web apps and
+    // domains they are reachable through
+    pair_within_groups(
+        cloud_resources.iter().filter_map(|resource| {
+            Some((nested_str(resource, "cloud_info", "vpc_id")?, resource.id))
+        }),
+        AssetRelationshipType::SameNetwork,
+        &mut relationships,
+    );
+    let has_public_ip = AssetRelationshipType::HasPublicIP.as_str();
+    let belongs_to = AssetRelationshipType::BelongsTo.as_str();
+    for resource in &cloud_resources {
+        if let Some(ip_id) = nested_str(resource, "cloud_info", "public_ip").and_then(first_ip) {
+            relationships.push((resource.id, ip_id, has_public_ip.clone()));
+        }
+        if let Some(dns_name) = nested_str(resource, "cloud_info", "dns_name") {
+            for web_app_id in web_apps_by_hostname.get(dns_name).into_iter().flatten() {
+                relationships.push((*web_app_id, resource.id, hosted_on.clone()));
+            }
+            for parent in parent_domains(dns_name) {
+                for domain_id in domains_by_value.get(parent).into_iter().flatten() {
+                    relationships.push((resource.id, *domain_id, belongs_to.clone()));
+                }
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    relationships.retain(|relationship| seen.insert(relationship.clone()));
+    relationships
+}
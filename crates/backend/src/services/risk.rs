@@ -0,0 +1,141 @@
+//! Per-asset risk scoring
+//!
+//! An asset's risk score summarizes how exposed it is on a 0 to 100 scale:
+//!
+//! ```text
+//! score = min(100, Σ severity weight of each open vulnerability
+//!                + 10 per open port from CRITICAL_PORTS
+//!                +  2 per other open port
+//!                + 15 per open port with weak TLS)
+//! ```
+//!
+//! with severity weights critical = 40, high = 20, medium = 8, low = 3 and
+//! info = 0. A single critical finding outweighs any number of ordinary web
+//! ports, and three criticals saturate the scale. Vulnerabilities that are
+//! closed, accepted or false positives don't count.
+//!
+//! A port has weak TLS when the `tls` details port scans record on it show
+//! a certificate that has expired or is self-signed (issued by its own
+//! subject), or a service that still accepts TLS 1.1 or older.
+
+use chrono::{DateTime, Utc};
+use shared::types::{Severity, VulnerabilityStatus, ID};
+
+use crate::{
+    models::{Asset, Vulnerability},
+    traits::{AssetRepository, VulnerabilityRepository},
+    Result,
+};
+
+/// Ports whose exposure to the internet is a finding in itself: remote
+/// administration, file sharing, databases and caches
+pub const CRITICAL_PORTS: &[u16] = &[
+    22, 23, 445, 1433, 2375, 3306, 3389, 5432, 5900, 6379, 9200, 11211, 27017,
+];
+
+/// Highest possible risk score
+pub const MAX_RISK_SCORE: f32 = 100.0;
+
+const CRITICAL_PORT_WEIGHT: f32 = 10.0;
+const OPEN_PORT_WEIGHT: f32 = 2.0;
+const WEAK_TLS_WEIGHT: f32 = 15.0;
+
+/// Upper bound on the open vulnerabilities loaded to score an asset. The
+/// score saturates long before it's reached.
+const RISK_VULNERABILITY_LIMIT: usize = 1_000;
+
+fn severity_weight(severity: Severity) -> f32 {
+    match severity {
+        Severity::Critical => 40.0,
+        Severity::High => 20.0,
+        Severity::Medium => 8.0,
+        Severity::Low => 3.0,
+        Severity::Info => 0.0,
+    }
+}
+
+/// Risk score of `asset` given its vulnerabilities, following the formula in
+/// the module documentation
+pub fn risk_score(asset: &Asset, vulnerabilities: &[Vulnerability]) -> f32 {
+    let vulnerability_score: f32 = vulnerabilities
+        .iter()
+        .filter(|vulnerability| vulnerability.status == VulnerabilityStatus::Open)
+        .map(|vulnerability| severity_weight(vulnerability.severity))
+        .sum();
+
+    let port_score: f32 = asset
+        .open_ports()
+        .into_iter()
+        .map(|port| {
+            if CRITICAL_PORTS.contains(&port) {
+                CRITICAL_PORT_WEIGHT
+            } else {
+                OPEN_PORT_WEIGHT
+            }
+        })
+        .sum();
+
+    let now = Utc::now();
+    let weak_tls_ports = asset
+        .open_port_attributes()
+        .into_iter()
+        .filter_map(|port| port.get("tls"))
+        .filter(|tls| has_weak_tls(tls, now))
+        .count();
+    let tls_score = weak_tls_ports as f32 * WEAK_TLS_WEIGHT;
+
+    (vulnerability_score + port_score + tls_score).min(MAX_RISK_SCORE)
+}
+
+/// Whether a port's recorded TLS details, as of `now`, show an expired or
+/// self-signed certificate or a legacy protocol
+fn has_weak_tls(tls: &serde_json::Value, now: DateTime<Utc>) -> bool {
+    let field = |name: &str| tls.get(name).and_then(|value| value.as_str());
+
+    let expired = field("not_after")
+        .and_then(|not_after| DateTime::parse_from_rfc3339(not_after).ok())
+        .is_some_and(|not_after| not_after < now);
+    let self_signed = matches!(
+        (field("subject"), field("issuer")),
+        (Some(subject), Some(issuer)) if !subject.is_empty() && subject == issuer
+    );
+    let legacy_protocol = tls
+        .get("legacy_protocol")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+
+    expired || self_signed || legacy_protocol
+}
+
+/// Recompute the risk score of an asset from its current open
+/// vulnerabilities and ports, storing it when it changed
+pub(crate) async fn refresh_risk_score(
+    asset_repository: &dyn AssetRepository,
+    vulnerability_repository: &dyn VulnerabilityRepository,
+    asset_id: ID,
+) -> Result<f32> {
+    let asset = asset_repository.get_asset(asset_id).await?;
+    let vulnerabilities = vulnerability_repository
+        .list_vulnerabilities(
+            Some(asset_id),
+            None,
+            None,
+            Some(VulnerabilityStatus::Open),
+            false,
+            RISK_VULNERABILITY_LIMIT,
+            0,
+        )
+        .await?;
+
+    let score = risk_score(&asset, &vulnerabilities);
+    if score != asset.risk_score {
+        asset_repository.update_risk_score(asset_id, score).await?;
+        tracing::debug!(
+            "Risk score of asset {} changed from {} to {}",
+            asset_id,
+            asset.risk_score,
+            score
+        );
+    }
+    Ok(score)
+}
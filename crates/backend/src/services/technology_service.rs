@@ -1,17 +1,29 @@
 use async_trait::async_trait;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tracing::{debug, info};
 use uuid::Uuid;
 
 use crate::{
-    models::Technology,
-    traits::{AssetRepository, TechnologyRepository, TechnologyService},
-    Result,
+    models::{AssetFilter, KnownVulnerability, Technology, Vulnerability},
+    services::{risk::refresh_risk_score, StaticVulnerabilityFeed},
+    traits::{
+        AssetRepository, TechnologyRepository, TechnologyService, VulnerabilityFeed,
+        VulnerabilityRepository,
+    },
+    Error, Result,
 };
 
+/// Most of an asset's existing vulnerabilities checked for duplicate CVEs
+const EXISTING_VULNERABILITY_LIMIT: usize = 1000;
+
 pub struct TechnologyServiceImpl {
     repository: Arc<dyn TechnologyRepository>,
     asset_repository: Arc<dyn AssetRepository>,
+    vulnerability_feed: Arc<dyn VulnerabilityFeed>,
+    vulnerability_repository: Option<Arc<dyn VulnerabilityRepository>>,
 }
 
 impl TechnologyServiceImpl {
@@ -22,8 +34,29 @@ impl TechnologyServiceImpl {
         Self {
             repository,
             asset_repository,
+            vulnerability_feed: Arc::new(StaticVulnerabilityFeed::bundled()),
+            vulnerability_repository: None,
         }
     }
+
+    /// Match technologies against `vulnerability_feed` instead of the
+    /// bundled feed
+    pub fn with_vulnerability_feed(
+        mut self,
+        vulnerability_feed: Arc<dyn VulnerabilityFeed>,
+    ) -> Self {
+        self.vulnerability_feed = vulnerability_feed;
+        self
+    }
+
+    /// Record known vulnerabilities in `vulnerability_repository`
+    pub fn with_vulnerability_repository(
+        mut self,
+        vulnerability_repository: Arc<dyn VulnerabilityRepository>,
+    ) -> Self {
+        self.vulnerability_repository = Some(vulnerability_repository);
+        self
+    }
 }
 
 #[async_trait]
@@ -92,7 +125,14 @@ impl TechnologyService for TechnologyServiceImpl {
         // Get assets for the organization
         let assets = self
             .asset_repository
-            .list_assets(Some(organization_id), None, None, 1000, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(organization_id),
+                    ..Default::default()
+                },
+                1000,
+                0,
+            )
             .await?;
 
         if assets.is_empty() {
@@ -126,4 +166,102 @@ impl TechnologyService for TechnologyServiceImpl {
         debug!("Technology statistics: {:?}", stats);
         Ok(stats)
     }
+
+    async fn find_known_vulnerabilities(
+        &self,
+        technology: &Technology,
+    ) -> Result<Vec<KnownVulnerability>> {
+        let Some(version) = &technology.version else {
+            return Ok(Vec::new());
+        };
+
+        let known = self
+            .vulnerability_feed
+            .find(&technology.name, version)
+            .await?;
+        debug!(
+            "Found {} known vulnerabilities for {} {}",
+            known.len(),
+            technology.name,
+            version
+        );
+        Ok(known)
+    }
+
+    async fn record_known_vulnerabilities(
+        &self,
+        technology: &Technology,
+    ) -> Result<Vec<Vulnerability>> {
+        let known = self.find_known_vulnerabilities(technology).await?;
+        if known.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let vulnerability_repository = self.vulnerability_repository.as_ref().ok_or_else(|| {
+            Error::Internal(
+                "Recording known vulnerabilities needs a vulnerability repository".to_string(),
+            )
+        })?;
+
+        // Deleted ones count too, so a dismissed finding isn't brought back
+        let existing: HashSet<String> = vulnerability_repository
+            .list_vulnerabilities(
+                Some(technology.asset_id),
+                None,
+                None,
+                None,
+                true,
+                EXISTING_VULNERABILITY_LIMIT,
+                0,
+            )
+            .await?
+            .into_iter()
+            .filter_map(|vulnerability| vulnerability.cve_id)
+            .collect();
+
+        let mut created = Vec::new();
+        for entry in known {
+            if existing.contains(&entry.cve_id) {
+                continue;
+            }
+
+            let mut vulnerability = Vulnerability::new(
+                technology.asset_id,
+                None,
+                entry.title,
+                entry.description,
+                entry.severity,
+                Some(entry.cve_id),
+                Some(serde_json::json!({
+                    "affected_technology": technology.name,
+                    "version": technology.version,
+                    "cpe": entry.cpe,
+                    "source": "vulnerability_feed",
+                })),
+                entry.remediation,
+            );
+            vulnerability.cvss_score = entry.cvss_score;
+            created.push(
+                vulnerability_repository
+                    .create_vulnerability(&vulnerability)
+                    .await?,
+            );
+        }
+
+        if !created.is_empty() {
+            info!(
+                "Recorded {} known vulnerabilities of {} on asset {}",
+                created.len(),
+                technology.name,
+                technology.asset_id
+            );
+            refresh_risk_score(
+                self.asset_repository.as_ref(),
+                vulnerability_repository.as_ref(),
+                technology.asset_id,
+            )
+            .await?;
+        }
+        Ok(created)
+    }
 }
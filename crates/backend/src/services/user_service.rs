@@ -4,12 +4,8 @@ use crate::{
     models::User,
     traits::{OrganizationRepository, UserRepository, UserService},
 };
-use argon2::{
-    self,
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
-};
 use async_trait::async_trait;
+use shared::types::UserRole;
 use std::sync::Arc;
 use uuid;
 
@@ -37,28 +33,83 @@ impl UserService for UserServiceImpl {
         organization_id: &uuid::Uuid,
         email: &str,
         password: &str,
+    ) -> Result<User> {
+        // Self-registered users start out as analysts
+        self.create_user(*organization_id, email, password, UserRole::Analyst)
+            .await
+    }
+
+    async fn login_user(&self, email: &str, password: &str) -> Result<User> {
+        // Find user by email
+        let user = self
+            .repository
+            .find_by_email(email)
+            .await?
+            .ok_or_else(|| BackendError::NotFound("User not found".to_string()))?;
+
+        // Verify password
+        if !user.verify_password(password)? {
+            return Err(BackendError::Authentication(
+                "Invalid credentials".to_string(),
+            ));
+        }
+
+        // Only said once the password is right, so it doesn't reveal accounts
+        if !user.active {
+            return Err(BackendError::Authentication(
+                "Account is deactivated".to_string(),
+            ));
+        }
+
+        Ok(user)
+    }
+
+    async fn get_user(&self, id: uuid::Uuid) -> Result<User> {
+        self.repository.get_user(id).await
+    }
+
+    async fn list_users(
+        &self,
+        organization_id: uuid::Uuid,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<User>> {
+        self.repository
+            .list_users(Some(organization_id), None, limit, offset)
+            .await
+    }
+
+    async fn count_users(&self, organization_id: uuid::Uuid) -> Result<usize> {
+        self.repository
+            .count_users(Some(organization_id), None)
+            .await
+    }
+
+    async fn create_user(
+        &self,
+        organization_id: uuid::Uuid,
+        email: &str,
+        password: &str,
+        role: UserRole,
     ) -> Result<User> {
         // Check if organization exists
         self.org_repository
-            .get_organization(*organization_id)
+            .get_organization(organization_id)
             .await?;
 
         // Hash password
-        let password_hash = hash_password(password)?;
+        User::validate_password(password)?;
+        let password_hash = User::hash_password(password)?;
 
         // Create user object
         let username = email.split('@').next().unwrap_or(email).to_string();
 
         let user = User::new(
-            *organization_id,
+            organization_id,
             username,
             email.to_string(),
             password_hash,
-            Some(
-                "analyst"
-                    .parse()
-                    .map_err(|_| BackendError::Internal("Invalid default role".to_string()))?,
-            ),
+            Some(role),
         );
 
         // Use atomic operation to check email and create user
@@ -67,79 +118,41 @@ impl UserService for UserServiceImpl {
         Ok(created_user)
     }
 
-    async fn login_user(&self, email: &str, password: &str) -> Result<User> {
-        // Find user by email
-        let user = self
-            .repository
-            .find_by_email(email)
-            .await?
-            .ok_or_else(|| BackendError::NotFound("User not found".to_string()))?;
+    async fn update_user(&self, user: &User) -> Result<User> {
+        if user.username.trim().is_empty() || user.email.trim().is_empty() {
+            return Err(BackendError::Validation(
+                "Username and email cannot be empty".to_string(),
+            ));
+        }
 
-        // Verify password
-        if verify_password(&user.password_hash, password)? {
-            Ok(user)
-        } else {
-            Err(BackendError::Authentication(
-                "Invalid credentials".to_string(),
-            ))
+        if let Some(existing) = self.repository.find_by_email(&user.email).await? {
+            if existing.id != user.id {
+                return Err(BackendError::Conflict("Email already exists".to_string()));
+            }
         }
+
+        self.repository.update_user(user).await
     }
-}
 
-// Helper function for password hashing
-fn hash_password(password: &str) -> Result<String> {
-    let salt = SaltString::generate(&mut OsRng);
-
-    // Configure Argon2 with explicit parameters for better security
-    let argon2 = Argon2::new(
-        argon2::Algorithm::Argon2id, // Argon2id variant (more secure)
-        argon2::Version::V0x13,      // Latest version
-        argon2::Params::new(
-            65536,    // Memory cost (64MB)
-            2,        // Iterations
-            1,        // Parallelism
-            Some(32), // Output size (32 bytes)
-        )
-        .map_err(|e| BackendError::Internal(format!("Invalid Argon2 parameters: {}", e)))?,
-    );
-
-    // Hash password to PHC string ($argon2id$v=19$...) format
-    let password_hash = argon2
-        .hash_password(password.as_bytes(), &salt)
-        .map_err(|e| BackendError::Internal(format!("Password hashing failed: {}", e)))?
-        .to_string();
-
-    Ok(password_hash)
-}
+    async fn change_password(
+        &self,
+        id: uuid::Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<()> {
+        let mut user = self.repository.get_user(id).await?;
+
+        if !user.verify_password(current_password)? {
+            return Err(BackendError::Authentication(
+                "Invalid credentials".to_string(),
+            ));
+        }
+
+        User::validate_password(new_password)?;
+        user.set_password(new_password)?;
+        user.updated_at = chrono::Utc::now();
+        self.repository.update_user(&user).await?;
 
-// Helper function to verify password
-fn verify_password(hash: &str, password: &str) -> Result<bool> {
-    let parsed_hash = PasswordHash::new(hash)
-        .map_err(|e| BackendError::Internal(format!("Invalid password hash format: {}", e)))?;
-
-    // Configure Argon2 with the same parameters used for hashing
-    let argon2 = Argon2::new(
-        argon2::Algorithm::Argon2id, // Same algorithm
-        argon2::Version::V0x13,      // Same version
-        argon2::Params::new(
-            65536,    // Same memory cost
-            2,        // Same iterations
-            1,        // Same parallelism
-            Some(32), // Same output size
-        )
-        .map_err(|e| BackendError::Internal(format!("Invalid Argon2 parameters: {}", e)))?,
-    );
-
-    // Verify password against hash
-    match argon2.verify_password(password.as_bytes(), &parsed_hash) {
-        Ok(_) => Ok(true),                                        // Password matches
-        Err(argon2::password_hash::Error::Password) => Ok(false), // Password does not match
-        Err(e) => Err(BackendError::Internal(format!(
-            "Password verification failed unexpectedly: {}",
-            e
-        ))), // Other errors (e.g., invalid hash format)
+        Ok(())
     }
 }
-
-// Helper function to generate salt - No longer needed with PasswordHasher
-// fn generate_salt() -> Result<Vec<u8>> { ... }
@@ -0,0 +1,55 @@
+//! Known vulnerabilities of detected technologies
+//!
+//! Fingerprinting records the technologies an asset runs and, often, their
+//! versions. A feed lists published vulnerabilities with the product (as a
+//! CPE name) and version ranges they affect, so a detected technology can
+//! be checked against it.
+
+use async_trait::async_trait;
+
+use crate::{models::KnownVulnerability, traits::VulnerabilityFeed, Error, Result};
+
+/// Vulnerabilities shipped with the application, for well-known flaws in
+/// common web servers and libraries
+const BUNDLED_FEED: &str = include_str!("known_vulnerabilities.json");
+
+/// A feed held in memory, loaded from a JSON list of [`KnownVulnerability`]
+pub struct StaticVulnerabilityFeed {
+    entries: Vec<KnownVulnerability>,
+}
+
+impl StaticVulnerabilityFeed {
+    pub fn new(entries: Vec<KnownVulnerability>) -> Self {
+        Self { entries }
+    }
+
+    /// The feed shipped with the application
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_FEED).expect("bundled vulnerability feed is valid")
+    }
+
+    /// Load a feed from a JSON list of entries
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries = serde_json::from_str(json)
+            .map_err(|e| Error::Validation(format!("Invalid vulnerability feed: {}", e)))?;
+        Ok(Self::new(entries))
+    }
+}
+
+impl Default for StaticVulnerabilityFeed {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+#[async_trait]
+impl VulnerabilityFeed for StaticVulnerabilityFeed {
+    async fn find(&self, name: &str, version: &str) -> Result<Vec<KnownVulnerability>> {
+        Ok(self
+            .entries
+            .iter()
+            .filter(|entry| entry.affects(name, version))
+            .cloned()
+            .collect())
+    }
+}
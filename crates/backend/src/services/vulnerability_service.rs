@@ -1,11 +1,15 @@
 use async_trait::async_trait;
-use shared::types::{Severity, VulnerabilityStatus, ID};
-use std::{collections::HashMap, sync::Arc};
+use shared::types::{Severity, Sort, VulnerabilitySortField, VulnerabilityStatus, ID};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tracing::{debug, info};
 
 use crate::{
     errors::Error,
-    models::Vulnerability,
+    models::{AssetFilter, Vulnerability, VulnerabilityGroup},
+    services::risk::refresh_risk_score,
     traits::{AssetRepository, VulnerabilityRepository, VulnerabilityService},
     Result,
 };
@@ -26,6 +30,17 @@ impl VulnerabilityServiceImpl {
         }
     }
 
+    /// Keep the risk score of an asset in step with its vulnerabilities
+    async fn refresh_asset_risk(&self, asset_id: ID) -> Result<()> {
+        refresh_risk_score(
+            self.asset_repository.as_ref(),
+            self.repository.as_ref(),
+            asset_id,
+        )
+        .await?;
+        Ok(())
+    }
+
     // Helper function to calculate similarity score between two vulnerabilities
     async fn calculate_similarity_score(
         &self,
@@ -279,7 +294,9 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
             enriched.cvss_score = Some(risk_score);
         }
 
-        self.repository.create_vulnerability(&enriched).await
+        let created = self.repository.create_vulnerability(&enriched).await?;
+        self.refresh_asset_risk(created.asset_id).await?;
+        Ok(created)
     }
 
     async fn get_vulnerability(&self, id: ID) -> Result<Vulnerability> {
@@ -297,12 +314,25 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
             updated.cvss_score = Some(risk_score);
         }
 
-        self.repository.update_vulnerability(&updated).await
+        let updated = self.repository.update_vulnerability(&updated).await?;
+        self.refresh_asset_risk(updated.asset_id).await?;
+        Ok(updated)
     }
 
     async fn delete_vulnerability(&self, id: ID) -> Result<bool> {
         info!("Deleting vulnerability with ID: {}", id);
-        self.repository.delete_vulnerability(id).await
+        let asset_id = self
+            .repository
+            .get_vulnerability(id)
+            .await
+            .ok()
+            .map(|vulnerability| vulnerability.asset_id);
+
+        let deleted = self.repository.delete_vulnerability(id).await?;
+        if let (true, Some(asset_id)) = (deleted, asset_id) {
+            self.refresh_asset_risk(asset_id).await?;
+        }
+        Ok(deleted)
     }
 
     async fn list_vulnerabilities(
@@ -319,7 +349,28 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
             id_filter, port_id, severity, status, limit, offset
         );
         self.repository
-            .list_vulnerabilities(id_filter, port_id, severity, status, limit, offset)
+            .list_vulnerabilities(id_filter, port_id, severity, status, false, limit, offset)
+            .await
+    }
+
+    async fn list_vulnerabilities_sorted(
+        &self,
+        id_filter: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        sort: Option<Sort<VulnerabilitySortField>>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Vulnerability>> {
+        debug!(
+            "Listing vulnerabilities with filters - id_filter: {:?}, port_id: {:?}, severity: {:?}, status: {:?}, sort: {:?}, limit: {}, offset: {}",
+            id_filter, port_id, severity, status, sort, limit, offset
+        );
+        self.repository
+            .list_vulnerabilities_sorted(
+                id_filter, port_id, severity, status, false, sort, limit, offset,
+            )
             .await
     }
 
@@ -335,7 +386,7 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
             id_filter, port_id, severity, status
         );
         self.repository
-            .count_vulnerabilities(id_filter, port_id, severity, status)
+            .count_vulnerabilities(id_filter, port_id, severity, status, false)
             .await
     }
 
@@ -352,7 +403,14 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
         // Get assets for the organization
         let assets = self
             .asset_repository
-            .list_assets(Some(organization_id), None, None, 1000, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(organization_id),
+                    ..Default::default()
+                },
+                1000,
+                0,
+            )
             .await?;
 
         if assets.is_empty() {
@@ -373,6 +431,7 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
                     None,
                     min_severity,
                     Some(VulnerabilityStatus::Open),
+                    false,
                     1000,
                     0,
                 )
@@ -444,6 +503,21 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
         Ok(correlation_map)
     }
 
+    async fn correlate(&self, organization_id: ID) -> Result<Vec<VulnerabilityGroup>> {
+        info!(
+            "Grouping open vulnerabilities for organization: {}",
+            organization_id
+        );
+
+        let groups = self
+            .repository
+            .group_open_vulnerabilities(organization_id)
+            .await?;
+
+        debug!("Found {} vulnerability groups", groups.len());
+        Ok(groups)
+    }
+
     async fn find_similar_vulnerabilities(
         &self,
         vulnerability_id: ID,
@@ -457,7 +531,7 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
         // Get all vulnerabilities for the same asset
         let asset_vulnerabilities = self
             .repository
-            .list_vulnerabilities(Some(target_vuln.asset_id), None, None, None, 1000, 0)
+            .list_vulnerabilities(Some(target_vuln.asset_id), None, None, None, false, 1000, 0)
             .await?;
 
         // Get vulnerabilities from other assets - use a reasonable limit
@@ -468,6 +542,7 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
                 None,
                 Some(target_vuln.severity),
                 Some(VulnerabilityStatus::Open),
+                false,
                 500,
                 0,
             )
@@ -511,40 +586,31 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
 
     async fn bulk_update_vulnerability_status(
         &self,
+        organization_id: ID,
         vulnerability_ids: Vec<ID>,
         status: VulnerabilityStatus,
     ) -> Result<usize> {
         info!(
-            "Updating status to {:?} for {} vulnerabilities",
+            "Updating status to {:?} for {} vulnerabilities in organization {}",
             status,
-            vulnerability_ids.len()
+            vulnerability_ids.len(),
+            organization_id
         );
 
-        let mut updated_count = 0;
-
-        for vuln_id in vulnerability_ids {
-            match self.repository.get_vulnerability(vuln_id).await {
-                Ok(mut vuln) => {
-                    vuln.status = status;
-
-                    // Set resolved_at if we're closing the vulnerability
-                    if status != VulnerabilityStatus::Open {
-                        vuln.resolved_at = Some(chrono::Utc::now());
-                    } else {
-                        vuln.resolved_at = None;
-                    }
+        let updated_count = self
+            .repository
+            .bulk_update_status(organization_id, &vulnerability_ids, status)
+            .await?;
 
-                    if (self.repository.update_vulnerability(&vuln).await).is_ok() {
-                        updated_count += 1;
-                    }
-                }
-                Err(e) => {
-                    debug!("Failed to get vulnerability {}: {:?}", vuln_id, e);
-                    // Continue with the next one
-                    continue;
-                }
+        let mut asset_ids = HashSet::new();
+        for id in &vulnerability_ids {
+            if let Ok(vulnerability) = self.repository.get_vulnerability(*id).await {
+                asset_ids.insert(vulnerability.asset_id);
             }
         }
+        for asset_id in asset_ids {
+            self.refresh_asset_risk(asset_id).await?;
+        }
 
         info!("Successfully updated {} vulnerabilities", updated_count);
         Ok(updated_count)
@@ -562,7 +628,14 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
         // Get assets for the organization
         let assets = self
             .asset_repository
-            .list_assets(Some(organization_id), None, None, 1000, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(organization_id),
+                    ..Default::default()
+                },
+                1000,
+                0,
+            )
             .await?;
 
         if assets.is_empty() {
@@ -587,6 +660,7 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
         // Count by status
         let mut status_counts = std::collections::HashMap::new();
         status_counts.insert("Open".to_string(), 0);
+        status_counts.insert("InProgress".to_string(), 0);
         status_counts.insert("Closed".to_string(), 0);
         status_counts.insert("AcceptedRisk".to_string(), 0);
         status_counts.insert("FalsePositive".to_string(), 0);
@@ -602,7 +676,7 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
         for asset_id in &asset_ids {
             let vulnerabilities = self
                 .repository
-                .list_vulnerabilities(Some(*asset_id), None, None, None, 1000, 0)
+                .list_vulnerabilities(Some(*asset_id), None, None, None, false, 1000, 0)
                 .await?;
 
             // Find corresponding asset to determine type
@@ -649,4 +723,32 @@ impl VulnerabilityService for VulnerabilityServiceImpl {
 
         Ok(statistics)
     }
+
+    async fn count_by_severity(
+        &self,
+        organization_id: ID,
+        status: Option<VulnerabilityStatus>,
+    ) -> Result<HashMap<Severity, usize>> {
+        debug!(
+            "Counting {:?} vulnerabilities by severity for organization: {}",
+            status, organization_id
+        );
+        self.repository
+            .count_by_severity(organization_id, status)
+            .await
+    }
+
+    async fn vuln_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+        debug!(
+            "Counting vulnerabilities per day since {} for organization: {}",
+            since, organization_id
+        );
+        self.repository
+            .vuln_counts_by_day(organization_id, since)
+            .await
+    }
 }
@@ -1,14 +1,19 @@
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use shared::types::{
-    AssetStatus, AssetType, JobStatus, JobType, PortStatus, Protocol, Severity, UserRole,
-    VulnerabilityStatus, ID,
+    AssetStatus, AssetType, JobSortField, JobStatus, JobType, Page, PortStatus, Protocol, Severity,
+    Sort, Timestamp, UserRole, VulnerabilitySortField, VulnerabilityStatus, ID,
 };
 
 use crate::{
     models::{
-        Asset, DiscoveryJob, JobAssetLink, Organization, Port, Technology, User, Vulnerability,
+        Asset, AssetFilter, AssetGraph, AssetHistory, AssetRelationship, AuditLogEntry,
+        AuditLogFilter, DetectedTechnology, DiscoveryJob, DiscoveryJobFilter, JobAssetLink,
+        JobResultSummary, KnownVulnerability, Organization, Port, RelationshipDirection,
+        ScanProfile, ScanSchedule, Technology, TechnologyDistribution, User, Vulnerability,
+        VulnerabilityActivity, VulnerabilityGroup,
     },
-    Result,
+    Error, Result,
 };
 
 use std::collections::HashMap;
@@ -19,6 +24,8 @@ pub trait OrganizationRepository: Send + Sync + 'static {
 
     async fn get_organization(&self, id: ID) -> Result<Organization>;
 
+    async fn get_organization_by_name(&self, name: &str) -> Result<Option<Organization>>;
+
     async fn update_organization(&self, organization: &Organization) -> Result<Organization>;
 
     async fn delete_organization(&self, id: ID) -> Result<bool>;
@@ -26,6 +33,10 @@ pub trait OrganizationRepository: Send + Sync + 'static {
     async fn list_organizations(&self, limit: usize, offset: usize) -> Result<Vec<Organization>>;
 
     async fn count_organizations(&self) -> Result<usize>;
+
+    /// Record that an organization was sent a summary report at `sent_at`,
+    /// returning false if the organization doesn't exist
+    async fn record_report_sent(&self, id: ID, sent_at: Timestamp) -> Result<bool>;
 }
 
 #[async_trait]
@@ -72,33 +83,193 @@ pub trait UserService: Send + Sync + 'static {
     ) -> Result<User>;
 
     async fn login_user(&self, email: &str, password: &str) -> Result<User>;
+
+    async fn get_user(&self, id: ID) -> Result<User>;
+
+    /// An organization's users, ordered by username
+    async fn list_users(
+        &self,
+        organization_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<User>>;
+
+    async fn count_users(&self, organization_id: ID) -> Result<usize>;
+
+    /// Create a user with `role` in an organization, as an administrator
+    /// rather than by self-registration
+    async fn create_user(
+        &self,
+        organization_id: ID,
+        email: &str,
+        password: &str,
+        role: UserRole,
+    ) -> Result<User>;
+
+    /// Save a user's username, email, role and whether they're active. The
+    /// email must not belong to another user.
+    async fn update_user(&self, user: &User) -> Result<User>;
+
+    /// Replace a user's password after checking their current one, failing
+    /// with an authentication error if it doesn't match
+    async fn change_password(
+        &self,
+        id: ID,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<()>;
 }
 
 #[async_trait]
 pub trait AssetRepository: Send + Sync + 'static {
     async fn create_asset(&self, asset: &Asset) -> Result<Asset>;
 
+    /// Create an asset, or if the organization already has a live asset of
+    /// the same type and value, refresh its `last_seen` and status and merge
+    /// in the new attributes. Returns the stored asset, keeping the existing
+    /// ID on conflict.
+    async fn upsert_asset(&self, asset: &Asset) -> Result<Asset>;
+
     async fn get_asset(&self, id: ID) -> Result<Asset>;
 
+    /// Get the assets among `ids`, in no particular order. Unknown and
+    /// soft-deleted IDs are skipped. Implementations should fetch them all
+    /// with a single query.
+    async fn get_assets(&self, ids: &[ID]) -> Result<Vec<Asset>> {
+        let mut assets = Vec::with_capacity(ids.len());
+        for id in ids {
+            match self.get_asset(*id).await {
+                Ok(asset) => assets.push(asset),
+                Err(Error::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(assets)
+    }
+
     async fn update_asset(&self, asset: &Asset) -> Result<Asset>;
 
+    /// Soft-delete an asset. It is hidden from `get_asset` and listings
+    /// until restored.
     async fn delete_asset(&self, id: ID) -> Result<bool>;
 
+    /// Undo a soft delete, returning false if the asset was not deleted
+    async fn restore_asset(&self, id: ID) -> Result<bool>;
+
+    /// List assets matching `filter`, ordered by value
     async fn list_assets(
         &self,
-        organization_id: Option<ID>,
-        asset_type: Option<AssetType>,
-        status: Option<AssetStatus>,
+        filter: &AssetFilter,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<Asset>>;
 
-    async fn count_assets(
+    async fn count_assets(&self, filter: &AssetFilter) -> Result<usize>;
+
+    /// `list_assets` along with the number of assets matching the filter.
+    /// Implementations should get both from a single query.
+    async fn list_assets_page(
+        &self,
+        filter: &AssetFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<Asset>> {
+        let items = self.list_assets(filter, limit, offset).await?;
+        let total = self.count_assets(filter).await?;
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Tag an asset, skipping tags it already has. Returns all of the
+    /// asset's tags.
+    async fn add_asset_tags(&self, asset_id: ID, tags: &[String]) -> Result<Vec<String>>;
+
+    /// Remove a tag from an asset, returning false if it wasn't tagged with it
+    async fn remove_asset_tag(&self, asset_id: ID, tag: &str) -> Result<bool>;
+
+    /// An asset's tags in alphabetical order
+    async fn list_asset_tags(&self, asset_id: ID) -> Result<Vec<String>>;
+
+    /// Set the status of every listed asset belonging to `organization_id`
+    /// in one statement. IDs from other organizations are ignored. Returns
+    /// the number of assets updated.
+    async fn bulk_update_status(
+        &self,
+        organization_id: ID,
+        asset_ids: &[ID],
+        status: AssetStatus,
+    ) -> Result<usize>;
+
+    /// Record a relationship between two assets, returning false if it was
+    /// already recorded
+    async fn add_asset_relationship(&self, relationship: &AssetRelationship) -> Result<bool>;
+
+    /// Record relationships, given as (source, target, type), in one
+    /// statement. Relationships already recorded and ones between assets
+    /// that don't exist are skipped. Returns the number added.
+    async fn add_relationships_bulk(&self, relationships: Vec<(ID, ID, String)>) -> Result<usize>;
+
+    /// Remove a relationship, returning false if it wasn't recorded
+    async fn remove_asset_relationship(
+        &self,
+        source_asset_id: ID,
+        target_asset_id: ID,
+        relationship_type: &str,
+    ) -> Result<bool>;
+
+    /// Relationships of an asset in `direction`, each with the asset at the
+    /// other end, ordered by relationship type and then that asset's value.
+    /// Relationships with deleted assets are left out.
+    async fn list_asset_relationships(
+        &self,
+        asset_id: ID,
+        direction: RelationshipDirection,
+        relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<(AssetRelationship, Asset)>>;
+
+    /// Mark active assets whose `last_seen` is older than `older_than` as
+    /// inactive, returning the number of assets transitioned
+    async fn mark_stale(
         &self,
         organization_id: Option<ID>,
-        asset_type: Option<AssetType>,
-        status: Option<AssetStatus>,
+        older_than: chrono::Duration,
     ) -> Result<usize>;
+
+    /// Store a recomputed risk score, returning false if the asset doesn't
+    /// exist
+    async fn update_risk_score(&self, id: ID, risk_score: f32) -> Result<bool>;
+
+    /// An organization's most recently discovered assets, newest first
+    async fn list_recent_assets(&self, organization_id: ID, limit: usize) -> Result<Vec<Asset>>;
+
+    /// Count the assets an organization discovered per UTC day since `since`,
+    /// oldest day first. Days without new assets are left out.
+    async fn asset_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>>;
+}
+
+#[async_trait]
+pub trait AssetHistoryRepository: Send + Sync + 'static {
+    async fn create_history_entry(&self, entry: &AssetHistory) -> Result<AssetHistory>;
+
+    /// List changes to an asset, most recent first
+    async fn list_asset_history(
+        &self,
+        asset_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AssetHistory>>;
+
+    async fn count_asset_history(&self, asset_id: ID) -> Result<usize>;
 }
 
 #[async_trait]
@@ -111,6 +282,10 @@ pub trait PortRepository: Send + Sync + 'static {
 
     async fn delete_port(&self, id: ID) -> Result<bool>;
 
+    /// Insert `ports` on an asset, refreshing the status, banner and
+    /// last_seen of ports already recorded with the same number and protocol
+    async fn upsert_ports_bulk(&self, asset_id: ID, ports: &[Port]) -> Result<Vec<Port>>;
+
     async fn list_ports(
         &self,
         asset_id: Option<ID>,
@@ -134,6 +309,13 @@ pub trait PortRepository: Send + Sync + 'static {
 pub trait TechnologyRepository: Send + Sync + 'static {
     async fn create_technology(&self, technology: &Technology) -> Result<Technology>;
 
+    /// Record a detection of a technology on an asset. A technology already
+    /// known on the asset by the same name is updated in place with the new
+    /// version, category and `last_seen`, keeping the stored version and
+    /// category when none were detected. A version change is recorded in the
+    /// asset's history under `technologies.<name>.version`.
+    async fn upsert_technology(&self, technology: &Technology) -> Result<Technology>;
+
     async fn get_technology(&self, id: ID) -> Result<Technology>;
 
     async fn update_technology(&self, technology: &Technology) -> Result<Technology>;
@@ -149,12 +331,41 @@ pub trait TechnologyRepository: Send + Sync + 'static {
         offset: usize,
     ) -> Result<Vec<Technology>>;
 
+    /// Technologies detected on one asset, ordered by name
+    async fn list_asset_technologies(
+        &self,
+        asset_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Technology>>;
+
     async fn count_technologies(
         &self,
         asset_id: Option<ID>,
         name: Option<String>,
         category: Option<String>,
     ) -> Result<usize>;
+
+    /// Number of distinct technologies detected across an organization's
+    /// assets
+    async fn count_organization_technologies(&self, organization_id: ID) -> Result<usize>;
+
+    /// Technologies on an organization's assets whose name contains
+    /// `search` and whose category is `category`, both ignoring case,
+    /// ordered by name
+    async fn list_organization_technologies(
+        &self,
+        organization_id: ID,
+        search: Option<String>,
+        category: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<DetectedTechnology>>;
+
+    /// Count the technologies on an organization's assets by name and by
+    /// category. Names differing only in case are counted together, as in
+    /// `count_organization_technologies`.
+    async fn technology_distribution(&self, organization_id: ID) -> Result<TechnologyDistribution>;
 }
 
 #[async_trait]
@@ -188,6 +399,20 @@ pub trait TechnologyService: Send + Sync + 'static {
         &self,
         organization_id: ID,
     ) -> Result<std::collections::HashMap<String, usize>>;
+
+    /// Known vulnerabilities affecting the technology's detected version.
+    /// Nothing matches a technology whose version wasn't detected.
+    async fn find_known_vulnerabilities(
+        &self,
+        technology: &Technology,
+    ) -> Result<Vec<KnownVulnerability>>;
+
+    /// Record the known vulnerabilities of a technology against its asset,
+    /// skipping CVEs the asset already has, and return the new records
+    async fn record_known_vulnerabilities(
+        &self,
+        technology: &Technology,
+    ) -> Result<Vec<Vulnerability>>;
 }
 
 #[async_trait]
@@ -198,14 +423,38 @@ pub trait VulnerabilityRepository: Send + Sync + 'static {
 
     async fn update_vulnerability(&self, vulnerability: &Vulnerability) -> Result<Vulnerability>;
 
+    /// Soft-delete a vulnerability. It is hidden from `get_vulnerability` and
+    /// listings until restored.
     async fn delete_vulnerability(&self, id: ID) -> Result<bool>;
 
+    /// Undo a soft delete, returning false if the vulnerability was not deleted
+    async fn restore_vulnerability(&self, id: ID) -> Result<bool>;
+
+    /// List vulnerabilities, skipping soft-deleted ones unless
+    /// `include_deleted` is set
+    #[allow(clippy::too_many_arguments)]
     async fn list_vulnerabilities(
         &self,
         id_filter: Option<ID>,
         port_id: Option<ID>,
         severity: Option<Severity>,
         status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Vulnerability>>;
+
+    /// `list_vulnerabilities` ordered by `sort`, or in the default order
+    /// without one. Ties are broken by ID so pages stay stable.
+    #[allow(clippy::too_many_arguments)]
+    async fn list_vulnerabilities_sorted(
+        &self,
+        id_filter: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
+        sort: Option<Sort<VulnerabilitySortField>>,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<Vulnerability>>;
@@ -216,7 +465,86 @@ pub trait VulnerabilityRepository: Send + Sync + 'static {
         port_id: Option<ID>,
         severity: Option<Severity>,
         status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
     ) -> Result<usize>;
+
+    /// `list_vulnerabilities` along with the number of vulnerabilities
+    /// matching the filters. Implementations should get both from a single
+    /// query.
+    #[allow(clippy::too_many_arguments)]
+    async fn list_vulnerabilities_page(
+        &self,
+        id_filter: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<Vulnerability>> {
+        let items = self
+            .list_vulnerabilities(
+                id_filter,
+                port_id,
+                severity,
+                status,
+                include_deleted,
+                limit,
+                offset,
+            )
+            .await?;
+        let total = self
+            .count_vulnerabilities(id_filter, port_id, severity, status, include_deleted)
+            .await?;
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Group an organization's open vulnerabilities by CVE (or title when
+    /// there is no CVE) and affected technology
+    async fn group_open_vulnerabilities(
+        &self,
+        organization_id: ID,
+    ) -> Result<Vec<VulnerabilityGroup>>;
+
+    /// Set the status of every listed vulnerability on an asset belonging to
+    /// `organization_id` in one statement. IDs from other organizations are
+    /// ignored. Returns the number of vulnerabilities updated.
+    async fn bulk_update_status(
+        &self,
+        organization_id: ID,
+        vulnerability_ids: &[ID],
+        status: VulnerabilityStatus,
+    ) -> Result<usize>;
+
+    /// Count an organization's vulnerabilities per severity, only those in
+    /// `status` if given. Severities without vulnerabilities are left out.
+    async fn count_by_severity(
+        &self,
+        organization_id: ID,
+        status: Option<VulnerabilityStatus>,
+    ) -> Result<HashMap<Severity, usize>>;
+
+    /// Count the vulnerabilities first seen on an organization's assets per
+    /// UTC day since `since`, oldest day first. Days without new
+    /// vulnerabilities are left out.
+    async fn vuln_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>>;
+
+    /// Count the vulnerabilities first seen on, or resolved on, an
+    /// organization's assets since `since`
+    async fn activity_since(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<VulnerabilityActivity>;
 }
 
 #[async_trait]
@@ -229,21 +557,17 @@ pub trait DiscoveryJobRepository: Send + Sync + 'static {
 
     async fn delete_job(&self, id: ID) -> Result<bool>;
 
+    /// List jobs ordered by `sort`, or newest first without one. Ties are
+    /// broken by ID so pages stay stable.
     async fn list_jobs(
         &self,
-        organization_id: Option<ID>,
-        job_type: Option<JobType>,
-        status: Option<JobStatus>,
+        filter: &DiscoveryJobFilter,
+        sort: Option<Sort<JobSortField>>,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<DiscoveryJob>>;
 
-    async fn count_jobs(
-        &self,
-        organization_id: Option<ID>,
-        job_type: Option<JobType>,
-        status: Option<JobStatus>,
-    ) -> Result<usize>;
+    async fn count_jobs(&self, filter: &DiscoveryJobFilter) -> Result<usize>;
 
     /// List jobs by status with a limit
     async fn list_jobs_by_status(
@@ -259,7 +583,117 @@ pub trait DiscoveryJobRepository: Send + Sync + 'static {
         self.create_job_asset_link(link).await
     }
 
+    /// Link a job to each of `asset_ids`, skipping links that already exist.
+    /// Returns the number of links created.
+    async fn link_job_to_assets(&self, job_id: ID, asset_ids: &[ID]) -> Result<usize>;
+
     async fn get_job_assets(&self, job_id: ID) -> Result<Vec<Asset>>;
+
+    /// Count the assets linked to a job, and the ports, technologies and
+    /// vulnerabilities on them. Deleted assets and vulnerabilities aren't
+    /// counted.
+    async fn get_job_summary(&self, job_id: ID) -> Result<JobResultSummary>;
+
+    /// Record that jobs of `job_type` scanned `targets` at `scanned_at`
+    async fn record_target_scans(
+        &self,
+        organization_id: ID,
+        job_type: JobType,
+        targets: &[String],
+        scanned_at: Timestamp,
+    ) -> Result<()>;
+
+    /// When jobs of `job_type` last scanned each of `targets`. Targets that
+    /// were never scanned are left out.
+    async fn last_target_scans(
+        &self,
+        organization_id: ID,
+        job_type: JobType,
+        targets: &[String],
+    ) -> Result<HashMap<String, Timestamp>>;
+}
+
+#[async_trait]
+pub trait ScanScheduleRepository: Send + Sync + 'static {
+    async fn create_schedule(&self, schedule: &ScanSchedule) -> Result<ScanSchedule>;
+
+    async fn get_schedule(&self, id: ID) -> Result<ScanSchedule>;
+
+    async fn update_schedule(&self, schedule: &ScanSchedule) -> Result<ScanSchedule>;
+
+    async fn delete_schedule(&self, id: ID) -> Result<bool>;
+
+    async fn list_schedules(
+        &self,
+        organization_id: Option<ID>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<ScanSchedule>>;
+
+    async fn count_schedules(&self, organization_id: Option<ID>) -> Result<usize>;
+
+    /// List all enabled schedules, for the scheduler to check which are due
+    async fn list_enabled_schedules(&self) -> Result<Vec<ScanSchedule>>;
+}
+
+#[async_trait]
+pub trait ScanProfileRepository: Send + Sync + 'static {
+    async fn create_profile(&self, profile: &ScanProfile) -> Result<ScanProfile>;
+
+    async fn get_profile(&self, id: ID) -> Result<ScanProfile>;
+
+    /// Find an organization's profile by name, ignoring case
+    async fn get_profile_by_name(
+        &self,
+        organization_id: ID,
+        name: &str,
+    ) -> Result<Option<ScanProfile>>;
+
+    async fn update_profile(&self, profile: &ScanProfile) -> Result<ScanProfile>;
+
+    async fn delete_profile(&self, id: ID) -> Result<bool>;
+
+    /// List an organization's own profiles, ordered by name
+    async fn list_profiles(&self, organization_id: ID) -> Result<Vec<ScanProfile>>;
+}
+
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync + 'static {
+    async fn create_entry(&self, entry: &AuditLogEntry) -> Result<AuditLogEntry>;
+
+    /// List entries matching `filter`, most recent first
+    async fn list_entries(
+        &self,
+        filter: &AuditLogFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditLogEntry>>;
+
+    async fn count_entries(&self, filter: &AuditLogFilter) -> Result<usize>;
+}
+
+#[async_trait]
+pub trait NotificationLogRepository: Send + Sync + 'static {
+    /// Record an alert with `fingerprint` as sent to an organization at
+    /// `now`, unless the same alert was sent less than `window` earlier.
+    /// Returns whether the alert should be sent.
+    async fn claim_notification(
+        &self,
+        organization_id: ID,
+        fingerprint: &str,
+        event_type: &str,
+        now: Timestamp,
+        window: chrono::Duration,
+    ) -> Result<bool>;
+}
+
+/// Source of published vulnerabilities to match detected technologies
+/// against
+#[async_trait]
+pub trait VulnerabilityFeed: Send + Sync + 'static {
+    /// Vulnerabilities affecting `version` of the technology detected as
+    /// `name`
+    async fn find(&self, name: &str, version: &str) -> Result<Vec<KnownVulnerability>>;
 }
 
 #[async_trait]
@@ -295,6 +729,7 @@ pub trait AssetService: Send + Sync {
         organization_id: Option<ID>,
         asset_type: Option<AssetType>,
         status: Option<AssetStatus>,
+        tag: Option<String>,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<Asset>>;
@@ -305,6 +740,26 @@ pub trait AssetService: Send + Sync {
         organization_id: Option<ID>,
         asset_type: Option<AssetType>,
         status: Option<AssetStatus>,
+        tag: Option<String>,
+    ) -> Result<usize>;
+
+    /// Tag an asset. Tags are trimmed and lowercased, and ones the asset
+    /// already has are skipped. Returns all of the asset's tags.
+    async fn add_tags(&self, asset_id: ID, tags: &[String]) -> Result<Vec<String>>;
+
+    /// Remove a tag from an asset
+    async fn remove_tag(&self, asset_id: ID, tag: &str) -> Result<bool>;
+
+    /// Get an asset's tags
+    async fn get_tags(&self, asset_id: ID) -> Result<Vec<String>>;
+
+    /// Update asset status in bulk. Only the organization's own assets are
+    /// touched.
+    async fn bulk_update_asset_status(
+        &self,
+        organization_id: ID,
+        asset_ids: Vec<ID>,
+        status: AssetStatus,
     ) -> Result<usize>;
 
     /// Create a relationship between two assets
@@ -316,6 +771,14 @@ pub trait AssetService: Send + Sync {
         metadata: Option<serde_json::Value>,
     ) -> Result<bool>;
 
+    /// Create many relationships, given as (source, target, type), at once,
+    /// e.g. those found by `discover_asset_relationships`. Returns the
+    /// number that weren't already recorded.
+    async fn create_asset_relationships(
+        &self,
+        relationships: Vec<(ID, ID, String)>,
+    ) -> Result<usize>;
+
     /// Delete a relationship between two assets
     async fn delete_asset_relationship(
         &self,
@@ -324,11 +787,26 @@ pub trait AssetService: Send + Sync {
         relationship_type: String,
     ) -> Result<bool>;
 
-    /// Get assets related to a specific asset
+    /// Get assets related to a specific asset, with the type of each
+    /// relationship. They are ordered by relationship type and then by
+    /// value, and paginated in that order.
     async fn get_related_assets(
         &self,
         asset_id: ID,
         relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<(Asset, String)>>;
+
+    /// Get assets with a relationship pointing at a specific asset, with
+    /// the type of each relationship, ordered and paginated like
+    /// `get_related_assets`
+    async fn get_referring_assets(
+        &self,
+        asset_id: ID,
+        relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
     ) -> Result<Vec<(Asset, String)>>;
 
     /// Discover relationships between assets
@@ -337,6 +815,10 @@ pub trait AssetService: Send + Sync {
         organization_id: ID,
     ) -> Result<Vec<(ID, ID, String)>>;
 
+    /// Every asset of an organization and the relationships discovered
+    /// between them, for visualization
+    async fn asset_graph(&self, organization_id: ID) -> Result<AssetGraph>;
+
     /// Analyze dependency chains for an asset
     async fn analyze_dependency_chain(
         &self,
@@ -353,6 +835,31 @@ pub trait AssetService: Send + Sync {
         visited: &mut std::collections::HashSet<ID>,
         max_depth: usize,
     ) -> Result<()>;
+
+    /// List recorded changes to an asset, most recent first
+    async fn get_asset_history(
+        &self,
+        asset_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AssetHistory>>;
+
+    /// Count recorded changes to an asset
+    async fn count_asset_history(&self, asset_id: ID) -> Result<usize>;
+
+    /// An organization's most recently discovered assets, newest first
+    async fn list_recent_assets(&self, organization_id: ID, limit: usize) -> Result<Vec<Asset>>;
+
+    /// Count the assets an organization discovered per UTC day since `since`
+    async fn asset_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>>;
+
+    /// Recompute and store an asset's risk score from its open
+    /// vulnerabilities and exposed ports (see `services::risk_score`)
+    async fn compute_risk_score(&self, asset_id: ID) -> Result<f32>;
 }
 
 #[async_trait]
@@ -375,6 +882,20 @@ pub trait VulnerabilityService: Send + Sync + 'static {
         offset: usize,
     ) -> Result<Vec<Vulnerability>>;
 
+    /// `list_vulnerabilities` ordered by `sort`, or in the default order
+    /// without one
+    #[allow(clippy::too_many_arguments)]
+    async fn list_vulnerabilities_sorted(
+        &self,
+        asset_id: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        sort: Option<Sort<VulnerabilitySortField>>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Vulnerability>>;
+
     async fn count_vulnerabilities(
         &self,
         asset_id: Option<ID>,
@@ -391,6 +912,10 @@ pub trait VulnerabilityService: Send + Sync + 'static {
         min_severity: Option<Severity>,
     ) -> Result<std::collections::HashMap<ID, Vec<ID>>>;
 
+    /// Group open vulnerabilities that share a CVE, title or affected
+    /// technology across an organization's assets, most severe first
+    async fn correlate(&self, organization_id: ID) -> Result<Vec<VulnerabilityGroup>>;
+
     /// Find similar vulnerabilities for a specific vulnerability
     async fn find_similar_vulnerabilities(
         &self,
@@ -398,9 +923,11 @@ pub trait VulnerabilityService: Send + Sync + 'static {
         limit: usize,
     ) -> Result<Vec<Vulnerability>>;
 
-    /// Update vulnerability status in bulk for easier management
+    /// Update vulnerability status in bulk for easier management. Only the
+    /// organization's own vulnerabilities are touched.
     async fn bulk_update_vulnerability_status(
         &self,
+        organization_id: ID,
         vulnerability_ids: Vec<ID>,
         status: VulnerabilityStatus,
     ) -> Result<usize>;
@@ -410,6 +937,22 @@ pub trait VulnerabilityService: Send + Sync + 'static {
         &self,
         organization_id: ID,
     ) -> Result<std::collections::HashMap<String, usize>>;
+
+    /// Count an organization's vulnerabilities per severity, only those in
+    /// `status` if given
+    async fn count_by_severity(
+        &self,
+        organization_id: ID,
+        status: Option<VulnerabilityStatus>,
+    ) -> Result<HashMap<Severity, usize>>;
+
+    /// Count the vulnerabilities first seen on an organization's assets per
+    /// UTC day since `since`
+    async fn vuln_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>>;
 }
 
 #[async_trait]
@@ -438,6 +981,10 @@ pub trait NotificationService: Send + Sync {
     /// Send notification for a new critical asset
     async fn notify_new_critical_asset(&self, asset: &Asset) -> Result<bool>;
 
+    /// Send notification for a change in a critical web asset's content.
+    /// The asset's `content_hash` attribute holds the new hash.
+    async fn notify_content_change(&self, asset: &Asset, previous_hash: &str) -> Result<bool>;
+
     /// Send periodic summary report
     async fn send_summary_report(
         &self,
@@ -455,7 +1002,8 @@ pub trait NotificationService: Send + Sync {
         settings: &NotificationSettings,
     ) -> Result<NotificationSettings>;
 
-    /// Send batch notification for multiple new vulnerabilities
+    /// Send one digest per organization for new vulnerabilities found
+    /// across any number of its assets
     async fn notify_new_vulnerabilities_batch(
         &self,
         vulnerabilities: &[Vulnerability],
@@ -463,13 +1011,27 @@ pub trait NotificationService: Send + Sync {
 }
 
 /// Period for notification reporting
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "VARCHAR", rename_all = "UPPERCASE")]
+#[serde(rename_all = "UPPERCASE")]
 pub enum NotificationPeriod {
+    #[default]
     Daily,
     Weekly,
     Monthly,
 }
 
+impl NotificationPeriod {
+    /// How far back a report for this period looks
+    pub fn window(&self) -> chrono::Duration {
+        match self {
+            NotificationPeriod::Daily => chrono::Duration::days(1),
+            NotificationPeriod::Weekly => chrono::Duration::weeks(1),
+            NotificationPeriod::Monthly => chrono::Duration::days(30),
+        }
+    }
+}
+
 /// Settings for notifications
 #[derive(Debug, Clone)]
 pub struct NotificationSettings {
@@ -482,6 +1044,7 @@ pub struct NotificationSettings {
     pub notify_on_new_vulnerability: bool,
     pub notify_on_status_change: bool,
     pub notify_on_new_critical_asset: bool,
+    pub notify_on_content_change: bool,
     pub minimum_severity_for_notification: Severity,
     pub additional_settings: Option<HashMap<String, serde_json::Value>>,
 }
@@ -1,11 +1,13 @@
 #[cfg(test)]
 mod tests {
     use async_trait::async_trait;
-    use backend::models::Asset;
+    use backend::models::{
+        Asset, AssetFilter, AssetHistory, AssetRelationship, RelationshipDirection,
+    };
     use backend::services::AssetServiceImpl;
-    use backend::{AssetRepository, AssetService, Error, Result};
+    use backend::{AssetHistoryRepository, AssetRepository, AssetService, Error, Result};
     use shared::types::{AssetStatus, AssetType, ID};
-    use std::collections::HashMap;
+    use std::collections::{BTreeSet, HashMap};
     use std::sync::{Arc, Mutex};
     use tokio::test;
     use uuid::Uuid;
@@ -13,14 +15,34 @@ mod tests {
     #[derive(Clone)]
     struct MockAssetRepository {
         assets: Arc<Mutex<HashMap<ID, Asset>>>,
+        tags: Arc<Mutex<HashMap<ID, BTreeSet<String>>>>,
+        relationships: Arc<Mutex<Vec<AssetRelationship>>>,
+        // Number of single asset lookups
+        single_lookups: Arc<Mutex<usize>>,
     }
 
     impl MockAssetRepository {
         fn new() -> Self {
             Self {
                 assets: Arc::new(Mutex::new(HashMap::new())),
+                tags: Arc::new(Mutex::new(HashMap::new())),
+                relationships: Arc::new(Mutex::new(Vec::new())),
+                single_lookups: Arc::new(Mutex::new(0)),
             }
         }
+
+        fn matches(&self, filter: &AssetFilter, asset: &Asset) -> bool {
+            let tags = self.tags.lock().unwrap();
+            filter
+                .organization_id
+                .is_none_or(|oid| asset.organization_id == oid)
+                && filter.asset_type.is_none_or(|at| asset.asset_type == at)
+                && filter.status.is_none_or(|s| asset.status == s)
+                && filter
+                    .tag
+                    .as_ref()
+                    .is_none_or(|t| tags.get(&asset.id).is_some_and(|set| set.contains(t)))
+        }
     }
 
     #[async_trait]
@@ -32,7 +54,36 @@ mod tests {
             Ok(new_asset)
         }
 
+        async fn upsert_asset(&self, asset: &Asset) -> Result<Asset> {
+            let mut assets = self.assets.lock().unwrap();
+            let existing = assets.values_mut().find(|existing| {
+                existing.organization_id == asset.organization_id
+                    && existing.asset_type == asset.asset_type
+                    && existing.value == asset.value
+            });
+
+            match existing {
+                Some(existing) => {
+                    existing.status = asset.status;
+                    existing.last_seen = existing.last_seen.max(asset.last_seen);
+                    if let (Some(stored), Some(new)) = (
+                        existing.attributes.as_object_mut(),
+                        asset.attributes.as_object(),
+                    ) {
+                        stored.extend(new.clone());
+                    }
+                    existing.updated_at = asset.updated_at;
+                    Ok(existing.clone())
+                }
+                None => {
+                    assets.insert(asset.id, asset.clone());
+                    Ok(asset.clone())
+                }
+            }
+        }
+
         async fn get_asset(&self, id: ID) -> Result<Asset> {
+            *self.single_lookups.lock().unwrap() += 1;
             let assets = self.assets.lock().unwrap();
             assets
                 .get(&id)
@@ -40,6 +91,14 @@ mod tests {
                 .ok_or_else(|| Error::NotFound(format!("Asset with ID {} not found", id)))
         }
 
+        async fn get_assets(&self, ids: &[ID]) -> Result<Vec<Asset>> {
+            let assets = self.assets.lock().unwrap();
+            Ok(ids
+                .iter()
+                .filter_map(|id| assets.get(id).cloned())
+                .collect())
+        }
+
         async fn update_asset(&self, asset: &Asset) -> Result<Asset> {
             let mut assets = self.assets.lock().unwrap();
 
@@ -66,11 +125,14 @@ mod tests {
             Ok(true)
         }
 
+        async fn restore_asset(&self, _id: ID) -> Result<bool> {
+            // Deleted assets are dropped outright, so there is nothing to restore
+            Ok(false)
+        }
+
         async fn list_assets(
             &self,
-            organization_id: Option<ID>,
-            asset_type: Option<AssetType>,
-            status: Option<AssetStatus>,
+            filter: &AssetFilter,
             limit: usize,
             offset: usize,
         ) -> Result<Vec<Asset>> {
@@ -78,11 +140,7 @@ mod tests {
 
             let filtered: Vec<Asset> = assets
                 .values()
-                .filter(|a| {
-                    organization_id.is_none_or(|oid| a.organization_id == oid)
-                        && asset_type.is_none_or(|at| a.asset_type == at)
-                        && status.is_none_or(|s| a.status == s)
-                })
+                .filter(|a| self.matches(filter, a))
                 .cloned()
                 .collect();
 
@@ -91,24 +149,253 @@ mod tests {
             Ok(paginated)
         }
 
-        async fn count_assets(
+        async fn count_assets(&self, filter: &AssetFilter) -> Result<usize> {
+            let assets = self.assets.lock().unwrap();
+
+            let count = assets.values().filter(|a| self.matches(filter, a)).count();
+
+            Ok(count)
+        }
+
+        async fn add_asset_tags(&self, asset_id: ID, tags: &[String]) -> Result<Vec<String>> {
+            let mut all_tags = self.tags.lock().unwrap();
+            let set = all_tags.entry(asset_id).or_default();
+            set.extend(tags.iter().cloned());
+            Ok(set.iter().cloned().collect())
+        }
+
+        async fn remove_asset_tag(&self, asset_id: ID, tag: &str) -> Result<bool> {
+            let mut all_tags = self.tags.lock().unwrap();
+            Ok(all_tags
+                .get_mut(&asset_id)
+                .is_some_and(|set| set.remove(tag)))
+        }
+
+        async fn list_asset_tags(&self, asset_id: ID) -> Result<Vec<String>> {
+            let all_tags = self.tags.lock().unwrap();
+            Ok(all_tags
+                .get(&asset_id)
+                .map(|set| set.iter().cloned().collect())
+                .unwrap_or_default())
+        }
+
+        async fn add_asset_relationship(&self, relationship: &AssetRelationship) -> Result<bool> {
+            let mut relationships = self.relationships.lock().unwrap();
+            let exists = relationships.iter().any(|r| {
+                r.source_asset_id == relationship.source_asset_id
+                    && r.target_asset_id == relationship.target_asset_id
+                    && r.relationship_type == relationship.relationship_type
+            });
+            if !exists {
+                relationships.push(relationship.clone());
+            }
+            Ok(!exists)
+        }
+
+        async fn add_relationships_bulk(
+            &self,
+            relationships: Vec<(ID, ID, String)>,
+        ) -> Result<usize> {
+            let mut added = 0;
+            for (source, target, relationship_type) in relationships {
+                let both_exist = {
+                    let assets = self.assets.lock().unwrap();
+                    assets.contains_key(&source) && assets.contains_key(&target)
+                };
+                let relationship = AssetRelationship {
+                    source_asset_id: source,
+                    target_asset_id: target,
+                    relationship_type,
+                    metadata: None,
+                };
+                if both_exist && self.add_asset_relationship(&relationship).await? {
+                    added += 1;
+                }
+            }
+            Ok(added)
+        }
+
+        async fn remove_asset_relationship(
+            &self,
+            source_asset_id: ID,
+            target_asset_id: ID,
+            relationship_type: &str,
+        ) -> Result<bool> {
+            let mut relationships = self.relationships.lock().unwrap();
+            let before = relationships.len();
+            relationships.retain(|r| {
+                !(r.source_asset_id == source_asset_id
+                    && r.target_asset_id == target_asset_id
+                    && r.relationship_type == relationship_type)
+            });
+            Ok(relationships.len() < before)
+        }
+
+        async fn list_asset_relationships(
+            &self,
+            asset_id: ID,
+            direction: RelationshipDirection,
+            relationship_type: Option<String>,
+            limit: usize,
+            offset: usize,
+        ) -> Result<Vec<(AssetRelationship, Asset)>> {
+            let relationships = self.relationships.lock().unwrap();
+            let assets = self.assets.lock().unwrap();
+
+            let mut found: Vec<(AssetRelationship, Asset)> = relationships
+                .iter()
+                .filter(|r| {
+                    relationship_type
+                        .as_ref()
+                        .is_none_or(|t| &r.relationship_type == t)
+                })
+                .filter_map(|r| {
+                    let other = match direction {
+                        RelationshipDirection::Outgoing if r.source_asset_id == asset_id => {
+                            r.target_asset_id
+                        }
+                        RelationshipDirection::Incoming if r.target_asset_id == asset_id => {
+                            r.source_asset_id
+                        }
+                        _ => return None,
+                    };
+                    Some((r.clone(), assets.get(&other)?.clone()))
+                })
+                .collect();
+            found.sort_by(|(a_rel, a), (b_rel, b)| {
+                (&a_rel.relationship_type, &a.value).cmp(&(&b_rel.relationship_type, &b.value))
+            });
+
+            Ok(found.into_iter().skip(offset).take(limit).collect())
+        }
+
+        async fn bulk_update_status(
+            &self,
+            organization_id: ID,
+            asset_ids: &[ID],
+            status: AssetStatus,
+        ) -> Result<usize> {
+            let mut assets = self.assets.lock().unwrap();
+
+            let mut updated = 0;
+            for asset in assets
+                .values_mut()
+                .filter(|a| a.organization_id == organization_id && asset_ids.contains(&a.id))
+            {
+                asset.status = status;
+                updated += 1;
+            }
+
+            Ok(updated)
+        }
+
+        async fn mark_stale(
             &self,
             organization_id: Option<ID>,
-            asset_type: Option<AssetType>,
-            status: Option<AssetStatus>,
+            older_than: chrono::Duration,
         ) -> Result<usize> {
+            let mut assets = self.assets.lock().unwrap();
+            let cutoff = chrono::Utc::now() - older_than;
+
+            let mut marked = 0;
+            for asset in assets.values_mut().filter(|a| {
+                organization_id.is_none_or(|oid| a.organization_id == oid)
+                    && a.status == AssetStatus::Active
+                    && a.last_seen < cutoff
+            }) {
+                asset.status = AssetStatus::Inactive;
+                asset.updated_at = chrono::Utc::now();
+                marked += 1;
+            }
+
+            Ok(marked)
+        }
+
+        async fn update_risk_score(&self, id: ID, risk_score: f32) -> Result<bool> {
+            let mut assets = self.assets.lock().unwrap();
+            match assets.get_mut(&id) {
+                Some(asset) => {
+                    asset.risk_score = risk_score;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        async fn list_recent_assets(
+            &self,
+            organization_id: ID,
+            limit: usize,
+        ) -> Result<Vec<Asset>> {
             let assets = self.assets.lock().unwrap();
+            let mut recent: Vec<Asset> = assets
+                .values()
+                .filter(|a| a.organization_id == organization_id)
+                .cloned()
+                .collect();
+            recent.sort_by_key(|asset| std::cmp::Reverse(asset.first_seen));
+            recent.truncate(limit);
+            Ok(recent)
+        }
 
-            let count = assets
+        async fn asset_counts_by_day(
+            &self,
+            organization_id: ID,
+            since: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+            let assets = self.assets.lock().unwrap();
+            let mut counts = std::collections::BTreeMap::new();
+            for asset in assets
                 .values()
-                .filter(|a| {
-                    organization_id.is_none_or(|oid| a.organization_id == oid)
-                        && asset_type.is_none_or(|at| a.asset_type == at)
-                        && status.is_none_or(|s| a.status == s)
-                })
-                .count();
+                .filter(|a| a.organization_id == organization_id && a.first_seen >= since)
+            {
+                *counts.entry(asset.first_seen.date_naive()).or_insert(0) += 1;
+            }
+            Ok(counts.into_iter().collect())
+        }
+    }
 
-            Ok(count)
+    // A mock history repository that keeps entries in memory
+    #[derive(Clone)]
+    struct MockAssetHistoryRepository {
+        entries: Arc<Mutex<Vec<AssetHistory>>>,
+    }
+
+    impl MockAssetHistoryRepository {
+        fn new() -> Self {
+            Self {
+                entries: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AssetHistoryRepository for MockAssetHistoryRepository {
+        async fn create_history_entry(&self, entry: &AssetHistory) -> Result<AssetHistory> {
+            self.entries.lock().unwrap().push(entry.clone());
+            Ok(entry.clone())
+        }
+
+        async fn list_asset_history(
+            &self,
+            asset_id: ID,
+            limit: usize,
+            offset: usize,
+        ) -> Result<Vec<AssetHistory>> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .rev()
+                .filter(|e| e.asset_id == asset_id)
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn count_asset_history(&self, asset_id: ID) -> Result<usize> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries.iter().filter(|e| e.asset_id == asset_id).count())
         }
     }
 
@@ -116,7 +403,10 @@ mod tests {
     #[test]
     async fn test_create_asset() {
         let repository = MockAssetRepository::new();
-        let service = AssetServiceImpl::new(Arc::new(repository));
+        let service = AssetServiceImpl::new(
+            Arc::new(repository),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         let org_id = Uuid::new_v4();
         let asset = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
@@ -130,7 +420,10 @@ mod tests {
     #[test]
     async fn test_get_asset() {
         let repository = MockAssetRepository::new();
-        let service = AssetServiceImpl::new(Arc::new(repository));
+        let service = AssetServiceImpl::new(
+            Arc::new(repository),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         let org_id = Uuid::new_v4();
         let asset = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
@@ -148,7 +441,10 @@ mod tests {
     #[test]
     async fn test_update_asset() {
         let repository = MockAssetRepository::new();
-        let service = AssetServiceImpl::new(Arc::new(repository));
+        let service = AssetServiceImpl::new(
+            Arc::new(repository),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         let org_id = Uuid::new_v4();
         let mut asset = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
@@ -167,10 +463,174 @@ mod tests {
         assert_eq!(updated.status, AssetStatus::Inactive);
     }
 
+    #[test]
+    async fn test_invalid_attributes_are_rejected() {
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let invalid = Asset::new(
+            org_id,
+            AssetType::WebApp,
+            "https://example.com".into(),
+            Some(serde_json::json!({ "host_info": { "ip_address": 42 } })),
+        );
+        let err = service.create_asset(&invalid).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{:?}", err);
+
+        let mut asset = service
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::WebApp,
+                "https://example.com".into(),
+                Some(serde_json::json!({ "host_info": { "ip_address": "192.0.2.1" } })),
+            ))
+            .await
+            .unwrap();
+
+        // An update with bad attributes leaves the stored asset untouched
+        asset.attributes = serde_json::json!({ "host_info": "192.0.2.1" });
+        let err = service.update_asset(&asset).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{:?}", err);
+        let stored = service.get_asset(asset.id).await.unwrap();
+        assert_eq!(stored.attributes["host_info"]["ip_address"], "192.0.2.1");
+    }
+
+    #[test]
+    async fn test_create_asset_canonicalizes_and_deduplicates() {
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let first = service
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::Domain,
+                "Example.com.".into(),
+                Some(serde_json::json!({ "source": "dns" })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(first.value, "example.com");
+
+        let second = service
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::Domain,
+                "http://example.com/".into(),
+                Some(serde_json::json!({ "whois_info": {} })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.attributes["source"], "dns");
+        assert!(second.attributes.get("whois_info").is_some());
+
+        let assets = service
+            .list_assets(Some(org_id), None, None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(assets.len(), 1);
+
+        let err = service
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::IPAddress,
+                "300.1.1.1".into(),
+                None,
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{:?}", err);
+    }
+
+    #[test]
+    async fn test_update_asset_status_records_history() {
+        let history = MockAssetHistoryRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(history.clone()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let asset = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
+        let mut asset = service.create_asset(&asset).await.unwrap();
+
+        asset.status = AssetStatus::Inactive;
+        service.update_asset(&asset).await.unwrap();
+
+        let entries = service.get_asset_history(asset.id, 10, 0).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].asset_id, asset.id);
+        assert_eq!(entries[0].field, "status");
+        assert_eq!(entries[0].old_value, serde_json::json!("ACTIVE"));
+        assert_eq!(entries[0].new_value, serde_json::json!("INACTIVE"));
+        assert_eq!(service.count_asset_history(asset.id).await.unwrap(), 1);
+    }
+
+    #[test]
+    async fn test_update_asset_attributes_records_changed_keys() {
+        let history = MockAssetHistoryRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(history.clone()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let asset = Asset::new(
+            org_id,
+            AssetType::Domain,
+            "example.com".into(),
+            Some(serde_json::json!({ "nginx": "1.24", "registrar": "Example" })),
+        );
+        let mut asset = service.create_asset(&asset).await.unwrap();
+
+        // Bump one technology version and open a port; the registrar is unchanged
+        asset.attributes = serde_json::json!({
+            "nginx": "1.25",
+            "registrar": "Example",
+            "open_ports": [443]
+        });
+        service.update_asset(&asset).await.unwrap();
+
+        let mut fields: Vec<String> = history
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.field.clone())
+            .collect();
+        fields.sort();
+        assert_eq!(fields, vec!["attributes.nginx", "attributes.open_ports"]);
+    }
+
+    #[test]
+    async fn test_update_asset_without_changes_records_nothing() {
+        let history = MockAssetHistoryRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(history.clone()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let asset = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
+        let created = service.create_asset(&asset).await.unwrap();
+
+        service.update_asset(&created).await.unwrap();
+        assert!(history.entries.lock().unwrap().is_empty());
+    }
+
     #[test]
     async fn test_delete_asset() {
         let repository = MockAssetRepository::new();
-        let service = AssetServiceImpl::new(Arc::new(repository));
+        let service = AssetServiceImpl::new(
+            Arc::new(repository),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         let org_id = Uuid::new_v4();
         let asset = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
@@ -193,7 +653,10 @@ mod tests {
     #[test]
     async fn test_list_assets() {
         let repository = MockAssetRepository::new();
-        let service = AssetServiceImpl::new(Arc::new(repository));
+        let service = AssetServiceImpl::new(
+            Arc::new(repository),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         let org_id = Uuid::new_v4();
 
@@ -216,21 +679,21 @@ mod tests {
 
         // Test filtering by organization
         let results = service
-            .list_assets(Some(org_id), None, None, 10, 0)
+            .list_assets(Some(org_id), None, None, None, 10, 0)
             .await
             .unwrap();
         assert_eq!(results.len(), 3);
 
         // Test filtering by asset type
         let results = service
-            .list_assets(Some(org_id), Some(AssetType::Domain), None, 10, 0)
+            .list_assets(Some(org_id), Some(AssetType::Domain), None, None, 10, 0)
             .await
             .unwrap();
         assert_eq!(results.len(), 2);
 
         // Test pagination
         let results = service
-            .list_assets(Some(org_id), None, None, 1, 1)
+            .list_assets(Some(org_id), None, None, None, 1, 1)
             .await
             .unwrap();
         assert_eq!(results.len(), 1);
@@ -239,7 +702,10 @@ mod tests {
     #[test]
     async fn test_count_assets() {
         let repository = MockAssetRepository::new();
-        let service = AssetServiceImpl::new(Arc::new(repository));
+        let service = AssetServiceImpl::new(
+            Arc::new(repository),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         let org_id = Uuid::new_v4();
 
@@ -262,18 +728,489 @@ mod tests {
 
         // Test counting with filters
         let count = service
-            .count_assets(Some(org_id), None, None)
+            .count_assets(Some(org_id), None, None, None)
             .await
             .unwrap();
         assert_eq!(count, 3);
 
         let count = service
-            .count_assets(Some(org_id), Some(AssetType::Domain), None)
+            .count_assets(Some(org_id), Some(AssetType::Domain), None, None)
             .await
             .unwrap();
         assert_eq!(count, 2);
 
-        let count = service.count_assets(None, None, None).await.unwrap();
+        let count = service.count_assets(None, None, None, None).await.unwrap();
         assert_eq!(count, 4);
     }
+
+    #[test]
+    async fn test_tags_are_normalized_and_filter_listings() {
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let tagged = service
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::Domain,
+                "pay.example.com".into(),
+                None,
+            ))
+            .await
+            .unwrap();
+        service
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::Domain,
+                "www.example.com".into(),
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let tags = service
+            .add_tags(
+                tagged.id,
+                &[" PCI-Scope ".to_string(), "pci-scope".to_string()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(tags, vec!["pci-scope"]);
+        assert_eq!(
+            service.get_tags(tagged.id).await.unwrap(),
+            vec!["pci-scope"]
+        );
+
+        // The filter is normalized the same way
+        let listed = service
+            .list_assets(
+                Some(org_id),
+                None,
+                None,
+                Some("PCI-SCOPE".to_string()),
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, tagged.id);
+
+        assert!(service.remove_tag(tagged.id, "PCI-Scope").await.unwrap());
+        assert_eq!(
+            service
+                .count_assets(Some(org_id), None, None, Some("pci-scope".to_string()))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    async fn test_add_tags_rejects_invalid_tags() {
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+        let asset = service
+            .create_asset(&Asset::new(
+                Uuid::new_v4(),
+                AssetType::Domain,
+                "example.com".into(),
+                None,
+            ))
+            .await
+            .unwrap();
+
+        for tag in ["   ".to_string(), "x".repeat(65)] {
+            match service.add_tags(asset.id, &[tag]).await {
+                Err(Error::Validation(_)) => {}
+                other => panic!("Expected validation error, got {:?}", other),
+            }
+        }
+
+        // Tagging an unknown asset is a NotFound rather than a database error
+        match service
+            .add_tags(Uuid::new_v4(), &["prod".to_string()])
+            .await
+        {
+            Err(Error::NotFound(_)) => {}
+            other => panic!("Expected NotFound error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    async fn test_bulk_update_asset_status_ignores_other_organizations() {
+        let service = AssetServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let mut ids = Vec::new();
+        for (organization_id, value) in [
+            (org_id, "a.example.com"),
+            (org_id, "b.example.com"),
+            (Uuid::new_v4(), "other-org.com"),
+        ] {
+            let asset = Asset::new(organization_id, AssetType::Domain, value.into(), None);
+            ids.push(service.create_asset(&asset).await.unwrap().id);
+        }
+
+        let updated = service
+            .bulk_update_asset_status(org_id, ids.clone(), AssetStatus::Archived)
+            .await
+            .unwrap();
+        assert_eq!(updated, 2);
+
+        assert_eq!(
+            service.get_asset(ids[0]).await.unwrap().status,
+            AssetStatus::Archived
+        );
+        assert_eq!(
+            service.get_asset(ids[1]).await.unwrap().status,
+            AssetStatus::Archived
+        );
+        assert_eq!(
+            service.get_asset(ids[2]).await.unwrap().status,
+            AssetStatus::Active
+        );
+    }
+
+    #[test]
+    async fn test_related_assets_paginate_in_both_directions() {
+        let repository = MockAssetRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(repository.clone()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let source = Asset::new(org_id, AssetType::Domain, "example.com".into(), None);
+        let source = repository.create_asset(&source).await.unwrap();
+        let mut hosts = Vec::new();
+        for i in 0..12 {
+            let target = Asset::new(
+                org_id,
+                AssetType::IPAddress,
+                format!("192.0.2.{}", i + 10),
+                None,
+            );
+            hosts.push(repository.create_asset(&target).await.unwrap().id);
+            assert!(service
+                .create_asset_relationship(source.id, hosts[i], "hosts".to_string(), None)
+                .await
+                .unwrap());
+        }
+        let mut dependencies = Vec::new();
+        for name in ["auth.example.net", "cdn.example.net", "gone.example.net"] {
+            let target = Asset::new(org_id, AssetType::Domain, name.into(), None);
+            let target = repository.create_asset(&target).await.unwrap();
+            service
+                .create_asset_relationship(
+                    source.id,
+                    target.id,
+                    "depends_on".to_string(),
+                    Some(serde_json::json!({ "via": "script" })),
+                )
+                .await
+                .unwrap();
+            dependencies.push(target.id);
+        }
+        // A related asset that has since been deleted is left out
+        repository.delete_asset(dependencies[2]).await.unwrap();
+
+        let related_ids = |related: Vec<(Asset, String)>| -> Vec<(ID, String)> {
+            related
+                .into_iter()
+                .map(|(asset, rel_type)| (asset.id, rel_type))
+                .collect()
+        };
+
+        // Relationship types are ordered, so the first page starts with
+        // the dependencies
+        let first = service
+            .get_related_assets(source.id, None, 5, 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            related_ids(first),
+            vec![
+                (dependencies[0], "depends_on".to_string()),
+                (dependencies[1], "depends_on".to_string()),
+                (hosts[0], "hosts".to_string()),
+                (hosts[1], "hosts".to_string()),
+                (hosts[2], "hosts".to_string()),
+            ]
+        );
+
+        let last = service
+            .get_related_assets(source.id, None, 10, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            related_ids(last),
+            hosts[8..]
+                .iter()
+                .map(|id| (*id, "hosts".to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        let filtered = service
+            .get_related_assets(source.id, Some("hosts".to_string()), 4, 4)
+            .await
+            .unwrap();
+        assert_eq!(
+            related_ids(filtered),
+            hosts[4..8]
+                .iter()
+                .map(|id| (*id, "hosts".to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        let beyond = service
+            .get_related_assets(source.id, None, 10, 100)
+            .await
+            .unwrap();
+        assert!(beyond.is_empty());
+
+        // The same relationships seen from the other end
+        let referring = service
+            .get_referring_assets(hosts[3], None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            related_ids(referring),
+            vec![(source.id, "hosts".to_string())]
+        );
+        assert!(service
+            .get_referring_assets(dependencies[0], Some("hosts".to_string()), 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Recording a relationship twice adds nothing, and deleting one
+        // removes it from both directions
+        assert!(!service
+            .create_asset_relationship(source.id, hosts[0], "hosts".to_string(), None)
+            .await
+            .unwrap());
+        assert!(service
+            .delete_asset_relationship(source.id, dependencies[0], "depends_on".to_string())
+            .await
+            .unwrap());
+        assert!(service
+            .get_referring_assets(dependencies[0], None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Relationships of an unknown asset are an error, not an empty list
+        assert!(matches!(
+            service
+                .get_related_assets(Uuid::new_v4(), None, 10, 0)
+                .await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    async fn test_discover_asset_relationships_scales_past_a_thousand_assets() {
+        let repository = MockAssetRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(repository.clone()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let mut ids: HashMap<String, ID> = HashMap::new();
+        let mut add = |asset: Asset| {
+            ids.insert(asset.value.clone(), asset.id);
+            let repository = repository.clone();
+            async move { repository.create_asset(&asset).await.unwrap() }
+        };
+
+        // 50 parents with 60 subdomains each, and one sub-subdomain per parent
+        for p in 0..50 {
+            let parent = format!("corp{}.example", p);
+            add(Asset::new(org_id, AssetType::Domain, parent.clone(), None)).await;
+            for s in 0..60 {
+                let value = format!("host{}.{}", s, parent);
+                add(Asset::new(org_id, AssetType::Domain, value, None)).await;
+            }
+            let value = format!("api.host0.{}", parent);
+            add(Asset::new(org_id, AssetType::Domain, value, None)).await;
+        }
+        add(Asset::new(
+            org_id,
+            AssetType::WebApp,
+            "https://www.corp1.example".into(),
+            Some(serde_json::json!({ "host_info": { "domain": "www.corp1.example" } })),
+        ))
+        .await;
+        add(Asset::new(
+            org_id,
+            AssetType::Certificate,
+            "cert-host5-corp2".into(),
+            Some(serde_json::json!({
+                "certificate_info": { "domains": ["host5.corp2.example"] }
+            })),
+        ))
+        .await;
+        // Another organization's assets are left alone
+        add(Asset::new(
+            Uuid::new_v4(),
+            AssetType::Domain,
+            "other.corp0.example".into(),
+            None,
+        ))
+        .await;
+
+        let started = std::time::Instant::now();
+        let relationships = service.discover_asset_relationships(org_id).await.unwrap();
+        let elapsed = started.elapsed();
+        assert!(
+            elapsed < std::time::Duration::from_secs(5),
+            "took {:?}",
+            elapsed
+        );
+
+        let of_type = |relationship_type: &str| -> Vec<(ID, ID)> {
+            relationships
+                .iter()
+                .filter(|(_, _, t)| t == relationship_type)
+                .map(|(source, target, _)| (*source, *target))
+                .collect()
+        };
+
+        // Every subdomain is linked to its parent, the nested ones to both
+        // of theirs
+        let subdomains = of_type("subdomain");
+        assert_eq!(subdomains.len(), 50 * 60 + 50 * 2);
+        for (child, parent) in [
+            ("host59.corp49.example", "corp49.example"),
+            ("api.host0.corp7.example", "host0.corp7.example"),
+            ("api.host0.corp7.example", "corp7.example"),
+        ] {
+            assert!(subdomains.contains(&(ids[child], ids[parent])));
+        }
+        assert!(!subdomains
+            .iter()
+            .any(|(child, _)| *child == ids["other.corp0.example"]));
+
+        // A web app on an unknown host is hosted on its nearest parent
+        assert_eq!(
+            of_type("hosted_on"),
+            vec![(ids["https://www.corp1.example"], ids["corp1.example"])]
+        );
+
+        let mut secured = of_type("secures");
+        secured.sort();
+        let mut expected = vec![
+            (ids["cert-host5-corp2"], ids["host5.corp2.example"]),
+            (ids["cert-host5-corp2"], ids["corp2.example"]),
+        ];
+        expected.sort();
+        assert_eq!(secured, expected);
+    }
+
+    #[test]
+    async fn test_asset_graph_covers_more_than_a_thousand_assets() {
+        let repository = MockAssetRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(repository.clone()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        // 600 domains with one subdomain each, 1200 assets in all
+        let org_id = Uuid::new_v4();
+        let mut expected = Vec::new();
+        for p in 0..600 {
+            let parent = format!("corp{}.example", p);
+            let parent = Asset::new(org_id, AssetType::Domain, parent, None);
+            let child = format!("www.{}", parent.value);
+            let child = Asset::new(org_id, AssetType::Domain, child, None);
+            let parent = repository.create_asset(&parent).await.unwrap();
+            let child = repository.create_asset(&child).await.unwrap();
+            expected.push((child.id, parent.id));
+        }
+
+        let graph = service.asset_graph(org_id).await.unwrap();
+
+        assert_eq!(graph.nodes.len(), 1200);
+        let mut edges: Vec<(ID, ID)> = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.relationship_type == "subdomain")
+            .map(|edge| (edge.source, edge.target))
+            .collect();
+        edges.sort();
+        expected.sort();
+        assert_eq!(edges, expected);
+    }
+
+    #[test]
+    async fn test_create_asset_relationships_in_bulk() {
+        let repository = MockAssetRepository::new();
+        let service = AssetServiceImpl::new(
+            Arc::new(repository.clone()),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let mut ids = Vec::new();
+        for value in ["example.com", "www.example.com", "api.example.com"] {
+            let asset = Asset::new(org_id, AssetType::Domain, value.into(), None);
+            ids.push(service.create_asset(&asset).await.unwrap().id);
+        }
+        let web_app = Asset::new(
+            org_id,
+            AssetType::WebApp,
+            "https://www.example.com".into(),
+            None,
+        );
+        let web_app = service.create_asset(&web_app).await.unwrap();
+        *repository.single_lookups.lock().unwrap() = 0;
+
+        let subdomain = "subdomain".to_string();
+        let added = service
+            .create_asset_relationships(vec![
+                (ids[1], ids[0], subdomain.clone()),
+                (ids[2], ids[0], subdomain.clone()),
+                (web_app.id, ids[1], "hosted_on".to_string()),
+                // Repeated within the batch
+                (ids[1], ids[0], subdomain.clone()),
+                // Unknown source
+                (Uuid::new_v4(), ids[0], subdomain.clone()),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(added, 3);
+        // Nothing was looked up one asset at a time
+        assert_eq!(*repository.single_lookups.lock().unwrap(), 0);
+
+        let related = service
+            .get_related_assets(ids[1], None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0.id, ids[0]);
+        assert_eq!(related[0].1, "subdomain");
+
+        let related = service
+            .get_related_assets(web_app.id, Some("hosted_on".to_string()), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0.value, "www.example.com");
+
+        // Persisting the same relationships again adds nothing
+        let added = service
+            .create_asset_relationships(vec![(ids[2], ids[0], subdomain)])
+            .await
+            .unwrap();
+        assert_eq!(added, 0);
+    }
 }
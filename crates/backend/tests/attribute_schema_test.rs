@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use backend::services::validate_attributes;
+    use backend::Error;
+    use serde_json::{json, Value};
+    use shared::types::AssetType;
+
+    fn problems(asset_type: AssetType, attributes: Value) -> String {
+        match validate_attributes(asset_type, &attributes) {
+            Err(Error::Validation(message)) => message,
+            other => panic!("Expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_domain_attributes() {
+        let valid = json!({
+            "source": "crt.sh",
+            "whois_info": { "registrar": "Example Registrar" },
+            "ports": [{ "port": 443, "protocol": "tcp", "status": "OPEN" }],
+            // Keys without a schema are left alone
+            "notes": ["anything", 1]
+        });
+        assert!(validate_attributes(AssetType::Domain, &valid).is_ok());
+
+        let message = problems(
+            AssetType::Domain,
+            json!({ "ports": [{ "port": 70000, "protocol": "tcp" }] }),
+        );
+        assert!(message.starts_with("Invalid attributes for Domain asset: /ports/0/port: "));
+    }
+
+    #[test]
+    fn test_ip_address_attributes() {
+        let valid = json!({
+            "source": "dns",
+            "asn_info": { "asn": 64496 },
+            "ports": [{ "port": 22 }]
+        });
+        assert!(validate_attributes(AssetType::IPAddress, &valid).is_ok());
+
+        // Every mismatch is reported
+        let message = problems(
+            AssetType::IPAddress,
+            json!({ "asn_info": "AS64496", "ports": [{ "protocol": "tcp" }] }),
+        );
+        assert!(message.contains("/asn_info: "), "{}", message);
+        assert!(message.contains("/ports/0: "), "{}", message);
+    }
+
+    #[test]
+    fn test_web_app_attributes() {
+        let valid = json!({
+            "host_info": {
+                "ip_address": "2001:db8::1",
+                "domain": "example.com",
+                "hostname": "www.example.com"
+            },
+            "dependencies": [{ "url": "https://cdn.example.com/app.js" }],
+            "content_hash": "abc123"
+        });
+        assert!(validate_attributes(AssetType::WebApp, &valid).is_ok());
+
+        let message = problems(
+            AssetType::WebApp,
+            json!({ "host_info": { "ip_address": "not-an-ip" } }),
+        );
+        assert!(message.contains("/host_info/ip_address: "), "{}", message);
+
+        let message = problems(
+            AssetType::WebApp,
+            json!({ "host_info": { "ip_address": 3232235777u32 } }),
+        );
+        assert!(message.contains("/host_info/ip_address: "), "{}", message);
+    }
+
+    #[test]
+    fn test_certificate_attributes() {
+        let valid = json!({
+            "certificate_info": {
+                "domains": ["example.com", "www.example.com"],
+                "issuer": "Example CA"
+            }
+        });
+        assert!(validate_attributes(AssetType::Certificate, &valid).is_ok());
+
+        let message = problems(
+            AssetType::Certificate,
+            json!({ "certificate_info": { "domains": "example.com" } }),
+        );
+        assert!(
+            message.contains("/certificate_info/domains: "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_attributes_must_be_an_object() {
+        for asset_type in [AssetType::Domain, AssetType::CodeRepo] {
+            let message = problems(asset_type, json!(["example.com"]));
+            assert!(message.contains("is not of type \"object\""), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_relationships_must_reference_asset_ids() {
+        let message = problems(
+            AssetType::CloudResource,
+            json!({ "relationships": { "hosts": ["not-a-uuid"] } }),
+        );
+        assert!(message.contains("/relationships/hosts/0: "), "{}", message);
+    }
+}
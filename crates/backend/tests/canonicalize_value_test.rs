@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use backend::services::canonicalize_value;
+    use backend::Error;
+    use shared::types::AssetType;
+
+    fn canonical(asset_type: AssetType, raw: &str) -> String {
+        canonicalize_value(asset_type, raw).unwrap()
+    }
+
+    #[test]
+    fn test_domain_is_lowercased() {
+        assert_eq!(canonical(AssetType::Domain, "Example.COM"), "example.com");
+        assert_eq!(
+            canonical(AssetType::Domain, "  www.Example.com "),
+            "www.example.com"
+        );
+    }
+
+    #[test]
+    fn test_domain_trailing_dots_are_stripped() {
+        assert_eq!(canonical(AssetType::Domain, "example.com."), "example.com");
+        assert_eq!(canonical(AssetType::Domain, "example.com.."), "example.com");
+    }
+
+    #[test]
+    fn test_domain_url_is_reduced_to_host() {
+        assert_eq!(
+            canonical(AssetType::Domain, "http://Example.com/"),
+            "example.com"
+        );
+        assert_eq!(
+            canonical(AssetType::Domain, "https://api.example.com:8443/v1?x=1"),
+            "api.example.com"
+        );
+    }
+
+    #[test]
+    fn test_domain_variants_share_one_value() {
+        let values: Vec<String> = ["Example.com", "example.com.", "http://example.com/"]
+            .into_iter()
+            .map(|raw| canonical(AssetType::Domain, raw))
+            .collect();
+
+        assert!(values.iter().all(|value| value == "example.com"));
+    }
+
+    #[test]
+    fn test_ip_addresses_are_validated() {
+        assert_eq!(
+            canonical(AssetType::IPAddress, " 192.0.2.10 "),
+            "192.0.2.10"
+        );
+        assert_eq!(
+            canonical(AssetType::IPAddress, "2001:DB8:0:0::0001"),
+            "2001:db8::1"
+        );
+
+        for raw in ["not-an-ip", "192.0.2.300", "192.0.2"] {
+            assert!(
+                matches!(
+                    canonicalize_value(AssetType::IPAddress, raw),
+                    Err(Error::Validation(_))
+                ),
+                "{} should be rejected",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_url_scheme_and_host_are_lowercased() {
+        assert_eq!(
+            canonical(AssetType::WebApp, "HTTPS://App.Example.com/Login"),
+            "https://app.example.com/Login"
+        );
+    }
+
+    #[test]
+    fn test_url_default_port_is_dropped() {
+        assert_eq!(
+            canonical(AssetType::WebApp, "http://example.com:80/"),
+            "http://example.com"
+        );
+        assert_eq!(
+            canonical(AssetType::WebApp, "https://example.com:443/app"),
+            "https://example.com/app"
+        );
+        // Other ports are part of the address
+        assert_eq!(
+            canonical(AssetType::WebApp, "https://example.com:8443/"),
+            "https://example.com:8443"
+        );
+    }
+
+    #[test]
+    fn test_url_trailing_slash_and_fragment_are_dropped() {
+        assert_eq!(
+            canonical(AssetType::WebApp, "https://example.com/"),
+            "https://example.com"
+        );
+        assert_eq!(
+            canonical(AssetType::WebApp, "https://example.com/docs/#intro"),
+            "https://example.com/docs"
+        );
+        // The path before a query is left alone
+        assert_eq!(
+            canonical(AssetType::WebApp, "https://example.com/search/?q=1"),
+            "https://example.com/search/?q=1"
+        );
+    }
+
+    #[test]
+    fn test_url_without_scheme_defaults_to_https() {
+        assert_eq!(
+            canonical(AssetType::WebApp, "example.com/app/"),
+            "https://example.com/app"
+        );
+    }
+
+    #[test]
+    fn test_other_values_are_trimmed() {
+        assert_eq!(
+            canonical(AssetType::CodeRepo, "  github.com/Example/Repo "),
+            "github.com/Example/Repo"
+        );
+        assert_eq!(
+            canonical(AssetType::CloudResource, "arn:aws:s3:::Bucket\n"),
+            "arn:aws:s3:::Bucket"
+        );
+    }
+
+    #[test]
+    fn test_empty_value_is_rejected() {
+        for asset_type in [AssetType::Domain, AssetType::WebApp, AssetType::CodeRepo] {
+            assert!(matches!(
+                canonicalize_value(asset_type, "   "),
+                Err(Error::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            canonicalize_value(AssetType::Domain, "..."),
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+use backend::models::DiscoveryJob;
+use chrono::{Duration, TimeZone, Utc};
+use shared::types::{JobStatus, JobType};
+use uuid::Uuid;
+
+fn job() -> DiscoveryJob {
+    DiscoveryJob::new(
+        Uuid::new_v4(),
+        JobType::PortScan,
+        Some("example.com".to_string()),
+        None,
+    )
+}
+
+#[test]
+fn test_completed_job_duration() {
+    let started_at = Utc.with_ymd_and_hms(2025, 5, 1, 12, 0, 0).unwrap();
+    let mut job = job();
+    job.status = JobStatus::Completed;
+    job.started_at = Some(started_at);
+    job.completed_at = Some(started_at + Duration::seconds(45 * 60 + 12));
+
+    // Later checks don't stretch a finished job
+    let much_later = started_at + Duration::days(3);
+    assert_eq!(
+        job.duration_at(much_later),
+        Some(Duration::seconds(45 * 60 + 12))
+    );
+    assert_eq!(job.duration(), Some(Duration::seconds(45 * 60 + 12)));
+}
+
+#[test]
+fn test_running_job_duration_runs_to_now() {
+    let started_at = Utc.with_ymd_and_hms(2025, 5, 1, 12, 0, 0).unwrap();
+    let mut job = job();
+    job.status = JobStatus::Running;
+    job.started_at = Some(started_at);
+
+    assert_eq!(
+        job.duration_at(started_at + Duration::minutes(10)),
+        Some(Duration::minutes(10))
+    );
+
+    job.started_at = Some(Utc::now() - Duration::minutes(5));
+    assert!(job.duration().unwrap() >= Duration::minutes(5));
+}
+
+#[test]
+fn test_pending_job_has_no_duration() {
+    let job = job();
+    assert_eq!(job.status, JobStatus::Pending);
+    assert_eq!(job.duration_at(Utc::now()), None);
+    assert_eq!(job.duration(), None);
+}
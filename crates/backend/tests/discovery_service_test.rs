@@ -1,24 +1,40 @@
 #[cfg(test)]
 mod tests {
     use async_trait::async_trait;
-    use backend::models::Vulnerability;
-    use backend::models::{Asset, DiscoveryJob, JobAssetLink};
+    use backend::models::{
+        Asset, AssetFilter, AssetRelationship, DiscoveryJob, DiscoveryJobFilter, JobAssetLink,
+        JobResultSummary, RelationshipDirection,
+    };
+    use backend::models::{Vulnerability, VulnerabilityActivity, VulnerabilityGroup};
+    use backend::services::{DiscoveryServiceImpl, VulnerabilityServiceImpl};
     use backend::{
-        AssetRepository, DiscoveryJobRepository, DiscoveryService, Error, Result,
-        VulnerabilityRepository,
+        AssetRepository, DiscoveryJobRepository, DiscoveryService, Error, NotificationPeriod,
+        NotificationService, NotificationSettings, Result, VulnerabilityRepository,
+        VulnerabilityService,
+    };
+    use discovery::asn::AsnDatabase;
+    use discovery::port_scan::DiscoveredPort;
+    use discovery::results::{
+        DiscoveredDomain, DiscoveredIp, DiscoveredWebResource, DiscoveryResult,
     };
-    use shared::types::{AssetStatus, Severity, VulnerabilityStatus};
-    use shared::types::{AssetType, JobStatus, JobType, ID};
+    use discovery::web_crawl::content_hash::content_hash;
+    use discovery::whois::WhoisInfo;
+    use shared::types::{AssetStatus, Severity, Sort, VulnerabilitySortField, VulnerabilityStatus};
+    use shared::types::{AssetType, JobSortField, JobStatus, JobType, Timestamp, ID};
     use std::collections::HashMap;
     use std::sync::{Arc, Mutex};
     use tokio::test;
     use uuid::Uuid;
 
+    type ScanKey = (ID, String, String);
+
     #[derive(Clone)]
     struct MockDiscoveryJobRepository {
         jobs: Arc<Mutex<HashMap<ID, DiscoveryJob>>>,
         assets: Arc<Mutex<HashMap<ID, Asset>>>,
         links: Arc<Mutex<Vec<JobAssetLink>>>,
+        // Last scan by organization, job type and target
+        scans: Arc<Mutex<HashMap<ScanKey, Timestamp>>>,
     }
 
     impl MockDiscoveryJobRepository {
@@ -27,6 +43,7 @@ mod tests {
                 jobs: Arc::new(Mutex::new(HashMap::new())),
                 assets: Arc::new(Mutex::new(HashMap::new())),
                 links: Arc::new(Mutex::new(Vec::new())),
+                scans: Arc::new(Mutex::new(HashMap::new())),
             }
         }
 
@@ -79,20 +96,15 @@ mod tests {
 
         async fn list_jobs(
             &self,
-            organization_id: Option<ID>,
-            job_type: Option<JobType>,
-            status: Option<JobStatus>,
+            filter: &DiscoveryJobFilter,
+            _sort: Option<Sort<JobSortField>>,
             limit: usize,
             offset: usize,
         ) -> Result<Vec<DiscoveryJob>> {
             let jobs = self.jobs.lock().unwrap();
             let filtered: Vec<DiscoveryJob> = jobs
                 .values()
-                .filter(|j| {
-                    organization_id.is_none_or(|oid| j.organization_id == oid)
-                        && job_type.is_none_or(|jt| j.job_type == jt)
-                        && status.is_none_or(|s| j.status == s)
-                })
+                .filter(|j| filter.matches(j))
                 .cloned()
                 .collect();
 
@@ -100,21 +112,9 @@ mod tests {
             Ok(paginated)
         }
 
-        async fn count_jobs(
-            &self,
-            organization_id: Option<ID>,
-            job_type: Option<JobType>,
-            status: Option<JobStatus>,
-        ) -> Result<usize> {
+        async fn count_jobs(&self, filter: &DiscoveryJobFilter) -> Result<usize> {
             let jobs = self.jobs.lock().unwrap();
-            let count = jobs
-                .values()
-                .filter(|j| {
-                    organization_id.is_none_or(|oid| j.organization_id == oid)
-                        && job_type.is_none_or(|jt| j.job_type == jt)
-                        && status.is_none_or(|s| j.status == s)
-                })
-                .count();
+            let count = jobs.values().filter(|j| filter.matches(j)).count();
             Ok(count)
         }
 
@@ -125,6 +125,21 @@ mod tests {
             Ok(new_link)
         }
 
+        async fn link_job_to_assets(&self, job_id: ID, asset_ids: &[ID]) -> Result<usize> {
+            let mut links = self.links.lock().unwrap();
+            let mut created = 0;
+            for &asset_id in asset_ids {
+                if !links
+                    .iter()
+                    .any(|link| link.job_id == job_id && link.asset_id == asset_id)
+                {
+                    links.push(JobAssetLink::new(job_id, asset_id));
+                    created += 1;
+                }
+            }
+            Ok(created)
+        }
+
         async fn get_job_assets(&self, job_id: ID) -> Result<Vec<Asset>> {
             let links = self.links.lock().unwrap();
             let assets = self.assets.lock().unwrap();
@@ -143,6 +158,46 @@ mod tests {
             Ok(job_assets)
         }
 
+        async fn get_job_summary(&self, job_id: ID) -> Result<JobResultSummary> {
+            Ok(JobResultSummary {
+                assets: self.get_job_assets(job_id).await?.len(),
+                ..Default::default()
+            })
+        }
+
+        async fn record_target_scans(
+            &self,
+            organization_id: ID,
+            job_type: JobType,
+            targets: &[String],
+            scanned_at: Timestamp,
+        ) -> Result<()> {
+            let mut scans = self.scans.lock().unwrap();
+            for target in targets {
+                let last = scans
+                    .entry((organization_id, format!("{job_type:?}"), target.clone()))
+                    .or_insert(scanned_at);
+                *last = (*last).max(scanned_at);
+            }
+            Ok(())
+        }
+
+        async fn last_target_scans(
+            &self,
+            organization_id: ID,
+            job_type: JobType,
+            targets: &[String],
+        ) -> Result<HashMap<String, Timestamp>> {
+            let scans = self.scans.lock().unwrap();
+            Ok(targets
+                .iter()
+                .filter_map(|target| {
+                    let key = (organization_id, format!("{job_type:?}"), target.clone());
+                    scans.get(&key).map(|at| (target.clone(), *at))
+                })
+                .collect())
+        }
+
         async fn list_jobs_by_status(
             &self,
             status: JobStatus,
@@ -163,14 +218,36 @@ mod tests {
     #[derive(Clone)]
     struct MockAssetRepository {
         assets: Arc<Mutex<HashMap<ID, Asset>>>,
+        tags: Arc<Mutex<HashMap<ID, Vec<String>>>>,
     }
 
     impl MockAssetRepository {
         fn new() -> Self {
             Self {
                 assets: Arc::new(Mutex::new(HashMap::new())),
+                tags: Arc::new(Mutex::new(HashMap::new())),
             }
         }
+
+        fn has_tag(&self, asset_id: ID, tag: &str) -> bool {
+            self.tags
+                .lock()
+                .unwrap()
+                .get(&asset_id)
+                .is_some_and(|tags| tags.iter().any(|t| t == tag))
+        }
+
+        fn matches(&self, filter: &AssetFilter, asset: &Asset) -> bool {
+            filter
+                .organization_id
+                .is_none_or(|oid| asset.organization_id == oid)
+                && filter.asset_type.is_none_or(|at| asset.asset_type == at)
+                && filter.status.is_none_or(|s| asset.status == s)
+                && filter
+                    .tag
+                    .as_deref()
+                    .is_none_or(|t| self.has_tag(asset.id, t))
+        }
     }
 
     #[async_trait]
@@ -182,6 +259,34 @@ mod tests {
             Ok(new_asset)
         }
 
+        async fn upsert_asset(&self, asset: &Asset) -> Result<Asset> {
+            let mut assets = self.assets.lock().unwrap();
+            let existing = assets.values_mut().find(|existing| {
+                existing.organization_id == asset.organization_id
+                    && existing.asset_type == asset.asset_type
+                    && existing.value == asset.value
+            });
+
+            match existing {
+                Some(existing) => {
+                    existing.status = asset.status;
+                    existing.last_seen = existing.last_seen.max(asset.last_seen);
+                    if let (Some(stored), Some(new)) = (
+                        existing.attributes.as_object_mut(),
+                        asset.attributes.as_object(),
+                    ) {
+                        stored.extend(new.clone());
+                    }
+                    existing.updated_at = asset.updated_at;
+                    Ok(existing.clone())
+                }
+                None => {
+                    assets.insert(asset.id, asset.clone());
+                    Ok(asset.clone())
+                }
+            }
+        }
+
         async fn get_asset(&self, id: ID) -> Result<Asset> {
             let assets = self.assets.lock().unwrap();
             assets
@@ -216,11 +321,14 @@ mod tests {
             Ok(true)
         }
 
+        async fn restore_asset(&self, _id: ID) -> Result<bool> {
+            // Deleted assets are dropped outright, so there is nothing to restore
+            Ok(false)
+        }
+
         async fn list_assets(
             &self,
-            organization_id: Option<ID>,
-            asset_type: Option<AssetType>,
-            status: Option<AssetStatus>,
+            filter: &AssetFilter,
             limit: usize,
             offset: usize,
         ) -> Result<Vec<Asset>> {
@@ -228,11 +336,7 @@ mod tests {
 
             let filtered: Vec<Asset> = assets
                 .values()
-                .filter(|a| {
-                    organization_id.is_none_or(|oid| a.organization_id == oid)
-                        && asset_type.is_none_or(|at| a.asset_type == at)
-                        && status.is_none_or(|s| a.status == s)
-                })
+                .filter(|a| self.matches(filter, a))
                 .cloned()
                 .collect();
 
@@ -241,24 +345,152 @@ mod tests {
             Ok(paginated)
         }
 
-        async fn count_assets(
+        async fn count_assets(&self, filter: &AssetFilter) -> Result<usize> {
+            let assets = self.assets.lock().unwrap();
+
+            let count = assets.values().filter(|a| self.matches(filter, a)).count();
+
+            Ok(count)
+        }
+
+        async fn add_asset_tags(&self, asset_id: ID, tags: &[String]) -> Result<Vec<String>> {
+            let mut all_tags = self.tags.lock().unwrap();
+            let asset_tags = all_tags.entry(asset_id).or_default();
+            asset_tags.extend(tags.iter().cloned());
+            asset_tags.sort();
+            asset_tags.dedup();
+            Ok(asset_tags.clone())
+        }
+
+        async fn remove_asset_tag(&self, _asset_id: ID, _tag: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn list_asset_tags(&self, asset_id: ID) -> Result<Vec<String>> {
+            Ok(self
+                .tags
+                .lock()
+                .unwrap()
+                .get(&asset_id)
+                .cloned()
+                .unwrap_or_default())
+        }
+
+        // Relationships aren't tracked here
+        async fn add_asset_relationship(&self, _relationship: &AssetRelationship) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn add_relationships_bulk(
+            &self,
+            _relationships: Vec<(ID, ID, String)>,
+        ) -> Result<usize> {
+            Ok(0)
+        }
+
+        async fn remove_asset_relationship(
+            &self,
+            _source_asset_id: ID,
+            _target_asset_id: ID,
+            _relationship_type: &str,
+        ) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn list_asset_relationships(
+            &self,
+            _asset_id: ID,
+            _direction: RelationshipDirection,
+            _relationship_type: Option<String>,
+            _limit: usize,
+            _offset: usize,
+        ) -> Result<Vec<(AssetRelationship, Asset)>> {
+            Ok(Vec::new())
+        }
+
+        async fn bulk_update_status(
+            &self,
+            organization_id: ID,
+            asset_ids: &[ID],
+            status: AssetStatus,
+        ) -> Result<usize> {
+            let mut assets = self.assets.lock().unwrap();
+
+            let mut updated = 0;
+            for asset in assets
+                .values_mut()
+                .filter(|a| a.organization_id == organization_id && asset_ids.contains(&a.id))
+            {
+                asset.status = status;
+                updated += 1;
+            }
+
+            Ok(updated)
+        }
+
+        async fn mark_stale(
             &self,
             organization_id: Option<ID>,
-            asset_type: Option<AssetType>,
-            status: Option<AssetStatus>,
+            older_than: chrono::Duration,
         ) -> Result<usize> {
-            let assets = self.assets.lock().unwrap();
+            let mut assets = self.assets.lock().unwrap();
+            let cutoff = chrono::Utc::now() - older_than;
+
+            let mut marked = 0;
+            for asset in assets.values_mut().filter(|a| {
+                organization_id.is_none_or(|oid| a.organization_id == oid)
+                    && a.status == AssetStatus::Active
+                    && a.last_seen < cutoff
+            }) {
+                asset.status = AssetStatus::Inactive;
+                asset.updated_at = chrono::Utc::now();
+                marked += 1;
+            }
+
+            Ok(marked)
+        }
+
+        async fn update_risk_score(&self, id: ID, risk_score: f32) -> Result<bool> {
+            let mut assets = self.assets.lock().unwrap();
+            match assets.get_mut(&id) {
+                Some(asset) => {
+                    asset.risk_score = risk_score;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
 
-            let count = assets
+        async fn list_recent_assets(
+            &self,
+            organization_id: ID,
+            limit: usize,
+        ) -> Result<Vec<Asset>> {
+            let assets = self.assets.lock().unwrap();
+            let mut recent: Vec<Asset> = assets
                 .values()
-                .filter(|a| {
-                    organization_id.is_none_or(|oid| a.organization_id == oid)
-                        && asset_type.is_none_or(|at| a.asset_type == at)
-                        && status.is_none_or(|s| a.status == s)
-                })
-                .count();
+                .filter(|a| a.organization_id == organization_id)
+                .cloned()
+                .collect();
+            recent.sort_by_key(|asset| std::cmp::Reverse(asset.first_seen));
+            recent.truncate(limit);
+            Ok(recent)
+        }
 
-            Ok(count)
+        async fn asset_counts_by_day(
+            &self,
+            organization_id: ID,
+            since: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+            let assets = self.assets.lock().unwrap();
+            let mut counts = std::collections::BTreeMap::new();
+            for asset in assets
+                .values()
+                .filter(|a| a.organization_id == organization_id && a.first_seen >= since)
+            {
+                *counts.entry(asset.first_seen.date_naive()).or_insert(0) += 1;
+            }
+            Ok(counts.into_iter().collect())
         }
     }
 
@@ -327,12 +559,18 @@ mod tests {
             Ok(true)
         }
 
+        async fn restore_vulnerability(&self, _id: ID) -> Result<bool> {
+            // Deleted vulnerabilities are dropped outright, so there is nothing to restore
+            Ok(false)
+        }
+
         async fn list_vulnerabilities(
             &self,
             asset_id: Option<ID>,
             port_id: Option<ID>,
             severity: Option<Severity>,
             status: Option<VulnerabilityStatus>,
+            _include_deleted: bool,
             limit: usize,
             offset: usize,
         ) -> Result<Vec<Vulnerability>> {
@@ -354,12 +592,36 @@ mod tests {
             Ok(paginated)
         }
 
+        async fn list_vulnerabilities_sorted(
+            &self,
+            asset_id: Option<ID>,
+            port_id: Option<ID>,
+            severity: Option<Severity>,
+            status: Option<VulnerabilityStatus>,
+            include_deleted: bool,
+            _sort: Option<Sort<VulnerabilitySortField>>,
+            limit: usize,
+            offset: usize,
+        ) -> Result<Vec<Vulnerability>> {
+            self.list_vulnerabilities(
+                asset_id,
+                port_id,
+                severity,
+                status,
+                include_deleted,
+                limit,
+                offset,
+            )
+            .await
+        }
+
         async fn count_vulnerabilities(
             &self,
             asset_id: Option<ID>,
             port_id: Option<ID>,
             severity: Option<Severity>,
             status: Option<VulnerabilityStatus>,
+            _include_deleted: bool,
         ) -> Result<usize> {
             let vulnerabilities = self.vulnerabilities.lock().unwrap();
 
@@ -375,6 +637,76 @@ mod tests {
 
             Ok(count)
         }
+
+        async fn group_open_vulnerabilities(
+            &self,
+            _organization_id: ID,
+        ) -> Result<Vec<VulnerabilityGroup>> {
+            // Assets aren't tracked here, so there is no organization to group by
+            Ok(Vec::new())
+        }
+
+        async fn bulk_update_status(
+            &self,
+            _organization_id: ID,
+            _vulnerability_ids: &[ID],
+            _status: VulnerabilityStatus,
+        ) -> Result<usize> {
+            // Assets aren't tracked here, so no vulnerability is in scope
+            Ok(0)
+        }
+
+        async fn count_by_severity(
+            &self,
+            _organization_id: ID,
+            status: Option<VulnerabilityStatus>,
+        ) -> Result<HashMap<Severity, usize>> {
+            let vulnerabilities = self.vulnerabilities.lock().unwrap();
+            let mut counts = HashMap::new();
+            for v in vulnerabilities
+                .values()
+                .filter(|v| status.is_none_or(|s| v.status == s))
+            {
+                *counts.entry(v.severity).or_insert(0) += 1;
+            }
+            Ok(counts)
+        }
+
+        async fn vuln_counts_by_day(
+            &self,
+            _organization_id: ID,
+            since: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+            let vulnerabilities = self.vulnerabilities.lock().unwrap();
+            let mut counts = std::collections::BTreeMap::new();
+            for v in vulnerabilities.values().filter(|v| v.first_seen >= since) {
+                *counts.entry(v.first_seen.date_naive()).or_insert(0) += 1;
+            }
+            Ok(counts.into_iter().collect())
+        }
+
+        async fn activity_since(
+            &self,
+            _organization_id: ID,
+            since: chrono::DateTime<chrono::Utc>,
+        ) -> Result<VulnerabilityActivity> {
+            let vulnerabilities = self.vulnerabilities.lock().unwrap();
+            let mut activity = VulnerabilityActivity::default();
+            for v in vulnerabilities.values() {
+                if v.first_seen >= since {
+                    activity.new += 1;
+                    match v.severity {
+                        Severity::Critical => activity.critical += 1,
+                        Severity::High => activity.high += 1,
+                        _ => {}
+                    }
+                }
+                if v.resolved_at.is_some_and(|resolved| resolved >= since) {
+                    activity.resolved += 1;
+                }
+            }
+            Ok(activity)
+        }
     }
 
     // Simplified Discovery Service implementation for testing
@@ -539,6 +871,541 @@ mod tests {
         assert_eq!(ip_assets.len(), 1);
     }
 
+    // Notification service that records which assets it was alerted about
+    #[derive(Clone, Default)]
+    struct RecordingNotificationService {
+        critical_assets: Arc<Mutex<Vec<String>>>,
+        content_changes: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl NotificationService for RecordingNotificationService {
+        async fn notify_new_vulnerability(&self, _vulnerability: &Vulnerability) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn notify_vulnerability_status_change(
+            &self,
+            _vulnerability: &Vulnerability,
+            _old_status: VulnerabilityStatus,
+        ) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn notify_new_critical_asset(&self, asset: &Asset) -> Result<bool> {
+            self.critical_assets
+                .lock()
+                .unwrap()
+                .push(asset.value.clone());
+            Ok(true)
+        }
+
+        async fn notify_content_change(&self, asset: &Asset, _previous_hash: &str) -> Result<bool> {
+            self.content_changes
+                .lock()
+                .unwrap()
+                .push(asset.value.clone());
+            Ok(true)
+        }
+
+        async fn send_summary_report(
+            &self,
+            _organization_id: ID,
+            _period: NotificationPeriod,
+        ) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn get_notification_settings(
+            &self,
+            organization_id: ID,
+        ) -> Result<NotificationSettings> {
+            Ok(NotificationSettings {
+                organization_id,
+                email_notifications: false,
+                email_recipients: Vec::new(),
+                webhook_notifications: false,
+                webhook_url: None,
+                notification_period: NotificationPeriod::default(),
+                notify_on_new_vulnerability: false,
+                notify_on_status_change: false,
+                notify_on_new_critical_asset: true,
+                notify_on_content_change: true,
+                minimum_severity_for_notification: Severity::Critical,
+                additional_settings: None,
+            })
+        }
+
+        async fn update_notification_settings(
+            &self,
+            _organization_id: ID,
+            settings: &NotificationSettings,
+        ) -> Result<NotificationSettings> {
+            Ok(settings.clone())
+        }
+
+        async fn notify_new_vulnerabilities_batch(
+            &self,
+            _vulnerabilities: &[Vulnerability],
+        ) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    fn open_port(ip: &str, port: u16) -> DiscoveredPort {
+        DiscoveredPort {
+            ip_address: ip.parse().unwrap(),
+            port,
+            protocol: "TCP".to_string(),
+            status: "OPEN".to_string(),
+            service_name: None,
+            banner: None,
+            http_status: None,
+            http_title: None,
+            tls_info: None,
+            source: "port_scan".to_string(),
+        }
+    }
+
+    #[test]
+    async fn test_reconcile_results_categorizes_assets() {
+        let job_repo = MockDiscoveryJobRepository::new();
+        let asset_repo = MockAssetRepository::new();
+        let notifications = RecordingNotificationService::default();
+
+        let org_id = Uuid::new_v4();
+
+        // Assets known from a previous run
+        let mut known_domain = Asset::new(
+            org_id,
+            AssetType::Domain,
+            "www.example.com".to_string(),
+            None,
+        );
+        known_domain.last_seen = chrono::Utc::now() - chrono::Duration::days(1);
+        let known_domain = asset_repo.create_asset(&known_domain).await.unwrap();
+        let gone_domain = asset_repo
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::Domain,
+                "old.example.com".to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+        let known_ip = asset_repo
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::IPAddress,
+                "192.0.2.1".to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+        // Another organization's asset is never considered
+        asset_repo
+            .create_asset(&Asset::new(
+                Uuid::new_v4(),
+                AssetType::Domain,
+                "api.example.com".to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let service = DiscoveryServiceImpl::new(Arc::new(asset_repo.clone()), Arc::new(job_repo))
+            .with_notification_service(Arc::new(notifications.clone()));
+
+        let result = DiscoveryResult {
+            domains: vec![
+                DiscoveredDomain {
+                    domain_name: "WWW.example.com.".to_string(),
+                    source: "dns_enum".to_string(),
+                },
+                DiscoveredDomain {
+                    domain_name: "api.example.com".to_string(),
+                    source: "dns_enum".to_string(),
+                },
+            ],
+            ip_addresses: vec![DiscoveredIp {
+                ip_address: "192.0.2.1".parse().unwrap(),
+                source: "dns_lookup".to_string(),
+            }],
+            // A new host with SSH exposed and a known host with a web port
+            ports: vec![open_port("192.0.2.7", 22), open_port("192.0.2.1", 443)],
+            ..Default::default()
+        };
+
+        let report = service.reconcile_results(org_id, &result).await.unwrap();
+
+        let mut new: Vec<&str> = report.new.iter().map(|a| a.value.as_str()).collect();
+        new.sort();
+        assert_eq!(new, vec!["192.0.2.7", "api.example.com"]);
+
+        let mut unchanged: Vec<ID> = report.unchanged.iter().map(|a| a.id).collect();
+        unchanged.sort();
+        let mut expected = vec![known_domain.id, known_ip.id];
+        expected.sort();
+        assert_eq!(unchanged, expected);
+
+        assert_eq!(report.disappeared.len(), 1);
+        assert_eq!(report.disappeared[0].id, gone_domain.id);
+
+        // Known assets were seen again
+        let refreshed = asset_repo.get_asset(known_domain.id).await.unwrap();
+        assert!(refreshed.last_seen > known_domain.last_seen);
+        let refreshed_ip = asset_repo.get_asset(known_ip.id).await.unwrap();
+        assert_eq!(refreshed_ip.attributes["ports"][0]["port"], 443);
+
+        // Only the new host exposing SSH is critical
+        assert_eq!(
+            *notifications.critical_assets.lock().unwrap(),
+            vec!["192.0.2.7".to_string()]
+        );
+    }
+
+    #[test]
+    async fn test_reconcile_results_reads_every_page_of_assets() {
+        let asset_repo = MockAssetRepository::new();
+        let org_id = Uuid::new_v4();
+
+        // More known domains than one page holds
+        for i in 0..2500 {
+            asset_repo
+                .create_asset(&Asset::new(
+                    org_id,
+                    AssetType::Domain,
+                    format!("host{}.example.com", i),
+                    None,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let service = DiscoveryServiceImpl::new(
+            Arc::new(asset_repo.clone()),
+            Arc::new(MockDiscoveryJobRepository::new()),
+        );
+        let result = DiscoveryResult {
+            domains: vec![DiscoveredDomain {
+                domain_name: "host2499.example.com".to_string(),
+                source: "dns_enum".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let report = service.reconcile_results(org_id, &result).await.unwrap();
+
+        // The domain is recognised wherever it falls, and every other one
+        // is reported as gone
+        assert!(report.new.is_empty());
+        assert_eq!(report.unchanged.len(), 1);
+        assert_eq!(report.unchanged[0].value, "host2499.example.com");
+        assert_eq!(report.disappeared.len(), 2499);
+    }
+
+    #[test]
+    async fn test_reconcile_results_stores_whois_info() {
+        let asset_repo = MockAssetRepository::new();
+        let org_id = Uuid::new_v4();
+        let known_domain = asset_repo
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::Domain,
+                "example.org".to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let service = DiscoveryServiceImpl::new(
+            Arc::new(asset_repo.clone()),
+            Arc::new(MockDiscoveryJobRepository::new()),
+        );
+
+        let mut result = DiscoveryResult {
+            domains: ["example.com", "example.org", "www.example.com"]
+                .into_iter()
+                .map(|domain| DiscoveredDomain {
+                    domain_name: domain.to_string(),
+                    source: "dns_input".to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for (domain, registrar) in [
+            ("example.com", "Registrar A"),
+            ("example.org", "Registrar B"),
+        ] {
+            result.add_whois(
+                domain,
+                WhoisInfo {
+                    registrar: Some(registrar.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let report = service.reconcile_results(org_id, &result).await.unwrap();
+
+        // New and known domains both carry their registration details
+        let created = report
+            .new
+            .iter()
+            .find(|asset| asset.value == "example.com")
+            .unwrap();
+        assert_eq!(created.attributes["whois_info"]["registrar"], "Registrar A");
+        let refreshed = asset_repo.get_asset(known_domain.id).await.unwrap();
+        assert_eq!(
+            refreshed.attributes["whois_info"]["registrar"],
+            "Registrar B"
+        );
+
+        // Domains that weren't looked up have none
+        let subdomain = report
+            .new
+            .iter()
+            .find(|asset| asset.value == "www.example.com")
+            .unwrap();
+        assert!(subdomain.attributes.get("whois_info").is_none());
+    }
+
+    #[test]
+    async fn test_reconcile_results_stores_asn_info() {
+        let asset_repo = MockAssetRepository::new();
+        let org_id = Uuid::new_v4();
+        let known_ip = asset_repo
+            .create_asset(&Asset::new(
+                org_id,
+                AssetType::IPAddress,
+                "8.8.8.8".to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let service = DiscoveryServiceImpl::new(
+            Arc::new(asset_repo.clone()),
+            Arc::new(MockDiscoveryJobRepository::new()),
+        );
+
+        let database = AsnDatabase::from_reader(
+            "1.1.1.0\t1.1.1.255\t13335\tUS\tCLOUDFLARENET\n\
+             8.8.8.0\t8.8.8.255\t15169\tUS\tGOOGLE\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let mut result = DiscoveryResult {
+            ip_addresses: ["1.1.1.1", "8.8.8.8", "192.0.2.1"]
+                .into_iter()
+                .map(|ip| DiscoveredIp {
+                    ip_address: ip.parse().unwrap(),
+                    source: "dns_lookup".to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        result.enrich_with_asn(&database);
+
+        let report = service.reconcile_results(org_id, &result).await.unwrap();
+
+        // New and known IPs both carry their network owner
+        let created = report
+            .new
+            .iter()
+            .find(|asset| asset.value == "1.1.1.1")
+            .unwrap();
+        assert_eq!(created.attributes["asn_info"]["asn"], 13335);
+        assert_eq!(created.attributes["asn_info"]["network"], "1.1.1.0/24");
+        let refreshed = asset_repo.get_asset(known_ip.id).await.unwrap();
+        assert_eq!(refreshed.attributes["asn_info"]["organization"], "GOOGLE");
+
+        // IPs outside the dataset have none
+        let unrouted = report
+            .new
+            .iter()
+            .find(|asset| asset.value == "192.0.2.1")
+            .unwrap();
+        assert!(unrouted.attributes.get("asn_info").is_none());
+    }
+
+    fn web_resource(url: &str, body: &str) -> DiscoveredWebResource {
+        DiscoveredWebResource {
+            url: url.to_string(),
+            status_code: 200,
+            title: None,
+            technologies: Vec::new(),
+            source: "web_crawl".to_string(),
+            screenshot_path: None,
+            content_hash: Some(content_hash(body)),
+        }
+    }
+
+    #[test]
+    async fn test_detect_content_changes_alerts_on_critical_assets() {
+        let asset_repo = MockAssetRepository::new();
+        let notifications = RecordingNotificationService::default();
+        let org_id = Uuid::new_v4();
+
+        let mut web_apps = HashMap::new();
+        for url in [
+            "https://www.example.com",
+            "https://blog.example.com",
+            "https://docs.example.com",
+        ] {
+            let asset = asset_repo
+                .create_asset(&Asset::new(
+                    org_id,
+                    AssetType::WebApp,
+                    url.to_string(),
+                    Some(serde_json::json!({ "content_hash": content_hash("<h1>Welcome</h1>") })),
+                ))
+                .await
+                .unwrap();
+            web_apps.insert(url, asset);
+        }
+        for url in ["https://www.example.com", "https://docs.example.com"] {
+            asset_repo
+                .add_asset_tags(web_apps[url].id, &["critical".to_string()])
+                .await
+                .unwrap();
+        }
+
+        let service = DiscoveryServiceImpl::new(
+            Arc::new(asset_repo.clone()),
+            Arc::new(MockDiscoveryJobRepository::new()),
+        )
+        .with_notification_service(Arc::new(notifications.clone()));
+
+        let result = DiscoveryResult {
+            web_resources: vec![
+                web_resource("https://www.example.com", "<h1>Hacked by someone</h1>"),
+                // Crawled URLs match the stored canonical values
+                web_resource("HTTPS://Blog.Example.com/", "<h1>New post</h1>"),
+                web_resource("https://docs.example.com", "<h1>Welcome</h1>"),
+                web_resource("https://new.example.com", "<h1>Hello</h1>"),
+            ],
+            ..Default::default()
+        };
+
+        let changes = service
+            .detect_content_changes(org_id, &result)
+            .await
+            .unwrap();
+
+        // Unchanged and previously unknown pages aren't changes
+        let mut changed: Vec<_> = changes.iter().map(|c| c.url.as_str()).collect();
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec!["https://blog.example.com", "https://www.example.com"]
+        );
+
+        // Only the critical asset's change is alerted on
+        assert_eq!(
+            *notifications.content_changes.lock().unwrap(),
+            vec!["https://www.example.com".to_string()]
+        );
+
+        // The new hash is stored for the next comparison
+        let stored = asset_repo
+            .get_asset(web_apps["https://www.example.com"].id)
+            .await
+            .unwrap();
+        assert_eq!(
+            stored.attributes["content_hash"],
+            content_hash("<h1>Hacked by someone</h1>")
+        );
+        let again = service
+            .detect_content_changes(org_id, &result)
+            .await
+            .unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    async fn test_incremental_job_skips_recently_scanned_targets() {
+        let job_repo = MockDiscoveryJobRepository::new();
+        let service = DiscoveryServiceImpl::new(
+            Arc::new(MockAssetRepository::new()),
+            Arc::new(job_repo.clone()),
+        );
+
+        let org_id = Uuid::new_v4();
+        let now = chrono::Utc::now();
+        let targets = vec![
+            "192.0.2.1".to_string(),
+            "192.0.2.2".to_string(),
+            "192.0.2.3".to_string(),
+        ];
+
+        // One target scanned an hour ago, one three days ago, one never
+        let mut earlier = DiscoveryJob::new(org_id, JobType::PortScan, None, None);
+        earlier.started_at = Some(now - chrono::Duration::hours(1));
+        service
+            .record_scanned_targets(&earlier, &targets[..1])
+            .await
+            .unwrap();
+        earlier.started_at = Some(now - chrono::Duration::days(3));
+        service
+            .record_scanned_targets(&earlier, &targets[1..2])
+            .await
+            .unwrap();
+
+        let incremental = DiscoveryJob::new(
+            org_id,
+            JobType::PortScan,
+            None,
+            Some(serde_json::json!({ "incremental": true, "freshness_window_hours": 24 })),
+        );
+        assert_eq!(
+            service
+                .targets_to_scan(&incremental, targets.clone())
+                .await
+                .unwrap(),
+            targets[1..].to_vec()
+        );
+
+        // A full run scans everything, and scans by other job types or
+        // organizations don't count
+        let full = DiscoveryJob::new(org_id, JobType::PortScan, None, None);
+        assert_eq!(
+            service
+                .targets_to_scan(&full, targets.clone())
+                .await
+                .unwrap(),
+            targets
+        );
+        let dns = DiscoveryJob::new(
+            org_id,
+            JobType::DnsEnum,
+            None,
+            Some(serde_json::json!({ "incremental": true })),
+        );
+        assert_eq!(
+            service
+                .targets_to_scan(&dns, targets.clone())
+                .await
+                .unwrap(),
+            targets
+        );
+
+        // A wider window also covers the older scan
+        let weekly = DiscoveryJob::new(
+            org_id,
+            JobType::PortScan,
+            None,
+            Some(serde_json::json!({ "incremental": true, "freshness_window_hours": 168 })),
+        );
+        assert_eq!(
+            service
+                .targets_to_scan(&weekly, targets.clone())
+                .await
+                .unwrap(),
+            targets[2..].to_vec()
+        );
+    }
+
     #[test]
     async fn test_scan_asset() {
         let job_repo = MockDiscoveryJobRepository::new();
@@ -576,4 +1443,65 @@ mod tests {
         assert_eq!(high_severity.title, "Test Vulnerability 2");
         assert_eq!(medium_severity.title, "Test Vulnerability 1");
     }
+
+    #[test]
+    async fn test_vulnerability_changes_refresh_risk_score() {
+        let asset_repo = MockAssetRepository::new();
+        let vuln_repo = MockVulnerabilityRepository::new();
+        let vulnerability_service =
+            VulnerabilityServiceImpl::new(Arc::new(vuln_repo), Arc::new(asset_repo.clone()));
+
+        let asset = Asset::new(
+            Uuid::new_v4(),
+            AssetType::IPAddress,
+            "192.0.2.10".to_string(),
+            Some(serde_json::json!({
+                "ports": [{ "port": 22, "protocol": "tcp", "status": "OPEN" }]
+            })),
+        );
+        let asset = asset_repo.create_asset(&asset).await.unwrap();
+
+        let vulnerability = vulnerability_service
+            .create_vulnerability(&Vulnerability::new(
+                asset.id,
+                None,
+                "Remote code execution".to_string(),
+                None,
+                Severity::Critical,
+                None,
+                None,
+                None,
+            ))
+            .await
+            .unwrap();
+        // 40 for the critical finding, 10 for exposed SSH
+        assert_eq!(
+            asset_repo.get_asset(asset.id).await.unwrap().risk_score,
+            50.0
+        );
+
+        let mut resolved = vulnerability.clone();
+        resolved.status = VulnerabilityStatus::Closed;
+        vulnerability_service
+            .update_vulnerability(&resolved)
+            .await
+            .unwrap();
+        assert_eq!(
+            asset_repo.get_asset(asset.id).await.unwrap().risk_score,
+            10.0
+        );
+
+        vulnerability_service
+            .update_vulnerability(&vulnerability)
+            .await
+            .unwrap();
+        vulnerability_service
+            .delete_vulnerability(vulnerability.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            asset_repo.get_asset(asset.id).await.unwrap().risk_score,
+            10.0
+        );
+    }
 }
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use backend::models::{compare_versions, AffectedVersions};
+    use backend::services::StaticVulnerabilityFeed;
+    use backend::VulnerabilityFeed;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_versions_compare_numerically() {
+        assert_eq!(compare_versions("2.4.49", "2.4.5"), Ordering::Greater);
+        assert_eq!(compare_versions("2.10", "2.9.9"), Ordering::Greater);
+        assert_eq!(compare_versions("2.4", "2.4.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.1", "1.0.1f"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_affected_versions_bounds() {
+        let range = AffectedVersions {
+            version_start_including: Some("2.4".to_string()),
+            version_end_excluding: Some("2.12.2".to_string()),
+            ..Default::default()
+        };
+        assert!(range.contains("2.4"));
+        assert!(range.contains("2.12.1"));
+        assert!(!range.contains("2.12.2"));
+        assert!(!range.contains("2.3.9"));
+
+        let exact = AffectedVersions {
+            version: Some("2.4.49".to_string()),
+            ..Default::default()
+        };
+        assert!(exact.contains("2.4.49"));
+        assert!(!exact.contains("2.4.50"));
+    }
+
+    #[tokio::test]
+    async fn test_bundled_feed_matches_detected_names() {
+        let feed = StaticVulnerabilityFeed::bundled();
+
+        let cves = |found: Vec<backend::models::KnownVulnerability>| -> Vec<String> {
+            found.into_iter().map(|k| k.cve_id).collect()
+        };
+
+        // Fingerprinting reports the Apache HTTP Server as "Apache"
+        assert_eq!(
+            cves(feed.find("Apache", "2.4.49").await.unwrap()),
+            vec!["CVE-2021-41773", "CVE-2021-42013"]
+        );
+        assert_eq!(
+            cves(feed.find("http_server", "2.4.50").await.unwrap()),
+            vec!["CVE-2021-42013"]
+        );
+        assert!(feed.find("Apache", "2.4.51").await.unwrap().is_empty());
+        assert_eq!(
+            cves(feed.find("Nginx", "1.18.0").await.unwrap()),
+            vec!["CVE-2021-23017"]
+        );
+        assert_eq!(
+            cves(feed.find("log4j", "2.14.1").await.unwrap()),
+            vec!["CVE-2021-44228"]
+        );
+    }
+}
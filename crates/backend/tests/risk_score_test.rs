@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use backend::models::{Asset, Vulnerability};
+    use backend::services::{risk_score, MAX_RISK_SCORE};
+    use serde_json::json;
+    use shared::types::{AssetType, Severity, VulnerabilityStatus};
+    use uuid::Uuid;
+
+    fn ip_asset(ports: serde_json::Value) -> Asset {
+        Asset::new(
+            Uuid::new_v4(),
+            AssetType::IPAddress,
+            "192.0.2.10".to_string(),
+            Some(json!({ "ports": ports })),
+        )
+    }
+
+    fn vulnerability(asset: &Asset, severity: Severity) -> Vulnerability {
+        Vulnerability::new(
+            asset.id,
+            None,
+            format!("{:?} finding", severity),
+            None,
+            severity,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_asset_without_issues_scores_zero() {
+        let asset = Asset::new(
+            Uuid::new_v4(),
+            AssetType::Domain,
+            "example.com".to_string(),
+            None,
+        );
+
+        assert_eq!(risk_score(&asset, &[]), 0.0);
+    }
+
+    #[test]
+    fn test_one_critical_vulnerability() {
+        let asset = ip_asset(json!([]));
+        let mut critical = vulnerability(&asset, Severity::Critical);
+
+        assert_eq!(risk_score(&asset, std::slice::from_ref(&critical)), 40.0);
+
+        // Once resolved it no longer counts
+        critical.status = VulnerabilityStatus::Closed;
+        assert_eq!(risk_score(&asset, &[critical]), 0.0);
+    }
+
+    #[test]
+    fn test_multiple_exposed_ports() {
+        let asset = ip_asset(json!([
+            { "port": 80, "protocol": "tcp", "status": "OPEN" },
+            { "port": 443, "protocol": "tcp", "status": "OPEN" },
+            { "port": 22, "protocol": "tcp", "status": "OPEN" },
+            { "port": 3306, "protocol": "tcp", "status": "OPEN" },
+            // Ports that aren't open don't count
+            { "port": 3389, "protocol": "tcp", "status": "FILTERED" },
+        ]));
+
+        // Two web ports at 2 each, SSH and MySQL at 10 each
+        assert_eq!(risk_score(&asset, &[]), 24.0);
+
+        let medium = vulnerability(&asset, Severity::Medium);
+        assert_eq!(risk_score(&asset, &[medium]), 32.0);
+    }
+
+    #[test]
+    fn test_weak_tls_ports() {
+        let tls = |issuer: &str, not_after: &str, legacy_protocol: bool| {
+            json!({
+                "subject": "CN=example.com",
+                "issuer": issuer,
+                "subject_alt_names": ["example.com"],
+                "not_before": "2020-01-01T00:00:00Z",
+                "not_after": not_after,
+                "legacy_protocol": legacy_protocol,
+            })
+        };
+        let trusted = "CN=R11, O=Let's Encrypt, C=US";
+        let asset = ip_asset(json!([
+            { "port": 443, "protocol": "tcp", "status": "OPEN",
+              "tls": tls(trusted, "2999-01-01T00:00:00Z", false) },
+            { "port": 8443, "protocol": "tcp", "status": "OPEN",
+              "tls": tls(trusted, "2021-01-01T00:00:00Z", false) },
+            { "port": 993, "protocol": "tcp", "status": "OPEN",
+              "tls": tls("CN=example.com", "2999-01-01T00:00:00Z", false) },
+            { "port": 995, "protocol": "tcp", "status": "OPEN",
+              "tls": tls(trusted, "2999-01-01T00:00:00Z", true) },
+            // Ports that aren't open don't count
+            { "port": 636, "protocol": "tcp", "status": "CLOSED",
+              "tls": tls(trusted, "2021-01-01T00:00:00Z", true) },
+        ]));
+
+        // Four web and mail ports at 2 each; expired, self-signed and
+        // legacy protocol at 15 each. A healthy certificate adds nothing.
+        assert_eq!(risk_score(&asset, &[]), 8.0 + 45.0);
+    }
+
+    #[test]
+    fn test_score_is_capped() {
+        let asset = ip_asset(json!([{ "port": 6379, "protocol": "tcp", "status": "OPEN" }]));
+        let vulnerabilities: Vec<_> = (0..3)
+            .map(|_| vulnerability(&asset, Severity::Critical))
+            .collect();
+
+        assert_eq!(risk_score(&asset, &vulnerabilities), MAX_RISK_SCORE);
+    }
+}
@@ -0,0 +1,70 @@
+use backend::{models::User, Error};
+use uuid::Uuid;
+
+fn user_with_password(password: &str) -> User {
+    User::new(
+        Uuid::new_v4(),
+        "alice".to_string(),
+        "alice@example.com".to_string(),
+        User::hash_password(password).unwrap(),
+        None,
+    )
+}
+
+#[test]
+fn test_password_hash_round_trip() {
+    let user = user_with_password("correct horse battery staple");
+
+    assert!(user.password_hash.starts_with("$argon2id$"));
+    assert!(!user.password_hash.contains("correct horse"));
+    assert!(user
+        .verify_password("correct horse battery staple")
+        .unwrap());
+}
+
+#[test]
+fn test_wrong_password_is_rejected() {
+    let user = user_with_password("correct horse battery staple");
+
+    assert!(!user
+        .verify_password("Correct horse battery staple")
+        .unwrap());
+    assert!(!user.verify_password("").unwrap());
+}
+
+#[test]
+fn test_same_password_hashes_differently() {
+    // Each hash gets its own salt
+    let first = User::hash_password("correct horse battery staple").unwrap();
+    let second = User::hash_password("correct horse battery staple").unwrap();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn test_set_password_replaces_hash() {
+    let mut user = user_with_password("correct horse battery staple");
+    user.set_password("a new passphrase").unwrap();
+
+    assert!(user.verify_password("a new passphrase").unwrap());
+    assert!(!user
+        .verify_password("correct horse battery staple")
+        .unwrap());
+}
+
+#[test]
+fn test_password_hash_is_never_serialized() {
+    let user = user_with_password("correct horse battery staple");
+    let json = serde_json::to_value(&user).unwrap();
+
+    assert!(json.get("password_hash").is_none());
+    assert!(!json.to_string().contains("argon2"));
+}
+
+#[test]
+fn test_short_passwords_are_invalid() {
+    assert!(matches!(
+        User::validate_password("short"),
+        Err(Error::Validation(_))
+    ));
+    assert!(User::validate_password("long enough").is_ok());
+}
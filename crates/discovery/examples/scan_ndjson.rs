@@ -0,0 +1,37 @@
+//! Port scan a target and print the findings as NDJSON
+//!
+//! ```text
+//! cargo run -p discovery --example scan_ndjson -- 192.0.2.0/30 22,80,443 | jq .
+//! ```
+//!
+//! The target may be an IP, a CIDR range or a hostname. Without a port list
+//! the scanner's common ports are scanned.
+
+use discovery::ndjson::NdjsonWriter;
+use discovery::port_scan::PortScanner;
+use std::io;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let Some(target) = args.next() else {
+        eprintln!("usage: scan_ndjson <ip|cidr|hostname> [port,port,...]");
+        std::process::exit(2);
+    };
+    let ports = args
+        .next()
+        .map(|list| {
+            list.split(',')
+                .map(|port| port.trim().parse::<u16>())
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    let result = PortScanner::new()
+        .scan_target(&target, ports.as_deref())
+        .await?;
+
+    let lines = NdjsonWriter::new(io::stdout().lock()).write_result(&result)?;
+    eprintln!("{} records", lines);
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! Network ownership lookups for IP addresses
+//!
+//! Maps an IP to the autonomous system announcing it using a local
+//! IP-to-ASN dataset in the iptoasn.com TSV format, one range per line:
+//!
+//! ```text
+//! range_start  range_end  AS_number  country_code  AS_description
+//! ```
+//!
+//! Ranges with AS number 0 are unrouted and are skipped.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+/// Network ownership of an IP, stored on IP assets under the `asn_info`
+/// attribute
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AsnInfo {
+    pub asn: u32,
+    /// Largest CIDR block around the IP announced by this AS
+    pub network: String,
+    /// Name of the organization operating the AS, e.g. "CLOUDFLARENET"
+    pub organization: String,
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct AsnRange {
+    start: u128,
+    end: u128,
+    asn: u32,
+    organization: String,
+    country: Option<String>,
+}
+
+/// In-memory IP-to-ASN dataset
+#[derive(Debug, Default)]
+pub struct AsnDatabase {
+    /// IPv4 and IPv6 ranges, each sorted by start address
+    v4: Vec<AsnRange>,
+    v6: Vec<AsnRange>,
+}
+
+impl AsnDatabase {
+    /// Load a dataset from a TSV file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open ASN database {}", path.display()))?;
+        Self::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("Failed to load ASN database {}", path.display()))
+    }
+
+    /// Load a dataset from TSV lines
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut database = Self::default();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 5 {
+                return Err(anyhow::anyhow!(
+                    "Line {}: expected 5 tab-separated fields, found {}",
+                    index + 1,
+                    fields.len()
+                ));
+            }
+
+            let start: IpAddr = fields[0]
+                .parse()
+                .with_context(|| format!("Line {}: invalid range start", index + 1))?;
+            let end: IpAddr = fields[1]
+                .parse()
+                .with_context(|| format!("Line {}: invalid range end", index + 1))?;
+            let asn: u32 = fields[2]
+                .parse()
+                .with_context(|| format!("Line {}: invalid AS number", index + 1))?;
+
+            if asn == 0 {
+                continue;
+            }
+
+            let country = match fields[3].trim() {
+                "" | "None" => None,
+                country => Some(country.to_string()),
+            };
+            let range = AsnRange {
+                start: to_u128(start),
+                end: to_u128(end),
+                asn,
+                organization: fields[4].trim().to_string(),
+                country,
+            };
+
+            match (start, end) {
+                (IpAddr::V4(_), IpAddr::V4(_)) => database.v4.push(range),
+                (IpAddr::V6(_), IpAddr::V6(_)) => database.v6.push(range),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Line {}: range mixes IPv4 and IPv6",
+                        index + 1
+                    ))
+                }
+            }
+        }
+
+        database.v4.sort_by_key(|range| range.start);
+        database.v6.sort_by_key(|range| range.start);
+
+        Ok(database)
+    }
+
+    /// Number of routed ranges loaded
+    pub fn len(&self) -> usize {
+        self.v4.len() + self.v6.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The AS announcing `ip`, if any
+    pub fn lookup(&self, ip: IpAddr) -> Option<AsnInfo> {
+        let (ranges, bits) = match ip {
+            IpAddr::V4(_) => (&self.v4, 32),
+            IpAddr::V6(_) => (&self.v6, 128),
+        };
+        let address = to_u128(ip);
+
+        // Last range starting at or before the address
+        let index = ranges.partition_point(|range| range.start <= address);
+        let range = ranges[..index].last()?;
+        if address > range.end {
+            return None;
+        }
+
+        Some(AsnInfo {
+            asn: range.asn,
+            network: covering_network(address, range.start, range.end, bits, ip),
+            organization: range.organization.clone(),
+            country: range.country.clone(),
+        })
+    }
+}
+
+fn to_u128(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => u32::from(ip) as u128,
+        IpAddr::V6(ip) => u128::from(ip),
+    }
+}
+
+/// The largest CIDR block containing `address` that fits in `start..=end`.
+/// Dataset ranges aren't always a single CIDR block, so the one around
+/// the address is used.
+fn covering_network(address: u128, start: u128, end: u128, bits: u32, ip: IpAddr) -> String {
+    let all_ones = if bits == 32 {
+        u32::MAX as u128
+    } else {
+        u128::MAX
+    };
+
+    for prefix_len in 0..=bits {
+        let host_mask = if prefix_len == bits {
+            0
+        } else {
+            all_ones >> prefix_len
+        };
+        let block_start = address & !host_mask & all_ones;
+        let block_end = block_start | host_mask;
+
+        if block_start >= start && block_end <= end {
+            let network = match ip {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(block_start as u32)),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(block_start)),
+            };
+            return format!("{}/{}", network, prefix_len);
+        }
+    }
+
+    // Unreachable: the address itself is always a block within the range
+    format!("{}/{}", ip, bits)
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Returned by scanners that stop early because their job was cancelled
+#[derive(Debug, Error)]
+#[error("Scan cancelled")]
+pub struct ScanCancelled;
+
+/// Whether an error returned by a scanner means the scan was cancelled
+pub fn is_cancelled(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ScanCancelled>().is_some()
+}
+
+/// Cancellation tokens for the jobs currently running, keyed by job ID
+#[derive(Debug, Clone, Default)]
+pub struct CancellationRegistry {
+    tokens: Arc<Mutex<HashMap<Uuid, CancellationToken>>>,
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a running job, returning the token its scans should observe
+    pub fn register(&self, job_id: Uuid) -> CancellationToken {
+        self.tokens
+            .lock()
+            .expect("cancellation registry poisoned")
+            .entry(job_id)
+            .or_default()
+            .clone()
+    }
+
+    /// Signal cancellation for a job. Returns false if it isn't running here
+    pub fn cancel(&self, job_id: Uuid) -> bool {
+        match self
+            .tokens
+            .lock()
+            .expect("cancellation registry poisoned")
+            .get(&job_id)
+        {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a job once it has finished
+    pub fn remove(&self, job_id: Uuid) {
+        self.tokens
+            .lock()
+            .expect("cancellation registry poisoned")
+            .remove(&job_id);
+    }
+
+    /// IDs of the jobs currently registered
+    pub fn running_jobs(&self) -> Vec<Uuid> {
+        self.tokens
+            .lock()
+            .expect("cancellation registry poisoned")
+            .keys()
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_signals_registered_token() {
+        let registry = CancellationRegistry::new();
+        let job_id = Uuid::new_v4();
+        let token = registry.register(job_id);
+
+        assert!(!token.is_cancelled());
+        assert!(registry.cancel(job_id));
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_unknown_job() {
+        let registry = CancellationRegistry::new();
+        assert!(!registry.cancel(Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_remove_forgets_job() {
+        let registry = CancellationRegistry::new();
+        let job_id = Uuid::new_v4();
+        registry.register(job_id);
+        assert_eq!(registry.running_jobs(), vec![job_id]);
+
+        registry.remove(job_id);
+        assert!(registry.running_jobs().is_empty());
+        assert!(!registry.cancel(job_id));
+    }
+
+    #[test]
+    fn test_is_cancelled() {
+        assert!(is_cancelled(&anyhow::Error::new(ScanCancelled)));
+        assert!(!is_cancelled(&anyhow::anyhow!("connection refused")));
+    }
+}
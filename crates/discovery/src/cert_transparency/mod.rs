@@ -1,6 +1,6 @@
+use crate::http_client::{http_get_with_retry, HttpClientConfig, DEFAULT_RETRIES};
 use crate::results::{DiscoveredDomain, DiscoveryResult};
 use anyhow::Result;
-use reqwest::Client;
 use serde::Deserialize;
 use std::collections::HashSet;
 
@@ -12,19 +12,43 @@ struct CrtShEntry {
     name_value: Option<String>,
 }
 
+/// Public crt.sh search service
+pub const CRT_SH_URL: &str = "https://crt.sh";
+
 pub async fn monitor_logs(domain: &str) -> Result<DiscoveryResult> {
+    monitor_logs_with_config(domain, &HttpClientConfig::default()).await
+}
+
+/// Search crt.sh with the user agent, headers and proxy of `http`
+pub async fn monitor_logs_with_config(
+    domain: &str,
+    http: &HttpClientConfig,
+) -> Result<DiscoveryResult> {
+    monitor_logs_at(CRT_SH_URL, domain, http).await
+}
+
+/// Search a crt.sh-compatible service at `base_url`
+pub async fn monitor_logs_at(
+    base_url: &str,
+    domain: &str,
+    http: &HttpClientConfig,
+) -> Result<DiscoveryResult> {
     tracing::debug!("Monitoring Certificate Transparency logs for: {}", domain);
-    let client = Client::builder()
-        .user_agent("EASM Discovery Bot/0.1")
+    let client = http
+        .client_builder()?
         .timeout(std::time::Duration::from_secs(30)) // CT logs can be slow
         .build()?;
 
-    let url = format!("https://crt.sh/?q={}&output=json", domain);
+    let url = format!(
+        "{}/?q={}&output=json",
+        base_url.trim_end_matches('/'),
+        domain
+    );
     let mut discovery_result = DiscoveryResult::new();
     let mut found_domains: HashSet<String> = HashSet::new();
     let source = format!("crt.sh_for_{}", domain);
 
-    match client.get(&url).send().await {
+    match http_get_with_retry(&client, &url, DEFAULT_RETRIES).await {
         Ok(response) => {
             if response.status().is_success() {
                 match response.json::<Vec<CrtShEntry>>().await {
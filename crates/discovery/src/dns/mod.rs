@@ -8,6 +8,7 @@ use anyhow::Result;
 use lazy_static::lazy_static;
 use std::sync::Mutex;
 use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::ResolveErrorKind;
 use trust_dns_resolver::proto::rr::RecordType;
 use trust_dns_resolver::TokioAsyncResolver;
 use trust_dns_resolver::TokioAsyncResolver as dnsresolv;
@@ -189,6 +190,22 @@ impl DnsEnumerator {
         Ok(results)
     }
 
+    /// The canonical name `domain` is an alias for, if it has a CNAME record
+    pub async fn lookup_cname(&self, domain: &str) -> Result<Option<String>> {
+        let lookup = match self.resolver.lookup(domain, RecordType::CNAME).await {
+            Ok(lookup) => lookup,
+            Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(anyhow::anyhow!("CNAME lookup failed for {}: {}", domain, e)),
+        };
+
+        Ok(lookup
+            .iter()
+            .find_map(|record| record.as_cname())
+            .map(|cname| cname.to_utf8().trim_end_matches('.').to_string()))
+    }
+
     /// Perform DNS enumeration on a domain
     pub async fn enumerate(&self, domain: &str) -> Result<DiscoveryResult> {
         let mut result = DiscoveryResult::new();
@@ -9,6 +9,7 @@
 //! - Security headers
 
 use crate::fingerprinting::Fingerprinter;
+use crate::http_client::HttpClientConfig;
 use crate::results::{DiscoveryResult, TechnologyFinding};
 use reqwest::header::HeaderMap;
 use reqwest::Client;
@@ -54,9 +55,19 @@ enum DetectionMethod {
 impl WebFingerprinter {
     /// Create a new WebFingerprinter with default signatures
     pub fn new() -> Result<Self, anyhow::Error> {
-        let client = Client::builder()
+        Self::with_http_config(&HttpClientConfig {
+            user_agent: "EASM-Scanner/1.0".to_string(),
+            ..HttpClientConfig::default()
+        })
+    }
+
+    /// Create a new WebFingerprinter with default signatures and custom
+    /// HTTP settings, e.g. a proxy
+    pub fn with_http_config(http: &HttpClientConfig) -> Result<Self, anyhow::Error> {
+        // Don't let the target or its redirects lead onto internal addresses
+        let client = http
+            .external_client_builder()?
             .timeout(Duration::from_secs(10))
-            .user_agent("EASM-Scanner/1.0")
             .build()?;
 
         // Load basic signatures for common technologies
@@ -0,0 +1,236 @@
+//! Settings shared by the HTTP clients discovery makes requests with
+//!
+//! By default requests identify as the EASM bot and go out directly. For
+//! authorized assessments the user agent can be changed, e.g. to look like a
+//! browser, headers added to identify the scan, and requests sent through
+//! an HTTP or SOCKS5 proxy. [`http_get_with_retry`] rides out the transient
+//! failures a single fetch would otherwise give up on.
+//!
+//! Clients fetching pages picked by the scanned site itself, like the
+//! crawler, refuse to reach internal addresses so a hostile target can't
+//! turn them on the scanner's own network.
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{redirect, Client, ClientBuilder, Proxy, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use url::{Host, Url};
+
+use crate::scope::is_internal_address;
+
+/// User agent sent when none is configured
+pub const DEFAULT_USER_AGENT: &str = "EASM Discovery Bot/0.1";
+
+/// Retries after the first attempt that crawler and CT requests get
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// Backoff before the first retry, doubled for each one after
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Longest backoff between two attempts
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Redirects followed before giving up, as reqwest does by default
+const MAX_REDIRECTS: usize = 10;
+
+/// Returned for a request that would have reached an internal address
+#[derive(Debug, Error)]
+#[error("Refusing to connect to internal address {0}")]
+pub struct InternalAddress(pub String);
+
+/// How discovery's HTTP clients present themselves and connect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpClientConfig {
+    /// `User-Agent` sent with every request
+    pub user_agent: String,
+    /// Headers added to every request, e.g. `X-Scan-Id`
+    pub extra_headers: BTreeMap<String, String>,
+    /// Proxy every request goes through, e.g. `http://proxy:3128` or
+    /// `socks5h://jump-host:1080`
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: BTreeMap::new(),
+            proxy: None,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Send `name: value` with every request
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.extra_headers
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Send every request through `proxy`
+    pub fn with_proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// The proxy, if it's a SOCKS5 one that raw TCP connections such as port
+    /// scans can go through too
+    pub fn socks_proxy(&self) -> Option<&str> {
+        self.proxy
+            .as_deref()
+            .filter(|proxy| proxy.starts_with("socks5://") || proxy.starts_with("socks5h://"))
+    }
+
+    /// A client builder with the user agent, headers and proxy applied, for
+    /// callers to add their own timeouts and redirect policy to
+    pub fn client_builder(&self) -> Result<ClientBuilder> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.extra_headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("invalid header name `{}`", name))?;
+            let value = HeaderValue::from_str(value)
+                .with_context(|| format!("invalid value for header `{}`", name))?;
+            headers.insert(name, value);
+        }
+
+        let mut builder = ClientBuilder::new()
+            .user_agent(&self.user_agent)
+            .default_headers(headers);
+        if let Some(proxy) = &self.proxy {
+            let proxy =
+                Proxy::all(proxy).map_err(|e| anyhow!("invalid proxy `{}`: {}", proxy, e))?;
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(builder)
+    }
+}
+
+impl HttpClientConfig {
+    /// Like [`Self::client_builder`], but the client refuses to reach
+    /// internal addresses, whether a host resolves to one or a redirect
+    /// points at one. Behind a proxy, which resolves names itself, only
+    /// redirects are checked.
+    pub fn external_client_builder(&self) -> Result<ClientBuilder> {
+        let mut builder = self.client_builder()?.redirect(external_redirect_policy());
+        if self.proxy.is_none() {
+            builder = builder.dns_resolver(Arc::new(ExternalResolver));
+        }
+        Ok(builder)
+    }
+}
+
+/// Resolves hosts for HTTP clients, leaving out internal addresses so a name
+/// pointing at one can't be connected to
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExternalResolver;
+
+impl Resolve for ExternalResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let external: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| !is_internal_address(addr.ip()))
+                .collect();
+            if external.is_empty() {
+                return Err(InternalAddress(host).into());
+            }
+            Ok(Box::new(external.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Redirect policy refusing to be sent to an internal address, e.g. a page
+/// redirecting to `http://169.254.169.254/`. Redirects within an internal
+/// host the request started on, such as to its login page, are followed.
+pub fn external_redirect_policy() -> redirect::Policy {
+    redirect::Policy::custom(|attempt| {
+        let url = attempt.url();
+        let same_host = attempt
+            .previous()
+            .first()
+            .is_some_and(|first| first.host() == url.host());
+
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if is_internal_url(url) && !same_host {
+            let url = url.to_string();
+            attempt.error(InternalAddress(url))
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Whether `url` points at an internal address or `localhost` by name.
+/// Other names are only checked once resolved.
+pub fn is_internal_url(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_internal_address(ip.into()),
+        Some(Host::Ipv6(ip)) => is_internal_address(ip.into()),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        None => false,
+    }
+}
+
+/// `GET url`, retrying up to `retries` times on connection errors, server
+/// errors and 429s with jittered exponential backoff. Other responses,
+/// including other errors, are returned straight away. Once the retries run
+/// out the last response or error is returned.
+pub async fn http_get_with_retry(
+    client: &Client,
+    url: &str,
+    retries: u32,
+) -> reqwest::Result<Response> {
+    let mut attempt = 0;
+    loop {
+        let result = client.get(url).send().await;
+        let retryable = match &result {
+            Ok(response) => is_retryable_status(response.status()),
+            Err(e) => e.is_connect(),
+        };
+        if !retryable || attempt >= retries {
+            return result;
+        }
+
+        let delay = backoff(attempt);
+        match &result {
+            Ok(response) => tracing::debug!(
+                "GET {} returned {}, retrying in {:?}",
+                url,
+                response.status(),
+                delay
+            ),
+            Err(e) => tracing::debug!("GET {} failed: {}, retrying in {:?}", url, e, delay),
+        }
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Whether a response with `status` may succeed when asked again
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Wait before retry number `attempt`, counting from 0: half the doubled
+/// base delay plus a random share of the other half, so clients that failed
+/// together don't retry together
+fn backoff(attempt: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RETRY_MAX_DELAY);
+    delay / 2 + delay.mul_f64(rand::random::<f64>() / 2.0)
+}
@@ -0,0 +1,144 @@
+//! Import of results produced by external reconnaissance tools
+//!
+//! Subdomain enumerators such as subfinder and amass write either one name
+//! per line or, with `-json`, one JSON object per line. Both formats are
+//! accepted, even mixed in the same input, and turned into a
+//! [`DiscoveryResult`] that can be persisted like any other discovery run.
+//! Nmap scans are imported by [`nmap`].
+
+pub mod nmap;
+
+use crate::results::{DiscoveredDomain, DiscoveredIp, DiscoveryResult};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Tool whose subdomain output is being imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubdomainTool {
+    Subfinder,
+    Amass,
+}
+
+impl SubdomainTool {
+    /// Source recorded on imported domains and IPs
+    pub fn source(self) -> &'static str {
+        match self {
+            SubdomainTool::Subfinder => "import_subfinder",
+            SubdomainTool::Amass => "import_amass",
+        }
+    }
+}
+
+/// One line of `subfinder -json` output
+#[derive(Debug, Deserialize)]
+struct SubfinderRecord {
+    host: String,
+}
+
+/// One line of `amass enum -json` output
+#[derive(Debug, Deserialize)]
+struct AmassRecord {
+    name: String,
+    #[serde(default)]
+    addresses: Vec<AmassAddress>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AmassAddress {
+    ip: IpAddr,
+}
+
+/// Parse subfinder or amass output into discovered domains
+///
+/// Lines starting with `{` are read as the tool's JSON records, anything
+/// else as a plain host name. Amass's graph lines
+/// (`www.example.com (FQDN) --> a_record --> 192.0.2.1 (IPAddress)`)
+/// contribute their FQDN nodes. Names are lowercased, a trailing dot or
+/// leading wildcard label is dropped and each name is kept once. Addresses
+/// in amass JSON records are imported as IPs.
+pub fn parse_subdomain_output(tool: SubdomainTool, input: &str) -> Result<DiscoveryResult> {
+    let mut result = DiscoveryResult::new();
+    let mut domains = HashSet::new();
+    let mut ips = HashSet::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (names, addresses) = if line.starts_with('{') {
+            parse_json_line(tool, line)
+                .with_context(|| format!("invalid {:?} JSON on line {}", tool, index + 1))?
+        } else {
+            (parse_plain_line(line), Vec::new())
+        };
+
+        for name in names {
+            let name = normalize_host(&name)
+                .ok_or_else(|| anyhow!("invalid host name on line {}: {}", index + 1, name))?;
+            if domains.insert(name.clone()) {
+                result.domains.push(DiscoveredDomain {
+                    domain_name: name,
+                    source: tool.source().to_string(),
+                });
+            }
+        }
+        for ip in addresses {
+            if ips.insert(ip) {
+                result.ip_addresses.push(DiscoveredIp {
+                    ip_address: ip,
+                    source: tool.source().to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_json_line(tool: SubdomainTool, line: &str) -> Result<(Vec<String>, Vec<IpAddr>)> {
+    match tool {
+        SubdomainTool::Subfinder => {
+            let record: SubfinderRecord = serde_json::from_str(line)?;
+            Ok((vec![record.host], Vec::new()))
+        }
+        SubdomainTool::Amass => {
+            let record: AmassRecord = serde_json::from_str(line)?;
+            let addresses = record.addresses.into_iter().map(|a| a.ip).collect();
+            Ok((vec![record.name], addresses))
+        }
+    }
+}
+
+fn parse_plain_line(line: &str) -> Vec<String> {
+    if !line.contains("-->") {
+        return vec![line.to_string()];
+    }
+
+    line.split("-->")
+        .filter_map(|node| node.trim().strip_suffix("(FQDN)"))
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+/// Lowercase `host` and drop a trailing dot and leading `*.`, rejecting
+/// anything that cannot be a host name
+fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    let host = host.strip_prefix("*.").unwrap_or(&host);
+
+    let valid = !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    valid.then(|| host.to_string())
+}
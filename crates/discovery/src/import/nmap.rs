@@ -0,0 +1,148 @@
+//! Nmap XML (`-oX`) import
+//!
+//! Every host nmap reported up becomes a [`DiscoveredIp`], and each of its
+//! scanned ports a [`DiscoveredPort`] carrying nmap's port state and
+//! service detection. Host names given on the nmap command line are
+//! imported as domains; reverse DNS names are not.
+
+use crate::port_scan::DiscoveredPort;
+use crate::results::{DiscoveredDomain, DiscoveredIp, DiscoveryResult};
+use anyhow::{anyhow, Context, Result};
+use roxmltree::{Document, Node, ParsingOptions};
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Source recorded on everything imported from nmap
+pub const NMAP_SOURCE: &str = "import_nmap";
+
+/// Parse the XML nmap writes with `-oX`
+pub fn parse_nmap_xml(xml: &str) -> Result<DiscoveryResult> {
+    // nmap declares `<!DOCTYPE nmaprun>`, which is rejected by default
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(xml, options).context("invalid nmap XML")?;
+    let root = document.root_element();
+    if !root.has_tag_name("nmaprun") {
+        return Err(anyhow!(
+            "not nmap XML output: root element is <{}>",
+            root.tag_name().name()
+        ));
+    }
+
+    let mut result = DiscoveryResult::new();
+    let mut domains = HashSet::new();
+    if let Some(args) = root.attribute("args") {
+        result
+            .metadata
+            .insert("nmap_args".to_string(), args.to_string());
+    }
+
+    for host in root.children().filter(|n| n.has_tag_name("host")) {
+        let state = child(host, "status").and_then(|s| s.attribute("state"));
+        if state.is_some_and(|state| state != "up") {
+            continue;
+        }
+        let Some(ip) = host_address(host)? else {
+            continue;
+        };
+
+        result.ip_addresses.push(DiscoveredIp {
+            ip_address: ip,
+            source: NMAP_SOURCE.to_string(),
+        });
+
+        let hostnames = child(host, "hostnames")
+            .into_iter()
+            .flat_map(|h| h.children().filter(|n| n.has_tag_name("hostname")))
+            .filter(|h| h.attribute("type") == Some("user"))
+            .filter_map(|h| h.attribute("name"));
+        for name in hostnames {
+            let name = name.trim_end_matches('.').to_lowercase();
+            if domains.insert(name.clone()) {
+                result.domains.push(DiscoveredDomain {
+                    domain_name: name,
+                    source: NMAP_SOURCE.to_string(),
+                });
+            }
+        }
+
+        let ports = child(host, "ports")
+            .into_iter()
+            .flat_map(|p| p.children().filter(|n| n.has_tag_name("port")));
+        for port in ports {
+            result.ports.push(parse_port(ip, port)?);
+        }
+    }
+
+    Ok(result)
+}
+
+/// The host's IPv4 or IPv6 address, skipping MAC addresses
+fn host_address(host: Node) -> Result<Option<IpAddr>> {
+    let address = host
+        .children()
+        .filter(|n| n.has_tag_name("address"))
+        .find(|a| matches!(a.attribute("addrtype"), Some("ipv4" | "ipv6")))
+        .and_then(|a| a.attribute("addr"));
+
+    address
+        .map(|addr| {
+            addr.parse()
+                .with_context(|| format!("invalid host address in nmap XML: {}", addr))
+        })
+        .transpose()
+}
+
+fn parse_port(ip: IpAddr, port: Node) -> Result<DiscoveredPort> {
+    let number = port
+        .attribute("portid")
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| anyhow!("nmap port on {} without a valid portid", ip))?;
+    let protocol = port.attribute("protocol").unwrap_or("tcp").to_uppercase();
+    // nmap's states (`open`, `open|filtered`, ...) match ours uppercased
+    let status = child(port, "state")
+        .and_then(|s| s.attribute("state"))
+        .unwrap_or("unknown")
+        .to_uppercase();
+
+    let service = child(port, "service");
+    let service_name = service.and_then(|s| s.attribute("name")).map(|name| {
+        match service.and_then(|s| s.attribute("tunnel")) {
+            Some(tunnel) => format!("{}/{}", tunnel, name),
+            None => name.to_string(),
+        }
+    });
+    // Version detection results are kept where scanned banners go, e.g.
+    // `nginx 1.25.3 (Ubuntu)`
+    let banner = service.and_then(|s| {
+        let detail = [s.attribute("product"), s.attribute("version")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        match (detail.is_empty(), s.attribute("extrainfo")) {
+            (true, _) => None,
+            (false, Some(extra)) => Some(format!("{} ({})", detail, extra)),
+            (false, None) => Some(detail),
+        }
+    });
+
+    Ok(DiscoveredPort {
+        ip_address: ip,
+        port: number,
+        protocol,
+        status,
+        service_name,
+        banner,
+        http_status: None,
+        http_title: None,
+        tls_info: None,
+        source: NMAP_SOURCE.to_string(),
+    })
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
@@ -1,8 +1,22 @@
+pub mod asn;
+pub mod cancellation;
 pub mod cert_transparency;
 pub mod dns;
 pub mod fingerprinting;
+pub mod http_client;
+pub mod import;
+pub mod ndjson;
+pub mod output;
+pub mod path_probe;
+pub mod plan;
 pub mod port_scan;
 pub mod results;
+pub mod scope;
+pub mod screenshot;
+pub mod secrets;
+pub mod shodan;
+pub mod takeover;
 pub mod tasks;
 pub mod vulnerability;
 pub mod web_crawl;
+pub mod whois;
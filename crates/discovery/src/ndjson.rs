@@ -0,0 +1,121 @@
+//! Newline-delimited JSON output of discovery results
+//!
+//! Each discovered entity becomes one JSON object on its own line, tagged
+//! with a `kind` field (`ip`, `domain`, `port`, `web_resource`,
+//! `technology`, `vulnerability`, `raw_vulnerability`, `whois` or `asn`), so
+//! results can be piped into `jq` or other tooling and processed as they
+//! arrive.
+
+use crate::asn::AsnInfo;
+use crate::port_scan::DiscoveredPort;
+use crate::results::{
+    DiscoveredDomain, DiscoveredIp, DiscoveredWebResource, DiscoveryResult, TechnologyFinding,
+    VulnerabilityFinding,
+};
+use crate::vulnerability::DiscoveredVulnerability;
+use crate::whois::WhoisInfo;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::net::IpAddr;
+
+/// One line of NDJSON output
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiscoveryRecord<'a> {
+    Ip(&'a DiscoveredIp),
+    Domain(&'a DiscoveredDomain),
+    Port(&'a DiscoveredPort),
+    WebResource(&'a DiscoveredWebResource),
+    Technology(&'a TechnologyFinding),
+    Vulnerability(&'a VulnerabilityFinding),
+    RawVulnerability(&'a DiscoveredVulnerability),
+    Whois {
+        domain: &'a str,
+        #[serde(flatten)]
+        info: &'a WhoisInfo,
+    },
+    Asn {
+        ip: IpAddr,
+        #[serde(flatten)]
+        info: &'a AsnInfo,
+    },
+}
+
+impl DiscoveryResult {
+    /// Every discovered entity as an NDJSON record
+    pub fn records(&self) -> impl Iterator<Item = DiscoveryRecord<'_>> {
+        self.ip_addresses
+            .iter()
+            .map(DiscoveryRecord::Ip)
+            .chain(self.domains.iter().map(DiscoveryRecord::Domain))
+            .chain(self.ports.iter().map(DiscoveryRecord::Port))
+            .chain(self.web_resources.iter().map(DiscoveryRecord::WebResource))
+            .chain(self.technologies.iter().map(DiscoveryRecord::Technology))
+            .chain(
+                self.vulnerabilities
+                    .iter()
+                    .map(DiscoveryRecord::Vulnerability),
+            )
+            .chain(
+                self.raw_vulnerabilities
+                    .iter()
+                    .map(DiscoveryRecord::RawVulnerability),
+            )
+            .chain(
+                self.whois
+                    .iter()
+                    .map(|(domain, info)| DiscoveryRecord::Whois { domain, info }),
+            )
+            .chain(
+                self.asn
+                    .iter()
+                    .map(|(ip, info)| DiscoveryRecord::Asn { ip: *ip, info }),
+            )
+    }
+
+    /// Number of discovered entities, i.e. of NDJSON records
+    pub fn entity_count(&self) -> usize {
+        self.ip_addresses.len()
+            + self.domains.len()
+            + self.ports.len()
+            + self.web_resources.len()
+            + self.technologies.len()
+            + self.vulnerabilities.len()
+            + self.raw_vulnerabilities.len()
+            + self.whois.len()
+            + self.asn.len()
+    }
+}
+
+/// Writes discovery results as NDJSON, one line per entity
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write every entity of `result`, returning the number of lines written
+    pub fn write_result(&mut self, result: &DiscoveryResult) -> io::Result<usize> {
+        let mut lines = 0;
+        for record in result.records() {
+            self.write_record(&record)?;
+            lines += 1;
+        }
+        self.writer.flush()?;
+        Ok(lines)
+    }
+
+    /// Write a single record as one line
+    pub fn write_record(&mut self, record: &DiscoveryRecord<'_>) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// The underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
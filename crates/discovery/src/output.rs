@@ -0,0 +1,211 @@
+//! Streaming discovery results out to other systems
+//!
+//! Every entity a job discovers can be sent to an [`OutputSink`] as it is
+//! stored, e.g. to feed a SIEM. Events carry the entity as its NDJSON record
+//! (see [`crate::ndjson`]) along with the organization and job it was found
+//! for. Sinks POST each event to a webhook, append it to an NDJSON file or,
+//! with the `kafka` feature, produce it to a Kafka topic.
+
+use crate::http_client::HttpClientConfig;
+use crate::results::DiscoveryResult;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// How long a webhook has to accept an event
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One discovered entity, as sent to an output sink
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryEvent {
+    pub organization_id: Uuid,
+    pub job_id: Option<Uuid>,
+    pub emitted_at: DateTime<Utc>,
+    /// The entity's NDJSON record, tagged with its `kind`
+    #[serde(flatten)]
+    pub record: serde_json::Value,
+}
+
+impl DiscoveryEvent {
+    /// One event per entity in `result`
+    pub fn from_result(
+        organization_id: Uuid,
+        job_id: Option<Uuid>,
+        result: &DiscoveryResult,
+    ) -> Result<Vec<Self>> {
+        let emitted_at = Utc::now();
+        result
+            .records()
+            .map(|record| {
+                Ok(Self {
+                    organization_id,
+                    job_id,
+                    emitted_at,
+                    record: serde_json::to_value(&record)?,
+                })
+            })
+            .collect()
+    }
+
+    /// The entity's kind, e.g. `domain` or `port`
+    pub fn kind(&self) -> Option<&str> {
+        self.record.get("kind").and_then(|kind| kind.as_str())
+    }
+}
+
+/// Somewhere discovery events are sent
+#[async_trait]
+pub trait OutputSink: Send + Sync {
+    /// Send a single event
+    async fn emit(&self, event: DiscoveryEvent) -> Result<()>;
+}
+
+/// Send every entity in `result` to `sink`, returning the number of events
+/// sent. An event that can't be sent is logged and skipped, so an
+/// unreachable sink doesn't hold up discovery.
+pub async fn emit_result(
+    sink: &dyn OutputSink,
+    organization_id: Uuid,
+    job_id: Option<Uuid>,
+    result: &DiscoveryResult,
+) -> Result<usize> {
+    let mut emitted = 0;
+    for event in DiscoveryEvent::from_result(organization_id, job_id, result)? {
+        match sink.emit(event).await {
+            Ok(()) => emitted += 1,
+            Err(e) => tracing::warn!("Failed to emit discovery event: {:#}", e),
+        }
+    }
+    Ok(emitted)
+}
+
+/// POSTs each event as JSON to a URL
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+}
+
+impl WebhookSink {
+    /// Create a sink posting to `url`. The webhook is picked by the
+    /// operator, so requests go out directly rather than through the scan
+    /// proxy.
+    pub fn new(url: &str) -> Result<Self> {
+        let parsed =
+            url::Url::parse(url).with_context(|| format!("invalid webhook URL `{}`", url))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(anyhow!("webhook URL must be http or https, got `{}`", url));
+        }
+
+        let client = HttpClientConfig::default()
+            .client_builder()?
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            client,
+            url: url.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl OutputSink for WebhookSink {
+    async fn emit(&self, event: DiscoveryEvent) -> Result<()> {
+        let response = self.client.post(&self.url).json(&event).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "webhook {} answered {}",
+                self.url,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Appends each event as one line of JSON to a file
+pub struct NdjsonFileSink {
+    file: Mutex<File>,
+}
+
+impl NdjsonFileSink {
+    /// Open `path` for appending, creating it if needed
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl OutputSink for NdjsonFileSink {
+    async fn emit(&self, event: DiscoveryEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+
+        // One write per line keeps events from concurrent jobs whole
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Produces each event to partition 0 of a Kafka topic, keyed by its
+/// organization
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    partition: rskafka::client::partition::PartitionClient,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    /// Connect to the cluster through `brokers` and produce to `topic`
+    pub async fn connect(brokers: &[String], topic: &str) -> Result<Self> {
+        use rskafka::client::{partition::UnknownTopicHandling, ClientBuilder};
+
+        let client = ClientBuilder::new(brokers.to_vec())
+            .build()
+            .await
+            .context("failed to connect to Kafka")?;
+        let partition = client
+            .partition_client(topic, 0, UnknownTopicHandling::Retry)
+            .await
+            .with_context(|| format!("failed to open Kafka topic `{}`", topic))?;
+        Ok(Self { partition })
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl OutputSink for KafkaSink {
+    async fn emit(&self, event: DiscoveryEvent) -> Result<()> {
+        use rskafka::client::partition::Compression;
+        use rskafka::record::Record;
+
+        let record = Record {
+            key: Some(event.organization_id.to_string().into_bytes()),
+            value: Some(serde_json::to_vec(&event)?),
+            headers: Default::default(),
+            timestamp: event.emitted_at,
+        };
+        self.partition
+            .produce(vec![record], Compression::NoCompression)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! Probing web hosts for well-known and commonly exposed paths
+//!
+//! Plenty of exposures live at predictable paths: a deployed `.git`
+//! directory, an `.env` file, a status page left enabled. Each discovered
+//! web host is asked for a list of such paths. A path counts as present when
+//! it answers below 400 and the body contains one of the path's
+//! fingerprints, which keeps sites that answer every URL with a page from
+//! looking like they expose everything. Present paths are recorded as web
+//! resources, and those with a severity are reported as vulnerabilities.
+
+use crate::http_client::HttpClientConfig;
+use crate::results::{DiscoveredWebResource, DiscoveryResult};
+use crate::vulnerability::DiscoveredVulnerability;
+use crate::web_crawl::politeness::HostRegistry;
+use anyhow::Result;
+use reqwest::{redirect, Client};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+use url::Url;
+
+/// Paths shipped with the scanner
+const DEFAULT_PATHS: &str = include_str!("paths.json");
+
+/// Source recorded on resources and findings
+pub const PATH_PROBE_SOURCE: &str = "path_probe";
+
+/// Most of a response body checked for fingerprints
+const MAX_BODY_SIZE: usize = 256 * 1024;
+
+/// A path to ask web hosts for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProbePath {
+    /// Short identifier, used in the finding's template ID
+    pub id: String,
+    /// Path requested on each host, starting with `/`
+    pub path: String,
+    /// Human-readable name of what the path exposes
+    pub name: String,
+    /// Any of these in the response body shows the path is really there.
+    /// With none, any response below 400 does.
+    #[serde(default)]
+    pub fingerprints: Vec<String>,
+    /// Severity of the exposure, `None` for paths that are only recorded
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// What the exposure means, for the finding
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ProbePath {
+    /// Whether a response shows the path is present
+    pub fn matches_response(&self, status_code: u16, body: &str) -> bool {
+        status_code < 400
+            && (self.fingerprints.is_empty()
+                || self
+                    .fingerprints
+                    .iter()
+                    .any(|fingerprint| body.contains(fingerprint.as_str())))
+    }
+}
+
+/// The paths shipped with the scanner
+pub fn default_paths() -> Vec<ProbePath> {
+    serde_json::from_str(DEFAULT_PATHS).expect("bundled probe paths are valid")
+}
+
+/// Asks web hosts for a list of paths
+pub struct PathProber {
+    client: Client,
+    paths: Vec<ProbePath>,
+}
+
+impl PathProber {
+    /// Create a prober using the default paths
+    pub fn new() -> Result<Self> {
+        Self::with_paths(default_paths())
+    }
+
+    /// Create a prober using a custom path list
+    pub fn with_paths(paths: Vec<ProbePath>) -> Result<Self> {
+        Self::with_config(paths, &HttpClientConfig::default())
+    }
+
+    /// Create a prober using a custom path list and HTTP settings, e.g. a
+    /// proxy
+    pub fn with_config(paths: Vec<ProbePath>, http: &HttpClientConfig) -> Result<Self> {
+        // Hosts resolving to internal addresses are skipped, and a redirect to
+        // a login page or the home page isn't the path itself
+        let client = http
+            .external_client_builder()?
+            .timeout(Duration::from_secs(10))
+            .redirect(redirect::Policy::none())
+            .build()?;
+
+        Ok(Self { client, paths })
+    }
+
+    /// Ask the host serving `base_url` for each path. Paths that can't be
+    /// fetched are skipped.
+    pub async fn probe(&self, base_url: &str) -> Result<DiscoveryResult> {
+        let base = Url::parse(base_url)?;
+        let host = base
+            .host_str()
+            .ok_or_else(|| anyhow::anyhow!("{} has no host", base_url))?
+            .to_string();
+        let mut result = DiscoveryResult::new();
+
+        for probe_path in &self.paths {
+            let url = base.join(&probe_path.path)?;
+            let _permit = HostRegistry::shared().acquire(&host).await;
+
+            let response = match self.client.get(url.clone()).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::debug!("Could not probe {}: {}", url, e);
+                    continue;
+                }
+            };
+            let status_code = response.status().as_u16();
+            let body = match response.bytes().await {
+                Ok(bytes) => {
+                    String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_BODY_SIZE)]).into_owned()
+                }
+                Err(e) => {
+                    tracing::debug!("Could not read {}: {}", url, e);
+                    continue;
+                }
+            };
+            if !probe_path.matches_response(status_code, &body) {
+                continue;
+            }
+
+            tracing::debug!("Found {} at {}", probe_path.name, url);
+            result.web_resources.push(DiscoveredWebResource {
+                url: url.to_string(),
+                status_code,
+                title: Some(probe_path.name.clone()),
+                technologies: Vec::new(),
+                source: PATH_PROBE_SOURCE.to_string(),
+                screenshot_path: None,
+                content_hash: None,
+            });
+            if let Some(severity) = &probe_path.severity {
+                result.raw_vulnerabilities.push(path_vulnerability(
+                    &host,
+                    url.as_str(),
+                    probe_path,
+                    severity,
+                ));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Probe every distinct web host among the web resources in `result`,
+    /// adding what was found to it. Returns the number of findings.
+    pub async fn probe_all(&self, result: &mut DiscoveryResult) -> usize {
+        let mut origins = Vec::new();
+        let mut seen = HashSet::new();
+        for resource in &result.web_resources {
+            let Ok(url) = Url::parse(&resource.url) else {
+                continue;
+            };
+            let origin = url.origin().ascii_serialization();
+            if url.has_host() && seen.insert(origin.clone()) {
+                origins.push(origin);
+            }
+        }
+
+        let mut found = DiscoveryResult::new();
+        for origin in origins {
+            match self.probe(&origin).await {
+                Ok(probed) => found.merge(probed),
+                Err(e) => tracing::warn!("Path probe of {} failed: {}", origin, e),
+            }
+        }
+
+        let count = found.raw_vulnerabilities.len();
+        result.merge(found);
+        count
+    }
+}
+
+fn path_vulnerability(
+    host: &str,
+    url: &str,
+    probe_path: &ProbePath,
+    severity: &str,
+) -> DiscoveredVulnerability {
+    let mut vulnerability = DiscoveredVulnerability::new(
+        host.to_string(),
+        probe_path.name.clone(),
+        severity.to_string(),
+        format!("exposed-path-{}", probe_path.id),
+        url.to_string(),
+    );
+    vulnerability.description = probe_path.description.clone();
+    vulnerability.tags = vec!["exposure".to_string(), probe_path.id.clone()];
+    vulnerability.source = PATH_PROBE_SOURCE.to_string();
+    vulnerability
+}
@@ -0,0 +1,118 @@
+//! Previews of what a discovery job would scan
+//!
+//! A dry run works out a job's targets, ports and the number of requests it
+//! would send, applying the same expansion and scope checks a real run
+//! does, without probing anything. Hostnames are still resolved, through a
+//! [`HostResolver`], since a port scan targets the addresses they point at.
+
+use crate::dns::DnsEnumerator;
+use crate::port_scan::{expand_cidr_with_limit, is_scannable, COMMON_PORTS, MAX_TARGET_HOSTS};
+use crate::scope::ScanScope;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Lookups a DNS enumeration makes for its target: A/AAAA, CNAME and MX
+/// records, an RDAP query and the CNAME lookup of the takeover check
+pub const DNS_ENUMERATION_REQUESTS: usize = 5;
+
+/// What a job would scan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanPlan {
+    /// Hosts or addresses that would be scanned
+    pub targets: Vec<String>,
+    /// Addresses left out, with why
+    pub skipped: Vec<SkippedTarget>,
+    /// Ports scanned on each target, empty for jobs that don't scan ports
+    pub ports: Vec<u16>,
+    /// Requests or probes the job would send
+    pub estimated_requests: usize,
+}
+
+/// An address a job would leave out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedTarget {
+    pub target: String,
+    pub reason: String,
+}
+
+impl ScanPlan {
+    /// One line describing the plan, for job logs
+    pub fn summary(&self) -> String {
+        format!(
+            "Dry run: {} targets, {} ports, ~{} requests, {} skipped",
+            self.targets.len(),
+            self.ports.len(),
+            self.estimated_requests,
+            self.skipped.len()
+        )
+    }
+}
+
+/// Resolves hostnames to the addresses a scan would target
+#[async_trait]
+pub trait HostResolver: Send + Sync {
+    async fn resolve(&self, host: &str) -> Result<Vec<IpAddr>>;
+}
+
+#[async_trait]
+impl HostResolver for DnsEnumerator {
+    async fn resolve(&self, host: &str) -> Result<Vec<IpAddr>> {
+        DnsEnumerator::resolve(self, host).await
+    }
+}
+
+/// Plan a DNS enumeration of `domain`
+pub fn plan_dns_enumeration(domain: &str) -> ScanPlan {
+    ScanPlan {
+        targets: vec![domain.trim().to_string()],
+        estimated_requests: DNS_ENUMERATION_REQUESTS,
+        ..ScanPlan::default()
+    }
+}
+
+/// Plan a port scan of `target`: a single IP, a CIDR range of at most
+/// `max_hosts` hosts ([`MAX_TARGET_HOSTS`] when `None`), or a hostname,
+/// resolved through `resolver`. Addresses that aren't
+/// [scannable](is_scannable) or are outside `scope` are skipped. `ports` of
+/// `None` scans [`COMMON_PORTS`].
+pub async fn plan_port_scan(
+    target: &str,
+    ports: Option<&[u16]>,
+    scope: &ScanScope,
+    resolver: &dyn HostResolver,
+    max_hosts: Option<usize>,
+) -> Result<ScanPlan> {
+    let target = target.trim();
+    let hosts = if target.contains('/') {
+        expand_cidr_with_limit(target, max_hosts.unwrap_or(MAX_TARGET_HOSTS))?
+    } else if let Ok(ip) = target.parse::<IpAddr>() {
+        vec![ip]
+    } else {
+        resolver.resolve(target).await?
+    };
+
+    let mut plan = ScanPlan {
+        ports: ports.map_or_else(|| COMMON_PORTS.to_vec(), <[u16]>::to_vec),
+        ..ScanPlan::default()
+    };
+    for ip in hosts {
+        // A domain in scope can still resolve to a blocked address
+        let skipped = if !is_scannable(ip) {
+            Some("not a scannable address".to_string())
+        } else {
+            scope.check_address(ip).err().map(|e| e.reason)
+        };
+        match skipped {
+            Some(reason) => plan.skipped.push(SkippedTarget {
+                target: ip.to_string(),
+                reason,
+            }),
+            None => plan.targets.push(ip.to_string()),
+        }
+    }
+    plan.estimated_requests = plan.targets.len() * plan.ports.len();
+
+    Ok(plan)
+}
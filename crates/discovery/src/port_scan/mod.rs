@@ -1,28 +1,98 @@
-use crate::results::{DiscoveredIp, DiscoveryResult};
+use crate::cancellation::ScanCancelled;
+use crate::results::{DiscoveredDomain, DiscoveredIp, DiscoveryResult};
+use crate::web_crawl;
 use anyhow::Result;
+use scraper::Html;
 use serde::{Deserialize, Serialize};
+use shared::types::PortStatus;
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::net::{TcpStream, UdpSocket};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::{sleep, timeout};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DiscoveredPort {
     pub ip_address: IpAddr,
     pub port: u16,
     pub protocol: String, // "TCP" or "UDP"
-    pub status: String,   // "OPEN", "CLOSED", "FILTERED"
+    pub status: String,   // "OPEN", "CLOSED", "FILTERED", "OPEN|FILTERED", "ERROR"
     pub service_name: Option<String>,
     pub banner: Option<String>,
+    /// Status code returned to `GET /` on HTTP ports
+    pub http_status: Option<u16>,
+    /// Page title returned to `GET /` on HTTP ports
+    pub http_title: Option<String>,
+    /// Certificate presented by TLS services
+    pub tls_info: Option<TlsInfo>,
     pub source: String,
 }
 
+impl DiscoveredPort {
+    /// Map the scanner's status onto the stored `PortStatus`. A UDP port that
+    /// never answered may be open but can't be told apart from a filtered
+    /// one, so it is recorded as filtered. Returns `None` if probing failed.
+    pub fn port_status(&self) -> Option<PortStatus> {
+        match self.status.as_str() {
+            "OPEN" => Some(PortStatus::Open),
+            "CLOSED" => Some(PortStatus::Closed),
+            "FILTERED" | "OPEN|FILTERED" | "CLOSED|FILTERED" => Some(PortStatus::Filtered),
+            _ => None,
+        }
+    }
+}
+
+/// How TCP ports are probed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanType {
+    /// Complete a TCP handshake with each port. Slow but needs no
+    /// privileges.
+    #[default]
+    Connect,
+    /// Send a single SYN per port over a raw socket and never complete the
+    /// handshake. Much faster, but needs the `syn-scan` feature and root or
+    /// `CAP_NET_RAW`; without them ports are connect-scanned instead.
+    Syn,
+}
+
+/// Settings for scanning the ports of a host
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PortScanConfig {
+    #[serde(default)]
+    pub scan_type: ScanType,
+    /// Most probes started per second, TCP and UDP together. `None` probes
+    /// as fast as the concurrency limit allows.
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+    /// SOCKS5 proxy to scan through, e.g. `socks5://127.0.0.1:1080`. TCP
+    /// probes and banner grabs go through it; SYN scans fall back to connect
+    /// scans and UDP ports are skipped, since neither can be proxied.
+    #[serde(default)]
+    pub socks_proxy: Option<String>,
+    /// Probes used to grab banners and identify services, instead of
+    /// [`default_service_probes`]
+    #[serde(default)]
+    pub service_probes: Option<Vec<ServiceProbe>>,
+    /// Most hosts a CIDR target may expand to, [`MAX_TARGET_HOSTS`] when
+    /// unset
+    #[serde(default)]
+    pub max_target_hosts: Option<usize>,
+}
+
 const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a SYN scan waits for replies after the last SYN went out
+#[cfg(feature = "syn-scan")]
+const SYN_REPLY_TIMEOUT: Duration = Duration::from_secs(2);
 const UDP_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
 const BANNER_GRAB_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long the TLS 1.1 handshake checking for a legacy protocol may take
+const LEGACY_TLS_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Upper bound on how much of an HTTP response we read looking for a title
+const MAX_HTTP_RESPONSE_SIZE: usize = 64 * 1024;
 
 // Common service to port mappings
 lazy_static::lazy_static! {
@@ -37,6 +107,7 @@ lazy_static::lazy_static! {
         m.insert(110, "POP3");
         m.insert(123, "NTP");
         m.insert(143, "IMAP");
+        m.insert(161, "SNMP");
         m.insert(443, "HTTPS");
         m.insert(465, "SMTPS");
         m.insert(587, "Submission");
@@ -58,7 +129,44 @@ lazy_static::lazy_static! {
 }
 
 pub async fn scan_ip(target_ip: IpAddr, ports: &[u16]) -> Result<DiscoveryResult> {
-    tracing::debug!("Scanning IP: {} for {} ports", target_ip, ports.len());
+    scan_ip_with_cancellation(target_ip, ports, &CancellationToken::new()).await
+}
+
+/// Scan an IP, stopping with [`ScanCancelled`] as soon as `cancel` fires
+pub async fn scan_ip_with_cancellation(
+    target_ip: IpAddr,
+    ports: &[u16],
+    cancel: &CancellationToken,
+) -> Result<DiscoveryResult> {
+    scan_ip_with_config(target_ip, ports, &PortScanConfig::default(), cancel).await
+}
+
+/// Scan an IP as set up by `config`, stopping with [`ScanCancelled`] as soon
+/// as `cancel` fires
+pub async fn scan_ip_with_config(
+    target_ip: IpAddr,
+    ports: &[u16],
+    config: &PortScanConfig,
+    cancel: &CancellationToken,
+) -> Result<DiscoveryResult> {
+    tracing::debug!(
+        "Scanning IP: {} for {} ports ({:?} scan)",
+        target_ip,
+        ports.len(),
+        config.scan_type
+    );
+
+    if config.rate_limit == Some(0) {
+        return Err(anyhow::anyhow!("Port scan rate limit must be at least 1"));
+    }
+    let mut rate_limiter = config.rate_limit.map(RateLimiter::new);
+    let connector = Connector::from_proxy_url(config.socks_proxy.as_deref())?;
+    let probes = ServiceProbes::new(
+        config
+            .service_probes
+            .clone()
+            .unwrap_or_else(default_service_probes),
+    )?;
 
     let (tx, mut rx) = mpsc::channel::<DiscoveredPort>(ports.len() * 2); // Channel for port results
     let source_base = format!("port_scan_for_{}", target_ip);
@@ -71,23 +179,80 @@ pub async fn scan_ip(target_ip: IpAddr, ports: &[u16]) -> Result<DiscoveryResult
     let open_tcp_ports = Arc::new(Mutex::new(Vec::new()));
 
     // TCP scan
-    for &port in ports {
-        let tx_clone = tx.clone();
-        let source = source_base.clone();
-        let permit = semaphore.clone().acquire_owned().await?;
-        let open_ports = open_tcp_ports.clone();
+    let tcp_backend = if connector.is_proxied() {
+        if config.scan_type == ScanType::Syn {
+            tracing::warn!(
+                "Can't SYN scan {} through a proxy, falling back to connect scan",
+                target_ip
+            );
+        }
+        TcpBackend::Connect
+    } else {
+        TcpBackend::select(config.scan_type, target_ip)
+    };
+    match tcp_backend {
+        TcpBackend::Connect => {
+            for &port in ports {
+                let tx_clone = tx.clone();
+                let source = source_base.clone();
+                throttle(rate_limiter.as_mut(), cancel).await?;
+                let permit = acquire_or_cancel(&semaphore, cancel).await?;
+                let open_ports = open_tcp_ports.clone();
+                let cancel_clone = cancel.clone();
+                let connector = connector.clone();
 
-        tokio::spawn(async move {
-            let _permit = permit; // Drop at end of scope
-            let tcp_result = scan_tcp_port(target_ip, port, source.clone()).await;
+                tokio::spawn(async move {
+                    let _permit = permit; // Drop at end of scope
+                    let tcp_result = tokio::select! {
+                        result = scan_tcp_port(&connector, target_ip, port, source.clone()) => result,
+                        _ = cancel_clone.cancelled() => None,
+                    };
+
+                    if let Some(port_info) = tcp_result {
+                        // If port is open, add to open ports list for banner grabbing
+                        if port_info.status == "OPEN" {
+                            open_ports.lock().await.push(port);
+                        }
 
-            if let Some(port_info) = tcp_result {
-                // If port is open, add to open ports list for banner grabbing
-                if port_info.status == "OPEN" {
-                    open_ports.lock().await.push(port);
+                        if tx_clone.send(port_info).await.is_err() {
+                            tracing::error!(
+                                "Failed to send TCP port scan result for {}:{}",
+                                target_ip,
+                                port
+                            );
+                        }
+                    }
+                });
+            }
+        }
+        #[cfg(feature = "syn-scan")]
+        TcpBackend::Syn(scanner) => {
+            let replies = scanner
+                .scan(ports, SYN_REPLY_TIMEOUT, rate_limiter.as_mut(), cancel)
+                .await?;
+            for &port in ports {
+                // Silence means something dropped the SYN
+                let status = replies.get(&port).copied().unwrap_or("FILTERED");
+                if status == "OPEN" {
+                    open_tcp_ports.lock().await.push(port);
                 }
+                let service_name = (status == "OPEN")
+                    .then(|| SERVICE_PORTS.get(&port).map(|s| s.to_string()))
+                    .flatten();
 
-                if tx_clone.send(port_info).await.is_err() {
+                let port_info = DiscoveredPort {
+                    ip_address: target_ip,
+                    port,
+                    protocol: "TCP".to_string(),
+                    status: status.to_string(),
+                    service_name,
+                    banner: None,
+                    http_status: None,
+                    http_title: None,
+                    tls_info: None,
+                    source: source_base.clone(),
+                };
+                if tx.send(port_info).await.is_err() {
                     tracing::error!(
                         "Failed to send TCP port scan result for {}:{}",
                         target_ip,
@@ -95,11 +260,12 @@ pub async fn scan_ip(target_ip: IpAddr, ports: &[u16]) -> Result<DiscoveryResult
                     );
                 }
             }
-        });
+        }
     }
 
-    // UDP scan (can be slower and less reliable)
-    if !ports.is_empty() {
+    // UDP scan (can be slower and less reliable). SOCKS5 proxies rarely
+    // relay UDP, so it would go out directly and is skipped instead.
+    if !ports.is_empty() && !connector.is_proxied() {
         let udp_ports = ports
             .iter()
             .cloned()
@@ -109,11 +275,16 @@ pub async fn scan_ip(target_ip: IpAddr, ports: &[u16]) -> Result<DiscoveryResult
         for &port in &udp_ports {
             let tx_clone = tx.clone();
             let source = source_base.clone();
-            let permit = semaphore.clone().acquire_owned().await?;
+            throttle(rate_limiter.as_mut(), cancel).await?;
+            let permit = acquire_or_cancel(&semaphore, cancel).await?;
+            let cancel_clone = cancel.clone();
 
             tokio::spawn(async move {
                 let _permit = permit; // Drop at end of scope
-                let udp_result = scan_udp_port(target_ip, port, source).await;
+                let udp_result = tokio::select! {
+                    result = scan_udp_port(target_ip, port, source) => result,
+                    _ = cancel_clone.cancelled() => None,
+                };
 
                 if let Some(port_info) = udp_result {
                     if tx_clone.send(port_info).await.is_err() {
@@ -151,24 +322,43 @@ pub async fn scan_ip(target_ip: IpAddr, ports: &[u16]) -> Result<DiscoveryResult
             open_ports.len()
         );
 
-        let banner_results = grab_banners(target_ip, &open_ports, &source_base).await?;
+        let banner_results =
+            grab_banners(&connector, &probes, target_ip, &open_ports, cancel).await?;
 
         // Collect ports and update with banner information
-        while let Some(mut port_info) = rx.recv().await {
+        while let Some(mut port_info) = recv_or_cancel(&mut rx, cancel).await? {
             // If we have banner info for this port, add it
             if let Some(banner_data) = banner_results.get(&port_info.port) {
-                port_info.banner = Some(banner_data.banner.clone());
+                if !banner_data.banner.is_empty() {
+                    port_info.banner = Some(banner_data.banner.clone());
+                }
 
                 // If we detected a service from the banner, use it
                 if !banner_data.detected_service.is_empty() {
                     port_info.service_name = Some(banner_data.detected_service.clone());
                 }
+
+                // Names on the certificate point at further domains to explore
+                if let Some(tls_info) = &banner_data.tls_info {
+                    for domain_name in tls_info.san_domains() {
+                        discovery_result.domains.push(DiscoveredDomain {
+                            domain_name,
+                            source: format!("tls_certificate_{}:{}", target_ip, port_info.port),
+                        });
+                    }
+                    port_info.tls_info = Some(tls_info.clone());
+                }
+
+                if let Some(http) = &banner_data.http {
+                    port_info.http_status = Some(http.status_code);
+                    port_info.http_title = http.title.clone();
+                }
             }
             discovery_result.ports.push(port_info);
         }
     } else {
         // No open ports for banner grabbing, just collect the scan results
-        while let Some(port_info) = rx.recv().await {
+        while let Some(port_info) = recv_or_cancel(&mut rx, cancel).await? {
             discovery_result.ports.push(port_info);
         }
     }
@@ -176,9 +366,108 @@ pub async fn scan_ip(target_ip: IpAddr, ports: &[u16]) -> Result<DiscoveryResult
     Ok(discovery_result)
 }
 
-async fn scan_tcp_port(ip: IpAddr, port: u16, source: String) -> Option<DiscoveredPort> {
+/// How the TCP ports of one target are probed
+enum TcpBackend {
+    Connect,
+    #[cfg(feature = "syn-scan")]
+    Syn(syn::SynScanner),
+}
+
+impl TcpBackend {
+    /// Backend for a `requested` scan of `target_ip`, falling back to connect
+    /// scanning when a SYN scan isn't possible
+    fn select(requested: ScanType, target_ip: IpAddr) -> Self {
+        match requested {
+            ScanType::Connect => TcpBackend::Connect,
+            ScanType::Syn => match open_syn_scanner(target_ip) {
+                Ok(backend) => backend,
+                Err(e) => {
+                    tracing::warn!(
+                        "Can't SYN scan {}, falling back to connect scan: {:#}",
+                        target_ip,
+                        e
+                    );
+                    TcpBackend::Connect
+                }
+            },
+        }
+    }
+
+    fn scan_type(&self) -> ScanType {
+        match self {
+            TcpBackend::Connect => ScanType::Connect,
+            #[cfg(feature = "syn-scan")]
+            TcpBackend::Syn(_) => ScanType::Syn,
+        }
+    }
+}
+
+#[cfg(feature = "syn-scan")]
+fn open_syn_scanner(target_ip: IpAddr) -> Result<TcpBackend> {
+    Ok(TcpBackend::Syn(syn::SynScanner::open(target_ip)?))
+}
+
+#[cfg(not(feature = "syn-scan"))]
+fn open_syn_scanner(_target_ip: IpAddr) -> Result<TcpBackend> {
+    Err(anyhow::anyhow!(
+        "discovery was built without the `syn-scan` feature"
+    ))
+}
+
+/// Scan type actually used when `requested` is asked for on `target_ip`,
+/// after falling back for missing privileges or support
+pub fn effective_scan_type(requested: ScanType, target_ip: IpAddr) -> ScanType {
+    TcpBackend::select(requested, target_ip).scan_type()
+}
+
+/// Wait until the rate limit, if any, allows the next probe, giving up if the
+/// scan is cancelled first
+async fn throttle(
+    rate_limiter: Option<&mut RateLimiter>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let Some(delay) = rate_limiter.map(RateLimiter::reserve) else {
+        return Ok(());
+    };
+    if delay.is_zero() {
+        return Ok(());
+    }
+    tokio::select! {
+        _ = sleep(delay) => Ok(()),
+        _ = cancel.cancelled() => Err(ScanCancelled.into()),
+    }
+}
+
+/// Wait for a scan slot, giving up if the scan is cancelled first
+async fn acquire_or_cancel(
+    semaphore: &Arc<tokio::sync::Semaphore>,
+    cancel: &CancellationToken,
+) -> Result<tokio::sync::OwnedSemaphorePermit> {
+    tokio::select! {
+        permit = semaphore.clone().acquire_owned() => Ok(permit?),
+        _ = cancel.cancelled() => Err(ScanCancelled.into()),
+    }
+}
+
+/// Wait for the next port result, giving up if the scan is cancelled first
+async fn recv_or_cancel(
+    rx: &mut mpsc::Receiver<DiscoveredPort>,
+    cancel: &CancellationToken,
+) -> Result<Option<DiscoveredPort>> {
+    tokio::select! {
+        port_info = rx.recv() => Ok(port_info),
+        _ = cancel.cancelled() => Err(ScanCancelled.into()),
+    }
+}
+
+async fn scan_tcp_port(
+    connector: &Connector,
+    ip: IpAddr,
+    port: u16,
+    source: String,
+) -> Option<DiscoveredPort> {
     let addr: std::net::SocketAddr = (ip, port).into();
-    let result = timeout(TCP_CONNECT_TIMEOUT, TcpStream::connect(addr)).await;
+    let result = timeout(TCP_CONNECT_TIMEOUT, connector.connect(addr)).await;
 
     match result {
         Ok(Ok(_stream)) => {
@@ -192,18 +481,27 @@ async fn scan_tcp_port(ip: IpAddr, port: u16, source: String) -> Option<Discover
                 status: "OPEN".to_string(),
                 service_name: service,
                 banner: None, // Will be filled later if banner grabbing succeeds
+                http_status: None,
+                http_title: None,
+                tls_info: None, // Filled in for TLS ports alongside the banner
                 source,
             })
         }
-        Ok(Err(_)) => {
-            // Connection refused - Port is CLOSED
+        Ok(Err(e)) => {
+            // Connection refused - Port is CLOSED, unless a proxy says otherwise
+            if let ConnectError::Proxy(e) = &e {
+                tracing::debug!("Could not probe {}:{} through proxy: {}", ip, port, e);
+            }
             Some(DiscoveredPort {
                 ip_address: ip,
                 port,
                 protocol: "TCP".to_string(),
-                status: "CLOSED".to_string(),
+                status: e.status().to_string(),
                 service_name: None,
                 banner: None,
+                http_status: None,
+                http_title: None,
+                tls_info: None,
                 source,
             })
         }
@@ -216,6 +514,9 @@ async fn scan_tcp_port(ip: IpAddr, port: u16, source: String) -> Option<Discover
                 status: "FILTERED".to_string(),
                 service_name: None,
                 banner: None,
+                http_status: None,
+                http_title: None,
+                tls_info: None,
                 source,
             })
         }
@@ -223,41 +524,11 @@ async fn scan_tcp_port(ip: IpAddr, port: u16, source: String) -> Option<Discover
 }
 
 async fn scan_udp_port(ip: IpAddr, port: u16, source: String) -> Option<DiscoveredPort> {
-    // UDP scanning is trickier - sending empty packet and checking for ICMP response
-    // This is a simplified version that may have false positives/negatives
-    let socket = match UdpSocket::bind("0.0.0.0:0").await {
-        Ok(s) => s,
-        Err(e) => {
-            tracing::warn!("Failed to bind UDP socket: {}", e);
-            return None;
-        }
-    };
-
     let addr: std::net::SocketAddr = (ip, port).into();
-    // Send empty UDP packet
-    if socket.send_to(&[0; 10], addr).await.is_err() {
-        return Some(DiscoveredPort {
-            ip_address: ip,
-            port,
-            protocol: "UDP".to_string(),
-            status: "ERROR".to_string(),
-            service_name: None,
-            banner: None,
-            source,
-        });
-    }
-
-    // Try to receive a response
-    let mut buf = [0; 65535];
-    let recv_result = timeout(UDP_PROBE_TIMEOUT, socket.recv(&mut buf)).await;
+    let result = udp::probe(addr, UdpProbe::for_port(port), UDP_PROBE_TIMEOUT).await;
 
-    let status = match recv_result {
-        Ok(Ok(_)) => "OPEN",       // Got a response, port might be open
-        Ok(Err(_)) => "ERROR",     // Error receiving
-        Err(_) => "OPEN|FILTERED", // No response, could be filtered or open
-    };
-
-    let service = if status == "OPEN" {
+    // Only name the service when it answered in its own protocol
+    let service = if result == UdpProbeResult::Open {
         SERVICE_PORTS.get(&port).map(|s| s.to_string())
     } else {
         None
@@ -267,9 +538,12 @@ async fn scan_udp_port(ip: IpAddr, port: u16, source: String) -> Option<Discover
         ip_address: ip,
         port,
         protocol: "UDP".to_string(),
-        status: status.to_string(),
+        status: result.status().to_string(),
         service_name: service,
         banner: None,
+        http_status: None,
+        http_title: None,
+        tls_info: None,
         source,
     })
 }
@@ -279,19 +553,33 @@ struct BannerResult {
     port: u16,
     banner: String,
     detected_service: String,
+    tls_info: Option<TlsInfo>,
+    http: Option<HttpResponseInfo>,
+}
+
+/// What an HTTP port answered to `GET /`
+struct HttpResponseInfo {
+    status_code: u16,
+    title: Option<String>,
 }
 
 async fn grab_banners(
+    connector: &Connector,
+    probes: &ServiceProbes,
     ip: IpAddr,
     ports: &[u16],
-    _source_base: &str,
+    cancel: &CancellationToken,
 ) -> Result<HashMap<u16, BannerResult>> {
     let mut results: HashMap<u16, BannerResult> = HashMap::new();
 
     for &port in ports {
+        if cancel.is_cancelled() {
+            return Err(ScanCancelled.into());
+        }
+
         match timeout(
             BANNER_GRAB_TIMEOUT,
-            grab_banner_for_port(ip, port, _source_base),
+            grab_banner_for_port(connector, probes, ip, port),
         )
         .await
         {
@@ -312,12 +600,35 @@ async fn grab_banners(
 }
 
 async fn grab_banner_for_port(
+    connector: &Connector,
+    probes: &ServiceProbes,
     ip: IpAddr,
     port: u16,
-    _source_base: &str,
 ) -> Result<Option<BannerResult>> {
     let addr: std::net::SocketAddr = (ip, port).into();
-    let mut stream = match TcpStream::connect(addr).await {
+    if tls::is_tls_port(port) {
+        return grab_tls_banner(connector, probes, ip, port).await;
+    }
+    if let Some(protocol) = StartTlsProtocol::for_port(port) {
+        let probe = match connector.connect(addr).await {
+            Ok(stream) => starttls::probe_stream(ip, port, stream, protocol).await,
+            Err(e) => Err(e.into()),
+        };
+        match probe {
+            Ok(probe) => {
+                return Ok(Some(banner_result(
+                    probes,
+                    port,
+                    &probe.banner,
+                    probe.tls_info,
+                )))
+            }
+            // Fall back to the plaintext stimulus below
+            Err(e) => tracing::debug!("STARTTLS probe failed for {}:{}: {}", ip, port, e),
+        }
+    }
+
+    let mut stream = match connector.connect(addr).await {
         Ok(s) => s,
         Err(e) => {
             tracing::debug!("Could not connect for banner grabbing: {}", e);
@@ -325,48 +636,174 @@ async fn grab_banner_for_port(
         }
     };
 
-    // Some services need a stimulus packet to respond
-    let stimulus = match port {
-        21 => b"USER anonymous\r\n".to_vec(),                // FTP
-        25 | 587 | 465 => b"EHLO easm.scanner\r\n".to_vec(), // SMTP
-        80 | 8080 => b"GET / HTTP/1.0\r\nHost: host\r\n\r\n".to_vec(), // HTTP
-        110 => b"CAPA\r\n".to_vec(),                         // POP3
-        143 => b"A001 CAPABILITY\r\n".to_vec(),              // IMAP
-        _ => Vec::new(),
+    let Some(response) = read_banner(&mut stream, probes, port).await else {
+        return Ok(None);
     };
 
-    if !stimulus.is_empty() {
+    Ok(Some(banner_result(probes, port, &response, None)))
+}
+
+/// Grab the certificate and, if the service talks first or answers our
+/// stimulus, the banner from behind a TLS handshake. A second handshake
+/// checks whether the service still accepts TLS 1.1 or older.
+async fn grab_tls_banner(
+    connector: &Connector,
+    probes: &ServiceProbes,
+    ip: IpAddr,
+    port: u16,
+) -> Result<Option<BannerResult>> {
+    let connection = match connector.connect((ip, port).into()).await {
+        Ok(tcp) => tls::handshake(ip, tcp).await,
+        Err(e) => Err(e.into()),
+    };
+    let (mut stream, mut tls_info) = match connection {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::debug!("TLS handshake failed for {}:{}: {}", ip, port, e);
+            return Ok(None);
+        }
+    };
+
+    let response = read_banner(&mut stream, probes, port)
+        .await
+        .unwrap_or_default();
+    drop(stream);
+
+    // Bounded, so a service stalling the second handshake doesn't hold up the scan
+    if let Some(info) = tls_info.as_mut() {
+        let probe = async {
+            let tcp = connector.connect((ip, port).into()).await.ok()?;
+            Some(tls::accepts_legacy_protocol(ip, tcp).await)
+        };
+        info.legacy_protocol = matches!(
+            timeout(LEGACY_TLS_PROBE_TIMEOUT, probe).await,
+            Ok(Some(true))
+        );
+    }
+
+    Ok(Some(banner_result(probes, port, &response, tls_info)))
+}
+
+fn banner_result(
+    probes: &ServiceProbes,
+    port: u16,
+    response: &str,
+    tls_info: Option<TlsInfo>,
+) -> BannerResult {
+    let http = if is_http_port(port) {
+        parse_http_response(response)
+    } else {
+        None
+    };
+    let banner_clean = clean_banner(response);
+
+    // Identify the service from its banner, falling back to known port
+    // associations
+    let detected_service = probes
+        .identify(&banner_clean, port)
+        .or_else(|| SERVICE_PORTS.get(&port).copied())
+        .unwrap_or_default()
+        .to_string();
+
+    BannerResult {
+        port,
+        banner: banner_clean,
+        detected_service,
+        tls_info,
+        http,
+    }
+}
+
+/// Whether we probe a port with an HTTP request
+fn is_http_port(port: u16) -> bool {
+    matches!(port, 80 | 443 | 8080 | 8443)
+}
+
+/// Send the port's stimulus, if any, and read back the raw response
+async fn read_banner<S>(stream: &mut S, probes: &ServiceProbes, port: u16) -> Option<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if is_http_port(port) {
+        return read_http_response(stream).await;
+    }
+
+    // Some services need a stimulus packet to respond
+    if let Some(stimulus) = probes.stimulus(port) {
         // If error sending stimulus, try without it
-        let _ = tokio::io::AsyncWriteExt::write(&mut stream, &stimulus).await;
+        let _ = stream.write(stimulus).await;
     }
 
     // Read response
     let mut buf = vec![0; 4096];
-    let n = match timeout(
-        Duration::from_secs(2),
-        tokio::io::AsyncReadExt::read(&mut stream, &mut buf),
-    )
-    .await
-    {
+    let n = match timeout(Duration::from_secs(2), stream.read(&mut buf)).await {
         Ok(Ok(n)) if n > 0 => n,
-        _ => return Ok(None),
+        _ => return None,
     };
 
     // Try to interpret as UTF-8, fall back to lossy if it's not valid
-    let banner_text = String::from_utf8_lossy(&buf[..n]).to_string();
-    let banner_clean = clean_banner(&banner_text);
+    Some(String::from_utf8_lossy(&buf[..n]).to_string())
+}
 
-    // Attempt to detect service from banner
-    let detected_service = detect_service_from_banner(&banner_clean, port);
+/// Request `/` and read the response until the server closes the connection,
+/// so the body is available for title extraction
+async fn read_http_response<S>(stream: &mut S) -> Option<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .write_all(b"GET / HTTP/1.0\r\nHost: host\r\n\r\n")
+        .await
+        .ok()?;
 
-    Ok(Some(BannerResult {
-        port,
-        banner: banner_clean,
-        detected_service,
-    }))
+    let mut response = Vec::new();
+    let mut buf = vec![0; 4096];
+    // Keep whatever arrived if the server is slow to close
+    let _ = timeout(Duration::from_secs(2), async {
+        while response.len() < MAX_HTTP_RESPONSE_SIZE {
+            match stream.read(&mut buf).await {
+                Ok(n) if n > 0 => response.extend_from_slice(&buf[..n]),
+                _ => break,
+            }
+        }
+    })
+    .await;
+
+    if response.is_empty() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&response).to_string())
 }
 
-fn clean_banner(banner: &str) -> String {
+/// Parse the status line and, for HTML responses, the page title
+fn parse_http_response(response: &str) -> Option<HttpResponseInfo> {
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response, ""));
+    let mut lines = head.lines();
+
+    // e.g. "HTTP/1.1 200 OK"
+    let mut status_line = lines.next()?.split_whitespace();
+    if !status_line.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    let status_code = status_line.next()?.parse().ok()?;
+
+    let is_html = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("content-type")
+                && value.to_lowercase().contains("html")
+        })
+    });
+    let title = if is_html {
+        web_crawl::extract_title(&Html::parse_document(body))
+    } else {
+        None
+    };
+
+    Some(HttpResponseInfo { status_code, title })
+}
+
+pub(crate) fn clean_banner(banner: &str) -> String {
     // Remove non-printable characters, limit length, etc.
     let mut cleaned = banner
         .chars()
@@ -382,50 +819,102 @@ fn clean_banner(banner: &str) -> String {
     cleaned
 }
 
-fn detect_service_from_banner(banner: &str, port: u16) -> String {
-    let banner_lower = banner.to_lowercase();
+// Add the naabu module
+pub mod naabu;
+pub mod probes;
+mod rate_limit;
+mod socks;
+pub mod starttls;
+#[cfg(feature = "syn-scan")]
+mod syn;
+pub mod tls;
+mod udp;
+
+use probes::ServiceProbes;
+pub use probes::{default_service_probes, load_service_probes, ServiceProbe};
+use rate_limit::RateLimiter;
+use socks::{ConnectError, Connector};
+use starttls::StartTlsProtocol;
+pub use tls::TlsInfo;
+use udp::{UdpProbe, UdpProbeResult};
 
-    // Look for common service signatures in banner
-    if banner_lower.contains("ssh") {
-        return "SSH".to_string();
-    } else if banner_lower.contains("ftp") {
-        return "FTP".to_string();
-    } else if banner_lower.contains("smtp") || banner_lower.contains("mail service") {
-        return "SMTP".to_string();
-    } else if banner_lower.contains("http") {
-        if port == 443 || banner_lower.contains("ssl") || banner_lower.contains("https") {
-            return "HTTPS".to_string();
-        }
-        return "HTTP".to_string();
-    } else if banner_lower.contains("pop3") {
-        return "POP3".to_string();
-    } else if banner_lower.contains("imap") {
-        return "IMAP".to_string();
-    } else if banner_lower.contains("mysql") {
-        return "MySQL".to_string();
-    } else if banner_lower.contains("postgresql") {
-        return "PostgreSQL".to_string();
-    } else if banner_lower.contains("mongodb") {
-        return "MongoDB".to_string();
-    } else if banner_lower.contains("redis") {
-        return "Redis".to_string();
-    } else if banner_lower.contains("vnc") {
-        return "VNC".to_string();
-    } else if banner_lower.contains("rdp") || banner_lower.contains("remote desktop") {
-        return "RDP".to_string();
-    }
-
-    // Fall back to known port associations
-    SERVICE_PORTS
-        .get(&port)
-        .map_or(String::new(), |s| s.to_string())
+/// Most hosts a single target may expand to, enough for an IPv4 /22
+pub const MAX_TARGET_HOSTS: usize = 1024;
+
+/// Ports scanned when a scan doesn't list any
+pub const COMMON_PORTS: [u16; 21] = [
+    21, 22, 23, 25, 53, 80, 110, 111, 135, 139, 143, 443, 445, 993, 995, 1723, 3306, 3389, 5900,
+    8080, 8443,
+];
+
+/// Whether `ip` can be scanned. IPv6 link-local addresses (`fe80::/10`)
+/// only mean something on one link and the unspecified address `::` names
+/// no host, so neither is.
+pub fn is_scannable(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(_) => true,
+        IpAddr::V6(ip) => !ip.is_unspecified() && !ip.is_unicast_link_local(),
+    }
 }
 
-// Add the naabu module
-pub mod naabu;
+/// Hosts in a CIDR range such as `10.0.0.0/24`. The network and broadcast
+/// addresses of IPv4 ranges larger than a /31 are left out, since they
+/// aren't hosts, as are addresses that aren't [scannable](is_scannable).
+/// Ranges with more than [`MAX_TARGET_HOSTS`] hosts are rejected.
+pub fn expand_cidr(cidr: &str) -> Result<Vec<IpAddr>> {
+    expand_cidr_with_limit(cidr, MAX_TARGET_HOSTS)
+}
+
+/// Hosts in a CIDR range as [`expand_cidr`] finds them, rejecting ranges
+/// with more than `max_hosts` hosts. The size of the range is checked
+/// before any host is listed, so a huge IPv6 prefix is refused outright.
+pub fn expand_cidr_with_limit(cidr: &str, max_hosts: usize) -> Result<Vec<IpAddr>> {
+    let invalid = || anyhow::anyhow!("Invalid CIDR range {}", cidr);
+    let (address, prefix) = cidr.split_once('/').ok_or_else(invalid)?;
+    let address: IpAddr = address.trim().parse().map_err(|_| invalid())?;
+    let prefix: u32 = prefix.trim().parse().map_err(|_| invalid())?;
+
+    let (bits, base) = match address {
+        IpAddr::V4(ip) => (32, u128::from(u32::from(ip))),
+        IpAddr::V6(ip) => (128, u128::from(ip)),
+    };
+    if prefix > bits {
+        return Err(invalid());
+    }
+    let host_bits = bits - prefix;
+
+    // Without network and broadcast addresses
+    let skip = u128::from(address.is_ipv4() && host_bits >= 2);
+    let hosts = 1u128
+        .checked_shl(host_bits)
+        .map(|size| size - 2 * skip)
+        .filter(|&hosts| hosts <= max_hosts as u128)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "CIDR range {} is too large to scan, at most {} hosts are allowed",
+                cidr,
+                max_hosts
+            )
+        })?;
+
+    let network = base & !(u128::MAX.checked_shr(128 - host_bits).unwrap_or(0));
+    Ok((0..hosts)
+        .map(|offset| {
+            let host = network + skip + offset;
+            match address {
+                IpAddr::V4(_) => IpAddr::V4((host as u32).into()),
+                IpAddr::V6(_) => IpAddr::V6(host.into()),
+            }
+        })
+        .filter(|&ip| is_scannable(ip))
+        .collect())
+}
 
 /// Port Scanner struct for scanning IP addresses
-pub struct PortScanner {}
+pub struct PortScanner {
+    cancel: CancellationToken,
+    config: PortScanConfig,
+}
 
 impl Default for PortScanner {
     fn default() -> Self {
@@ -436,7 +925,22 @@ impl Default for PortScanner {
 impl PortScanner {
     /// Create a new port scanner
     pub fn new() -> Self {
-        Self {}
+        Self {
+            cancel: CancellationToken::new(),
+            config: PortScanConfig::default(),
+        }
+    }
+
+    /// Scan with `config` instead of the defaults
+    pub fn with_config(mut self, config: PortScanConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Stop scanning as soon as `cancel` fires
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
     }
 
     /// Scan an IP address for open ports
@@ -453,13 +957,125 @@ impl PortScanner {
         // Use common ports if none specified
         let ports_to_scan = match ports {
             Some(p) => p.to_vec(),
-            None => vec![
-                21, 22, 23, 25, 53, 80, 110, 111, 135, 139, 143, 443, 445, 993, 995, 1723, 3306,
-                3389, 5900, 8080, 8443,
-            ],
+            None => COMMON_PORTS.to_vec(),
         };
 
         // Scan the IP
-        scan_ip(ip, &ports_to_scan).await
+        scan_ip_with_config(ip, &ports_to_scan, &self.config, &self.cancel).await
+    }
+
+    /// Scan every host `target` stands for: a single IP, a CIDR range of at
+    /// most [`PortScanConfig::max_target_hosts`] hosts, or a hostname, which
+    /// is scanned on each address it resolves to. Addresses that aren't
+    /// [scannable](is_scannable) are skipped. The results of all hosts are
+    /// merged.
+    /// If ports is None, scans common ports
+    pub async fn scan_target(
+        &self,
+        target: &str,
+        ports: Option<&[u16]>,
+    ) -> Result<DiscoveryResult> {
+        let target = target.trim();
+        let hosts = if target.contains('/') {
+            let max_hosts = self.config.max_target_hosts.unwrap_or(MAX_TARGET_HOSTS);
+            expand_cidr_with_limit(target, max_hosts)?
+        } else if let Ok(ip) = target.parse::<IpAddr>() {
+            vec![ip]
+        } else {
+            let ips = crate::dns::resolve_domain(target).await;
+            if ips.is_empty() {
+                return Err(anyhow::anyhow!("Could not resolve {}", target));
+            }
+            ips
+        };
+
+        let hosts: Vec<IpAddr> = hosts.into_iter().filter(|&ip| is_scannable(ip)).collect();
+        if hosts.is_empty() {
+            return Err(anyhow::anyhow!("No scannable addresses in {}", target));
+        }
+
+        tracing::debug!("Scanning {} hosts for target {}", hosts.len(), target);
+        let mut result = DiscoveryResult::new();
+        for ip in hosts {
+            result.merge(self.scan_ip(&ip.to_string(), ports).await?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[tokio::test]
+    async fn test_http_probe_captures_status_and_title() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 403 Forbidden\r\n\
+                      Server: nginx\r\n\
+                      Content-Type: text/html; charset=utf-8\r\n\
+                      Connection: close\r\n\r\n\
+                      <html><head><title> Admin Console </title></head><body></body></html>",
+                )
+                .await;
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let response = read_http_response(&mut stream).await.unwrap();
+        let probes = ServiceProbes::new(default_service_probes()).unwrap();
+        let result = banner_result(&probes, 80, &response, None);
+
+        let http = result.http.expect("response should parse as HTTP");
+        assert_eq!(http.status_code, 403);
+        assert_eq!(http.title.as_deref(), Some("Admin Console"));
+        assert_eq!(result.detected_service, "HTTP");
+    }
+
+    #[test]
+    fn test_non_html_response_has_no_title() {
+        let http = parse_http_response(
+            "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{\"title\":\"<title>x</title>\"}",
+        )
+        .unwrap();
+        assert_eq!(http.status_code, 200);
+        assert_eq!(http.title, None);
+
+        assert!(parse_http_response("SSH-2.0-OpenSSH_8.9\r\n").is_none());
+    }
+
+    #[test]
+    fn test_port_status_mapping() {
+        let port = |status: &str| DiscoveredPort {
+            ip_address: "127.0.0.1".parse().unwrap(),
+            port: 53,
+            protocol: "UDP".to_string(),
+            status: status.to_string(),
+            service_name: None,
+            banner: None,
+            http_status: None,
+            http_title: None,
+            tls_info: None,
+            source: "test".to_string(),
+        };
+
+        assert_eq!(port("OPEN").port_status(), Some(PortStatus::Open));
+        assert_eq!(port("CLOSED").port_status(), Some(PortStatus::Closed));
+        assert_eq!(port("FILTERED").port_status(), Some(PortStatus::Filtered));
+        assert_eq!(
+            port("OPEN|FILTERED").port_status(),
+            Some(PortStatus::Filtered)
+        );
+        assert_eq!(
+            port("CLOSED|FILTERED").port_status(),
+            Some(PortStatus::Filtered)
+        );
+        assert_eq!(port("ERROR").port_status(), None);
     }
 }
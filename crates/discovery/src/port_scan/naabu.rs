@@ -62,6 +62,9 @@ impl NaabuRunner {
                                 .and_then(|s| s.as_str())
                                 .map(String::from),
                             banner: None,
+                            http_status: None,
+                            http_title: None,
+                            tls_info: None,
                             source: source.clone(),
                         });
                     }
@@ -121,6 +124,9 @@ impl NaabuRunner {
                                 .and_then(|s| s.as_str())
                                 .map(String::from),
                             banner: None,
+                            http_status: None,
+                            http_title: None,
+                            tls_info: None,
                             source: source.clone(),
                         });
                     }
@@ -0,0 +1,91 @@
+//! Service probes for banner grabbing
+//!
+//! Each probe names the ports it is sent to, the stimulus sent after
+//! connecting and a regex identifying the service from what comes back,
+//! in the spirit of Nmap's service probes. Probes for the scanned port are
+//! matched first, then every other probe, so a service on an unusual port
+//! is still recognized when it talks first.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Probes shipped with the scanner
+const DEFAULT_PROBES: &str = include_str!("probes.json");
+
+/// A stimulus to send and how to recognize the service answering it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServiceProbe {
+    /// Ports the stimulus is sent to. With none, the probe only identifies
+    /// services.
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// Sent once connected, empty to wait for the service to talk first
+    #[serde(default)]
+    pub send: String,
+    /// Regex the response is matched against
+    pub match_regex: String,
+    /// Service name recorded for a matching response
+    pub service: String,
+}
+
+/// The probes shipped with the scanner
+pub fn default_service_probes() -> Vec<ServiceProbe> {
+    serde_json::from_str(DEFAULT_PROBES).expect("bundled service probes are valid")
+}
+
+/// Read a JSON list of probes from `path`
+pub fn load_service_probes<P: AsRef<Path>>(path: P) -> Result<Vec<ServiceProbe>> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read service probes {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid service probes {}", path.display()))
+}
+
+/// Probes with their regexes compiled, ready to match banners
+#[derive(Debug)]
+pub(crate) struct ServiceProbes {
+    probes: Vec<(ServiceProbe, Regex)>,
+}
+
+impl ServiceProbes {
+    /// Compile the regexes of `probes`, failing on the first invalid one
+    pub(crate) fn new(probes: Vec<ServiceProbe>) -> Result<Self> {
+        let probes = probes
+            .into_iter()
+            .map(|probe| {
+                let regex = Regex::new(&probe.match_regex).with_context(|| {
+                    format!("Invalid match_regex for service probe {}", probe.service)
+                })?;
+                Ok((probe, regex))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { probes })
+    }
+
+    /// Stimulus to send to `port`, if any of its probes has one
+    pub(crate) fn stimulus(&self, port: u16) -> Option<&[u8]> {
+        self.probes
+            .iter()
+            .map(|(probe, _)| probe)
+            .find(|probe| probe.ports.contains(&port) && !probe.send.is_empty())
+            .map(|probe| probe.send.as_bytes())
+    }
+
+    /// Service `banner` identifies on `port`, trying the port's own probes
+    /// before the rest
+    pub(crate) fn identify(&self, banner: &str, port: u16) -> Option<&str> {
+        let (own, others): (Vec<_>, Vec<_>) = self
+            .probes
+            .iter()
+            .partition(|(probe, _)| probe.ports.contains(&port));
+
+        own.into_iter()
+            .chain(others)
+            .find(|(_, regex)| regex.is_match(banner))
+            .map(|(probe, _)| probe.service.as_str())
+    }
+}
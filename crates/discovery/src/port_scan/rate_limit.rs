@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+
+/// Token bucket pacing probe attempts to a fixed rate
+///
+/// The bucket holds a single token, so attempts are spread evenly instead of
+/// going out in bursts, which is what intrusion detection keys on. Asking for
+/// more tokens than are available runs the bucket into debt, so callers that
+/// wait out the returned delays before each attempt keep to the rate.
+pub(crate) struct RateLimiter {
+    per_second: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `per_second` attempts per second, which must be positive
+    pub(crate) fn new(per_second: u32) -> Self {
+        debug_assert!(per_second > 0, "rate limit must be positive");
+        Self {
+            per_second: f64::from(per_second),
+            capacity: 1.0,
+            tokens: 1.0,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Take a token for the next attempt, returning how long to wait before
+    /// making it
+    pub(crate) fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.refilled_at = now;
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity) - 1.0;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attempts_are_spaced_by_the_rate() {
+        let mut limiter = RateLimiter::new(10);
+
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        // Without waiting, each attempt is pushed another tenth of a second out
+        let second = limiter.reserve();
+        let third = limiter.reserve();
+        assert!(second > Duration::from_millis(90) && second <= Duration::from_millis(100));
+        assert!(third > Duration::from_millis(190) && third <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_idle_time_does_not_build_a_burst() {
+        let mut limiter = RateLimiter::new(1000);
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert!(limiter.reserve() > Duration::ZERO);
+    }
+}
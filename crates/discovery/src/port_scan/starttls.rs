@@ -0,0 +1,182 @@
+//! STARTTLS upgrades for mail services
+//!
+//! SMTP submission, IMAP and POP3 usually start in plaintext and switch to
+//! TLS on request. The service's capability list says whether it supports
+//! that, so we read it, ask for the upgrade when it is advertised and
+//! complete the handshake to see the certificate.
+
+use super::tls::{self, TlsInfo};
+use anyhow::{anyhow, Result};
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// How long to wait for each reply from the service
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Largest reply read before giving up on finding its end
+const MAX_REPLY_SIZE: usize = 8 * 1024;
+
+/// Mail protocol spoken on a port that can upgrade via STARTTLS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartTlsProtocol {
+    Smtp,
+    Imap,
+    Pop3,
+}
+
+impl StartTlsProtocol {
+    /// The protocol conventionally served on `port`, if it upgrades via
+    /// STARTTLS
+    pub fn for_port(port: u16) -> Option<Self> {
+        match port {
+            25 | 587 => Some(StartTlsProtocol::Smtp),
+            143 => Some(StartTlsProtocol::Imap),
+            110 => Some(StartTlsProtocol::Pop3),
+            _ => None,
+        }
+    }
+
+    /// Command asking for the capability list
+    fn capability_command(self) -> &'static [u8] {
+        match self {
+            StartTlsProtocol::Smtp => b"EHLO easm.scanner\r\n",
+            StartTlsProtocol::Imap => b"A001 CAPABILITY\r\n",
+            StartTlsProtocol::Pop3 => b"CAPA\r\n",
+        }
+    }
+
+    /// Capability advertising the upgrade
+    fn capability(self) -> &'static str {
+        match self {
+            StartTlsProtocol::Smtp | StartTlsProtocol::Imap => "STARTTLS",
+            StartTlsProtocol::Pop3 => "STLS",
+        }
+    }
+
+    /// Command requesting the upgrade
+    fn upgrade_command(self) -> &'static [u8] {
+        match self {
+            StartTlsProtocol::Smtp => b"STARTTLS\r\n",
+            StartTlsProtocol::Imap => b"A002 STARTTLS\r\n",
+            StartTlsProtocol::Pop3 => b"STLS\r\n",
+        }
+    }
+
+    /// Whether `line` is the last line of the reply to `command`, or of the
+    /// greeting when there is no command
+    fn is_final_line(self, command: Option<&[u8]>, line: &str) -> bool {
+        match (self, command) {
+            // Multiline replies continue with `250-` and end with `250 `
+            (StartTlsProtocol::Smtp, _) => line.len() < 4 || line.as_bytes()[3] != b'-',
+            (_, None) => true,
+            // Untagged `*` lines come before the command's tagged status
+            (StartTlsProtocol::Imap, Some(command)) => {
+                let tag = command.split(|&b| b == b' ').next().unwrap_or_default();
+                line.as_bytes().starts_with(tag)
+            }
+            // The capability list ends with a lone `.`, other replies are one line
+            (StartTlsProtocol::Pop3, Some(command)) => {
+                command != self.capability_command() || line == "." || line.starts_with("-ERR")
+            }
+        }
+    }
+
+    /// Whether the reply accepts the upgrade request
+    fn accepts_upgrade(self, reply: &str) -> bool {
+        let status = reply.lines().last().unwrap_or_default();
+        match self {
+            StartTlsProtocol::Smtp => status.starts_with("220"),
+            StartTlsProtocol::Imap => status.starts_with("A002 OK"),
+            StartTlsProtocol::Pop3 => status.starts_with("+OK"),
+        }
+    }
+}
+
+/// What a mail service showed before and after STARTTLS
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartTlsProbe {
+    /// The greeting and capability list, read in plaintext
+    pub banner: String,
+    /// Certificate presented after the upgrade, `None` if the service
+    /// doesn't offer STARTTLS
+    pub tls_info: Option<TlsInfo>,
+}
+
+/// Read the greeting and capabilities of the mail service on `ip:port` and,
+/// if it advertises STARTTLS, upgrade the connection to capture its
+/// certificate
+pub async fn probe(ip: IpAddr, port: u16, protocol: StartTlsProtocol) -> Result<StartTlsProbe> {
+    let stream = TcpStream::connect((ip, port)).await?;
+    probe_stream(ip, port, stream, protocol).await
+}
+
+/// [`probe`] over an already connected stream, e.g. one opened through a
+/// proxy
+pub(crate) async fn probe_stream(
+    ip: IpAddr,
+    port: u16,
+    mut stream: TcpStream,
+    protocol: StartTlsProtocol,
+) -> Result<StartTlsProbe> {
+    let mut banner = read_reply(&mut stream, |line| protocol.is_final_line(None, line)).await?;
+    let command = protocol.capability_command();
+    stream.write_all(command).await?;
+    let capabilities = read_reply(&mut stream, |line| {
+        protocol.is_final_line(Some(command), line)
+    })
+    .await?;
+    banner.push_str(&capabilities);
+
+    let offered = capabilities
+        .lines()
+        .any(|line| line.to_uppercase().contains(protocol.capability()));
+    if !offered {
+        return Ok(StartTlsProbe {
+            banner,
+            tls_info: None,
+        });
+    }
+
+    let command = protocol.upgrade_command();
+    stream.write_all(command).await?;
+    let reply = read_reply(&mut stream, |line| {
+        protocol.is_final_line(Some(command), line)
+    })
+    .await?;
+    if !protocol.accepts_upgrade(&reply) {
+        return Err(anyhow!(
+            "{}:{} refused STARTTLS: {}",
+            ip,
+            port,
+            reply.trim()
+        ));
+    }
+
+    let (_stream, tls_info) = tls::handshake(ip, stream).await?;
+    Ok(StartTlsProbe { banner, tls_info })
+}
+
+/// Read whole lines until `is_final` accepts one, returning everything read
+async fn read_reply(stream: &mut TcpStream, is_final: impl Fn(&str) -> bool) -> Result<String> {
+    let mut reply = Vec::new();
+    let mut buf = [0u8; 1024];
+
+    loop {
+        let n = timeout(REPLY_TIMEOUT, stream.read(&mut buf)).await??;
+        if n == 0 {
+            return Err(anyhow!("connection closed mid-reply"));
+        }
+        reply.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&reply);
+        if text.ends_with('\n') && text.lines().last().is_some_and(&is_final) {
+            return Ok(text.into_owned());
+        }
+        if reply.len() > MAX_REPLY_SIZE {
+            return Err(anyhow!("reply too long"));
+        }
+    }
+}
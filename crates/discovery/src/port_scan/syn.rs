@@ -0,0 +1,254 @@
+//! Stateless SYN ("half-open") scanning over raw sockets
+//!
+//! Every port gets a single SYN from one source port. A SYN/ACK means the
+//! port is open and a RST that it's closed; ports that stay silent are
+//! filtered. No connection is ever completed, so thousands of ports are
+//! probed in the time a connect scan spends waiting on a few timeouts.
+//! Crafting packets needs root or `CAP_NET_RAW`, and only IPv4 targets are
+//! supported.
+
+use super::rate_limit::RateLimiter;
+use super::throttle;
+use crate::cancellation::ScanCancelled;
+use anyhow::{anyhow, Result};
+use pnet_packet::ip::IpNextHeaderProtocols;
+use pnet_packet::tcp::{ipv4_checksum, MutableTcpPacket, TcpFlags};
+use pnet_transport::TransportChannelType::Layer4;
+use pnet_transport::TransportProtocol::Ipv4;
+use pnet_transport::{tcp_packet_iter, transport_channel, TransportReceiver, TransportSender};
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+/// TCP header without options
+const TCP_HEADER_LEN: usize = 20;
+
+/// Receive buffer of the raw socket, enough for any TCP segment we care about
+const RECEIVE_BUFFER_SIZE: usize = 4096;
+
+/// How often the receiver checks whether the scan is over
+const RECEIVE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Source ports are picked from the dynamic range so replies don't collide
+/// with local services
+const SOURCE_PORTS: std::ops::RangeInclusive<u16> = 49152..=65535;
+
+/// Raw socket set up to SYN scan one target
+pub(crate) struct SynScanner {
+    sender: TransportSender,
+    receiver: TransportReceiver,
+    source: Ipv4Addr,
+    target: Ipv4Addr,
+}
+
+impl SynScanner {
+    /// Open a raw socket for scanning `target`. Fails without raw socket
+    /// privileges or for IPv6 targets.
+    pub(crate) fn open(target: IpAddr) -> Result<Self> {
+        let IpAddr::V4(target) = target else {
+            return Err(anyhow!(
+                "SYN scanning supports IPv4 targets only, not {}",
+                target
+            ));
+        };
+
+        let source = source_address(target)?;
+        let (sender, receiver) = transport_channel(
+            RECEIVE_BUFFER_SIZE,
+            Layer4(Ipv4(IpNextHeaderProtocols::Tcp)),
+        )
+        .map_err(open_error)?;
+
+        Ok(Self {
+            sender,
+            receiver,
+            source,
+            target,
+        })
+    }
+
+    /// Send a SYN to each port, paced by `rate_limiter` if given, and wait
+    /// `reply_timeout` after the last one for stragglers. Returns "OPEN" or
+    /// "CLOSED" for each port that answered. The limiter is only borrowed,
+    /// so probes sent after the SYNs keep to the same rate.
+    pub(crate) async fn scan(
+        self,
+        ports: &[u16],
+        reply_timeout: Duration,
+        mut rate_limiter: Option<&mut RateLimiter>,
+        cancel: &CancellationToken,
+    ) -> Result<HashMap<u16, &'static str>> {
+        let Self {
+            mut sender,
+            receiver,
+            source,
+            target,
+        } = self;
+        let source_port = rand::random_range(SOURCE_PORTS);
+        let sequence: u32 = rand::random();
+        let done = Arc::new(AtomicBool::new(false));
+
+        // Both halves block, so they get a thread each
+        let listener = tokio::task::spawn_blocking({
+            let done = done.clone();
+            move || receive_replies(receiver, target, source_port, sequence, &done)
+        });
+
+        // Sending blocks too, so packets go out from another thread while
+        // the pacing happens here, where the limiter can be borrowed
+        let (port_sender, port_receiver) = std::sync::mpsc::channel::<u16>();
+        let sending = tokio::task::spawn_blocking(move || -> io::Result<()> {
+            for port in port_receiver {
+                let packet = syn_packet(source, target, source_port, port, sequence);
+                sender.send_to(packet, IpAddr::V4(target))?;
+            }
+            Ok(())
+        });
+        for &port in ports {
+            if throttle(rate_limiter.as_deref_mut(), cancel).await.is_err() || cancel.is_cancelled()
+            {
+                break;
+            }
+            // The sending thread only hangs up after a send failed
+            if port_sender.send(port).is_err() {
+                break;
+            }
+        }
+        drop(port_sender);
+        let sent = sending.await?;
+
+        if sent.is_ok() {
+            tokio::select! {
+                _ = sleep(reply_timeout) => {}
+                _ = cancel.cancelled() => {}
+            }
+        }
+        done.store(true, Ordering::Relaxed);
+        let replies = listener.await??;
+
+        if cancel.is_cancelled() {
+            return Err(ScanCancelled.into());
+        }
+        sent.map_err(|e| anyhow!("Failed to send SYN to {}: {}", target, e))?;
+
+        Ok(replies)
+    }
+}
+
+/// Local address packets to `target` leave from. Connecting a UDP socket
+/// sends nothing but makes the kernel pick the route.
+fn source_address(target: Ipv4Addr) -> Result<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect((target, 9))?;
+    match socket.local_addr()?.ip() {
+        IpAddr::V4(source) => Ok(source),
+        IpAddr::V6(source) => Err(anyhow!("No IPv4 route to {}, only {}", target, source)),
+    }
+}
+
+/// Explain why the raw socket couldn't be opened
+pub(crate) fn open_error(error: io::Error) -> anyhow::Error {
+    if error.kind() == io::ErrorKind::PermissionDenied {
+        anyhow!("SYN scanning needs root or CAP_NET_RAW: {}", error)
+    } else {
+        anyhow!("Failed to open raw socket: {}", error)
+    }
+}
+
+/// A bare SYN segment with a valid checksum
+pub(crate) fn syn_packet(
+    source: Ipv4Addr,
+    target: Ipv4Addr,
+    source_port: u16,
+    port: u16,
+    sequence: u32,
+) -> MutableTcpPacket<'static> {
+    let mut packet =
+        MutableTcpPacket::owned(vec![0; TCP_HEADER_LEN]).expect("buffer holds a TCP header");
+    packet.set_source(source_port);
+    packet.set_destination(port);
+    packet.set_sequence(sequence);
+    packet.set_data_offset((TCP_HEADER_LEN / 4) as u8);
+    packet.set_flags(TcpFlags::SYN);
+    packet.set_window(1024);
+    let checksum = ipv4_checksum(&packet.to_immutable(), &source, &target);
+    packet.set_checksum(checksum);
+    packet
+}
+
+/// Collect answers to our SYNs until `done` is set
+fn receive_replies(
+    mut receiver: TransportReceiver,
+    target: Ipv4Addr,
+    source_port: u16,
+    sequence: u32,
+    done: &AtomicBool,
+) -> io::Result<HashMap<u16, &'static str>> {
+    let mut replies = HashMap::new();
+    let mut packets = tcp_packet_iter(&mut receiver);
+
+    while !done.load(Ordering::Relaxed) {
+        let Some((packet, from)) = packets.next_with_timeout(RECEIVE_POLL_INTERVAL)? else {
+            continue;
+        };
+        // The socket sees all TCP traffic to this host, including our own
+        // SYNs on loopback
+        if from != IpAddr::V4(target)
+            || packet.get_destination() != source_port
+            || packet.get_acknowledgement() != sequence.wrapping_add(1)
+        {
+            continue;
+        }
+
+        let flags = packet.get_flags();
+        let status = if flags & (TcpFlags::SYN | TcpFlags::ACK) == TcpFlags::SYN | TcpFlags::ACK {
+            "OPEN"
+        } else if flags & TcpFlags::RST != 0 {
+            "CLOSED"
+        } else {
+            continue;
+        };
+        replies.entry(packet.get_source()).or_insert(status);
+    }
+
+    Ok(replies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pnet_packet::tcp::TcpPacket;
+
+    #[test]
+    fn test_syn_packet_fields() {
+        let source = Ipv4Addr::new(192, 0, 2, 1);
+        let target = Ipv4Addr::new(198, 51, 100, 7);
+        let packet = syn_packet(source, target, 50000, 443, 0xdead_beef);
+        let packet = TcpPacket::new(pnet_packet::Packet::packet(&packet)).unwrap();
+
+        assert_eq!(packet.get_source(), 50000);
+        assert_eq!(packet.get_destination(), 443);
+        assert_eq!(packet.get_sequence(), 0xdead_beef);
+        assert_eq!(packet.get_flags(), TcpFlags::SYN);
+        assert_eq!(
+            packet.get_checksum(),
+            ipv4_checksum(&packet, &source, &target)
+        );
+    }
+
+    #[test]
+    fn test_missing_privileges_are_explained() {
+        let error = open_error(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(error.to_string().contains("CAP_NET_RAW"), "{}", error);
+    }
+
+    #[test]
+    fn test_ipv6_targets_are_rejected() {
+        assert!(SynScanner::open("::1".parse().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use tokio::net::TcpStream;
+use tokio_native_tls::TlsStream;
+use x509_parser::extensions::GeneralName;
+
+/// Ports that speak TLS from the first byte rather than upgrading via STARTTLS
+pub const TLS_PORTS: &[u16] = &[443, 465, 636, 993, 995, 8443];
+
+/// Whether a port is expected to start with a TLS handshake
+pub fn is_tls_port(port: u16) -> bool {
+    TLS_PORTS.contains(&port)
+}
+
+/// Certificate details captured during a TLS handshake
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TlsInfo {
+    /// Certificate subject, e.g. `CN=example.com`
+    pub subject: String,
+    /// Certificate issuer
+    pub issuer: String,
+    /// DNS names and IP addresses from the subjectAltName extension
+    pub subject_alt_names: Vec<String>,
+    /// Start of the validity period
+    pub not_before: DateTime<Utc>,
+    /// End of the validity period
+    pub not_after: DateTime<Utc>,
+    /// Whether the service also completes a handshake over TLS 1.1 or
+    /// older. Only checked on ports that speak TLS from the first byte.
+    #[serde(default)]
+    pub legacy_protocol: bool,
+}
+
+impl TlsInfo {
+    /// Parse the details we care about from a DER-encoded certificate
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let (_, cert) = x509_parser::parse_x509_certificate(der)
+            .map_err(|e| anyhow::anyhow!("Invalid certificate: {}", e))?;
+
+        let mut subject_alt_names = Vec::new();
+        if let Ok(Some(san)) = cert.subject_alternative_name() {
+            for name in &san.value.general_names {
+                match name {
+                    GeneralName::DNSName(dns) => subject_alt_names.push(dns.to_string()),
+                    GeneralName::IPAddress(bytes) => {
+                        if let Some(ip) = ip_from_bytes(bytes) {
+                            subject_alt_names.push(ip.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let validity = cert.validity();
+
+        Ok(Self {
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            subject_alt_names,
+            not_before: timestamp(validity.not_before.timestamp()),
+            not_after: timestamp(validity.not_after.timestamp()),
+            legacy_protocol: false,
+        })
+    }
+
+    /// Domain names covered by the certificate, lowercased and deduplicated.
+    /// Wildcards are reduced to their parent domain.
+    pub fn san_domains(&self) -> Vec<String> {
+        let mut domains: Vec<String> = self
+            .subject_alt_names
+            .iter()
+            .filter(|name| name.parse::<IpAddr>().is_err())
+            .map(|name| {
+                name.trim_start_matches("*.")
+                    .trim_end_matches('.')
+                    .to_lowercase()
+            })
+            .filter(|name| !name.is_empty())
+            .collect();
+        domains.sort();
+        domains.dedup();
+        domains
+    }
+}
+
+/// Complete a TLS handshake with `ip:port`, returning the stream and the
+/// server's certificate details. Certificates are not validated: we want to
+/// see whatever the service presents, including self-signed or expired ones.
+pub async fn connect(ip: IpAddr, port: u16) -> Result<(TlsStream<TcpStream>, Option<TlsInfo>)> {
+    let tcp = TcpStream::connect((ip, port)).await?;
+    handshake(ip, tcp).await
+}
+
+/// Run a TLS handshake over an already connected stream, as after STARTTLS
+pub(crate) async fn handshake(
+    ip: IpAddr,
+    tcp: TcpStream,
+) -> Result<(TlsStream<TcpStream>, Option<TlsInfo>)> {
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()?;
+    let connector = tokio_native_tls::TlsConnector::from(connector);
+
+    let stream = connector.connect(&ip.to_string(), tcp).await?;
+
+    let tls_info = match stream.get_ref().peer_certificate()? {
+        Some(cert) => Some(TlsInfo::from_der(&cert.to_der()?)?),
+        None => None,
+    };
+
+    Ok((stream, tls_info))
+}
+
+/// Whether the service behind `tcp` completes a handshake when offered
+/// nothing newer than TLS 1.1
+pub(crate) async fn accepts_legacy_protocol(ip: IpAddr, tcp: TcpStream) -> bool {
+    let connector = match native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .min_protocol_version(Some(native_tls::Protocol::Tlsv10))
+        .max_protocol_version(Some(native_tls::Protocol::Tlsv11))
+        .build()
+    {
+        Ok(connector) => connector,
+        Err(_) => return false,
+    };
+    tokio_native_tls::TlsConnector::from(connector)
+        .connect(&ip.to_string(), tcp)
+        .await
+        .is_ok()
+}
+
+/// Fetch certificate details from a TLS service without reading a banner
+pub async fn grab_tls_info(ip: IpAddr, port: u16) -> Result<Option<TlsInfo>> {
+    let (_stream, tls_info) = connect(ip, port).await?;
+    Ok(tls_info)
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+fn timestamp(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap_or_default()
+}
@@ -0,0 +1,243 @@
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+/// Transaction/request ID carried by our DNS and SNMP probes, so replies can
+/// be matched to them
+const PROBE_ID: [u8; 2] = [0xea, 0x53];
+
+/// Payload sent to ports we have no protocol-specific probe for
+const GENERIC_PAYLOAD: [u8; 10] = [0; 10];
+
+/// Protocol-specific payloads for common UDP services. A closed or filtered
+/// port looks the same as a service ignoring garbage, so we send something
+/// the service will actually answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UdpProbe {
+    /// DNS query for the root NS records
+    Dns,
+    /// NTPv3 client request
+    Ntp,
+    /// SNMPv2c GetRequest for sysDescr with the `public` community
+    Snmp,
+    /// No protocol-specific probe; any reply counts
+    Generic,
+}
+
+impl UdpProbe {
+    /// Probe for the service usually found on `port`
+    pub(crate) fn for_port(port: u16) -> Self {
+        match port {
+            53 => UdpProbe::Dns,
+            123 => UdpProbe::Ntp,
+            161 => UdpProbe::Snmp,
+            _ => UdpProbe::Generic,
+        }
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        match self {
+            UdpProbe::Dns => {
+                let mut query = PROBE_ID.to_vec();
+                query.extend_from_slice(&[
+                    0x01, 0x00, // flags: recursion desired
+                    0x00, 0x01, // one question
+                    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // no answer/authority/additional
+                    0x00, // root name
+                    0x00, 0x02, // type NS
+                    0x00, 0x01, // class IN
+                ]);
+                query
+            }
+            UdpProbe::Ntp => {
+                // LI = 0, version 3, mode 3 (client), rest zeroed
+                let mut request = vec![0; 48];
+                request[0] = 0x1b;
+                request
+            }
+            UdpProbe::Snmp => {
+                let mut request = vec![
+                    0x30, 0x29, // message
+                    0x02, 0x01, 0x01, // version: v2c
+                    0x04, 0x06, b'p', b'u', b'b', b'l', b'i', b'c', // community
+                    0xa0, 0x1c, // GetRequest PDU
+                    0x02, 0x04, 0x00, 0x00, // request ID, completed below
+                ];
+                request.extend_from_slice(&PROBE_ID);
+                request.extend_from_slice(&[
+                    0x02, 0x01, 0x00, // error status
+                    0x02, 0x01, 0x00, // error index
+                    0x30, 0x0e, 0x30, 0x0c, // varbind list with one binding
+                    0x06, 0x08, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00, // sysDescr.0
+                    0x05, 0x00, // null value
+                ]);
+                request
+            }
+            UdpProbe::Generic => GENERIC_PAYLOAD.to_vec(),
+        }
+    }
+
+    /// Whether `response` is a valid reply to this probe
+    fn matches(&self, response: &[u8]) -> bool {
+        match self {
+            // Same transaction ID with the QR (response) bit set
+            UdpProbe::Dns => {
+                response.len() >= 12 && response[..2] == PROBE_ID && response[2] & 0x80 != 0
+            }
+            // Mode 4 (server)
+            UdpProbe::Ntp => response.len() >= 48 && response[0] & 0x07 == 4,
+            // A GetResponse PDU echoing our request ID
+            UdpProbe::Snmp => {
+                response.first() == Some(&0x30)
+                    && response.contains(&0xa2)
+                    && response.windows(4).any(|w| w == [0x00, 0x00, 0xea, 0x53])
+            }
+            UdpProbe::Generic => true,
+        }
+    }
+}
+
+/// What probing a UDP port told us
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UdpProbeResult {
+    /// The service answered in the protocol we probed for
+    Open,
+    /// Something answered, but not in the protocol we probed for
+    OpenUnrecognized,
+    /// The host answered with ICMP port unreachable
+    Closed,
+    /// No answer: the port may be open and ignoring us, or filtered
+    OpenFiltered,
+    /// The probe could not be sent or the reply could not be read
+    Error,
+}
+
+impl UdpProbeResult {
+    /// Status string recorded on the `DiscoveredPort`
+    pub(crate) fn status(&self) -> &'static str {
+        match self {
+            UdpProbeResult::Open | UdpProbeResult::OpenUnrecognized => "OPEN",
+            UdpProbeResult::Closed => "CLOSED",
+            UdpProbeResult::OpenFiltered => "OPEN|FILTERED",
+            UdpProbeResult::Error => "ERROR",
+        }
+    }
+}
+
+/// Send `probe` to `addr` and classify the reply, waiting up to `wait`
+pub(crate) async fn probe(addr: SocketAddr, probe: UdpProbe, wait: Duration) -> UdpProbeResult {
+    let bind_addr = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = match UdpSocket::bind(bind_addr).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!("Failed to bind UDP socket: {}", e);
+            return UdpProbeResult::Error;
+        }
+    };
+
+    // A connected socket surfaces ICMP port unreachable as ECONNREFUSED
+    if socket.connect(addr).await.is_err() || socket.send(&probe.payload()).await.is_err() {
+        return UdpProbeResult::Error;
+    }
+
+    let mut buf = [0; 65535];
+    match timeout(wait, socket.recv(&mut buf)).await {
+        Ok(Ok(n)) if probe.matches(&buf[..n]) => UdpProbeResult::Open,
+        Ok(Ok(_)) => UdpProbeResult::OpenUnrecognized,
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => UdpProbeResult::Closed,
+        Ok(Err(_)) => UdpProbeResult::Error,
+        Err(_) => UdpProbeResult::OpenFiltered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAIT: Duration = Duration::from_millis(500);
+
+    /// Bind a local UDP responder that answers each datagram with `reply`
+    async fn start_responder<F>(reply: F) -> SocketAddr
+    where
+        F: Fn(&[u8]) -> Option<Vec<u8>> + Send + 'static,
+    {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0; 2048];
+            while let Ok((n, peer)) = socket.recv_from(&mut buf).await {
+                if let Some(response) = reply(&buf[..n]) {
+                    let _ = socket.send_to(&response, peer).await;
+                }
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_dns_response_is_open() {
+        // Answer with the query's ID and the QR bit set
+        let addr = start_responder(|query| {
+            let mut response = query.to_vec();
+            response[2] |= 0x80;
+            Some(response)
+        })
+        .await;
+
+        assert_eq!(probe(addr, UdpProbe::Dns, WAIT).await, UdpProbeResult::Open);
+    }
+
+    #[tokio::test]
+    async fn test_echo_is_not_a_valid_dns_response() {
+        let addr = start_responder(|datagram| Some(datagram.to_vec())).await;
+
+        let result = probe(addr, UdpProbe::Dns, WAIT).await;
+        assert_eq!(result, UdpProbeResult::OpenUnrecognized);
+        assert_eq!(result.status(), "OPEN");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_port_is_closed() {
+        // Grab a free port, then release it so nothing is listening
+        let addr = UdpSocket::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        assert_eq!(
+            probe(addr, UdpProbe::Generic, WAIT).await,
+            UdpProbeResult::Closed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_silent_port_is_open_filtered() {
+        let addr = start_responder(|_| None).await;
+
+        assert_eq!(
+            probe(addr, UdpProbe::Ntp, WAIT).await,
+            UdpProbeResult::OpenFiltered
+        );
+    }
+
+    #[test]
+    fn test_probes_match_their_protocol() {
+        let mut ntp = vec![0; 48];
+        ntp[0] = 0x1c; // version 3, mode 4 (server)
+        assert!(UdpProbe::Ntp.matches(&ntp));
+        assert!(!UdpProbe::Ntp.matches(&UdpProbe::Ntp.payload()));
+
+        // The request itself is not a response
+        assert!(!UdpProbe::Snmp.matches(&UdpProbe::Snmp.payload()));
+        let mut snmp = UdpProbe::Snmp.payload();
+        snmp[13] = 0xa2;
+        assert!(UdpProbe::Snmp.matches(&snmp));
+    }
+}
@@ -1,10 +1,16 @@
+use crate::asn::{AsnDatabase, AsnInfo};
 use crate::port_scan::DiscoveredPort;
 use crate::vulnerability::DiscoveredVulnerability;
+use crate::whois::WhoisInfo;
 use serde::{Deserialize, Serialize};
 use shared::types::ID;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::net::IpAddr;
 
+/// Separates the sources of an entry found by more than one module
+const SOURCE_SEPARATOR: char = ',';
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct DiscoveredIp {
     pub ip_address: IpAddr,
@@ -24,6 +30,13 @@ pub struct DiscoveredWebResource {
     pub title: Option<String>,
     pub technologies: Vec<String>, // e.g., ["React", "Nginx"]
     pub source: String,
+    /// Where a screenshot of the page was stored, if one was captured
+    #[serde(default)]
+    pub screenshot_path: Option<String>,
+    /// Hash of the normalized page body, for detecting content changes
+    /// between scans
+    #[serde(default)]
+    pub content_hash: Option<String>,
 }
 
 /// Technology finding that can be added to an asset
@@ -73,12 +86,19 @@ pub struct DiscoveryResult {
     pub ports: Vec<DiscoveredPort>,
     /// Discovered web resources
     pub web_resources: Vec<DiscoveredWebResource>,
+    /// Links a crawl found outside its scope, which it didn't fetch
+    #[serde(default)]
+    pub out_of_scope_urls: Vec<String>,
     /// Discovered technologies
     pub technologies: Vec<TechnologyFinding>,
     /// Discovered vulnerabilities (for database storage)
     pub vulnerabilities: Vec<VulnerabilityFinding>,
     /// Raw vulnerability findings from scanners like Nuclei
     pub raw_vulnerabilities: Vec<DiscoveredVulnerability>,
+    /// Registration details keyed by lowercased domain name
+    pub whois: HashMap<String, WhoisInfo>,
+    /// Network ownership of discovered IPs
+    pub asn: HashMap<IpAddr, AsnInfo>,
     /// Additional metadata from the discovery process
     pub metadata: HashMap<String, String>,
 }
@@ -91,25 +111,123 @@ impl DiscoveryResult {
             domains: Vec::new(),
             ports: Vec::new(),
             web_resources: Vec::new(),
+            out_of_scope_urls: Vec::new(),
             technologies: Vec::new(),
             vulnerabilities: Vec::new(),
             raw_vulnerabilities: Vec::new(),
+            whois: HashMap::new(),
+            asn: HashMap::new(),
             metadata: HashMap::new(),
         }
     }
 
+    /// Serialize the whole result as a single JSON document
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Parse a result previously written by [`DiscoveryResult::to_json`]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
     /// Merge another discovery result into this one
+    ///
+    /// Domains, IPs, ports and web resources are kept once per natural key:
+    /// the normalized domain name, the IP, IP, port and protocol, and the
+    /// URL. When both results have an entry, their sources are combined into
+    /// a comma-separated list and details only the other result has, such as
+    /// a banner or a page title, are filled in. A port open in either result
+    /// is open. Out-of-scope URLs are kept once each. Everything else is
+    /// appended.
     pub fn merge(&mut self, other: DiscoveryResult) {
-        self.ip_addresses.extend(other.ip_addresses);
-        self.domains.extend(other.domains);
-        self.ports.extend(other.ports);
-        self.web_resources.extend(other.web_resources);
+        merge_by_key(
+            &mut self.ip_addresses,
+            other.ip_addresses,
+            |ip| ip.ip_address,
+            |ip, other| combine_sources(&mut ip.source, &other.source),
+        );
+        merge_by_key(
+            &mut self.domains,
+            other.domains,
+            |domain| whois_key(&domain.domain_name),
+            |domain, other| combine_sources(&mut domain.source, &other.source),
+        );
+        merge_by_key(
+            &mut self.ports,
+            other.ports,
+            |port| (port.ip_address, port.port, port.protocol.to_uppercase()),
+            merge_port,
+        );
+        merge_by_key(
+            &mut self.web_resources,
+            other.web_resources,
+            |resource| resource.url.trim().to_string(),
+            merge_web_resource,
+        );
+        for url in other.out_of_scope_urls {
+            if !self.out_of_scope_urls.contains(&url) {
+                self.out_of_scope_urls.push(url);
+            }
+        }
         self.technologies.extend(other.technologies);
         self.vulnerabilities.extend(other.vulnerabilities);
         self.raw_vulnerabilities.extend(other.raw_vulnerabilities);
+        self.whois.extend(other.whois);
+        self.asn.extend(other.asn);
         self.metadata.extend(other.metadata);
     }
 
+    /// Record registration details for a domain
+    pub fn add_whois(&mut self, domain: &str, info: WhoisInfo) {
+        self.whois.insert(whois_key(domain), info);
+    }
+
+    /// Registration details looked up for a domain, if any
+    pub fn whois_for(&self, domain: &str) -> Option<&WhoisInfo> {
+        self.whois.get(&whois_key(domain))
+    }
+
+    /// Attributes for the asset created from a discovered domain: its source
+    /// and, when looked up, its registration details under `whois_info`
+    pub fn domain_attributes(&self, domain: &DiscoveredDomain) -> serde_json::Value {
+        let mut attributes = serde_json::json!({ "source": domain.source });
+        if let Some(info) = self.whois_for(&domain.domain_name) {
+            attributes["whois_info"] = serde_json::json!(info);
+        }
+        attributes
+    }
+
+    /// Look up the network owner of every discovered IP, including port
+    /// scan targets, that isn't enriched yet
+    pub fn enrich_with_asn(&mut self, database: &AsnDatabase) {
+        let ips: Vec<IpAddr> = self
+            .ip_addresses
+            .iter()
+            .map(|ip| ip.ip_address)
+            .chain(self.ports.iter().map(|port| port.ip_address))
+            .collect();
+
+        for ip in ips {
+            if self.asn.contains_key(&ip) {
+                continue;
+            }
+            if let Some(info) = database.lookup(ip) {
+                self.asn.insert(ip, info);
+            }
+        }
+    }
+
+    /// Attributes for the asset created from a discovered IP: its source
+    /// and, when known, its network owner under `asn_info`
+    pub fn ip_attributes(&self, ip: IpAddr, source: &str) -> serde_json::Value {
+        let mut attributes = serde_json::json!({ "source": source });
+        if let Some(info) = self.asn.get(&ip) {
+            attributes["asn_info"] = serde_json::json!(info);
+        }
+        attributes
+    }
+
     /// Convert raw vulnerabilities to VulnerabilityFindings
     pub fn convert_raw_vulnerabilities(&mut self, asset_id: ID) {
         for raw_vuln in &self.raw_vulnerabilities {
@@ -129,3 +247,68 @@ impl DiscoveryResult {
         }
     }
 }
+
+fn whois_key(domain: &str) -> String {
+    domain.trim().trim_end_matches('.').to_lowercase()
+}
+
+/// Append each of `incoming` to `items` unless an item with the same key is
+/// already there, in which case `combine` folds it into that item
+fn merge_by_key<T, K: Eq + Hash>(
+    items: &mut Vec<T>,
+    incoming: Vec<T>,
+    key: impl Fn(&T) -> K,
+    combine: impl Fn(&mut T, T),
+) {
+    let mut index: HashMap<K, usize> = HashMap::new();
+    for (position, item) in items.iter().enumerate() {
+        index.entry(key(item)).or_insert(position);
+    }
+
+    for item in incoming {
+        match index.get(&key(&item)) {
+            Some(&position) => combine(&mut items[position], item),
+            None => {
+                index.insert(key(&item), items.len());
+                items.push(item);
+            }
+        }
+    }
+}
+
+/// Add the sources in `other` that `source` doesn't list yet
+fn combine_sources(source: &mut String, other: &str) {
+    for name in other.split(SOURCE_SEPARATOR) {
+        if name.is_empty() || source.split(SOURCE_SEPARATOR).any(|known| known == name) {
+            continue;
+        }
+        if !source.is_empty() {
+            source.push(SOURCE_SEPARATOR);
+        }
+        source.push_str(name);
+    }
+}
+
+fn merge_port(port: &mut DiscoveredPort, other: DiscoveredPort) {
+    combine_sources(&mut port.source, &other.source);
+    if other.status == "OPEN" {
+        port.status = other.status;
+    }
+    port.service_name = port.service_name.take().or(other.service_name);
+    port.banner = port.banner.take().or(other.banner);
+    port.http_status = port.http_status.or(other.http_status);
+    port.http_title = port.http_title.take().or(other.http_title);
+    port.tls_info = port.tls_info.take().or(other.tls_info);
+}
+
+fn merge_web_resource(resource: &mut DiscoveredWebResource, other: DiscoveredWebResource) {
+    combine_sources(&mut resource.source, &other.source);
+    for technology in other.technologies {
+        if !resource.technologies.contains(&technology) {
+            resource.technologies.push(technology);
+        }
+    }
+    resource.title = resource.title.take().or(other.title);
+    resource.screenshot_path = resource.screenshot_path.take().or(other.screenshot_path);
+    resource.content_hash = resource.content_hash.take().or(other.content_hash);
+}
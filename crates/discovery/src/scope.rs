@@ -0,0 +1,305 @@
+//! Which targets may be scanned at all
+//!
+//! Every scan is checked against a global blocklist before it starts: cloud
+//! metadata endpoints are always refused, internal addresses (private,
+//! loopback, link-local and the like) unless opted into, and any ranges
+//! configured as off limits. An organization can
+//! further restrict its scans to an allowlist of domains, IPs and CIDR
+//! ranges, so a typo in a target doesn't end with a third party scanned.
+
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use thiserror::Error;
+use url::Url;
+
+use crate::web_crawl::scope::normalize_domain;
+
+/// Cloud instance metadata services, never scanned whatever the settings
+pub const CLOUD_METADATA_RANGES: [&str; 2] = ["169.254.169.254/32", "fd00:ec2::254/128"];
+
+/// Addresses of this machine and networks not reachable from the internet,
+/// refused unless scanning private networks is opted into. These are what
+/// [`is_internal_address`] matches.
+pub const INTERNAL_RANGES: [&str; 11] = [
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "255.255.255.255/32",
+    "::/128",
+    "::1/128",
+    "fc00::/7",
+    "fe80::/10",
+];
+
+static INTERNAL: LazyLock<Vec<IpRange>> = LazyLock::new(|| {
+    INTERNAL_RANGES
+        .iter()
+        .map(|range| range.parse().expect("internal ranges are valid"))
+        .collect()
+});
+
+/// Returned for a target the scope doesn't allow scanning
+#[derive(Debug, Error)]
+#[error("Target {target} is out of scope: {reason}")]
+pub struct OutOfScope {
+    pub target: String,
+    pub reason: String,
+}
+
+/// An IP address or CIDR range, e.g. `203.0.113.7` or `203.0.113.0/24`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl IpRange {
+    /// Whether `ip` falls within the range
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        ip.is_ipv4() == self.network.is_ipv4()
+            && mask(to_bits(ip), self.host_bits()) == to_bits(self.network)
+    }
+
+    /// Whether every address of `other` falls within the range
+    pub fn covers(&self, other: &IpRange) -> bool {
+        self.prefix <= other.prefix && self.contains(other.network)
+    }
+
+    /// Whether the ranges share any address
+    pub fn overlaps(&self, other: &IpRange) -> bool {
+        self.covers(other) || other.covers(self)
+    }
+
+    fn host_bits(&self) -> u32 {
+        address_bits(self.network) - self.prefix
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Invalid IP address or CIDR range {}", s);
+        let (address, prefix) = match s.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s.trim(), None),
+        };
+        let address: IpAddr = address.trim().parse().map_err(|_| invalid())?;
+        let bits = address_bits(address);
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse().map_err(|_| invalid())?,
+            None => bits,
+        };
+        if prefix > bits {
+            return Err(invalid());
+        }
+
+        // Host bits are dropped, so 10.1.2.3/8 is 10.0.0.0/8
+        let network = match address {
+            IpAddr::V4(_) => IpAddr::V4((mask(to_bits(address), bits - prefix) as u32).into()),
+            IpAddr::V6(_) => IpAddr::V6(mask(to_bits(address), bits - prefix).into()),
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+impl std::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// An allowlist entry: a domain, matching its subdomains too, or IPs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScopeEntry {
+    Domain(String),
+    Range(IpRange),
+}
+
+impl FromStr for ScopeEntry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(range) = s.parse() {
+            return Ok(ScopeEntry::Range(range));
+        }
+
+        let domain = normalize_domain(s.trim().trim_start_matches("*."));
+        let valid = !domain.is_empty()
+            && domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_');
+        if !valid {
+            return Err(anyhow::anyhow!(
+                "Invalid scope entry {}, expected a domain, IP address or CIDR range",
+                s
+            ));
+        }
+        Ok(ScopeEntry::Domain(domain))
+    }
+}
+
+/// Blocklist applied to every scan, optionally narrowed by an organization's
+/// allowlist
+#[derive(Debug, Clone)]
+pub struct ScanScope {
+    /// Refused ranges, each with why
+    blocked: Vec<(IpRange, &'static str)>,
+    /// When not empty, only targets matching one of these are scanned
+    allowed: Vec<ScopeEntry>,
+}
+
+impl ScanScope {
+    /// Scope refusing cloud metadata endpoints, `no_scan` ranges and, unless
+    /// `scan_private_networks` is set, internal addresses
+    pub fn new(no_scan: &[String], scan_private_networks: bool) -> Result<Self> {
+        let mut blocked = Vec::new();
+        for range in CLOUD_METADATA_RANGES {
+            blocked.push((range.parse()?, "cloud metadata endpoint"));
+        }
+        if !scan_private_networks {
+            for range in INTERNAL.iter() {
+                blocked.push((*range, "private network"));
+            }
+        }
+        for range in no_scan {
+            blocked.push((range.parse()?, "configured no-scan range"));
+        }
+
+        Ok(Self {
+            blocked,
+            allowed: Vec::new(),
+        })
+    }
+
+    /// Only allow targets matching one of `entries`, e.g. an organization's
+    /// domains and networks. No entries leaves every target allowed.
+    pub fn with_allowlist(mut self, entries: &[String]) -> Result<Self> {
+        self.allowed = entries
+            .iter()
+            .map(|entry| entry.parse())
+            .collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Check a job target: a domain, IP address, CIDR range or URL
+    pub fn check(&self, target: &str) -> Result<(), OutOfScope> {
+        let out_of_scope = |reason: String| OutOfScope {
+            target: target.to_string(),
+            reason,
+        };
+
+        // An IPv4-mapped IPv6 address is checked as the IPv4 address it is
+        let host = target_host(target);
+        let host = match host.parse::<IpAddr>() {
+            Ok(ip) => ip.to_canonical().to_string(),
+            Err(_) => host,
+        };
+        match host.parse::<IpRange>() {
+            Ok(range) => {
+                if let Some((blocked, reason)) = self
+                    .blocked
+                    .iter()
+                    .find(|(blocked, _)| blocked.overlaps(&range))
+                {
+                    return Err(out_of_scope(format!("{} ({})", reason, blocked)));
+                }
+                let allowed = self.allowed.is_empty()
+                    || self.allowed.iter().any(|entry| match entry {
+                        ScopeEntry::Range(allowed) => allowed.covers(&range),
+                        ScopeEntry::Domain(_) => false,
+                    });
+                if !allowed {
+                    return Err(out_of_scope(
+                        "not within the organization's allowed ranges".to_string(),
+                    ));
+                }
+            }
+            Err(_) => {
+                let domain = normalize_domain(&host);
+                let allowed = self.allowed.is_empty()
+                    || self.allowed.iter().any(|entry| match entry {
+                        ScopeEntry::Domain(allowed) => {
+                            domain == *allowed || domain.ends_with(&format!(".{}", allowed))
+                        }
+                        ScopeEntry::Range(_) => false,
+                    });
+                if !allowed {
+                    return Err(out_of_scope(
+                        "not within the organization's allowed domains".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check an address a domain target resolved to against the blocklist.
+    /// The allowlist was already applied to the domain.
+    pub fn check_address(&self, ip: IpAddr) -> Result<(), OutOfScope> {
+        let ip = ip.to_canonical();
+        match self
+            .blocked
+            .iter()
+            .find(|(blocked, _)| blocked.contains(ip))
+        {
+            Some((blocked, reason)) => Err(OutOfScope {
+                target: ip.to_string(),
+                reason: format!("{} ({})", reason, blocked),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether `ip` belongs to this machine or a network not reachable from the
+/// internet: loopback, private, link-local (cloud metadata included),
+/// unspecified and broadcast addresses, as listed in [`INTERNAL_RANGES`]
+pub fn is_internal_address(ip: IpAddr) -> bool {
+    let ip = ip.to_canonical();
+    INTERNAL.iter().any(|range| range.contains(ip))
+}
+
+/// Host part of a target given as a URL, or the target itself
+fn target_host(target: &str) -> String {
+    let target = target.trim();
+    if target.contains("://") {
+        if let Some(host) = Url::parse(target)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        {
+            return host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string();
+        }
+    }
+    target.to_string()
+}
+
+fn address_bits(ip: IpAddr) -> u32 {
+    if ip.is_ipv4() {
+        32
+    } else {
+        128
+    }
+}
+
+fn to_bits(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => u128::from(u32::from(ip)),
+        IpAddr::V6(ip) => u128::from(ip),
+    }
+}
+
+/// `bits` with its lowest `host_bits` cleared
+fn mask(bits: u128, host_bits: u32) -> u128 {
+    bits & !(u128::MAX.checked_shr(128 - host_bits).unwrap_or(0))
+}
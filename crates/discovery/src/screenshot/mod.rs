@@ -0,0 +1,194 @@
+//! Screenshots of discovered web resources for triage
+//!
+//! Pages are rendered by a local Chrome/Chromium driven over the DevTools
+//! protocol, which is only compiled in with the `screenshots` feature.
+//! Capturing is best effort: when the feature is off or no browser can be
+//! launched the step is skipped with a warning, and resources that fail to
+//! render are left without a screenshot.
+
+use crate::results::DiscoveryResult;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Captures PNG screenshots of web resources into a directory
+pub struct ScreenshotCapturer {
+    output_dir: PathBuf,
+    browser_path: Option<PathBuf>,
+    window_size: (u32, u32),
+    page_timeout: Duration,
+}
+
+impl ScreenshotCapturer {
+    /// Create a capturer storing screenshots in `output_dir`, which may be a
+    /// local directory or a mounted object storage bucket
+    pub fn new<P: Into<PathBuf>>(output_dir: P) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            browser_path: None,
+            window_size: (1280, 800),
+            page_timeout: Duration::from_secs(20),
+        }
+    }
+
+    /// Use a specific Chrome/Chromium binary instead of searching for one
+    pub fn with_browser_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.browser_path = Some(path.into());
+        self
+    }
+
+    /// Viewport size pages are rendered at
+    pub fn with_window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = (width, height);
+        self
+    }
+
+    /// How long to wait for a page to load before giving up on it
+    pub fn with_page_timeout(mut self, timeout: Duration) -> Self {
+        self.page_timeout = timeout;
+        self
+    }
+
+    /// Capture each web resource in `result` that has no screenshot yet,
+    /// recording the file it was stored in on the resource. Returns the
+    /// number of screenshots captured.
+    pub async fn capture_all(&self, result: &mut DiscoveryResult) -> usize {
+        let pending: Vec<(usize, String)> = result
+            .web_resources
+            .iter()
+            .enumerate()
+            .filter(|(_, resource)| resource.screenshot_path.is_none())
+            .map(|(index, resource)| (index, resource.url.clone()))
+            .collect();
+
+        if pending.is_empty() {
+            return 0;
+        }
+
+        let captured = match self.capture_urls(pending).await {
+            Ok(captured) => captured,
+            Err(e) => {
+                tracing::warn!("Skipping web resource screenshots: {:#}", e);
+                return 0;
+            }
+        };
+
+        let count = captured.len();
+        for (index, path) in captured {
+            result.web_resources[index].screenshot_path = Some(path);
+        }
+        tracing::info!("Captured {} web resource screenshots", count);
+        count
+    }
+
+    #[cfg(feature = "screenshots")]
+    async fn capture_urls(&self, urls: Vec<(usize, String)>) -> Result<Vec<(usize, String)>> {
+        let output_dir = self.output_dir.clone();
+        let browser_path = self.browser_path.clone();
+        let window_size = self.window_size;
+        let page_timeout = self.page_timeout;
+
+        // The DevTools client blocks, so keep it off the async runtime
+        tokio::task::spawn_blocking(move || {
+            chrome::capture(&output_dir, browser_path, window_size, page_timeout, urls)
+        })
+        .await?
+    }
+
+    #[cfg(not(feature = "screenshots"))]
+    async fn capture_urls(&self, _urls: Vec<(usize, String)>) -> Result<Vec<(usize, String)>> {
+        Err(anyhow::anyhow!(
+            "discovery was built without the `screenshots` feature, nothing stored in {}",
+            self.output_dir.display()
+        ))
+    }
+}
+
+/// File a screenshot of `url` is stored under in `output_dir`. Distinct URLs
+/// map to distinct files, and the name stays readable in a directory listing.
+pub fn screenshot_path(output_dir: &Path, url: &str) -> PathBuf {
+    let readable: String = url
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(100)
+        .collect();
+
+    output_dir.join(format!("{}_{:016x}.png", readable, fnv1a(url.trim())))
+}
+
+/// Stable hash of a URL, so screenshots of the same URL overwrite each other
+/// across runs
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(feature = "screenshots")]
+mod chrome {
+    use super::screenshot_path;
+    use anyhow::{Context, Result};
+    use headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption;
+    use headless_chrome::{Browser, LaunchOptions};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    pub(super) fn capture(
+        output_dir: &Path,
+        browser_path: Option<PathBuf>,
+        window_size: (u32, u32),
+        page_timeout: Duration,
+        urls: Vec<(usize, String)>,
+    ) -> Result<Vec<(usize, String)>> {
+        let options = LaunchOptions::default_builder()
+            .path(browser_path)
+            .window_size(Some(window_size))
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid browser options: {}", e))?;
+        let browser = Browser::new(options).context("Failed to launch Chrome/Chromium")?;
+
+        std::fs::create_dir_all(output_dir).with_context(|| {
+            format!(
+                "Failed to create screenshot directory {}",
+                output_dir.display()
+            )
+        })?;
+
+        let tab = browser.new_tab()?;
+        tab.set_default_timeout(page_timeout);
+
+        let mut captured = Vec::new();
+        for (index, url) in urls {
+            let png = tab
+                .navigate_to(&url)
+                .and_then(|tab| tab.wait_until_navigated())
+                .and_then(|tab| {
+                    tab.capture_screenshot(CaptureScreenshotFormatOption::Png, None, None, true)
+                });
+
+            let png = match png {
+                Ok(png) => png,
+                Err(e) => {
+                    tracing::warn!("Failed to capture screenshot of {}: {}", url, e);
+                    continue;
+                }
+            };
+
+            let path = screenshot_path(output_dir, &url);
+            match std::fs::write(&path, png) {
+                Ok(()) => captured.push((index, path.display().to_string())),
+                Err(e) => tracing::warn!(
+                    "Failed to store screenshot of {} in {}: {}",
+                    url,
+                    path.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(captured)
+    }
+}
@@ -0,0 +1,275 @@
+//! Passive enrichment from Shodan
+//!
+//! Shodan crawls the internet continuously, so it knows about services on a
+//! host without us probing them: open ports, their banners and the CVEs
+//! Shodan associates with the detected software. Lookups need an API key;
+//! without one, [`ShodanClient::from_api_key`] returns no client and
+//! enrichment is skipped.
+
+use crate::http_client::HttpClientConfig;
+use crate::port_scan::DiscoveredPort;
+use crate::results::{DiscoveredDomain, DiscoveredIp, DiscoveryResult};
+use crate::vulnerability::DiscoveredVulnerability;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Shodan's REST API
+pub const DEFAULT_SHODAN_BASE_URL: &str = "https://api.shodan.io";
+
+/// Source recorded on everything learned from Shodan
+pub const SHODAN_SOURCE: &str = "shodan";
+
+/// The parts of a `/shodan/host/{ip}` response we use
+#[derive(Debug, Deserialize)]
+struct ShodanHost {
+    ip_str: IpAddr,
+    #[serde(default)]
+    hostnames: Vec<String>,
+    #[serde(default)]
+    data: Vec<ShodanService>,
+}
+
+/// One banner Shodan collected from the host
+#[derive(Debug, Deserialize)]
+struct ShodanService {
+    port: u16,
+    #[serde(default)]
+    transport: Option<String>,
+    #[serde(default)]
+    product: Option<String>,
+    #[serde(default)]
+    data: Option<String>,
+    #[serde(default, rename = "_shodan")]
+    shodan: Option<ShodanModule>,
+    #[serde(default)]
+    http: Option<ShodanHttp>,
+    #[serde(default)]
+    vulns: HashMap<String, ShodanVuln>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShodanModule {
+    module: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShodanHttp {
+    status: Option<u16>,
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShodanVuln {
+    cvss: Option<f32>,
+    summary: Option<String>,
+    #[serde(default)]
+    references: Vec<String>,
+    #[serde(default)]
+    verified: bool,
+}
+
+/// Parse a Shodan host response into a discovery result with the host's
+/// IP, host names, open ports and the vulnerabilities reported on them
+pub fn parse_host_response(body: &str) -> Result<DiscoveryResult> {
+    let host: ShodanHost = serde_json::from_str(body)?;
+    let ip = host.ip_str;
+    let mut result = DiscoveryResult::new();
+
+    result.ip_addresses.push(DiscoveredIp {
+        ip_address: ip,
+        source: SHODAN_SOURCE.to_string(),
+    });
+    for hostname in &host.hostnames {
+        result.domains.push(DiscoveredDomain {
+            domain_name: hostname.trim_end_matches('.').to_lowercase(),
+            source: SHODAN_SOURCE.to_string(),
+        });
+    }
+
+    let mut reported = HashSet::new();
+    for service in host.data {
+        let banner = service
+            .data
+            .as_deref()
+            .map(|data| data.trim())
+            .filter(|data| !data.is_empty())
+            .map(crate::port_scan::clean_banner);
+        let service_name = service.shodan.and_then(|s| s.module).or(service.product);
+        let (http_status, http_title) = service
+            .http
+            .map_or((None, None), |http| (http.status, http.title));
+
+        result.ports.push(DiscoveredPort {
+            ip_address: ip,
+            port: service.port,
+            protocol: service.transport.as_deref().unwrap_or("tcp").to_uppercase(),
+            // Shodan only keeps banners of services that answered
+            status: "OPEN".to_string(),
+            service_name,
+            banner,
+            http_status,
+            http_title,
+            tls_info: None,
+            source: SHODAN_SOURCE.to_string(),
+        });
+
+        let mut vulns: Vec<_> = service.vulns.into_iter().collect();
+        vulns.sort_by(|a, b| a.0.cmp(&b.0));
+        for (cve, vuln) in vulns {
+            if !reported.insert(cve.clone()) {
+                continue;
+            }
+            let mut tags = vec![SHODAN_SOURCE.to_string()];
+            if !vuln.verified {
+                tags.push("unverified".to_string());
+            }
+            result.raw_vulnerabilities.push(DiscoveredVulnerability {
+                target: ip.to_string(),
+                name: cve.clone(),
+                severity: severity_for_cvss(vuln.cvss).to_string(),
+                description: vuln.summary,
+                template_id: cve.clone(),
+                tags,
+                references: vuln.references,
+                cve_id: Some(cve),
+                cvss_score: vuln.cvss,
+                matched_at: format!("{}:{}", ip, service.port),
+                detected_at: Utc::now(),
+                source: SHODAN_SOURCE.to_string(),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Severity label for a CVSS base score, per the CVSS v3 rating scale
+fn severity_for_cvss(score: Option<f32>) -> &'static str {
+    match score {
+        Some(score) if score >= 9.0 => "critical",
+        Some(score) if score >= 7.0 => "high",
+        Some(score) if score >= 4.0 => "medium",
+        Some(score) if score > 0.0 => "low",
+        _ => "info",
+    }
+}
+
+/// Shodan API client for host lookups
+pub struct ShodanClient {
+    client: Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl ShodanClient {
+    /// Create a client for the configured API key, or `None` when no key is
+    /// configured so callers can skip enrichment
+    pub fn from_api_key(api_key: Option<&str>, http: &HttpClientConfig) -> Result<Option<Self>> {
+        match api_key.map(str::trim).filter(|key| !key.is_empty()) {
+            Some(key) => Self::with_config(key, DEFAULT_SHODAN_BASE_URL, http).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a client using Shodan's API
+    pub fn new(api_key: &str) -> Result<Self> {
+        Self::with_base_url(api_key, DEFAULT_SHODAN_BASE_URL)
+    }
+
+    /// Create a client querying a specific Shodan-compatible server
+    pub fn with_base_url(api_key: &str, base_url: &str) -> Result<Self> {
+        Self::with_config(api_key, base_url, &HttpClientConfig::default())
+    }
+
+    /// Create a client querying a specific Shodan-compatible server with
+    /// custom HTTP settings, e.g. a proxy
+    pub fn with_config(api_key: &str, base_url: &str, http: &HttpClientConfig) -> Result<Self> {
+        let client = http
+            .client_builder()?
+            .timeout(Duration::from_secs(15))
+            .build()?;
+
+        Ok(Self {
+            client,
+            api_key: api_key.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Look up what Shodan knows about an IP. A host Shodan has no data on
+    /// gives an empty result rather than an error.
+    pub async fn host(&self, ip: IpAddr) -> Result<DiscoveryResult> {
+        tracing::debug!("Looking up Shodan host data for: {}", ip);
+
+        let url = format!("{}/shodan/host/{}", self.base_url, ip);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("key", &self.api_key)])
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+
+        match response.status() {
+            status if status.is_success() => {
+                parse_host_response(&response.text().await.map_err(|e| e.without_url())?)
+            }
+            StatusCode::NOT_FOUND => Ok(DiscoveryResult::new()),
+            status => Err(anyhow!(
+                "Shodan lookup for {} failed with status: {}",
+                ip,
+                status
+            )),
+        }
+    }
+
+    /// Resolve a domain through Shodan's DNS, returning `None` if it has no
+    /// address
+    pub async fn resolve(&self, domain: &str) -> Result<Option<IpAddr>> {
+        let domain = domain.trim().trim_end_matches('.').to_lowercase();
+        let url = format!("{}/dns/resolve", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("hostnames", domain.as_str()), ("key", &self.api_key)])
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Shodan DNS lookup for {} failed with status: {}",
+                domain,
+                response.status()
+            ));
+        }
+
+        let mut addresses: HashMap<String, Option<IpAddr>> =
+            response.json().await.map_err(|e| e.without_url())?;
+        Ok(addresses.remove(&domain).flatten())
+    }
+
+    /// Look up an IP, or the address a domain resolves to
+    pub async fn lookup(&self, target: &str) -> Result<DiscoveryResult> {
+        let ip = match target.trim().parse::<IpAddr>() {
+            Ok(ip) => ip,
+            Err(_) => match self.resolve(target).await? {
+                Some(ip) => ip,
+                None => return Ok(DiscoveryResult::new()),
+            },
+        };
+
+        self.host(ip).await
+    }
+
+    /// Merge what Shodan knows about `target` into `result`
+    pub async fn enrich(&self, target: &str, result: &mut DiscoveryResult) -> Result<()> {
+        result.merge(self.lookup(target).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+//! Subdomain takeover detection
+//!
+//! A domain whose CNAME points at a hosted service (an S3 bucket, a GitHub
+//! Pages site, a Heroku app...) that has since been deleted can be claimed
+//! by anyone who registers the same resource with the provider. Providers
+//! answer requests for such unclaimed resources with a recognizable error
+//! page, so a domain is reported as vulnerable when its CNAME points at a
+//! known provider and the page it serves matches that provider's
+//! fingerprint.
+
+use crate::http_client::HttpClientConfig;
+use crate::vulnerability::DiscoveredVulnerability;
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Fingerprints shipped with the scanner, based on the community-maintained
+/// can-i-take-over-xyz list
+const DEFAULT_FINGERPRINTS: &str = include_str!("fingerprints.json");
+
+/// How a provider answers for a resource nobody has claimed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TakeoverFingerprint {
+    /// Short identifier, used in the finding's template ID
+    pub id: String,
+    /// Human-readable provider name
+    pub provider: String,
+    /// Domain suffixes of the provider's CNAME targets
+    pub cnames: Vec<String>,
+    /// Any of these in the response body marks the resource as unclaimed
+    pub fingerprints: Vec<String>,
+    /// Status code the error page is served with, if the provider is
+    /// consistent about it
+    pub status_code: Option<u16>,
+}
+
+impl TakeoverFingerprint {
+    /// Whether `cname` points at this provider
+    pub fn matches_cname(&self, cname: &str) -> bool {
+        let cname = normalize(cname);
+        self.cnames.iter().any(|suffix| {
+            let suffix = normalize(suffix);
+            cname == suffix || cname.ends_with(&format!(".{}", suffix))
+        })
+    }
+
+    /// Whether a response is the provider's unclaimed resource page
+    pub fn matches_response(&self, status_code: u16, body: &str) -> bool {
+        if self
+            .status_code
+            .is_some_and(|expected| expected != status_code)
+        {
+            return false;
+        }
+        self.fingerprints
+            .iter()
+            .any(|fingerprint| body.contains(fingerprint.as_str()))
+    }
+}
+
+/// The fingerprints shipped with the scanner
+pub fn default_fingerprints() -> Vec<TakeoverFingerprint> {
+    serde_json::from_str(DEFAULT_FINGERPRINTS).expect("bundled takeover fingerprints are valid")
+}
+
+/// Checks domains with a CNAME to a known provider for takeover
+pub struct TakeoverChecker {
+    client: Client,
+    fingerprints: Vec<TakeoverFingerprint>,
+}
+
+impl TakeoverChecker {
+    /// Create a checker using the default fingerprints
+    pub fn new() -> Result<Self> {
+        Self::with_fingerprints(default_fingerprints())
+    }
+
+    /// Create a checker using a custom fingerprint set
+    pub fn with_fingerprints(fingerprints: Vec<TakeoverFingerprint>) -> Result<Self> {
+        Self::with_config(fingerprints, &HttpClientConfig::default())
+    }
+
+    /// Create a checker using a custom fingerprint set and HTTP settings,
+    /// e.g. a proxy. Hosts on internal addresses are not fetched, whether a
+    /// name resolves to one or a redirect leads there.
+    pub fn with_config(
+        fingerprints: Vec<TakeoverFingerprint>,
+        http: &HttpClientConfig,
+    ) -> Result<Self> {
+        let client = http
+            .external_client_builder()?
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        Ok(Self {
+            client,
+            fingerprints,
+        })
+    }
+
+    /// The provider `cname` points at, if it's one we have a fingerprint for
+    pub fn provider_for(&self, cname: &str) -> Option<&TakeoverFingerprint> {
+        self.fingerprints
+            .iter()
+            .find(|fingerprint| fingerprint.matches_cname(cname))
+    }
+
+    /// Check `domain`, whose CNAME is `cname`, by fetching its home page
+    pub async fn check(
+        &self,
+        domain: &str,
+        cname: &str,
+    ) -> Result<Option<DiscoveredVulnerability>> {
+        let url = format!("http://{}/", normalize(domain));
+        self.check_at(domain, cname, &url).await
+    }
+
+    /// Check `domain`, whose CNAME is `cname`, against the page served at
+    /// `url`. Domains pointing at unknown providers aren't fetched.
+    pub async fn check_at(
+        &self,
+        domain: &str,
+        cname: &str,
+        url: &str,
+    ) -> Result<Option<DiscoveredVulnerability>> {
+        let Some(fingerprint) = self.provider_for(cname) else {
+            return Ok(None);
+        };
+
+        tracing::debug!(
+            "Checking {} (CNAME {}) for {} takeover",
+            domain,
+            cname,
+            fingerprint.provider
+        );
+        let response = self.client.get(url).send().await?;
+        let status_code = response.status().as_u16();
+        let body = response.text().await?;
+
+        if !fingerprint.matches_response(status_code, &body) {
+            return Ok(None);
+        }
+
+        tracing::info!(
+            "{} is vulnerable to takeover through {} ({})",
+            domain,
+            fingerprint.provider,
+            cname
+        );
+        Ok(Some(takeover_vulnerability(domain, cname, fingerprint)))
+    }
+}
+
+fn takeover_vulnerability(
+    domain: &str,
+    cname: &str,
+    fingerprint: &TakeoverFingerprint,
+) -> DiscoveredVulnerability {
+    let domain = normalize(domain);
+    let cname = normalize(cname);
+
+    let mut vulnerability = DiscoveredVulnerability::new(
+        domain.clone(),
+        format!("Subdomain takeover via {}", fingerprint.provider),
+        "high".to_string(),
+        format!("takeover-{}", fingerprint.id),
+        cname.clone(),
+    );
+    vulnerability.description = Some(format!(
+        "{} has a CNAME to {}, which is not claimed on {}. Anyone can register \
+         it with {} and serve content on {}.",
+        domain, cname, fingerprint.provider, fingerprint.provider, domain
+    ));
+    vulnerability.tags = vec!["takeover".to_string(), fingerprint.id.clone()];
+    vulnerability.source = "takeover_check".to_string();
+    vulnerability
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_lowercase()
+}
@@ -30,6 +30,26 @@ pub struct DiscoveryTask {
     pub task_type: DiscoveryTaskType,
     pub target: String,                          // e.g., domain name, IP range
     pub nuclei_params: Option<NucleiTaskParams>, // Parameters for Nuclei tasks
+    /// Directory web app scans store screenshots of discovered pages in;
+    /// no screenshots are taken when unset
+    #[serde(default)]
+    pub screenshot_dir: Option<String>,
+    /// Regular expressions for matches the secret scan of web app scans
+    /// should ignore, on top of the built-in allowlist
+    #[serde(default)]
+    pub secret_allowlist: Vec<String>,
+    /// Paths web app scans ask each discovered host for; the built-in list
+    /// when unset
+    #[serde(default)]
+    pub probe_paths: Option<Vec<crate::path_probe::ProbePath>>,
+    /// Links the built-in web app scan follows; the start page's host, one
+    /// link deep, when unset
+    #[serde(default)]
+    pub crawl_scope: crate::web_crawl::scope::ScopeConfig,
+    /// How the built-in web app scan's requests present themselves and
+    /// connect, e.g. through a proxy
+    #[serde(default)]
+    pub http: crate::http_client::HttpClientConfig,
 }
 
 // Implement method to execute tasks
@@ -42,7 +62,11 @@ impl DiscoveryTask {
             }
             DiscoveryTaskType::WebAppScanHttpx => {
                 let scanner = crate::web_crawl::httpx::HttpxRunner::new();
-                scanner.scan_urls(&[self.target.clone()]).await
+                let mut result = scanner.scan_urls(&[self.target.clone()]).await?;
+                self.capture_screenshots(&mut result).await;
+                self.scan_secrets(&mut result).await?;
+                self.probe_paths(&mut result).await?;
+                Ok(result)
             }
             DiscoveryTaskType::VulnerabilityScanNuclei => {
                 let mut scanner = crate::vulnerability::nuclei::NucleiRunner::new();
@@ -71,7 +95,18 @@ impl DiscoveryTask {
             }
             DiscoveryTaskType::WebAppScan => {
                 // Use the built-in crawler
-                crate::web_crawl::crawl_url(&self.target, 1).await
+                let mut result = crate::web_crawl::crawl_url_in_scope(
+                    &self.target,
+                    &self.crawl_scope,
+                    &self.http,
+                    crate::web_crawl::politeness::HostRegistry::shared(),
+                    &tokio_util::sync::CancellationToken::new(),
+                )
+                .await?;
+                self.capture_screenshots(&mut result).await;
+                self.scan_secrets(&mut result).await?;
+                self.probe_paths(&mut result).await?;
+                Ok(result)
             }
             DiscoveryTaskType::DnsEnumeration => {
                 // Use the built-in DNS enumerator
@@ -88,4 +123,43 @@ impl DiscoveryTask {
             }
         }
     }
+
+    /// Look for secrets exposed in the pages a scan found and the scripts
+    /// they load
+    async fn scan_secrets(
+        &self,
+        result: &mut crate::results::DiscoveryResult,
+    ) -> anyhow::Result<()> {
+        crate::secrets::SecretScanner::with_config(&self.http)?
+            .with_allowlist(&self.secret_allowlist)?
+            .scan_all(result)
+            .await;
+        Ok(())
+    }
+
+    /// Ask each web host a scan found for well-known and commonly exposed
+    /// paths
+    async fn probe_paths(
+        &self,
+        result: &mut crate::results::DiscoveryResult,
+    ) -> anyhow::Result<()> {
+        let paths = self
+            .probe_paths
+            .clone()
+            .unwrap_or_else(crate::path_probe::default_paths);
+        crate::path_probe::PathProber::with_config(paths, &self.http)?
+            .probe_all(result)
+            .await;
+        Ok(())
+    }
+
+    /// Screenshot the web resources a scan found, when a screenshot
+    /// directory is configured
+    async fn capture_screenshots(&self, result: &mut crate::results::DiscoveryResult) {
+        if let Some(dir) = &self.screenshot_dir {
+            crate::screenshot::ScreenshotCapturer::new(dir)
+                .capture_all(result)
+                .await;
+        }
+    }
 }
@@ -44,3 +44,6 @@ impl DiscoveredVulnerability {
 
 // Nuclei runner module
 pub mod nuclei;
+
+// Concurrent runner for vulnerability checks
+pub mod scanner;
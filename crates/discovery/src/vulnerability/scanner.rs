@@ -0,0 +1,116 @@
+use crate::cancellation::ScanCancelled;
+use crate::path_probe::PathProber;
+use crate::results::DiscoveryResult;
+use crate::vulnerability::DiscoveredVulnerability;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+/// Checks run at once when no limit is given
+pub const DEFAULT_MAX_CONCURRENT_CHECKS: usize = 10;
+
+/// One kind of vulnerability check, run against a single target
+#[async_trait]
+pub trait VulnCheck: Send + Sync {
+    /// Name of the check, for logs
+    fn name(&self) -> &str;
+
+    /// Look for vulnerabilities on `target`, a host or base URL
+    async fn check(&self, target: &str) -> Result<Vec<DiscoveredVulnerability>>;
+}
+
+#[async_trait]
+impl VulnCheck for PathProber {
+    fn name(&self) -> &str {
+        "path_probe"
+    }
+
+    async fn check(&self, target: &str) -> Result<Vec<DiscoveredVulnerability>> {
+        Ok(self.probe(target).await?.raw_vulnerabilities)
+    }
+}
+
+/// Runs every registered check against every target, a bounded number at
+/// a time
+pub struct VulnScanner {
+    checks: Vec<Arc<dyn VulnCheck>>,
+    max_concurrent: usize,
+}
+
+impl VulnScanner {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            max_concurrent: DEFAULT_MAX_CONCURRENT_CHECKS,
+        }
+    }
+
+    pub fn with_check(mut self, check: Arc<dyn VulnCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Run at most `max_concurrent` checks at once, at least one
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = max_concurrent.max(1);
+        self
+    }
+
+    /// Run each check against each target, collecting what they find. A
+    /// check that fails on a target is logged and skipped so the rest of the
+    /// scan still completes.
+    pub async fn scan(
+        &self,
+        targets: &[String],
+        cancel: &CancellationToken,
+    ) -> Result<DiscoveryResult> {
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrent));
+        let mut tasks = JoinSet::new();
+
+        for target in targets {
+            for check in &self.checks {
+                let permit = tokio::select! {
+                    permit = semaphore.clone().acquire_owned() => permit?,
+                    _ = cancel.cancelled() => return Err(ScanCancelled.into()),
+                };
+                let check = check.clone();
+                let target = target.clone();
+
+                tasks.spawn(async move {
+                    let _permit = permit;
+                    let found = check.check(&target).await;
+                    (check, target, found)
+                });
+            }
+        }
+
+        let mut result = DiscoveryResult::new();
+        loop {
+            let joined = tokio::select! {
+                joined = tasks.join_next() => joined,
+                _ = cancel.cancelled() => return Err(ScanCancelled.into()),
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+
+            match joined? {
+                (_, _, Ok(vulnerabilities)) => result.raw_vulnerabilities.extend(vulnerabilities),
+                (check, target, Err(e)) => {
+                    tracing::warn!("{} check of {} failed: {}", check.name(), target, e)
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl Default for VulnScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,87 @@
+//! Content hashing for web page change detection
+//!
+//! Pages are hashed after stripping the parts that change on every request
+//! (CSRF tokens, nonces, timestamps, cache busters, comments and
+//! whitespace), so two scans of an unchanged page hash the same and a
+//! differing hash points at a real change such as a defacement.
+
+use crate::results::DiscoveredWebResource;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref HTML_COMMENT: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    /// `<input>` and `<meta>` tags, whose values may be per-request tokens
+    static ref INPUT_OR_META_TAG: Regex = Regex::new(r"(?i)<(?:input|meta)\b[^>]*>").unwrap();
+    static ref TOKEN_NAME: Regex =
+        Regex::new(r#"(?i)\b(?:name|id)\s*=\s*["'][^"']*(?:csrf|xsrf|token|nonce|authenticity)[^"']*["']"#)
+            .unwrap();
+    static ref VALUE_ATTRIBUTE: Regex =
+        Regex::new(r#"(?i)\b(value|content)\s*=\s*(?:"[^"]*"|'[^']*')"#).unwrap();
+    static ref NONCE_ATTRIBUTE: Regex =
+        Regex::new(r#"(?i)\bnonce\s*=\s*(?:"[^"]*"|'[^']*')"#).unwrap();
+    static ref ISO_TIMESTAMP: Regex = Regex::new(
+        r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?"
+    )
+    .unwrap();
+    /// Seconds or milliseconds since the epoch, 2001 to 2033
+    static ref UNIX_TIMESTAMP: Regex = Regex::new(r"\b1\d{9}(?:\d{3})?\b").unwrap();
+    static ref CACHE_BUSTER: Regex =
+        Regex::new(r#"(?i)([?&](?:v|ver|version|_|t|ts|cb|cachebuster)=)[^&"'\s>]*"#).unwrap();
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+}
+
+/// Strip the volatile parts of a page body
+pub fn normalize_content(body: &str) -> String {
+    let body = HTML_COMMENT.replace_all(body, "");
+    let body = INPUT_OR_META_TAG.replace_all(&body, |tag: &Captures| {
+        let tag = &tag[0];
+        if TOKEN_NAME.is_match(tag) {
+            VALUE_ATTRIBUTE.replace_all(tag, "$1=\"\"").into_owned()
+        } else {
+            tag.to_string()
+        }
+    });
+    let body = NONCE_ATTRIBUTE.replace_all(&body, "");
+    let body = ISO_TIMESTAMP.replace_all(&body, "<timestamp>");
+    let body = UNIX_TIMESTAMP.replace_all(&body, "<timestamp>");
+    let body = CACHE_BUSTER.replace_all(&body, "$1");
+    WHITESPACE.replace_all(&body, " ").trim().to_string()
+}
+
+/// Hex-encoded SHA-256 of the normalized page body
+pub fn content_hash(body: &str) -> String {
+    format!("{:x}", Sha256::digest(normalize_content(body).as_bytes()))
+}
+
+/// A web resource whose content hash differs from the previous scan's
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContentChange {
+    pub url: String,
+    pub previous_hash: String,
+    pub current_hash: String,
+}
+
+/// Compare `resources` against the hashes from the previous scan, keyed by
+/// URL. Resources that weren't hashed, or weren't seen before, aren't
+/// reported.
+pub fn detect_content_changes(
+    previous: &HashMap<String, String>,
+    resources: &[DiscoveredWebResource],
+) -> Vec<ContentChange> {
+    resources
+        .iter()
+        .filter_map(|resource| {
+            let current_hash = resource.content_hash.as_ref()?;
+            let previous_hash = previous.get(resource.url.trim())?;
+            (previous_hash != current_hash).then(|| ContentChange {
+                url: resource.url.trim().to_string(),
+                previous_hash: previous_hash.clone(),
+                current_hash: current_hash.clone(),
+            })
+        })
+        .collect()
+}
@@ -68,6 +68,8 @@ impl HttpxRunner {
                             .map(String::from),
                         technologies,
                         source,
+                        screenshot_path: None,
+                        content_hash: None,
                     });
                 }
             }
@@ -131,6 +133,8 @@ impl HttpxRunner {
                             .map(String::from),
                         technologies,
                         source,
+                        screenshot_path: None,
+                        content_hash: None,
                     });
                 }
             }
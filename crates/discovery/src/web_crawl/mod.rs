@@ -1,25 +1,97 @@
+use crate::cancellation::ScanCancelled;
+use crate::http_client::{
+    http_get_with_retry, is_internal_url, HttpClientConfig, InternalAddress, DEFAULT_RETRIES,
+};
 use crate::results::{DiscoveredWebResource, DiscoveryResult};
 use anyhow::Result;
-use reqwest::Client;
 use scraper::{Html, Selector};
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
+pub mod content_hash;
 // Add the httpx module
 pub mod httpx;
+pub mod politeness;
+pub mod scope;
+
+use politeness::HostRegistry;
+use scope::ScopeConfig;
 
 // Basic web crawler
 pub async fn crawl_url(target_url: &str, depth: u8) -> Result<DiscoveryResult> {
+    crawl_url_with_cancellation(target_url, depth, &CancellationToken::new()).await
+}
+
+/// Crawl a URL, stopping with [`ScanCancelled`] as soon as `cancel` fires
+pub async fn crawl_url_with_cancellation(
+    target_url: &str,
+    depth: u8,
+    cancel: &CancellationToken,
+) -> Result<DiscoveryResult> {
+    crawl_url_with_config(target_url, depth, &HttpClientConfig::default(), cancel).await
+}
+
+/// Crawl a URL with the user agent, headers and proxy of `http`, stopping
+/// with [`ScanCancelled`] as soon as `cancel` fires
+pub async fn crawl_url_with_config(
+    target_url: &str,
+    depth: u8,
+    http: &HttpClientConfig,
+    cancel: &CancellationToken,
+) -> Result<DiscoveryResult> {
+    crawl_url_with_politeness(target_url, depth, http, HostRegistry::shared(), cancel).await
+}
+
+/// Crawl a URL, taking turns to request each host from `politeness` so
+/// concurrent crawls sharing it don't overload a host
+pub async fn crawl_url_with_politeness(
+    target_url: &str,
+    depth: u8,
+    http: &HttpClientConfig,
+    politeness: &HostRegistry,
+    cancel: &CancellationToken,
+) -> Result<DiscoveryResult> {
+    crawl_url_in_scope(
+        target_url,
+        &ScopeConfig::new(depth),
+        http,
+        politeness,
+        cancel,
+    )
+    .await
+}
+
+/// Crawl a URL, only following links `scope` allows. Links outside it are
+/// listed in the result's `out_of_scope_urls` without being fetched. Unless
+/// `scope` allows internal addresses, starting at one fails with
+/// [`InternalAddress`], and links, redirects and names leading to one
+/// aren't followed.
+pub async fn crawl_url_in_scope(
+    target_url: &str,
+    scope: &ScopeConfig,
+    http: &HttpClientConfig,
+    politeness: &HostRegistry,
+    cancel: &CancellationToken,
+) -> Result<DiscoveryResult> {
+    let depth = scope.max_depth;
     tracing::debug!("Crawling URL: {} with depth: {}", target_url, depth);
-    let client = Client::builder()
-        .user_agent("EASM Discovery Bot/0.1") // Be a good bot citizen
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
+    let client = if scope.allow_internal_addresses {
+        http.client_builder()?
+    } else {
+        http.external_client_builder()?
+    }
+    .timeout(std::time::Duration::from_secs(10))
+    .build()?;
 
     let mut discovery_result = DiscoveryResult::new();
     let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut out_of_scope: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut queue: std::collections::VecDeque<(String, u8)> = std::collections::VecDeque::new();
 
     let base_url = Url::parse(target_url)?;
+    if !scope.allow_internal_addresses && is_internal_url(&base_url) {
+        return Err(InternalAddress(target_url.to_string()).into());
+    }
     queue.push_back((target_url.to_string(), 0));
     visited.insert(target_url.to_string());
 
@@ -30,8 +102,21 @@ pub async fn crawl_url(target_url: &str, depth: u8) -> Result<DiscoveryResult> {
             continue;
         }
 
+        let host = Url::parse(&current_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let _permit = tokio::select! {
+            permit = politeness.acquire(&host) => permit,
+            _ = cancel.cancelled() => return Err(ScanCancelled.into()),
+        };
+
         tracing::trace!("Fetching: {}", current_url);
-        match client.get(&current_url).send().await {
+        let response = tokio::select! {
+            response = http_get_with_retry(&client, &current_url, DEFAULT_RETRIES) => response,
+            _ = cancel.cancelled() => return Err(ScanCancelled.into()),
+        };
+        match response {
             Ok(response) => {
                 let status = response.status();
                 // Store headers for technology detection
@@ -41,11 +126,7 @@ pub async fn crawl_url(target_url: &str, depth: u8) -> Result<DiscoveryResult> {
                 match response.text().await {
                     Ok(body) => {
                         let document = Html::parse_document(&body);
-                        let title_selector = Selector::parse("title").unwrap();
-                        let title = document
-                            .select(&title_selector)
-                            .next()
-                            .map(|t| t.text().collect::<String>().trim().to_string());
+                        let title = extract_title(&document);
 
                         // Implement basic technology detection
                         let mut technologies = Vec::new();
@@ -59,6 +140,8 @@ pub async fn crawl_url(target_url: &str, depth: u8) -> Result<DiscoveryResult> {
                             title,
                             technologies,
                             source: source.clone(),
+                            screenshot_path: None,
+                            content_hash: Some(content_hash::content_hash(&body)),
                         });
 
                         // Find links if depth allows further crawling
@@ -71,11 +154,18 @@ pub async fn crawl_url(target_url: &str, depth: u8) -> Result<DiscoveryResult> {
                                             next_url.set_fragment(None); // Ignore fragments
                                             let next_url_str = next_url.to_string();
 
-                                            // Stay on the same domain (optional, configurable?)
-                                            if next_url.host_str() == base_url.host_str()
-                                                && !visited.contains(&next_url_str)
-                                            {
-                                                visited.insert(next_url_str.clone());
+                                            if !matches!(next_url.scheme(), "http" | "https") {
+                                                continue;
+                                            }
+                                            if !scope.in_scope(&base_url, &next_url) {
+                                                if out_of_scope.insert(next_url_str.clone()) {
+                                                    discovery_result
+                                                        .out_of_scope_urls
+                                                        .push(next_url_str);
+                                                }
+                                                continue;
+                                            }
+                                            if visited.insert(next_url_str.clone()) {
                                                 queue.push_back((next_url_str, current_depth + 1));
                                             }
                                         }
@@ -105,6 +195,15 @@ pub async fn crawl_url(target_url: &str, depth: u8) -> Result<DiscoveryResult> {
     Ok(discovery_result)
 }
 
+/// Text of the page's `<title>` element, trimmed
+pub fn extract_title(document: &Html) -> Option<String> {
+    let title_selector = Selector::parse("title").unwrap();
+    document
+        .select(&title_selector)
+        .next()
+        .map(|t| t.text().collect::<String>().trim().to_string())
+}
+
 // Helper function to detect web technologies from HTML
 fn detect_technologies(document: &Html, technologies: &mut Vec<String>) {
     // Check for common JS frameworks
@@ -0,0 +1,119 @@
+//! Per-host politeness for the crawler
+//!
+//! Crawls of many targets often end up on the same host, e.g. a shared CDN
+//! or a site reached through several subdomains' links. A [`HostRegistry`]
+//! shared by all those crawls caps how many requests a host has in flight
+//! and spaces out the requests it gets, however many crawls are running.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep_until, Instant};
+
+/// Most requests a host has in flight when none is configured
+pub const DEFAULT_MAX_CONCURRENT_PER_HOST: usize = 2;
+
+/// Time between requests to a host when none is configured
+pub const DEFAULT_HOST_DELAY_MS: u64 = 250;
+
+lazy_static::lazy_static! {
+    /// Registry shared by every crawl that isn't given its own
+    static ref SHARED_REGISTRY: HostRegistry = HostRegistry::new(PolitenessConfig::default());
+}
+
+/// How hard the crawler may hit a single host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolitenessConfig {
+    /// Most requests in flight to a host at once
+    pub max_concurrent_per_host: usize,
+    /// Least time between the starts of two requests to a host
+    pub delay_ms: u64,
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_per_host: DEFAULT_MAX_CONCURRENT_PER_HOST,
+            delay_ms: DEFAULT_HOST_DELAY_MS,
+        }
+    }
+}
+
+/// Hands out turns to request hosts under a [`PolitenessConfig`]
+pub struct HostRegistry {
+    config: PolitenessConfig,
+    hosts: Mutex<HashMap<String, Arc<HostState>>>,
+}
+
+/// Requests in flight to a host and when it may be requested next
+struct HostState {
+    in_flight: Arc<Semaphore>,
+    next_request: Mutex<Instant>,
+}
+
+/// A turn to request a host, which counts towards its concurrency limit
+/// until dropped
+pub struct HostPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl HostRegistry {
+    /// Registry enforcing `config`. A concurrency limit of 0 is treated as 1.
+    pub fn new(config: PolitenessConfig) -> Self {
+        Self {
+            config,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The registry shared by all crawls in the process
+    pub fn shared() -> &'static HostRegistry {
+        &SHARED_REGISTRY
+    }
+
+    /// Limits this registry enforces
+    pub fn config(&self) -> &PolitenessConfig {
+        &self.config
+    }
+
+    /// Wait for a turn to request `host`: until fewer than the allowed
+    /// requests are in flight and the delay since the previous request has
+    /// passed
+    pub async fn acquire(&self, host: &str) -> HostPermit {
+        let state = self.state_for(host);
+        let permit = state
+            .in_flight
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("host semaphores are never closed");
+
+        // Reserve the next slot before waiting for it, so concurrent callers
+        // line up one delay apart
+        let start = {
+            let mut next_request = state.next_request.lock().unwrap();
+            let start = (*next_request).max(Instant::now());
+            *next_request = start + Duration::from_millis(self.config.delay_ms);
+            start
+        };
+        sleep_until(start).await;
+
+        HostPermit { _permit: permit }
+    }
+
+    fn state_for(&self, host: &str) -> Arc<HostState> {
+        let mut hosts = self.hosts.lock().unwrap();
+        hosts
+            .entry(host.to_lowercase())
+            .or_insert_with(|| {
+                Arc::new(HostState {
+                    in_flight: Arc::new(Semaphore::new(self.config.max_concurrent_per_host.max(1))),
+                    next_request: Mutex::new(Instant::now()),
+                })
+            })
+            .clone()
+    }
+}
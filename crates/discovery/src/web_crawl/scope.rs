@@ -0,0 +1,102 @@
+//! Which links the crawler follows
+//!
+//! By default a crawl stays on the host it started on. It can be widened to
+//! that host's subdomains, or pointed at an allowlist of domains instead.
+//! Links that fall outside the scope are recorded but never fetched.
+//! Internal addresses, such as `localhost` or a cloud metadata endpoint, are
+//! out of scope unless explicitly allowed.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::http_client::is_internal_url;
+
+/// Links followed away from the start page when no depth is configured
+pub const DEFAULT_MAX_DEPTH: u8 = 1;
+
+/// Where a crawl may go and how far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScopeConfig {
+    /// Also follow links to subdomains of the in-scope domains
+    pub include_subdomains: bool,
+    /// Domains links may be followed to. When empty, only the host the crawl
+    /// started on is in scope.
+    pub allowed_domains: Vec<String>,
+    /// Links followed away from the start page: 0 fetches only the start
+    /// page, 1 also the pages it links to, and so on
+    pub max_depth: u8,
+    /// Allow fetching loopback, private and link-local addresses, e.g. to
+    /// crawl an intranet. Refused by default, redirects included.
+    pub allow_internal_addresses: bool,
+}
+
+impl Default for ScopeConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_DEPTH)
+    }
+}
+
+impl ScopeConfig {
+    /// Stay on the start page's host, following links up to `max_depth`
+    /// pages away
+    pub fn new(max_depth: u8) -> Self {
+        Self {
+            include_subdomains: false,
+            allowed_domains: Vec::new(),
+            max_depth,
+            allow_internal_addresses: false,
+        }
+    }
+
+    pub fn with_subdomains(mut self, include_subdomains: bool) -> Self {
+        self.include_subdomains = include_subdomains;
+        self
+    }
+
+    /// Only follow links to `domains`, rather than the start page's host
+    pub fn with_allowed_domains(mut self, domains: Vec<String>) -> Self {
+        self.allowed_domains = domains;
+        self
+    }
+
+    pub fn with_internal_addresses(mut self, allow_internal_addresses: bool) -> Self {
+        self.allow_internal_addresses = allow_internal_addresses;
+        self
+    }
+
+    /// Whether a crawl that started at `start` may fetch `url`
+    pub fn in_scope(&self, start: &Url, url: &Url) -> bool {
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        if !self.allow_internal_addresses && is_internal_url(url) {
+            return false;
+        }
+        let Some(host) = url.host_str().map(normalize_domain) else {
+            return false;
+        };
+
+        let matches = |domain: &str| {
+            host == domain || (self.include_subdomains && host.ends_with(&format!(".{}", domain)))
+        };
+
+        if self.allowed_domains.is_empty() {
+            start
+                .host_str()
+                .map(normalize_domain)
+                .is_some_and(|start_host| matches(&start_host))
+        } else {
+            self.allowed_domains
+                .iter()
+                .map(|domain| normalize_domain(domain))
+                .any(|domain| matches(&domain))
+        }
+    }
+}
+
+/// Lowercase a domain and drop any trailing dot, so spellings of the same
+/// name compare equal
+pub(crate) fn normalize_domain(domain: &str) -> String {
+    domain.trim().trim_end_matches('.').to_lowercase()
+}
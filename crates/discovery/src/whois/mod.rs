@@ -0,0 +1,175 @@
+//! Domain registration lookups over RDAP
+//!
+//! RDAP is the JSON-over-HTTPS successor to WHOIS. Unlike WHOIS, its
+//! responses have a fixed structure, so registrar, registration dates and
+//! nameservers can be read without per-registry text parsing.
+
+use crate::http_client::HttpClientConfig;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Public RDAP bootstrap service, which redirects to the registry
+/// responsible for the domain's TLD
+pub const DEFAULT_RDAP_BASE_URL: &str = "https://rdap.org";
+
+/// Registration details for a domain, stored on domain assets under the
+/// `whois_info` attribute
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WhoisInfo {
+    pub registrar: Option<String>,
+    pub creation_date: Option<DateTime<Utc>>,
+    pub expiration_date: Option<DateTime<Utc>>,
+    /// Lowercased nameserver host names
+    pub nameservers: Vec<String>,
+}
+
+/// The parts of an RDAP domain response (RFC 9083) we use
+#[derive(Debug, Deserialize)]
+struct RdapDomain {
+    #[serde(default)]
+    events: Vec<RdapEvent>,
+    #[serde(default)]
+    nameservers: Vec<RdapNameserver>,
+    #[serde(default)]
+    entities: Vec<RdapEntity>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RdapEvent {
+    event_action: String,
+    event_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RdapNameserver {
+    ldh_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RdapEntity {
+    #[serde(default)]
+    roles: Vec<String>,
+    /// jCard (RFC 7095): `["vcard", [[name, params, type, value], ...]]`
+    vcard_array: Option<serde_json::Value>,
+}
+
+impl RdapEntity {
+    /// The entity's formatted name (`fn` property) from its jCard
+    fn formatted_name(&self) -> Option<String> {
+        self.vcard_array
+            .as_ref()?
+            .get(1)?
+            .as_array()?
+            .iter()
+            .find(|property| property.get(0).and_then(|name| name.as_str()) == Some("fn"))?
+            .get(3)?
+            .as_str()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+    }
+}
+
+impl From<RdapDomain> for WhoisInfo {
+    fn from(domain: RdapDomain) -> Self {
+        let event_date = |action: &str| {
+            domain
+                .events
+                .iter()
+                .find(|event| event.event_action.eq_ignore_ascii_case(action))
+                .and_then(|event| event.event_date)
+        };
+
+        let registrar = domain
+            .entities
+            .iter()
+            .filter(|entity| {
+                entity
+                    .roles
+                    .iter()
+                    .any(|role| role.eq_ignore_ascii_case("registrar"))
+            })
+            .find_map(RdapEntity::formatted_name);
+
+        let nameservers = domain
+            .nameservers
+            .iter()
+            .filter_map(|ns| ns.ldh_name.as_deref())
+            .map(|name| name.trim_end_matches('.').to_lowercase())
+            .collect();
+
+        Self {
+            registrar,
+            creation_date: event_date("registration"),
+            expiration_date: event_date("expiration"),
+            nameservers,
+        }
+    }
+}
+
+/// Parse an RDAP domain response
+pub fn parse_rdap_response(body: &str) -> Result<WhoisInfo> {
+    let domain: RdapDomain = serde_json::from_str(body)?;
+    Ok(domain.into())
+}
+
+/// RDAP client for domain registration lookups
+pub struct RdapClient {
+    client: Client,
+    base_url: String,
+}
+
+impl RdapClient {
+    /// Create a client using the public RDAP bootstrap service
+    pub fn new() -> Result<Self> {
+        Self::with_base_url(DEFAULT_RDAP_BASE_URL)
+    }
+
+    /// Create a client querying a specific RDAP server
+    pub fn with_base_url(base_url: &str) -> Result<Self> {
+        Self::with_config(base_url, &HttpClientConfig::default())
+    }
+
+    /// Create a client querying a specific RDAP server with custom HTTP
+    /// settings, e.g. a proxy
+    pub fn with_config(base_url: &str, http: &HttpClientConfig) -> Result<Self> {
+        let client = http
+            .client_builder()?
+            .timeout(Duration::from_secs(15))
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Look up a domain's registration details
+    pub async fn lookup(&self, domain: &str) -> Result<WhoisInfo> {
+        let domain = domain.trim().trim_end_matches('.').to_lowercase();
+        tracing::debug!("Looking up RDAP registration data for: {}", domain);
+
+        let url = format!("{}/domain/{}", self.base_url, domain);
+        let response = self
+            .client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/rdap+json")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "RDAP lookup for {} failed with status: {}",
+                domain,
+                response.status()
+            ));
+        }
+
+        parse_rdap_response(&response.text().await?)
+    }
+}
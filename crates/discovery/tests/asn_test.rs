@@ -0,0 +1,122 @@
+use discovery::asn::{AsnDatabase, AsnInfo};
+use discovery::port_scan::DiscoveredPort;
+use discovery::results::{DiscoveredIp, DiscoveryResult};
+use std::net::IpAddr;
+use std::path::Path;
+
+fn sample_database() -> AsnDatabase {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ip2asn-sample.tsv");
+    AsnDatabase::from_path(path).unwrap()
+}
+
+fn ip(value: &str) -> IpAddr {
+    value.parse().unwrap()
+}
+
+#[test]
+fn test_lookup_cloudflare_ip() {
+    let database = sample_database();
+
+    assert_eq!(
+        database.lookup(ip("1.1.1.1")),
+        Some(AsnInfo {
+            asn: 13335,
+            network: "1.1.1.0/24".to_string(),
+            organization: "CLOUDFLARENET".to_string(),
+            country: Some("US".to_string()),
+        })
+    );
+
+    let info = database.lookup(ip("104.21.35.7")).unwrap();
+    assert_eq!(info.asn, 13335);
+    assert_eq!(info.network, "104.16.0.0/12");
+}
+
+#[test]
+fn test_lookup_ipv6() {
+    let database = sample_database();
+
+    let info = database.lookup(ip("2606:4700:4700::1111")).unwrap();
+    assert_eq!(info.asn, 13335);
+    assert_eq!(info.network, "2606:4700::/32");
+
+    let info = database.lookup(ip("2001:4860:4860::8888")).unwrap();
+    assert_eq!(info.asn, 15169);
+    assert_eq!(info.organization, "GOOGLE");
+}
+
+#[test]
+fn test_lookup_outside_dataset() {
+    let database = sample_database();
+
+    // Unrouted range, gap between ranges, past the last range
+    assert_eq!(database.lookup(ip("1.0.2.1")), None);
+    assert_eq!(database.lookup(ip("8.8.6.1")), None);
+    assert_eq!(database.lookup(ip("203.0.113.1")), None);
+    assert_eq!(database.lookup(ip("0.0.0.1")), None);
+    assert_eq!(database.lookup(ip("::1")), None);
+}
+
+#[test]
+fn test_unrouted_ranges_are_skipped() {
+    assert_eq!(sample_database().len(), 7);
+}
+
+#[test]
+fn test_network_for_range_spanning_several_blocks() {
+    let database =
+        AsnDatabase::from_reader("10.0.1.0\t10.0.3.255\t64512\tNone\tPRIVATE-AS\n".as_bytes())
+            .unwrap();
+
+    let info = database.lookup(ip("10.0.1.9")).unwrap();
+    assert_eq!(info.network, "10.0.1.0/24");
+    assert_eq!(info.country, None);
+    assert_eq!(
+        database.lookup(ip("10.0.2.9")).unwrap().network,
+        "10.0.2.0/23"
+    );
+}
+
+#[test]
+fn test_malformed_line_is_rejected() {
+    let result =
+        AsnDatabase::from_reader("1.1.1.0\t1.1.1.255\tAS13335\tUS\tCLOUDFLARENET\n".as_bytes());
+    assert!(result.is_err());
+
+    let result = AsnDatabase::from_reader("1.1.1.0\t1.1.1.255\n".as_bytes());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_enrich_discovery_result() {
+    let database = sample_database();
+    let mut result = DiscoveryResult::new();
+    result.ip_addresses.push(DiscoveredIp {
+        ip_address: ip("1.1.1.1"),
+        source: "dns_lookup".to_string(),
+    });
+    result.ports.push(DiscoveredPort {
+        ip_address: ip("8.8.8.8"),
+        port: 53,
+        protocol: "TCP".to_string(),
+        status: "OPEN".to_string(),
+        service_name: None,
+        banner: None,
+        http_status: None,
+        http_title: None,
+        tls_info: None,
+        source: "port_scan".to_string(),
+    });
+
+    result.enrich_with_asn(&database);
+
+    let attributes = result.ip_attributes(ip("1.1.1.1"), "dns_lookup");
+    assert_eq!(attributes["source"], "dns_lookup");
+    assert_eq!(attributes["asn_info"]["asn"], 13335);
+    assert_eq!(attributes["asn_info"]["network"], "1.1.1.0/24");
+    assert_eq!(attributes["asn_info"]["organization"], "CLOUDFLARENET");
+    assert_eq!(result.asn[&ip("8.8.8.8")].asn, 15169);
+
+    let attributes = result.ip_attributes(ip("192.0.2.1"), "dns_lookup");
+    assert!(attributes.get("asn_info").is_none());
+}
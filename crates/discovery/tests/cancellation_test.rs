@@ -0,0 +1,77 @@
+use discovery::cancellation::is_cancelled;
+use discovery::http_client::HttpClientConfig;
+use discovery::port_scan::PortScanner;
+use discovery::web_crawl::crawl_url_in_scope;
+use discovery::web_crawl::politeness::HostRegistry;
+use discovery::web_crawl::scope::ScopeConfig;
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio_util::sync::CancellationToken;
+
+/// Generous bound on how long a scan may keep running once cancelled
+const CANCEL_DEADLINE: Duration = Duration::from_secs(2);
+
+#[tokio::test]
+async fn test_port_scan_stops_promptly_when_cancelled() {
+    // Unanswered UDP probes wait for their full timeout, so scanning a few
+    // hundred local ports held by silent UDP sockets takes well over ten seconds
+    let mut silent_sockets = Vec::new();
+    for _ in 0..400 {
+        silent_sockets.push(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+    }
+    let ports: Vec<u16> = silent_sockets
+        .iter()
+        .map(|socket| socket.local_addr().unwrap().port())
+        .collect();
+    let cancel = CancellationToken::new();
+    let scanner = PortScanner::new().with_cancellation(cancel.clone());
+
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        canceller.cancel();
+    });
+
+    let started = Instant::now();
+    let result = scanner.scan_ip("127.0.0.1", Some(&ports)).await;
+
+    let error = result.expect_err("cancelled scan should not complete");
+    assert!(is_cancelled(&error), "unexpected error: {}", error);
+    assert!(started.elapsed() < CANCEL_DEADLINE);
+}
+
+#[tokio::test]
+async fn test_crawl_stops_promptly_when_cancelled() {
+    // A server that accepts connections but never answers keeps the request
+    // pending until the crawler's own ten second timeout
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+
+    let cancel = CancellationToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        canceller.cancel();
+    });
+
+    let started = Instant::now();
+    // The server is local, so internal addresses have to be allowed
+    let result = crawl_url_in_scope(
+        &format!("http://{}/", addr),
+        &ScopeConfig::new(1).with_internal_addresses(true),
+        &HttpClientConfig::default(),
+        HostRegistry::shared(),
+        &cancel,
+    )
+    .await;
+
+    let error = result.expect_err("cancelled crawl should not complete");
+    assert!(is_cancelled(&error), "unexpected error: {}", error);
+    assert!(started.elapsed() < CANCEL_DEADLINE);
+}
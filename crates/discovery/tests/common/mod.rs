@@ -0,0 +1,60 @@
+//! Fixtures shared by the integration tests. Each test binary uses only
+//! some of them.
+#![allow(dead_code)]
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// A request received by [`start_http_server`]
+pub struct ReceivedRequest {
+    /// Request line and headers as sent
+    pub head: String,
+    pub arrived_at: Instant,
+}
+
+impl ReceivedRequest {
+    /// e.g. `GET /path HTTP/1.1`
+    pub fn request_line(&self) -> &str {
+        self.head.lines().next().unwrap_or_default()
+    }
+}
+
+/// Start an HTTP server on a random local port that answers every request
+/// with `status`, `content_type` and `body`, returning the port and a
+/// receiver for each request as it arrives
+pub async fn start_http_server(
+    status: &'static str,
+    content_type: &'static str,
+    body: &'static str,
+) -> (u16, mpsc::UnboundedReceiver<ReceivedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let request_tx = request_tx.clone();
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let _ = request_tx.send(ReceivedRequest {
+                    head: String::from_utf8_lossy(&buf[..n]).to_string(),
+                    arrived_at: Instant::now(),
+                });
+
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    content_type,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    (port, request_rx)
+}
@@ -0,0 +1,116 @@
+use discovery::results::DiscoveredWebResource;
+use discovery::web_crawl::content_hash::{content_hash, detect_content_changes, ContentChange};
+use std::collections::HashMap;
+
+/// A login page as served on two requests a few seconds apart: the CSRF
+/// token, script nonce, render time and cache buster differ
+const LOGIN_PAGE_FIRST: &str = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta name="csrf-token" content="Zm9vYmFyMTIz">
+    <script nonce="r4nd0m1" src="/app.js?v=1714053600"></script>
+    <!-- rendered by web-3 in 12ms -->
+  </head>
+  <body>
+    <form action="/login" method="post">
+      <input type="hidden" name="authenticity_token" value="abc123def456">
+      <input type="text" name="username" value="">
+      <button>Sign in</button>
+    </form>
+    <footer>Generated at 2025-04-25T14:00:00Z</footer>
+  </body>
+</html>"#;
+
+const LOGIN_PAGE_SECOND: &str = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta name="csrf-token" content="YmF6cXV4NDU2">
+    <script nonce="0th3rn0nc3" src="/app.js?v=1714053721"></script>
+    <!-- rendered by web-1 in 9ms -->
+  </head>
+  <body>
+    <form action="/login" method="post">
+      <input type="hidden" name="authenticity_token" value="zyx987wvu654">
+      <input type="text"   name="username" value="">
+      <button>Sign in</button>
+    </form>
+    <footer>Generated at 2025-04-25T14:02:01Z</footer>
+  </body>
+</html>"#;
+
+fn resource(url: &str, body: &str) -> DiscoveredWebResource {
+    DiscoveredWebResource {
+        url: url.to_string(),
+        status_code: 200,
+        title: None,
+        technologies: Vec::new(),
+        source: "web_crawl".to_string(),
+        screenshot_path: None,
+        content_hash: Some(content_hash(body)),
+    }
+}
+
+#[test]
+fn test_identical_content_hashes_identically() {
+    assert_eq!(
+        content_hash(LOGIN_PAGE_FIRST),
+        content_hash(LOGIN_PAGE_FIRST)
+    );
+    assert_eq!(content_hash(LOGIN_PAGE_FIRST).len(), 64);
+}
+
+#[test]
+fn test_volatile_content_is_ignored() {
+    assert_eq!(
+        content_hash(LOGIN_PAGE_FIRST),
+        content_hash(LOGIN_PAGE_SECOND)
+    );
+}
+
+#[test]
+fn test_meaningful_change_differs() {
+    let defaced = LOGIN_PAGE_FIRST.replace("<button>Sign in</button>", "<h1>Hacked</h1>");
+    assert_ne!(content_hash(LOGIN_PAGE_FIRST), content_hash(&defaced));
+
+    // Values of ordinary form fields are content too
+    let prefilled = LOGIN_PAGE_FIRST.replace(
+        r#"name="username" value="""#,
+        r#"name="username" value="admin""#,
+    );
+    assert_ne!(content_hash(LOGIN_PAGE_FIRST), content_hash(&prefilled));
+}
+
+#[test]
+fn test_detect_content_changes() {
+    let previous = HashMap::from([
+        (
+            "https://www.example.com".to_string(),
+            content_hash(LOGIN_PAGE_FIRST),
+        ),
+        (
+            "https://blog.example.com".to_string(),
+            content_hash("<h1>Blog</h1>"),
+        ),
+    ]);
+    let mut unhashed = resource("https://api.example.com", "{}");
+    unhashed.content_hash = None;
+
+    let changes = detect_content_changes(
+        &previous,
+        &[
+            resource("https://www.example.com", LOGIN_PAGE_SECOND),
+            resource("https://blog.example.com", "<h1>Blog</h1><p>New post</p>"),
+            resource("https://new.example.com", "<h1>New</h1>"),
+            unhashed,
+        ],
+    );
+
+    assert_eq!(
+        changes,
+        vec![ContentChange {
+            url: "https://blog.example.com".to_string(),
+            previous_hash: content_hash("<h1>Blog</h1>"),
+            current_hash: content_hash("<h1>Blog</h1><p>New post</p>"),
+        }]
+    );
+}
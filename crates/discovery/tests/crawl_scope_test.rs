@@ -0,0 +1,139 @@
+use discovery::http_client::HttpClientConfig;
+use discovery::results::DiscoveryResult;
+use discovery::web_crawl::crawl_url_in_scope;
+use discovery::web_crawl::politeness::{HostRegistry, PolitenessConfig};
+use discovery::web_crawl::scope::ScopeConfig;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// Start page of the crawls, linking to a page on the same host, a
+/// subdomain and an unrelated domain
+const START_PAGE: &str = r#"<title>Home</title>
+<a href="/about">About</a>
+<a href="http://blog.example.test/">Blog</a>
+<a href="http://partner.test/">Partner</a>
+<a href="mailto:security@example.test">Contact</a>"#;
+
+/// Start a forward HTTP proxy on a random local port that serves the start
+/// page for `http://example.test/` and an empty page for anything else,
+/// returning the port and a receiver for the URL of each request. Going
+/// through it lets the crawl reach made-up domains.
+async fn start_proxy() -> (u16, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let url = request
+                .lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or_default()
+                .to_string();
+
+            let body = if url == "http://example.test/" {
+                START_PAGE
+            } else {
+                "<title>Page</title>"
+            };
+            let _ = request_tx.send(url);
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (port, request_rx)
+}
+
+/// Crawl `http://example.test/` within `scope`, returning the result and the
+/// URLs that were fetched
+async fn crawl(scope: ScopeConfig) -> (DiscoveryResult, Vec<String>) {
+    let (port, mut requests) = start_proxy().await;
+    let http = HttpClientConfig::default().with_proxy(&format!("http://127.0.0.1:{}", port));
+    let politeness = HostRegistry::new(PolitenessConfig {
+        max_concurrent_per_host: 1,
+        delay_ms: 0,
+    });
+
+    let result = crawl_url_in_scope(
+        "http://example.test/",
+        &scope,
+        &http,
+        &politeness,
+        &CancellationToken::new(),
+    )
+    .await
+    .unwrap();
+
+    let mut fetched = Vec::new();
+    while let Ok(url) = requests.try_recv() {
+        fetched.push(url);
+    }
+    fetched.sort();
+    (result, fetched)
+}
+
+#[tokio::test]
+async fn test_crawl_stays_on_start_host() {
+    let (result, fetched) = crawl(ScopeConfig::new(1)).await;
+
+    assert_eq!(
+        fetched,
+        vec!["http://example.test/", "http://example.test/about"]
+    );
+    assert_eq!(
+        result.out_of_scope_urls,
+        vec!["http://blog.example.test/", "http://partner.test/"]
+    );
+}
+
+#[tokio::test]
+async fn test_crawl_follows_subdomains_when_enabled() {
+    let (result, fetched) = crawl(ScopeConfig::new(1).with_subdomains(true)).await;
+
+    assert_eq!(
+        fetched,
+        vec![
+            "http://blog.example.test/",
+            "http://example.test/",
+            "http://example.test/about"
+        ]
+    );
+    assert_eq!(result.out_of_scope_urls, vec!["http://partner.test/"]);
+}
+
+#[tokio::test]
+async fn test_crawl_depth_zero_fetches_only_start_page() {
+    let (result, fetched) = crawl(ScopeConfig::new(0)).await;
+
+    assert_eq!(fetched, vec!["http://example.test/"]);
+    assert_eq!(result.web_resources.len(), 1);
+    assert!(result.out_of_scope_urls.is_empty());
+}
+
+#[test]
+fn test_allowlist_replaces_start_host() {
+    let start = Url::parse("https://example.test/").unwrap();
+    let scope = ScopeConfig::new(1).with_allowed_domains(vec!["Partner.test.".to_string()]);
+
+    assert!(scope.in_scope(&start, &Url::parse("https://partner.test/a").unwrap()));
+    assert!(!scope.in_scope(&start, &Url::parse("https://example.test/").unwrap()));
+    assert!(!scope.in_scope(&start, &Url::parse("https://www.partner.test/").unwrap()));
+
+    let scope = scope.with_subdomains(true);
+    assert!(scope.in_scope(&start, &Url::parse("https://www.partner.test/").unwrap()));
+    assert!(!scope.in_scope(&start, &Url::parse("https://notpartner.test/").unwrap()));
+    assert!(!scope.in_scope(&start, &Url::parse("ftp://partner.test/").unwrap()));
+}
@@ -0,0 +1,255 @@
+mod common;
+
+use common::start_http_server;
+use discovery::cert_transparency::monitor_logs_at;
+use discovery::http_client::{http_get_with_retry, HttpClientConfig, DEFAULT_USER_AGENT};
+use discovery::web_crawl::politeness::HostRegistry;
+use discovery::web_crawl::scope::ScopeConfig;
+use discovery::web_crawl::{crawl_url_in_scope, crawl_url_with_config};
+use reqwest::{Client, StatusCode};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// Start an HTTP server on a random local port that answers with each of
+/// `failures` in turn and then with `200 OK` and `body`, returning the port
+/// and a count of the requests it got
+async fn start_flaky_server(
+    failures: &'static [&'static str],
+    body: &'static str,
+) -> (u16, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let count = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+
+            let n = count.fetch_add(1, Ordering::SeqCst);
+            let (status, body) = match failures.get(n) {
+                Some(status) => (*status, ""),
+                None => ("200 OK", body),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (port, requests)
+}
+
+/// The value of header `name` in a raw request head
+fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines().find_map(|line| {
+        let (header, value) = line.split_once(':')?;
+        header.eq_ignore_ascii_case(name).then_some(value.trim())
+    })
+}
+
+fn browser_config() -> HttpClientConfig {
+    HttpClientConfig {
+        user_agent: "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0"
+            .to_string(),
+        ..HttpClientConfig::default()
+    }
+    .with_header("X-Scan-Id", "assessment-42")
+}
+
+#[test]
+fn test_default_config_identifies_as_the_bot() {
+    let config = HttpClientConfig::default();
+
+    assert_eq!(config.user_agent, DEFAULT_USER_AGENT);
+    assert!(config.extra_headers.is_empty());
+    assert_eq!(config.proxy, None);
+}
+
+#[test]
+fn test_invalid_settings_are_rejected() {
+    let bad_header = HttpClientConfig::default().with_header("Bad Header", "value");
+    assert!(bad_header.client_builder().is_err());
+
+    let bad_proxy = HttpClientConfig::default().with_proxy("not a proxy url");
+    assert!(bad_proxy.client_builder().is_err());
+
+    let socks = HttpClientConfig::default().with_proxy("socks5h://127.0.0.1:1080");
+    assert!(socks.client_builder().is_ok());
+}
+
+#[tokio::test]
+async fn test_crawler_sends_configured_user_agent_and_headers() {
+    let (port, mut requests) =
+        start_http_server("200 OK", "text/html", "<title>Home</title>").await;
+
+    let result = crawl_url_in_scope(
+        &format!("http://127.0.0.1:{}/", port),
+        &ScopeConfig::new(0).with_internal_addresses(true),
+        &browser_config(),
+        HostRegistry::shared(),
+        &CancellationToken::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.web_resources.len(), 1);
+
+    let request = requests.recv().await.unwrap();
+    assert_eq!(
+        header(&request.head, "user-agent"),
+        Some("Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0")
+    );
+    assert_eq!(header(&request.head, "x-scan-id"), Some("assessment-42"));
+}
+
+#[tokio::test]
+async fn test_ct_client_sends_configured_user_agent_and_headers() {
+    let (port, mut requests) = start_http_server(
+        "200 OK",
+        "application/json",
+        r#"[{"common_name": "www.example.com", "name_value": "www.example.com\\napi.example.com"}]"#,
+    )
+    .await;
+
+    let result = monitor_logs_at(
+        &format!("http://127.0.0.1:{}/", port),
+        "example.com",
+        &browser_config(),
+    )
+    .await
+    .unwrap();
+
+    let mut domains: Vec<&str> = result
+        .domains
+        .iter()
+        .map(|d| d.domain_name.as_str())
+        .collect();
+    domains.sort();
+    assert_eq!(domains, vec!["api.example.com", "www.example.com"]);
+
+    let request = requests.recv().await.unwrap();
+    assert!(request
+        .head
+        .starts_with("GET /?q=example.com&output=json HTTP/1.1"));
+    assert_eq!(header(&request.head, "x-scan-id"), Some("assessment-42"));
+    assert!(header(&request.head, "user-agent")
+        .unwrap()
+        .starts_with("Mozilla/5.0"));
+}
+
+#[tokio::test]
+async fn test_requests_go_through_configured_proxy() {
+    let (port, mut requests) =
+        start_http_server("200 OK", "text/html", "<title>Proxied</title>").await;
+    let config = browser_config().with_proxy(&format!("http://127.0.0.1:{}", port));
+
+    let result = crawl_url_with_config(
+        "http://crawl.example.test/",
+        0,
+        &config,
+        &CancellationToken::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.web_resources[0].title.as_deref(), Some("Proxied"));
+
+    // A forward proxy is asked for the absolute URL
+    let request = requests.recv().await.unwrap();
+    assert!(request
+        .head
+        .starts_with("GET http://crawl.example.test/ HTTP/1.1"));
+    assert_eq!(header(&request.head, "x-scan-id"), Some("assessment-42"));
+}
+
+#[tokio::test]
+async fn test_retry_succeeds_after_server_errors() {
+    let (port, requests) =
+        start_flaky_server(&["503 Service Unavailable", "429 Too Many Requests"], "ok").await;
+
+    let response = http_get_with_retry(&Client::new(), &format!("http://127.0.0.1:{}/", port), 2)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "ok");
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_retry_gives_up_with_last_response() {
+    let (port, requests) =
+        start_flaky_server(&["500 Internal Server Error", "502 Bad Gateway"], "ok").await;
+
+    let response = http_get_with_retry(&Client::new(), &format!("http://127.0.0.1:{}/", port), 1)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() {
+    let (port, requests) = start_flaky_server(&["404 Not Found"], "ok").await;
+
+    let response = http_get_with_retry(&Client::new(), &format!("http://127.0.0.1:{}/", port), 3)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_retry_survives_refused_connections() {
+    // Nothing listens on the port until shortly after the first attempt
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let _ = stream.read(&mut buf).await;
+        let _ = stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nup")
+            .await;
+    });
+
+    let response = http_get_with_retry(&Client::new(), &format!("http://127.0.0.1:{}/", port), 3)
+        .await
+        .unwrap();
+
+    assert_eq!(response.text().await.unwrap(), "up");
+}
+
+#[tokio::test]
+async fn test_ct_client_retries_failed_queries() {
+    let (port, requests) = start_flaky_server(
+        &["502 Bad Gateway"],
+        r#"[{"common_name": "www.example.com", "name_value": null}]"#,
+    )
+    .await;
+
+    let result = monitor_logs_at(
+        &format!("http://127.0.0.1:{}/", port),
+        "example.com",
+        &HttpClientConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.domains.len(), 1);
+    assert_eq!(result.domains[0].domain_name, "www.example.com");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
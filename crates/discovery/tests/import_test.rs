@@ -0,0 +1,236 @@
+use discovery::import::nmap::parse_nmap_xml;
+use discovery::import::{parse_subdomain_output, SubdomainTool};
+use discovery::port_scan::DiscoveredPort;
+use discovery::results::DiscoveryResult;
+use shared::types::PortStatus;
+use std::net::IpAddr;
+
+const SUBFINDER_PLAIN: &str = include_str!("fixtures/import/subfinder.txt");
+const SUBFINDER_JSON: &str = include_str!("fixtures/import/subfinder.jsonl");
+const AMASS_PLAIN: &str = include_str!("fixtures/import/amass.txt");
+const AMASS_JSON: &str = include_str!("fixtures/import/amass.jsonl");
+const NMAP_XML: &str = include_str!("fixtures/import/nmap.xml");
+
+fn domain_names(result: &DiscoveryResult) -> Vec<&str> {
+    result
+        .domains
+        .iter()
+        .map(|d| d.domain_name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_subfinder_plain_output() {
+    let result = parse_subdomain_output(SubdomainTool::Subfinder, SUBFINDER_PLAIN).unwrap();
+
+    assert_eq!(
+        domain_names(&result),
+        vec![
+            "www.example.com",
+            "api.example.com",
+            "mail.example.com",
+            "dev.example.com"
+        ]
+    );
+    assert!(result
+        .domains
+        .iter()
+        .all(|d| d.source == "import_subfinder"));
+    assert!(result.ip_addresses.is_empty());
+}
+
+#[test]
+fn test_subfinder_json_output() {
+    let result = parse_subdomain_output(SubdomainTool::Subfinder, SUBFINDER_JSON).unwrap();
+
+    assert_eq!(
+        domain_names(&result),
+        vec!["www.example.com", "api.example.com", "cdn.example.com"]
+    );
+    assert!(result
+        .domains
+        .iter()
+        .all(|d| d.source == "import_subfinder"));
+}
+
+#[test]
+fn test_amass_plain_output() {
+    let result = parse_subdomain_output(SubdomainTool::Amass, AMASS_PLAIN).unwrap();
+
+    assert_eq!(
+        domain_names(&result),
+        vec![
+            "www.example.com",
+            "api.example.com",
+            "edge.example.net",
+            "vpn.example.com"
+        ]
+    );
+    assert!(result.domains.iter().all(|d| d.source == "import_amass"));
+}
+
+#[test]
+fn test_amass_json_output() {
+    let result = parse_subdomain_output(SubdomainTool::Amass, AMASS_JSON).unwrap();
+
+    assert_eq!(
+        domain_names(&result),
+        vec!["www.example.com", "api.example.com", "legacy.example.com"]
+    );
+    let ips: Vec<IpAddr> = result.ip_addresses.iter().map(|ip| ip.ip_address).collect();
+    assert_eq!(
+        ips,
+        vec![
+            "192.0.2.10".parse::<IpAddr>().unwrap(),
+            "192.0.2.11".parse().unwrap(),
+            "2001:db8::11".parse().unwrap()
+        ]
+    );
+    assert!(result
+        .ip_addresses
+        .iter()
+        .all(|ip| ip.source == "import_amass"));
+}
+
+#[test]
+fn test_malformed_input_is_rejected() {
+    let error = parse_subdomain_output(SubdomainTool::Subfinder, "www.example.com\n{\"host\":")
+        .unwrap_err();
+    assert!(format!("{:#}", error).contains("line 2"));
+
+    assert!(parse_subdomain_output(SubdomainTool::Amass, "not a host name").is_err());
+    assert!(parse_subdomain_output(SubdomainTool::Subfinder, "\n\n")
+        .unwrap()
+        .domains
+        .is_empty());
+}
+
+fn nmap_port(
+    ip: &str,
+    port: u16,
+    protocol: &str,
+    status: &str,
+    service_name: Option<&str>,
+    banner: Option<&str>,
+) -> DiscoveredPort {
+    DiscoveredPort {
+        ip_address: ip.parse().unwrap(),
+        port,
+        protocol: protocol.to_string(),
+        status: status.to_string(),
+        service_name: service_name.map(str::to_string),
+        banner: banner.map(str::to_string),
+        http_status: None,
+        http_title: None,
+        tls_info: None,
+        source: "import_nmap".to_string(),
+    }
+}
+
+#[test]
+fn test_nmap_xml_maps_hosts_and_ports() {
+    let result = parse_nmap_xml(NMAP_XML).unwrap();
+
+    // The host that was down is skipped
+    let ips: Vec<IpAddr> = result.ip_addresses.iter().map(|ip| ip.ip_address).collect();
+    assert_eq!(
+        ips,
+        vec![
+            "192.0.2.10".parse::<IpAddr>().unwrap(),
+            "192.0.2.20".parse().unwrap()
+        ]
+    );
+    // Only names given on the command line, not reverse DNS
+    assert_eq!(domain_names(&result), vec!["www.example.com"]);
+
+    assert_eq!(
+        result.ports,
+        vec![
+            nmap_port(
+                "192.0.2.10",
+                22,
+                "TCP",
+                "OPEN",
+                Some("ssh"),
+                Some("OpenSSH 9.6p1 Ubuntu 3ubuntu13 (Ubuntu Linux; protocol 2.0)")
+            ),
+            nmap_port(
+                "192.0.2.10",
+                80,
+                "TCP",
+                "OPEN",
+                Some("http"),
+                Some("nginx 1.25.3")
+            ),
+            nmap_port(
+                "192.0.2.10",
+                443,
+                "TCP",
+                "OPEN",
+                Some("ssl/http"),
+                Some("nginx")
+            ),
+            nmap_port(
+                "192.0.2.10",
+                8080,
+                "TCP",
+                "FILTERED",
+                Some("http-proxy"),
+                None
+            ),
+            nmap_port(
+                "192.0.2.20",
+                5432,
+                "TCP",
+                "OPEN",
+                Some("postgresql"),
+                Some("PostgreSQL DB 16.0 - 16.2")
+            ),
+            nmap_port(
+                "192.0.2.20",
+                53,
+                "UDP",
+                "OPEN|FILTERED",
+                Some("domain"),
+                None
+            ),
+            nmap_port(
+                "192.0.2.20",
+                3389,
+                "TCP",
+                "CLOSED",
+                Some("ms-wbt-server"),
+                None
+            ),
+        ]
+    );
+
+    let statuses: Vec<Option<PortStatus>> =
+        result.ports.iter().map(|port| port.port_status()).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            Some(PortStatus::Open),
+            Some(PortStatus::Open),
+            Some(PortStatus::Open),
+            Some(PortStatus::Filtered),
+            Some(PortStatus::Open),
+            Some(PortStatus::Filtered),
+            Some(PortStatus::Closed),
+        ]
+    );
+    assert_eq!(
+        result.metadata.get("nmap_args").map(String::as_str),
+        Some("nmap -sV -oX nmap.xml www.example.com 192.0.2.20-21")
+    );
+}
+
+#[test]
+fn test_invalid_nmap_xml_is_rejected() {
+    assert!(parse_nmap_xml("<nmaprun><host>").is_err());
+    assert!(parse_nmap_xml("<?xml version=\"1.0\"?><html></html>").is_err());
+    assert!(parse_nmap_xml("<nmaprun></nmaprun>")
+        .unwrap()
+        .ports
+        .is_empty());
+}
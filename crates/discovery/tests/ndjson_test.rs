@@ -0,0 +1,149 @@
+use discovery::asn::AsnInfo;
+use discovery::ndjson::NdjsonWriter;
+use discovery::port_scan::DiscoveredPort;
+use discovery::results::{
+    DiscoveredDomain, DiscoveredIp, DiscoveredWebResource, DiscoveryResult, TechnologyFinding,
+};
+use discovery::whois::WhoisInfo;
+use serde_json::Value;
+use uuid::Uuid;
+
+fn sample_result() -> DiscoveryResult {
+    let mut result = DiscoveryResult::new();
+    result.domains.push(DiscoveredDomain {
+        domain_name: "www.example.com".to_string(),
+        source: "dns_enum".to_string(),
+    });
+    result.domains.push(DiscoveredDomain {
+        domain_name: "api.example.com".to_string(),
+        source: "certificate_transparency".to_string(),
+    });
+    result.ip_addresses.push(DiscoveredIp {
+        ip_address: "192.0.2.1".parse().unwrap(),
+        source: "dns_lookup".to_string(),
+    });
+    result.ports.push(DiscoveredPort {
+        ip_address: "192.0.2.1".parse().unwrap(),
+        port: 443,
+        protocol: "TCP".to_string(),
+        status: "OPEN".to_string(),
+        service_name: Some("HTTPS".to_string()),
+        banner: None,
+        http_status: Some(200),
+        http_title: Some("Example".to_string()),
+        tls_info: None,
+        source: "port_scan".to_string(),
+    });
+    result.web_resources.push(DiscoveredWebResource {
+        url: "https://www.example.com/".to_string(),
+        status_code: 200,
+        title: Some("Example".to_string()),
+        technologies: vec!["Nginx".to_string()],
+        source: "web_crawl".to_string(),
+        screenshot_path: None,
+        content_hash: Some("abc123".to_string()),
+    });
+    result.technologies.push(TechnologyFinding {
+        asset_id: Uuid::new_v4(),
+        name: "Nginx".to_string(),
+        version: Some("1.25.3".to_string()),
+        category: Some("Web Server".to_string()),
+        evidence: "Server header".to_string(),
+    });
+    result.add_whois(
+        "example.com",
+        WhoisInfo {
+            registrar: Some("Example Registrar".to_string()),
+            nameservers: vec!["ns1.example.com".to_string()],
+            ..WhoisInfo::default()
+        },
+    );
+    result.asn.insert(
+        "192.0.2.1".parse().unwrap(),
+        AsnInfo {
+            asn: 64496,
+            network: "192.0.2.0/24".to_string(),
+            organization: "EXAMPLE-NET".to_string(),
+            country: Some("US".to_string()),
+        },
+    );
+    result
+        .metadata
+        .insert("scanner".to_string(), "test".to_string());
+    result
+}
+
+#[test]
+fn test_json_round_trip() {
+    let result = sample_result();
+
+    let json = result.to_json().unwrap();
+    let parsed = DiscoveryResult::from_json(&json).unwrap();
+
+    assert_eq!(parsed.domains, result.domains);
+    assert_eq!(parsed.ip_addresses, result.ip_addresses);
+    assert_eq!(parsed.ports, result.ports);
+    assert_eq!(parsed.web_resources, result.web_resources);
+    assert_eq!(parsed.technologies, result.technologies);
+    assert_eq!(parsed.whois, result.whois);
+    assert_eq!(parsed.asn, result.asn);
+    assert_eq!(parsed.metadata, result.metadata);
+    assert_eq!(
+        serde_json::from_str::<Value>(&parsed.to_json().unwrap()).unwrap(),
+        serde_json::from_str::<Value>(&json).unwrap()
+    );
+}
+
+#[test]
+fn test_ndjson_has_one_line_per_entity() {
+    let result = sample_result();
+
+    let mut writer = NdjsonWriter::new(Vec::new());
+    let written = writer.write_result(&result).unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    // Two domains, an IP, a port, a web resource, a technology, WHOIS and ASN
+    assert_eq!(result.entity_count(), 8);
+    assert_eq!(written, result.entity_count());
+    assert_eq!(output.lines().count(), result.entity_count());
+    assert!(output.ends_with('\n'));
+
+    let records: Vec<Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let kinds: Vec<&str> = records
+        .iter()
+        .map(|record| record["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "ip",
+            "domain",
+            "domain",
+            "port",
+            "web_resource",
+            "technology",
+            "whois",
+            "asn"
+        ]
+    );
+
+    // Records carry the entity's own fields next to the kind
+    assert_eq!(records[1]["domain_name"], "www.example.com");
+    assert_eq!(records[3]["port"], 443);
+    assert_eq!(records[3]["http_title"], "Example");
+    assert_eq!(records[6]["domain"], "example.com");
+    assert_eq!(records[6]["registrar"], "Example Registrar");
+    assert_eq!(records[7]["ip"], "192.0.2.1");
+    assert_eq!(records[7]["asn"], 64496);
+}
+
+#[test]
+fn test_empty_result_writes_nothing() {
+    let mut writer = NdjsonWriter::new(Vec::new());
+
+    assert_eq!(writer.write_result(&DiscoveryResult::new()).unwrap(), 0);
+    assert!(writer.into_inner().is_empty());
+}
@@ -48,6 +48,11 @@ async fn test_nuclei_via_task() {
             templates: Some(vec!["cves".to_string()]), // Only use CVE templates
             ..Default::default()
         }),
+        screenshot_dir: None,
+        secret_allowlist: Vec::new(),
+        probe_paths: None,
+        crawl_scope: Default::default(),
+        http: Default::default(),
     };
 
     match task.execute().await {
@@ -0,0 +1,195 @@
+use discovery::output::{emit_result, NdjsonFileSink, WebhookSink};
+use discovery::port_scan::DiscoveredPort;
+use discovery::results::{DiscoveredDomain, DiscoveredIp, DiscoveredWebResource, DiscoveryResult};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+fn sample_result() -> DiscoveryResult {
+    let mut result = DiscoveryResult::new();
+    result.domains.push(DiscoveredDomain {
+        domain_name: "www.example.com".to_string(),
+        source: "dns_enum".to_string(),
+    });
+    result.ip_addresses.push(DiscoveredIp {
+        ip_address: "192.0.2.1".parse().unwrap(),
+        source: "dns_lookup".to_string(),
+    });
+    result.ports.push(DiscoveredPort {
+        ip_address: "192.0.2.1".parse().unwrap(),
+        port: 443,
+        protocol: "TCP".to_string(),
+        status: "OPEN".to_string(),
+        service_name: Some("HTTPS".to_string()),
+        banner: None,
+        http_status: None,
+        http_title: None,
+        tls_info: None,
+        source: "port_scan".to_string(),
+    });
+    result.web_resources.push(DiscoveredWebResource {
+        url: "https://www.example.com/".to_string(),
+        status_code: 200,
+        title: Some("Example".to_string()),
+        technologies: vec!["Nginx".to_string()],
+        source: "web_crawl".to_string(),
+        screenshot_path: None,
+        content_hash: None,
+    });
+    result
+}
+
+/// Read one HTTP request from `stream`, returning its request line and body
+async fn read_request(stream: &mut TcpStream) -> (String, Vec<u8>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed before the headers ended");
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let content_length: usize = headers
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse().unwrap())
+        })
+        .unwrap_or(0);
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed before the body ended");
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request_line = headers.lines().next().unwrap_or_default().to_string();
+    (
+        request_line,
+        buf[header_end..header_end + content_length].to_vec(),
+    )
+}
+
+/// Start a webhook on a random local port answering every request with
+/// `status`, returning its URL and the request line and JSON body of each
+/// request it received
+async fn start_webhook(status: &'static str) -> (String, Arc<Mutex<Vec<(String, Value)>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/ingest", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    tokio::spawn({
+        let received = received.clone();
+        async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let received = received.clone();
+                tokio::spawn(async move {
+                    loop {
+                        let (request_line, body) = read_request(&mut stream).await;
+                        received
+                            .lock()
+                            .unwrap()
+                            .push((request_line, serde_json::from_slice(&body).unwrap()));
+                        let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+                        if stream.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                        // Wait for the next request on the kept-alive connection
+                        let mut peek = [0u8; 1];
+                        if stream.peek(&mut peek).await.unwrap_or(0) == 0 {
+                            return;
+                        }
+                    }
+                });
+            }
+        }
+    });
+
+    (url, received)
+}
+
+#[tokio::test]
+async fn test_webhook_sink_receives_one_event_per_entity() {
+    let (url, received) = start_webhook("200 OK").await;
+    let sink = WebhookSink::new(&url).unwrap();
+    let result = sample_result();
+    let organization_id = Uuid::new_v4();
+    let job_id = Uuid::new_v4();
+
+    let emitted = emit_result(&sink, organization_id, Some(job_id), &result)
+        .await
+        .unwrap();
+
+    assert_eq!(emitted, result.entity_count());
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), result.entity_count());
+    assert!(received
+        .iter()
+        .all(|(request_line, _)| request_line == "POST /ingest HTTP/1.1"));
+
+    let kinds: Vec<&str> = received
+        .iter()
+        .map(|(_, event)| event["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["ip", "domain", "port", "web_resource"]);
+
+    // Events carry where the entity was found next to its own fields
+    for (_, event) in received.iter() {
+        assert_eq!(event["organization_id"], organization_id.to_string());
+        assert_eq!(event["job_id"], job_id.to_string());
+        assert!(event["emitted_at"].is_string());
+    }
+    assert_eq!(received[1].1["domain_name"], "www.example.com");
+    assert_eq!(received[2].1["port"], 443);
+}
+
+#[tokio::test]
+async fn test_webhook_sink_skips_rejected_events() {
+    let (url, received) = start_webhook("503 Service Unavailable").await;
+    let sink = WebhookSink::new(&url).unwrap();
+    let result = sample_result();
+
+    // A failing webhook doesn't fail the caller, it just isn't counted
+    let emitted = emit_result(&sink, Uuid::new_v4(), None, &result)
+        .await
+        .unwrap();
+
+    assert_eq!(emitted, 0);
+    assert_eq!(received.lock().unwrap().len(), result.entity_count());
+}
+
+#[test]
+fn test_webhook_sink_rejects_non_http_urls() {
+    assert!(WebhookSink::new("ftp://siem.example.com/ingest").is_err());
+    assert!(WebhookSink::new("not a url").is_err());
+}
+
+#[tokio::test]
+async fn test_file_sink_appends_one_line_per_entity() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("discovery.ndjson");
+    let result = sample_result();
+
+    // Reopening the file appends rather than truncating
+    for _ in 0..2 {
+        let sink = NdjsonFileSink::open(&path).await.unwrap();
+        let emitted = emit_result(&sink, Uuid::new_v4(), None, &result)
+            .await
+            .unwrap();
+        assert_eq!(emitted, result.entity_count());
+    }
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), 2 * result.entity_count());
+    for line in contents.lines() {
+        let event: Value = serde_json::from_str(line).unwrap();
+        assert!(event["kind"].is_string());
+        assert!(event["job_id"].is_null());
+    }
+}
@@ -0,0 +1,177 @@
+use discovery::path_probe::{default_paths, PathProber, ProbePath};
+use discovery::results::{DiscoveredWebResource, DiscoveryResult};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// An exposed Laravel-style environment file
+const DOTENV: &str = include_str!("fixtures/path_probe/dotenv");
+/// A security.txt with a contact
+const SECURITY_TXT: &str = include_str!("fixtures/path_probe/security.txt");
+
+/// Start an HTTP server on a random local port that answers `/` and each
+/// of `routes` with a page, and everything else with `fallback`
+async fn start_server(
+    routes: &'static [(&'static str, &'static str)],
+    fallback: (&'static str, &'static str),
+) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+            let (status, body) = match routes.iter().find(|(route, _)| *route == path) {
+                Some((_, body)) => ("200 OK", *body),
+                None if path == "/" => ("200 OK", "<title>Home</title>"),
+                None => fallback,
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    port
+}
+
+fn web_resource(url: &str) -> DiscoveredWebResource {
+    DiscoveredWebResource {
+        url: url.to_string(),
+        status_code: 200,
+        title: None,
+        technologies: Vec::new(),
+        source: "web_crawl".to_string(),
+        screenshot_path: None,
+        content_hash: None,
+    }
+}
+
+#[test]
+fn test_default_paths() {
+    let paths = default_paths();
+    let ids: Vec<&str> = paths.iter().map(|p| p.id.as_str()).collect();
+    for id in [
+        "git-config",
+        "dotenv",
+        "apache-server-status",
+        "spring-actuator-health",
+        "security-txt",
+    ] {
+        assert!(ids.contains(&id), "missing {}", id);
+    }
+    assert!(paths.iter().all(|p| p.path.starts_with('/')));
+
+    // Informational paths are recorded but not flagged
+    let security_txt = paths.iter().find(|p| p.id == "security-txt").unwrap();
+    assert_eq!(security_txt.severity, None);
+}
+
+#[test]
+fn test_fingerprints_must_match() {
+    let dotenv = default_paths()
+        .into_iter()
+        .find(|p| p.id == "dotenv")
+        .unwrap();
+
+    assert!(dotenv.matches_response(200, DOTENV));
+    assert!(!dotenv.matches_response(200, "<html>Welcome</html>"));
+    assert!(!dotenv.matches_response(403, DOTENV));
+}
+
+#[tokio::test]
+async fn test_probe_flags_exposed_env_but_not_missing_git() {
+    let port = start_server(
+        &[
+            ("/.env", DOTENV),
+            ("/.well-known/security.txt", SECURITY_TXT),
+        ],
+        ("404 Not Found", "Not Found"),
+    )
+    .await;
+    let base = format!("http://127.0.0.1:{}", port);
+
+    let result = PathProber::new().unwrap().probe(&base).await.unwrap();
+
+    let mut found: Vec<&str> = result
+        .web_resources
+        .iter()
+        .map(|r| r.url.as_str())
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            format!("{}/.env", base),
+            format!("{}/.well-known/security.txt", base)
+        ]
+    );
+    assert!(result
+        .web_resources
+        .iter()
+        .all(|r| r.status_code == 200 && r.source == "path_probe"));
+
+    // Only the sensitive path is a vulnerability; .git/config isn't there
+    assert_eq!(result.raw_vulnerabilities.len(), 1);
+    let env = &result.raw_vulnerabilities[0];
+    assert_eq!(env.name, "Exposed environment file");
+    assert_eq!(env.severity, "high");
+    assert_eq!(env.template_id, "exposed-path-dotenv");
+    assert_eq!(env.target, "127.0.0.1");
+    assert_eq!(env.matched_at, format!("{}/.env", base));
+}
+
+#[tokio::test]
+async fn test_catch_all_pages_are_not_exposures() {
+    // Every path gets the same page, as single-page apps often do
+    let port = start_server(&[], ("200 OK", "<html><title>App</title></html>")).await;
+
+    let result = PathProber::new()
+        .unwrap()
+        .probe(&format!("http://127.0.0.1:{}/", port))
+        .await
+        .unwrap();
+
+    assert!(result.web_resources.is_empty());
+    assert!(result.raw_vulnerabilities.is_empty());
+}
+
+#[tokio::test]
+async fn test_probe_all_uses_custom_paths_once_per_host() {
+    let port = start_server(&[("/internal/debug", "debug=true")], ("404 Not Found", "")).await;
+    let paths = vec![ProbePath {
+        id: "debug-page".to_string(),
+        path: "/internal/debug".to_string(),
+        name: "Debug page".to_string(),
+        fingerprints: Vec::new(),
+        severity: Some("medium".to_string()),
+        description: None,
+    }];
+
+    let mut result = DiscoveryResult::new();
+    result
+        .web_resources
+        .push(web_resource(&format!("http://127.0.0.1:{}/", port)));
+    result
+        .web_resources
+        .push(web_resource(&format!("http://127.0.0.1:{}/about", port)));
+
+    let count = PathProber::with_paths(paths)
+        .unwrap()
+        .probe_all(&mut result)
+        .await;
+
+    assert_eq!(count, 1);
+    assert_eq!(
+        result.raw_vulnerabilities[0].template_id,
+        "exposed-path-debug-page"
+    );
+    assert_eq!(result.web_resources.len(), 3);
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use discovery::plan::{
+    plan_dns_enumeration, plan_port_scan, HostResolver, DNS_ENUMERATION_REQUESTS,
+};
+use discovery::port_scan::COMMON_PORTS;
+use discovery::scope::ScanScope;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Resolver answering from a fixed list and recording the names looked up
+struct StaticResolver {
+    addresses: Vec<IpAddr>,
+    lookups: Mutex<Vec<String>>,
+}
+
+impl StaticResolver {
+    fn new(addresses: &[&str]) -> Self {
+        Self {
+            addresses: addresses.iter().map(|ip| ip.parse().unwrap()).collect(),
+            lookups: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl HostResolver for StaticResolver {
+    async fn resolve(&self, host: &str) -> Result<Vec<IpAddr>> {
+        self.lookups.lock().unwrap().push(host.to_string());
+        Ok(self.addresses.clone())
+    }
+}
+
+#[tokio::test]
+async fn test_port_scan_plan_resolves_hostnames_and_skips_blocked_addresses() {
+    let scope = ScanScope::new(&["198.51.100.0/24".to_string()], false).unwrap();
+    let resolver = StaticResolver::new(&["203.0.113.7", "198.51.100.9", "fe80::1", "10.0.0.5"]);
+
+    let plan = plan_port_scan("www.example.com", Some(&[80, 443]), &scope, &resolver, None)
+        .await
+        .unwrap();
+
+    assert_eq!(*resolver.lookups.lock().unwrap(), vec!["www.example.com"]);
+    assert_eq!(plan.targets, vec!["203.0.113.7"]);
+    assert_eq!(plan.ports, vec![80, 443]);
+    assert_eq!(plan.estimated_requests, 2);
+
+    let skipped: Vec<(&str, &str)> = plan
+        .skipped
+        .iter()
+        .map(|skipped| (skipped.target.as_str(), skipped.reason.as_str()))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            ("198.51.100.9", "configured no-scan range (198.51.100.0/24)"),
+            ("fe80::1", "not a scannable address"),
+            ("10.0.0.5", "private network (10.0.0.0/8)"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_port_scan_plan_expands_cidr_ranges_without_lookups() {
+    let scope = ScanScope::new(&[], false).unwrap();
+    let resolver = StaticResolver::new(&[]);
+
+    let plan = plan_port_scan("203.0.113.0/29", None, &scope, &resolver, None)
+        .await
+        .unwrap();
+
+    assert!(resolver.lookups.lock().unwrap().is_empty());
+    assert_eq!(plan.targets.len(), 6);
+    assert_eq!(plan.targets[0], "203.0.113.1");
+    assert_eq!(plan.ports, COMMON_PORTS.to_vec());
+    assert_eq!(plan.estimated_requests, 6 * COMMON_PORTS.len());
+
+    // Ranges past the host limit are refused as a real scan would
+    assert!(
+        plan_port_scan("203.0.113.0/24", None, &scope, &resolver, Some(16))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_port_scan_plan_sends_nothing_to_the_target() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    // Loopback is internal, so only planned with private networks allowed
+    let scope = ScanScope::new(&[], true).unwrap();
+
+    let plan = plan_port_scan(
+        "127.0.0.1",
+        Some(&[port]),
+        &scope,
+        &StaticResolver::new(&[]),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(plan.targets, vec!["127.0.0.1"]);
+    assert_eq!(plan.estimated_requests, 1);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), listener.accept())
+            .await
+            .is_err(),
+        "planning must not connect to the target"
+    );
+}
+
+#[test]
+fn test_dns_enumeration_plan() {
+    let plan = plan_dns_enumeration(" example.com ");
+
+    assert_eq!(plan.targets, vec!["example.com"]);
+    assert!(plan.ports.is_empty());
+    assert_eq!(plan.estimated_requests, DNS_ENUMERATION_REQUESTS);
+    assert_eq!(
+        plan.summary(),
+        "Dry run: 1 targets, 0 ports, ~5 requests, 0 skipped"
+    );
+}
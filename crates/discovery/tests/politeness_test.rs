@@ -0,0 +1,101 @@
+mod common;
+
+use common::start_http_server;
+use discovery::http_client::HttpClientConfig;
+use discovery::web_crawl::crawl_url_in_scope;
+use discovery::web_crawl::politeness::{HostRegistry, PolitenessConfig};
+use discovery::web_crawl::scope::ScopeConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+#[test]
+fn test_default_politeness() {
+    let config = PolitenessConfig::default();
+    assert_eq!(config.max_concurrent_per_host, 2);
+    assert_eq!(config.delay_ms, 250);
+    assert_eq!(HostRegistry::shared().config(), &config);
+
+    let config: PolitenessConfig = serde_json::from_str(r#"{"delay_ms": 1000}"#).unwrap();
+    assert_eq!(config.max_concurrent_per_host, 2);
+    assert_eq!(config.delay_ms, 1000);
+}
+
+// Each crawl runs on its own worker so none holds up another's request
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_crawls_space_out_requests_to_a_host() {
+    let (port, mut requests) =
+        start_http_server("200 OK", "text/html", "<title>Page</title>").await;
+    let registry = Arc::new(HostRegistry::new(PolitenessConfig {
+        max_concurrent_per_host: 4,
+        delay_ms: 200,
+    }));
+
+    let crawls: Vec<_> = (0..3)
+        .map(|i| {
+            let registry = registry.clone();
+            let url = format!("http://127.0.0.1:{}/page{}", port, i);
+            tokio::spawn(async move {
+                crawl_url_in_scope(
+                    &url,
+                    &ScopeConfig::new(0).with_internal_addresses(true),
+                    &HttpClientConfig::default(),
+                    &registry,
+                    &CancellationToken::new(),
+                )
+                .await
+            })
+        })
+        .collect();
+    for crawl in crawls {
+        assert_eq!(crawl.await.unwrap().unwrap().web_resources.len(), 1);
+    }
+
+    let mut times = Vec::new();
+    while let Ok(request) = requests.try_recv() {
+        times.push(request.arrived_at);
+    }
+    times.sort();
+    assert_eq!(times.len(), 3);
+    for pair in times.windows(2) {
+        // Allow for the requests not arriving exactly as they were sent
+        assert!(
+            pair[1] - pair[0] >= Duration::from_millis(180),
+            "requests only {:?} apart",
+            pair[1] - pair[0]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_hosts_are_limited_to_concurrent_requests() {
+    let registry = HostRegistry::new(PolitenessConfig {
+        max_concurrent_per_host: 1,
+        delay_ms: 0,
+    });
+
+    let first = registry.acquire("www.example.com").await;
+    // The host is busy, whatever case it's written in
+    assert!(timeout(
+        Duration::from_millis(50),
+        registry.acquire("WWW.example.com")
+    )
+    .await
+    .is_err());
+    // Other hosts aren't held up
+    assert!(timeout(
+        Duration::from_millis(50),
+        registry.acquire("api.example.com")
+    )
+    .await
+    .is_ok());
+
+    drop(first);
+    assert!(timeout(
+        Duration::from_millis(50),
+        registry.acquire("www.example.com")
+    )
+    .await
+    .is_ok());
+}
@@ -0,0 +1,389 @@
+use discovery::port_scan::{
+    default_service_probes, effective_scan_type, expand_cidr, expand_cidr_with_limit, is_scannable,
+    load_service_probes, PortScanConfig, PortScanner, ScanType, ServiceProbe,
+};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_connect_scan_is_the_default() {
+    assert_eq!(PortScanConfig::default().scan_type, ScanType::Connect);
+
+    let config: PortScanConfig = serde_json::from_str(r#"{"scan_type": "syn"}"#).unwrap();
+    assert_eq!(config.scan_type, ScanType::Syn);
+    let config: PortScanConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.scan_type, ScanType::Connect);
+    assert_eq!(config.rate_limit, None);
+}
+
+#[test]
+fn test_connect_scan_type_is_kept() {
+    let ip: IpAddr = "127.0.0.1".parse().unwrap();
+    assert_eq!(
+        effective_scan_type(ScanType::Connect, ip),
+        ScanType::Connect
+    );
+}
+
+#[cfg(not(feature = "syn-scan"))]
+#[test]
+fn test_syn_scan_falls_back_without_the_feature() {
+    let ip: IpAddr = "127.0.0.1".parse().unwrap();
+    assert_eq!(effective_scan_type(ScanType::Syn, ip), ScanType::Connect);
+}
+
+#[test]
+fn test_syn_scan_falls_back_for_ipv6_targets() {
+    let ip: IpAddr = "::1".parse().unwrap();
+    assert_eq!(effective_scan_type(ScanType::Syn, ip), ScanType::Connect);
+}
+
+#[tokio::test]
+async fn test_syn_scan_reports_open_ports() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // Whether this ends up SYN or connect scanning depends on the build and
+    // our privileges; the results look the same either way
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        scan_type: ScanType::Syn,
+        ..PortScanConfig::default()
+    });
+    let result = scanner.scan_ip("127.0.0.1", Some(&[port])).await.unwrap();
+
+    let tcp = result
+        .ports
+        .iter()
+        .find(|p| p.port == port && p.protocol == "TCP")
+        .expect("TCP result for the listening port");
+    assert_eq!(tcp.status, "OPEN");
+    assert_eq!(tcp.ip_address, "127.0.0.1".parse::<IpAddr>().unwrap());
+}
+
+#[tokio::test]
+async fn test_rate_limit_paces_probes() {
+    // Nothing listens on these, so every probe returns right away
+    let ports: Vec<u16> = (1..=6).collect();
+    let rate = 20;
+
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        rate_limit: Some(rate),
+        ..PortScanConfig::default()
+    });
+    let started = Instant::now();
+    let result = scanner.scan_ip("127.0.0.1", Some(&ports)).await.unwrap();
+    let elapsed = started.elapsed();
+
+    // One TCP and one UDP probe per port, the first of which goes out at once
+    let probes = 2 * ports.len() as u32;
+    let minimum = Duration::from_secs(1) * (probes - 1) / rate;
+    assert!(
+        elapsed >= minimum,
+        "scan took {:?}, expected at least {:?}",
+        elapsed,
+        minimum
+    );
+    assert_eq!(result.ports.len(), probes as usize);
+}
+
+#[tokio::test]
+async fn test_rate_limit_covers_syn_and_udp_probes() {
+    // Slow enough that the SYN reply wait can't cover unpaced UDP probes
+    let ports: Vec<u16> = (1..=12).collect();
+    let rate = 4;
+
+    // Whether the TCP half is a SYN or connect scan depends on the build and
+    // our privileges; either way the UDP probes share its limit
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        scan_type: ScanType::Syn,
+        rate_limit: Some(rate),
+        ..PortScanConfig::default()
+    });
+    let started = Instant::now();
+    let result = scanner.scan_ip("127.0.0.1", Some(&ports)).await.unwrap();
+    let elapsed = started.elapsed();
+
+    let probes = 2 * ports.len() as u32;
+    let minimum = Duration::from_secs(1) * (probes - 1) / rate;
+    assert!(
+        elapsed >= minimum,
+        "scan took {:?}, expected at least {:?}",
+        elapsed,
+        minimum
+    );
+    assert!(result.ports.iter().any(|p| p.protocol == "UDP"));
+}
+
+#[tokio::test]
+async fn test_zero_rate_limit_is_rejected() {
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        rate_limit: Some(0),
+        ..PortScanConfig::default()
+    });
+
+    assert!(scanner.scan_ip("127.0.0.1", Some(&[1])).await.is_err());
+}
+
+#[test]
+fn test_cidr_expansion() {
+    let hosts = expand_cidr("10.0.0.0/30").unwrap();
+    assert_eq!(
+        hosts,
+        vec![
+            "10.0.0.1".parse::<IpAddr>().unwrap(),
+            "10.0.0.2".parse().unwrap()
+        ]
+    );
+
+    // Point-to-point links and single hosts have no network or broadcast
+    // address to leave out
+    assert_eq!(expand_cidr("10.0.0.4/31").unwrap().len(), 2);
+    assert_eq!(
+        expand_cidr("192.0.2.77/32").unwrap(),
+        vec!["192.0.2.77".parse::<IpAddr>().unwrap()]
+    );
+    // Host bits of the address are ignored
+    assert_eq!(
+        expand_cidr("10.0.0.10/30").unwrap()[0].to_string(),
+        "10.0.0.9"
+    );
+    assert_eq!(expand_cidr("10.1.0.0/22").unwrap().len(), 1022);
+    assert_eq!(expand_cidr("2001:db8::/126").unwrap().len(), 4);
+}
+
+#[test]
+fn test_invalid_or_oversized_cidr_is_rejected() {
+    for cidr in [
+        "10.0.0.0/8",
+        "10.0.0.0/21",
+        "0.0.0.0/0",
+        "2001:db8::/64",
+        "::/0",
+        "10.0.0.0/33",
+        "10.0.0/24",
+        "10.0.0.0/abc",
+    ] {
+        assert!(expand_cidr(cidr).is_err(), "{} should be rejected", cidr);
+    }
+}
+
+#[tokio::test]
+async fn test_scan_target_expands_cidr() {
+    // Listening on all interfaces answers on every loopback address
+    let listener = TcpListener::bind("0.0.0.0:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let result = PortScanner::new()
+        .scan_target("127.0.0.0/30", Some(&[port]))
+        .await
+        .unwrap();
+
+    let scanned: Vec<String> = result
+        .ip_addresses
+        .iter()
+        .map(|ip| ip.ip_address.to_string())
+        .collect();
+    assert_eq!(scanned, vec!["127.0.0.1", "127.0.0.2"]);
+    for ip in ["127.0.0.1", "127.0.0.2"] {
+        assert!(
+            result.ports.iter().any(|p| p.ip_address.to_string() == ip
+                && p.port == port
+                && p.protocol == "TCP"
+                && p.status == "OPEN"),
+            "{}:{} should be open",
+            ip,
+            port
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_scan_target_resolves_hostname() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // localhost comes from the hosts file, no DNS server needed
+    let result = PortScanner::new()
+        .scan_target("localhost", Some(&[port]))
+        .await
+        .unwrap();
+
+    assert!(result
+        .ports
+        .iter()
+        .any(|p| p.ip_address.to_string() == "127.0.0.1"
+            && p.port == port
+            && p.protocol == "TCP"
+            && p.status == "OPEN"));
+}
+
+#[tokio::test]
+async fn test_scan_target_rejects_oversized_cidr() {
+    let started = Instant::now();
+    let result = PortScanner::new()
+        .scan_target("10.0.0.0/8", Some(&[80]))
+        .await;
+
+    assert!(result.is_err());
+    // Rejected before anything is scanned
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_default_service_probes_are_valid() {
+    let probes = default_service_probes();
+    assert!(!probes.is_empty());
+    for probe in &probes {
+        assert!(
+            regex::Regex::new(&probe.match_regex).is_ok(),
+            "{} has an invalid match_regex",
+            probe.service
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_custom_probe_identifies_service() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // Only answers once it has been greeted, and is accepted once for the
+    // connect scan and once for the banner grab
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(async move {
+                let mut greeting = vec![0; 64];
+                let n = socket.read(&mut greeting).await.unwrap_or(0);
+                if &greeting[..n] == b"HELLO\r\n" {
+                    let _ = socket.write_all(b"+OK Widgetd 3.1 ready\r\n").await;
+                }
+            });
+        }
+    });
+
+    let mut service_probes = default_service_probes();
+    service_probes.push(ServiceProbe {
+        ports: vec![port],
+        send: "HELLO\r\n".to_string(),
+        match_regex: r"^\+OK Widgetd \d+\.\d+".to_string(),
+        service: "Widgetd".to_string(),
+    });
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        service_probes: Some(service_probes),
+        ..PortScanConfig::default()
+    });
+    let result = scanner.scan_ip("127.0.0.1", Some(&[port])).await.unwrap();
+
+    let tcp = result
+        .ports
+        .iter()
+        .find(|p| p.port == port && p.protocol == "TCP")
+        .expect("TCP result for the listening port");
+    assert_eq!(tcp.status, "OPEN");
+    assert_eq!(tcp.service_name.as_deref(), Some("Widgetd"));
+    assert_eq!(tcp.banner.as_deref(), Some("+OK Widgetd 3.1 ready\r\n"));
+}
+
+#[tokio::test]
+async fn test_invalid_probe_regex_is_rejected() {
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        service_probes: Some(vec![ServiceProbe {
+            ports: vec![],
+            send: String::new(),
+            match_regex: "(unclosed".to_string(),
+            service: "Broken".to_string(),
+        }]),
+        ..PortScanConfig::default()
+    });
+
+    assert!(scanner.scan_ip("127.0.0.1", Some(&[1])).await.is_err());
+}
+
+#[test]
+fn test_service_probes_load_from_file() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        file.path(),
+        r#"[{"ports": [7000], "send": "PING\r\n", "match_regex": "PONG", "service": "Pinger"}]"#,
+    )
+    .unwrap();
+
+    let probes = load_service_probes(file.path()).unwrap();
+    assert_eq!(
+        probes,
+        vec![ServiceProbe {
+            ports: vec![7000],
+            send: "PING\r\n".to_string(),
+            match_regex: "PONG".to_string(),
+            service: "Pinger".to_string(),
+        }]
+    );
+
+    std::fs::write(file.path(), "not json").unwrap();
+    assert!(load_service_probes(file.path()).is_err());
+}
+
+#[test]
+fn test_link_local_and_unspecified_ipv6_are_skipped() {
+    assert!(!is_scannable("fe80::1".parse().unwrap()));
+    assert!(!is_scannable("febf::1".parse().unwrap()));
+    assert!(!is_scannable("::".parse().unwrap()));
+    assert!(is_scannable("2001:db8::1".parse().unwrap()));
+    assert!(is_scannable("::1".parse().unwrap()));
+    assert!(is_scannable("169.254.0.1".parse().unwrap()));
+
+    assert!(expand_cidr("fe80::/126").unwrap().is_empty());
+    assert!(expand_cidr("::/128").unwrap().is_empty());
+    assert_eq!(
+        expand_cidr("::/126").unwrap(),
+        vec![
+            "::1".parse::<IpAddr>().unwrap(),
+            "::2".parse().unwrap(),
+            "::3".parse().unwrap()
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_scan_target_rejects_link_local_targets() {
+    for target in ["fe80::1", "fe80::/120", "::", "::/128"] {
+        let started = Instant::now();
+        let result = PortScanner::new().scan_target(target, Some(&[80])).await;
+
+        assert!(result.is_err(), "{} should be rejected", target);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn test_ipv6_range_over_host_cap_is_rejected() {
+    assert_eq!(
+        expand_cidr_with_limit("2001:db8::/120", 256).unwrap().len(),
+        256
+    );
+
+    let err = expand_cidr_with_limit("2001:db8::/119", 256).unwrap_err();
+    assert!(err.to_string().contains("at most 256 hosts"));
+
+    // Refused from the prefix alone, without listing any hosts
+    let started = Instant::now();
+    assert!(expand_cidr_with_limit("2001:db8::/32", usize::MAX).is_err());
+    assert!(expand_cidr_with_limit("::/0", usize::MAX).is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_scan_target_uses_configured_host_cap() {
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        max_target_hosts: Some(2),
+        ..PortScanConfig::default()
+    });
+
+    let result = scanner.scan_target("2001:db8::/126", Some(&[80])).await;
+    assert!(result.is_err());
+}
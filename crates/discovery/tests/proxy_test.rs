@@ -0,0 +1,269 @@
+use discovery::http_client::HttpClientConfig;
+use discovery::path_probe::ProbePath;
+use discovery::port_scan::{PortScanConfig, PortScanner};
+use discovery::shodan::ShodanClient;
+use discovery::takeover::{default_fingerprints, TakeoverChecker};
+use discovery::tasks::{DiscoveryTask, DiscoveryTaskType};
+use discovery::web_crawl::scope::ScopeConfig;
+use discovery::whois::RdapClient;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Page GitHub Pages serves for a custom domain no repository claims
+const GITHUB_PAGES_UNCLAIMED: &str = include_str!("fixtures/takeover/github-pages-unclaimed.html");
+
+/// Start a forward HTTP proxy on a random local port that answers every
+/// request itself with `status` and `body`, returning the port and a
+/// receiver for the request line of each request
+async fn start_http_proxy(
+    status: &'static str,
+    body: &'static str,
+) -> (u16, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let _ = request_tx.send(request.lines().next().unwrap_or_default().to_string());
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (port, request_rx)
+}
+
+/// Start a SOCKS5 proxy on a random local port that pretends to reach an
+/// SSH server on port 22 of every host, gets refused on port 23 and can't
+/// route anywhere else. Returns the port and a receiver for each requested
+/// destination.
+async fn start_socks_proxy() -> (u16, mpsc::UnboundedReceiver<SocketAddrV4>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (target_tx, target_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let target_tx = target_tx.clone();
+            tokio::spawn(async move {
+                let _ = serve_socks_client(stream, target_tx).await;
+            });
+        }
+    });
+
+    (port, target_rx)
+}
+
+async fn serve_socks_client(
+    mut stream: TcpStream,
+    target_tx: mpsc::UnboundedSender<SocketAddrV4>,
+) -> std::io::Result<()> {
+    // Greeting: version, method count, methods. We take "no auth".
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).await?;
+    stream.write_all(&[5, 0]).await?;
+
+    // CONNECT to an IPv4 address: version, command, reserved, type, address, port
+    let mut request = [0u8; 10];
+    stream.read_exact(&mut request).await?;
+    let ip = Ipv4Addr::new(request[4], request[5], request[6], request[7]);
+    let port = u16::from_be_bytes([request[8], request[9]]);
+    let _ = target_tx.send(SocketAddrV4::new(ip, port));
+
+    let reply = match port {
+        22 => 0, // Succeeded
+        23 => 5, // Connection refused
+        _ => 4,  // Host unreachable
+    };
+    stream
+        .write_all(&[5, reply, 0, 1, 0, 0, 0, 0, 0, 0])
+        .await?;
+    if reply == 0 {
+        stream.write_all(b"SSH-2.0-OpenSSH_9.6\r\n").await?;
+        let mut rest = Vec::new();
+        let _ = stream.read_to_end(&mut rest).await;
+    }
+    Ok(())
+}
+
+fn proxied(port: u16, scheme: &str) -> HttpClientConfig {
+    HttpClientConfig::default().with_proxy(&format!("{}://127.0.0.1:{}", scheme, port))
+}
+
+#[test]
+fn test_only_socks_proxies_carry_port_scans() {
+    assert_eq!(HttpClientConfig::default().socks_proxy(), None);
+    assert_eq!(proxied(3128, "http").socks_proxy(), None);
+    assert_eq!(
+        proxied(1080, "socks5h").socks_proxy(),
+        Some("socks5h://127.0.0.1:1080")
+    );
+}
+
+#[tokio::test]
+async fn test_rdap_lookup_goes_through_proxy() {
+    let (port, mut requests) = start_http_proxy("200 OK", r#"{"objectClassName": "domain"}"#).await;
+
+    let client =
+        RdapClient::with_config("http://rdap.example.test", &proxied(port, "http")).unwrap();
+    client.lookup("example.com").await.unwrap();
+
+    assert_eq!(
+        requests.recv().await.unwrap(),
+        "GET http://rdap.example.test/domain/example.com HTTP/1.1"
+    );
+}
+
+#[tokio::test]
+async fn test_shodan_lookup_goes_through_proxy() {
+    let (port, mut requests) = start_http_proxy(
+        "404 Not Found",
+        r#"{"error": "No information available for that IP."}"#,
+    )
+    .await;
+
+    let client = ShodanClient::from_api_key(Some("test-key"), &proxied(port, "http"))
+        .unwrap()
+        .unwrap();
+    // The default API URL is https, which a proxy is asked to tunnel to
+    assert!(client.lookup("192.0.2.1").await.is_err());
+    assert_eq!(
+        requests.recv().await.unwrap(),
+        "CONNECT api.shodan.io:443 HTTP/1.1"
+    );
+
+    let client =
+        ShodanClient::with_config("test-key", "http://api.shodan.test", &proxied(port, "http"))
+            .unwrap();
+    let result = client.host("192.0.2.1".parse().unwrap()).await.unwrap();
+    assert_eq!(result.entity_count(), 0);
+    assert_eq!(
+        requests.recv().await.unwrap(),
+        "GET http://api.shodan.test/shodan/host/192.0.2.1?key=test-key HTTP/1.1"
+    );
+}
+
+#[tokio::test]
+async fn test_takeover_check_goes_through_proxy() {
+    let (port, mut requests) = start_http_proxy("404 Not Found", GITHUB_PAGES_UNCLAIMED).await;
+
+    let checker =
+        TakeoverChecker::with_config(default_fingerprints(), &proxied(port, "http")).unwrap();
+    let finding = checker
+        .check("docs.example.test", "example.github.io")
+        .await
+        .unwrap();
+
+    assert!(finding.is_some());
+    assert_eq!(
+        requests.recv().await.unwrap(),
+        "GET http://docs.example.test/ HTTP/1.1"
+    );
+}
+
+#[tokio::test]
+async fn test_web_app_scan_probes_paths_through_proxy() {
+    let (port, mut requests) = start_http_proxy("200 OK", "{}").await;
+
+    let task = DiscoveryTask {
+        job_id: uuid::Uuid::new_v4(),
+        organization_id: uuid::Uuid::new_v4(),
+        task_type: DiscoveryTaskType::WebAppScan,
+        target: "http://app.example.test/".to_string(),
+        nuclei_params: None,
+        screenshot_dir: None,
+        secret_allowlist: Vec::new(),
+        probe_paths: Some(vec![ProbePath {
+            id: "env".to_string(),
+            path: "/.env".to_string(),
+            name: "Environment file".to_string(),
+            fingerprints: vec!["APP_KEY=".to_string()],
+            severity: Some("high".to_string()),
+            description: None,
+        }]),
+        crawl_scope: ScopeConfig::new(0),
+        http: proxied(port, "http"),
+    };
+    task.execute().await.unwrap();
+
+    let mut received = Vec::new();
+    while let Ok(request) = requests.try_recv() {
+        received.push(request);
+    }
+    assert!(
+        received.contains(&"GET http://app.example.test/.env HTTP/1.1".to_string()),
+        "{:?}",
+        received
+    );
+}
+
+#[tokio::test]
+async fn test_port_scan_goes_through_socks_proxy() {
+    let (port, mut targets) = start_socks_proxy().await;
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        socks_proxy: Some(format!("socks5://127.0.0.1:{}", port)),
+        ..PortScanConfig::default()
+    });
+
+    let result = scanner
+        .scan_ip("192.0.2.1", Some(&[22, 23, 8081]))
+        .await
+        .unwrap();
+
+    // UDP can't be proxied, so only TCP results come back
+    let mut ports: Vec<_> = result
+        .ports
+        .iter()
+        .map(|p| (p.port, p.protocol.as_str(), p.status.as_str()))
+        .collect();
+    ports.sort();
+    assert_eq!(
+        ports,
+        vec![
+            (22, "TCP", "OPEN"),
+            (23, "TCP", "CLOSED"),
+            (8081, "TCP", "FILTERED")
+        ]
+    );
+
+    // The banner was grabbed through the proxy too
+    let ssh = result.ports.iter().find(|p| p.port == 22).unwrap();
+    assert_eq!(
+        ssh.banner.as_deref().map(str::trim),
+        Some("SSH-2.0-OpenSSH_9.6")
+    );
+
+    let mut requested = Vec::new();
+    while let Ok(target) = targets.try_recv() {
+        requested.push(target);
+    }
+    assert!(requested
+        .iter()
+        .all(|target| *target.ip() == Ipv4Addr::new(192, 0, 2, 1)));
+    // Three probes plus the banner grab
+    assert_eq!(requested.len(), 4);
+}
+
+#[tokio::test]
+async fn test_port_scan_rejects_http_proxy() {
+    let scanner = PortScanner::new().with_config(PortScanConfig {
+        socks_proxy: Some("http://127.0.0.1:3128".to_string()),
+        ..PortScanConfig::default()
+    });
+
+    assert!(scanner.scan_ip("192.0.2.1", Some(&[22])).await.is_err());
+}
@@ -0,0 +1,195 @@
+use discovery::port_scan::DiscoveredPort;
+use discovery::results::{DiscoveredDomain, DiscoveredIp, DiscoveredWebResource, DiscoveryResult};
+use std::net::IpAddr;
+
+fn domain(name: &str, source: &str) -> DiscoveredDomain {
+    DiscoveredDomain {
+        domain_name: name.to_string(),
+        source: source.to_string(),
+    }
+}
+
+fn ip(address: &str, source: &str) -> DiscoveredIp {
+    DiscoveredIp {
+        ip_address: address.parse().unwrap(),
+        source: source.to_string(),
+    }
+}
+
+fn port(address: &str, number: u16, protocol: &str, status: &str, source: &str) -> DiscoveredPort {
+    DiscoveredPort {
+        ip_address: address.parse().unwrap(),
+        port: number,
+        protocol: protocol.to_string(),
+        status: status.to_string(),
+        service_name: None,
+        banner: None,
+        http_status: None,
+        http_title: None,
+        tls_info: None,
+        source: source.to_string(),
+    }
+}
+
+fn web_resource(url: &str, technologies: &[&str], source: &str) -> DiscoveredWebResource {
+    DiscoveredWebResource {
+        url: url.to_string(),
+        status_code: 200,
+        title: None,
+        technologies: technologies.iter().map(|t| t.to_string()).collect(),
+        source: source.to_string(),
+        screenshot_path: None,
+        content_hash: None,
+    }
+}
+
+#[test]
+fn test_merge_deduplicates_domains_and_ips() {
+    let mut result = DiscoveryResult::new();
+    result.domains.push(domain("www.example.com", "dns_enum"));
+    result.ip_addresses.push(ip("192.0.2.1", "dns_lookup"));
+
+    let mut other = DiscoveryResult::new();
+    other
+        .domains
+        .push(domain("WWW.Example.com.", "certificate_transparency"));
+    other.domains.push(domain("api.example.com", "dns_enum"));
+    other.ip_addresses.push(ip("192.0.2.1", "port_scan_target"));
+    other.ip_addresses.push(ip("192.0.2.2", "dns_lookup"));
+
+    result.merge(other);
+
+    assert_eq!(result.domains.len(), 2);
+    assert_eq!(result.domains[0].domain_name, "www.example.com");
+    assert_eq!(
+        result.domains[0].source,
+        "dns_enum,certificate_transparency"
+    );
+    assert_eq!(result.domains[1].domain_name, "api.example.com");
+
+    assert_eq!(result.ip_addresses.len(), 2);
+    assert_eq!(result.ip_addresses[0].source, "dns_lookup,port_scan_target");
+    assert_eq!(result.ip_addresses[1].source, "dns_lookup");
+}
+
+#[test]
+fn test_merge_combines_sources_once() {
+    let mut result = DiscoveryResult::new();
+    result.domains.push(domain("example.com", "dns_enum"));
+
+    for source in ["dns_enum", "certificate_transparency", "dns_enum,whois"] {
+        let mut other = DiscoveryResult::new();
+        other.domains.push(domain("example.com", source));
+        result.merge(other);
+    }
+
+    assert_eq!(result.domains.len(), 1);
+    assert_eq!(
+        result.domains[0].source,
+        "dns_enum,certificate_transparency,whois"
+    );
+}
+
+#[test]
+fn test_merge_deduplicates_ports_by_ip_port_and_protocol() {
+    let mut result = DiscoveryResult::new();
+    result
+        .ports
+        .push(port("192.0.2.1", 443, "TCP", "FILTERED", "port_scan"));
+    result
+        .ports
+        .push(port("192.0.2.1", 53, "UDP", "OPEN|FILTERED", "port_scan"));
+
+    let mut open = port("192.0.2.1", 443, "tcp", "OPEN", "naabu");
+    open.service_name = Some("HTTPS".to_string());
+    open.http_status = Some(200);
+    let mut other = DiscoveryResult::new();
+    other.ports.push(open);
+    // Same port number over another protocol is another port
+    other
+        .ports
+        .push(port("192.0.2.1", 443, "UDP", "CLOSED", "port_scan"));
+    other
+        .ports
+        .push(port("192.0.2.2", 443, "TCP", "OPEN", "naabu"));
+
+    result.merge(other);
+
+    assert_eq!(result.ports.len(), 4);
+    let https = &result.ports[0];
+    assert_eq!(https.status, "OPEN");
+    assert_eq!(https.source, "port_scan,naabu");
+    assert_eq!(https.service_name.as_deref(), Some("HTTPS"));
+    assert_eq!(https.http_status, Some(200));
+
+    let ips: Vec<(IpAddr, u16, &str)> = result
+        .ports
+        .iter()
+        .map(|p| (p.ip_address, p.port, p.protocol.as_str()))
+        .collect();
+    assert_eq!(ips[1], ("192.0.2.1".parse().unwrap(), 53, "UDP"));
+    assert_eq!(ips[2], ("192.0.2.1".parse().unwrap(), 443, "UDP"));
+    assert_eq!(ips[3], ("192.0.2.2".parse().unwrap(), 443, "TCP"));
+}
+
+#[test]
+fn test_merge_keeps_details_already_known() {
+    let mut known = port("192.0.2.1", 22, "TCP", "OPEN", "port_scan");
+    known.banner = Some("SSH-2.0-OpenSSH_9.6".to_string());
+    let mut result = DiscoveryResult::new();
+    result.ports.push(known);
+
+    let mut other = DiscoveryResult::new();
+    other
+        .ports
+        .push(port("192.0.2.1", 22, "TCP", "FILTERED", "rescan"));
+    result.merge(other);
+
+    assert_eq!(result.ports.len(), 1);
+    assert_eq!(result.ports[0].status, "OPEN");
+    assert_eq!(
+        result.ports[0].banner.as_deref(),
+        Some("SSH-2.0-OpenSSH_9.6")
+    );
+}
+
+#[test]
+fn test_merge_deduplicates_web_resources_by_url() {
+    let mut result = DiscoveryResult::new();
+    result.web_resources.push(web_resource(
+        "https://example.com/",
+        &["Nginx"],
+        "web_crawl",
+    ));
+
+    let mut crawled = web_resource("https://example.com/", &["Nginx", "React"], "port_scan");
+    crawled.title = Some("Example".to_string());
+    let mut other = DiscoveryResult::new();
+    other.web_resources.push(crawled);
+    other
+        .web_resources
+        .push(web_resource("https://example.com/login", &[], "web_crawl"));
+
+    result.merge(other);
+
+    assert_eq!(result.web_resources.len(), 2);
+    let home = &result.web_resources[0];
+    assert_eq!(home.source, "web_crawl,port_scan");
+    assert_eq!(home.technologies, vec!["Nginx", "React"]);
+    assert_eq!(home.title.as_deref(), Some("Example"));
+}
+
+#[test]
+fn test_merge_deduplicates_within_the_other_result() {
+    let mut other = DiscoveryResult::new();
+    other.domains.push(domain("example.com", "dns_enum"));
+    other.domains.push(domain("example.com", "dns_mx"));
+
+    let mut result = DiscoveryResult::new();
+    result.merge(other);
+
+    assert_eq!(
+        result.domains,
+        vec![domain("example.com", "dns_enum,dns_mx")]
+    );
+}
@@ -0,0 +1,140 @@
+use discovery::scope::{is_internal_address, IpRange, ScanScope};
+use std::net::IpAddr;
+
+fn scope_for(allowlist: &[&str]) -> ScanScope {
+    let allowlist: Vec<String> = allowlist.iter().map(|entry| entry.to_string()).collect();
+    ScanScope::new(&[], false)
+        .unwrap()
+        .with_allowlist(&allowlist)
+        .unwrap()
+}
+
+#[test]
+fn test_in_scope_targets_proceed() {
+    let scope = scope_for(&["example.com", "203.0.113.0/24"]);
+
+    assert!(scope.check("example.com").is_ok());
+    assert!(scope.check("api.example.com").is_ok());
+    assert!(scope.check("https://www.Example.com/login").is_ok());
+    assert!(scope.check("203.0.113.7").is_ok());
+    assert!(scope.check("203.0.113.128/25").is_ok());
+
+    // Without an allowlist, anything not blocked goes
+    let scope = ScanScope::new(&[], false).unwrap();
+    assert!(scope.check("example.org").is_ok());
+    assert!(scope.check("198.51.100.1").is_ok());
+}
+
+#[test]
+fn test_targets_outside_the_allowlist_are_refused() {
+    let scope = scope_for(&["example.com", "203.0.113.0/24"]);
+
+    let error = scope.check("example.org").unwrap_err();
+    assert_eq!(error.target, "example.org");
+    assert!(error.to_string().contains("allowed domains"));
+
+    // Only whole labels match
+    assert!(scope.check("notexample.com").is_err());
+    assert!(scope.check("198.51.100.1").is_err());
+    // A range reaching past the allowed one is refused as a whole
+    assert!(scope.check("203.0.112.0/23").is_err());
+}
+
+#[test]
+fn test_cloud_metadata_is_always_refused() {
+    let scopes = [
+        ScanScope::new(&[], false).unwrap(),
+        ScanScope::new(&[], true).unwrap(),
+        ScanScope::new(&[], true)
+            .unwrap()
+            .with_allowlist(&["169.254.0.0/16".to_string()])
+            .unwrap(),
+    ];
+
+    for scope in &scopes {
+        let error = scope.check("169.254.169.254").unwrap_err();
+        assert!(error.to_string().contains("cloud metadata"));
+        assert!(scope
+            .check("http://169.254.169.254/latest/meta-data/")
+            .is_err());
+        assert!(scope.check("169.254.0.0/16").is_err());
+        assert!(scope
+            .check_address("169.254.169.254".parse().unwrap())
+            .is_err());
+    }
+}
+
+#[test]
+fn test_private_networks_are_refused_unless_opted_into() {
+    let scope = ScanScope::new(&[], false).unwrap();
+    assert!(scope.check("10.1.2.3").is_err());
+    assert!(scope.check("192.168.1.0/24").is_err());
+    assert!(scope.check_address("172.20.0.5".parse().unwrap()).is_err());
+
+    let scope = ScanScope::new(&[], true).unwrap();
+    assert!(scope.check("10.1.2.3").is_ok());
+    assert!(scope.check("192.168.1.0/24").is_ok());
+}
+
+#[test]
+fn test_internal_addresses_are_refused_unless_opted_into() {
+    let scope = ScanScope::new(&[], false).unwrap();
+    for target in [
+        "127.0.0.1",
+        "127.0.0.0/8",
+        "0.0.0.0",
+        "0.1.2.3",
+        "169.254.1.1",
+        "::1",
+        "fe80::1",
+        "::ffff:127.0.0.1",
+        "http://127.0.0.1:8080/",
+    ] {
+        assert!(scope.check(target).is_err(), "{} should be refused", target);
+    }
+    for ip in ["127.0.0.1", "0.0.0.0", "::1", "::ffff:10.0.0.1"] {
+        let ip = ip.parse().unwrap();
+        assert!(scope.check_address(ip).is_err(), "{} should be refused", ip);
+        assert!(is_internal_address(ip));
+    }
+    assert!(!is_internal_address("203.0.113.7".parse().unwrap()));
+
+    let scope = ScanScope::new(&[], true).unwrap();
+    assert!(scope.check("127.0.0.1").is_ok());
+    assert!(scope.check_address("::1".parse().unwrap()).is_ok());
+}
+
+#[test]
+fn test_configured_no_scan_ranges_are_refused() {
+    let scope = ScanScope::new(&["198.51.100.0/24".to_string()], false).unwrap();
+
+    let error = scope.check("198.51.100.10").unwrap_err();
+    assert!(error.to_string().contains("no-scan"));
+    assert!(scope.check("198.51.0.0/16").is_err());
+    assert!(scope.check("198.51.101.1").is_ok());
+
+    assert!(ScanScope::new(&["198.51.100.0/33".to_string()], false).is_err());
+}
+
+#[test]
+fn test_invalid_allowlist_entries_are_rejected() {
+    let scope = ScanScope::new(&[], false).unwrap();
+    assert!(scope
+        .clone()
+        .with_allowlist(&["not a domain".to_string()])
+        .is_err());
+    assert!(scope.with_allowlist(&["*.example.com".to_string()]).is_ok());
+}
+
+#[test]
+fn test_ip_range_parsing() {
+    let range: IpRange = "10.1.2.3/8".parse().unwrap();
+    assert_eq!(range.to_string(), "10.0.0.0/8");
+    assert!(range.contains("10.255.0.1".parse::<IpAddr>().unwrap()));
+    assert!(!range.contains("11.0.0.1".parse::<IpAddr>().unwrap()));
+    assert!(!range.contains("::a01:203".parse::<IpAddr>().unwrap()));
+
+    let range: IpRange = "2001:db8::1".parse().unwrap();
+    assert_eq!(range.to_string(), "2001:db8::1/128");
+    assert!("2001:db8::/129".parse::<IpRange>().is_err());
+}
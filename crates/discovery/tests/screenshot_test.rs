@@ -0,0 +1,90 @@
+use discovery::results::{DiscoveredWebResource, DiscoveryResult};
+use discovery::screenshot::{screenshot_path, ScreenshotCapturer};
+use std::path::Path;
+
+fn result_with_resources(urls: &[&str]) -> DiscoveryResult {
+    let mut result = DiscoveryResult::new();
+    for url in urls {
+        result.web_resources.push(DiscoveredWebResource {
+            url: url.to_string(),
+            status_code: 200,
+            title: None,
+            technologies: Vec::new(),
+            source: "web_crawl".to_string(),
+            screenshot_path: None,
+            content_hash: None,
+        });
+    }
+    result
+}
+
+#[cfg(not(feature = "screenshots"))]
+#[tokio::test]
+async fn test_capture_is_skipped_without_the_feature() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("screenshots");
+    let mut result = result_with_resources(&["https://www.example.com"]);
+
+    let captured = ScreenshotCapturer::new(&output_dir)
+        .capture_all(&mut result)
+        .await;
+
+    assert_eq!(captured, 0);
+    assert_eq!(result.web_resources[0].screenshot_path, None);
+    assert!(!output_dir.exists());
+}
+
+#[tokio::test]
+async fn test_capture_is_skipped_when_browser_is_unavailable() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut result = result_with_resources(&["https://www.example.com", "https://api.example.com"]);
+
+    let captured = ScreenshotCapturer::new(dir.path())
+        .with_browser_path(dir.path().join("no-such-chrome"))
+        .capture_all(&mut result)
+        .await;
+
+    assert_eq!(captured, 0);
+    assert!(result
+        .web_resources
+        .iter()
+        .all(|resource| resource.screenshot_path.is_none()));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_existing_screenshots_are_kept() {
+    let mut result = result_with_resources(&["https://www.example.com"]);
+    result.web_resources[0].screenshot_path = Some("/screenshots/www.png".to_string());
+
+    let captured = ScreenshotCapturer::new("/nonexistent")
+        .capture_all(&mut result)
+        .await;
+
+    assert_eq!(captured, 0);
+    assert_eq!(
+        result.web_resources[0].screenshot_path.as_deref(),
+        Some("/screenshots/www.png")
+    );
+}
+
+#[test]
+fn test_screenshot_path_is_stable_and_distinct() {
+    let dir = Path::new("/var/lib/easm/screenshots");
+
+    let path = screenshot_path(dir, "https://www.example.com/login?next=/");
+    assert_eq!(
+        path,
+        screenshot_path(dir, "https://www.example.com/login?next=/")
+    );
+    assert_eq!(path.parent(), Some(dir));
+    let name = path.file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("www_example_com_login_next__"));
+    assert!(name.ends_with(".png"));
+
+    // URLs that only differ in punctuation don't collide
+    assert_ne!(
+        screenshot_path(dir, "https://example.com/a-b"),
+        screenshot_path(dir, "https://example.com/a_b")
+    );
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use common::start_http_server;
+use discovery::http_client::HttpClientConfig;
+use discovery::port_scan::DiscoveredPort;
+use discovery::results::DiscoveryResult;
+use discovery::shodan::{parse_host_response, ShodanClient};
+use tokio::net::TcpListener;
+
+/// Trimmed-down `/shodan/host/{ip}` response
+const SHODAN_HOST_RESPONSE: &str = r#"{
+    "ip_str": "192.0.2.1",
+    "ip": 3221225985,
+    "org": "Example Hosting",
+    "hostnames": ["www.example.com"],
+    "domains": ["example.com"],
+    "ports": [22, 443],
+    "vulns": ["CVE-2023-38408", "CVE-2023-44487"],
+    "data": [
+        {
+            "port": 22,
+            "transport": "tcp",
+            "product": "OpenSSH",
+            "version": "8.9p1 Ubuntu-3ubuntu0.1",
+            "data": "SSH-2.0-OpenSSH_8.9p1 Ubuntu-3ubuntu0.1\nKey type: ssh-ed25519\n",
+            "_shodan": {"module": "ssh", "crawler": "abc"},
+            "vulns": {
+                "CVE-2023-38408": {
+                    "cvss": 9.8,
+                    "summary": "The PKCS#11 feature in ssh-agent has an insufficiently trustworthy search path.",
+                    "references": ["https://www.openssh.com/security.html"],
+                    "verified": false
+                }
+            }
+        },
+        {
+            "port": 443,
+            "transport": "tcp",
+            "product": "nginx",
+            "data": "HTTP/1.1 200 OK\r\nServer: nginx\r\n",
+            "_shodan": {"module": "https"},
+            "http": {"status": 200, "title": "Example Domain", "server": "nginx"},
+            "vulns": {
+                "CVE-2023-44487": {
+                    "cvss": 7.5,
+                    "summary": "HTTP/2 rapid reset.",
+                    "references": [],
+                    "verified": true
+                }
+            }
+        }
+    ]
+}"#;
+
+#[test]
+fn test_parse_host_response_maps_ports_and_vulns() {
+    let result = parse_host_response(SHODAN_HOST_RESPONSE).unwrap();
+
+    assert_eq!(result.ip_addresses.len(), 1);
+    assert_eq!(result.ip_addresses[0].source, "shodan");
+    assert_eq!(result.domains.len(), 1);
+    assert_eq!(result.domains[0].domain_name, "www.example.com");
+
+    assert_eq!(result.ports.len(), 2);
+    let ssh = &result.ports[0];
+    assert_eq!((ssh.port, ssh.protocol.as_str()), (22, "TCP"));
+    assert_eq!(ssh.status, "OPEN");
+    assert_eq!(ssh.service_name.as_deref(), Some("ssh"));
+    assert_eq!(
+        ssh.banner.as_deref(),
+        Some("SSH-2.0-OpenSSH_8.9p1 Ubuntu-3ubuntu0.1\nKey type: ssh-ed25519")
+    );
+    assert_eq!(ssh.source, "shodan");
+    let https = &result.ports[1];
+    assert_eq!(https.service_name.as_deref(), Some("https"));
+    assert_eq!(https.http_status, Some(200));
+    assert_eq!(https.http_title.as_deref(), Some("Example Domain"));
+
+    assert_eq!(result.raw_vulnerabilities.len(), 2);
+    let agent = &result.raw_vulnerabilities[0];
+    assert_eq!(agent.cve_id.as_deref(), Some("CVE-2023-38408"));
+    assert_eq!(agent.severity, "critical");
+    assert_eq!(agent.cvss_score, Some(9.8));
+    assert_eq!(agent.target, "192.0.2.1");
+    assert_eq!(agent.matched_at, "192.0.2.1:22");
+    assert_eq!(agent.tags, vec!["shodan", "unverified"]);
+    let reset = &result.raw_vulnerabilities[1];
+    assert_eq!(reset.severity, "high");
+    assert_eq!(reset.tags, vec!["shodan"]);
+}
+
+#[test]
+fn test_parse_host_response_rejects_other_json() {
+    assert!(parse_host_response(r#"{"error": "Invalid API key"}"#).is_err());
+}
+
+#[test]
+fn test_client_is_disabled_without_api_key() {
+    assert!(
+        ShodanClient::from_api_key(None, &HttpClientConfig::default())
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        ShodanClient::from_api_key(Some("  "), &HttpClientConfig::default())
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        ShodanClient::from_api_key(Some("key"), &HttpClientConfig::default())
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_enrich_merges_shodan_data_into_the_result() {
+    let (port, mut requests) =
+        start_http_server("200 OK", "application/json", SHODAN_HOST_RESPONSE).await;
+    let client =
+        ShodanClient::with_base_url("test-key", &format!("http://127.0.0.1:{}/", port)).unwrap();
+
+    let mut result = DiscoveryResult::new();
+    result.ports.push(DiscoveredPort {
+        ip_address: "192.0.2.1".parse().unwrap(),
+        port: 22,
+        protocol: "TCP".to_string(),
+        status: "OPEN".to_string(),
+        service_name: None,
+        banner: None,
+        http_status: None,
+        http_title: None,
+        tls_info: None,
+        source: "port_scan".to_string(),
+    });
+    client.enrich("192.0.2.1", &mut result).await.unwrap();
+
+    assert_eq!(
+        requests.recv().await.unwrap().request_line(),
+        "GET /shodan/host/192.0.2.1?key=test-key HTTP/1.1"
+    );
+    // The scanned port picks up Shodan's banner instead of being duplicated
+    assert_eq!(result.ports.len(), 2);
+    assert_eq!(result.ports[0].source, "port_scan,shodan");
+    assert_eq!(result.ports[0].service_name.as_deref(), Some("ssh"));
+    assert!(result.ports[0].banner.is_some());
+    assert_eq!(result.raw_vulnerabilities.len(), 2);
+}
+
+#[tokio::test]
+async fn test_unknown_host_gives_an_empty_result() {
+    let (port, _) = start_http_server(
+        "404 Not Found",
+        "application/json",
+        r#"{"error": "No information available for that IP."}"#,
+    )
+    .await;
+    let client =
+        ShodanClient::with_base_url("test-key", &format!("http://127.0.0.1:{}", port)).unwrap();
+
+    let result = client.host("192.0.2.1".parse().unwrap()).await.unwrap();
+    assert_eq!(result.entity_count(), 0);
+}
+
+#[tokio::test]
+async fn test_rejected_api_key_is_an_error() {
+    let (port, _) = start_http_server(
+        "401 Unauthorized",
+        "application/json",
+        r#"{"error": "Invalid API key"}"#,
+    )
+    .await;
+    let client =
+        ShodanClient::with_base_url("bad-key", &format!("http://127.0.0.1:{}", port)).unwrap();
+
+    assert!(client.lookup("192.0.2.1").await.is_err());
+}
+
+#[tokio::test]
+async fn test_errors_do_not_leak_the_api_key() {
+    // Nothing listens on a port just released
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed = listener.local_addr().unwrap().port();
+    drop(listener);
+    let client =
+        ShodanClient::with_base_url("secret-key", &format!("http://127.0.0.1:{}", closed)).unwrap();
+    let error = client.lookup("192.0.2.1").await.unwrap_err();
+    assert!(
+        !format!("{:#}", error).contains("secret-key"),
+        "{:#}",
+        error
+    );
+
+    // A body that isn't the expected JSON
+    let (port, _) = start_http_server("200 OK", "application/json", "not json").await;
+    let client =
+        ShodanClient::with_base_url("secret-key", &format!("http://127.0.0.1:{}", port)).unwrap();
+    let error = client.lookup("www.example.com").await.unwrap_err();
+    assert!(
+        !format!("{:#}", error).contains("secret-key"),
+        "{:#}",
+        error
+    );
+}
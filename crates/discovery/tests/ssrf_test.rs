@@ -0,0 +1,278 @@
+use discovery::fingerprinting::web::WebFingerprinter;
+use discovery::fingerprinting::Fingerprinter;
+use discovery::http_client::{external_redirect_policy, HttpClientConfig, InternalAddress};
+use discovery::path_probe::{PathProber, ProbePath};
+use discovery::results::{DiscoveredWebResource, DiscoveryResult};
+use discovery::secrets::SecretScanner;
+use discovery::web_crawl::crawl_url_in_scope;
+use discovery::web_crawl::politeness::{HostRegistry, PolitenessConfig};
+use discovery::web_crawl::scope::ScopeConfig;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// Start a server on a random local port that redirects `/` and
+/// `http://example.test/` to `location`, with `{port}` replaced by its own
+/// port, and serves a page for anything else. It works as a forward proxy
+/// too, so a crawl can reach made-up domains through it. Returns the port and
+/// a receiver for the target of each request.
+async fn start_redirecting_server(
+    location: &'static str,
+) -> (u16, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let location = location.replace("{port}", &port.to_string());
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let target = request
+                .lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or_default()
+                .to_string();
+
+            let response = if target == "/" || target == "http://example.test/" {
+                format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    location
+                )
+            } else {
+                let body = "<title>Internal</title>";
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            };
+            let _ = request_tx.send(target);
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (port, request_rx)
+}
+
+/// Crawl `http://example.test/` through the server on `port` within `scope`,
+/// returning the titles of the pages crawled and the requests the server got
+async fn crawl_through(
+    port: u16,
+    requests: &mut mpsc::UnboundedReceiver<String>,
+    scope: ScopeConfig,
+) -> (Vec<String>, Vec<String>) {
+    let http = HttpClientConfig::default().with_proxy(&format!("http://127.0.0.1:{}", port));
+    let politeness = HostRegistry::new(PolitenessConfig {
+        max_concurrent_per_host: 1,
+        delay_ms: 0,
+    });
+
+    let result = crawl_url_in_scope(
+        "http://example.test/",
+        &scope,
+        &http,
+        &politeness,
+        &CancellationToken::new(),
+    )
+    .await
+    .unwrap();
+
+    let titles = result
+        .web_resources
+        .into_iter()
+        .filter_map(|resource| resource.title)
+        .collect();
+    let mut received = Vec::new();
+    while let Ok(target) = requests.try_recv() {
+        received.push(target);
+    }
+    (titles, received)
+}
+
+#[tokio::test]
+async fn test_crawl_redirect_to_loopback_is_blocked() {
+    let (port, mut requests) = start_redirecting_server("http://127.0.0.1:{port}/admin").await;
+
+    let (titles, received) = crawl_through(port, &mut requests, ScopeConfig::new(0)).await;
+
+    assert!(titles.is_empty());
+    assert_eq!(received, vec!["http://example.test/"]);
+}
+
+#[tokio::test]
+async fn test_crawl_redirect_to_cloud_metadata_is_blocked() {
+    let (port, mut requests) =
+        start_redirecting_server("http://169.254.169.254/latest/meta-data/").await;
+
+    let (titles, received) = crawl_through(port, &mut requests, ScopeConfig::new(0)).await;
+
+    assert!(titles.is_empty());
+    assert_eq!(received, vec!["http://example.test/"]);
+}
+
+#[tokio::test]
+async fn test_crawl_follows_internal_redirect_when_allowed() {
+    let (port, mut requests) = start_redirecting_server("http://127.0.0.1:{port}/admin").await;
+
+    let scope = ScopeConfig::new(0).with_internal_addresses(true);
+    let (titles, received) = crawl_through(port, &mut requests, scope).await;
+
+    assert_eq!(titles, vec!["Internal"]);
+    assert_eq!(
+        received,
+        vec![
+            "http://example.test/".to_string(),
+            format!("http://127.0.0.1:{}/admin", port),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_crawl_refuses_internal_start_url() {
+    let (port, mut requests) = start_redirecting_server("/admin").await;
+
+    let error = crawl_url_in_scope(
+        &format!("http://127.0.0.1:{}/", port),
+        &ScopeConfig::new(1),
+        &HttpClientConfig::default(),
+        HostRegistry::shared(),
+        &CancellationToken::new(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(error.downcast_ref::<InternalAddress>().is_some());
+    assert!(requests.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_names_resolving_to_internal_addresses_are_refused() {
+    let (port, mut requests) = start_redirecting_server("/admin").await;
+    let client = HttpClientConfig::default()
+        .external_client_builder()
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let error = client
+        .get(format!("http://localhost:{}/page", port))
+        .send()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_connect() || error.is_request(), "{}", error);
+    assert!(requests.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_secret_scan_skips_names_resolving_to_internal_addresses() {
+    let (port, mut requests) = start_redirecting_server("/admin").await;
+    let mut result = DiscoveryResult::new();
+    result.web_resources.push(DiscoveredWebResource {
+        url: format!("http://localhost:{}/page", port),
+        status_code: 200,
+        title: None,
+        technologies: Vec::new(),
+        source: "web_crawl".to_string(),
+        screenshot_path: None,
+        content_hash: None,
+    });
+
+    let scanner = SecretScanner::with_config(&HttpClientConfig::default()).unwrap();
+    let count = scanner.scan_all(&mut result).await;
+
+    assert_eq!(count, 0);
+    assert!(requests.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_path_probe_skips_names_resolving_to_internal_addresses() {
+    let (port, mut requests) = start_redirecting_server("/admin").await;
+    let paths = vec![ProbePath {
+        id: "env-file".to_string(),
+        path: "/.env".to_string(),
+        name: "Environment file".to_string(),
+        fingerprints: Vec::new(),
+        severity: Some("high".to_string()),
+        description: None,
+    }];
+
+    let prober = PathProber::with_config(paths, &HttpClientConfig::default()).unwrap();
+    let result = prober
+        .probe(&format!("http://localhost:{}/", port))
+        .await
+        .unwrap();
+
+    assert!(result.web_resources.is_empty());
+    assert!(requests.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_fingerprint_skips_names_resolving_to_internal_addresses() {
+    let (port, mut requests) = start_redirecting_server("/admin").await;
+
+    let fingerprinter = WebFingerprinter::with_http_config(&HttpClientConfig::default()).unwrap();
+    let result = fingerprinter
+        .fingerprint(
+            &format!("http://localhost:{}/page", port),
+            uuid::Uuid::new_v4(),
+        )
+        .await;
+
+    assert!(result.technologies.is_empty());
+    assert!(!result.metadata.contains_key("url"));
+    assert!(requests.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_redirect_policy_blocks_redirects_to_other_internal_hosts() {
+    let client = reqwest::Client::builder()
+        .redirect(external_redirect_policy())
+        .build()
+        .unwrap();
+
+    // Redirects within the internal host the request started on are followed
+    let (port, _) = start_redirecting_server("/admin").await;
+    let response = client
+        .get(format!("http://127.0.0.1:{}/", port))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.url().path(), "/admin");
+
+    let (port, mut requests) =
+        start_redirecting_server("http://169.254.169.254/latest/meta-data/").await;
+    let error = client
+        .get(format!("http://127.0.0.1:{}/", port))
+        .send()
+        .await
+        .unwrap_err();
+    assert!(error.is_redirect());
+    assert_eq!(requests.recv().await.unwrap(), "/");
+}
+
+#[test]
+fn test_links_to_internal_addresses_are_out_of_scope() {
+    let start = Url::parse("http://example.test/").unwrap();
+    let scope = ScopeConfig::new(1);
+
+    for link in [
+        "http://127.0.0.1/",
+        "http://localhost:8080/",
+        "http://169.254.169.254/latest/meta-data/",
+        "http://10.0.0.1/",
+        "http://[::1]/",
+    ] {
+        let link = Url::parse(link).unwrap();
+        assert!(!scope.in_scope(&start, &link), "{} should be refused", link);
+        assert!(ScopeConfig::new(1)
+            .with_allowed_domains(vec![link.host_str().unwrap().to_string()])
+            .with_internal_addresses(true)
+            .in_scope(&start, &link));
+    }
+}
@@ -0,0 +1,183 @@
+use discovery::port_scan::starttls::{probe, StartTlsProtocol};
+use std::net::{IpAddr, Ipv4Addr};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// Start a plaintext mail server on a random local port that sends
+/// `greeting`, answers each command line with the reply `respond` gives for
+/// it, and switches to TLS with a self-signed certificate for
+/// `mail.example.test` after the reply to `upgrade_command`
+async fn start_mail_server(
+    greeting: &'static str,
+    upgrade_command: &'static str,
+    respond: fn(&str) -> &'static str,
+) -> u16 {
+    let certified =
+        rcgen::generate_simple_self_signed(vec!["mail.example.test".to_string()]).unwrap();
+    let identity = native_tls::Identity::from_pkcs8(
+        certified.cert.pem().as_bytes(),
+        certified.key_pair.serialize_pem().as_bytes(),
+    )
+    .unwrap();
+    let acceptor =
+        tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((tcp, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let mut tcp = BufReader::new(tcp);
+                tcp.get_mut().write_all(greeting.as_bytes()).await.unwrap();
+
+                let mut line = String::new();
+                while tcp.read_line(&mut line).await.unwrap_or(0) > 0 {
+                    let command = line.trim_end().to_string();
+                    line.clear();
+                    tcp.get_mut()
+                        .write_all(respond(&command).as_bytes())
+                        .await
+                        .unwrap();
+
+                    if command == upgrade_command {
+                        let _ = acceptor.accept(tcp.into_inner()).await;
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    port
+}
+
+fn smtp_reply(command: &str) -> &'static str {
+    match command {
+        "EHLO easm.scanner" => {
+            "250-mail.example.test\r\n250-PIPELINING\r\n250-STARTTLS\r\n250 8BITMIME\r\n"
+        }
+        "STARTTLS" => "220 2.0.0 Ready to start TLS\r\n",
+        _ => "502 5.5.2 Error: command not recognized\r\n",
+    }
+}
+
+fn imap_reply(command: &str) -> &'static str {
+    match command {
+        "A001 CAPABILITY" => {
+            "* CAPABILITY IMAP4rev1 STARTTLS LOGINDISABLED\r\nA001 OK Capability completed\r\n"
+        }
+        "A002 STARTTLS" => "A002 OK Begin TLS negotiation now\r\n",
+        _ => "* BAD Unknown command\r\n",
+    }
+}
+
+fn pop3_reply(command: &str) -> &'static str {
+    match command {
+        "CAPA" => "+OK Capability list follows\r\nUSER\r\nSTLS\r\nUIDL\r\n.\r\n",
+        "STLS" => "+OK Begin TLS negotiation\r\n",
+        _ => "-ERR Unknown command\r\n",
+    }
+}
+
+fn plaintext_smtp_reply(command: &str) -> &'static str {
+    match command {
+        "EHLO easm.scanner" => "250-mail.example.test\r\n250 8BITMIME\r\n",
+        _ => "502 5.5.2 Error: command not recognized\r\n",
+    }
+}
+
+#[test]
+fn test_mail_ports_map_to_their_protocol() {
+    assert_eq!(
+        StartTlsProtocol::for_port(587),
+        Some(StartTlsProtocol::Smtp)
+    );
+    assert_eq!(StartTlsProtocol::for_port(25), Some(StartTlsProtocol::Smtp));
+    assert_eq!(
+        StartTlsProtocol::for_port(143),
+        Some(StartTlsProtocol::Imap)
+    );
+    assert_eq!(
+        StartTlsProtocol::for_port(110),
+        Some(StartTlsProtocol::Pop3)
+    );
+    // Implicit TLS ports handshake straight away
+    assert_eq!(StartTlsProtocol::for_port(465), None);
+    assert_eq!(StartTlsProtocol::for_port(993), None);
+}
+
+#[tokio::test]
+async fn test_smtp_starttls_captures_certificate() {
+    let port = start_mail_server(
+        "220-mail.example.test ESMTP\r\n220 ready\r\n",
+        "STARTTLS",
+        smtp_reply,
+    )
+    .await;
+
+    let result = probe(LOCALHOST, port, StartTlsProtocol::Smtp)
+        .await
+        .unwrap();
+
+    assert!(result.banner.starts_with("220-mail.example.test ESMTP"));
+    assert!(result.banner.contains("250-STARTTLS"));
+    let tls_info = result
+        .tls_info
+        .expect("certificate should be captured after the upgrade");
+    assert!(tls_info
+        .subject_alt_names
+        .contains(&"mail.example.test".to_string()));
+    assert!(!tls_info.issuer.is_empty());
+    assert!(tls_info.not_after > tls_info.not_before);
+}
+
+#[tokio::test]
+async fn test_imap_starttls_captures_certificate() {
+    let port = start_mail_server(
+        "* OK [CAPABILITY IMAP4rev1] Dovecot ready.\r\n",
+        "A002 STARTTLS",
+        imap_reply,
+    )
+    .await;
+
+    let result = probe(LOCALHOST, port, StartTlsProtocol::Imap)
+        .await
+        .unwrap();
+
+    assert!(result.banner.contains("Dovecot ready"));
+    assert!(result.banner.contains("A001 OK"));
+    assert!(result.tls_info.is_some());
+}
+
+#[tokio::test]
+async fn test_pop3_stls_captures_certificate() {
+    let port = start_mail_server("+OK POP3 ready\r\n", "STLS", pop3_reply).await;
+
+    let result = probe(LOCALHOST, port, StartTlsProtocol::Pop3)
+        .await
+        .unwrap();
+
+    assert!(result.banner.contains("STLS"));
+    assert!(result.tls_info.is_some());
+}
+
+#[tokio::test]
+async fn test_service_without_starttls_keeps_plaintext_banner() {
+    let port = start_mail_server(
+        "220 mail.example.test ESMTP\r\n",
+        "STARTTLS",
+        plaintext_smtp_reply,
+    )
+    .await;
+
+    let result = probe(LOCALHOST, port, StartTlsProtocol::Smtp)
+        .await
+        .unwrap();
+
+    assert!(result.banner.contains("250 8BITMIME"));
+    assert_eq!(result.tls_info, None);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::start_http_server;
+use discovery::takeover::{default_fingerprints, TakeoverChecker};
+
+/// Page GitHub Pages serves for a custom domain no repository claims
+const GITHUB_PAGES_UNCLAIMED: &str = include_str!("fixtures/takeover/github-pages-unclaimed.html");
+/// A published GitHub Pages site
+const GITHUB_PAGES_SITE: &str = include_str!("fixtures/takeover/github-pages-site.html");
+
+#[test]
+fn test_default_fingerprints_cover_common_providers() {
+    let checker = TakeoverChecker::new().unwrap();
+
+    for (cname, provider) in [
+        ("example-bucket.s3.amazonaws.com", "AWS S3"),
+        ("example.github.io.", "GitHub Pages"),
+        ("example-app.herokuapp.com", "Heroku"),
+    ] {
+        assert_eq!(checker.provider_for(cname).unwrap().provider, provider);
+    }
+
+    assert!(checker.provider_for("example.com").is_none());
+    // Suffixes only match on a label boundary
+    assert!(checker.provider_for("notgithub.io").is_none());
+    assert!(default_fingerprints()
+        .iter()
+        .all(|fingerprint| !fingerprint.cnames.is_empty() && !fingerprint.fingerprints.is_empty()));
+}
+
+#[test]
+fn test_fingerprint_requires_expected_status() {
+    let checker = TakeoverChecker::new().unwrap();
+    let github_pages = checker.provider_for("example.github.io").unwrap();
+
+    assert!(github_pages.matches_response(404, GITHUB_PAGES_UNCLAIMED));
+    assert!(!github_pages.matches_response(200, GITHUB_PAGES_UNCLAIMED));
+    assert!(!github_pages.matches_response(404, GITHUB_PAGES_SITE));
+}
+
+#[tokio::test]
+async fn test_dangling_cname_is_reported() {
+    let (port, _) = start_http_server("404 Not Found", "text/html", GITHUB_PAGES_UNCLAIMED).await;
+    let checker = TakeoverChecker::new().unwrap();
+
+    let finding = checker
+        .check_at(
+            "Docs.Example.com",
+            "example.github.io.",
+            &format!("http://127.0.0.1:{}/", port),
+        )
+        .await
+        .unwrap()
+        .expect("unclaimed GitHub Pages site is vulnerable");
+
+    assert_eq!(finding.target, "docs.example.com");
+    assert_eq!(finding.name, "Subdomain takeover via GitHub Pages");
+    assert_eq!(finding.severity, "high");
+    assert_eq!(finding.template_id, "takeover-github-pages");
+    assert_eq!(finding.matched_at, "example.github.io");
+    assert_eq!(finding.tags, vec!["takeover", "github-pages"]);
+    assert_eq!(finding.source, "takeover_check");
+}
+
+#[tokio::test]
+async fn test_claimed_site_is_not_reported() {
+    let (port, _) = start_http_server("200 OK", "text/html", GITHUB_PAGES_SITE).await;
+    let checker = TakeoverChecker::new().unwrap();
+
+    let finding = checker
+        .check_at(
+            "docs.example.com",
+            "example.github.io",
+            &format!("http://127.0.0.1:{}/", port),
+        )
+        .await
+        .unwrap();
+
+    assert!(finding.is_none());
+}
+
+#[tokio::test]
+async fn test_unknown_provider_is_not_fetched() {
+    let checker = TakeoverChecker::new().unwrap();
+
+    // Nothing listens on the URL, so fetching it would fail
+    let finding = checker
+        .check_at("www.example.com", "lb.example.net", "http://127.0.0.1:1/")
+        .await
+        .unwrap();
+
+    assert!(finding.is_none());
+}
@@ -0,0 +1,59 @@
+use discovery::port_scan::tls::grab_tls_info;
+use std::net::{IpAddr, Ipv4Addr};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+/// Start a TLS server on a random local port that presents a self-signed
+/// certificate for `names`, returning the port it listens on
+async fn start_tls_server(names: &[&str]) -> u16 {
+    let certified =
+        rcgen::generate_simple_self_signed(names.iter().map(|n| n.to_string()).collect::<Vec<_>>())
+            .unwrap();
+    let identity = native_tls::Identity::from_pkcs8(
+        certified.cert.pem().as_bytes(),
+        certified.key_pair.serialize_pem().as_bytes(),
+    )
+    .unwrap();
+    let acceptor =
+        tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((tcp, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                if let Ok(mut stream) = acceptor.accept(tcp).await {
+                    let _ = stream.write_all(b"220 test ESMTP ready\r\n").await;
+                }
+            });
+        }
+    });
+
+    port
+}
+
+#[tokio::test]
+async fn test_tls_info_captures_subject_alt_names() {
+    let port = start_tls_server(&["tls.example.test", "*.wild.example.test"]).await;
+
+    let tls_info = grab_tls_info(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+        .await
+        .expect("TLS handshake should succeed against a self-signed certificate")
+        .expect("server should present a certificate");
+
+    assert!(tls_info
+        .subject_alt_names
+        .contains(&"tls.example.test".to_string()));
+    assert!(tls_info.not_after > tls_info.not_before);
+
+    // Wildcards are reported as their parent domain
+    assert_eq!(
+        tls_info.san_domains(),
+        vec![
+            "tls.example.test".to_string(),
+            "wild.example.test".to_string()
+        ]
+    );
+}
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use discovery::cancellation::is_cancelled;
+use discovery::vulnerability::scanner::{VulnCheck, VulnScanner};
+use discovery::vulnerability::DiscoveredVulnerability;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Check that reports one finding per target after a short delay, keeping
+/// track of how many checks run at once across all instances
+struct SlowCheck {
+    name: String,
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+    checked: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+#[async_trait]
+impl VulnCheck for SlowCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self, target: &str) -> anyhow::Result<Vec<DiscoveredVulnerability>> {
+        let now_running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now_running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+
+        self.checked
+            .lock()
+            .unwrap()
+            .push((self.name.clone(), target.to_string()));
+        if target == "broken.example.com" {
+            anyhow::bail!("connection refused");
+        }
+
+        Ok(vec![DiscoveredVulnerability::new(
+            target.to_string(),
+            format!("{} finding", self.name),
+            "medium".to_string(),
+            self.name.clone(),
+            target.to_string(),
+        )])
+    }
+}
+
+struct Checks {
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+    checked: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl Checks {
+    fn new() -> Self {
+        Self {
+            running: Arc::new(AtomicUsize::new(0)),
+            peak: Arc::new(AtomicUsize::new(0)),
+            checked: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn check(&self, name: &str) -> Arc<dyn VulnCheck> {
+        Arc::new(SlowCheck {
+            name: name.to_string(),
+            running: self.running.clone(),
+            peak: self.peak.clone(),
+            checked: self.checked.clone(),
+        })
+    }
+}
+
+fn targets(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| format!("host{}.example.com", i))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_scan_runs_every_check_on_every_target() {
+    let checks = Checks::new();
+    let scanner = VulnScanner::new()
+        .with_check(checks.check("exposed-panel"))
+        .with_check(checks.check("default-login"))
+        .with_max_concurrent(3);
+
+    let targets = targets(6);
+    let result = scanner
+        .scan(&targets, &CancellationToken::new())
+        .await
+        .unwrap();
+
+    let mut checked = checks.checked.lock().unwrap().clone();
+    checked.sort();
+    let mut expected: Vec<(String, String)> = targets
+        .iter()
+        .flat_map(|target| {
+            ["default-login", "exposed-panel"]
+                .iter()
+                .map(move |name| (name.to_string(), target.clone()))
+        })
+        .collect();
+    expected.sort();
+    assert_eq!(checked, expected);
+    assert_eq!(result.raw_vulnerabilities.len(), 12);
+
+    // Bounded, but the checks did overlap
+    let peak = checks.peak.load(Ordering::SeqCst);
+    assert!(peak <= 3, "{} checks ran at once", peak);
+    assert!(peak > 1, "checks ran one at a time");
+}
+
+#[tokio::test]
+async fn test_failed_check_does_not_stop_scan() {
+    let checks = Checks::new();
+    let scanner = VulnScanner::new().with_check(checks.check("exposed-panel"));
+
+    let targets = vec![
+        "ok.example.com".to_string(),
+        "broken.example.com".to_string(),
+    ];
+    let result = scanner
+        .scan(&targets, &CancellationToken::new())
+        .await
+        .unwrap();
+
+    assert_eq!(checks.checked.lock().unwrap().len(), 2);
+    assert_eq!(result.raw_vulnerabilities.len(), 1);
+    assert_eq!(result.raw_vulnerabilities[0].target, "ok.example.com");
+}
+
+#[tokio::test]
+async fn test_cancelled_scan_stops() {
+    let checks = Checks::new();
+    let scanner = VulnScanner::new()
+        .with_check(checks.check("exposed-panel"))
+        .with_max_concurrent(1);
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+
+    let error = scanner.scan(&targets(4), &cancel).await.unwrap_err();
+    assert!(is_cancelled(&error));
+}
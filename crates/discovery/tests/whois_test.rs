@@ -0,0 +1,119 @@
+mod common;
+
+use common::start_http_server;
+use discovery::results::{DiscoveredDomain, DiscoveryResult};
+use discovery::whois::{parse_rdap_response, RdapClient, WhoisInfo};
+
+/// Trimmed-down RDAP response for example.com as served by Verisign
+const EXAMPLE_RDAP_RESPONSE: &str = r#"{
+    "objectClassName": "domain",
+    "handle": "2336799_DOMAIN_COM-VRSN",
+    "ldhName": "EXAMPLE.COM",
+    "entities": [
+        {
+            "objectClassName": "entity",
+            "handle": "376",
+            "roles": ["registrar"],
+            "vcardArray": ["vcard", [
+                ["version", {}, "text", "4.0"],
+                ["fn", {}, "text", "RESERVED-Internet Assigned Numbers Authority"]
+            ]]
+        }
+    ],
+    "events": [
+        {"eventAction": "registration", "eventDate": "1995-08-14T04:00:00Z"},
+        {"eventAction": "expiration", "eventDate": "2025-08-13T04:00:00Z"},
+        {"eventAction": "last update of RDAP database", "eventDate": "2025-04-23T10:21:44Z"}
+    ],
+    "nameservers": [
+        {"objectClassName": "nameserver", "ldhName": "A.IANA-SERVERS.NET"},
+        {"objectClassName": "nameserver", "ldhName": "B.IANA-SERVERS.NET"}
+    ]
+}"#;
+
+#[test]
+fn test_parse_rdap_response_extracts_registration_details() {
+    let info = parse_rdap_response(EXAMPLE_RDAP_RESPONSE).unwrap();
+
+    assert_eq!(
+        info.registrar.as_deref(),
+        Some("RESERVED-Internet Assigned Numbers Authority")
+    );
+    assert_eq!(
+        info.creation_date.unwrap().to_rfc3339(),
+        "1995-08-14T04:00:00+00:00"
+    );
+    assert_eq!(
+        info.expiration_date.unwrap().to_rfc3339(),
+        "2025-08-13T04:00:00+00:00"
+    );
+    assert_eq!(
+        info.nameservers,
+        vec!["a.iana-servers.net", "b.iana-servers.net"]
+    );
+}
+
+#[test]
+fn test_parse_rdap_response_tolerates_missing_fields() {
+    let info = parse_rdap_response(r#"{"objectClassName": "domain"}"#).unwrap();
+    assert_eq!(info, WhoisInfo::default());
+
+    assert!(parse_rdap_response("<html>not rdap</html>").is_err());
+}
+
+#[tokio::test]
+async fn test_rdap_lookup_queries_the_domain_endpoint() {
+    let (port, mut requests) =
+        start_http_server("200 OK", "application/rdap+json", EXAMPLE_RDAP_RESPONSE).await;
+    let client = RdapClient::with_base_url(&format!("http://127.0.0.1:{}/", port)).unwrap();
+
+    let info = client.lookup("Example.COM.").await.unwrap();
+
+    assert_eq!(
+        requests.recv().await.unwrap().request_line(),
+        "GET /domain/example.com HTTP/1.1"
+    );
+    assert_eq!(
+        info.registrar.as_deref(),
+        Some("RESERVED-Internet Assigned Numbers Authority")
+    );
+    assert_eq!(info.nameservers.len(), 2);
+}
+
+#[tokio::test]
+async fn test_rdap_lookup_fails_for_unknown_domains() {
+    let (port, _) = start_http_server("404 Not Found", "application/rdap+json", "{}").await;
+    let client = RdapClient::with_base_url(&format!("http://127.0.0.1:{}", port)).unwrap();
+
+    assert!(client.lookup("unregistered.example").await.is_err());
+}
+
+#[test]
+fn test_domain_attributes_include_whois_info() {
+    let mut result = DiscoveryResult::new();
+    result.add_whois(
+        "example.com",
+        parse_rdap_response(EXAMPLE_RDAP_RESPONSE).unwrap(),
+    );
+
+    let looked_up = DiscoveredDomain {
+        domain_name: "EXAMPLE.com.".to_string(),
+        source: "dns_input".to_string(),
+    };
+    let attributes = result.domain_attributes(&looked_up);
+    assert_eq!(attributes["source"], "dns_input");
+    assert_eq!(
+        attributes["whois_info"]["registrar"],
+        "RESERVED-Internet Assigned Numbers Authority"
+    );
+    assert_eq!(
+        attributes["whois_info"]["nameservers"][0],
+        "a.iana-servers.net"
+    );
+
+    let other = DiscoveredDomain {
+        domain_name: "www.example.com".to_string(),
+        source: "dns_enum".to_string(),
+    };
+    assert!(result.domain_attributes(&other).get("whois_info").is_none());
+}
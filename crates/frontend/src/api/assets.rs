@@ -0,0 +1,79 @@
+use serde::Deserialize;
+
+use super::{ApiClient, ApiError};
+
+/// Asset as returned by the API
+#[derive(Deserialize, Debug, Clone)]
+pub struct AssetSummary {
+    pub id: String,
+    pub organization_id: String,
+    pub asset_type: String,
+    pub value: String,
+    pub status: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub attributes: Option<serde_json::Value>,
+}
+
+/// Asset linked to the one being viewed
+#[derive(Deserialize, Debug, Clone)]
+pub struct RelatedAsset {
+    pub relationship_type: String,
+    pub asset: AssetSummary,
+}
+
+/// Port discovered on an asset
+#[derive(Deserialize, Debug, Clone)]
+pub struct AssetPort {
+    pub id: String,
+    pub port_number: i32,
+    pub protocol: String,
+    pub service_name: Option<String>,
+    pub banner: Option<String>,
+    pub status: String,
+    pub last_seen: String,
+}
+
+/// Technology detected on an asset
+#[derive(Deserialize, Debug, Clone)]
+pub struct AssetTechnology {
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Vulnerability reported against an asset
+#[derive(Deserialize, Debug, Clone)]
+pub struct AssetVulnerability {
+    pub id: String,
+    pub title: String,
+    pub severity: String,
+    pub status: String,
+    pub cve_id: Option<String>,
+    pub cvss_score: Option<f64>,
+    pub first_seen: String,
+}
+
+/// Aggregate returned by `GET /api/assets/{id}/details`
+#[derive(Deserialize, Debug, Clone)]
+pub struct AssetDetails {
+    pub asset: AssetSummary,
+    pub related_assets: Vec<RelatedAsset>,
+    pub ports: Vec<AssetPort>,
+    pub technologies: Vec<AssetTechnology>,
+    pub vulnerabilities: Vec<AssetVulnerability>,
+}
+
+impl ApiClient {
+    /// Fetch a single asset
+    pub async fn get_asset(&self, id: &str) -> Result<AssetSummary, ApiError> {
+        self.get(&format!("/api/assets/{}", id)).await
+    }
+
+    /// Fetch an asset together with its relationships, ports, technologies
+    /// and vulnerabilities
+    pub async fn get_asset_details(&self, id: &str) -> Result<AssetDetails, ApiError> {
+        self.get(&format!("/api/assets/{}/details", id)).await
+    }
+}
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+
+use super::assets::AssetSummary;
+use super::{ApiClient, ApiError};
+
+/// Number of assets of each type
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AssetTypeCounts {
+    pub domain: usize,
+    pub ip_address: usize,
+    pub web_app: usize,
+    pub certificate: usize,
+    pub code_repo: usize,
+    pub cloud_resource: usize,
+}
+
+/// Number of open vulnerabilities of each severity
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct SeverityCounts {
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    pub info: usize,
+}
+
+/// Organization-wide numbers returned by `GET /api/dashboard/stats`
+#[derive(Deserialize, Debug, Clone)]
+pub struct DashboardStats {
+    pub total_assets: usize,
+    pub assets_by_type: AssetTypeCounts,
+    pub open_vulnerabilities: usize,
+    pub open_vulnerabilities_by_severity: SeverityCounts,
+    pub technologies: usize,
+    pub recent_discoveries: Vec<AssetSummary>,
+}
+
+impl ApiClient {
+    /// Fetch the dashboard numbers for the current user's organization
+    pub async fn get_dashboard_stats(&self) -> Result<DashboardStats, ApiError> {
+        self.get("/api/dashboard/stats").await
+    }
+}
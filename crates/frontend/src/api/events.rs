@@ -0,0 +1,72 @@
+//! Live updates from the API's server-sent event stream
+
+use futures::{
+    channel::oneshot,
+    future::{select, Either},
+    StreamExt,
+};
+use gloo::net::eventsource::futures::EventSource;
+use leptos::prelude::on_cleanup;
+use shared::types::{ResourceEvent, ResourceKind};
+use wasm_bindgen_futures::spawn_local;
+
+use crate::utils::{api_base, get_auth_token};
+
+/// Endpoint streaming the organization's resource events
+const EVENTS_ENDPOINT: &str = "/api/events";
+
+/// Call `on_change` whenever one of the organization's `resource`s is
+/// created or updated, until the calling component is unmounted.
+///
+/// An EventSource can't send an authorization header, so the token goes in
+/// the query string instead.
+pub fn on_resource_change(resource: ResourceKind, on_change: impl Fn() + 'static) {
+    let Some(token) = get_auth_token() else {
+        return;
+    };
+    let url = format!("{}{}?access_token={}", api_base(), EVENTS_ENDPOINT, token);
+
+    let mut source = match EventSource::new(&url) {
+        Ok(source) => source,
+        Err(e) => {
+            log::error!("Error opening live updates: {}", e);
+            return;
+        }
+    };
+    let mut messages = match source.subscribe("message") {
+        Ok(messages) => messages,
+        Err(e) => {
+            log::error!("Error subscribing to live updates: {}", e);
+            return;
+        }
+    };
+
+    let (stop, mut stopped) = oneshot::channel::<()>();
+    on_cleanup(move || {
+        let _ = stop.send(());
+    });
+
+    spawn_local(async move {
+        loop {
+            match select(messages.next(), &mut stopped).await {
+                Either::Left((Some(Ok((_, message))), _)) => {
+                    let Some(data) = message.data().as_string() else {
+                        continue;
+                    };
+                    match serde_json::from_str::<ResourceEvent>(&data) {
+                        Ok(event) if event.resource == resource => on_change(),
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Ignoring malformed live update: {}", e),
+                    }
+                }
+                Either::Left((Some(Err(e)), _)) => {
+                    log::warn!("Live updates stopped: {}", e);
+                    break;
+                }
+                // The stream ended or the component was unmounted
+                Either::Left((None, _)) | Either::Right(_) => break,
+            }
+        }
+        source.close();
+    });
+}
@@ -1,7 +1,18 @@
+pub mod assets;
+pub mod dashboard;
+pub mod events;
+pub mod technologies;
+pub mod vulnerabilities;
+
 use gloo::net::http::{Request, Response};
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
+use crate::utils::clear_auth_token;
+
+/// Header the API uses to report the unpaginated total of a list
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
 /// API error
 #[derive(Debug, Error)]
 pub enum ApiError {
@@ -24,11 +35,53 @@ pub enum ApiError {
     DeserializationError(String),
 }
 
+/// Side effects applied when the backend rejects the current session
+pub(crate) trait Session {
+    /// Drop the persisted auth token
+    fn clear_token(&mut self);
+
+    /// Send the user back to the login page
+    fn redirect_to_login(&mut self);
+}
+
+/// Session backed by local storage and `window.location`
+struct BrowserSession;
+
+impl Session for BrowserSession {
+    fn clear_token(&mut self) {
+        if let Err(e) = clear_auth_token() {
+            log::error!("Error clearing auth token: {}", e);
+        }
+    }
+
+    fn redirect_to_login(&mut self) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let location = window.location();
+
+        // Avoid a reload loop when the login page itself is rejected
+        if location.pathname().is_ok_and(|path| path == "/login") {
+            return;
+        }
+        let _ = location.replace("/login");
+    }
+}
+
+/// Expire the session on 401/403 unless the caller opted out
+pub(crate) fn handle_auth_failure(status: u16, expire_session: bool, session: &mut impl Session) {
+    if expire_session && matches!(status, 401 | 403) {
+        session.clear_token();
+        session.redirect_to_login();
+    }
+}
+
 /// API client for communicating with the backend
 #[derive(Debug, Clone)]
 pub struct ApiClient {
     base_url: String,
     token: Option<String>,
+    expire_session_on_auth_error: bool,
 }
 
 impl ApiClient {
@@ -37,9 +90,17 @@ impl ApiClient {
         Self {
             base_url,
             token: None,
+            expire_session_on_auth_error: true,
         }
     }
 
+    /// Keep the stored session and stay on the page when a request is
+    /// rejected with 401/403, e.g. for the login call itself
+    pub fn without_auth_redirect(mut self) -> Self {
+        self.expire_session_on_auth_error = false;
+        self
+    }
+
     /// Set the auth token
     pub fn set_token(&mut self, token: String) {
         self.token = Some(token);
@@ -71,7 +132,37 @@ impl ApiClient {
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
 
-        Self::process_response(response).await
+        self.process_response(response).await
+    }
+
+    /// Execute a GET request against a paginated list endpoint, returning the
+    /// body together with the total advertised in the `X-Total-Count` header
+    pub async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<(T, Option<usize>), ApiError> {
+        let url = self.get_url(endpoint);
+
+        let mut request = Request::get(&url);
+
+        // Add auth header if token is present
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", &format!("Bearer {}", token));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+
+        // Read the header before the body consumes the response
+        let total = response
+            .headers()
+            .get(TOTAL_COUNT_HEADER)
+            .and_then(|value| value.parse::<usize>().ok());
+
+        let body = self.process_response(response).await?;
+        Ok((body, total))
     }
 
     /// Execute a POST request
@@ -97,7 +188,7 @@ impl ApiClient {
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
 
-        Self::process_response(response).await
+        self.process_response(response).await
     }
 
     /// Execute a PUT request
@@ -123,7 +214,33 @@ impl ApiClient {
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
 
-        Self::process_response(response).await
+        self.process_response(response).await
+    }
+
+    /// Execute a PATCH request
+    pub async fn patch<T: DeserializeOwned, B: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<T, ApiError> {
+        let url = self.get_url(endpoint);
+
+        let mut request = Request::patch(&url);
+
+        // Add auth header if token is present
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", &format!("Bearer {}", token));
+        }
+
+        // Add JSON body
+        let response = request
+            .json(body)
+            .map_err(|e| ApiError::DeserializationError(e.to_string()))?
+            .send()
+            .await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+
+        self.process_response(response).await
     }
 
     /// Execute a DELETE request
@@ -142,18 +259,29 @@ impl ApiClient {
             .await
             .map_err(|e| ApiError::NetworkError(e.to_string()))?;
 
-        Self::process_response(response).await
+        self.process_response(response).await
     }
 
     /// Process the API response
-    async fn process_response<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    async fn process_response<T: DeserializeOwned>(
+        &self,
+        response: Response,
+    ) -> Result<T, ApiError> {
         match response.status() {
             200 | 201 => response
                 .json::<T>()
                 .await
                 .map_err(|e| ApiError::DeserializationError(e.to_string())),
-            401 | 403 => {
+            // No content, e.g. for a logout, reads as a JSON null
+            204 => serde_json::from_str("null")
+                .map_err(|e| ApiError::DeserializationError(e.to_string())),
+            status @ (401 | 403) => {
                 let text = response.text().await.unwrap_or_default();
+                handle_auth_failure(
+                    status,
+                    self.expire_session_on_auth_error,
+                    &mut BrowserSession,
+                );
                 Err(ApiError::AuthError(text))
             }
             404 => Err(ApiError::NotFound),
@@ -168,3 +296,69 @@ impl ApiClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeSession {
+        token: Option<String>,
+        redirected: bool,
+    }
+
+    impl Session for FakeSession {
+        fn clear_token(&mut self) {
+            self.token = None;
+        }
+
+        fn redirect_to_login(&mut self) {
+            self.redirected = true;
+        }
+    }
+
+    fn logged_in() -> FakeSession {
+        FakeSession {
+            token: Some("expired".to_string()),
+            redirected: false,
+        }
+    }
+
+    #[test]
+    fn test_unauthorized_clears_token_and_redirects() {
+        let mut session = logged_in();
+        handle_auth_failure(401, true, &mut session);
+        assert!(session.token.is_none());
+        assert!(session.redirected);
+
+        let mut session = logged_in();
+        handle_auth_failure(403, true, &mut session);
+        assert!(session.token.is_none());
+        assert!(session.redirected);
+    }
+
+    #[test]
+    fn test_opt_out_keeps_session() {
+        let mut session = logged_in();
+        handle_auth_failure(401, false, &mut session);
+        assert_eq!(session.token.as_deref(), Some("expired"));
+        assert!(!session.redirected);
+    }
+
+    #[test]
+    fn test_other_statuses_keep_session() {
+        for status in [200, 400, 404, 500] {
+            let mut session = logged_in();
+            handle_auth_failure(status, true, &mut session);
+            assert!(session.token.is_some());
+            assert!(!session.redirected);
+        }
+    }
+
+    #[test]
+    fn test_without_auth_redirect() {
+        let client = ApiClient::new("http://localhost:3000".to_string());
+        assert!(client.expire_session_on_auth_error);
+        assert!(!client.without_auth_redirect().expire_session_on_auth_error);
+    }
+}
@@ -0,0 +1,140 @@
+use serde::Deserialize;
+
+use super::{ApiClient, ApiError};
+use crate::components::ui::pagination::PageState;
+
+/// Technology as returned by `GET /api/technologies`, with the asset it was
+/// detected on
+#[derive(Deserialize, Debug, Clone)]
+pub struct TechnologySummary {
+    pub id: String,
+    pub asset_id: String,
+    pub asset_value: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub category: Option<String>,
+    pub last_seen: String,
+    pub created_at: String,
+}
+
+/// Envelope returned by the paginated technologies list
+#[derive(Deserialize, Debug, Clone)]
+pub struct TechnologyListResponse {
+    pub technologies: Vec<TechnologySummary>,
+    pub total: usize,
+}
+
+/// Number of assets running a technology, or a technology of a category
+#[derive(Deserialize, Debug, Clone)]
+pub struct TechnologyCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Counts returned by `GET /api/technologies/distribution`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct TechnologyDistribution {
+    pub by_name: Vec<TechnologyCount>,
+    pub by_category: Vec<TechnologyCount>,
+}
+
+/// Search and category filters for the technologies list. Blank values
+/// match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TechnologyFilter {
+    pub search: String,
+    pub category: Option<String>,
+}
+
+impl TechnologyFilter {
+    /// The list endpoint for this filter and page
+    pub fn endpoint(&self, page: &PageState) -> String {
+        let mut params = Vec::new();
+        let search = self.search.trim();
+        if !search.is_empty() {
+            params.push(format!("search={}", encode_query_value(search)));
+        }
+        if let Some(category) = self.category.as_deref().filter(|c| !c.is_empty()) {
+            params.push(format!("category={}", encode_query_value(category)));
+        }
+
+        let path = if params.is_empty() {
+            "/api/technologies".to_string()
+        } else {
+            format!("/api/technologies?{}", params.join("&"))
+        };
+        page.endpoint(&path)
+    }
+}
+
+/// Percent-encode a query parameter value, leaving only unreserved
+/// characters as they are
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+impl ApiClient {
+    /// Fetch a page of the technologies detected on the current user's
+    /// organization's assets, with the total matching the filter
+    pub async fn list_technologies(
+        &self,
+        filter: &TechnologyFilter,
+        page: &PageState,
+    ) -> Result<(TechnologyListResponse, Option<usize>), ApiError> {
+        self.get_paginated(&filter.endpoint(page)).await
+    }
+
+    /// Fetch the technology counts by name and by category
+    pub async fn get_technology_distribution(&self) -> Result<TechnologyDistribution, ApiError> {
+        self.get("/api/technologies/distribution").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_without_filters() {
+        let filter = TechnologyFilter::default();
+        assert_eq!(
+            filter.endpoint(&PageState::new(20)),
+            "/api/technologies?limit=20&offset=0"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_encodes_filters() {
+        let filter = TechnologyFilter {
+            search: " node.js ".to_string(),
+            category: Some("Web Server & Proxy".to_string()),
+        };
+        let mut page = PageState::new(10);
+        page.set_total(25);
+        page.next();
+        assert_eq!(
+            filter.endpoint(&page),
+            "/api/technologies?search=node.js&category=Web%20Server%20%26%20Proxy&limit=10&offset=10"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_skips_blank_filters() {
+        let filter = TechnologyFilter {
+            search: "   ".to_string(),
+            category: Some(String::new()),
+        };
+        assert_eq!(
+            filter.endpoint(&PageState::new(5)),
+            "/api/technologies?limit=5&offset=0"
+        );
+    }
+}
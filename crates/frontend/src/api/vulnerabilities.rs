@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ApiClient, ApiError};
+use crate::components::ui::pagination::PageState;
+
+/// Vulnerability as returned by the API
+#[derive(Deserialize, Debug, Clone)]
+pub struct VulnerabilitySummary {
+    pub id: String,
+    pub asset_id: String,
+    pub port_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub severity: String,
+    pub status: String,
+    pub cve_id: Option<String>,
+    pub cvss_score: Option<f64>,
+    pub remediation: Option<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Envelope returned by the paginated vulnerabilities list
+#[derive(Deserialize, Debug, Clone)]
+pub struct VulnerabilityListResponse {
+    pub vulnerabilities: Vec<VulnerabilitySummary>,
+    pub total: usize,
+}
+
+/// Body of `PATCH /api/vulnerabilities/bulk`
+#[derive(Serialize, Debug, Clone)]
+pub struct VulnerabilityStatusUpdate {
+    pub ids: Vec<String>,
+    pub status: String,
+}
+
+/// Number of vulnerabilities a status update changed
+#[derive(Deserialize, Debug, Clone)]
+pub struct BulkUpdateResponse {
+    pub updated: usize,
+}
+
+/// Severity and status filters for the vulnerabilities list, using the
+/// API's values (e.g. `HIGH`, `INPROGRESS`). `None` matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VulnerabilityFilter {
+    pub severity: Option<String>,
+    pub status: Option<String>,
+}
+
+impl VulnerabilityFilter {
+    /// The list endpoint for this filter and page, most severe first
+    pub fn endpoint(&self, page: &PageState) -> String {
+        let mut path = "/api/vulnerabilities?sort_by=severity&sort_order=desc".to_string();
+        if let Some(severity) = &self.severity {
+            path.push_str(&format!("&severity={}", severity));
+        }
+        if let Some(status) = &self.status {
+            path.push_str(&format!("&status={}", status));
+        }
+        page.endpoint(&path)
+    }
+}
+
+/// Display name for a vulnerability status as serialized by the API
+pub fn status_label(status: &str) -> &str {
+    match status {
+        "OPEN" => "Open",
+        "INPROGRESS" => "In Progress",
+        "CLOSED" => "Resolved",
+        "ACCEPTEDRISK" => "Accepted Risk",
+        "FALSEPOSITIVE" => "False Positive",
+        other => other,
+    }
+}
+
+impl ApiClient {
+    /// Fetch a page of vulnerabilities with the total matching the filter
+    pub async fn list_vulnerabilities(
+        &self,
+        filter: &VulnerabilityFilter,
+        page: &PageState,
+    ) -> Result<(VulnerabilityListResponse, Option<usize>), ApiError> {
+        self.get_paginated(&filter.endpoint(page)).await
+    }
+
+    /// Set the status of a vulnerability, e.g. `CLOSED` to resolve it
+    pub async fn update_vulnerability_status(
+        &self,
+        id: &str,
+        status: &str,
+    ) -> Result<BulkUpdateResponse, ApiError> {
+        let update = VulnerabilityStatusUpdate {
+            ids: vec![id.to_string()],
+            status: status.to_string(),
+        };
+        self.patch("/api/vulnerabilities/bulk", &update).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_without_filters() {
+        let filter = VulnerabilityFilter::default();
+        assert_eq!(
+            filter.endpoint(&PageState::new(20)),
+            "/api/vulnerabilities?sort_by=severity&sort_order=desc&limit=20&offset=0"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_with_filters() {
+        let filter = VulnerabilityFilter {
+            severity: Some("HIGH".to_string()),
+            status: Some("INPROGRESS".to_string()),
+        };
+        let mut page = PageState::new(10);
+        page.set_total(30);
+        page.next();
+        assert_eq!(
+            filter.endpoint(&page),
+            "/api/vulnerabilities?sort_by=severity&sort_order=desc&severity=HIGH&status=INPROGRESS&limit=10&offset=10"
+        );
+    }
+
+    #[test]
+    fn test_status_labels() {
+        assert_eq!(status_label("INPROGRESS"), "In Progress");
+        assert_eq!(status_label("CLOSED"), "Resolved");
+        assert_eq!(status_label("SOMETHING"), "SOMETHING");
+    }
+}
@@ -4,7 +4,8 @@ use leptos_router::components::*;
 use leptos_router::hooks::use_location;
 use wasm_bindgen_futures::spawn_local;
 
-use crate::utils::clear_auth_token;
+use crate::api::ApiClient;
+use crate::utils::{api_base, clear_auth_token, get_auth_token};
 
 #[component]
 pub fn AppLayout(children: Children) -> impl IntoView {
@@ -42,6 +43,15 @@ fn Navbar() -> impl IntoView {
     // Function to handle logout
     let handle_logout = move |_| {
         spawn_local(async move {
+            // Revoke the token server-side, so a copy of it stops working too
+            if let Some(token) = get_auth_token() {
+                let mut client = ApiClient::new(api_base()).without_auth_redirect();
+                client.set_token(token);
+                if let Err(e) = client.post::<(), _>("/api/auth/logout", &()).await {
+                    log::error!("Error revoking auth token: {}", e);
+                }
+            }
+
             // Clear the auth token
             if let Err(e) = clear_auth_token() {
                 log::error!("Error clearing auth token: {}", e);
@@ -3,4 +3,5 @@ pub mod asset_table;
 pub mod chart;
 pub mod data_table;
 pub mod discovery_task;
+pub mod pagination;
 pub mod vulnerability_card;
@@ -0,0 +1,230 @@
+use leptos::prelude::*;
+
+/// Default number of items requested per page
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Position within a paginated list, expressed as a zero-based page index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageState {
+    pub page: usize,
+    pub page_size: usize,
+    pub total: usize,
+}
+
+impl PageState {
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page: 0,
+            page_size: page_size.max(1),
+            total: 0,
+        }
+    }
+
+    /// Offset of the first item on the current page
+    pub fn offset(&self) -> usize {
+        self.page * self.page_size
+    }
+
+    pub fn has_previous(&self) -> bool {
+        self.page > 0
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.offset() + self.page_size < self.total
+    }
+
+    /// Move to the next page if there is one
+    pub fn next(&mut self) {
+        if self.has_next() {
+            self.page += 1;
+        }
+    }
+
+    /// Move to the previous page if there is one
+    pub fn previous(&mut self) {
+        if self.has_previous() {
+            self.page -= 1;
+        }
+    }
+
+    /// Update the total, stepping back to the last page if the current one
+    /// no longer exists (e.g. after deleting the only item on it)
+    pub fn set_total(&mut self, total: usize) {
+        self.total = total;
+        let last_page = total.saturating_sub(1) / self.page_size;
+        if self.page > last_page {
+            self.page = last_page;
+        }
+    }
+
+    /// Append `limit`/`offset` query parameters to an endpoint
+    pub fn endpoint(&self, path: &str) -> String {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        format!(
+            "{}{}limit={}&offset={}",
+            path,
+            separator,
+            self.page_size,
+            self.offset()
+        )
+    }
+
+    /// Human readable range, e.g. "Showing 21-40 of 57"
+    pub fn summary(&self) -> String {
+        if self.total == 0 {
+            return "Showing 0 of 0".to_string();
+        }
+        let first = self.offset() + 1;
+        let last = (self.offset() + self.page_size).min(self.total);
+        format!("Showing {}-{} of {}", first, last, self.total)
+    }
+}
+
+impl Default for PageState {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAGE_SIZE)
+    }
+}
+
+/// Step one page forward or back and, if the page changed, hand the new
+/// state to `on_change` so the caller refetches
+fn turn_page(state: RwSignal<PageState>, on_change: Callback<PageState>, forward: bool) {
+    let before = state.get_untracked();
+    state.update(|s| if forward { s.next() } else { s.previous() });
+    let after = state.get_untracked();
+    if after != before {
+        on_change.run(after);
+    }
+}
+
+/// Previous/Next controls bound to a page state signal
+#[component]
+pub fn Pagination(
+    /// Current pagination state; updated in place when a button is clicked
+    state: RwSignal<PageState>,
+    /// Called after the page changes so the caller can refetch
+    #[prop(into)]
+    on_change: Callback<PageState>,
+) -> impl IntoView {
+    let go_previous = move |_| turn_page(state, on_change, false);
+    let go_next = move |_| turn_page(state, on_change, true);
+
+    view! {
+        <div class="pagination">
+            <button
+                class="btn btn-outline-secondary"
+                disabled=move || !state.get().has_previous()
+                on:click=go_previous
+            >
+                "Previous"
+            </button>
+            <span class="pagination-info">{move || state.get().summary()}</span>
+            <button
+                class="btn btn-outline-secondary"
+                disabled=move || !state.get().has_next()
+                on:click=go_next
+            >
+                "Next"
+            </button>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_clicking_next_advances_offset_and_refetches() {
+        let mut initial = PageState::new(5);
+        initial.set_total(12);
+        let state = RwSignal::new(initial);
+
+        // Record what the page would fetch after each change
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let on_change = Callback::new({
+            let fetched = fetched.clone();
+            move |page: PageState| fetched.lock().unwrap().push(page.endpoint("/api/assets"))
+        });
+
+        turn_page(state, on_change, true);
+        assert_eq!(state.get_untracked().offset(), 5);
+        turn_page(state, on_change, true);
+        assert_eq!(state.get_untracked().offset(), 10);
+
+        // Past the last page nothing changes, so nothing is refetched
+        turn_page(state, on_change, true);
+        turn_page(state, on_change, false);
+        assert_eq!(state.get_untracked().offset(), 5);
+
+        assert_eq!(
+            *fetched.lock().unwrap(),
+            vec![
+                "/api/assets?limit=5&offset=5",
+                "/api/assets?limit=5&offset=10",
+                "/api/assets?limit=5&offset=5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_advances_offset_and_endpoint() {
+        let mut state = PageState::new(5);
+        state.set_total(12);
+        assert_eq!(
+            state.endpoint("/api/assets"),
+            "/api/assets?limit=5&offset=0"
+        );
+
+        state.next();
+        assert_eq!(state.offset(), 5);
+        assert_eq!(
+            state.endpoint("/api/assets"),
+            "/api/assets?limit=5&offset=5"
+        );
+        assert_eq!(state.summary(), "Showing 6-10 of 12");
+    }
+
+    #[test]
+    fn test_bounds_disable_navigation() {
+        let mut state = PageState::new(5);
+        state.set_total(7);
+        assert!(!state.has_previous());
+        assert!(state.has_next());
+
+        state.next();
+        assert!(state.has_previous());
+        assert!(!state.has_next());
+
+        // Already on the last page
+        state.next();
+        assert_eq!(state.page, 1);
+        assert_eq!(state.summary(), "Showing 6-7 of 7");
+    }
+
+    #[test]
+    fn test_set_total_clamps_page() {
+        let mut state = PageState::new(5);
+        state.set_total(11);
+        state.next();
+        state.next();
+        assert_eq!(state.page, 2);
+
+        state.set_total(10);
+        assert_eq!(state.page, 1);
+
+        state.set_total(0);
+        assert_eq!(state.page, 0);
+        assert_eq!(state.summary(), "Showing 0 of 0");
+    }
+
+    #[test]
+    fn test_endpoint_with_existing_query() {
+        let state = PageState::new(10);
+        assert_eq!(
+            state.endpoint("/api/assets?status=ACTIVE"),
+            "/api/assets?status=ACTIVE&limit=10&offset=0"
+        );
+    }
+}
@@ -1,137 +1,354 @@
-use crate::api::ApiClient;
-use crate::components::ui::asset_card::Asset;
+use crate::api::assets::AssetDetails;
+use crate::api::{ApiClient, ApiError};
 use crate::components::ui::discovery_task::DiscoveryTaskForm;
+use crate::utils::{api_base, get_auth_token};
 use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
 use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
 
+// Tabs shown below the asset summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DetailTab {
+    Related,
+    Ports,
+    Technologies,
+    Vulnerabilities,
+}
+
+impl DetailTab {
+    const ALL: [DetailTab; 4] = [
+        DetailTab::Related,
+        DetailTab::Ports,
+        DetailTab::Technologies,
+        DetailTab::Vulnerabilities,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            DetailTab::Related => "Related Assets",
+            DetailTab::Ports => "Open Ports",
+            DetailTab::Technologies => "Technologies",
+            DetailTab::Vulnerabilities => "Vulnerabilities",
+        }
+    }
+
+    fn count(self, details: &AssetDetails) -> usize {
+        match self {
+            DetailTab::Related => details.related_assets.len(),
+            DetailTab::Ports => details.ports.len(),
+            DetailTab::Technologies => details.technologies.len(),
+            DetailTab::Vulnerabilities => details.vulnerabilities.len(),
+        }
+    }
+}
+
+#[allow(non_snake_case)]
 #[component]
 pub fn AssetDetailPage() -> impl IntoView {
-    // Get the asset ID from the URL (in a real application)
-    // For demo purposes, we'll use a fixed asset
-    let asset_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap_or_default();
-    
-    // We'd fetch the asset details using the API client
-    let _api_client = ApiClient::new("http://localhost:3000".to_string());
-    
-    // Mock asset data (in a real app, this would come from an API call)
-    let (asset, _set_asset) = create_signal(Some(Asset {
-        id: asset_id.to_string(),
-        name: "example.com".to_string(),
-        asset_type: "Domain".to_string(),
-        status: "Active".to_string(),
-        discovery_date: "2023-03-15".to_string(),
-        vulnerabilities_count: 3,
-    }));
-    
+    let params = use_params_map();
+    let asset_id = move || params.with(|p| p.get("id")).unwrap_or_default();
+
+    // Create API client
+    let api_client = RwSignal::new(ApiClient::new(api_base()));
+
+    // Set token if available
+    if let Some(token) = get_auth_token() {
+        api_client.update(|client| client.set_token(token));
+    }
+
+    let (details, set_details) = signal::<Option<AssetDetails>>(None);
+    let (loading, set_loading) = signal(false);
+    let (error, set_error) = signal::<Option<String>>(None);
+    let (active_tab, set_active_tab) = signal(DetailTab::Related);
+
     // State for showing the discovery task form
-    let (show_discovery_task_form, set_show_discovery_task_form) = create_signal(false);
-    
-    // Handler for the "Add Discovery Task" button
-    let add_discovery_task = move |_| {
-        set_show_discovery_task_form.set(true);
+    let (show_discovery_task_form, set_show_discovery_task_form) = signal(false);
+
+    // Function to fetch the asset aggregate from the API
+    let fetch_details = move || {
+        let id = asset_id();
+        if id.is_empty() {
+            return;
+        }
+
+        set_loading.set(true);
+        set_error.set(None);
+
+        let client = api_client.get_untracked();
+        spawn_local(async move {
+            match client.get_asset_details(&id).await {
+                Ok(response) => {
+                    set_details.set(Some(response));
+                    set_loading.set(false);
+                }
+                Err(e) => {
+                    let error_msg = match e {
+                        ApiError::AuthError(_) => {
+                            "Authentication error - please log in again".to_string()
+                        }
+                        ApiError::NotFound => "Asset not found".to_string(),
+                        ApiError::NetworkError(msg) => format!("Network error: {}", msg),
+                        ApiError::ServerError(msg) => format!("Server error: {}", msg),
+                        _ => "Failed to fetch asset details".to_string(),
+                    };
+
+                    set_error.set(Some(error_msg));
+                    set_loading.set(false);
+                }
+            }
+        });
     };
-    
+
+    // Refetch whenever the :id route parameter changes
+    Effect::new(move |_| {
+        asset_id();
+        fetch_details();
+    });
+
     // Handler for task creation success
     let on_task_created = move |_| {
         set_show_discovery_task_form.set(false);
-        // Refresh asset data or tasks list
+        fetch_details();
     };
-    
+
     // Handler for cancelling task creation
     let on_task_cancel = move |_| {
         set_show_discovery_task_form.set(false);
     };
-    
+
+    let summary_view = move || {
+        details.get().map(|details| {
+            let asset = details.asset;
+            view! {
+                <div class="bg-white shadow overflow-hidden sm:rounded-lg">
+                    <div class="px-4 py-5 sm:px-6 bg-gray-50">
+                        <h3 class="text-lg font-medium leading-6 text-gray-900">{asset.value}</h3>
+                        <p class="mt-1 max-w-2xl text-sm text-gray-500">{"ID: "}{asset.id}</p>
+                    </div>
+                    <div class="border-t border-gray-200">
+                        <dl>
+                            <div class="bg-white px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
+                                <dt class="text-sm font-medium text-gray-500">"Type"</dt>
+                                <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">{asset.asset_type}</dd>
+                            </div>
+                            <div class="bg-gray-50 px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
+                                <dt class="text-sm font-medium text-gray-500">"Status"</dt>
+                                <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">{asset.status}</dd>
+                            </div>
+                            <div class="bg-white px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
+                                <dt class="text-sm font-medium text-gray-500">"First Seen"</dt>
+                                <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">{asset.first_seen}</dd>
+                            </div>
+                            <div class="bg-gray-50 px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
+                                <dt class="text-sm font-medium text-gray-500">"Last Seen"</dt>
+                                <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">{asset.last_seen}</dd>
+                            </div>
+                        </dl>
+                    </div>
+                </div>
+            }
+        })
+    };
+
+    let tabs_view = move || {
+        details.get().map(|details| {
+            DetailTab::ALL
+                .into_iter()
+                .map(|tab| {
+                    let count = tab.count(&details);
+                    let class = move || {
+                        if active_tab.get() == tab {
+                            "px-4 py-2 border-b-2 border-blue-600 font-medium text-blue-600"
+                        } else {
+                            "px-4 py-2 border-b-2 border-transparent text-gray-500 hover:text-gray-700"
+                        }
+                    };
+                    view! {
+                        <button class=class on:click=move |_| set_active_tab.set(tab)>
+                            {format!("{} ({})", tab.label(), count)}
+                        </button>
+                    }
+                })
+                .collect_view()
+        })
+    };
+
+    let tab_content = move || {
+        let Some(details) = details.get() else {
+            return view! { <div></div> }.into_any();
+        };
+
+        match active_tab.get() {
+            DetailTab::Related => view! {
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>"Relationship"</th>
+                            <th>"Type"</th>
+                            <th>"Value"</th>
+                            <th>"Status"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {details.related_assets.into_iter().map(|related| {
+                            let href = format!("/app/assets/{}", related.asset.id);
+                            view! {
+                                <tr>
+                                    <td>{related.relationship_type}</td>
+                                    <td>{related.asset.asset_type}</td>
+                                    <td><a href=href class="text-blue-600 hover:underline">{related.asset.value}</a></td>
+                                    <td>{related.asset.status}</td>
+                                </tr>
+                            }
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            }
+            .into_any(),
+            DetailTab::Ports => view! {
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>"Port"</th>
+                            <th>"Protocol"</th>
+                            <th>"Service"</th>
+                            <th>"Banner"</th>
+                            <th>"Status"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {details.ports.into_iter().map(|port| view! {
+                            <tr>
+                                <td>{port.port_number}</td>
+                                <td>{port.protocol}</td>
+                                <td>{port.service_name.unwrap_or_default()}</td>
+                                <td>{port.banner.unwrap_or_default()}</td>
+                                <td>{port.status}</td>
+                            </tr>
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            }
+            .into_any(),
+            DetailTab::Technologies => view! {
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>"Name"</th>
+                            <th>"Version"</th>
+                            <th>"Category"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {details.technologies.into_iter().map(|technology| view! {
+                            <tr>
+                                <td>{technology.name}</td>
+                                <td>{technology.version.unwrap_or_default()}</td>
+                                <td>{technology.category.unwrap_or_default()}</td>
+                            </tr>
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            }
+            .into_any(),
+            DetailTab::Vulnerabilities => view! {
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>"Title"</th>
+                            <th>"Severity"</th>
+                            <th>"Status"</th>
+                            <th>"CVE"</th>
+                            <th>"CVSS"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {details.vulnerabilities.into_iter().map(|vulnerability| view! {
+                            <tr>
+                                <td>{vulnerability.title}</td>
+                                <td>
+                                    <span class=format!("status-badge severity-{}", vulnerability.severity.to_lowercase())>
+                                        {vulnerability.severity.clone()}
+                                    </span>
+                                </td>
+                                <td>{vulnerability.status}</td>
+                                <td>{vulnerability.cve_id.unwrap_or_default()}</td>
+                                <td>{vulnerability.cvss_score.map(|score| format!("{:.1}", score)).unwrap_or_default()}</td>
+                            </tr>
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            }
+            .into_any(),
+        }
+    };
+
     view! {
         <div class="container mx-auto px-4 py-8">
             <div class="flex justify-between items-center mb-6">
-                <h1 class="text-2xl font-bold">Asset Details</h1>
+                <h1 class="text-2xl font-bold">"Asset Details"</h1>
                 <div class="flex space-x-2">
                     <button
                         class="px-4 py-2 bg-blue-600 text-white rounded hover:bg-blue-700"
-                        on:click=add_discovery_task
+                        disabled=move || details.get().is_none()
+                        on:click=move |_| set_show_discovery_task_form.set(true)
                     >
                         "Add Discovery Task"
                     </button>
                     <a
-                        href="/assets"
+                        href="/app/assets"
                         class="px-4 py-2 border border-gray-300 rounded hover:bg-gray-50"
                     >
                         "Back to Assets"
                     </a>
                 </div>
             </div>
-            
+
+            {move || {
+                error.get().map(|err| view! {
+                    <div class="alert alert-danger">{err}</div>
+                })
+            }}
+
             <Show
-                when=move || asset.get().is_some()
+                when=move || !loading.get() || details.get().is_some()
                 fallback=|| view! { <div>"Loading asset details..."</div> }
             >
+                {summary_view}
+
+                <div class="mt-8">
+                    <div class="flex border-b border-gray-200 mb-4">{tabs_view}</div>
+                    <div class="bg-white shadow overflow-hidden sm:rounded-lg p-4">{tab_content}</div>
+                </div>
+            </Show>
+
+            <Show when=move || show_discovery_task_form.get()>
                 {move || {
-                    let asset = asset.get().unwrap();
+                    let (asset_uuid, organization_uuid) = details
+                        .get()
+                        .map(|details| {
+                            (
+                                Uuid::parse_str(&details.asset.id).ok(),
+                                Uuid::parse_str(&details.asset.organization_id).ok(),
+                            )
+                        })
+                        .unwrap_or((None, None));
+
                     view! {
-                        <div class="bg-white shadow overflow-hidden sm:rounded-lg">
-                            <div class="px-4 py-5 sm:px-6 bg-gray-50">
-                                <h3 class="text-lg font-medium leading-6 text-gray-900">
-                                    {asset.name}
-                                </h3>
-                                <p class="mt-1 max-w-2xl text-sm text-gray-500">
-                                    {"ID: "}{asset.id}
-                                </p>
-                            </div>
-                            <div class="border-t border-gray-200">
-                                <dl>
-                                    <div class="bg-white px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
-                                        <dt class="text-sm font-medium text-gray-500">Type</dt>
-                                        <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">
-                                            {asset.asset_type}
-                                        </dd>
-                                    </div>
-                                    <div class="bg-gray-50 px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
-                                        <dt class="text-sm font-medium text-gray-500">Status</dt>
-                                        <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">
-                                            {asset.status}
-                                        </dd>
-                                    </div>
-                                    <div class="bg-white px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
-                                        <dt class="text-sm font-medium text-gray-500">Discovery Date</dt>
-                                        <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">
-                                            {asset.discovery_date}
-                                        </dd>
-                                    </div>
-                                    <div class="bg-gray-50 px-4 py-5 sm:grid sm:grid-cols-3 sm:gap-4 sm:px-6">
-                                        <dt class="text-sm font-medium text-gray-500">Vulnerabilities</dt>
-                                        <dd class="mt-1 text-sm text-gray-900 sm:mt-0 sm:col-span-2">
-                                            {asset.vulnerabilities_count}
-                                        </dd>
-                                    </div>
-                                </dl>
+                        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+                            <div class="bg-white rounded-lg shadow-xl max-w-lg w-full">
+                                <DiscoveryTaskForm
+                                    asset_id=asset_uuid.unwrap_or_default()
+                                    organization_id=organization_uuid.unwrap_or_default()
+                                    on_success=on_task_created
+                                    on_cancel=on_task_cancel
+                                />
                             </div>
                         </div>
                     }
                 }}
             </Show>
-            
-            <div class="mt-8">
-                <h2 class="text-xl font-bold mb-4">Recent Discovery Tasks</h2>
-                
-                // We'd fetch and display discovery tasks for this asset
-                <div class="bg-white shadow overflow-hidden sm:rounded-lg p-4">
-                    <p class="text-gray-500 italic">No discovery tasks found for this asset.</p>
-                </div>
-            </div>
-            
-            <Show when=move || show_discovery_task_form.get()>
-                <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
-                    <div class="bg-white rounded-lg shadow-xl max-w-lg w-full">
-                        <DiscoveryTaskForm
-                            asset_id=Some(asset_id)
-                            organization_id=Some(Uuid::nil()) // In a real app, we'd use the asset's organization_id
-                            on_success=on_task_created
-                            on_cancel=on_task_cancel
-                        />
-                    </div>
-                </div>
-            </Show>
         </div>
     }
-} 
\ No newline at end of file
+}
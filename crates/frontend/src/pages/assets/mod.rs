@@ -1,8 +1,13 @@
-use crate::api::{ApiClient, ApiError};
+pub mod detail;
+
+use crate::api::{events::on_resource_change, ApiClient, ApiError};
 use crate::components::ui::asset_card::Asset;
-use crate::utils::get_auth_token;
+use crate::components::ui::pagination::{PageState, Pagination};
+use crate::utils::{api_base, get_auth_token};
 use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
 use serde::{Deserialize, Serialize};
+use shared::types::ResourceKind;
 use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlInputElement;
 
@@ -28,6 +33,13 @@ struct AssetResponse {
     vulnerabilities_count: Option<i32>,
 }
 
+// Envelope returned by the paginated assets list
+#[derive(Deserialize, Debug, Clone)]
+struct AssetListResponse {
+    assets: Vec<AssetResponse>,
+    total: usize,
+}
+
 // Modal states
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum ModalState {
@@ -41,7 +53,7 @@ enum ModalState {
 #[component]
 pub fn AssetsPage() -> impl IntoView {
     // Create API client
-    let api_client = RwSignal::new(ApiClient::new("http://localhost:3000".to_string()));
+    let api_client = RwSignal::new(ApiClient::new(api_base()));
 
     // Set token if available
     if let Some(token) = get_auth_token() {
@@ -53,6 +65,7 @@ pub fn AssetsPage() -> impl IntoView {
     let (loading, set_loading) = signal(false);
     let (error, set_error) = signal::<Option<String>>(None);
     let (modal_state, set_modal_state) = signal(ModalState::Closed);
+    let page_state = RwSignal::new(PageState::default());
 
     // Form signals
     let (form_asset_type, set_form_asset_type) = signal(String::new());
@@ -79,11 +92,17 @@ pub fn AssetsPage() -> impl IntoView {
         set_error.set(None);
 
         let client = api_client.get().clone();
+        let endpoint = page_state.get_untracked().endpoint("/api/assets");
         spawn_local(async move {
-            match client.get::<Vec<AssetResponse>>("/api/assets").await {
-                Ok(response) => {
+            match client.get_paginated::<AssetListResponse>(&endpoint).await {
+                Ok((response, total)) => {
+                    // Prefer the header total, falling back to the body
+                    let total = total.unwrap_or(response.total);
+                    page_state.update(|state| state.set_total(total));
+
                     // Convert API response to Asset format
                     let mapped_assets: Vec<Asset> = response
+                        .assets
                         .into_iter()
                         .map(|a| Asset {
                             id: a.id,
@@ -253,6 +272,9 @@ pub fn AssetsPage() -> impl IntoView {
         fetch_assets();
     });
 
+    // Refresh when a scan or another user adds or changes an asset
+    on_resource_change(ResourceKind::Asset, fetch_assets);
+
     // Loading indicator
     let loading_view = move || {
         loading.get().then(|| {
@@ -314,6 +336,8 @@ pub fn AssetsPage() -> impl IntoView {
                                     let name_for_delete = asset.name.clone();
                                     let asset_type_for_edit = asset.asset_type.clone();
                                     let name_for_edit = asset.name.clone();
+                                    let detail_path = format!("/app/assets/{}", asset.id);
+                                    let navigate = use_navigate();
 
                                     view! {
                                         <tr>
@@ -326,6 +350,13 @@ pub fn AssetsPage() -> impl IntoView {
                                             </td>
                                             <td>{asset.discovery_date}</td>
                                             <td class="actions-cell">
+                                                <button
+                                                    class="btn btn-icon btn-sm"
+                                                    title="View asset"
+                                                    on:click=move |_| navigate(&detail_path, Default::default())
+                                                >
+                                                    "👁️"
+                                                </button>
                                                 <button
                                                     class="btn btn-icon btn-sm"
                                                     title="Edit asset"
@@ -441,6 +472,8 @@ pub fn AssetsPage() -> impl IntoView {
                 {assets_grid()}
             </div>
 
+            <Pagination state=page_state on_change=move |_| fetch_assets() />
+
             // Modals container using leptos Show component
             <div class="modals-container">
                 <Show
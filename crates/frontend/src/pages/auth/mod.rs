@@ -1,6 +1,6 @@
 use crate::api::{ApiClient, ApiError};
 use crate::pages::auth::hooks::use_auth_navigate;
-use crate::utils::{clear_auth_token, get_auth_token, save_auth_token};
+use crate::utils::{api_base, clear_auth_token, get_auth_token, save_auth_token};
 use leptos::prelude::*;
 use leptos_router::*;
 use serde::{Deserialize, Serialize};
@@ -27,7 +27,8 @@ pub fn LoginPage() -> impl IntoView {
     let (error, set_error) = signal(String::new());
     let (loading, set_loading) = signal(false);
     let navigate = use_auth_navigate();
-    let api_client = ApiClient::new("http://localhost:3000".to_string());
+    // Bad credentials come back as 401; show them here instead of redirecting
+    let api_client = ApiClient::new(api_base()).without_auth_redirect();
 
     // Check if we already have a token
     let api_client_clone = api_client.clone();
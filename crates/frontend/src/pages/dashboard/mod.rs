@@ -1,11 +1,26 @@
+use crate::api::dashboard::DashboardStats;
+use crate::api::{ApiClient, ApiError};
 use crate::components::ui::chart::{Chart, ChartData, ChartDataset, ChartType};
-use crate::utils::{format_date, format_severity, severity_class, truncate};
+use crate::utils::{api_base, format_date, get_auth_token, truncate};
 use leptos::prelude::*;
+use wasm_bindgen_futures::spawn_local;
 
-#[component]
-pub fn DashboardPage() -> impl IntoView {
-    // Create chart data (assuming this data might be dynamic later)
-    let vulnerability_chart_data = ChartData {
+/// Display name for an asset type as serialized by the API
+fn asset_type_label(asset_type: &str) -> &str {
+    match asset_type {
+        "DOMAIN" => "Domain",
+        "IPADDRESS" => "IP Address",
+        "WEBAPP" => "Web App",
+        "CERTIFICATE" => "Certificate",
+        "CODEREPO" => "Code Repository",
+        "CLOUDRESOURCE" => "Cloud Resource",
+        other => other,
+    }
+}
+
+fn vulnerability_chart_data(stats: &DashboardStats) -> ChartData {
+    let counts = &stats.open_vulnerabilities_by_severity;
+    ChartData {
         labels: vec![
             "Critical".to_string(),
             "High".to_string(),
@@ -15,7 +30,16 @@ pub fn DashboardPage() -> impl IntoView {
         ],
         datasets: vec![ChartDataset {
             label: "Vulnerabilities".to_string(),
-            data: vec![5.0, 15.0, 12.0, 8.0, 2.0],
+            data: [
+                counts.critical,
+                counts.high,
+                counts.medium,
+                counts.low,
+                counts.info,
+            ]
+            .into_iter()
+            .map(|count| count as f64)
+            .collect(),
             // Using slightly softer, modern colors inspired by examples
             background_colors: Some(vec![
                 "rgba(199, 0, 57, 0.7)".to_string(),    // Crimson
@@ -32,41 +56,117 @@ pub fn DashboardPage() -> impl IntoView {
                 "rgb(149, 165, 166)".to_string(),
             ]),
         }],
-    };
+    }
+}
 
-    let asset_chart_data = ChartData {
+fn asset_chart_data(stats: &DashboardStats) -> ChartData {
+    let counts = &stats.assets_by_type;
+    ChartData {
         labels: vec![
             "Domains".to_string(),
             "IPs".to_string(),
-            "Subdomains".to_string(),
-            "URLs".to_string(),
+            "Web Apps".to_string(),
+            "Certificates".to_string(),
+            "Code Repos".to_string(),
+            "Cloud".to_string(),
         ],
         datasets: vec![ChartDataset {
             label: "Assets".to_string(),
-            data: vec![42.0, 36.0, 68.0, 125.0],
+            data: [
+                counts.domain,
+                counts.ip_address,
+                counts.web_app,
+                counts.certificate,
+                counts.code_repo,
+                counts.cloud_resource,
+            ]
+            .into_iter()
+            .map(|count| count as f64)
+            .collect(),
             background_colors: Some(vec![
-                "rgba(52, 152, 219, 0.7)".to_string(), // Belize Hole Blue
-                "rgba(46, 204, 113, 0.7)".to_string(), // Emerald Green
-                "rgba(241, 196, 15, 0.7)".to_string(), // Sun Flower Yellow
-                "rgba(231, 76, 60, 0.7)".to_string(),  // Alizarin Red
+                "rgba(52, 152, 219, 0.7)".to_string(),  // Belize Hole Blue
+                "rgba(46, 204, 113, 0.7)".to_string(),  // Emerald Green
+                "rgba(241, 196, 15, 0.7)".to_string(),  // Sun Flower Yellow
+                "rgba(231, 76, 60, 0.7)".to_string(),   // Alizarin Red
+                "rgba(155, 89, 182, 0.7)".to_string(),  // Amethyst
+                "rgba(149, 165, 166, 0.7)".to_string(), // Silver
             ]),
             border_colors: Some(vec![
                 "rgb(52, 152, 219)".to_string(),
                 "rgb(46, 204, 113)".to_string(),
                 "rgb(241, 196, 15)".to_string(),
                 "rgb(231, 76, 60)".to_string(),
+                "rgb(155, 89, 182)".to_string(),
+                "rgb(149, 165, 166)".to_string(),
             ]),
         }],
+    }
+}
+
+#[component]
+pub fn DashboardPage() -> impl IntoView {
+    // Create API client
+    let api_client = RwSignal::new(ApiClient::new(api_base()));
+
+    // Set token if available
+    if let Some(token) = get_auth_token() {
+        api_client.update(|client| client.set_token(token));
+    }
+
+    let (stats, set_stats) = signal::<Option<DashboardStats>>(None);
+    let (error, set_error) = signal::<Option<String>>(None);
+
+    spawn_local(async move {
+        let client = api_client.get_untracked();
+        match client.get_dashboard_stats().await {
+            Ok(response) => set_stats.set(Some(response)),
+            Err(e) => {
+                let error_msg = match e {
+                    ApiError::AuthError(_) => {
+                        "Authentication error - please log in again".to_string()
+                    }
+                    ApiError::NetworkError(msg) => format!("Network error: {}", msg),
+                    ApiError::ServerError(msg) => format!("Server error: {}", msg),
+                    _ => "Failed to fetch dashboard statistics".to_string(),
+                };
+                set_error.set(Some(error_msg));
+            }
+        }
+    });
+
+    // Counts render as a dash until the statistics have loaded
+    let stat = move |count: fn(&DashboardStats) -> usize| {
+        move || {
+            stats
+                .get()
+                .map(|stats| count(&stats).to_string())
+                .unwrap_or_else(|| "-".to_string())
+        }
     };
 
-    // Sample data (keeping structure, just using utils)
-    let sample_date = "2023-04-15T14:32:18Z";
-    let formatted_date = format_date(sample_date);
-    let severity_critical = "critical";
-    let severity_medium = "medium";
-    let severity_low = "low";
-    let long_text = "Cross-Site Scripting (XSS) vulnerability found on login page allowing attackers to inject malicious scripts.";
-    let truncated_text_title = truncate(long_text, 40); // Slightly longer truncation for title
+    let recent_discoveries = move || {
+        stats
+            .get()
+            .map(|stats| stats.recent_discoveries)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|asset| {
+                let badge = if asset.status == "ACTIVE" {
+                    "badge bg-green-100 text-green-700 rounded-full px-3 py-1 text-xs font-medium"
+                } else {
+                    "badge bg-gray-100 text-gray-700 rounded-full px-3 py-1 text-xs font-medium"
+                };
+                view! {
+                    <tr class="hover:bg-gray-50 border-b border-gray-100">
+                        <td class="py-3 px-4">{asset_type_label(&asset.asset_type).to_string()}</td>
+                        <td class="py-3 px-4">{truncate(&asset.value, 25)}</td>
+                        <td class="py-3 px-4">{format_date(&asset.first_seen)}</td>
+                        <td class="py-3 px-4"><span class=badge>{asset.status}</span></td>
+                    </tr>
+                }
+            })
+            .collect_view()
+    };
 
     view! {
         // Use padding for overall spacing
@@ -77,12 +177,16 @@ pub fn DashboardPage() -> impl IntoView {
                 <p class="text-gray-500">"Your central hub for asset and vulnerability insights."</p>
             </div>
 
+            {move || error.get().map(|err| view! {
+                <div class="alert alert-danger">{err}</div>
+            })}
+
             // Updated grid and card styles
             <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6">
                 <div class="card bg-white shadow-lg rounded-xl p-6 transition hover:shadow-xl">
                     <h2 class="text-lg font-semibold text-gray-700 mb-3">"Asset Summary"</h2>
                     <div class="stat flex items-baseline space-x-2">
-                        <span class="stat-value text-4xl font-bold text-blue-600">"125"</span>
+                        <span class="stat-value text-4xl font-bold text-blue-600">{stat(|stats| stats.total_assets)}</span>
                         <span class="stat-label text-gray-500">"Total Assets"</span>
                     </div>
                 </div>
@@ -90,7 +194,7 @@ pub fn DashboardPage() -> impl IntoView {
                 <div class="card bg-white shadow-lg rounded-xl p-6 transition hover:shadow-xl">
                     <h2 class="text-lg font-semibold text-gray-700 mb-3">"Vulnerability Summary"</h2>
                     <div class="stat flex items-baseline space-x-2">
-                        <span class="stat-value text-4xl font-bold text-red-600">"42"</span>
+                        <span class="stat-value text-4xl font-bold text-red-600">{stat(|stats| stats.open_vulnerabilities)}</span>
                         <span class="stat-label text-gray-500">"Open Vulnerabilities"</span>
                     </div>
                 </div>
@@ -98,7 +202,7 @@ pub fn DashboardPage() -> impl IntoView {
                 <div class="card bg-white shadow-lg rounded-xl p-6 transition hover:shadow-xl">
                     <h2 class="text-lg font-semibold text-gray-700 mb-3">"Technology Summary"</h2>
                     <div class="stat flex items-baseline space-x-2">
-                        <span class="stat-value text-4xl font-bold text-green-600">"18"</span>
+                        <span class="stat-value text-4xl font-bold text-green-600">{stat(|stats| stats.technologies)}</span>
                         <span class="stat-label text-gray-500">"Detected Technologies"</span>
                     </div>
                 </div>
@@ -110,26 +214,30 @@ pub fn DashboardPage() -> impl IntoView {
                     <h2 class="text-lg font-semibold text-gray-700 mb-4">"Vulnerabilities by Severity"</h2>
                     // Ensure chart component adapts to container
                     <div class="h-[250px] flex justify-center items-center">
-                        <Chart
-                            title="" // Title is now part of the card header
-                            chart_type=ChartType::Pie
-                            data=vulnerability_chart_data
-                            height=250 // Adjust as needed
-                            show_legend=true
-                        />
+                        {move || stats.get().map(|stats| view! {
+                            <Chart
+                                title="" // Title is now part of the card header
+                                chart_type=ChartType::Pie
+                                data=vulnerability_chart_data(&stats)
+                                height=250 // Adjust as needed
+                                show_legend=true
+                            />
+                        })}
                     </div>
                 </div>
 
                 <div class="card bg-white shadow-lg rounded-xl p-6 transition hover:shadow-xl">
                     <h2 class="text-lg font-semibold text-gray-700 mb-4">"Assets by Type"</h2>
                     <div class="h-[250px] flex justify-center items-center">
-                        <Chart
-                            title="" // Title is now part of the card header
-                            chart_type=ChartType::Bar // Changed to Bar for variety, matching image 1
-                            data=asset_chart_data
-                            height=250 // Adjust as needed
-                            show_legend=false // Often cleaner for bar charts
-                        />
+                        {move || stats.get().map(|stats| view! {
+                            <Chart
+                                title="" // Title is now part of the card header
+                                chart_type=ChartType::Bar // Changed to Bar for variety, matching image 1
+                                data=asset_chart_data(&stats)
+                                height=250 // Adjust as needed
+                                show_legend=false // Often cleaner for bar charts
+                            />
+                        })}
                     </div>
                 </div>
             </div>
@@ -147,60 +255,7 @@ pub fn DashboardPage() -> impl IntoView {
                         </tr>
                     </thead>
                     <tbody class="text-gray-700">
-                        <tr class="hover:bg-gray-50 border-b border-gray-100">
-                            <td class="py-3 px-4">"Domain"</td>
-                            <td class="py-3 px-4">{truncate("example-long-domain-name.com", 25)}</td>
-                            <td class="py-3 px-4">{formatted_date.clone()}</td>
-                            <td class="py-3 px-4"><span class="badge bg-green-100 text-green-700 rounded-full px-3 py-1 text-xs font-medium">"Active"</span></td>
-                        </tr>
-                        <tr class="hover:bg-gray-50 border-b border-gray-100">
-                            <td class="py-3 px-4">"IP Address"</td>
-                            <td class="py-3 px-4">"198.51.100.42"</td>
-                            <td class="py-3 px-4">{"2023-04-14 08:15:10"}</td>
-                            <td class="py-3 px-4"><span class="badge bg-green-100 text-green-700 rounded-full px-3 py-1 text-xs font-medium">"Active"</span></td>
-                        </tr>
-                        <tr class="hover:bg-gray-50">
-                            <td class="py-3 px-4">"Technology"</td>
-                            <td class="py-3 px-4">"Nginx 1.23.1"</td>
-                            <td class="py-3 px-4">{"2023-04-14 07:22:30"}</td>
-                            <td class="py-3 px-4"><span class="badge bg-blue-100 text-blue-700 rounded-full px-3 py-1 text-xs font-medium">"Detected"</span></td>
-                        </tr>
-                    </tbody>
-                </table>
-            </div>
-
-            // Recent Vulnerabilities Table
-            <div class="card bg-white shadow-lg rounded-xl p-6 transition hover:shadow-xl overflow-x-auto">
-                <h2 class="text-lg font-semibold text-gray-700 mb-4">"Recent Vulnerabilities"</h2>
-                <table class="table w-full text-left border-collapse">
-                    <thead>
-                        <tr class="border-b border-gray-200">
-                            <th class="py-3 px-4 font-semibold text-sm text-gray-600 uppercase">"Title"</th>
-                            <th class="py-3 px-4 font-semibold text-sm text-gray-600 uppercase">"Asset"</th>
-                            <th class="py-3 px-4 font-semibold text-sm text-gray-600 uppercase">"Severity"</th>
-                            <th class="py-3 px-4 font-semibold text-sm text-gray-600 uppercase">"Status"</th>
-                        </tr>
-                    </thead>
-                    <tbody class="text-gray-700">
-                        <tr class="hover:bg-gray-50 border-b border-gray-100">
-                            <td class="py-3 px-4">{truncated_text_title}</td>
-                            <td class="py-3 px-4">"login.example.com"</td>
-                            // Using severity_class for dynamic styling based on severity
-                            <td class="py-3 px-4"><span class={format!("badge rounded-full px-3 py-1 text-xs font-medium {}", severity_class(severity_critical))}>{format_severity(severity_critical)}</span></td>
-                            <td class="py-3 px-4"><span class="badge bg-red-100 text-red-700 rounded-full px-3 py-1 text-xs font-medium">"Open"</span></td>
-                        </tr>
-                        <tr class="hover:bg-gray-50 border-b border-gray-100">
-                            <td class="py-3 px-4">"TLS Certificate Expiring Soon"</td>
-                            <td class="py-3 px-4">"api.example.com"</td>
-                            <td class="py-3 px-4"><span class={format!("badge rounded-full px-3 py-1 text-xs font-medium {}", severity_class(severity_medium))}>{format_severity(severity_medium)}</span></td>
-                            <td class="py-3 px-4"><span class="badge bg-yellow-100 text-yellow-700 rounded-full px-3 py-1 text-xs font-medium">"Review"</span></td>
-                        </tr>
-                        <tr class="hover:bg-gray-50">
-                            <td class="py-3 px-4">"Open RDP Port (3389)"</td>
-                            <td class="py-3 px-4">"198.51.100.42"</td>
-                            <td class="py-3 px-4"><span class={format!("badge rounded-full px-3 py-1 text-xs font-medium {}", severity_class(severity_low))}>{format_severity(severity_low)}</span></td>
-                            <td class="py-3 px-4"><span class="badge bg-red-100 text-red-700 rounded-full px-3 py-1 text-xs font-medium">"Open"</span></td>
-                        </tr>
+                        {recent_discoveries}
                     </tbody>
                 </table>
             </div>
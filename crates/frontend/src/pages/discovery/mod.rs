@@ -1,5 +1,5 @@
 use crate::api::{ApiClient, ApiError};
-use crate::utils::get_auth_token;
+use crate::utils::{api_base, format_duration, get_auth_token};
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -17,6 +17,8 @@ struct DiscoveryJobRequest {
 // Configuration for discovery jobs
 #[derive(Serialize, Debug, Clone)]
 struct DiscoveryConfiguration {
+    /// Links the crawler follows away from each target's start page, its
+    /// scope's `max_depth`
     depth: u8,
     methods: Vec<String>,
 }
@@ -32,6 +34,14 @@ struct DiscoveryJobResponse {
     completed_at: Option<String>,
     created_at: String,
     configuration: Option<serde_json::Value>,
+    /// Seconds the job ran, or has run so far; `None` before it starts
+    duration_seconds: Option<i64>,
+}
+
+// Page of jobs from the discovery API
+#[derive(Deserialize, Debug, Clone)]
+struct DiscoveryJobListResponse {
+    tasks: Vec<DiscoveryJobResponse>,
 }
 
 // For active jobs table
@@ -48,7 +58,7 @@ struct DiscoveryJob {
 #[component]
 pub fn DiscoveryPage() -> impl IntoView {
     // Create API client
-    let mut api_client_value = ApiClient::new("http://localhost:3000".to_string());
+    let mut api_client_value = ApiClient::new(api_base());
 
     // Set token if available
     if let Some(token) = get_auth_token() {
@@ -84,12 +94,13 @@ pub fn DiscoveryPage() -> impl IntoView {
         spawn_local(async move {
             // Fetch active jobs
             match client
-                .get::<Vec<DiscoveryJobResponse>>("/api/discovery/jobs?status=RUNNING,PENDING")
+                .get::<DiscoveryJobListResponse>("/api/discovery/jobs?status=RUNNING,PENDING")
                 .await
             {
                 Ok(response) => {
                     // Convert to active jobs
                     let active = response
+                        .tasks
                         .into_iter()
                         .map(|j| {
                             // Calculate mock progress
@@ -121,11 +132,11 @@ pub fn DiscoveryPage() -> impl IntoView {
 
             // Fetch completed jobs
             match client
-                .get::<Vec<DiscoveryJobResponse>>("/api/discovery/jobs?status=COMPLETED,FAILED")
+                .get::<DiscoveryJobListResponse>("/api/discovery/jobs?status=COMPLETED,FAILED")
                 .await
             {
                 Ok(response) => {
-                    set_completed_jobs.set(response);
+                    set_completed_jobs.set(response.tasks);
                 }
                 Err(e) => {
                     log::error!("Failed to fetch completed jobs: {:?}", e);
@@ -273,7 +284,10 @@ pub fn DiscoveryPage() -> impl IntoView {
 
         spawn_local(async move {
             match client
-                .post::<(), _>(&format!("/discovery/jobs/{}/cancel", job_id), &())
+                .post::<serde_json::Value, _>(
+                    &format!("/api/discovery-tasks/{}/cancel", job_id),
+                    &(),
+                )
                 .await
             {
                 Ok(_) => {
@@ -431,9 +445,9 @@ pub fn DiscoveryPage() -> impl IntoView {
                                     set_scan_depth.set(input.value().parse::<u8>().unwrap_or(2));
                                 }
                             >
-                                <option value="1">"Light (1 level)"</option>
-                                <option value="2" selected=scan_depth.get() == 2>"Normal (2 levels)"</option>
-                                <option value="3">"Deep (3 levels)"</option>
+                                <option value="1">"Light (links 1 page deep)"</option>
+                                <option value="2" selected=scan_depth.get() == 2>"Normal (links 2 pages deep)"</option>
+                                <option value="3">"Deep (links 3 pages deep)"</option>
                             </select>
                         </div>
 
@@ -537,8 +551,10 @@ pub fn DiscoveryPage() -> impl IntoView {
                                 }.into_any()
                             } else {
                                 jobs.into_iter().take(5).map(|job| {
-                                    // Calculate duration (mock for now)
-                                    let duration = "45m 12s";
+                                    let duration = job
+                                        .duration_seconds
+                                        .map(format_duration)
+                                        .unwrap_or_else(|| "-".to_string());
 
                                     // Format findings (mock for now)
                                     let findings = "12 assets, 4 vulnerabilities";
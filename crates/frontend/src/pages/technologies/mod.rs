@@ -1,7 +1,189 @@
+use crate::api::events::on_resource_change;
+use crate::api::technologies::{TechnologyDistribution, TechnologyFilter, TechnologySummary};
+use crate::api::{ApiClient, ApiError};
+use crate::components::ui::chart::{Chart, ChartData, ChartDataset, ChartType};
+use crate::components::ui::pagination::{PageState, Pagination};
+use crate::utils::{api_base, format_date, get_auth_token};
 use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+use shared::types::ResourceKind;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+
+/// Error message shown for a failed technologies request
+fn error_message(error: ApiError, fallback: &str) -> String {
+    match error {
+        ApiError::AuthError(_) => "Authentication error - please log in again".to_string(),
+        ApiError::NetworkError(msg) => format!("Network error: {}", msg),
+        ApiError::ServerError(msg) => format!("Server error: {}", msg),
+        _ => fallback.to_string(),
+    }
+}
+
+fn distribution_chart_data(distribution: &TechnologyDistribution) -> ChartData {
+    ChartData {
+        labels: distribution
+            .by_category
+            .iter()
+            .map(|category| category.name.clone())
+            .collect(),
+        datasets: vec![ChartDataset {
+            label: "Technologies".to_string(),
+            data: distribution
+                .by_category
+                .iter()
+                .map(|category| category.count as f64)
+                .collect(),
+            background_colors: None,
+            border_colors: None,
+        }],
+    }
+}
 
 #[component]
 pub fn TechnologiesPage() -> impl IntoView {
+    // Create API client
+    let api_client = RwSignal::new(ApiClient::new(api_base()));
+
+    // Set token if available
+    if let Some(token) = get_auth_token() {
+        api_client.update(|client| client.set_token(token));
+    }
+
+    // Signals for the list and UI state
+    let (technologies, set_technologies) = signal(Vec::<TechnologySummary>::new());
+    let (distribution, set_distribution) = signal::<Option<TechnologyDistribution>>(None);
+    let (loading, set_loading) = signal(false);
+    let (error, set_error) = signal::<Option<String>>(None);
+    let page_state = RwSignal::new(PageState::default());
+
+    // Filter form signals, applied when the list is fetched
+    let (search_query, set_search_query) = signal(String::new());
+    let (filter_category, set_filter_category) = signal::<Option<String>>(None);
+
+    // Function to fetch technologies from the API
+    let fetch_technologies = move || {
+        set_loading.set(true);
+        set_error.set(None);
+
+        let client = api_client.get_untracked();
+        let filter = TechnologyFilter {
+            search: search_query.get_untracked(),
+            category: filter_category.get_untracked(),
+        };
+        let page = page_state.get_untracked();
+        spawn_local(async move {
+            match client.list_technologies(&filter, &page).await {
+                Ok((response, total)) => {
+                    // Prefer the header total, falling back to the body
+                    let total = total.unwrap_or(response.total);
+                    page_state.update(|state| state.set_total(total));
+                    set_technologies.set(response.technologies);
+                }
+                Err(e) => set_error.set(Some(error_message(e, "Failed to fetch technologies"))),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    // Function to fetch the counts behind the chart and category filter
+    let fetch_distribution = move || {
+        let client = api_client.get_untracked();
+        spawn_local(async move {
+            match client.get_technology_distribution().await {
+                Ok(response) => set_distribution.set(Some(response)),
+                Err(e) => set_error.set(Some(error_message(
+                    e,
+                    "Failed to fetch technology distribution",
+                ))),
+            }
+        });
+    };
+
+    // Start again from the first page when the filters change
+    let apply_filters = move || {
+        page_state.update(|state| state.page = 0);
+        fetch_technologies();
+    };
+
+    // Fetch when the component mounts
+    Effect::new(move |_| {
+        fetch_technologies();
+        fetch_distribution();
+    });
+
+    // Scans record technologies as they update assets
+    on_resource_change(ResourceKind::Asset, move || {
+        fetch_technologies();
+        fetch_distribution();
+    });
+
+    // Loading indicator
+    let loading_view = move || {
+        loading.get().then(|| {
+            view! {
+                <div class="loading-overlay">
+                    <div class="spinner"></div>
+                </div>
+            }
+        })
+    };
+
+    // Categories offered by the filter, as detected across the organization
+    let category_options = move || {
+        distribution
+            .get()
+            .unwrap_or_default()
+            .by_category
+            .into_iter()
+            .map(|category| {
+                let value = category.name.clone();
+                view! { <option value=value>{category.name}</option> }
+            })
+            .collect_view()
+    };
+
+    let technology_rows = move || {
+        let technologies = technologies.get();
+        if technologies.is_empty() {
+            return view! {
+                <tr>
+                    <td colspan="6" class="empty-state">
+                        "No technologies found matching your criteria"
+                    </td>
+                </tr>
+            }
+            .into_any();
+        }
+
+        technologies
+            .into_iter()
+            .map(|technology| {
+                let detail_path = format!("/app/assets/{}", technology.asset_id);
+                let navigate = use_navigate();
+
+                view! {
+                    <tr>
+                        <td>{technology.name}</td>
+                        <td>{technology.version.unwrap_or_else(|| "-".to_string())}</td>
+                        <td>{technology.category.unwrap_or_else(|| "-".to_string())}</td>
+                        <td>{technology.asset_value}</td>
+                        <td>{format_date(&technology.created_at)}</td>
+                        <td>
+                            <button
+                                class="btn btn-small btn-secondary"
+                                on:click=move |_| navigate(&detail_path, Default::default())
+                            >
+                                "View Details"
+                            </button>
+                        </td>
+                    </tr>
+                }
+            })
+            .collect_view()
+            .into_any()
+    };
+
     view! {
         <div>
             <div class="page-header">
@@ -11,18 +193,37 @@ pub fn TechnologiesPage() -> impl IntoView {
                 </button>
             </div>
 
+            {move || error.get().map(|err| view! {
+                <div class="alert alert-danger">{err}</div>
+            })}
+
+            {loading_view}
+
             <div class="card">
                 <div class="filter-bar">
-                    <input type="text" class="form-input" placeholder="Search technologies..." />
-                    <select class="form-select">
-                        <option>"All Categories"</option>
-                        <option>"Web Servers"</option>
-                        <option>"Frameworks"</option>
-                        <option>"CMS"</option>
-                        <option>"JavaScript Libraries"</option>
-                        <option>"Analytics"</option>
+                    <input
+                        type="text"
+                        class="form-input"
+                        placeholder="Search technologies..."
+                        on:input=move |ev| {
+                            let input = event_target::<HtmlInputElement>(&ev);
+                            set_search_query.set(input.value());
+                        }
+                    />
+                    <select
+                        class="form-select"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            set_filter_category.set(if value == "all" { None } else { Some(value) });
+                            apply_filters();
+                        }
+                    >
+                        <option value="all">"All Categories"</option>
+                        {category_options}
                     </select>
-                    <button class="btn btn-secondary">"Filter"</button>
+                    <button class="btn btn-secondary" on:click=move |_| apply_filters()>
+                        "Filter"
+                    </button>
                 </div>
 
                 <table class="table">
@@ -37,71 +238,36 @@ pub fn TechnologiesPage() -> impl IntoView {
                         </tr>
                     </thead>
                     <tbody>
-                        <tr>
-                            <td>"Apache"</td>
-                            <td>"2.4.51"</td>
-                            <td>"Web Server"</td>
-                            <td>"example.com"</td>
-                            <td>"2023-03-10"</td>
-                            <td>
-                                <button class="btn btn-small btn-secondary">"View Details"</button>
-                            </td>
-                        </tr>
-                        <tr>
-                            <td>"WordPress"</td>
-                            <td>"5.9.3"</td>
-                            <td>"CMS"</td>
-                            <td>"example.com"</td>
-                            <td>"2023-03-10"</td>
-                            <td>
-                                <button class="btn btn-small btn-secondary">"View Details"</button>
-                            </td>
-                        </tr>
-                        <tr>
-                            <td>"jQuery"</td>
-                            <td>"3.6.0"</td>
-                            <td>"JavaScript Library"</td>
-                            <td>"example.com"</td>
-                            <td>"2023-03-10"</td>
-                            <td>
-                                <button class="btn btn-small btn-secondary">"View Details"</button>
-                            </td>
-                        </tr>
-                        <tr>
-                            <td>"Bootstrap"</td>
-                            <td>"5.1.3"</td>
-                            <td>"CSS Framework"</td>
-                            <td>"example.com"</td>
-                            <td>"2023-03-10"</td>
-                            <td>
-                                <button class="btn btn-small btn-secondary">"View Details"</button>
-                            </td>
-                        </tr>
-                        <tr>
-                            <td>"Google Analytics"</td>
-                            <td>"UA-123456-7"</td>
-                            <td>"Analytics"</td>
-                            <td>"example.com"</td>
-                            <td>"2023-03-10"</td>
-                            <td>
-                                <button class="btn btn-small btn-secondary">"View Details"</button>
-                            </td>
-                        </tr>
+                        {technology_rows}
                     </tbody>
                 </table>
 
-                <div class="pagination">
-                    <button class="btn btn-secondary">"Previous"</button>
-                    <span class="pagination-info">"Showing 1-5 of 23"</span>
-                    <button class="btn btn-secondary">"Next"</button>
-                </div>
+                <Pagination state=page_state on_change=move |_| fetch_technologies() />
             </div>
 
             <div class="card">
                 <h2>"Technology Distribution"</h2>
                 <div class="chart-container">
                     <div id="technology-chart">
-                        <p class="chart-placeholder">"Chart will render here..."</p>
+                        {move || match distribution.get() {
+                            Some(distribution) if !distribution.by_category.is_empty() => view! {
+                                <Chart
+                                    title="Technologies by Category"
+                                    chart_type=ChartType::Bar
+                                    data=distribution_chart_data(&distribution)
+                                    height=250
+                                />
+                            }
+                            .into_any(),
+                            Some(_) => view! {
+                                <p class="chart-placeholder">"No technologies detected yet"</p>
+                            }
+                            .into_any(),
+                            None => view! {
+                                <p class="chart-placeholder">"Loading..."</p>
+                            }
+                            .into_any(),
+                        }}
                     </div>
                 </div>
             </div>
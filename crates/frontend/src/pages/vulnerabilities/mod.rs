@@ -1,86 +1,257 @@
+use crate::api::dashboard::DashboardStats;
+use crate::api::events::on_resource_change;
+use crate::api::vulnerabilities::{status_label, VulnerabilityFilter, VulnerabilitySummary};
+use crate::api::{ApiClient, ApiError};
+use crate::components::ui::pagination::{PageState, Pagination};
 use crate::components::ui::vulnerability_card::{Vulnerability, VulnerabilityCard};
+use crate::utils::{api_base, format_date, format_severity, get_auth_token, truncate};
+use futures::future::join_all;
 use leptos::prelude::*;
+use shared::types::ResourceKind;
+use std::collections::HashMap;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+
+/// Error message shown for a failed vulnerabilities request
+fn error_message(error: ApiError, fallback: &str) -> String {
+    match error {
+        ApiError::AuthError(_) => "Authentication error - please log in again".to_string(),
+        ApiError::NetworkError(msg) => format!("Network error: {}", msg),
+        ApiError::ServerError(msg) => format!("Server error: {}", msg),
+        ApiError::BadRequest(msg) => format!("Invalid request: {}", msg),
+        _ => fallback.to_string(),
+    }
+}
+
+/// `None` for the "all" option of a filter select
+fn filter_value(value: String) -> Option<String> {
+    (value != "all").then_some(value)
+}
 
 #[component]
 pub fn VulnerabilitiesPage() -> impl IntoView {
-    // Mock data - in a real app, this would be fetched from API
-    let vulnerabilities = vec![
-        Vulnerability {
-            id: "1".to_string(),
-            title: "Outdated WordPress Version".to_string(),
-            description: "The WordPress installation is using an outdated version (5.8.1) with known security vulnerabilities.".to_string(),
-            severity: "High".to_string(),
-            status: "Open".to_string(),
-            asset_name: "example.com".to_string(),
-            discovery_date: "2023-03-15".to_string(),
-        },
-        Vulnerability {
-            id: "2".to_string(),
-            title: "SSL Certificate Expiring Soon".to_string(),
-            description: "The SSL certificate for this domain will expire in 14 days, which may lead to browser warnings.".to_string(),
-            severity: "Medium".to_string(),
-            status: "In Progress".to_string(),
-            asset_name: "api.example.com".to_string(),
-            discovery_date: "2023-03-16".to_string(),
-        },
-        Vulnerability {
-            id: "3".to_string(),
-            title: "Open SSH Port".to_string(),
-            description: "SSH port 22 is open and accessible from the internet, consider restricting access.".to_string(),
-            severity: "Low".to_string(),
-            status: "Open".to_string(),
-            asset_name: "192.168.1.1".to_string(),
-            discovery_date: "2023-03-14".to_string(),
-        },
-        Vulnerability {
-            id: "4".to_string(),
-            title: "Cross-Site Scripting (XSS)".to_string(),
-            description: "A cross-site scripting vulnerability was detected in the search functionality.".to_string(),
-            severity: "Critical".to_string(),
-            status: "Open".to_string(),
-            asset_name: "example.org".to_string(),
-            discovery_date: "2023-03-12".to_string(),
-        },
-        Vulnerability {
-            id: "5".to_string(),
-            title: "Insecure HTTP Access".to_string(),
-            description: "The site allows non-HTTPS access, which can lead to data interception.".to_string(),
-            severity: "Medium".to_string(),
-            status: "Fixed".to_string(),
-            asset_name: "blog.example.com".to_string(),
-            discovery_date: "2023-03-10".to_string(),
-        },
-    ];
-
-    let (vulnerabilities_signal, _set_vulnerabilities) = signal(vulnerabilities);
-
-    // We don't use this directly - marking as unused with _
-    let _handle_vuln_click = move |id: String| {
-        log::info!("Vulnerability selected: {}", id);
-        // In a real app, this would navigate to vulnerability details or open a modal
-    };
+    // Create API client
+    let api_client = RwSignal::new(ApiClient::new(api_base()));
 
-    let total_count = move || vulnerabilities_signal.get().len();
-    let open_count = move || {
-        vulnerabilities_signal
-            .get()
+    // Set token if available
+    if let Some(token) = get_auth_token() {
+        api_client.update(|client| client.set_token(token));
+    }
+
+    // Signals for the list and UI state
+    let (vulnerabilities, set_vulnerabilities) = signal(Vec::<VulnerabilitySummary>::new());
+    let (stats, set_stats) = signal::<Option<DashboardStats>>(None);
+    let (loading, set_loading) = signal(false);
+    let (error, set_error) = signal::<Option<String>>(None);
+    let page_state = RwSignal::new(PageState::default());
+
+    // Asset values by ID, looked up for the vulnerabilities on screen
+    let asset_names = RwSignal::new(HashMap::<String, String>::new());
+
+    // Search narrows the current page, the selects are sent to the API
+    let (search_query, set_search_query) = signal(String::new());
+    let (filter_severity, set_filter_severity) = signal::<Option<String>>(None);
+    let (filter_status, set_filter_status) = signal::<Option<String>>(None);
+
+    // Look up the assets of the listed vulnerabilities not already known
+    let fetch_asset_names = move |client: ApiClient, vulnerabilities: &[VulnerabilitySummary]| {
+        let known = asset_names.get_untracked();
+        let mut missing: Vec<String> = vulnerabilities
             .iter()
-            .filter(|v| v.status == "Open")
-            .count()
+            .map(|v| v.asset_id.clone())
+            .filter(|id| !known.contains_key(id))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return;
+        }
+
+        spawn_local(async move {
+            let assets = join_all(missing.iter().map(|id| client.get_asset(id))).await;
+            asset_names.update(|names| {
+                for asset in assets.into_iter().flatten() {
+                    names.insert(asset.id, asset.value);
+                }
+            });
+        });
     };
-    let critical_count = move || {
-        vulnerabilities_signal
-            .get()
-            .iter()
-            .filter(|v| v.severity == "Critical")
-            .count()
+
+    // Function to fetch vulnerabilities from the API
+    let fetch_vulnerabilities = move || {
+        set_loading.set(true);
+        set_error.set(None);
+
+        let client = api_client.get_untracked();
+        let filter = VulnerabilityFilter {
+            severity: filter_severity.get_untracked(),
+            status: filter_status.get_untracked(),
+        };
+        let page = page_state.get_untracked();
+        spawn_local(async move {
+            match client.list_vulnerabilities(&filter, &page).await {
+                Ok((response, total)) => {
+                    // Prefer the header total, falling back to the body
+                    let total = total.unwrap_or(response.total);
+                    page_state.update(|state| state.set_total(total));
+                    fetch_asset_names(client, &response.vulnerabilities);
+                    set_vulnerabilities.set(response.vulnerabilities);
+                }
+                Err(e) => set_error.set(Some(error_message(e, "Failed to fetch vulnerabilities"))),
+            }
+            set_loading.set(false);
+        });
     };
-    let high_count = move || {
-        vulnerabilities_signal
+
+    // Function to fetch the organization-wide counts in the overview
+    let fetch_stats = move || {
+        let client = api_client.get_untracked();
+        spawn_local(async move {
+            match client.get_dashboard_stats().await {
+                Ok(response) => set_stats.set(Some(response)),
+                Err(e) => set_error.set(Some(error_message(
+                    e,
+                    "Failed to fetch vulnerability statistics",
+                ))),
+            }
+        });
+    };
+
+    // Function to move a vulnerability to another status, e.g. to resolve it
+    let update_status = move |id: String, status: &'static str| {
+        set_loading.set(true);
+        set_error.set(None);
+
+        let client = api_client.get_untracked();
+        spawn_local(async move {
+            match client.update_vulnerability_status(&id, status).await {
+                Ok(_) => {
+                    fetch_vulnerabilities();
+                    fetch_stats();
+                }
+                Err(e) => {
+                    set_error.set(Some(error_message(e, "Failed to update vulnerability")));
+                    set_loading.set(false);
+                }
+            }
+        });
+    };
+
+    // Start again from the first page when the filters change
+    let apply_filters = move || {
+        page_state.update(|state| state.page = 0);
+        fetch_vulnerabilities();
+    };
+
+    // Fetch when the component mounts
+    Effect::new(move |_| {
+        fetch_vulnerabilities();
+        fetch_stats();
+    });
+
+    // Refresh when a scan or another user adds or changes a vulnerability
+    on_resource_change(ResourceKind::Vulnerability, move || {
+        fetch_vulnerabilities();
+        fetch_stats();
+    });
+
+    // Counts render as a dash until they have loaded
+    let total_count = move || page_state.get().total.to_string();
+    let stat = move |count: fn(&DashboardStats) -> usize| {
+        move || {
+            stats
+                .get()
+                .map(|stats| count(&stats).to_string())
+                .unwrap_or_else(|| "-".to_string())
+        }
+    };
+
+    // Loading indicator
+    let loading_view = move || {
+        loading.get().then(|| {
+            view! {
+                <div class="loading-overlay">
+                    <div class="spinner"></div>
+                </div>
+            }
+        })
+    };
+
+    let vulnerability_cards = move || {
+        let query = search_query.get().to_lowercase();
+        let names = asset_names.get();
+        let vulnerabilities: Vec<_> = vulnerabilities
             .get()
-            .iter()
-            .filter(|v| v.severity == "High")
-            .count()
+            .into_iter()
+            .filter(|v| {
+                v.title.to_lowercase().contains(&query)
+                    || v.cve_id
+                        .as_ref()
+                        .is_some_and(|cve| cve.to_lowercase().contains(&query))
+            })
+            .collect();
+
+        if vulnerabilities.is_empty() {
+            return view! {
+                <p class="empty-state">"No vulnerabilities found matching your criteria"</p>
+            }
+            .into_any();
+        }
+
+        vulnerabilities
+            .into_iter()
+            .map(|vuln| {
+                let asset_name = names
+                    .get(&vuln.asset_id)
+                    .cloned()
+                    .unwrap_or_else(|| truncate(&vuln.asset_id, 8));
+                let card = Vulnerability {
+                    id: vuln.id.clone(),
+                    title: vuln.title.clone(),
+                    description: vuln.description.clone().unwrap_or_default(),
+                    severity: format_severity(&vuln.severity),
+                    status: status_label(&vuln.status).to_string(),
+                    asset_name,
+                    discovery_date: format_date(&vuln.first_seen),
+                };
+                let vuln_id = vuln.id.clone();
+                let on_click_callback = Callback::new(move |_| {
+                    log::info!("Vulnerability selected: {}", vuln_id);
+                });
+
+                let start_id = vuln.id.clone();
+                let resolve_id = vuln.id.clone();
+                let can_start = vuln.status == "OPEN";
+                let can_resolve = vuln.status == "OPEN" || vuln.status == "INPROGRESS";
+
+                view! {
+                    <div class="vulnerability-item">
+                        <VulnerabilityCard vulnerability=card on_click=on_click_callback />
+                        <div class="vulnerability-actions">
+                            {can_start.then(|| view! {
+                                <button
+                                    class="btn btn-small btn-secondary"
+                                    disabled=loading
+                                    on:click=move |_| update_status(start_id.clone(), "INPROGRESS")
+                                >
+                                    "Mark In Progress"
+                                </button>
+                            })}
+                            {can_resolve.then(|| view! {
+                                <button
+                                    class="btn btn-small btn-primary"
+                                    disabled=loading
+                                    on:click=move |_| update_status(resolve_id.clone(), "CLOSED")
+                                >
+                                    "Resolve"
+                                </button>
+                            })}
+                        </div>
+                    </div>
+                }
+            })
+            .collect_view()
+            .into_any()
     };
 
     view! {
@@ -96,7 +267,7 @@ pub fn VulnerabilitiesPage() -> impl IntoView {
                 <div class="card">
                     <h2>"Overview"</h2>
                     <div class="stat">
-                        <span class="stat-value">{move || total_count().to_string()}</span>
+                        <span class="stat-value">{total_count}</span>
                         <span class="stat-label">"Total Vulnerabilities"</span>
                     </div>
                 </div>
@@ -104,7 +275,7 @@ pub fn VulnerabilitiesPage() -> impl IntoView {
                 <div class="card">
                     <h2>"Open Issues"</h2>
                     <div class="stat">
-                        <span class="stat-value">{move || open_count().to_string()}</span>
+                        <span class="stat-value">{stat(|stats| stats.open_vulnerabilities)}</span>
                         <span class="stat-label">"Open Vulnerabilities"</span>
                     </div>
                 </div>
@@ -112,44 +283,74 @@ pub fn VulnerabilitiesPage() -> impl IntoView {
                 <div class="card">
                     <h2>"Critical & High"</h2>
                     <div class="stat">
-                        <span class="stat-value">{move || (critical_count() + high_count()).to_string()}</span>
+                        <span class="stat-value">
+                            {stat(|stats| {
+                                let counts = &stats.open_vulnerabilities_by_severity;
+                                counts.critical + counts.high
+                            })}
+                        </span>
                         <span class="stat-label">"High Priority Issues"</span>
                     </div>
                 </div>
             </div>
 
             <div class="filter-bar">
-                <input type="text" placeholder="Search vulnerabilities..." class="search-input" />
-                <select class="filter-select">
-                    <option>"All Severities"</option>
-                    <option>"Critical"</option>
-                    <option>"High"</option>
-                    <option>"Medium"</option>
-                    <option>"Low"</option>
+                <input
+                    type="text"
+                    placeholder="Search vulnerabilities..."
+                    class="search-input"
+                    on:input=move |ev| {
+                        let input = event_target::<HtmlInputElement>(&ev);
+                        set_search_query.set(input.value());
+                    }
+                />
+                <select
+                    class="filter-select"
+                    on:change=move |ev| {
+                        set_filter_severity.set(filter_value(event_target_value(&ev)));
+                        apply_filters();
+                    }
+                >
+                    <option value="all">"All Severities"</option>
+                    <option value="CRITICAL">"Critical"</option>
+                    <option value="HIGH">"High"</option>
+                    <option value="MEDIUM">"Medium"</option>
+                    <option value="LOW">"Low"</option>
+                    <option value="INFO">"Info"</option>
                 </select>
-                <select class="filter-select">
-                    <option>"All Statuses"</option>
-                    <option>"Open"</option>
-                    <option>"In Progress"</option>
-                    <option>"Fixed"</option>
+                <select
+                    class="filter-select"
+                    on:change=move |ev| {
+                        set_filter_status.set(filter_value(event_target_value(&ev)));
+                        apply_filters();
+                    }
+                >
+                    <option value="all">"All Statuses"</option>
+                    <option value="OPEN">"Open"</option>
+                    <option value="INPROGRESS">"In Progress"</option>
+                    <option value="CLOSED">"Resolved"</option>
+                    <option value="ACCEPTEDRISK">"Accepted Risk"</option>
+                    <option value="FALSEPOSITIVE">"False Positive"</option>
                 </select>
+                <button
+                    class="btn btn-outline-primary"
+                    on:click=move |_| fetch_vulnerabilities()
+                >
+                    "Refresh"
+                </button>
             </div>
 
+            {move || error.get().map(|err| view! {
+                <div class="alert alert-danger">{err}</div>
+            })}
+
+            {loading_view}
+
             <div class="vulnerability-grid">
-                {move || vulnerabilities_signal.get().iter().map(|vuln| {
-                    let vuln_id = vuln.id.clone();
-                    let on_click_callback = Callback::new(move |_| {
-                        log::info!("Vulnerability selected: {}", vuln_id);
-                    });
-
-                    view! {
-                        <VulnerabilityCard
-                            vulnerability={vuln.clone()}
-                            on_click={on_click_callback}
-                        />
-                    }
-                }).collect::<Vec<_>>()}
+                {vulnerability_cards}
             </div>
+
+            <Pagination state=page_state on_change=move |_| fetch_vulnerabilities() />
         </div>
     }
 }
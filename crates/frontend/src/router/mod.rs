@@ -9,8 +9,13 @@ use leptos_router::{
 
 use crate::components::layout::{AppLayout, AuthLayout};
 use crate::pages::{
-    assets::AssetsPage, auth::LoginPage, dashboard::DashboardPage, discovery::DiscoveryPage,
-    not_found::NotFoundPage, technologies::TechnologiesPage, vulnerabilities::VulnerabilitiesPage,
+    assets::{detail::AssetDetailPage, AssetsPage},
+    auth::LoginPage,
+    dashboard::DashboardPage,
+    discovery::DiscoveryPage,
+    not_found::NotFoundPage,
+    technologies::TechnologiesPage,
+    vulnerabilities::VulnerabilitiesPage,
 };
 use crate::utils::get_auth_token;
 
@@ -37,6 +42,7 @@ pub fn AppRouter() -> impl IntoView {
                 // Protected routes
                 <Route path=path!("/dashboard") view=move || view! { <RequireAuth><DashboardPage/></RequireAuth> }/>
                 <Route path=path!("/app/assets") view=move || view! { <RequireAuth><AppLayout><AssetsPage/></AppLayout></RequireAuth> }/>
+                <Route path=path!("/app/assets/:id") view=move || view! { <RequireAuth><AppLayout><AssetDetailPage/></AppLayout></RequireAuth> }/>
                 <Route path=path!("/app/technologies") view=move || view! { <RequireAuth><AppLayout><TechnologiesPage/></AppLayout></RequireAuth> }/>
                 <Route path=path!("/app/vulnerabilities") view=move || view! { <RequireAuth><AppLayout><VulnerabilitiesPage/></AppLayout></RequireAuth> }/>
                 <Route path=path!("/app/discovery") view=move || view! { <RequireAuth><AppLayout><DiscoveryPage/></AppLayout></RequireAuth> }/>
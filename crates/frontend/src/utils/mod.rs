@@ -1,8 +1,45 @@
 use gloo::storage::{LocalStorage, Storage};
+use wasm_bindgen::JsValue;
 
 /// Storage key for auth token
 pub const AUTH_TOKEN_KEY: &str = "easm_auth_token";
 
+/// API origin used when no override is configured
+pub const DEFAULT_API_BASE: &str = "http://localhost:3000";
+
+/// Name of both the build-time env var and the `window` property that
+/// override the API origin
+pub const API_BASE_KEY: &str = "EASM_API_BASE";
+
+/// Base URL of the backend API.
+///
+/// A `window.EASM_API_BASE` set by the hosting page takes precedence, then
+/// the `EASM_API_BASE` env var at build time, then [`DEFAULT_API_BASE`].
+pub fn api_base() -> String {
+    resolve_api_base(runtime_api_base(), option_env!("EASM_API_BASE"))
+}
+
+/// Read the API origin injected into `window` at runtime, if any
+fn runtime_api_base() -> Option<String> {
+    let window = web_sys::window()?;
+    js_sys::Reflect::get(&window, &JsValue::from_str(API_BASE_KEY))
+        .ok()?
+        .as_string()
+}
+
+/// Pick the first non-blank candidate, trimming any trailing slash so
+/// endpoints like "/api/assets" can be appended directly
+fn resolve_api_base(runtime: Option<String>, compile_time: Option<&str>) -> String {
+    runtime
+        .as_deref()
+        .into_iter()
+        .chain(compile_time)
+        .map(|base| base.trim().trim_end_matches('/'))
+        .find(|base| !base.is_empty())
+        .unwrap_or(DEFAULT_API_BASE)
+        .to_string()
+}
+
 /// Get the auth token from local storage
 pub fn get_auth_token() -> Option<String> {
     LocalStorage::get(AUTH_TOKEN_KEY).ok()
@@ -26,6 +63,19 @@ pub fn format_date(date_string: &str) -> String {
     date_string.to_string()
 }
 
+/// Format a duration in seconds for display, e.g. `1h 5m` or `45m 12s`
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
 /// Format severity for display
 pub fn format_severity(severity: &str) -> String {
     match severity.to_lowercase().as_str() {
@@ -50,11 +100,83 @@ pub fn severity_class(severity: &str) -> String {
     }
 }
 
-/// Truncate a string if it's too long
+/// Truncate a string to at most `max_chars` characters, appending an ellipsis
+/// only when something was cut off. Counts `char`s rather than bytes so
+/// multi-byte input never splits a code point.
 pub fn truncate(s: &str, max_chars: usize) -> String {
-    if s.len() > max_chars {
-        format!("{}...", &s[0..max_chars])
-    } else {
-        s.to_string()
+    match s.char_indices().nth(max_chars) {
+        Some((byte_idx, _)) => format!("{}...", &s[..byte_idx]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_base_defaults_when_unset() {
+        assert_eq!(resolve_api_base(None, None), DEFAULT_API_BASE);
+    }
+
+    #[test]
+    fn test_api_base_prefers_runtime_over_compile_time() {
+        assert_eq!(
+            resolve_api_base(
+                Some("https://runtime.example.com".to_string()),
+                Some("https://build.example.com")
+            ),
+            "https://runtime.example.com"
+        );
+        assert_eq!(
+            resolve_api_base(None, Some("https://build.example.com")),
+            "https://build.example.com"
+        );
+    }
+
+    #[test]
+    fn test_api_base_skips_blank_values_and_trims_slash() {
+        assert_eq!(
+            resolve_api_base(Some("  ".to_string()), Some("https://build.example.com/")),
+            "https://build.example.com"
+        );
+        assert_eq!(
+            resolve_api_base(Some(String::new()), Some("")),
+            DEFAULT_API_BASE
+        );
+    }
+
+    #[test]
+    fn test_truncate_short_string_is_unchanged() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("", 3), "");
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate("hello world", 5), "hello...");
+        assert_eq!(truncate("hello", 0), "...");
+    }
+
+    #[test]
+    fn test_truncate_counts_chars_not_bytes() {
+        // "café" is 4 chars but 5 bytes, so it must not be truncated at 4
+        assert_eq!(truncate("café", 4), "café");
+        assert_eq!(truncate("naïve résumé", 5), "naïve...");
+    }
+
+    #[test]
+    fn test_truncate_accented_char_at_boundary() {
+        // The boundary falls right after the multi-byte 'é'
+        assert_eq!(truncate("éééé", 2), "éé...");
+        assert_eq!(truncate("abcé", 3), "abc...");
+    }
+
+    #[test]
+    fn test_truncate_emoji_at_boundary() {
+        assert_eq!(truncate("🔒🔓🔑", 1), "🔒...");
+        assert_eq!(truncate("ab🔒cd", 3), "ab🔒...");
+        assert_eq!(truncate("ab🔒", 3), "ab🔒");
     }
 }
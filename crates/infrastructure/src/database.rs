@@ -1,8 +1,55 @@
 pub mod migrations;
 
+use shared::config::Config;
 use shared::errors::{AppError, Result};
 use sqlx::{postgres::PgPoolOptions, PgPool};
-use tracing::info;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Connection pool settings and how hard to try reaching the database at
+/// startup
+#[derive(Debug, Clone)]
+pub struct DatabaseOptions {
+    pub max_connections: u32,
+    /// How long a query waits for a pooled connection
+    pub acquire_timeout: Duration,
+    /// How long an unused connection stays open, `None` to keep it
+    pub idle_timeout: Option<Duration>,
+    /// Connection attempts before giving up, at least one
+    pub connect_attempts: u32,
+    /// Delay before the first retry, doubled after every failed attempt
+    pub initial_backoff: Duration,
+    /// Longest delay between two attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(600)),
+            connect_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl DatabaseOptions {
+    /// Pool settings from the application configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_connections: config.database_max_connections,
+            acquire_timeout: Duration::from_secs(config.database_acquire_timeout_secs),
+            idle_timeout: (config.database_idle_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.database_idle_timeout_secs)),
+            connect_attempts: config.database_connect_attempts,
+            ..Self::default()
+        }
+    }
+}
 
 /// Database connection pool singleton
 #[derive(Clone)]
@@ -13,13 +60,30 @@ pub struct Database {
 impl Database {
     /// Create a new database connection pool
     pub async fn new(database_url: &str, max_connections: u32) -> Result<Self> {
+        Self::connect(
+            database_url,
+            &DatabaseOptions {
+                max_connections,
+                ..DatabaseOptions::default()
+            },
+        )
+        .await
+    }
+
+    /// Create a new database connection pool, retrying while the database
+    /// can't be reached yet, e.g. when it's still starting next to us
+    pub async fn connect(database_url: &str, options: &DatabaseOptions) -> Result<Self> {
         info!("Connecting to database: {}", database_url);
 
-        let pool = PgPoolOptions::new()
-            .max_connections(max_connections)
-            .connect(database_url)
-            .await
-            .map_err(|e| AppError::database(format!("Failed to connect to database: {}", e)))?;
+        let pool = retry_with_backoff(options, || {
+            PgPoolOptions::new()
+                .max_connections(options.max_connections)
+                .acquire_timeout(options.acquire_timeout)
+                .idle_timeout(options.idle_timeout)
+                .connect(database_url)
+        })
+        .await
+        .map_err(|e| AppError::database(format!("Failed to connect to database: {}", e)))?;
 
         info!("Connected to database");
 
@@ -36,3 +100,50 @@ impl Database {
         Ok(())
     }
 }
+
+/// Whether `error` may go away by itself: the server can't be reached, is
+/// still starting up, or has no connection slot free
+fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(e) => matches!(
+            e.code().as_deref(),
+            // cannot_connect_now, too_many_connections
+            Some("57P03") | Some("53300")
+        ),
+        _ => false,
+    }
+}
+
+/// Run `connect` until it succeeds, fails with an error that retrying won't
+/// fix, or `options.connect_attempts` attempts have failed. The delay between
+/// attempts starts at `options.initial_backoff` and doubles each time, up to
+/// `options.max_backoff`.
+pub async fn retry_with_backoff<T, F, Fut>(
+    options: &DatabaseOptions,
+    mut connect: F,
+) -> std::result::Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, sqlx::Error>>,
+{
+    let attempts = options.connect_attempts.max(1);
+    let mut backoff = options.initial_backoff;
+    let mut attempt = 1;
+
+    loop {
+        match connect().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < attempts && is_transient(&e) => {
+                warn!(
+                    "Database unavailable (attempt {} of {}), retrying in {:?}: {}",
+                    attempt, attempts, backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(options.max_backoff);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -2,6 +2,90 @@ use shared::errors::{AppError, Result};
 use sqlx::{migrate::MigrateDatabase, Pool, Postgres};
 use tracing::info;
 
+/// Migrations in the order they must be applied: (version, description, SQL)
+const MIGRATIONS: &[(i64, &str, &str)] = &[
+    (
+        20250331180000,
+        "initial_schema",
+        include_str!("../../../../migrations/20250331180000_initial_schema.sql"),
+    ),
+    (
+        20250420000000,
+        "scan_schedules",
+        include_str!("../../../../migrations/20250420000000_scan_schedules.sql"),
+    ),
+    (
+        20250421000000,
+        "asset_history",
+        include_str!("../../../../migrations/20250421000000_asset_history.sql"),
+    ),
+    (
+        20250422000000,
+        "soft_delete",
+        include_str!("../../../../migrations/20250422000000_soft_delete.sql"),
+    ),
+    (
+        20250423000000,
+        "asset_tags",
+        include_str!("../../../../migrations/20250423000000_asset_tags.sql"),
+    ),
+    (
+        20250424000000,
+        "asset_risk_score",
+        include_str!("../../../../migrations/20250424000000_asset_risk_score.sql"),
+    ),
+    (
+        20250425000000,
+        "asset_relationships",
+        include_str!("../../../../migrations/20250425000000_asset_relationships.sql"),
+    ),
+    (
+        20250426000000,
+        "target_scans",
+        include_str!("../../../../migrations/20250426000000_target_scans.sql"),
+    ),
+    (
+        20250427000000,
+        "scan_profiles",
+        include_str!("../../../../migrations/20250427000000_scan_profiles.sql"),
+    ),
+    (
+        20250428000000,
+        "audit_log",
+        include_str!("../../../../migrations/20250428000000_audit_log.sql"),
+    ),
+    (
+        20250429000000,
+        "summary_reports",
+        include_str!("../../../../migrations/20250429000000_summary_reports.sql"),
+    ),
+    (
+        20250430000000,
+        "notification_log",
+        include_str!("../../../../migrations/20250430000000_notification_log.sql"),
+    ),
+    (
+        20250501000000,
+        "technology_upsert",
+        include_str!("../../../../migrations/20250501000000_technology_upsert.sql"),
+    ),
+    (
+        20250502000000,
+        "scan_scope",
+        include_str!("../../../../migrations/20250502000000_scan_scope.sql"),
+    ),
+    (
+        20250503000000,
+        "user_active",
+        include_str!("../../../../migrations/20250503000000_user_active.sql"),
+    ),
+    (
+        20250504000000,
+        "canonical_asset_values",
+        include_str!("../../../../migrations/20250504000000_canonical_asset_values.sql"),
+    ),
+];
+
 /// The migrator manages database migrations using SQLx
 pub struct Migrator {
     pool: Pool<Postgres>,
@@ -62,6 +146,16 @@ impl Migrator {
             .map_err(|e| AppError::database(format!("Failed to create migrations table: {}", e)))?;
         }
 
+        for (version, description, sql) in MIGRATIONS {
+            self.apply_migration(*version, description, sql).await?;
+        }
+
+        info!("Database migrations complete");
+        Ok(())
+    }
+
+    /// Apply a single migration unless it is already recorded as applied
+    async fn apply_migration(&self, version: i64, description: &str, sql: &str) -> Result<()> {
         // Check if the migration has already been applied
         let migration_applied = sqlx::query_scalar!(
             r#"
@@ -70,7 +164,7 @@ impl Migrator {
                 WHERE version = $1
             ) as "exists!"
             "#,
-            20250331180000_i64
+            version
         )
         .fetch_one(&self.pool)
         .await
@@ -78,20 +172,16 @@ impl Migrator {
 
         // If the migration is already applied, we're done
         if migration_applied {
-            info!("Migration already applied, skipping");
+            info!("Migration {} already applied, skipping", description);
             return Ok(());
         }
 
-        // Load migration SQL directly
-        let mut migration_sql =
-            include_str!("../../../../migrations/20250331180000_initial_schema.sql").to_string();
-
         // Make tables creation idempotent by adding IF NOT EXISTS
-        migration_sql = migration_sql
+        let migration_sql = sql
             .replace("CREATE TABLE ", "CREATE TABLE IF NOT EXISTS ")
             .replace("CREATE INDEX ", "CREATE INDEX IF NOT EXISTS ");
 
-        info!("Applying initial schema migration");
+        info!("Applying {} migration", description);
 
         // Begin a transaction
         let mut tx = self.pool.begin().await?;
@@ -124,8 +214,8 @@ impl Migrator {
             ON CONFLICT (version) DO NOTHING
             "#,
         )
-        .bind(20250331180000_i64)
-        .bind("initial_schema")
+        .bind(version)
+        .bind(description)
         .bind(true)
         .bind(&[0u8; 32][..]) // Simple checksum placeholder
         .bind(0_i64) // Simple execution time placeholder
@@ -136,7 +226,6 @@ impl Migrator {
         // Commit the transaction
         tx.commit().await?;
 
-        info!("Database migrations complete");
         Ok(())
     }
 
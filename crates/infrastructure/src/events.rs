@@ -0,0 +1,42 @@
+//! Live resource events, carried over Postgres `LISTEN`/`NOTIFY` so that
+//! every API instance hears about writes made by any process, including the
+//! discovery worker
+
+use shared::types::{ResourceEvent, ResourceEventType, ResourceKind, ID};
+use sqlx::PgPool;
+
+/// Postgres channel resource events are published on
+pub const RESOURCE_EVENTS_CHANNEL: &str = "resource_events";
+
+/// Publish an event to everyone listening on [`RESOURCE_EVENTS_CHANNEL`].
+///
+/// A failure is logged rather than returned, since the write being reported
+/// has already happened and shouldn't be undone by a missed refresh.
+pub async fn publish_resource_event(
+    pool: &PgPool,
+    event_type: ResourceEventType,
+    org_id: ID,
+    resource: ResourceKind,
+) {
+    let event = ResourceEvent {
+        event_type,
+        org_id,
+        resource,
+    };
+    let payload = match serde_json::to_string(&event) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("Failed to encode resource event {:?}: {}", event, e);
+            return;
+        }
+    };
+
+    if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(RESOURCE_EVENTS_CHANNEL)
+        .bind(payload)
+        .execute(pool)
+        .await
+    {
+        tracing::warn!("Failed to publish resource event {:?}: {}", event, e);
+    }
+}
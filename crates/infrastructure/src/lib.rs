@@ -1,6 +1,7 @@
 // Re-exports
 pub mod database;
 pub mod errors;
+pub mod events;
 pub mod repositories;
 pub mod utils;
 
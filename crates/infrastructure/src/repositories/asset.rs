@@ -1,8 +1,15 @@
-use crate::utils::{from_offset_datetime, to_offset_datetime};
+use crate::{
+    events::publish_resource_event,
+    utils::{from_offset_datetime, to_offset_datetime},
+};
 use async_trait::async_trait;
-use backend::{models::Asset, traits::AssetRepository, Result};
-use shared::types::{AssetStatus, AssetType, ID};
-use sqlx::PgPool;
+use backend::{
+    models::{Asset, AssetFilter, AssetRelationship, RelationshipDirection},
+    traits::AssetRepository,
+    Result,
+};
+use shared::types::{AssetStatus, AssetType, Page, ResourceEventType, ResourceKind, ID};
+use sqlx::{postgres::PgRow, PgPool, Row};
 
 /// PostgreSQL implementation of the Asset Repository
 pub struct PgAssetRepository {
@@ -16,6 +23,55 @@ impl PgAssetRepository {
     }
 }
 
+/// Columns selected for `asset_from_row`
+const ASSET_COLUMNS: &str = "id, organization_id, asset_type, value, status, first_seen, last_seen, created_at, updated_at, attributes, risk_score";
+
+/// Append `filter`'s criteria to a query ending in a `WHERE` clause
+fn push_asset_filter(
+    query_builder: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>,
+    filter: &AssetFilter,
+) {
+    if let Some(org_id) = filter.organization_id {
+        query_builder.push(" AND organization_id = ");
+        query_builder.push_bind(org_id);
+    }
+    if let Some(asset_type) = filter.asset_type {
+        query_builder.push(" AND asset_type = ");
+        query_builder.push_bind(asset_type as AssetType);
+    }
+    if let Some(status) = filter.status {
+        query_builder.push(" AND status = ");
+        query_builder.push_bind(status as AssetStatus);
+    }
+    if let Some(tag) = &filter.tag {
+        query_builder.push(
+            " AND EXISTS (SELECT 1 FROM asset_tags WHERE asset_tags.asset_id = assets.id AND asset_tags.tag = ",
+        );
+        query_builder.push_bind(tag.clone());
+        query_builder.push(")");
+    }
+    if !filter.include_deleted {
+        query_builder.push(" AND deleted_at IS NULL");
+    }
+}
+
+/// An asset from a row selected with `ASSET_COLUMNS`
+fn asset_from_row(row: &PgRow) -> Result<Asset> {
+    Ok(Asset {
+        id: row.try_get("id")?,
+        organization_id: row.try_get("organization_id")?,
+        asset_type: row.try_get("asset_type")?,
+        value: row.try_get("value")?,
+        status: row.try_get("status")?,
+        first_seen: from_offset_datetime(Some(row.try_get("first_seen")?)),
+        last_seen: from_offset_datetime(Some(row.try_get("last_seen")?)),
+        attributes: row.try_get("attributes")?,
+        risk_score: row.try_get("risk_score")?,
+        created_at: from_offset_datetime(Some(row.try_get("created_at")?)),
+        updated_at: from_offset_datetime(Some(row.try_get("updated_at")?)),
+    })
+}
+
 #[async_trait]
 impl AssetRepository for PgAssetRepository {
     async fn create_asset(&self, asset: &Asset) -> Result<Asset> {
@@ -29,7 +85,7 @@ impl AssetRepository for PgAssetRepository {
             r#"
             INSERT INTO assets (id, organization_id, asset_type, value, status, first_seen, last_seen, created_at, updated_at, attributes)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
+            RETURNING id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes, risk_score
             "#,
             asset.id,
             asset.organization_id,
@@ -45,6 +101,14 @@ impl AssetRepository for PgAssetRepository {
         .fetch_one(&self.pool)
         .await?;
 
+        publish_resource_event(
+            &self.pool,
+            ResourceEventType::Created,
+            record.organization_id,
+            ResourceKind::Asset,
+        )
+        .await;
+
         // Convert back from DB types to model types
         Ok(Asset {
             id: record.id,
@@ -57,6 +121,72 @@ impl AssetRepository for PgAssetRepository {
             attributes: record
                 .attributes
                 .expect("Asset attributes should not be null"),
+            risk_score: record.risk_score,
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn upsert_asset(&self, asset: &Asset) -> Result<Asset> {
+        let first_seen = to_offset_datetime(asset.first_seen);
+        let last_seen = to_offset_datetime(asset.last_seen);
+        let created_at = to_offset_datetime(asset.created_at);
+        let updated_at = to_offset_datetime(asset.updated_at);
+
+        // The conflict target is the partial unique index on live assets,
+        // so a soft-deleted asset doesn't stop the value being rediscovered
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO assets (id, organization_id, asset_type, value, status, first_seen, last_seen, created_at, updated_at, attributes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (organization_id, asset_type, value) WHERE deleted_at IS NULL
+            DO UPDATE SET
+                status = EXCLUDED.status,
+                last_seen = GREATEST(assets.last_seen, EXCLUDED.last_seen),
+                attributes = COALESCE(assets.attributes, '{}'::jsonb) || EXCLUDED.attributes,
+                updated_at = EXCLUDED.updated_at
+            RETURNING id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes, risk_score, (xmax = 0) as "inserted!"
+            "#,
+            asset.id,
+            asset.organization_id,
+            asset.asset_type as AssetType,
+            asset.value,
+            asset.status as AssetStatus,
+            first_seen,
+            last_seen,
+            created_at,
+            updated_at,
+            asset.attributes
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        // xmax is only set on a row the conflict clause updated
+        let event_type = if record.inserted {
+            ResourceEventType::Created
+        } else {
+            ResourceEventType::Updated
+        };
+        publish_resource_event(
+            &self.pool,
+            event_type,
+            record.organization_id,
+            ResourceKind::Asset,
+        )
+        .await;
+
+        Ok(Asset {
+            id: record.id,
+            organization_id: record.organization_id,
+            asset_type: record.asset_type,
+            value: record.value,
+            status: record.status.expect("Asset status should not be null"),
+            first_seen: from_offset_datetime(Some(record.first_seen)),
+            last_seen: from_offset_datetime(Some(record.last_seen)),
+            attributes: record
+                .attributes
+                .expect("Asset attributes should not be null"),
+            risk_score: record.risk_score,
             created_at: from_offset_datetime(Some(record.created_at)),
             updated_at: from_offset_datetime(Some(record.updated_at)),
         })
@@ -65,9 +195,9 @@ impl AssetRepository for PgAssetRepository {
     async fn get_asset(&self, id: ID) -> Result<Asset> {
         let record = sqlx::query!(
             r#"
-            SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
+            SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes, risk_score
             FROM assets
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
             id
         )
@@ -86,11 +216,44 @@ impl AssetRepository for PgAssetRepository {
             attributes: record
                 .attributes
                 .expect("Asset attributes should not be null"),
+            risk_score: record.risk_score,
             created_at: from_offset_datetime(Some(record.created_at)),
             updated_at: from_offset_datetime(Some(record.updated_at)),
         })
     }
 
+    async fn get_assets(&self, ids: &[ID]) -> Result<Vec<Asset>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes, risk_score
+            FROM assets
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            "#,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| Asset {
+                id: record.id,
+                organization_id: record.organization_id,
+                asset_type: record.asset_type,
+                value: record.value,
+                status: record.status.expect("Asset status should not be null"),
+                first_seen: from_offset_datetime(Some(record.first_seen)),
+                last_seen: from_offset_datetime(Some(record.last_seen)),
+                attributes: record
+                    .attributes
+                    .expect("Asset attributes should not be null"),
+                risk_score: record.risk_score,
+                created_at: from_offset_datetime(Some(record.created_at)),
+                updated_at: from_offset_datetime(Some(record.updated_at)),
+            })
+            .collect())
+    }
+
     async fn update_asset(&self, asset: &Asset) -> Result<Asset> {
         // Convert DateTime types for database operation
         let first_seen = to_offset_datetime(asset.first_seen);
@@ -102,7 +265,7 @@ impl AssetRepository for PgAssetRepository {
             UPDATE assets
             SET organization_id = $2, asset_type = $3, value = $4, status = $5, first_seen = $6, last_seen = $7, updated_at = $8, attributes = $9
             WHERE id = $1
-            RETURNING id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
+            RETURNING id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes, risk_score
             "#,
             asset.id,
             asset.organization_id,
@@ -117,6 +280,14 @@ impl AssetRepository for PgAssetRepository {
         .fetch_one(&self.pool)
         .await?;
 
+        publish_resource_event(
+            &self.pool,
+            ResourceEventType::Updated,
+            record.organization_id,
+            ResourceKind::Asset,
+        )
+        .await;
+
         // Convert back from DB types to model types
         Ok(Asset {
             id: record.id,
@@ -129,18 +300,41 @@ impl AssetRepository for PgAssetRepository {
             attributes: record
                 .attributes
                 .expect("Asset attributes should not be null"),
+            risk_score: record.risk_score,
             created_at: from_offset_datetime(Some(record.created_at)),
             updated_at: from_offset_datetime(Some(record.updated_at)),
         })
     }
 
     async fn delete_asset(&self, id: ID) -> Result<bool> {
+        let now = to_offset_datetime(chrono::Utc::now());
+
         let result = sqlx::query!(
             r#"
-            DELETE FROM assets
-            WHERE id = $1
+            UPDATE assets
+            SET deleted_at = $2, updated_at = $2
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
-            id
+            id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn restore_asset(&self, id: ID) -> Result<bool> {
+        let now = to_offset_datetime(chrono::Utc::now());
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE assets
+            SET deleted_at = NULL, updated_at = $2
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            "#,
+            id,
+            now
         )
         .execute(&self.pool)
         .await?;
@@ -150,401 +344,386 @@ impl AssetRepository for PgAssetRepository {
 
     async fn list_assets(
         &self,
-        organization_id: Option<ID>,
-        asset_type: Option<AssetType>,
-        status: Option<AssetStatus>,
+        filter: &AssetFilter,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<Asset>> {
-        let assets = match (organization_id, asset_type, status) {
-            (Some(org_id), Some(a_type), Some(s)) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE organization_id = $1 AND asset_type = $2 AND status = $3
-                    ORDER BY value
-                    LIMIT $4 OFFSET $5
-                    "#,
-                    org_id,
-                    a_type as AssetType,
-                    s as AssetStatus,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (Some(org_id), Some(a_type), None) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE organization_id = $1 AND asset_type = $2
-                    ORDER BY value
-                    LIMIT $3 OFFSET $4
-                    "#,
-                    org_id,
-                    a_type as AssetType,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (Some(org_id), None, Some(s)) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE organization_id = $1 AND status = $2
-                    ORDER BY value
-                    LIMIT $3 OFFSET $4
-                    "#,
-                    org_id,
-                    s as AssetStatus,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (None, Some(a_type), Some(s)) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE asset_type = $1 AND status = $2
-                    ORDER BY value
-                    LIMIT $3 OFFSET $4
-                    "#,
-                    a_type as AssetType,
-                    s as AssetStatus,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (Some(org_id), None, None) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE organization_id = $1
-                    ORDER BY value
-                    LIMIT $2 OFFSET $3
-                    "#,
-                    org_id,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (None, Some(a_type), None) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE asset_type = $1
-                    ORDER BY value
-                    LIMIT $2 OFFSET $3
-                    "#,
-                    a_type as AssetType,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (None, None, Some(s)) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    WHERE status = $1
-                    ORDER BY value
-                    LIMIT $2 OFFSET $3
-                    "#,
-                    s as AssetStatus,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
-            (None, None, None) => {
-                let records = sqlx::query!(
-                    r#"
-                    SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes
-                    FROM assets
-                    ORDER BY value
-                    LIMIT $1 OFFSET $2
-                    "#,
-                    limit as i64,
-                    offset as i64
-                )
-                .fetch_all(&self.pool)
-                .await?;
-
-                records
-                    .into_iter()
-                    .map(|record| Asset {
-                        id: record.id,
-                        organization_id: record.organization_id,
-                        asset_type: record.asset_type,
-                        value: record.value,
-                        status: record.status.expect("Asset status should not be null"),
-                        first_seen: from_offset_datetime(Some(record.first_seen)),
-                        last_seen: from_offset_datetime(Some(record.last_seen)),
-                        attributes: record
-                            .attributes
-                            .expect("Asset attributes should not be null"),
-                        created_at: from_offset_datetime(Some(record.created_at)),
-                        updated_at: from_offset_datetime(Some(record.updated_at)),
-                    })
-                    .collect()
-            }
+        let mut query_builder =
+            sqlx::QueryBuilder::new(format!("SELECT {} FROM assets WHERE 1 = 1", ASSET_COLUMNS));
+        push_asset_filter(&mut query_builder, filter);
+        query_builder.push(" ORDER BY value, id LIMIT ");
+        query_builder.push_bind(limit as i64);
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        let rows = query_builder.build().fetch_all(&self.pool).await?;
+        rows.iter().map(asset_from_row).collect()
+    }
+
+    async fn count_assets(&self, filter: &AssetFilter) -> Result<usize> {
+        let mut query_builder = sqlx::QueryBuilder::new("SELECT COUNT(*) FROM assets WHERE 1 = 1");
+        push_asset_filter(&mut query_builder, filter);
+
+        let count: i64 = query_builder.build().fetch_one(&self.pool).await?.get(0);
+
+        Ok(count as usize)
+    }
+
+    async fn list_assets_page(
+        &self,
+        filter: &AssetFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<Asset>> {
+        // The window function counts every matching row before LIMIT applies
+        let mut query_builder = sqlx::QueryBuilder::new(format!(
+            "SELECT {}, COUNT(*) OVER() AS total FROM assets WHERE 1 = 1",
+            ASSET_COLUMNS
+        ));
+        push_asset_filter(&mut query_builder, filter);
+        query_builder.push(" ORDER BY value, id LIMIT ");
+        query_builder.push_bind(limit as i64);
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        let rows = query_builder.build().fetch_all(&self.pool).await?;
+
+        // A page past the end has no rows to carry the total
+        let total = match rows.first() {
+            Some(row) => row.try_get::<i64, _>("total")? as usize,
+            None if offset > 0 => self.count_assets(filter).await?,
+            None => 0,
         };
 
-        Ok(assets)
+        Ok(Page {
+            items: rows.iter().map(asset_from_row).collect::<Result<_>>()?,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    async fn add_asset_tags(&self, asset_id: ID, tags: &[String]) -> Result<Vec<String>> {
+        sqlx::query!(
+            r#"
+            INSERT INTO asset_tags (asset_id, tag)
+            SELECT $1, tag FROM UNNEST($2::varchar[]) AS tag
+            ON CONFLICT (asset_id, tag) DO NOTHING
+            "#,
+            asset_id,
+            tags
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.list_asset_tags(asset_id).await
+    }
+
+    async fn remove_asset_tag(&self, asset_id: ID, tag: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM asset_tags
+            WHERE asset_id = $1 AND tag = $2
+            "#,
+            asset_id,
+            tag
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_asset_tags(&self, asset_id: ID) -> Result<Vec<String>> {
+        let tags = sqlx::query_scalar!(
+            r#"
+            SELECT tag
+            FROM asset_tags
+            WHERE asset_id = $1
+            ORDER BY tag
+            "#,
+            asset_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    async fn bulk_update_status(
+        &self,
+        organization_id: ID,
+        asset_ids: &[ID],
+        status: AssetStatus,
+    ) -> Result<usize> {
+        let now = to_offset_datetime(chrono::Utc::now());
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE assets
+            SET status = $3, updated_at = $4
+            WHERE id = ANY($1) AND organization_id = $2 AND deleted_at IS NULL
+            "#,
+            asset_ids,
+            organization_id,
+            status as AssetStatus,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            publish_resource_event(
+                &self.pool,
+                ResourceEventType::Updated,
+                organization_id,
+                ResourceKind::Asset,
+            )
+            .await;
+        }
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn add_asset_relationship(&self, relationship: &AssetRelationship) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO asset_relationships (source_id, target_id, relationship_type, metadata)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (source_id, relationship_type, target_id) DO NOTHING
+            "#,
+            relationship.source_asset_id,
+            relationship.target_asset_id,
+            relationship.relationship_type,
+            relationship.metadata
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn add_relationships_bulk(&self, relationships: Vec<(ID, ID, String)>) -> Result<usize> {
+        if relationships.is_empty() {
+            return Ok(0);
+        }
+
+        let mut sources = Vec::with_capacity(relationships.len());
+        let mut targets = Vec::with_capacity(relationships.len());
+        let mut types = Vec::with_capacity(relationships.len());
+        for (source, target, relationship_type) in relationships {
+            sources.push(source);
+            targets.push(target);
+            types.push(relationship_type);
+        }
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO asset_relationships (source_id, target_id, relationship_type)
+            SELECT new.source_id, new.target_id, new.relationship_type
+            FROM UNNEST($1::uuid[], $2::uuid[], $3::varchar[]) AS new(source_id, target_id, relationship_type)
+            WHERE EXISTS (SELECT 1 FROM assets WHERE id = new.source_id AND deleted_at IS NULL)
+                AND EXISTS (SELECT 1 FROM assets WHERE id = new.target_id AND deleted_at IS NULL)
+            ON CONFLICT (source_id, relationship_type, target_id) DO NOTHING
+            "#,
+            &sources,
+            &targets,
+            &types
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn remove_asset_relationship(
+        &self,
+        source_asset_id: ID,
+        target_asset_id: ID,
+        relationship_type: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM asset_relationships
+            WHERE source_id = $1 AND target_id = $2 AND relationship_type = $3
+            "#,
+            source_asset_id,
+            target_asset_id,
+            relationship_type
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
-    async fn count_assets(
+    async fn list_asset_relationships(
+        &self,
+        asset_id: ID,
+        direction: RelationshipDirection,
+        relationship_type: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<(AssetRelationship, Asset)>> {
+        let outgoing = direction == RelationshipDirection::Outgoing;
+
+        // Each direction has its own index; the asset joined is the other end
+        let records = sqlx::query!(
+            r#"
+            SELECT r.source_id, r.target_id, r.relationship_type, r.metadata,
+                a.id, a.organization_id, a.asset_type as "asset_type: AssetType", a.value, a.status as "status: AssetStatus", a.first_seen, a.last_seen, a.created_at, a.updated_at, a.attributes, a.risk_score
+            FROM asset_relationships r
+            JOIN assets a ON a.id = CASE WHEN $2 THEN r.target_id ELSE r.source_id END
+            WHERE (($2 AND r.source_id = $1) OR (NOT $2 AND r.target_id = $1))
+                AND ($3::varchar IS NULL OR r.relationship_type = $3)
+                AND a.deleted_at IS NULL
+            ORDER BY r.relationship_type, a.value, a.id
+            LIMIT $4 OFFSET $5
+            "#,
+            asset_id,
+            outgoing,
+            relationship_type,
+            limit.min(i64::MAX as usize) as i64,
+            offset as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let relationship = AssetRelationship {
+                    source_asset_id: record.source_id,
+                    target_asset_id: record.target_id,
+                    relationship_type: record.relationship_type,
+                    metadata: record.metadata,
+                };
+                let asset = Asset {
+                    id: record.id,
+                    organization_id: record.organization_id,
+                    asset_type: record.asset_type,
+                    value: record.value,
+                    status: record.status.expect("Asset status should not be null"),
+                    first_seen: from_offset_datetime(Some(record.first_seen)),
+                    last_seen: from_offset_datetime(Some(record.last_seen)),
+                    attributes: record
+                        .attributes
+                        .expect("Asset attributes should not be null"),
+                    risk_score: record.risk_score,
+                    created_at: from_offset_datetime(Some(record.created_at)),
+                    updated_at: from_offset_datetime(Some(record.updated_at)),
+                };
+                (relationship, asset)
+            })
+            .collect())
+    }
+
+    async fn mark_stale(
         &self,
         organization_id: Option<ID>,
-        asset_type: Option<AssetType>,
-        status: Option<AssetStatus>,
+        older_than: chrono::Duration,
     ) -> Result<usize> {
-        let count: Option<i64> = match (organization_id, asset_type, status) {
-            (Some(org_id), Some(a_type), Some(s)) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE organization_id = $1 AND asset_type = $2 AND status = $3
-                    "#,
-                    org_id,
-                    a_type as AssetType,
-                    s as AssetStatus
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (Some(org_id), Some(a_type), None) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE organization_id = $1 AND asset_type = $2
-                    "#,
-                    org_id,
-                    a_type as AssetType,
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (Some(org_id), None, Some(s)) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE organization_id = $1 AND status = $2
-                    "#,
-                    org_id,
-                    s as AssetStatus,
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (None, Some(a_type), Some(s)) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE asset_type = $1 AND status = $2
-                    "#,
-                    a_type as AssetType,
-                    s as AssetStatus,
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (Some(org_id), None, None) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE organization_id = $1
-                    "#,
-                    org_id,
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (None, Some(a_type), None) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE asset_type = $1
-                    "#,
-                    a_type as AssetType,
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (None, None, Some(s)) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    WHERE status = $1
-                    "#,
-                    s as AssetStatus,
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-            (None, None, None) => {
-                sqlx::query_scalar!(
-                    r#"
-                    SELECT COUNT(*) as count
-                    FROM assets
-                    "#
-                )
-                .fetch_one(&self.pool)
-                .await?
-            }
-        };
+        let now = chrono::Utc::now();
+        let cutoff = to_offset_datetime(now - older_than);
+        let updated_at = to_offset_datetime(now);
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE assets
+            SET status = 'INACTIVE', updated_at = $3
+            WHERE status = 'ACTIVE'
+              AND deleted_at IS NULL
+              AND last_seen < $2
+              AND ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            organization_id,
+            cutoff,
+            updated_at
+        )
+        .execute(&self.pool)
+        .await?;
 
-        Ok(count.unwrap_or(0) as usize)
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn update_risk_score(&self, id: ID, risk_score: f32) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE assets
+            SET risk_score = $2
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+            risk_score
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_recent_assets(&self, organization_id: ID, limit: usize) -> Result<Vec<Asset>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT id, organization_id, asset_type as "asset_type: AssetType", value, status as "status: AssetStatus", first_seen, last_seen, created_at, updated_at, attributes, risk_score
+            FROM assets
+            WHERE organization_id = $1 AND deleted_at IS NULL
+            ORDER BY first_seen DESC, value
+            LIMIT $2
+            "#,
+            organization_id,
+            limit as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| Asset {
+                id: record.id,
+                organization_id: record.organization_id,
+                asset_type: record.asset_type,
+                value: record.value,
+                status: record.status.expect("Asset status should not be null"),
+                first_seen: from_offset_datetime(Some(record.first_seen)),
+                last_seen: from_offset_datetime(Some(record.last_seen)),
+                attributes: record
+                    .attributes
+                    .expect("Asset attributes should not be null"),
+                risk_score: record.risk_score,
+                created_at: from_offset_datetime(Some(record.created_at)),
+                updated_at: from_offset_datetime(Some(record.updated_at)),
+            })
+            .collect())
+    }
+
+    async fn asset_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT (first_seen AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS count
+            FROM assets
+            WHERE organization_id = $1 AND first_seen >= $2 AND deleted_at IS NULL
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+        .bind(organization_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get::<chrono::NaiveDate, _>("day"),
+                    row.get::<i64, _>("count") as usize,
+                )
+            })
+            .collect())
     }
 }
@@ -0,0 +1,103 @@
+use crate::utils::{from_offset_datetime, to_offset_datetime};
+use async_trait::async_trait;
+use backend::{models::AssetHistory, traits::AssetHistoryRepository, Result};
+use shared::types::ID;
+use sqlx::PgPool;
+
+/// PostgreSQL implementation of the AssetHistory Repository
+pub struct PgAssetHistoryRepository {
+    pool: PgPool,
+}
+
+impl PgAssetHistoryRepository {
+    /// Create a new PgAssetHistoryRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Store JSON null as SQL NULL so added/removed fields are easy to query
+fn to_nullable_json(value: &serde_json::Value) -> Option<serde_json::Value> {
+    (!value.is_null()).then(|| value.clone())
+}
+
+#[async_trait]
+impl AssetHistoryRepository for PgAssetHistoryRepository {
+    async fn create_history_entry(&self, entry: &AssetHistory) -> Result<AssetHistory> {
+        let changed_at = to_offset_datetime(entry.changed_at);
+
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO asset_history (id, asset_id, field, old_value, new_value, changed_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, asset_id, field, old_value, new_value, changed_at
+            "#,
+            entry.id,
+            entry.asset_id,
+            entry.field,
+            to_nullable_json(&entry.old_value),
+            to_nullable_json(&entry.new_value),
+            changed_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(AssetHistory {
+            id: record.id,
+            asset_id: record.asset_id,
+            field: record.field,
+            old_value: record.old_value.unwrap_or_default(),
+            new_value: record.new_value.unwrap_or_default(),
+            changed_at: from_offset_datetime(Some(record.changed_at)),
+        })
+    }
+
+    async fn list_asset_history(
+        &self,
+        asset_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AssetHistory>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT id, asset_id, field, old_value, new_value, changed_at
+            FROM asset_history
+            WHERE asset_id = $1
+            ORDER BY changed_at DESC, field
+            LIMIT $2 OFFSET $3
+            "#,
+            asset_id,
+            limit as i64,
+            offset as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| AssetHistory {
+                id: record.id,
+                asset_id: record.asset_id,
+                field: record.field,
+                old_value: record.old_value.unwrap_or_default(),
+                new_value: record.new_value.unwrap_or_default(),
+                changed_at: from_offset_datetime(Some(record.changed_at)),
+            })
+            .collect())
+    }
+
+    async fn count_asset_history(&self, asset_id: ID) -> Result<usize> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as count
+            FROM asset_history
+            WHERE asset_id = $1
+            "#,
+            asset_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.unwrap_or(0) as usize)
+    }
+}
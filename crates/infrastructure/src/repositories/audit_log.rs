@@ -0,0 +1,139 @@
+use crate::utils::{from_offset_datetime, to_offset_datetime};
+use async_trait::async_trait;
+use backend::{
+    models::{AuditLogEntry, AuditLogFilter},
+    traits::AuditLogRepository,
+    Result,
+};
+use shared::types::AuditAction;
+use sqlx::PgPool;
+
+/// PostgreSQL implementation of the AuditLog Repository
+pub struct PgAuditLogRepository {
+    pool: PgPool,
+}
+
+impl PgAuditLogRepository {
+    /// Create a new PgAuditLogRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for PgAuditLogRepository {
+    async fn create_entry(&self, entry: &AuditLogEntry) -> Result<AuditLogEntry> {
+        let created_at = to_offset_datetime(entry.created_at);
+
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO audit_log (
+                id, user_id, organization_id, action, resource_type, resource_id,
+                ip_address, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING
+                id, user_id, organization_id, action as "action: AuditAction",
+                resource_type, resource_id, ip_address, created_at
+            "#,
+            entry.id,
+            entry.user_id,
+            entry.organization_id,
+            entry.action as AuditAction,
+            entry.resource_type,
+            entry.resource_id,
+            entry.ip_address,
+            created_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(AuditLogEntry {
+            id: record.id,
+            user_id: record.user_id,
+            organization_id: record.organization_id,
+            action: record.action,
+            resource_type: record.resource_type,
+            resource_id: record.resource_id,
+            ip_address: record.ip_address,
+            created_at: from_offset_datetime(Some(record.created_at)),
+        })
+    }
+
+    async fn list_entries(
+        &self,
+        filter: &AuditLogFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                id, user_id, organization_id, action as "action: AuditAction",
+                resource_type, resource_id, ip_address, created_at
+            FROM audit_log
+            WHERE ($1::uuid IS NULL OR organization_id = $1)
+                AND ($2::uuid IS NULL OR user_id = $2)
+                AND ($3::varchar IS NULL OR action = $3)
+                AND ($4::varchar IS NULL OR resource_type = $4)
+                AND ($5::uuid IS NULL OR resource_id = $5)
+                AND ($6::timestamptz IS NULL OR created_at >= $6)
+                AND ($7::timestamptz IS NULL OR created_at <= $7)
+            ORDER BY created_at DESC, id
+            LIMIT $8 OFFSET $9
+            "#,
+            filter.organization_id,
+            filter.user_id,
+            filter.action as Option<AuditAction>,
+            filter.resource_type,
+            filter.resource_id,
+            filter.since.map(to_offset_datetime),
+            filter.until.map(to_offset_datetime),
+            limit as i64,
+            offset as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| AuditLogEntry {
+                id: record.id,
+                user_id: record.user_id,
+                organization_id: record.organization_id,
+                action: record.action,
+                resource_type: record.resource_type,
+                resource_id: record.resource_id,
+                ip_address: record.ip_address,
+                created_at: from_offset_datetime(Some(record.created_at)),
+            })
+            .collect())
+    }
+
+    async fn count_entries(&self, filter: &AuditLogFilter) -> Result<usize> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as count
+            FROM audit_log
+            WHERE ($1::uuid IS NULL OR organization_id = $1)
+                AND ($2::uuid IS NULL OR user_id = $2)
+                AND ($3::varchar IS NULL OR action = $3)
+                AND ($4::varchar IS NULL OR resource_type = $4)
+                AND ($5::uuid IS NULL OR resource_id = $5)
+                AND ($6::timestamptz IS NULL OR created_at >= $6)
+                AND ($7::timestamptz IS NULL OR created_at <= $7)
+            "#,
+            filter.organization_id,
+            filter.user_id,
+            filter.action as Option<AuditAction>,
+            filter.resource_type,
+            filter.resource_id,
+            filter.since.map(to_offset_datetime),
+            filter.until.map(to_offset_datetime)
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.unwrap_or(0) as usize)
+    }
+}
@@ -1,16 +1,19 @@
 use crate::utils::{
-    from_offset_datetime, from_option_offset_datetime, to_offset_datetime,
+    from_offset_datetime, from_option_offset_datetime, sort_direction, to_offset_datetime,
     to_option_offset_datetime,
 };
 use async_trait::async_trait;
 use backend::{
-    models::{Asset, DiscoveryJob, JobAssetLink},
+    models::{Asset, DiscoveryJob, DiscoveryJobFilter, JobAssetLink, JobResultSummary},
     traits::DiscoveryJobRepository,
     Result,
 };
-use shared::types::{AssetStatus, AssetType, JobStatus, JobType, ID};
+use shared::types::{
+    AssetStatus, AssetType, JobSortField, JobStatus, JobType, Sort, Timestamp, ID,
+};
 use sqlx::PgPool;
 use sqlx::Row;
+use std::collections::HashMap;
 
 /// PostgreSQL implementation of the DiscoveryJob Repository
 pub struct PgDiscoveryJobRepository {
@@ -24,6 +27,35 @@ impl PgDiscoveryJobRepository {
     }
 }
 
+/// Append `filter`'s criteria to a query ending in a `WHERE` clause
+fn push_job_filter(
+    query_builder: &mut sqlx::QueryBuilder<'_, sqlx::Postgres>,
+    filter: &DiscoveryJobFilter,
+) {
+    if let Some(org_id) = filter.organization_id {
+        query_builder.push(" AND organization_id = ");
+        query_builder.push_bind(org_id);
+    }
+    if !filter.statuses.is_empty() {
+        let statuses: Vec<String> = filter.statuses.iter().map(ToString::to_string).collect();
+        query_builder.push(" AND status = ANY(");
+        query_builder.push_bind(statuses);
+        query_builder.push(")");
+    }
+    if let Some(jt) = filter.job_type {
+        query_builder.push(" AND job_type = ");
+        query_builder.push_bind(jt as JobType);
+    }
+    if let Some(since) = filter.since {
+        query_builder.push(" AND created_at >= ");
+        query_builder.push_bind(to_offset_datetime(since));
+    }
+    if let Some(until) = filter.until {
+        query_builder.push(" AND created_at <= ");
+        query_builder.push_bind(to_offset_datetime(until));
+    }
+}
+
 #[async_trait]
 impl DiscoveryJobRepository for PgDiscoveryJobRepository {
     async fn create_job(&self, job: &DiscoveryJob) -> Result<DiscoveryJob> {
@@ -174,9 +206,8 @@ impl DiscoveryJobRepository for PgDiscoveryJobRepository {
 
     async fn list_jobs(
         &self,
-        organization_id: Option<ID>,
-        job_type: Option<JobType>,
-        status: Option<JobStatus>,
+        filter: &DiscoveryJobFilter,
+        sort: Option<Sort<JobSortField>>,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<DiscoveryJob>> {
@@ -189,21 +220,22 @@ impl DiscoveryJobRepository for PgDiscoveryJobRepository {
             WHERE 1 = 1
             "#,
         );
+        push_job_filter(&mut query_builder, filter);
 
-        if let Some(org_id) = organization_id {
-            query_builder.push(" AND organization_id = ");
-            query_builder.push_bind(org_id);
-        }
-        if let Some(jt) = job_type {
-            query_builder.push(" AND job_type = ");
-            query_builder.push_bind(jt as JobType);
-        }
-        if let Some(st) = status {
-            query_builder.push(" AND status = ");
-            query_builder.push_bind(st as JobStatus);
-        }
-
-        query_builder.push(" ORDER BY created_at DESC LIMIT ");
+        // Only fixed column names reach the SQL, never the client's input
+        let order_by = match sort {
+            Some(Sort {
+                field: JobSortField::Status,
+                order,
+            }) => format!("status {}, created_at DESC", sort_direction(order)),
+            Some(Sort {
+                field: JobSortField::CreatedAt,
+                order,
+            }) => format!("created_at {}", sort_direction(order)),
+            None => "created_at DESC".to_string(),
+        };
+        query_builder.push(format!(" ORDER BY {}", order_by));
+        query_builder.push(", id LIMIT ");
         query_builder.push_bind(limit as i64);
         query_builder.push(" OFFSET ");
         query_builder.push_bind(offset as i64);
@@ -234,27 +266,10 @@ impl DiscoveryJobRepository for PgDiscoveryJobRepository {
         Ok(jobs)
     }
 
-    async fn count_jobs(
-        &self,
-        organization_id: Option<ID>,
-        job_type: Option<JobType>,
-        status: Option<JobStatus>,
-    ) -> Result<usize> {
+    async fn count_jobs(&self, filter: &DiscoveryJobFilter) -> Result<usize> {
         let mut query_builder =
             sqlx::QueryBuilder::new("SELECT COUNT(*) FROM discovery_jobs WHERE 1 = 1");
-
-        if let Some(org_id) = organization_id {
-            query_builder.push(" AND organization_id = ");
-            query_builder.push_bind(org_id);
-        }
-        if let Some(jt) = job_type {
-            query_builder.push(" AND job_type = ");
-            query_builder.push_bind(jt as JobType);
-        }
-        if let Some(st) = status {
-            query_builder.push(" AND status = ");
-            query_builder.push_bind(st as JobStatus);
-        }
+        push_job_filter(&mut query_builder, filter);
 
         let count: i64 = query_builder.build().fetch_one(&self.pool).await?.get(0);
 
@@ -319,13 +334,33 @@ impl DiscoveryJobRepository for PgDiscoveryJobRepository {
         Ok(link.clone())
     }
 
+    async fn link_job_to_assets(&self, job_id: ID, asset_ids: &[ID]) -> Result<usize> {
+        if asset_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO job_asset_links (job_id, asset_id)
+            SELECT $1, asset_id FROM UNNEST($2::uuid[]) AS asset_id
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(job_id)
+        .bind(asset_ids)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
     async fn get_job_assets(&self, job_id: ID) -> Result<Vec<Asset>> {
         let records = sqlx::query!(
             r#"
             SELECT 
                 a.id, a.organization_id, a.asset_type as "asset_type: AssetType", 
                 a.value, a.status as "status: AssetStatus", a.first_seen, 
-                a.last_seen, a.created_at, a.updated_at, a.attributes
+                a.last_seen, a.created_at, a.updated_at, a.attributes, a.risk_score
             FROM assets a
             JOIN job_asset_links j ON a.id = j.asset_id
             WHERE j.job_id = $1
@@ -349,6 +384,7 @@ impl DiscoveryJobRepository for PgDiscoveryJobRepository {
                 attributes: record
                     .attributes
                     .expect("Asset attributes should not be null"),
+                risk_score: record.risk_score,
                 created_at: from_offset_datetime(Some(record.created_at)),
                 updated_at: from_offset_datetime(Some(record.updated_at)),
             })
@@ -356,4 +392,88 @@ impl DiscoveryJobRepository for PgDiscoveryJobRepository {
 
         Ok(assets)
     }
+
+    async fn get_job_summary(&self, job_id: ID) -> Result<JobResultSummary> {
+        let row = sqlx::query(
+            r#"
+            WITH linked AS (
+                SELECT a.id
+                FROM assets a
+                JOIN job_asset_links j ON a.id = j.asset_id
+                WHERE j.job_id = $1 AND a.deleted_at IS NULL
+            )
+            SELECT
+                (SELECT COUNT(*) FROM linked) AS assets,
+                (SELECT COUNT(*) FROM ports WHERE asset_id IN (SELECT id FROM linked)) AS ports,
+                (
+                    SELECT COUNT(*) FROM technologies
+                    WHERE asset_id IN (SELECT id FROM linked)
+                ) AS technologies,
+                (
+                    SELECT COUNT(*) FROM vulnerabilities
+                    WHERE asset_id IN (SELECT id FROM linked) AND deleted_at IS NULL
+                ) AS vulnerabilities
+            "#,
+        )
+        .bind(job_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(JobResultSummary {
+            assets: row.get::<i64, _>("assets") as usize,
+            ports: row.get::<i64, _>("ports") as usize,
+            technologies: row.get::<i64, _>("technologies") as usize,
+            vulnerabilities: row.get::<i64, _>("vulnerabilities") as usize,
+        })
+    }
+
+    async fn record_target_scans(
+        &self,
+        organization_id: ID,
+        job_type: JobType,
+        targets: &[String],
+        scanned_at: Timestamp,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO target_scans (organization_id, job_type, target, scanned_at)
+            SELECT DISTINCT $1::uuid, $2::varchar, target, $4::timestamptz FROM UNNEST($3::text[]) AS target
+            ON CONFLICT (organization_id, job_type, target)
+            DO UPDATE SET scanned_at = GREATEST(target_scans.scanned_at, EXCLUDED.scanned_at)
+            "#,
+            organization_id,
+            job_type as JobType,
+            targets,
+            to_offset_datetime(scanned_at)
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn last_target_scans(
+        &self,
+        organization_id: ID,
+        job_type: JobType,
+        targets: &[String],
+    ) -> Result<HashMap<String, Timestamp>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT target, scanned_at
+            FROM target_scans
+            WHERE organization_id = $1 AND job_type = $2 AND target = ANY($3)
+            "#,
+            organization_id,
+            job_type as JobType,
+            targets
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| (record.target, from_offset_datetime(Some(record.scanned_at))))
+            .collect())
+    }
 }
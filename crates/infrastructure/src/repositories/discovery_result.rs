@@ -0,0 +1,274 @@
+use crate::utils::{from_offset_datetime, to_offset_datetime};
+use backend::{models::Technology, services::canonicalize_value, Error, Result};
+use discovery::import::nmap::parse_nmap_xml;
+use discovery::results::DiscoveryResult;
+use shared::types::{AssetStatus, AssetType, Protocol, ID};
+use sqlx::{types::time::OffsetDateTime, PgConnection, Row};
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+use uuid::Uuid;
+
+use super::{technology, RepositoryFactory};
+
+/// Number of rows written by `RepositoryFactory::persist_discovery_result`.
+/// Rows refreshed because they already existed are counted too.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersistedDiscovery {
+    pub assets: usize,
+    pub ports: usize,
+    pub technologies: usize,
+    /// IDs of the assets written, each once
+    pub asset_ids: Vec<ID>,
+}
+
+impl PersistedDiscovery {
+    fn add_asset(&mut self, asset_id: ID) {
+        self.assets += 1;
+        self.asset_ids.push(asset_id);
+    }
+}
+
+impl RepositoryFactory {
+    /// Write the domains, IPs, ports, web resources and technologies in
+    /// `result` for an organization in a single transaction. Known assets,
+    /// ports and technologies are refreshed instead of duplicated. Asset
+    /// values are stored in the form `canonicalize_value` gives them. If any
+    /// value is invalid or any write fails the transaction is rolled back
+    /// and nothing is stored. Returns what was written, including the IDs
+    /// of the assets, so a job can be linked to them.
+    pub async fn persist_discovery_result(
+        &self,
+        organization_id: ID,
+        result: &DiscoveryResult,
+    ) -> Result<PersistedDiscovery> {
+        let now = to_offset_datetime(chrono::Utc::now());
+        let mut persisted = PersistedDiscovery::default();
+
+        // Dropping the transaction on an early return rolls it back
+        let mut tx = self.pool().begin().await?;
+
+        for domain in &result.domains {
+            let asset_id = upsert_asset(
+                &mut tx,
+                organization_id,
+                AssetType::Domain,
+                &domain.domain_name,
+                serde_json::json!({ "source": domain.source }),
+                now,
+            )
+            .await?;
+            persisted.add_asset(asset_id);
+        }
+
+        // Ports are recorded on their IP asset, so scanned IPs count as discovered
+        let mut ip_assets = HashMap::new();
+        let ips = result
+            .ip_addresses
+            .iter()
+            .map(|ip| (ip.ip_address, &ip.source))
+            .chain(
+                result
+                    .ports
+                    .iter()
+                    .map(|port| (port.ip_address, &port.source)),
+            );
+        for (ip, source) in ips {
+            let value = ip.to_string();
+            if ip_assets.contains_key(&value) {
+                continue;
+            }
+            let asset_id = upsert_asset(
+                &mut tx,
+                organization_id,
+                AssetType::IPAddress,
+                &value,
+                serde_json::json!({ "source": source }),
+                now,
+            )
+            .await?;
+            ip_assets.insert(value, asset_id);
+            persisted.add_asset(asset_id);
+        }
+
+        for port in &result.ports {
+            // Ports whose probe errored tell us nothing about their state
+            let Some(status) = port.port_status() else {
+                continue;
+            };
+            let protocol = if port.protocol.eq_ignore_ascii_case("UDP") {
+                Protocol::UDP
+            } else {
+                Protocol::TCP
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO ports (
+                    id, asset_id, port_number, protocol, service_name, banner, status,
+                    first_seen, last_seen, created_at, updated_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8, $8, $8)
+                ON CONFLICT (asset_id, port_number, protocol) DO UPDATE
+                SET service_name = COALESCE(EXCLUDED.service_name, ports.service_name),
+                    banner = COALESCE(EXCLUDED.banner, ports.banner),
+                    status = EXCLUDED.status,
+                    last_seen = EXCLUDED.last_seen,
+                    updated_at = EXCLUDED.updated_at
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(ip_assets[&port.ip_address.to_string()])
+            .bind(port.port as i32)
+            .bind(protocol)
+            .bind(&port.service_name)
+            .bind(&port.banner)
+            .bind(status)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+            persisted.ports += 1;
+        }
+
+        for resource in &result.web_resources {
+            let mut attributes = serde_json::json!({
+                "source": resource.source,
+                "status_code": resource.status_code,
+                "title": resource.title,
+            });
+            // Keep an earlier screenshot or hash when this run didn't take one
+            if let Some(path) = &resource.screenshot_path {
+                attributes["screenshot_path"] = path.clone().into();
+            }
+            if let Some(hash) = &resource.content_hash {
+                attributes["content_hash"] = hash.clone().into();
+            }
+            let asset_id = upsert_asset(
+                &mut tx,
+                organization_id,
+                AssetType::WebApp,
+                &resource.url,
+                attributes,
+                now,
+            )
+            .await?;
+            persisted.add_asset(asset_id);
+
+            // Technologies are reported as e.g. `WordPress 6.4` or `Nginx`
+            let mut seen = HashSet::new();
+            for technology in resource.technologies.iter().filter(|t| seen.insert(*t)) {
+                let (name, version) = match technology.trim().split_once(' ') {
+                    Some((name, version)) => (name, Some(version.trim())),
+                    None => (technology.trim(), None),
+                };
+                upsert_technology(&mut tx, asset_id, name, version, None, now).await?;
+                persisted.technologies += 1;
+            }
+        }
+
+        for finding in &result.technologies {
+            upsert_technology(
+                &mut tx,
+                finding.asset_id,
+                &finding.name,
+                finding.version.as_deref(),
+                finding.category.as_deref(),
+                now,
+            )
+            .await?;
+            persisted.technologies += 1;
+        }
+
+        tx.commit().await?;
+
+        // A result can list the same asset twice, written differently
+        persisted.asset_ids.sort();
+        persisted.asset_ids.dedup();
+
+        info!(
+            "Persisted discovery result for organization {}: {} assets, {} ports, {} technologies",
+            organization_id, persisted.assets, persisted.ports, persisted.technologies
+        );
+
+        Ok(persisted)
+    }
+
+    /// Store the hosts and ports of an nmap `-oX` scan for an organization
+    /// as [`RepositoryFactory::persist_discovery_result`] does. XML that
+    /// can't be parsed is a validation error.
+    pub async fn import_nmap_xml(
+        &self,
+        organization_id: ID,
+        xml: &str,
+    ) -> Result<PersistedDiscovery> {
+        let result = parse_nmap_xml(xml).map_err(|e| Error::Validation(format!("{:#}", e)))?;
+        self.persist_discovery_result(organization_id, &result)
+            .await
+    }
+}
+
+/// Create an asset, or mark the existing one active and seen now, merging in
+/// `attributes`. `value` is canonicalized first, so it matches assets created
+/// through the API. Returns the asset's ID.
+async fn upsert_asset(
+    conn: &mut PgConnection,
+    organization_id: ID,
+    asset_type: AssetType,
+    value: &str,
+    attributes: serde_json::Value,
+    now: OffsetDateTime,
+) -> Result<ID> {
+    let value = canonicalize_value(asset_type, value)?;
+    let row = sqlx::query(
+        r#"
+        INSERT INTO assets (
+            id, organization_id, asset_type, value, status,
+            first_seen, last_seen, created_at, updated_at, attributes
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $6, $6, $6, $7)
+        ON CONFLICT (organization_id, asset_type, value) WHERE deleted_at IS NULL DO UPDATE
+        SET status = EXCLUDED.status,
+            last_seen = EXCLUDED.last_seen,
+            updated_at = EXCLUDED.updated_at,
+            attributes = COALESCE(assets.attributes, '{}'::jsonb) || EXCLUDED.attributes
+        RETURNING id
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(organization_id)
+    .bind(asset_type)
+    .bind(&value)
+    .bind(AssetStatus::Active)
+    .bind(now)
+    .bind(attributes)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+/// Create a technology on an asset, or refresh the existing one with the
+/// same name.
+async fn upsert_technology(
+    conn: &mut PgConnection,
+    asset_id: ID,
+    name: &str,
+    version: Option<&str>,
+    category: Option<&str>,
+    now: OffsetDateTime,
+) -> Result<()> {
+    let now = from_offset_datetime(Some(now));
+    let technology = Technology {
+        last_seen: now,
+        created_at: now,
+        updated_at: now,
+        ..Technology::new(
+            asset_id,
+            name.to_string(),
+            version.map(str::to_string),
+            category.map(str::to_string),
+        )
+    };
+    technology::upsert_technology(conn, &technology).await?;
+
+    Ok(())
+}
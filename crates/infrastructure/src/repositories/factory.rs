@@ -1,13 +1,16 @@
 use backend::traits::{
-    AssetRepository, DiscoveryJobRepository, OrganizationRepository, PortRepository,
-    TechnologyRepository, UserRepository, VulnerabilityRepository,
+    AssetHistoryRepository, AssetRepository, AuditLogRepository, DiscoveryJobRepository,
+    NotificationLogRepository, OrganizationRepository, PortRepository, ScanProfileRepository,
+    ScanScheduleRepository, TechnologyRepository, UserRepository, VulnerabilityRepository,
 };
 use sqlx::PgPool;
 use std::sync::Arc;
 
 use super::{
-    PgAssetRepository, PgDiscoveryJobRepository, PgOrganizationRepository, PgPortRepository,
-    PgTechnologyRepository, PgUserRepository, PgVulnerabilityRepository,
+    PgAssetHistoryRepository, PgAssetRepository, PgAuditLogRepository, PgDiscoveryJobRepository,
+    PgNotificationLogRepository, PgOrganizationRepository, PgPortRepository,
+    PgScanProfileRepository, PgScanScheduleRepository, PgTechnologyRepository, PgUserRepository,
+    PgVulnerabilityRepository,
 };
 
 /// Factory for creating all repositories
@@ -42,6 +45,11 @@ impl RepositoryFactory {
         Arc::new(PgAssetRepository::new(self.pool.clone()))
     }
 
+    /// Create an asset history repository
+    pub fn asset_history_repository(&self) -> Arc<dyn AssetHistoryRepository> {
+        Arc::new(PgAssetHistoryRepository::new(self.pool.clone()))
+    }
+
     /// Create a port repository
     pub fn port_repository(&self) -> Arc<dyn PortRepository> {
         Arc::new(PgPortRepository::new(self.pool.clone()))
@@ -62,11 +70,36 @@ impl RepositoryFactory {
         Arc::new(PgDiscoveryJobRepository::new(self.pool.clone()))
     }
 
+    /// Create a scan schedule repository
+    pub fn scan_schedule_repository(&self) -> Arc<dyn ScanScheduleRepository> {
+        Arc::new(PgScanScheduleRepository::new(self.pool.clone()))
+    }
+
+    /// Create a scan profile repository
+    pub fn scan_profile_repository(&self) -> Arc<dyn ScanProfileRepository> {
+        Arc::new(PgScanProfileRepository::new(self.pool.clone()))
+    }
+
+    /// Create an audit log repository
+    pub fn audit_log_repository(&self) -> Arc<dyn AuditLogRepository> {
+        Arc::new(PgAuditLogRepository::new(self.pool.clone()))
+    }
+
+    /// Create a notification log repository
+    pub fn notification_log_repository(&self) -> Arc<dyn NotificationLogRepository> {
+        Arc::new(PgNotificationLogRepository::new(self.pool.clone()))
+    }
+
     /// Create a concrete PgAssetRepository
     pub fn create_asset_repository(&self, pool: PgPool) -> PgAssetRepository {
         PgAssetRepository::new(pool)
     }
 
+    /// Create a concrete PgAssetHistoryRepository
+    pub fn create_asset_history_repository(&self, pool: PgPool) -> PgAssetHistoryRepository {
+        PgAssetHistoryRepository::new(pool)
+    }
+
     /// Create a concrete PgVulnerabilityRepository
     pub fn create_vulnerability_repository(&self, pool: PgPool) -> PgVulnerabilityRepository {
         PgVulnerabilityRepository::new(pool)
@@ -96,4 +129,9 @@ impl RepositoryFactory {
     pub fn create_discovery_job_repository(&self, pool: PgPool) -> PgDiscoveryJobRepository {
         PgDiscoveryJobRepository::new(pool)
     }
+
+    /// Create a concrete PgScanScheduleRepository
+    pub fn create_scan_schedule_repository(&self, pool: PgPool) -> PgScanScheduleRepository {
+        PgScanScheduleRepository::new(pool)
+    }
 }
@@ -1,8 +1,14 @@
 mod asset;
+mod asset_history;
+mod audit_log;
 mod discovery_job;
+mod discovery_result;
 pub mod factory;
+mod notification_log;
 mod organization;
 mod port;
+mod scan_profile;
+mod scan_schedule;
 mod technology;
 mod user;
 mod vulnerability;
@@ -12,10 +18,16 @@ mod tests;
 
 // Re-exports
 pub use asset::*;
+pub use asset_history::*;
+pub use audit_log::*;
 pub use discovery_job::*;
+pub use discovery_result::*;
 pub use factory::*;
+pub use notification_log::*;
 pub use organization::*;
 pub use port::*;
+pub use scan_profile::*;
+pub use scan_schedule::*;
 pub use technology::*;
 pub use user::*;
 pub use vulnerability::*;
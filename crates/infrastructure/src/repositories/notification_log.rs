@@ -0,0 +1,54 @@
+use crate::utils::to_offset_datetime;
+use async_trait::async_trait;
+use backend::{traits::NotificationLogRepository, Result};
+use shared::types::{Timestamp, ID};
+use sqlx::PgPool;
+
+/// PostgreSQL implementation of the NotificationLog Repository
+pub struct PgNotificationLogRepository {
+    pool: PgPool,
+}
+
+impl PgNotificationLogRepository {
+    /// Create a new PgNotificationLogRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationLogRepository for PgNotificationLogRepository {
+    async fn claim_notification(
+        &self,
+        organization_id: ID,
+        fingerprint: &str,
+        event_type: &str,
+        now: Timestamp,
+        window: chrono::Duration,
+    ) -> Result<bool> {
+        let sent_at = to_offset_datetime(now);
+        let suppressed_since = to_offset_datetime(now - window);
+
+        // The conflict update only applies once the window has passed, so
+        // concurrent senders can't both claim the same alert
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO notification_log (organization_id, fingerprint, event_type, sent_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id, fingerprint) DO UPDATE
+            SET event_type = EXCLUDED.event_type, sent_at = EXCLUDED.sent_at
+            WHERE notification_log.sent_at <= $5
+            RETURNING organization_id
+            "#,
+            organization_id,
+            fingerprint,
+            event_type,
+            sent_at,
+            suppressed_since
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.is_some())
+    }
+}
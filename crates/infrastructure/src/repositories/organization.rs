@@ -1,7 +1,14 @@
-use crate::utils::{from_offset_datetime, to_offset_datetime};
+use crate::utils::{
+    from_offset_datetime, from_option_offset_datetime, to_offset_datetime,
+    to_option_offset_datetime,
+};
 use async_trait::async_trait;
-use backend::{models::Organization, traits::OrganizationRepository, Result};
-use shared::types::ID;
+use backend::{
+    models::Organization,
+    traits::{NotificationPeriod, OrganizationRepository},
+    Result,
+};
+use shared::types::{Timestamp, ID};
 use sqlx::PgPool;
 
 /// PostgreSQL implementation of the Organization Repository
@@ -19,17 +26,26 @@ impl PgOrganizationRepository {
 #[async_trait]
 impl OrganizationRepository for PgOrganizationRepository {
     async fn create_organization(&self, organization: &Organization) -> Result<Organization> {
+        let last_report_sent_at = to_option_offset_datetime(organization.last_report_sent_at);
         let created_at = to_offset_datetime(organization.created_at);
         let updated_at = to_offset_datetime(organization.updated_at);
 
         let record = sqlx::query!(
             r#"
-            INSERT INTO organizations (id, name, created_at, updated_at)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, name, created_at, updated_at
+            INSERT INTO organizations (
+                id, name, notification_period, last_report_sent_at, scan_scope, created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING
+                id, name, notification_period as "notification_period: NotificationPeriod",
+                last_report_sent_at, scan_scope, created_at, updated_at
             "#,
             organization.id,
             organization.name,
+            organization.notification_period as NotificationPeriod,
+            last_report_sent_at,
+            &organization.scan_scope,
             created_at,
             updated_at
         )
@@ -39,6 +55,9 @@ impl OrganizationRepository for PgOrganizationRepository {
         Ok(Organization {
             id: record.id,
             name: record.name,
+            notification_period: record.notification_period,
+            last_report_sent_at: from_option_offset_datetime(record.last_report_sent_at),
+            scan_scope: record.scan_scope,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -51,7 +70,9 @@ impl OrganizationRepository for PgOrganizationRepository {
     async fn get_organization(&self, id: ID) -> Result<Organization> {
         let record = sqlx::query!(
             r#"
-            SELECT id, name, created_at, updated_at
+            SELECT
+                id, name, notification_period as "notification_period: NotificationPeriod",
+                last_report_sent_at, scan_scope, created_at, updated_at
             FROM organizations
             WHERE id = $1
             "#,
@@ -63,6 +84,9 @@ impl OrganizationRepository for PgOrganizationRepository {
         Ok(Organization {
             id: record.id,
             name: record.name,
+            notification_period: record.notification_period,
+            last_report_sent_at: from_option_offset_datetime(record.last_report_sent_at),
+            scan_scope: record.scan_scope,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -72,18 +96,54 @@ impl OrganizationRepository for PgOrganizationRepository {
         })
     }
 
+    async fn get_organization_by_name(&self, name: &str) -> Result<Option<Organization>> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                id, name, notification_period as "notification_period: NotificationPeriod",
+                last_report_sent_at, scan_scope, created_at, updated_at
+            FROM organizations
+            WHERE name = $1
+            "#,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record.map(|record| Organization {
+            id: record.id,
+            name: record.name,
+            notification_period: record.notification_period,
+            last_report_sent_at: from_option_offset_datetime(record.last_report_sent_at),
+            scan_scope: record.scan_scope,
+            created_at: from_offset_datetime(Some(
+                record.created_at.expect("created_at should not be null"),
+            )),
+            updated_at: from_offset_datetime(Some(
+                record.updated_at.expect("updated_at should not be null"),
+            )),
+        }))
+    }
+
     async fn update_organization(&self, organization: &Organization) -> Result<Organization> {
+        let last_report_sent_at = to_option_offset_datetime(organization.last_report_sent_at);
         let updated_at = to_offset_datetime(organization.updated_at);
 
         let record = sqlx::query!(
             r#"
             UPDATE organizations
-            SET name = $2, updated_at = $3
+            SET name = $2, notification_period = $3, last_report_sent_at = $4, scan_scope = $5,
+                updated_at = $6
             WHERE id = $1
-            RETURNING id, name, created_at, updated_at
+            RETURNING
+                id, name, notification_period as "notification_period: NotificationPeriod",
+                last_report_sent_at, scan_scope, created_at, updated_at
             "#,
             organization.id,
             organization.name,
+            organization.notification_period as NotificationPeriod,
+            last_report_sent_at,
+            &organization.scan_scope,
             updated_at
         )
         .fetch_one(&self.pool)
@@ -92,6 +152,9 @@ impl OrganizationRepository for PgOrganizationRepository {
         Ok(Organization {
             id: record.id,
             name: record.name,
+            notification_period: record.notification_period,
+            last_report_sent_at: from_option_offset_datetime(record.last_report_sent_at),
+            scan_scope: record.scan_scope,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -118,7 +181,9 @@ impl OrganizationRepository for PgOrganizationRepository {
     async fn list_organizations(&self, limit: usize, offset: usize) -> Result<Vec<Organization>> {
         let records = sqlx::query!(
             r#"
-            SELECT id, name, created_at, updated_at
+            SELECT
+                id, name, notification_period as "notification_period: NotificationPeriod",
+                last_report_sent_at, scan_scope, created_at, updated_at
             FROM organizations
             ORDER BY name
             LIMIT $1 OFFSET $2
@@ -134,6 +199,9 @@ impl OrganizationRepository for PgOrganizationRepository {
             .map(|record| Organization {
                 id: record.id,
                 name: record.name,
+                notification_period: record.notification_period,
+                last_report_sent_at: from_option_offset_datetime(record.last_report_sent_at),
+                scan_scope: record.scan_scope,
                 created_at: from_offset_datetime(Some(
                     record.created_at.expect("created_at should not be null"),
                 )),
@@ -158,4 +226,22 @@ impl OrganizationRepository for PgOrganizationRepository {
 
         Ok(record.count.unwrap_or(0) as usize)
     }
+
+    async fn record_report_sent(&self, id: ID, sent_at: Timestamp) -> Result<bool> {
+        let sent_at = to_offset_datetime(sent_at);
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE organizations
+            SET last_report_sent_at = $2
+            WHERE id = $1
+            "#,
+            id,
+            sent_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
 }
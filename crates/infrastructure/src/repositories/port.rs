@@ -2,7 +2,12 @@ use crate::utils::{from_offset_datetime, to_offset_datetime};
 use async_trait::async_trait;
 use backend::{models::Port, traits::PortRepository, Result};
 use shared::types::{PortStatus, Protocol, ID};
-use sqlx::PgPool;
+use sqlx::{PgPool, Row};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// Rows per INSERT in `upsert_ports_bulk`, keeping the bind parameters of a
+/// single statement well under PostgreSQL's limit of 65535
+const UPSERT_BATCH_SIZE: usize = 1000;
 
 /// PostgreSQL implementation of the Port Repository
 pub struct PgPortRepository {
@@ -147,6 +152,78 @@ impl PortRepository for PgPortRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    async fn upsert_ports_bulk(&self, asset_id: ID, ports: &[Port]) -> Result<Vec<Port>> {
+        // A port may only appear once per INSERT ... ON CONFLICT, so later
+        // entries for the same port number and protocol win, keeping the
+        // position of the first
+        let mut positions: HashMap<(ID, i32, Protocol), usize> =
+            HashMap::with_capacity(ports.len());
+        let mut unique: Vec<&Port> = Vec::with_capacity(ports.len());
+        for port in ports {
+            match positions.entry((asset_id, port.port_number, port.protocol)) {
+                Entry::Occupied(position) => unique[*position.get()] = port,
+                Entry::Vacant(position) => {
+                    position.insert(unique.len());
+                    unique.push(port);
+                }
+            }
+        }
+
+        let mut upserted = Vec::with_capacity(unique.len());
+        let mut tx = self.pool.begin().await?;
+
+        for batch in unique.chunks(UPSERT_BATCH_SIZE) {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "INSERT INTO ports (id, asset_id, port_number, protocol, service_name, banner, status, first_seen, last_seen, created_at, updated_at) ",
+            );
+            query_builder.push_values(batch, |mut row, port| {
+                row.push_bind(port.id)
+                    .push_bind(asset_id)
+                    .push_bind(port.port_number)
+                    .push_bind(port.protocol as Protocol)
+                    .push_bind(&port.service_name)
+                    .push_bind(&port.banner)
+                    .push_bind(port.status as PortStatus)
+                    .push_bind(to_offset_datetime(port.first_seen))
+                    .push_bind(to_offset_datetime(port.last_seen))
+                    .push_bind(to_offset_datetime(port.created_at))
+                    .push_bind(to_offset_datetime(port.updated_at));
+            });
+            query_builder.push(
+                r#"
+                ON CONFLICT (asset_id, port_number, protocol) DO UPDATE
+                SET service_name = COALESCE(EXCLUDED.service_name, ports.service_name),
+                    banner = COALESCE(EXCLUDED.banner, ports.banner),
+                    status = EXCLUDED.status,
+                    last_seen = EXCLUDED.last_seen,
+                    updated_at = EXCLUDED.updated_at
+                RETURNING id, asset_id, port_number, protocol, service_name, banner, status, first_seen, last_seen, created_at, updated_at
+                "#,
+            );
+
+            let records = query_builder.build().fetch_all(&mut *tx).await?;
+            for row in records {
+                upserted.push(Port {
+                    id: row.try_get("id")?,
+                    asset_id: row.try_get("asset_id")?,
+                    port_number: row.try_get("port_number")?,
+                    protocol: row.try_get("protocol")?,
+                    service_name: row.try_get("service_name")?,
+                    banner: row.try_get("banner")?,
+                    status: row.try_get("status")?,
+                    first_seen: from_offset_datetime(Some(row.try_get("first_seen")?)),
+                    last_seen: from_offset_datetime(Some(row.try_get("last_seen")?)),
+                    created_at: from_offset_datetime(Some(row.try_get("created_at")?)),
+                    updated_at: from_offset_datetime(Some(row.try_get("updated_at")?)),
+                });
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(upserted)
+    }
+
     async fn list_ports(
         &self,
         asset_id: Option<ID>,
@@ -0,0 +1,226 @@
+use crate::utils::{from_offset_datetime, to_offset_datetime};
+use async_trait::async_trait;
+use backend::{models::ScanProfile, traits::ScanProfileRepository, Error, Result};
+use shared::types::{JobType, ID};
+use sqlx::PgPool;
+
+/// PostgreSQL implementation of the ScanProfile Repository
+pub struct PgScanProfileRepository {
+    pool: PgPool,
+}
+
+impl PgScanProfileRepository {
+    /// Create a new PgScanProfileRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Read back the job types stored in the `methods` column
+fn parse_methods(methods: serde_json::Value) -> Result<Vec<JobType>> {
+    serde_json::from_value(methods)
+        .map_err(|e| Error::Internal(format!("Invalid scan profile methods: {}", e)))
+}
+
+fn to_db_ports(ports: &[u16]) -> Vec<i32> {
+    ports.iter().map(|port| i32::from(*port)).collect()
+}
+
+fn from_db_ports(ports: Vec<i32>) -> Vec<u16> {
+    ports.into_iter().map(|port| port as u16).collect()
+}
+
+#[async_trait]
+impl ScanProfileRepository for PgScanProfileRepository {
+    async fn create_profile(&self, profile: &ScanProfile) -> Result<ScanProfile> {
+        let organization_id = profile.organization_id.ok_or_else(|| {
+            Error::Validation("Built-in scan profiles can't be stored".to_string())
+        })?;
+        let created_at = to_offset_datetime(profile.created_at);
+        let updated_at = to_offset_datetime(profile.updated_at);
+
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO scan_profiles (
+                id, organization_id, name, methods, depth, ports, timeout_secs,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                id, organization_id, name, methods, depth, ports, timeout_secs,
+                created_at, updated_at
+            "#,
+            profile.id,
+            organization_id,
+            profile.name,
+            serde_json::to_value(&profile.methods).unwrap_or_default(),
+            profile.depth as i32,
+            &to_db_ports(&profile.ports),
+            profile.timeout_secs as i32,
+            created_at,
+            updated_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ScanProfile {
+            id: record.id,
+            organization_id: Some(record.organization_id),
+            name: record.name,
+            methods: parse_methods(record.methods)?,
+            depth: record.depth as u32,
+            ports: from_db_ports(record.ports),
+            timeout_secs: record.timeout_secs as u32,
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn get_profile(&self, id: ID) -> Result<ScanProfile> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                id, organization_id, name, methods, depth, ports, timeout_secs,
+                created_at, updated_at
+            FROM scan_profiles
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ScanProfile {
+            id: record.id,
+            organization_id: Some(record.organization_id),
+            name: record.name,
+            methods: parse_methods(record.methods)?,
+            depth: record.depth as u32,
+            ports: from_db_ports(record.ports),
+            timeout_secs: record.timeout_secs as u32,
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn get_profile_by_name(
+        &self,
+        organization_id: ID,
+        name: &str,
+    ) -> Result<Option<ScanProfile>> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                id, organization_id, name, methods, depth, ports, timeout_secs,
+                created_at, updated_at
+            FROM scan_profiles
+            WHERE organization_id = $1 AND LOWER(name) = LOWER($2)
+            "#,
+            organization_id,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        record
+            .map(|record| {
+                Ok(ScanProfile {
+                    id: record.id,
+                    organization_id: Some(record.organization_id),
+                    name: record.name,
+                    methods: parse_methods(record.methods)?,
+                    depth: record.depth as u32,
+                    ports: from_db_ports(record.ports),
+                    timeout_secs: record.timeout_secs as u32,
+                    created_at: from_offset_datetime(Some(record.created_at)),
+                    updated_at: from_offset_datetime(Some(record.updated_at)),
+                })
+            })
+            .transpose()
+    }
+
+    async fn update_profile(&self, profile: &ScanProfile) -> Result<ScanProfile> {
+        let updated_at = to_offset_datetime(profile.updated_at);
+
+        let record = sqlx::query!(
+            r#"
+            UPDATE scan_profiles
+            SET
+                name = $2, methods = $3, depth = $4, ports = $5, timeout_secs = $6,
+                updated_at = $7
+            WHERE id = $1
+            RETURNING
+                id, organization_id, name, methods, depth, ports, timeout_secs,
+                created_at, updated_at
+            "#,
+            profile.id,
+            profile.name,
+            serde_json::to_value(&profile.methods).unwrap_or_default(),
+            profile.depth as i32,
+            &to_db_ports(&profile.ports),
+            profile.timeout_secs as i32,
+            updated_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ScanProfile {
+            id: record.id,
+            organization_id: Some(record.organization_id),
+            name: record.name,
+            methods: parse_methods(record.methods)?,
+            depth: record.depth as u32,
+            ports: from_db_ports(record.ports),
+            timeout_secs: record.timeout_secs as u32,
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn delete_profile(&self, id: ID) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM scan_profiles
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_profiles(&self, organization_id: ID) -> Result<Vec<ScanProfile>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                id, organization_id, name, methods, depth, ports, timeout_secs,
+                created_at, updated_at
+            FROM scan_profiles
+            WHERE organization_id = $1
+            ORDER BY LOWER(name)
+            "#,
+            organization_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                Ok(ScanProfile {
+                    id: record.id,
+                    organization_id: Some(record.organization_id),
+                    name: record.name,
+                    methods: parse_methods(record.methods)?,
+                    depth: record.depth as u32,
+                    ports: from_db_ports(record.ports),
+                    timeout_secs: record.timeout_secs as u32,
+                    created_at: from_offset_datetime(Some(record.created_at)),
+                    updated_at: from_offset_datetime(Some(record.updated_at)),
+                })
+            })
+            .collect()
+    }
+}
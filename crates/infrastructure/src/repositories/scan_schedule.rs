@@ -0,0 +1,230 @@
+use crate::utils::{
+    from_offset_datetime, from_option_offset_datetime, to_offset_datetime,
+    to_option_offset_datetime,
+};
+use async_trait::async_trait;
+use backend::{models::ScanSchedule, traits::ScanScheduleRepository, Result};
+use shared::types::{JobType, ID};
+use sqlx::PgPool;
+
+/// PostgreSQL implementation of the ScanSchedule Repository
+pub struct PgScanScheduleRepository {
+    pool: PgPool,
+}
+
+impl PgScanScheduleRepository {
+    /// Create a new PgScanScheduleRepository instance
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ScanScheduleRepository for PgScanScheduleRepository {
+    async fn create_schedule(&self, schedule: &ScanSchedule) -> Result<ScanSchedule> {
+        let last_run_at = to_option_offset_datetime(schedule.last_run_at);
+        let created_at = to_offset_datetime(schedule.created_at);
+        let updated_at = to_offset_datetime(schedule.updated_at);
+
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO scan_schedules (
+                id, organization_id, target, job_type, cron_expression,
+                enabled, last_run_at, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                id, organization_id, target, job_type as "job_type: JobType", cron_expression,
+                enabled, last_run_at, created_at, updated_at
+            "#,
+            schedule.id,
+            schedule.organization_id,
+            schedule.target,
+            schedule.job_type as JobType,
+            schedule.cron_expression,
+            schedule.enabled,
+            last_run_at,
+            created_at,
+            updated_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ScanSchedule {
+            id: record.id,
+            organization_id: record.organization_id,
+            target: record.target,
+            job_type: record.job_type,
+            cron_expression: record.cron_expression,
+            enabled: record.enabled,
+            last_run_at: from_option_offset_datetime(record.last_run_at),
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn get_schedule(&self, id: ID) -> Result<ScanSchedule> {
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                id, organization_id, target, job_type as "job_type: JobType", cron_expression,
+                enabled, last_run_at, created_at, updated_at
+            FROM scan_schedules
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ScanSchedule {
+            id: record.id,
+            organization_id: record.organization_id,
+            target: record.target,
+            job_type: record.job_type,
+            cron_expression: record.cron_expression,
+            enabled: record.enabled,
+            last_run_at: from_option_offset_datetime(record.last_run_at),
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn update_schedule(&self, schedule: &ScanSchedule) -> Result<ScanSchedule> {
+        let last_run_at = to_option_offset_datetime(schedule.last_run_at);
+        let updated_at = to_offset_datetime(schedule.updated_at);
+
+        let record = sqlx::query!(
+            r#"
+            UPDATE scan_schedules
+            SET
+                organization_id = $2, target = $3, job_type = $4, cron_expression = $5,
+                enabled = $6, last_run_at = $7, updated_at = $8
+            WHERE id = $1
+            RETURNING
+                id, organization_id, target, job_type as "job_type: JobType", cron_expression,
+                enabled, last_run_at, created_at, updated_at
+            "#,
+            schedule.id,
+            schedule.organization_id,
+            schedule.target,
+            schedule.job_type as JobType,
+            schedule.cron_expression,
+            schedule.enabled,
+            last_run_at,
+            updated_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ScanSchedule {
+            id: record.id,
+            organization_id: record.organization_id,
+            target: record.target,
+            job_type: record.job_type,
+            cron_expression: record.cron_expression,
+            enabled: record.enabled,
+            last_run_at: from_option_offset_datetime(record.last_run_at),
+            created_at: from_offset_datetime(Some(record.created_at)),
+            updated_at: from_offset_datetime(Some(record.updated_at)),
+        })
+    }
+
+    async fn delete_schedule(&self, id: ID) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM scan_schedules
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_schedules(
+        &self,
+        organization_id: Option<ID>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<ScanSchedule>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                id, organization_id, target, job_type as "job_type: JobType", cron_expression,
+                enabled, last_run_at, created_at, updated_at
+            FROM scan_schedules
+            WHERE ($1::uuid IS NULL OR organization_id = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            organization_id,
+            limit as i64,
+            offset as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| ScanSchedule {
+                id: record.id,
+                organization_id: record.organization_id,
+                target: record.target,
+                job_type: record.job_type,
+                cron_expression: record.cron_expression,
+                enabled: record.enabled,
+                last_run_at: from_option_offset_datetime(record.last_run_at),
+                created_at: from_offset_datetime(Some(record.created_at)),
+                updated_at: from_offset_datetime(Some(record.updated_at)),
+            })
+            .collect())
+    }
+
+    async fn count_schedules(&self, organization_id: Option<ID>) -> Result<usize> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as count
+            FROM scan_schedules
+            WHERE ($1::uuid IS NULL OR organization_id = $1)
+            "#,
+            organization_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.unwrap_or(0) as usize)
+    }
+
+    async fn list_enabled_schedules(&self) -> Result<Vec<ScanSchedule>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                id, organization_id, target, job_type as "job_type: JobType", cron_expression,
+                enabled, last_run_at, created_at, updated_at
+            FROM scan_schedules
+            WHERE enabled = TRUE
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| ScanSchedule {
+                id: record.id,
+                organization_id: record.organization_id,
+                target: record.target,
+                job_type: record.job_type,
+                cron_expression: record.cron_expression,
+                enabled: record.enabled,
+                last_run_at: from_option_offset_datetime(record.last_run_at),
+                created_at: from_offset_datetime(Some(record.created_at)),
+                updated_at: from_offset_datetime(Some(record.updated_at)),
+            })
+            .collect())
+    }
+}
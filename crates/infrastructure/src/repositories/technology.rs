@@ -1,8 +1,13 @@
 use crate::utils::{from_offset_datetime, to_offset_datetime};
 use async_trait::async_trait;
-use backend::{models::Technology, traits::TechnologyRepository, Result};
-use shared::types::ID;
-use sqlx::PgPool;
+use backend::{
+    models::{DetectedTechnology, Technology, TechnologyCount, TechnologyDistribution},
+    traits::TechnologyRepository,
+    Result,
+};
+use shared::types::{Page, ID};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
 
 /// PostgreSQL implementation of the Technology Repository
 pub struct PgTechnologyRepository {
@@ -20,13 +25,14 @@ impl PgTechnologyRepository {
 impl TechnologyRepository for PgTechnologyRepository {
     async fn create_technology(&self, technology: &Technology) -> Result<Technology> {
         let created_at = to_offset_datetime(technology.created_at);
+        let last_seen = to_offset_datetime(technology.last_seen);
         let updated_at = to_offset_datetime(technology.updated_at);
 
         let record = sqlx::query!(
             r#"
             INSERT INTO technologies (id, asset_id, name, version, category, first_seen, last_seen, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING id, asset_id, name, version, category, created_at, updated_at
+            RETURNING id, asset_id, name, version, category, last_seen, created_at, updated_at
             "#,
             technology.id,
             technology.asset_id,
@@ -34,7 +40,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             technology.version,
             technology.category,
             created_at, // first_seen
-            created_at, // last_seen
+            last_seen,
             created_at,
             updated_at
         )
@@ -47,15 +53,23 @@ impl TechnologyRepository for PgTechnologyRepository {
             name: record.name,
             version: record.version,
             category: record.category,
+            last_seen: from_offset_datetime(Some(record.last_seen)),
             created_at: from_offset_datetime(Some(record.created_at)),
             updated_at: from_offset_datetime(Some(record.updated_at)),
         })
     }
 
+    async fn upsert_technology(&self, technology: &Technology) -> Result<Technology> {
+        let mut tx = self.pool.begin().await?;
+        let technology = upsert_technology(&mut tx, technology).await?;
+        tx.commit().await?;
+        Ok(technology)
+    }
+
     async fn get_technology(&self, id: ID) -> Result<Technology> {
         let record = sqlx::query!(
             r#"
-            SELECT id, asset_id, name, version, category, created_at, updated_at
+            SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
             FROM technologies
             WHERE id = $1
             "#,
@@ -70,6 +84,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             name: record.name,
             version: record.version,
             category: record.category,
+            last_seen: from_offset_datetime(Some(record.last_seen)),
             created_at: from_offset_datetime(Some(record.created_at)),
             updated_at: from_offset_datetime(Some(record.updated_at)),
         })
@@ -84,7 +99,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             UPDATE technologies
             SET asset_id = $2, name = $3, version = $4, category = $5, last_seen = $6, updated_at = $7
             WHERE id = $1
-            RETURNING id, asset_id, name, version, category, created_at, updated_at
+            RETURNING id, asset_id, name, version, category, last_seen, created_at, updated_at
             "#,
             technology.id,
             technology.asset_id,
@@ -103,6 +118,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             name: record.name,
             version: record.version,
             category: record.category,
+            last_seen: from_offset_datetime(Some(record.last_seen)),
             created_at: from_offset_datetime(Some(record.created_at)),
             updated_at: from_offset_datetime(Some(record.updated_at)),
         })
@@ -134,7 +150,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             // First, try to find technologies by a direct asset ID query
             let asset_records = sqlx::query!(
                 r#"
-                SELECT id, asset_id, name, version, category, created_at, updated_at
+                SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
                 FROM technologies
                 WHERE asset_id = $1
                 ORDER BY name
@@ -152,7 +168,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                 // Use a JOIN to find all technologies for assets within this organization
                 let org_records = sqlx::query!(
                     r#"
-                    SELECT t.id, t.asset_id, t.name, t.version, t.category, t.created_at, t.updated_at
+                    SELECT t.id, t.asset_id, t.name, t.version, t.category, t.last_seen, t.created_at, t.updated_at
                     FROM technologies t
                     JOIN assets a ON t.asset_id = a.id
                     WHERE a.organization_id = $1
@@ -174,6 +190,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                         name: record.name,
                         version: record.version,
                         category: record.category,
+                        last_seen: from_offset_datetime(Some(record.last_seen)),
                         created_at: from_offset_datetime(Some(record.created_at)),
                         updated_at: from_offset_datetime(Some(record.updated_at)),
                     })
@@ -188,6 +205,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                         name: record.name,
                         version: record.version,
                         category: record.category,
+                        last_seen: from_offset_datetime(Some(record.last_seen)),
                         created_at: from_offset_datetime(Some(record.created_at)),
                         updated_at: from_offset_datetime(Some(record.updated_at)),
                     })
@@ -198,7 +216,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                 // Both name and category filter
                 let records = sqlx::query!(
                     r#"
-                    SELECT id, asset_id, name, version, category, created_at, updated_at
+                    SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
                     FROM technologies
                     WHERE name ILIKE $1 AND category ILIKE $2
                     ORDER BY name
@@ -220,6 +238,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                         name: record.name,
                         version: record.version,
                         category: record.category,
+                        last_seen: from_offset_datetime(Some(record.last_seen)),
                         created_at: from_offset_datetime(Some(record.created_at)),
                         updated_at: from_offset_datetime(Some(record.updated_at)),
                     })
@@ -228,7 +247,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                 // Only name filter
                 let records = sqlx::query!(
                     r#"
-                    SELECT id, asset_id, name, version, category, created_at, updated_at
+                    SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
                     FROM technologies
                     WHERE name ILIKE $1
                     ORDER BY name
@@ -249,6 +268,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                         name: record.name,
                         version: record.version,
                         category: record.category,
+                        last_seen: from_offset_datetime(Some(record.last_seen)),
                         created_at: from_offset_datetime(Some(record.created_at)),
                         updated_at: from_offset_datetime(Some(record.updated_at)),
                     })
@@ -258,7 +278,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             // Only category filter
             let records = sqlx::query!(
                 r#"
-                SELECT id, asset_id, name, version, category, created_at, updated_at
+                SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
                 FROM technologies
                 WHERE category ILIKE $1
                 ORDER BY name
@@ -279,6 +299,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                     name: record.name,
                     version: record.version,
                     category: record.category,
+                    last_seen: from_offset_datetime(Some(record.last_seen)),
                     created_at: from_offset_datetime(Some(record.created_at)),
                     updated_at: from_offset_datetime(Some(record.updated_at)),
                 })
@@ -287,7 +308,7 @@ impl TechnologyRepository for PgTechnologyRepository {
             // No filters
             let records = sqlx::query!(
                 r#"
-                SELECT id, asset_id, name, version, category, created_at, updated_at
+                SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
                 FROM technologies
                 ORDER BY name
                 LIMIT $1 OFFSET $2
@@ -306,6 +327,7 @@ impl TechnologyRepository for PgTechnologyRepository {
                     name: record.name,
                     version: record.version,
                     category: record.category,
+                    last_seen: from_offset_datetime(Some(record.last_seen)),
                     created_at: from_offset_datetime(Some(record.created_at)),
                     updated_at: from_offset_datetime(Some(record.updated_at)),
                 })
@@ -315,6 +337,42 @@ impl TechnologyRepository for PgTechnologyRepository {
         Ok(technologies)
     }
 
+    async fn list_asset_technologies(
+        &self,
+        asset_id: ID,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Technology>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT id, asset_id, name, version, category, last_seen, created_at, updated_at
+            FROM technologies
+            WHERE asset_id = $1
+            ORDER BY name
+            LIMIT $2 OFFSET $3
+            "#,
+            asset_id,
+            limit as i64,
+            offset as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| Technology {
+                id: record.id,
+                asset_id: record.asset_id,
+                name: record.name,
+                version: record.version,
+                category: record.category,
+                last_seen: from_offset_datetime(Some(record.last_seen)),
+                created_at: from_offset_datetime(Some(record.created_at)),
+                updated_at: from_offset_datetime(Some(record.updated_at)),
+            })
+            .collect())
+    }
+
     async fn count_technologies(
         &self,
         asset_id: Option<ID>,
@@ -430,4 +488,227 @@ impl TechnologyRepository for PgTechnologyRepository {
 
         Ok(count.unwrap_or(0) as usize)
     }
+
+    async fn count_organization_technologies(&self, organization_id: ID) -> Result<usize> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(DISTINCT LOWER(t.name)) as count
+            FROM technologies t
+            JOIN assets a ON t.asset_id = a.id
+            WHERE a.organization_id = $1 AND a.deleted_at IS NULL
+            "#,
+            organization_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.unwrap_or(0) as usize)
+    }
+
+    async fn list_organization_technologies(
+        &self,
+        organization_id: ID,
+        search: Option<String>,
+        category: Option<String>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<DetectedTechnology>> {
+        // The window function counts every matching row before LIMIT applies
+        let records = sqlx::query!(
+            r#"
+            SELECT t.id, t.asset_id, t.name, t.version, t.category, t.last_seen, t.created_at, t.updated_at,
+                a.value AS asset_value, COUNT(*) OVER() AS "total!"
+            FROM technologies t
+            JOIN assets a ON t.asset_id = a.id
+            WHERE a.organization_id = $1 AND a.deleted_at IS NULL
+                AND ($2::text IS NULL OR t.name ILIKE '%' || $2 || '%')
+                AND ($3::text IS NULL OR LOWER(t.category) = LOWER($3))
+            ORDER BY LOWER(t.name), a.value, t.id
+            LIMIT $4 OFFSET $5
+            "#,
+            organization_id,
+            search.clone(),
+            category.clone(),
+            limit as i64,
+            offset as i64
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // A page past the end has no rows to carry the total
+        let total = match records.first() {
+            Some(record) => record.total as usize,
+            None if offset > 0 => {
+                sqlx::query_scalar!(
+                    r#"
+                SELECT COUNT(*) AS "count!"
+                FROM technologies t
+                JOIN assets a ON t.asset_id = a.id
+                WHERE a.organization_id = $1 AND a.deleted_at IS NULL
+                    AND ($2::text IS NULL OR t.name ILIKE '%' || $2 || '%')
+                    AND ($3::text IS NULL OR LOWER(t.category) = LOWER($3))
+                "#,
+                    organization_id,
+                    search,
+                    category
+                )
+                .fetch_one(&self.pool)
+                .await? as usize
+            }
+            None => 0,
+        };
+
+        let items = records
+            .into_iter()
+            .map(|record| DetectedTechnology {
+                technology: Technology {
+                    id: record.id,
+                    asset_id: record.asset_id,
+                    name: record.name,
+                    version: record.version,
+                    category: record.category,
+                    last_seen: from_offset_datetime(Some(record.last_seen)),
+                    created_at: from_offset_datetime(Some(record.created_at)),
+                    updated_at: from_offset_datetime(Some(record.updated_at)),
+                },
+                asset_value: record.asset_value,
+            })
+            .collect();
+
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    async fn technology_distribution(&self, organization_id: ID) -> Result<TechnologyDistribution> {
+        let by_name = sqlx::query!(
+            r#"
+            SELECT MIN(t.name) AS "name!", COUNT(*) AS "count!"
+            FROM technologies t
+            JOIN assets a ON t.asset_id = a.id
+            WHERE a.organization_id = $1 AND a.deleted_at IS NULL
+            GROUP BY LOWER(t.name)
+            ORDER BY COUNT(*) DESC, MIN(t.name)
+            "#,
+            organization_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| TechnologyCount {
+            name: row.name,
+            count: row.count as usize,
+        })
+        .collect();
+
+        let by_category = sqlx::query!(
+            r#"
+            SELECT COALESCE(t.category, 'Uncategorized') AS "name!", COUNT(*) AS "count!"
+            FROM technologies t
+            JOIN assets a ON t.asset_id = a.id
+            WHERE a.organization_id = $1 AND a.deleted_at IS NULL
+            GROUP BY 1
+            ORDER BY 2 DESC, 1
+            "#,
+            organization_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| TechnologyCount {
+            name: row.name,
+            count: row.count as usize,
+        })
+        .collect();
+
+        Ok(TechnologyDistribution {
+            by_name,
+            by_category,
+        })
+    }
+}
+
+/// Insert a technology, or update the one with the same name on the asset.
+/// A detection without a version or category keeps the stored one. When the
+/// version changes the old and new versions are recorded in the asset's
+/// history.
+pub(crate) async fn upsert_technology(
+    conn: &mut PgConnection,
+    technology: &Technology,
+) -> Result<Technology> {
+    let last_seen = to_offset_datetime(technology.last_seen);
+
+    // Lock the existing row so concurrent scans agree on the version it
+    // changed from
+    let previous_version = sqlx::query_scalar!(
+        r#"
+        SELECT version
+        FROM technologies
+        WHERE asset_id = $1 AND name = $2
+        FOR UPDATE
+        "#,
+        technology.asset_id,
+        technology.name
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO technologies (
+            id, asset_id, name, version, category,
+            first_seen, last_seen, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8)
+        ON CONFLICT (asset_id, name) DO UPDATE
+        SET version = COALESCE(EXCLUDED.version, technologies.version),
+            category = COALESCE(EXCLUDED.category, technologies.category),
+            last_seen = GREATEST(technologies.last_seen, EXCLUDED.last_seen),
+            updated_at = EXCLUDED.updated_at
+        RETURNING id, asset_id, name, version, category, last_seen, created_at, updated_at
+        "#,
+        technology.id,
+        technology.asset_id,
+        technology.name,
+        technology.version,
+        technology.category,
+        last_seen,
+        to_offset_datetime(technology.created_at),
+        to_offset_datetime(technology.updated_at)
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if let Some(previous_version) = previous_version {
+        if previous_version != record.version {
+            sqlx::query!(
+                r#"
+                INSERT INTO asset_history (id, asset_id, field, old_value, new_value, changed_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+                Uuid::new_v4(),
+                record.asset_id,
+                format!("technologies.{}.version", record.name),
+                previous_version.map(serde_json::Value::from),
+                record.version.clone().map(serde_json::Value::from),
+                last_seen
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(Technology {
+        id: record.id,
+        asset_id: record.asset_id,
+        name: record.name,
+        version: record.version,
+        category: record.category,
+        last_seen: from_offset_datetime(Some(record.last_seen)),
+        created_at: from_offset_datetime(Some(record.created_at)),
+        updated_at: from_offset_datetime(Some(record.updated_at)),
+    })
 }
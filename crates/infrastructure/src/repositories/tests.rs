@@ -1,4 +1,4 @@
-use backend::models::{Asset, Organization, User};
+use backend::models::{Asset, AssetFilter, Organization, User};
 use shared::types::{AssetStatus, AssetType, UserRole};
 use sqlx::PgPool;
 
@@ -32,6 +32,18 @@ async fn test_organization_repository(pool: PgPool) -> sqlx::Result<()> {
     assert_eq!(fetched_org.id, created_org.id);
     assert_eq!(fetched_org.name, created_org.name);
 
+    // Get organization by name
+    let by_name = org_repo
+        .get_organization_by_name("Test Organization")
+        .await
+        .unwrap();
+    assert_eq!(by_name.map(|org| org.id), Some(created_org.id));
+    assert!(org_repo
+        .get_organization_by_name("Missing Organization")
+        .await
+        .unwrap()
+        .is_none());
+
     // Update organization
     let mut updated_org = fetched_org.clone();
     updated_org.name = "Updated Organization".to_string();
@@ -99,9 +111,12 @@ async fn test_user_repository(pool: PgPool) -> sqlx::Result<()> {
 
     // Update user
     let mut updated_user = fetched_user.clone();
+    assert!(updated_user.active);
     updated_user.username = "updateduser".to_string();
+    updated_user.active = false;
     let updated_user = user_repo.update_user(&updated_user).await.unwrap();
     assert_eq!(updated_user.username, "updateduser");
+    assert!(!updated_user.active);
 
     // List users
     let users = user_repo
@@ -168,9 +183,12 @@ async fn test_asset_repository(pool: PgPool) -> sqlx::Result<()> {
     // List assets
     let assets = asset_repo
         .list_assets(
-            Some(created_org.id),
-            Some(AssetType::Domain),
-            Some(AssetStatus::Active),
+            &AssetFilter {
+                organization_id: Some(created_org.id),
+                asset_type: Some(AssetType::Domain),
+                status: Some(AssetStatus::Active),
+                ..Default::default()
+            },
             10,
             0,
         )
@@ -180,11 +198,12 @@ async fn test_asset_repository(pool: PgPool) -> sqlx::Result<()> {
 
     // Count assets
     let count = asset_repo
-        .count_assets(
-            Some(created_org.id),
-            Some(AssetType::Domain),
-            Some(AssetStatus::Active),
-        )
+        .count_assets(&AssetFilter {
+            organization_id: Some(created_org.id),
+            asset_type: Some(AssetType::Domain),
+            status: Some(AssetStatus::Active),
+            ..Default::default()
+        })
         .await
         .unwrap();
     assert!(count > 0);
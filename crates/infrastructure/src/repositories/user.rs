@@ -25,9 +25,9 @@ impl UserRepository for PgUserRepository {
 
         let record = sqlx::query!(
             r#"
-            INSERT INTO users (id, organization_id, username, email, role, password_hash, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            INSERT INTO users (id, organization_id, username, email, role, password_hash, active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             "#,
             user.id,
             user.organization_id,
@@ -35,6 +35,7 @@ impl UserRepository for PgUserRepository {
             user.email,
             user.role as UserRole,
             user.password_hash,
+            user.active,
             created_at,
             updated_at
         )
@@ -51,6 +52,7 @@ impl UserRepository for PgUserRepository {
             email: record.email,
             role: record.role.expect("role should not be null"),
             password_hash: record.password_hash,
+            active: record.active,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -63,7 +65,7 @@ impl UserRepository for PgUserRepository {
     async fn get_user(&self, id: ID) -> Result<User> {
         let record = sqlx::query!(
             r#"
-            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             FROM users
             WHERE id = $1
             "#,
@@ -82,6 +84,7 @@ impl UserRepository for PgUserRepository {
             email: record.email,
             role: record.role.expect("role should not be null"),
             password_hash: record.password_hash,
+            active: record.active,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -94,7 +97,7 @@ impl UserRepository for PgUserRepository {
     async fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
         let record = sqlx::query!(
             r#"
-            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             FROM users
             WHERE username = $1
             "#,
@@ -114,6 +117,7 @@ impl UserRepository for PgUserRepository {
                 email: r.email,
                 role: r.role.expect("role should not be null"),
                 password_hash: r.password_hash,
+                active: r.active,
                 created_at: from_offset_datetime(Some(
                     r.created_at.expect("created_at should not be null"),
                 )),
@@ -127,7 +131,7 @@ impl UserRepository for PgUserRepository {
     async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
         let record = sqlx::query!(
             r#"
-            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             FROM users
             WHERE email = $1
             "#,
@@ -147,6 +151,7 @@ impl UserRepository for PgUserRepository {
                 email: r.email,
                 role: r.role.expect("role should not be null"),
                 password_hash: r.password_hash,
+                active: r.active,
                 created_at: from_offset_datetime(Some(
                     r.created_at.expect("created_at should not be null"),
                 )),
@@ -164,9 +169,9 @@ impl UserRepository for PgUserRepository {
         let record = sqlx::query!(
             r#"
             UPDATE users
-            SET organization_id = $2, username = $3, email = $4, role = $5, password_hash = $6, updated_at = $7
+            SET organization_id = $2, username = $3, email = $4, role = $5, password_hash = $6, active = $7, updated_at = $8
             WHERE id = $1
-            RETURNING id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            RETURNING id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             "#,
             user.id,
             user.organization_id,
@@ -174,6 +179,7 @@ impl UserRepository for PgUserRepository {
             user.email,
             user.role as UserRole,
             user.password_hash,
+            user.active,
             updated_at
         )
         .fetch_one(&self.pool)
@@ -189,6 +195,7 @@ impl UserRepository for PgUserRepository {
             email: record.email,
             role: record.role.expect("role should not be null"),
             password_hash: record.password_hash,
+            active: record.active,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -221,7 +228,7 @@ impl UserRepository for PgUserRepository {
     ) -> Result<Vec<User>> {
         // Use QueryBuilder to dynamically build the query
         let mut query_builder = sqlx::QueryBuilder::new(
-            "SELECT id, organization_id, username, email, role, password_hash, created_at, updated_at FROM users WHERE 1=1"
+            "SELECT id, organization_id, username, email, role, password_hash, active, created_at, updated_at FROM users WHERE 1=1"
         );
 
         // Add filters conditionally
@@ -250,6 +257,7 @@ impl UserRepository for PgUserRepository {
             String,
             UserRole,
             String,
+            bool,
             Option<sqlx::types::time::OffsetDateTime>,
             Option<sqlx::types::time::OffsetDateTime>,
         )>();
@@ -267,6 +275,7 @@ impl UserRepository for PgUserRepository {
                     email,
                     role,
                     password_hash,
+                    active,
                     created_at,
                     updated_at,
                 ) = record;
@@ -277,6 +286,7 @@ impl UserRepository for PgUserRepository {
                     email,
                     role,
                     password_hash,
+                    active,
                     created_at: from_offset_datetime(created_at),
                     updated_at: from_offset_datetime(updated_at),
                 }
@@ -319,7 +329,7 @@ impl UserRepository for PgUserRepository {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
         let record = sqlx::query!(
             r#"
-            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            SELECT id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             FROM users
             WHERE email = $1
             "#,
@@ -337,6 +347,7 @@ impl UserRepository for PgUserRepository {
             email: r.email,
             role: r.role.expect("role should not be null"),
             password_hash: r.password_hash,
+            active: r.active,
             created_at: from_offset_datetime(Some(
                 r.created_at.expect("created_at should not be null"),
             )),
@@ -374,9 +385,9 @@ impl UserRepository for PgUserRepository {
         // Insert the user
         let record = sqlx::query!(
             r#"
-            INSERT INTO users (id, organization_id, username, email, role, password_hash, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, organization_id, username, email, role as "role: UserRole", password_hash, created_at, updated_at
+            INSERT INTO users (id, organization_id, username, email, role, password_hash, active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, organization_id, username, email, role as "role: UserRole", password_hash, active, created_at, updated_at
             "#,
             user.id,
             user.organization_id,
@@ -384,6 +395,7 @@ impl UserRepository for PgUserRepository {
             user.email,
             user.role as UserRole,
             user.password_hash,
+            user.active,
             created_at,
             updated_at
         )
@@ -403,6 +415,7 @@ impl UserRepository for PgUserRepository {
             email: record.email,
             role: record.role.expect("role should not be null"),
             password_hash: record.password_hash,
+            active: record.active,
             created_at: from_offset_datetime(Some(
                 record.created_at.expect("created_at should not be null"),
             )),
@@ -1,11 +1,22 @@
-use crate::utils::{
-    from_offset_datetime, from_option_bigdecimal, from_option_offset_datetime, to_offset_datetime,
-    to_option_bigdecimal, to_option_offset_datetime,
+use crate::{
+    events::publish_resource_event,
+    utils::{
+        from_offset_datetime, from_option_bigdecimal, from_option_offset_datetime, sort_direction,
+        to_offset_datetime, to_option_bigdecimal, to_option_offset_datetime,
+    },
 };
 use async_trait::async_trait;
-use backend::{models::Vulnerability, traits::VulnerabilityRepository, Result};
-use shared::types::{Severity, VulnerabilityStatus, ID};
+use backend::{
+    models::{Vulnerability, VulnerabilityActivity, VulnerabilityGroup},
+    traits::VulnerabilityRepository,
+    Result,
+};
+use shared::types::{
+    Page, ResourceEventType, ResourceKind, Severity, Sort, VulnerabilitySortField,
+    VulnerabilityStatus, ID,
+};
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 
 /// PostgreSQL implementation of the Vulnerability Repository
 pub struct PgVulnerabilityRepository {
@@ -33,12 +44,39 @@ impl PgVulnerabilityRepository {
     fn status_to_string(status: VulnerabilityStatus) -> &'static str {
         match status {
             VulnerabilityStatus::Open => "OPEN",
+            VulnerabilityStatus::InProgress => "INPROGRESS",
             VulnerabilityStatus::Closed => "CLOSED",
             VulnerabilityStatus::AcceptedRisk => "ACCEPTEDRISK",
             VulnerabilityStatus::FalsePositive => "FALSEPOSITIVE",
         }
     }
 
+    /// ORDER BY terms for `sort`, with columns qualified by `prefix`. Each
+    /// field maps to a fixed expression so nothing from the request reaches
+    /// the SQL, and the ID comes last so rows that tie keep their order
+    /// between pages.
+    fn order_by(sort: Option<Sort<VulnerabilitySortField>>, prefix: &str) -> String {
+        let Some(sort) = sort else {
+            return format!("{p}severity, {p}title, {p}id", p = prefix);
+        };
+
+        let expression = match sort.field {
+            VulnerabilitySortField::Severity => format!(
+                "CASE {}severity WHEN 'CRITICAL' THEN 5 WHEN 'HIGH' THEN 4 \
+                 WHEN 'MEDIUM' THEN 3 WHEN 'LOW' THEN 2 ELSE 1 END",
+                prefix
+            ),
+            VulnerabilitySortField::Status => format!("{}status", prefix),
+            VulnerabilitySortField::CreatedAt => format!("{}created_at", prefix),
+        };
+        format!(
+            "{e} {d}, {p}title, {p}id",
+            e = expression,
+            d = sort_direction(sort.order),
+            p = prefix
+        )
+    }
+
     /// Helper to apply vulnerability filters to SQL query
     fn apply_filters(
         query: &mut String,
@@ -46,6 +84,7 @@ impl PgVulnerabilityRepository {
         port_id: Option<ID>,
         severity: Option<Severity>,
         status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
         is_org_query: bool,
     ) {
         // Add asset_id filter if provided
@@ -92,6 +131,15 @@ impl PgVulnerabilityRepository {
             let status_str = Self::status_to_string(st);
             query.push_str(&format!("{}{}'", prefix, status_str));
         }
+
+        // Hide soft-deleted vulnerabilities unless asked for
+        if !include_deleted {
+            query.push_str(if is_org_query {
+                " AND v.deleted_at IS NULL"
+            } else {
+                " AND deleted_at IS NULL"
+            });
+        }
     }
 }
 
@@ -119,7 +167,8 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 id, asset_id, port_id, title, description, 
                 severity, status,
                 cve_id, cvss_score, evidence, remediation, first_seen, last_seen,
-                resolved_at, created_at, updated_at
+                resolved_at, created_at, updated_at,
+                (SELECT organization_id FROM assets WHERE id = asset_id) AS organization_id
         "#;
 
         let row = sqlx::query(query)
@@ -159,6 +208,14 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
             updated_at: from_offset_datetime(row.get("updated_at")),
         };
 
+        publish_resource_event(
+            &self.pool,
+            ResourceEventType::Created,
+            row.get("organization_id"),
+            ResourceKind::Vulnerability,
+        )
+        .await;
+
         Ok(vulnerability)
     }
 
@@ -170,7 +227,7 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 cve_id, cvss_score, evidence, remediation, first_seen, last_seen,
                 resolved_at, created_at, updated_at
             FROM vulnerabilities
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
         "#;
 
         let row_result = sqlx::query(query)
@@ -239,7 +296,8 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 id, asset_id, port_id, title, description, 
                 severity, status,
                 cve_id, cvss_score, evidence, remediation, first_seen, last_seen,
-                resolved_at, created_at, updated_at
+                resolved_at, created_at, updated_at,
+                (SELECT organization_id FROM assets WHERE id = asset_id) AS organization_id
         "#;
 
         let row = sqlx::query(query)
@@ -279,13 +337,41 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
             updated_at: from_offset_datetime(row.get("updated_at")),
         };
 
+        publish_resource_event(
+            &self.pool,
+            ResourceEventType::Updated,
+            row.get("organization_id"),
+            ResourceKind::Vulnerability,
+        )
+        .await;
+
         Ok(updated_vulnerability)
     }
 
     async fn delete_vulnerability(&self, id: ID) -> Result<bool> {
-        let result = sqlx::query!("DELETE FROM vulnerabilities WHERE id = $1", id)
-            .execute(&self.pool)
-            .await?;
+        let now = to_offset_datetime(chrono::Utc::now());
+
+        let result = sqlx::query!(
+            "UPDATE vulnerabilities SET deleted_at = $2, updated_at = $2 WHERE id = $1 AND deleted_at IS NULL",
+            id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn restore_vulnerability(&self, id: ID) -> Result<bool> {
+        let now = to_offset_datetime(chrono::Utc::now());
+
+        let result = sqlx::query!(
+            "UPDATE vulnerabilities SET deleted_at = NULL, updated_at = $2 WHERE id = $1 AND deleted_at IS NOT NULL",
+            id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
 
         Ok(result.rows_affected() > 0)
     }
@@ -296,6 +382,31 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
         port_id: Option<ID>,
         severity: Option<Severity>,
         status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Vulnerability>> {
+        self.list_vulnerabilities_sorted(
+            asset_id,
+            port_id,
+            severity,
+            status,
+            include_deleted,
+            None,
+            limit,
+            offset,
+        )
+        .await
+    }
+
+    async fn list_vulnerabilities_sorted(
+        &self,
+        asset_id: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
+        sort: Option<Sort<VulnerabilitySortField>>,
         limit: usize,
         offset: usize,
     ) -> Result<Vec<Vulnerability>> {
@@ -311,12 +422,22 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 FROM vulnerabilities",
             );
 
-            Self::apply_filters(&mut query, Some(id), port_id, severity, status, false);
+            Self::apply_filters(
+                &mut query,
+                Some(id),
+                port_id,
+                severity,
+                status,
+                include_deleted,
+                false,
+            );
 
             // Add order by and limit/offset
             query.push_str(&format!(
-                " ORDER BY severity, title LIMIT {} OFFSET {}",
-                limit, offset
+                " ORDER BY {} LIMIT {} OFFSET {}",
+                Self::order_by(sort, ""),
+                limit,
+                offset
             ));
 
             // Execute query
@@ -376,10 +497,16 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 org_query.push_str(&format!(" AND v.port_id = '{}'", pid));
             }
 
+            if !include_deleted {
+                org_query.push_str(" AND v.deleted_at IS NULL");
+            }
+
             // Add order by and limit/offset
             org_query.push_str(&format!(
-                " ORDER BY v.severity, v.title LIMIT {} OFFSET {}",
-                limit, offset
+                " ORDER BY {} LIMIT {} OFFSET {}",
+                Self::order_by(sort, "v."),
+                limit,
+                offset
             ));
 
             // Execute query
@@ -422,12 +549,22 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
             FROM vulnerabilities",
         );
 
-        Self::apply_filters(&mut query, None, port_id, severity, status, false);
+        Self::apply_filters(
+            &mut query,
+            None,
+            port_id,
+            severity,
+            status,
+            include_deleted,
+            false,
+        );
 
         // Add order by and limit/offset
         query.push_str(&format!(
-            " ORDER BY severity, title LIMIT {} OFFSET {}",
-            limit, offset
+            " ORDER BY {} LIMIT {} OFFSET {}",
+            Self::order_by(sort, ""),
+            limit,
+            offset
         ));
 
         // Execute query
@@ -463,6 +600,7 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
         port_id: Option<ID>,
         severity: Option<Severity>,
         status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
     ) -> Result<usize> {
         // First try to count by asset_id
         if let Some(id) = asset_id {
@@ -491,6 +629,11 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 query.push_str(&format!(" AND status = '{}'", status_str));
             }
 
+            // Hide soft-deleted vulnerabilities unless asked for
+            if !include_deleted {
+                query.push_str(" AND deleted_at IS NULL");
+            }
+
             // Execute query
             let row = sqlx::query(&query).fetch_one(&self.pool).await?;
 
@@ -525,6 +668,10 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
                 org_query.push_str(&format!(" AND v.port_id = '{}'", pid));
             }
 
+            if !include_deleted {
+                org_query.push_str(" AND v.deleted_at IS NULL");
+            }
+
             // Execute query
             let org_row = sqlx::query(&org_query)
                 .bind(id)
@@ -560,10 +707,290 @@ impl VulnerabilityRepository for PgVulnerabilityRepository {
             query.push_str(&format!(" AND status = '{}'", status_str));
         }
 
+        // Hide soft-deleted vulnerabilities unless asked for
+        if !include_deleted {
+            query.push_str(" AND deleted_at IS NULL");
+        }
+
         // Execute query
         let row = sqlx::query(&query).fetch_one(&self.pool).await?;
 
         let count: i64 = row.get("count");
         Ok(count as usize)
     }
+
+    async fn list_vulnerabilities_page(
+        &self,
+        id_filter: Option<ID>,
+        port_id: Option<ID>,
+        severity: Option<Severity>,
+        status: Option<VulnerabilityStatus>,
+        include_deleted: bool,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<Vulnerability>> {
+        // Like `list_vulnerabilities`, the ID filter matches either an asset
+        // or an organization. The window function counts every matching row
+        // before LIMIT applies.
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                v.id, v.asset_id, v.port_id, v.title, v.description,
+                v.severity, v.status,
+                v.cve_id, v.cvss_score, v.evidence, v.remediation, v.first_seen, v.last_seen,
+                v.resolved_at, v.created_at, v.updated_at,
+                COUNT(*) OVER() AS total
+            FROM vulnerabilities v
+            JOIN assets a ON v.asset_id = a.id
+            WHERE ($1::uuid IS NULL OR v.asset_id = $1 OR a.organization_id = $1)
+                AND ($2::uuid IS NULL OR v.port_id = $2)
+                AND ($3::varchar IS NULL OR v.severity = $3)
+                AND ($4::varchar IS NULL OR v.status = $4)
+                AND ($5 OR v.deleted_at IS NULL)
+            ORDER BY v.severity, v.title, v.id
+            LIMIT $6 OFFSET $7
+            "#,
+        )
+        .bind(id_filter)
+        .bind(port_id)
+        .bind(severity)
+        .bind(status)
+        .bind(include_deleted)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // A page past the end has no rows to carry the total
+        let total = match rows.first() {
+            Some(row) => row.get::<i64, _>("total") as usize,
+            None if offset > 0 => {
+                self.count_vulnerabilities(id_filter, port_id, severity, status, include_deleted)
+                    .await?
+            }
+            None => 0,
+        };
+
+        let items = rows
+            .into_iter()
+            .map(|row| Vulnerability {
+                id: row.get("id"),
+                asset_id: row.get("asset_id"),
+                port_id: row.get("port_id"),
+                title: row.get("title"),
+                description: row.get("description"),
+                severity: row.get("severity"),
+                status: row.get("status"),
+                cve_id: row.get("cve_id"),
+                cvss_score: from_option_bigdecimal(row.get("cvss_score")),
+                evidence: row.get("evidence"),
+                remediation: row.get("remediation"),
+                first_seen: from_offset_datetime(row.get("first_seen")),
+                last_seen: from_offset_datetime(row.get("last_seen")),
+                resolved_at: from_option_offset_datetime(row.get("resolved_at")),
+                created_at: from_offset_datetime(row.get("created_at")),
+                updated_at: from_offset_datetime(row.get("updated_at")),
+            })
+            .collect();
+
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    async fn bulk_update_status(
+        &self,
+        organization_id: ID,
+        vulnerability_ids: &[ID],
+        status: VulnerabilityStatus,
+    ) -> Result<usize> {
+        // Reopening or starting work clears the resolution time, any other
+        // status keeps the first one recorded
+        let query = r#"
+            UPDATE vulnerabilities v
+            SET status = $3,
+                resolved_at = CASE WHEN $3 IN ('OPEN', 'INPROGRESS') THEN NULL ELSE COALESCE(v.resolved_at, $4) END,
+                updated_at = $4
+            FROM assets a
+            WHERE v.asset_id = a.id
+              AND a.organization_id = $2
+              AND v.id = ANY($1)
+              AND v.deleted_at IS NULL
+        "#;
+
+        let result = sqlx::query(query)
+            .bind(vulnerability_ids)
+            .bind(organization_id)
+            .bind(status as VulnerabilityStatus)
+            .bind(to_offset_datetime(chrono::Utc::now()))
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() > 0 {
+            publish_resource_event(
+                &self.pool,
+                ResourceEventType::Updated,
+                organization_id,
+                ResourceKind::Vulnerability,
+            )
+            .await;
+        }
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn group_open_vulnerabilities(
+        &self,
+        organization_id: ID,
+    ) -> Result<Vec<VulnerabilityGroup>> {
+        // Severities are ranked so MAX() picks the most severe one, then the
+        // rank is mapped back to its string form
+        let query = r#"
+            SELECT
+                MIN(title) AS title,
+                MAX(cve_id) AS cve_id,
+                technology,
+                COUNT(DISTINCT asset_id) AS affected_asset_count,
+                COUNT(*) AS vulnerability_count,
+                (ARRAY['INFO', 'LOW', 'MEDIUM', 'HIGH', 'CRITICAL'])[MAX(severity_rank)]::VARCHAR
+                    AS highest_severity,
+                ARRAY_AGG(DISTINCT asset_id) AS asset_ids
+            FROM (
+                SELECT
+                    v.title, v.cve_id, v.asset_id,
+                    v.evidence->>'affected_technology' AS technology,
+                    CASE v.severity
+                        WHEN 'CRITICAL' THEN 5
+                        WHEN 'HIGH' THEN 4
+                        WHEN 'MEDIUM' THEN 3
+                        WHEN 'LOW' THEN 2
+                        ELSE 1
+                    END AS severity_rank
+                FROM vulnerabilities v
+                JOIN assets a ON v.asset_id = a.id
+                WHERE a.organization_id = $1 AND v.status = 'OPEN'
+                    AND v.deleted_at IS NULL AND a.deleted_at IS NULL
+            ) ranked
+            GROUP BY COALESCE(cve_id, title), technology
+            ORDER BY MAX(severity_rank) DESC, affected_asset_count DESC, title
+        "#;
+
+        let rows = sqlx::query(query)
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VulnerabilityGroup {
+                title: row.get("title"),
+                cve_id: row.get("cve_id"),
+                technology: row.get("technology"),
+                affected_asset_count: row.get::<i64, _>("affected_asset_count") as usize,
+                vulnerability_count: row.get::<i64, _>("vulnerability_count") as usize,
+                highest_severity: row.get("highest_severity"),
+                asset_ids: row.get("asset_ids"),
+            })
+            .collect())
+    }
+
+    async fn count_by_severity(
+        &self,
+        organization_id: ID,
+        status: Option<VulnerabilityStatus>,
+    ) -> Result<HashMap<Severity, usize>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT v.severity, COUNT(*) AS count
+            FROM vulnerabilities v
+            JOIN assets a ON v.asset_id = a.id
+            WHERE a.organization_id = $1
+                AND ($2::varchar IS NULL OR v.status = $2)
+                AND v.deleted_at IS NULL AND a.deleted_at IS NULL
+            GROUP BY v.severity
+            "#,
+        )
+        .bind(organization_id)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get::<Severity, _>("severity"),
+                    row.get::<i64, _>("count") as usize,
+                )
+            })
+            .collect())
+    }
+
+    async fn vuln_counts_by_day(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(chrono::NaiveDate, usize)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT (v.first_seen AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS count
+            FROM vulnerabilities v
+            JOIN assets a ON v.asset_id = a.id
+            WHERE a.organization_id = $1 AND v.first_seen >= $2
+                AND v.deleted_at IS NULL AND a.deleted_at IS NULL
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+        .bind(organization_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get::<chrono::NaiveDate, _>("day"),
+                    row.get::<i64, _>("count") as usize,
+                )
+            })
+            .collect())
+    }
+
+    async fn activity_since(
+        &self,
+        organization_id: ID,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<VulnerabilityActivity> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE v.first_seen >= $2) AS new,
+                COUNT(*) FILTER (WHERE v.resolved_at >= $2) AS resolved,
+                COUNT(*) FILTER (
+                    WHERE v.first_seen >= $2 AND v.severity = 'CRITICAL'
+                ) AS critical,
+                COUNT(*) FILTER (WHERE v.first_seen >= $2 AND v.severity = 'HIGH') AS high
+            FROM vulnerabilities v
+            JOIN assets a ON v.asset_id = a.id
+            WHERE a.organization_id = $1
+                AND v.deleted_at IS NULL AND a.deleted_at IS NULL
+            "#,
+        )
+        .bind(organization_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(VulnerabilityActivity {
+            new: row.get::<i64, _>("new") as usize,
+            resolved: row.get::<i64, _>("resolved") as usize,
+            critical: row.get::<i64, _>("critical") as usize,
+            high: row.get::<i64, _>("high") as usize,
+        })
+    }
 }
@@ -1,6 +1,7 @@
 pub mod testing;
 
 use chrono::{DateTime, Utc};
+use shared::types::SortOrder;
 use sqlx::types::{time::OffsetDateTime, BigDecimal};
 use std::str::FromStr;
 
@@ -35,3 +36,11 @@ pub fn to_option_bigdecimal(value: Option<f64>) -> Option<BigDecimal> {
 pub fn from_option_bigdecimal(value: Option<BigDecimal>) -> Option<f64> {
     value.map(|bd| bd.to_string().parse::<f64>().unwrap_or(0.0))
 }
+
+/// Convert SortOrder to the SQL keyword for an ORDER BY clause
+pub fn sort_direction(order: SortOrder) -> &'static str {
+    match order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    }
+}
@@ -0,0 +1,65 @@
+use backend::{models::AssetHistory, Result};
+use infrastructure::{
+    database::migrations::Migrator,
+    repositories::RepositoryFactory,
+    utils::testing::{create_test_asset, create_test_organization},
+};
+use serde_json::json;
+use shared::types::AssetType;
+use sqlx::PgPool;
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_asset_history_repository_basic_operations(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let history_repo = factory.asset_history_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "example.com").await?;
+
+    let now = chrono::Utc::now();
+    let status_change = AssetHistory::new(
+        asset.id,
+        "status".to_string(),
+        json!("ACTIVE"),
+        json!("INACTIVE"),
+        now - chrono::Duration::minutes(5),
+    );
+    let port_opened = AssetHistory::new(
+        asset.id,
+        "attributes.open_ports".to_string(),
+        serde_json::Value::Null,
+        json!([443]),
+        now,
+    );
+
+    // Test create
+    let created = history_repo.create_history_entry(&status_change).await?;
+    assert_eq!(created.field, "status");
+    assert_eq!(created.old_value, json!("ACTIVE"));
+    assert_eq!(created.new_value, json!("INACTIVE"));
+
+    // A field that didn't exist before round-trips as null
+    let created = history_repo.create_history_entry(&port_opened).await?;
+    assert!(created.old_value.is_null());
+
+    // Test list, most recent first
+    let entries = history_repo.list_asset_history(asset.id, 10, 0).await?;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].field, "attributes.open_ports");
+    assert_eq!(entries[1].field, "status");
+
+    // Test count
+    assert_eq!(history_repo.count_asset_history(asset.id).await?, 2);
+
+    Ok(())
+}
@@ -1,10 +1,13 @@
 #[cfg(test)]
 mod tests {
-    use backend::models::Asset;
+    use backend::models::{Asset, AssetFilter, AssetRelationship, RelationshipDirection};
+    use backend::services::AssetServiceImpl;
+    use backend::AssetService;
     use infrastructure::{
         repositories::factory::RepositoryFactory,
         utils::testing::{create_test_asset, create_test_organization, setup_test_db},
     };
+    use serde_json::json;
     use shared::types::{AssetStatus, AssetType, PaginationParams};
 
     #[tokio::test]
@@ -104,9 +107,10 @@ mod tests {
         };
         let assets_org1 = asset_repo
             .list_assets(
-                Some(org1.id),
-                None,
-                None,
+                &AssetFilter {
+                    organization_id: Some(org1.id),
+                    ..Default::default()
+                },
                 pagination.page_size as usize,
                 pagination.offset() as usize,
             )
@@ -117,9 +121,11 @@ mod tests {
         // List active for Org 1
         let active_assets_org1 = asset_repo
             .list_assets(
-                Some(org1.id),
-                None,
-                Some(AssetStatus::Active),
+                &AssetFilter {
+                    organization_id: Some(org1.id),
+                    status: Some(AssetStatus::Active),
+                    ..Default::default()
+                },
                 pagination.page_size as usize,
                 pagination.offset() as usize,
             )
@@ -132,9 +138,11 @@ mod tests {
         // List Domain type for Org 1
         let domain_assets_org1 = asset_repo
             .list_assets(
-                Some(org1.id),
-                Some(AssetType::Domain),
-                None,
+                &AssetFilter {
+                    organization_id: Some(org1.id),
+                    asset_type: Some(AssetType::Domain),
+                    ..Default::default()
+                },
                 pagination.page_size as usize,
                 pagination.offset() as usize,
             )
@@ -146,21 +154,32 @@ mod tests {
 
         // Count assets for Org 1
         let count_org1 = asset_repo
-            .count_assets(Some(org1.id), None, None)
+            .count_assets(&AssetFilter {
+                organization_id: Some(org1.id),
+                ..Default::default()
+            })
             .await
             .expect("Failed to count assets for Org 1");
         assert_eq!(count_org1, 2);
 
         // Count active for Org 1
         let count_active_org1 = asset_repo
-            .count_assets(Some(org1.id), None, Some(AssetStatus::Active))
+            .count_assets(&AssetFilter {
+                organization_id: Some(org1.id),
+                status: Some(AssetStatus::Active),
+                ..Default::default()
+            })
             .await
             .expect("Failed to count active assets for Org 1");
         assert_eq!(count_active_org1, 1);
 
         // Count Domain type for Org 1
         let count_domain_org1 = asset_repo
-            .count_assets(Some(org1.id), Some(AssetType::Domain), None)
+            .count_assets(&AssetFilter {
+                organization_id: Some(org1.id),
+                asset_type: Some(AssetType::Domain),
+                ..Default::default()
+            })
             .await
             .expect("Failed to count domain assets for Org 1");
         assert_eq!(count_domain_org1, 1);
@@ -188,4 +207,685 @@ mod tests {
         assert!(result.is_err());
         // Ideally, check for a specific database duplicate key error type
     }
+
+    #[tokio::test]
+    async fn test_asset_repository_upsert() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Upsert Asset Org")
+            .await
+            .unwrap();
+
+        let first = Asset::new(
+            org.id,
+            AssetType::Domain,
+            "upsert.com".to_string(),
+            Some(serde_json::json!({ "source": "dns" })),
+        );
+        let created = asset_repo.upsert_asset(&first).await.unwrap();
+        assert_eq!(created.id, first.id);
+
+        // Seeing the same value again updates the existing row
+        let mut again = Asset::new(
+            org.id,
+            AssetType::Domain,
+            "upsert.com".to_string(),
+            Some(serde_json::json!({ "whois_info": { "registrar": "Example" } })),
+        );
+        again.status = AssetStatus::Inactive;
+        let upserted = asset_repo.upsert_asset(&again).await.unwrap();
+        assert_eq!(upserted.id, first.id);
+        assert_eq!(upserted.status, AssetStatus::Inactive);
+        assert_eq!(upserted.attributes["source"], "dns");
+        assert_eq!(upserted.attributes["whois_info"]["registrar"], "Example");
+        assert_eq!(
+            asset_repo
+                .count_assets(&AssetFilter {
+                    organization_id: Some(org.id),
+                    ..Default::default()
+                })
+                .await
+                .unwrap(),
+            1
+        );
+
+        // A soft-deleted asset doesn't block rediscovery
+        asset_repo.delete_asset(first.id).await.unwrap();
+        let rediscovered = Asset::new(org.id, AssetType::Domain, "upsert.com".to_string(), None);
+        let recreated = asset_repo.upsert_asset(&rediscovered).await.unwrap();
+        assert_eq!(recreated.id, rediscovered.id);
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_mark_stale() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Stale Asset Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Stale Asset Org")
+            .await
+            .unwrap();
+
+        let now = chrono::Utc::now();
+        let mut stale = Asset::new(
+            org.id,
+            AssetType::Domain,
+            "old.example.com".to_string(),
+            None,
+        );
+        stale.last_seen = now - chrono::Duration::days(45);
+        let stale = asset_repo.create_asset(&stale).await.unwrap();
+
+        let mut recent = Asset::new(
+            org.id,
+            AssetType::Domain,
+            "new.example.com".to_string(),
+            None,
+        );
+        recent.last_seen = now - chrono::Duration::days(1);
+        let recent = asset_repo.create_asset(&recent).await.unwrap();
+
+        // Archived assets are left alone even if they're old
+        let mut archived = Asset::new(
+            org.id,
+            AssetType::Domain,
+            "archived.example.com".to_string(),
+            None,
+        );
+        archived.last_seen = now - chrono::Duration::days(90);
+        archived.status = AssetStatus::Archived;
+        let archived = asset_repo.create_asset(&archived).await.unwrap();
+
+        // Stale, but in another organization
+        let mut other = Asset::new(
+            other_org.id,
+            AssetType::Domain,
+            "old.example.org".to_string(),
+            None,
+        );
+        other.last_seen = now - chrono::Duration::days(45);
+        let other = asset_repo.create_asset(&other).await.unwrap();
+
+        let marked = asset_repo
+            .mark_stale(Some(org.id), chrono::Duration::days(30))
+            .await
+            .expect("Failed to mark stale assets");
+        assert_eq!(marked, 1);
+
+        let status_of = |id| {
+            let asset_repo = &asset_repo;
+            async move { asset_repo.get_asset(id).await.unwrap().status }
+        };
+        assert_eq!(status_of(stale.id).await, AssetStatus::Inactive);
+        assert_eq!(status_of(recent.id).await, AssetStatus::Active);
+        assert_eq!(status_of(archived.id).await, AssetStatus::Archived);
+        assert_eq!(status_of(other.id).await, AssetStatus::Active);
+
+        // Sweeping every organization picks up the rest
+        let marked = asset_repo
+            .mark_stale(None, chrono::Duration::days(30))
+            .await
+            .unwrap();
+        assert_eq!(marked, 1);
+        assert_eq!(status_of(other.id).await, AssetStatus::Inactive);
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_soft_delete_and_restore() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Soft Delete Org")
+            .await
+            .unwrap();
+        let kept = create_test_asset(&factory, org.id, AssetType::Domain, "kept.example.com")
+            .await
+            .unwrap();
+        let removed = create_test_asset(&factory, org.id, AssetType::Domain, "gone.example.com")
+            .await
+            .unwrap();
+
+        assert!(asset_repo.delete_asset(removed.id).await.unwrap());
+        // Deleting twice is a no-op
+        assert!(!asset_repo.delete_asset(removed.id).await.unwrap());
+
+        // Hidden from lookups and listings by default
+        assert!(asset_repo.get_asset(removed.id).await.is_err());
+        let listed = asset_repo
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, kept.id);
+        assert_eq!(
+            asset_repo
+                .count_assets(&AssetFilter {
+                    organization_id: Some(org.id),
+                    ..Default::default()
+                })
+                .await
+                .unwrap(),
+            1
+        );
+
+        // ...but still there when asked for
+        let listed = asset_repo
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    include_deleted: true,
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(
+            asset_repo
+                .count_assets(&AssetFilter {
+                    organization_id: Some(org.id),
+                    include_deleted: true,
+                    ..Default::default()
+                })
+                .await
+                .unwrap(),
+            2
+        );
+
+        // Restoring brings it back
+        assert!(asset_repo.restore_asset(removed.id).await.unwrap());
+        assert!(!asset_repo.restore_asset(kept.id).await.unwrap());
+        let restored = asset_repo
+            .get_asset(removed.id)
+            .await
+            .expect("Restored asset should be found");
+        assert_eq!(restored.value, "gone.example.com");
+        let listed = asset_repo
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_get_assets_in_one_batch() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Batch Lookup Org")
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for value in ["a.example.com", "b.example.com", "c.example.com"] {
+            let asset = create_test_asset(&factory, org.id, AssetType::Domain, value)
+                .await
+                .unwrap();
+            ids.push(asset.id);
+        }
+        asset_repo.delete_asset(ids[2]).await.unwrap();
+
+        // Deleted and unknown IDs are skipped
+        let mut wanted = ids.clone();
+        wanted.push(uuid::Uuid::new_v4());
+        let mut found: Vec<String> = asset_repo
+            .get_assets(&wanted)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|asset| asset.value)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec!["a.example.com", "b.example.com"]);
+
+        assert!(asset_repo.get_assets(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_add_relationships_bulk() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+        let service = AssetServiceImpl::new(asset_repo.clone(), factory.asset_history_repository());
+
+        let org = create_test_organization(&factory, "Bulk Relationships Org")
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for value in ["example.com", "www.example.com", "api.example.com"] {
+            let asset = create_test_asset(&factory, org.id, AssetType::Domain, value)
+                .await
+                .unwrap();
+            ids.push(asset.id);
+        }
+
+        let added = asset_repo
+            .add_relationships_bulk(vec![
+                (ids[1], ids[0], "subdomain".to_string()),
+                (ids[2], ids[0], "subdomain".to_string()),
+                (ids[1], ids[2], "redirects_to".to_string()),
+                (ids[1], ids[0], "subdomain".to_string()),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(added, 3);
+
+        let related = service
+            .get_related_assets(ids[1], None, 10, 0)
+            .await
+            .unwrap();
+        let related: Vec<(String, String)> = related
+            .into_iter()
+            .map(|(asset, relationship_type)| (relationship_type, asset.value))
+            .collect();
+        assert_eq!(
+            related,
+            vec![
+                ("redirects_to".to_string(), "api.example.com".to_string()),
+                ("subdomain".to_string(), "example.com".to_string()),
+            ]
+        );
+
+        // Recording them again changes nothing
+        let added = asset_repo
+            .add_relationships_bulk(vec![(ids[2], ids[0], "subdomain".to_string())])
+            .await
+            .unwrap();
+        assert_eq!(added, 0);
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_relationships_forward_and_reverse() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Relationships Org")
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for value in [
+            "example.com",
+            "api.example.com",
+            "www.example.com",
+            "old.example.com",
+        ] {
+            let asset = create_test_asset(&factory, org.id, AssetType::Domain, value)
+                .await
+                .unwrap();
+            ids.push(asset.id);
+        }
+        let ip = create_test_asset(&factory, org.id, AssetType::IPAddress, "192.0.2.1")
+            .await
+            .unwrap();
+
+        for subdomain in &ids[1..] {
+            let relationship = AssetRelationship {
+                source_asset_id: *subdomain,
+                target_asset_id: ids[0],
+                relationship_type: "subdomain".to_string(),
+                metadata: None,
+            };
+            assert!(asset_repo
+                .add_asset_relationship(&relationship)
+                .await
+                .unwrap());
+        }
+        let hosted_on = AssetRelationship {
+            source_asset_id: ids[2],
+            target_asset_id: ip.id,
+            relationship_type: "hosted_on".to_string(),
+            metadata: Some(json!({ "record": "A" })),
+        };
+        assert!(asset_repo.add_asset_relationship(&hosted_on).await.unwrap());
+        // Recording it again is a no-op
+        assert!(!asset_repo.add_asset_relationship(&hosted_on).await.unwrap());
+
+        // Forward: what www.example.com points at, by type
+        let forward = asset_repo
+            .list_asset_relationships(ids[2], RelationshipDirection::Outgoing, None, 10, 0)
+            .await
+            .unwrap();
+        let forward: Vec<(&str, &str)> = forward
+            .iter()
+            .map(|(r, asset)| (r.relationship_type.as_str(), asset.value.as_str()))
+            .collect();
+        assert_eq!(
+            forward,
+            vec![("hosted_on", "192.0.2.1"), ("subdomain", "example.com")]
+        );
+        let hosted = asset_repo
+            .list_asset_relationships(
+                ids[2],
+                RelationshipDirection::Outgoing,
+                Some("hosted_on".to_string()),
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(hosted.len(), 1);
+        assert_eq!(hosted[0].0.metadata, Some(json!({ "record": "A" })));
+
+        // Reverse: what points at example.com, ordered by value and paged
+        asset_repo.delete_asset(ids[3]).await.unwrap();
+        let reverse = asset_repo
+            .list_asset_relationships(ids[0], RelationshipDirection::Incoming, None, 10, 0)
+            .await
+            .unwrap();
+        let reverse: Vec<&str> = reverse
+            .iter()
+            .map(|(_, asset)| asset.value.as_str())
+            .collect();
+        assert_eq!(reverse, vec!["api.example.com", "www.example.com"]);
+        let second = asset_repo
+            .list_asset_relationships(
+                ids[0],
+                RelationshipDirection::Incoming,
+                Some("subdomain".to_string()),
+                1,
+                1,
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].0.source_asset_id, ids[2]);
+        assert_eq!(second[0].1.value, "www.example.com");
+        // Nothing points at a leaf
+        assert!(asset_repo
+            .list_asset_relationships(ids[1], RelationshipDirection::Incoming, None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Removing a relationship removes it from both directions
+        assert!(asset_repo
+            .remove_asset_relationship(ids[2], ip.id, "hosted_on")
+            .await
+            .unwrap());
+        assert!(!asset_repo
+            .remove_asset_relationship(ids[2], ip.id, "hosted_on")
+            .await
+            .unwrap());
+        assert!(asset_repo
+            .list_asset_relationships(ip.id, RelationshipDirection::Incoming, None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_tags() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Tagging Org")
+            .await
+            .unwrap();
+        let tagged = create_test_asset(&factory, org.id, AssetType::Domain, "pay.example.com")
+            .await
+            .unwrap();
+        let untagged = create_test_asset(&factory, org.id, AssetType::Domain, "www.example.com")
+            .await
+            .unwrap();
+
+        // Tagging returns every tag on the asset, and repeats are ignored
+        let tags = asset_repo
+            .add_asset_tags(tagged.id, &["pci-scope".to_string(), "prod".to_string()])
+            .await
+            .expect("Failed to tag asset");
+        assert_eq!(tags, vec!["pci-scope", "prod"]);
+        let tags = asset_repo
+            .add_asset_tags(tagged.id, &["acquired".to_string(), "prod".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(tags, vec!["acquired", "pci-scope", "prod"]);
+        assert!(asset_repo
+            .list_asset_tags(untagged.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Filtering by tag only lists tagged assets
+        let listed = asset_repo
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    tag: Some("pci-scope".to_string()),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, tagged.id);
+        assert_eq!(
+            asset_repo
+                .count_assets(&AssetFilter {
+                    organization_id: Some(org.id),
+                    tag: Some("pci-scope".to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            asset_repo
+                .count_assets(&AssetFilter {
+                    organization_id: Some(org.id),
+                    ..Default::default()
+                })
+                .await
+                .unwrap(),
+            2
+        );
+
+        // Untagging
+        assert!(asset_repo
+            .remove_asset_tag(tagged.id, "pci-scope")
+            .await
+            .unwrap());
+        assert!(!asset_repo
+            .remove_asset_tag(tagged.id, "pci-scope")
+            .await
+            .unwrap());
+        assert_eq!(
+            asset_repo.list_asset_tags(tagged.id).await.unwrap(),
+            vec!["acquired", "prod"]
+        );
+        assert!(asset_repo
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    tag: Some("pci-scope".to_string()),
+                    ..Default::default()
+                },
+                10,
+                0
+            )
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_bulk_update_status_is_org_scoped() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Bulk Asset Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Bulk Asset Org")
+            .await
+            .unwrap();
+        let mine1 = create_test_asset(&factory, org.id, AssetType::Domain, "a.example.com")
+            .await
+            .unwrap();
+        let mine2 = create_test_asset(&factory, org.id, AssetType::Domain, "b.example.com")
+            .await
+            .unwrap();
+        let untouched = create_test_asset(&factory, org.id, AssetType::Domain, "c.example.com")
+            .await
+            .unwrap();
+        let theirs = create_test_asset(&factory, other_org.id, AssetType::Domain, "d.example.com")
+            .await
+            .unwrap();
+
+        // The other organization's asset is ignored
+        let updated = asset_repo
+            .bulk_update_status(
+                org.id,
+                &[mine1.id, mine2.id, theirs.id],
+                AssetStatus::Archived,
+            )
+            .await
+            .expect("Failed to bulk update assets");
+        assert_eq!(updated, 2);
+
+        for id in [mine1.id, mine2.id] {
+            let asset = asset_repo.get_asset(id).await.unwrap();
+            assert_eq!(asset.status, AssetStatus::Archived);
+        }
+        for id in [untouched.id, theirs.id] {
+            let asset = asset_repo.get_asset(id).await.unwrap();
+            assert_eq!(asset.status, AssetStatus::Active);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_counts_by_day() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Trend Asset Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Trend Asset Org")
+            .await
+            .unwrap();
+
+        let now = chrono::Utc::now();
+        let seed = |organization_id, value: &str, days_ago: i64| {
+            let mut asset = Asset::new(organization_id, AssetType::Domain, value.to_string(), None);
+            asset.first_seen = now - chrono::Duration::days(days_ago);
+            asset
+        };
+        for asset in [
+            seed(org.id, "a.example.com", 5),
+            seed(org.id, "b.example.com", 5),
+            seed(org.id, "c.example.com", 2),
+            seed(org.id, "d.example.com", 0),
+            // Before the window
+            seed(org.id, "old.example.com", 40),
+            // Another organization's
+            seed(other_org.id, "e.example.org", 2),
+        ] {
+            asset_repo.create_asset(&asset).await.unwrap();
+        }
+
+        let counts = asset_repo
+            .asset_counts_by_day(org.id, now - chrono::Duration::days(30))
+            .await
+            .expect("Failed to count assets by day");
+        let day = |days_ago| (now - chrono::Duration::days(days_ago)).date_naive();
+        assert_eq!(counts, vec![(day(5), 2), (day(2), 1), (day(0), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_asset_repository_list_assets_page() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let asset_repo = factory.asset_repository();
+
+        let org = create_test_organization(&factory, "Paged Asset Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Paged Asset Org")
+            .await
+            .unwrap();
+        for value in [
+            "a.example.com",
+            "b.example.com",
+            "c.example.com",
+            "d.example.com",
+        ] {
+            create_test_asset(&factory, org.id, AssetType::Domain, value)
+                .await
+                .unwrap();
+        }
+        create_test_asset(&factory, org.id, AssetType::IPAddress, "192.0.2.1")
+            .await
+            .unwrap();
+        create_test_asset(&factory, other_org.id, AssetType::Domain, "e.example.org")
+            .await
+            .unwrap();
+
+        let page = |asset_type, limit, offset| {
+            let asset_repo = &asset_repo;
+            async move {
+                asset_repo
+                    .list_assets_page(
+                        &AssetFilter {
+                            organization_id: Some(org.id),
+                            asset_type,
+                            ..Default::default()
+                        },
+                        limit,
+                        offset,
+                    )
+                    .await
+                    .expect("Failed to list page of assets")
+            }
+        };
+
+        // The total covers every matching asset, not just the page
+        let first = page(None, 2, 0).await;
+        assert_eq!(first.total, 5);
+        assert_eq!((first.limit, first.offset), (2, 0));
+        let values: Vec<_> = first.items.iter().map(|a| a.value.as_str()).collect();
+        assert_eq!(values, vec!["192.0.2.1", "a.example.com"]);
+
+        let last = page(None, 2, 4).await;
+        assert_eq!(last.total, 5);
+        assert_eq!(last.items.len(), 1);
+
+        // Past the end there are no items, but the total is still known
+        let beyond = page(None, 2, 10).await;
+        assert_eq!(beyond.total, 5);
+        assert!(beyond.items.is_empty());
+
+        // Filters narrow the total as well
+        let domains = page(Some(AssetType::Domain), 3, 0).await;
+        assert_eq!(domains.total, 4);
+        assert_eq!(domains.items.len(), 3);
+    }
 }
@@ -0,0 +1,105 @@
+use backend::{
+    models::{AuditLogEntry, AuditLogFilter},
+    Result,
+};
+use infrastructure::{database::migrations::Migrator, repositories::RepositoryFactory};
+use shared::types::AuditAction;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_audit_log_repository_filters_entries(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let audit_repo = factory.audit_log_repository();
+
+    let org_id = Uuid::new_v4();
+    let alice = Uuid::new_v4();
+    let bob = Uuid::new_v4();
+    let asset_id = Uuid::new_v4();
+
+    let created = audit_repo
+        .create_entry(&AuditLogEntry::new(
+            alice,
+            Some(org_id),
+            AuditAction::Create,
+            "asset".to_string(),
+            Some(asset_id),
+            Some("203.0.113.7".to_string()),
+        ))
+        .await?;
+    assert_eq!(created.user_id, alice);
+    assert_eq!(created.action, AuditAction::Create);
+    assert_eq!(created.ip_address.as_deref(), Some("203.0.113.7"));
+
+    audit_repo
+        .create_entry(&AuditLogEntry::new(
+            bob,
+            Some(org_id),
+            AuditAction::Delete,
+            "asset".to_string(),
+            Some(asset_id),
+            None,
+        ))
+        .await?;
+    audit_repo
+        .create_entry(&AuditLogEntry::new(
+            alice,
+            None,
+            AuditAction::Update,
+            "vulnerability".to_string(),
+            None,
+            None,
+        ))
+        .await?;
+
+    // No filter lists everything
+    let all = AuditLogFilter::default();
+    assert_eq!(audit_repo.count_entries(&all).await?, 3);
+    assert_eq!(audit_repo.list_entries(&all, 10, 0).await?.len(), 3);
+    assert_eq!(audit_repo.list_entries(&all, 2, 2).await?.len(), 1);
+
+    // Everything alice did
+    let by_alice = AuditLogFilter {
+        user_id: Some(alice),
+        ..Default::default()
+    };
+    assert_eq!(audit_repo.count_entries(&by_alice).await?, 2);
+
+    // Deletions of the asset
+    let deletions = AuditLogFilter {
+        action: Some(AuditAction::Delete),
+        resource_type: Some("asset".to_string()),
+        resource_id: Some(asset_id),
+        ..Default::default()
+    };
+    let entries = audit_repo.list_entries(&deletions, 10, 0).await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].user_id, bob);
+    assert_eq!(entries[0].organization_id, Some(org_id));
+
+    // Entries for the organization
+    let in_org = AuditLogFilter {
+        organization_id: Some(org_id),
+        ..Default::default()
+    };
+    assert_eq!(audit_repo.count_entries(&in_org).await?, 2);
+
+    // Nothing recorded in the future
+    let later = AuditLogFilter {
+        since: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+        ..Default::default()
+    };
+    assert_eq!(audit_repo.count_entries(&later).await?, 0);
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
 #[cfg(test)]
 mod tests {
-    use backend::models::{DiscoveryJob, JobAssetLink};
+    use backend::models::{
+        DiscoveryJob, DiscoveryJobFilter, JobAssetLink, JobResultSummary, Port, Technology,
+        Vulnerability,
+    };
     use infrastructure::{
         repositories::factory::RepositoryFactory,
         utils::testing::{create_test_asset, create_test_organization, setup_test_db},
     };
-    use shared::types::{AssetType, JobStatus, JobType, ID};
+    use shared::types::{AssetType, JobStatus, JobType, Protocol, Severity, ID};
 
     use serde_json::json;
 
@@ -128,24 +131,44 @@ mod tests {
 
         let limit = 10;
         let offset = 0;
+        let org1_filter = DiscoveryJobFilter {
+            organization_id: Some(org1.id),
+            ..Default::default()
+        };
 
         // List all for Org 1
         let jobs_org1 = job_repo
-            .list_jobs(Some(org1.id), None, None, limit, offset)
+            .list_jobs(&org1_filter, None, limit, offset)
             .await
             .expect("Failed to list jobs for org 1");
         assert_eq!(jobs_org1.len(), 3);
 
         // List DnsEnum type for Org 1
         let dns_jobs_org1 = job_repo
-            .list_jobs(Some(org1.id), Some(JobType::DnsEnum), None, limit, offset)
+            .list_jobs(
+                &DiscoveryJobFilter {
+                    job_type: Some(JobType::DnsEnum),
+                    ..org1_filter.clone()
+                },
+                None,
+                limit,
+                offset,
+            )
             .await
             .expect("Failed to list DNS jobs for org 1");
         assert_eq!(dns_jobs_org1.len(), 2);
 
         // List Running status for Org 1
         let running_jobs_org1 = job_repo
-            .list_jobs(Some(org1.id), None, Some(JobStatus::Running), limit, offset)
+            .list_jobs(
+                &DiscoveryJobFilter {
+                    statuses: vec![JobStatus::Running],
+                    ..org1_filter.clone()
+                },
+                None,
+                limit,
+                offset,
+            )
             .await
             .expect("Failed to list running jobs for org 1");
         assert_eq!(running_jobs_org1.len(), 1);
@@ -154,9 +177,12 @@ mod tests {
         // List Completed DnsEnum for Org 1
         let completed_dns_jobs_org1 = job_repo
             .list_jobs(
-                Some(org1.id),
-                Some(JobType::DnsEnum),
-                Some(JobStatus::Completed),
+                &DiscoveryJobFilter {
+                    statuses: vec![JobStatus::Completed],
+                    job_type: Some(JobType::DnsEnum),
+                    ..org1_filter.clone()
+                },
+                None,
                 limit,
                 offset,
             )
@@ -167,33 +193,127 @@ mod tests {
 
         // Count all for Org 1
         let count_org1 = job_repo
-            .count_jobs(Some(org1.id), None, None)
+            .count_jobs(&org1_filter)
             .await
             .expect("Failed to count jobs for org 1");
         assert_eq!(count_org1, 3);
 
         // Count Pending for Org 1
         let count_pending_org1 = job_repo
-            .count_jobs(Some(org1.id), None, Some(JobStatus::Pending))
+            .count_jobs(&DiscoveryJobFilter {
+                statuses: vec![JobStatus::Pending],
+                ..org1_filter.clone()
+            })
             .await
             .expect("Failed to count pending jobs for org 1");
         assert_eq!(count_pending_org1, 1);
 
         // Count PortScan for Org 1
         let count_scan_org1 = job_repo
-            .count_jobs(Some(org1.id), Some(JobType::PortScan), None)
+            .count_jobs(&DiscoveryJobFilter {
+                job_type: Some(JobType::PortScan),
+                ..org1_filter.clone()
+            })
             .await
             .expect("Failed to count scan jobs for org 1");
         assert_eq!(count_scan_org1, 1);
 
         // Count all jobs
         let count_all = job_repo
-            .count_jobs(None, None, None)
+            .count_jobs(&DiscoveryJobFilter::default())
             .await
             .expect("Failed to count all jobs");
         assert_eq!(count_all, 4);
     }
 
+    #[tokio::test]
+    async fn test_discovery_job_repository_filters_by_statuses_type_and_date() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let job_repo = factory.discovery_job_repository();
+
+        let org = create_test_organization(&factory, "Test Org Job Statuses")
+            .await
+            .unwrap();
+        let pending = create_test_job(&factory, org.id, JobType::DnsEnum, JobStatus::Pending).await;
+        let running =
+            create_test_job(&factory, org.id, JobType::PortScan, JobStatus::Running).await;
+        let completed =
+            create_test_job(&factory, org.id, JobType::PortScan, JobStatus::Completed).await;
+        let failed = create_test_job(&factory, org.id, JobType::DnsEnum, JobStatus::Failed).await;
+
+        let ids = |jobs: Vec<DiscoveryJob>| {
+            let mut ids: Vec<ID> = jobs.into_iter().map(|job| job.id).collect();
+            ids.sort();
+            ids
+        };
+        let sorted = |mut expected: Vec<ID>| {
+            expected.sort();
+            expected
+        };
+
+        // Jobs in any of several statuses, as the discovery page asks for
+        let active = DiscoveryJobFilter {
+            organization_id: Some(org.id),
+            statuses: vec![JobStatus::Running, JobStatus::Pending],
+            ..Default::default()
+        };
+        let jobs = job_repo.list_jobs(&active, None, 10, 0).await.unwrap();
+        assert_eq!(ids(jobs), sorted(vec![pending.id, running.id]));
+        assert_eq!(job_repo.count_jobs(&active).await.unwrap(), 2);
+
+        let finished = DiscoveryJobFilter {
+            statuses: vec![JobStatus::Completed, JobStatus::Failed],
+            ..active.clone()
+        };
+        let jobs = job_repo.list_jobs(&finished, None, 10, 0).await.unwrap();
+        assert_eq!(ids(jobs), sorted(vec![completed.id, failed.id]));
+
+        // Statuses combine with the job type
+        let finished_scans = DiscoveryJobFilter {
+            job_type: Some(JobType::PortScan),
+            ..finished.clone()
+        };
+        let jobs = job_repo
+            .list_jobs(&finished_scans, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(ids(jobs), vec![completed.id]);
+        assert_eq!(job_repo.count_jobs(&finished_scans).await.unwrap(), 1);
+
+        let dns_jobs = DiscoveryJobFilter {
+            organization_id: Some(org.id),
+            job_type: Some(JobType::DnsEnum),
+            ..Default::default()
+        };
+        let jobs = job_repo.list_jobs(&dns_jobs, None, 10, 0).await.unwrap();
+        assert_eq!(ids(jobs), sorted(vec![pending.id, failed.id]));
+
+        // Creation time bounds
+        let created_at = pending.created_at;
+        let window = DiscoveryJobFilter {
+            organization_id: Some(org.id),
+            since: Some(created_at - chrono::Duration::hours(1)),
+            until: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+            ..Default::default()
+        };
+        assert_eq!(job_repo.count_jobs(&window).await.unwrap(), 4);
+        let future = DiscoveryJobFilter {
+            since: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+            ..window.clone()
+        };
+        assert_eq!(job_repo.count_jobs(&future).await.unwrap(), 0);
+        let past = DiscoveryJobFilter {
+            until: Some(created_at - chrono::Duration::hours(1)),
+            ..window
+        };
+        assert!(job_repo
+            .list_jobs(&past, None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_discovery_job_asset_links() {
         let (db_pool, _container) = setup_test_db().await;
@@ -262,5 +382,146 @@ mod tests {
             .await
             .expect("Failed to get assets for job with no links");
         assert!(no_linked_assets.is_empty());
+
+        // Bulk linking skips links that already exist
+        let asset3 = create_test_asset(&factory, org.id, AssetType::Domain, "link3.com")
+            .await
+            .unwrap();
+        let created = job_repo
+            .link_job_to_assets(job.id, &[asset1.id, asset3.id, asset3.id])
+            .await
+            .expect("Failed to link assets");
+        assert_eq!(created, 1);
+        assert_eq!(job_repo.get_job_assets(job.id).await.unwrap().len(), 3);
+        assert_eq!(job_repo.link_job_to_assets(job.id, &[]).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_discovery_job_summary() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let job_repo = factory.discovery_job_repository();
+        let vuln_repo = factory.vulnerability_repository();
+
+        let org = create_test_organization(&factory, "Test Org Job Summary")
+            .await
+            .unwrap();
+        let job = create_test_job(&factory, org.id, JobType::PortScan, JobStatus::Completed).await;
+        let found = create_test_asset(&factory, org.id, AssetType::IPAddress, "3.3.3.3")
+            .await
+            .unwrap();
+        let other = create_test_asset(&factory, org.id, AssetType::IPAddress, "4.4.4.4")
+            .await
+            .unwrap();
+        job_repo
+            .create_job_asset_link(&JobAssetLink::new(job.id, found.id))
+            .await
+            .unwrap();
+
+        // Findings on the linked asset count, those on the other asset don't
+        for asset_id in [found.id, other.id] {
+            for port in [22, 443] {
+                factory
+                    .port_repository()
+                    .create_port(&Port::new(asset_id, port, Protocol::TCP, None, None))
+                    .await
+                    .unwrap();
+            }
+            factory
+                .technology_repository()
+                .create_technology(&Technology::new(asset_id, "nginx".to_string(), None, None))
+                .await
+                .unwrap();
+        }
+        let mut vulnerabilities = Vec::new();
+        for title in ["Open SSH", "Weak TLS", "Old nginx"] {
+            let vulnerability = Vulnerability::new(
+                found.id,
+                None,
+                title.to_string(),
+                None,
+                Severity::Medium,
+                None,
+                None,
+                None,
+            );
+            vulnerabilities.push(
+                vuln_repo
+                    .create_vulnerability(&vulnerability)
+                    .await
+                    .unwrap(),
+            );
+        }
+        // Deleted vulnerabilities aren't counted
+        vuln_repo
+            .delete_vulnerability(vulnerabilities[0].id)
+            .await
+            .unwrap();
+
+        let summary = job_repo.get_job_summary(job.id).await.unwrap();
+        assert_eq!(
+            summary,
+            JobResultSummary {
+                assets: 1,
+                ports: 2,
+                technologies: 1,
+                vulnerabilities: 2,
+            }
+        );
+
+        let job_no_links =
+            create_test_job(&factory, org.id, JobType::CertScan, JobStatus::Completed).await;
+        assert_eq!(
+            job_repo.get_job_summary(job_no_links.id).await.unwrap(),
+            JobResultSummary::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discovery_job_target_scans() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let job_repo = factory.discovery_job_repository();
+
+        let org = create_test_organization(&factory, "Test Org Target Scans")
+            .await
+            .unwrap();
+        let now = chrono::Utc::now();
+        let earlier = now - chrono::Duration::days(2);
+        let targets = vec!["192.0.2.1".to_string(), "192.0.2.2".to_string()];
+
+        job_repo
+            .record_target_scans(org.id, JobType::PortScan, &targets, now)
+            .await
+            .expect("Failed to record target scans");
+        // An older scan, or a repeated target, doesn't move the last scan back
+        job_repo
+            .record_target_scans(
+                org.id,
+                JobType::PortScan,
+                &[targets[0].clone(), targets[0].clone()],
+                earlier,
+            )
+            .await
+            .expect("Failed to record older target scan");
+
+        let mut lookup = targets.clone();
+        lookup.push("192.0.2.3".to_string());
+        let last_scans = job_repo
+            .last_target_scans(org.id, JobType::PortScan, &lookup)
+            .await
+            .expect("Failed to get last target scans");
+        assert_eq!(last_scans.len(), 2);
+        for target in &targets {
+            let at = last_scans[target];
+            assert!((at - now).num_milliseconds().abs() < 1, "{target} at {at}");
+        }
+
+        // Scans are tracked per job type
+        let dns_scans = job_repo
+            .last_target_scans(org.id, JobType::DnsEnum, &lookup)
+            .await
+            .expect("Failed to get last DNS scans");
+        assert!(dns_scans.is_empty());
     }
 }
@@ -0,0 +1,331 @@
+#[cfg(test)]
+mod tests {
+    use backend::models::AssetFilter;
+    use discovery::port_scan::DiscoveredPort;
+    use discovery::results::{
+        DiscoveredDomain, DiscoveredIp, DiscoveredWebResource, DiscoveryResult, TechnologyFinding,
+    };
+    use infrastructure::{
+        repositories::factory::RepositoryFactory,
+        utils::testing::{create_test_organization, setup_test_db},
+    };
+    use shared::types::AssetType;
+    use uuid::Uuid;
+
+    fn discovery_result() -> DiscoveryResult {
+        DiscoveryResult {
+            domains: vec![DiscoveredDomain {
+                domain_name: "WWW.Example.com.".to_string(),
+                source: "dns_enum".to_string(),
+            }],
+            ip_addresses: vec![DiscoveredIp {
+                ip_address: "192.0.2.10".parse().unwrap(),
+                source: "dns_lookup".to_string(),
+            }],
+            ports: vec![DiscoveredPort {
+                ip_address: "192.0.2.10".parse().unwrap(),
+                port: 443,
+                protocol: "TCP".to_string(),
+                status: "OPEN".to_string(),
+                service_name: Some("https".to_string()),
+                banner: None,
+                http_status: Some(200),
+                http_title: None,
+                tls_info: None,
+                source: "port_scan".to_string(),
+            }],
+            web_resources: vec![DiscoveredWebResource {
+                url: "https://www.example.com".to_string(),
+                status_code: 200,
+                title: Some("Example".to_string()),
+                technologies: vec!["Nginx".to_string(), "WordPress 6.4".to_string()],
+                source: "web_crawl".to_string(),
+                screenshot_path: None,
+                content_hash: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    async fn count_rows(factory: &RepositoryFactory, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(factory.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_persist_discovery_result_dedupes_existing_assets() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org = create_test_organization(&factory, "Persist Discovery Org")
+            .await
+            .unwrap();
+
+        let persisted = factory
+            .persist_discovery_result(org.id, &discovery_result())
+            .await
+            .expect("Failed to persist discovery result");
+        assert_eq!(
+            (persisted.assets, persisted.ports, persisted.technologies),
+            (3, 1, 2)
+        );
+        assert_eq!(persisted.asset_ids.len(), 3);
+
+        // A second run refreshes the same rows
+        factory
+            .persist_discovery_result(org.id, &discovery_result())
+            .await
+            .expect("Failed to persist discovery result twice");
+        assert_eq!(count_rows(&factory, "assets").await, 3);
+        assert_eq!(count_rows(&factory, "ports").await, 1);
+        assert_eq!(count_rows(&factory, "technologies").await, 2);
+
+        let domains = factory
+            .asset_repository()
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::Domain),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(domains.len(), 1);
+        assert_eq!(domains[0].value, "www.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_persist_discovery_result_canonicalizes_values() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org = create_test_organization(&factory, "Canonical Discovery Org")
+            .await
+            .unwrap();
+
+        factory
+            .persist_discovery_result(org.id, &discovery_result())
+            .await
+            .expect("Failed to persist discovery result");
+
+        // The same assets written differently are refreshed, not duplicated
+        let mut result = discovery_result();
+        result.domains[0].domain_name = "https://www.example.com/login".to_string();
+        result.web_resources[0].url = "HTTPS://WWW.Example.com:443/#top".to_string();
+        factory
+            .persist_discovery_result(org.id, &result)
+            .await
+            .expect("Failed to persist discovery result again");
+        assert_eq!(count_rows(&factory, "assets").await, 3);
+
+        let web_apps = factory
+            .asset_repository()
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::WebApp),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(web_apps.len(), 1);
+        assert_eq!(web_apps[0].value, "https://www.example.com");
+
+        // A value that can't be canonicalized fails the whole run
+        let mut result = discovery_result();
+        result.web_resources[0].url = "https://".to_string();
+        let persisted = factory.persist_discovery_result(org.id, &result).await;
+        assert!(matches!(persisted, Err(backend::Error::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_canonical_asset_values_migration_merges_duplicates() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org = create_test_organization(&factory, "Canonical Migration Org")
+            .await
+            .unwrap();
+
+        // Rows as discovery runs stored them before values were canonicalized
+        let insert_asset = |asset_type: &'static str,
+                            value: &'static str,
+                            first_seen: &'static str,
+                            attributes: serde_json::Value| {
+            sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO assets (organization_id, asset_type, value, first_seen, last_seen, attributes)
+                VALUES ($1, $2, $3, $4::timestamptz, $4::timestamptz, $5)
+                RETURNING id
+                "#,
+            )
+            .bind(org.id)
+            .bind(asset_type)
+            .bind(value)
+            .bind(first_seen)
+            .bind(attributes)
+            .fetch_one(factory.pool())
+        };
+        let kept = insert_asset(
+            "WEBAPP",
+            "https://www.example.com",
+            "2025-01-01T00:00:00Z",
+            serde_json::json!({ "source": "web_crawl", "title": "Old" }),
+        )
+        .await
+        .unwrap();
+        let duplicate = insert_asset(
+            "WEBAPP",
+            "HTTPS://WWW.Example.com/",
+            "2025-02-01T00:00:00Z",
+            serde_json::json!({ "title": "New" }),
+        )
+        .await
+        .unwrap();
+        insert_asset(
+            "DOMAIN",
+            "WWW.Example.com.",
+            "2025-01-01T00:00:00Z",
+            serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+
+        for (asset_id, port) in [(kept, 443), (duplicate, 443), (duplicate, 8443)] {
+            sqlx::query(
+                "INSERT INTO ports (asset_id, port_number, protocol) VALUES ($1, $2, 'TCP')",
+            )
+            .bind(asset_id)
+            .bind(port)
+            .execute(factory.pool())
+            .await
+            .unwrap();
+        }
+        sqlx::query("INSERT INTO asset_tags (asset_id, tag) VALUES ($1, 'critical')")
+            .bind(duplicate)
+            .execute(factory.pool())
+            .await
+            .unwrap();
+
+        sqlx::raw_sql(include_str!(
+            "../../../migrations/20250504000000_canonical_asset_values.sql"
+        ))
+        .execute(factory.pool())
+        .await
+        .expect("Failed to run migration");
+
+        let assets: Vec<(Uuid, String, serde_json::Value)> =
+            sqlx::query_as("SELECT id, value, attributes FROM assets ORDER BY asset_type")
+                .fetch_all(factory.pool())
+                .await
+                .unwrap();
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].1, "www.example.com");
+        assert_eq!(assets[1].0, kept);
+        assert_eq!(assets[1].1, "https://www.example.com");
+        assert_eq!(
+            assets[1].2,
+            serde_json::json!({ "source": "web_crawl", "title": "New" })
+        );
+
+        let ports: Vec<i32> = sqlx::query_scalar(
+            "SELECT port_number FROM ports WHERE asset_id = $1 ORDER BY port_number",
+        )
+        .bind(kept)
+        .fetch_all(factory.pool())
+        .await
+        .unwrap();
+        assert_eq!(ports, vec![443, 8443]);
+        assert_eq!(count_rows(&factory, "ports").await, 2);
+
+        let tags: Vec<String> =
+            sqlx::query_scalar("SELECT tag FROM asset_tags WHERE asset_id = $1")
+                .bind(kept)
+                .fetch_all(factory.pool())
+                .await
+                .unwrap();
+        assert_eq!(tags, vec!["critical".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_persist_discovery_result_rolls_back_on_failure() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org = create_test_organization(&factory, "Rollback Discovery Org")
+            .await
+            .unwrap();
+
+        // Technology findings are written last; one pointing at a missing
+        // asset fails on its foreign key
+        let mut result = discovery_result();
+        result.technologies.push(TechnologyFinding {
+            asset_id: Uuid::new_v4(),
+            name: "OpenSSH".to_string(),
+            version: Some("9.6".to_string()),
+            category: None,
+            evidence: "banner".to_string(),
+        });
+
+        let persisted = factory.persist_discovery_result(org.id, &result).await;
+        assert!(persisted.is_err());
+
+        assert_eq!(count_rows(&factory, "assets").await, 0);
+        assert_eq!(count_rows(&factory, "ports").await, 0);
+        assert_eq!(count_rows(&factory, "technologies").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_import_nmap_xml_persists_hosts_and_ports() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org = create_test_organization(&factory, "Nmap Import Org")
+            .await
+            .unwrap();
+
+        let xml = include_str!("../../discovery/tests/fixtures/import/nmap.xml");
+        let persisted = factory
+            .import_nmap_xml(org.id, xml)
+            .await
+            .expect("Failed to import nmap XML");
+        // Two hosts and the domain given on the command line
+        assert_eq!(
+            (persisted.assets, persisted.ports, persisted.technologies),
+            (3, 7, 0)
+        );
+
+        let ips = factory
+            .asset_repository()
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::IPAddress),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
+            .await
+            .unwrap();
+        let host = ips.iter().find(|ip| ip.value == "192.0.2.10").unwrap();
+        let ssh: (Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT service_name, banner FROM ports WHERE asset_id = $1 AND port_number = 22",
+        )
+        .bind(host.id)
+        .fetch_one(factory.pool())
+        .await
+        .unwrap();
+        assert_eq!(ssh.0.as_deref(), Some("ssh"));
+        assert_eq!(
+            ssh.1.as_deref(),
+            Some("OpenSSH 9.6p1 Ubuntu 3ubuntu13 (Ubuntu Linux; protocol 2.0)")
+        );
+
+        let invalid = factory.import_nmap_xml(org.id, "<nmaprun>").await;
+        assert!(matches!(invalid, Err(backend::Error::Validation(_))));
+    }
+}
@@ -0,0 +1,49 @@
+use backend::Result;
+use chrono::{Duration, Utc};
+use infrastructure::{
+    database::migrations::Migrator, repositories::RepositoryFactory,
+    utils::testing::create_test_organization,
+};
+use sqlx::PgPool;
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_notification_log_suppresses_within_window(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let log_repo = factory.notification_log_repository();
+    let org = create_test_organization(&factory, "Notification Log Org").await?;
+    let other_org = create_test_organization(&factory, "Other Notification Log Org").await?;
+
+    let window = Duration::hours(24);
+    let sent_at = Utc::now();
+    let claim = |org_id, fingerprint: &'static str, at| {
+        let log_repo = log_repo.clone();
+        async move {
+            log_repo
+                .claim_notification(org_id, fingerprint, "new_critical_asset", at, window)
+                .await
+        }
+    };
+
+    assert!(claim(org.id, "asset:a", sent_at).await?);
+
+    // The same alert within the window is held back, others aren't
+    assert!(!claim(org.id, "asset:a", sent_at + Duration::hours(23)).await?);
+    assert!(claim(org.id, "asset:b", sent_at + Duration::hours(23)).await?);
+    assert!(claim(other_org.id, "asset:a", sent_at + Duration::hours(23)).await?);
+
+    // Once the window has passed it's sent again, starting a new window
+    assert!(claim(org.id, "asset:a", sent_at + Duration::hours(25)).await?);
+    assert!(!claim(org.id, "asset:a", sent_at + Duration::hours(26)).await?);
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use backend::{
+    models::{Asset, Vulnerability},
+    services::{NotificationServiceImpl, SummaryStats},
+    NotificationPeriod, NotificationService, Result,
+};
+use infrastructure::{
+    database::migrations::Migrator,
+    repositories::RepositoryFactory,
+    utils::testing::{create_test_asset, create_test_organization},
+};
+use shared::types::{AssetType, Severity, VulnerabilityStatus};
+use sqlx::PgPool;
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+// Helper to create a vulnerability first seen `days_ago` days ago
+async fn create_vulnerability(
+    factory: &RepositoryFactory,
+    pool: &PgPool,
+    asset: &Asset,
+    severity: Severity,
+    days_ago: i64,
+) -> Result<Vulnerability> {
+    let vuln = Vulnerability::new(
+        asset.id,
+        None, // port_id
+        format!("{:?} finding", severity),
+        None, // description
+        severity,
+        None, // cve_id
+        None, // evidence
+        None, // remediation
+    );
+    let vuln = factory
+        .vulnerability_repository()
+        .create_vulnerability(&vuln)
+        .await?;
+
+    sqlx::query("UPDATE vulnerabilities SET first_seen = $2 WHERE id = $1")
+        .bind(vuln.id)
+        .bind(chrono::Utc::now() - chrono::Duration::days(days_ago))
+        .execute(pool)
+        .await?;
+    Ok(vuln)
+}
+
+// Helper to backdate when an asset was first seen
+async fn backdate_asset(pool: &PgPool, asset: &Asset, days_ago: i64) -> Result<()> {
+    sqlx::query("UPDATE assets SET first_seen = $2 WHERE id = $1")
+        .bind(asset.id)
+        .bind(chrono::Utc::now() - chrono::Duration::days(days_ago))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_summary_report_counts_activity_in_period(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool.clone());
+    let service = NotificationServiceImpl::new(
+        factory.asset_repository(),
+        factory.vulnerability_repository(),
+    );
+
+    let org = create_test_organization(&factory, "Summary Report Org").await?;
+    let other_org = create_test_organization(&factory, "Other Summary Report Org").await?;
+
+    let today = create_test_asset(&factory, org.id, AssetType::Domain, "today.example.com").await?;
+    let this_week =
+        create_test_asset(&factory, org.id, AssetType::Domain, "week.example.com").await?;
+    backdate_asset(&pool, &this_week, 3).await?;
+    let this_month =
+        create_test_asset(&factory, org.id, AssetType::Domain, "month.example.com").await?;
+    backdate_asset(&pool, &this_month, 20).await?;
+    let elsewhere =
+        create_test_asset(&factory, other_org.id, AssetType::Domain, "example.org").await?;
+
+    create_vulnerability(&factory, &pool, &today, Severity::Critical, 0).await?;
+    create_vulnerability(&factory, &pool, &today, Severity::High, 0).await?;
+    create_vulnerability(&factory, &pool, &today, Severity::Medium, 0).await?;
+    create_vulnerability(&factory, &pool, &this_week, Severity::High, 3).await?;
+    create_vulnerability(&factory, &pool, &elsewhere, Severity::Critical, 0).await?;
+
+    // Found last month and resolved just now
+    let mut resolved =
+        create_vulnerability(&factory, &pool, &this_month, Severity::Low, 20).await?;
+    resolved.status = VulnerabilityStatus::Closed;
+    factory
+        .vulnerability_repository()
+        .update_vulnerability(&resolved)
+        .await?;
+
+    let daily = service
+        .build_summary_report(org.id, NotificationPeriod::Daily)
+        .await?;
+    assert_eq!(
+        daily.stats,
+        SummaryStats {
+            new_vulnerabilities: 3,
+            resolved_vulnerabilities: 1,
+            critical_vulnerabilities: 1,
+            high_vulnerabilities: 1,
+            new_assets: 1,
+        }
+    );
+
+    let weekly = service
+        .build_summary_report(org.id, NotificationPeriod::Weekly)
+        .await?;
+    assert_eq!(weekly.subject, "[EASM] Weekly Security Summary Report");
+    assert_eq!(weekly.until - weekly.since, chrono::Duration::weeks(1));
+    for line in [
+        "New vulnerabilities: 4\n",
+        "Resolved vulnerabilities: 1\n",
+        "New critical vulnerabilities: 1\n",
+        "New high vulnerabilities: 2\n",
+        "New assets: 2\n",
+    ] {
+        assert!(weekly.text_body.contains(line), "missing {:?}", line);
+    }
+    assert!(weekly
+        .html_body
+        .contains("<tr><th>New vulnerabilities</th><td>4</td></tr>"));
+    assert!(weekly
+        .html_body
+        .contains("<tr><th>New assets</th><td>2</td></tr>"));
+
+    let monthly = service
+        .build_summary_report(org.id, NotificationPeriod::Monthly)
+        .await?;
+    assert_eq!(monthly.stats.new_vulnerabilities, 5);
+    assert_eq!(monthly.stats.new_assets, 3);
+
+    assert!(
+        service
+            .send_summary_report(org.id, NotificationPeriod::Weekly)
+            .await?
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_batch_notification_groups_by_organization(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool.clone());
+    let service = NotificationServiceImpl::new(
+        factory.asset_repository(),
+        factory.vulnerability_repository(),
+    );
+
+    let org = create_test_organization(&factory, "Batch Org").await?;
+    let other_org = create_test_organization(&factory, "Other Batch Org").await?;
+
+    let mut vulnerabilities = Vec::new();
+    for value in ["a.batch.com", "b.batch.com", "c.batch.com"] {
+        let asset = create_test_asset(&factory, org.id, AssetType::Domain, value).await?;
+        vulnerabilities
+            .push(create_vulnerability(&factory, &pool, &asset, Severity::High, 0).await?);
+    }
+    // Reported twice in the same run
+    vulnerabilities.push(vulnerabilities[0].clone());
+
+    // Every vulnerability lands in a single digest for the organization
+    let digests = service.group_by_organization(&vulnerabilities).await?;
+    assert_eq!(digests.len(), 1);
+    assert_eq!(digests[&org.id].len(), 3);
+
+    let elsewhere =
+        create_test_asset(&factory, other_org.id, AssetType::Domain, "batch.org").await?;
+    vulnerabilities
+        .push(create_vulnerability(&factory, &pool, &elsewhere, Severity::High, 0).await?);
+
+    let digests = service.group_by_organization(&vulnerabilities).await?;
+    assert_eq!(digests.len(), 2);
+    assert_eq!(digests[&org.id].len(), 3);
+    assert_eq!(digests[&other_org.id].len(), 1);
+
+    assert!(
+        service
+            .notify_new_vulnerabilities_batch(&vulnerabilities)
+            .await?
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_identical_alert_suppressed_within_window(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool.clone());
+    let service = NotificationServiceImpl::new(
+        factory.asset_repository(),
+        factory.vulnerability_repository(),
+    )
+    .with_notification_log(
+        factory.notification_log_repository(),
+        chrono::Duration::hours(24),
+    );
+
+    let org = create_test_organization(&factory, "Flapping Org").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "flap.example.com").await?;
+
+    // A flapping service reports the same finding under a new ID each time
+    let first = create_vulnerability(&factory, &pool, &asset, Severity::High, 0).await?;
+    let repeat = create_vulnerability(&factory, &pool, &asset, Severity::High, 0).await?;
+
+    assert!(service.notify_new_vulnerability(&first).await?);
+    assert!(!service.notify_new_vulnerability(&repeat).await?);
+
+    // Also held back when it comes in as part of a batch
+    assert!(
+        !service
+            .notify_new_vulnerabilities_batch(std::slice::from_ref(&repeat))
+            .await?
+    );
+
+    // Sent again once the window has passed
+    sqlx::query("UPDATE notification_log SET sent_at = sent_at - INTERVAL '25 hours'")
+        .execute(&pool)
+        .await?;
+    assert!(service.notify_new_vulnerability(&repeat).await?);
+
+    Ok(())
+}
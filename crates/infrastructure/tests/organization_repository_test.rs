@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use backend::models::Organization;
+    use backend::{models::Organization, NotificationPeriod};
     use infrastructure::{repositories::factory::RepositoryFactory, utils::testing::setup_test_db};
 
     #[tokio::test]
@@ -123,4 +123,36 @@ mod tests {
             "Should fail on duplicate organization name"
         );
     }
+
+    #[tokio::test]
+    async fn test_organization_repository_summary_report_tracking() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org_repo = factory.organization_repository();
+
+        let mut org = org_repo
+            .create_organization(&Organization::new("Report Org".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(org.notification_period, NotificationPeriod::Daily);
+        assert!(org.last_report_sent_at.is_none());
+
+        org.notification_period = NotificationPeriod::Weekly;
+        org_repo.update_organization(&org).await.unwrap();
+
+        let sent_at = chrono::Utc::now();
+        assert!(org_repo.record_report_sent(org.id, sent_at).await.unwrap());
+
+        let found = org_repo.get_organization(org.id).await.unwrap();
+        assert_eq!(found.notification_period, NotificationPeriod::Weekly);
+        assert_eq!(
+            found.last_report_sent_at.map(|at| at.timestamp()),
+            Some(sent_at.timestamp())
+        );
+
+        assert!(!org_repo
+            .record_report_sent(uuid::Uuid::new_v4(), sent_at)
+            .await
+            .unwrap());
+    }
 }
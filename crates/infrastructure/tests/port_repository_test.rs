@@ -143,3 +143,123 @@ async fn test_port_repository_filters(pool: PgPool) -> Result<()> {
 
     Ok(())
 }
+
+#[sqlx::test]
+async fn test_port_repository_upsert_ports_bulk(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let port_repo = factory.port_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::IPAddress, "192.168.1.1").await?;
+
+    // Insert
+    let ports = vec![
+        Port::new(asset.id, 22, Protocol::TCP, Some("ssh".to_string()), None),
+        Port::new(asset.id, 80, Protocol::TCP, Some("http".to_string()), None),
+        Port::new(asset.id, 53, Protocol::UDP, Some("dns".to_string()), None),
+    ];
+    let inserted = port_repo.upsert_ports_bulk(asset.id, &ports).await?;
+    assert_eq!(inserted.len(), 3);
+    assert!(inserted.iter().all(|p| p.asset_id == asset.id));
+
+    // Update: a port already recorded keeps its ID and first_seen, and takes
+    // the new status and banner
+    let ssh = inserted
+        .iter()
+        .find(|p| p.port_number == 22)
+        .unwrap()
+        .clone();
+    let mut rescanned = Port::new(
+        asset.id,
+        22,
+        Protocol::TCP,
+        None,
+        Some("SSH-2.0-OpenSSH_9.6".to_string()),
+    );
+    rescanned.status = PortStatus::Filtered;
+    let updated = port_repo.upsert_ports_bulk(asset.id, &[rescanned]).await?;
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].id, ssh.id);
+    assert_eq!(updated[0].status, PortStatus::Filtered);
+    assert_eq!(updated[0].banner.as_deref(), Some("SSH-2.0-OpenSSH_9.6"));
+    assert_eq!(updated[0].service_name.as_deref(), Some("ssh"));
+    assert_eq!(
+        updated[0].first_seen.timestamp_micros(),
+        ssh.first_seen.timestamp_micros()
+    );
+    assert!(updated[0].last_seen >= ssh.last_seen);
+
+    let count = port_repo
+        .count_ports(Some(asset.id), None, None, None)
+        .await?;
+    assert_eq!(count, 3);
+
+    // Repeats within a batch collapse to the last, per port and protocol
+    let batch = vec![
+        Port::new(asset.id, 443, Protocol::TCP, Some("http".to_string()), None),
+        Port::new(asset.id, 443, Protocol::UDP, Some("quic".to_string()), None),
+        Port::new(
+            asset.id,
+            443,
+            Protocol::TCP,
+            Some("https".to_string()),
+            None,
+        ),
+    ];
+    let upserted = port_repo.upsert_ports_bulk(asset.id, &batch).await?;
+    assert_eq!(upserted.len(), 2);
+    let tcp = upserted
+        .iter()
+        .find(|p| p.port_number == 443 && p.protocol == Protocol::TCP)
+        .unwrap();
+    assert_eq!(tcp.service_name.as_deref(), Some("https"));
+
+    let count = port_repo
+        .count_ports(Some(asset.id), None, None, None)
+        .await?;
+    assert_eq!(count, 5);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_port_repository_upsert_ports_bulk_dedupes_runs(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let port_repo = factory.port_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::IPAddress, "192.168.1.1").await?;
+
+    let scan = || {
+        (1..=200)
+            .map(|port| Port::new(asset.id, port, Protocol::TCP, None, None))
+            .collect::<Vec<_>>()
+    };
+
+    // The same port twice in one run is stored once
+    let mut first_run = scan();
+    first_run.push(Port::new(asset.id, 1, Protocol::TCP, None, None));
+    let first = port_repo.upsert_ports_bulk(asset.id, &first_run).await?;
+    assert_eq!(first.len(), 200);
+
+    // A second run refreshes the same rows
+    let second = port_repo.upsert_ports_bulk(asset.id, &scan()).await?;
+    assert_eq!(second.len(), 200);
+
+    let mut first_ids: Vec<_> = first.iter().map(|p| p.id).collect();
+    let mut second_ids: Vec<_> = second.iter().map(|p| p.id).collect();
+    first_ids.sort();
+    second_ids.sort();
+    assert_eq!(first_ids, second_ids);
+
+    let count = port_repo
+        .count_ports(Some(asset.id), None, None, None)
+        .await?;
+    assert_eq!(count, 200);
+
+    Ok(())
+}
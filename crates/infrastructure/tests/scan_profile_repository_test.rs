@@ -0,0 +1,83 @@
+use backend::{models::ScanProfile, Error, Result};
+use infrastructure::{
+    database::migrations::Migrator, repositories::RepositoryFactory,
+    utils::testing::create_test_organization,
+};
+use shared::types::JobType;
+use sqlx::PgPool;
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_scan_profile_repository_basic_operations(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let profile_repo = factory.scan_profile_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+
+    let profile = ScanProfile::new(
+        org.id,
+        "Web only".to_string(),
+        vec![JobType::WebCrawl, JobType::VulnScan],
+        2,
+        vec![80, 443, 8443],
+        600,
+    );
+
+    // Test create
+    let created = profile_repo.create_profile(&profile).await?;
+    assert_eq!(created.organization_id, Some(org.id));
+    assert_eq!(created.methods, vec![JobType::WebCrawl, JobType::VulnScan]);
+    assert_eq!(created.ports, vec![80, 443, 8443]);
+
+    // Names are unique within an organization, ignoring case
+    let duplicate = ScanProfile::new(
+        org.id,
+        "WEB ONLY".to_string(),
+        vec![JobType::WebCrawl],
+        1,
+        vec![80],
+        60,
+    );
+    assert!(matches!(
+        profile_repo.create_profile(&duplicate).await,
+        Err(Error::Conflict(_))
+    ));
+
+    // Test get by ID and by name
+    let fetched = profile_repo.get_profile(created.id).await?;
+    assert_eq!(fetched.name, "Web only");
+    let by_name = profile_repo.get_profile_by_name(org.id, "web ONLY").await?;
+    assert_eq!(by_name.map(|p| p.id), Some(created.id));
+    assert!(profile_repo
+        .get_profile_by_name(org.id, "Quick")
+        .await?
+        .is_none());
+
+    // Test update
+    let mut to_update = fetched.clone();
+    to_update.depth = 3;
+    to_update.ports = vec![443];
+    let updated = profile_repo.update_profile(&to_update).await?;
+    assert_eq!(updated.depth, 3);
+    assert_eq!(updated.ports, vec![443]);
+
+    // Test list
+    let profiles = profile_repo.list_profiles(org.id).await?;
+    assert_eq!(profiles.len(), 1);
+
+    // Test delete
+    let deleted = profile_repo.delete_profile(created.id).await?;
+    assert!(deleted);
+    assert!(profile_repo.list_profiles(org.id).await?.is_empty());
+
+    Ok(())
+}
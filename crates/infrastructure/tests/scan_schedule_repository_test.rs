@@ -0,0 +1,69 @@
+use backend::{models::ScanSchedule, Result};
+use infrastructure::{
+    database::migrations::Migrator, repositories::RepositoryFactory,
+    utils::testing::create_test_organization,
+};
+use shared::types::JobType;
+use sqlx::PgPool;
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_scan_schedule_repository_basic_operations(pool: PgPool) -> Result<()> {
+    // Run migrations first
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let schedule_repo = factory.scan_schedule_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+
+    // Create a nightly DNS enumeration schedule
+    let schedule = ScanSchedule::new(
+        org.id,
+        "example.com".to_string(),
+        JobType::DnsEnum,
+        "0 0 2 * * *".to_string(),
+    );
+
+    // Test create
+    let created = schedule_repo.create_schedule(&schedule).await?;
+    assert_eq!(created.target, "example.com");
+    assert_eq!(created.job_type, JobType::DnsEnum);
+    assert!(created.enabled);
+    assert!(created.last_run_at.is_none());
+
+    // Test get
+    let fetched = schedule_repo.get_schedule(created.id).await?;
+    assert_eq!(fetched.id, created.id);
+    assert_eq!(fetched.cron_expression, "0 0 2 * * *");
+
+    // Test update
+    let mut to_update = fetched.clone();
+    to_update.enabled = false;
+    to_update.last_run_at = Some(chrono::Utc::now());
+    let updated = schedule_repo.update_schedule(&to_update).await?;
+    assert!(!updated.enabled);
+    assert!(updated.last_run_at.is_some());
+
+    // Disabled schedules are not handed to the scheduler
+    let enabled = schedule_repo.list_enabled_schedules().await?;
+    assert!(enabled.iter().all(|s| s.id != created.id));
+
+    // Test list and count
+    let schedules = schedule_repo.list_schedules(Some(org.id), 10, 0).await?;
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedule_repo.count_schedules(Some(org.id)).await?, 1);
+
+    // Test delete
+    let deleted = schedule_repo.delete_schedule(created.id).await?;
+    assert!(deleted);
+    assert_eq!(schedule_repo.count_schedules(Some(org.id)).await?, 0);
+
+    Ok(())
+}
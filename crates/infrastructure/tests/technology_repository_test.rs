@@ -1,9 +1,14 @@
-use backend::{models::Technology, Result};
+use backend::{
+    models::{Technology, TechnologyCount},
+    Result,
+};
+use chrono::Duration;
 use infrastructure::{
     database::migrations::Migrator,
     repositories::RepositoryFactory,
     utils::testing::{create_test_asset, create_test_organization},
 };
+use serde_json::json;
 use shared::types::AssetType;
 use sqlx::PgPool;
 
@@ -158,3 +163,269 @@ async fn test_technology_repository_filters(pool: PgPool) -> Result<()> {
 
     Ok(())
 }
+
+#[sqlx::test]
+async fn test_technology_repository_list_asset_technologies(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let tech_repo = factory.technology_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "example.com").await?;
+    let other = create_test_asset(&factory, org.id, AssetType::Domain, "other.com").await?;
+
+    // The other asset's technologies sort first, so filtering after a limit
+    // would leave this asset's out
+    for name in ["Apache", "Bootstrap", "Caddy"] {
+        tech_repo
+            .create_technology(&Technology::new(other.id, name.to_string(), None, None))
+            .await?;
+    }
+    for name in ["Nginx", "PHP", "WordPress"] {
+        tech_repo
+            .create_technology(&Technology::new(asset.id, name.to_string(), None, None))
+            .await?;
+    }
+
+    let names = |technologies: Vec<Technology>| {
+        technologies
+            .into_iter()
+            .map(|technology| technology.name)
+            .collect::<Vec<_>>()
+    };
+    let first_page = tech_repo.list_asset_technologies(asset.id, 2, 0).await?;
+    assert_eq!(names(first_page), vec!["Nginx", "PHP"]);
+    let second_page = tech_repo.list_asset_technologies(asset.id, 2, 2).await?;
+    assert_eq!(names(second_page), vec!["WordPress"]);
+
+    // An asset without technologies has none, rather than its organization's
+    let empty = create_test_asset(&factory, org.id, AssetType::Domain, "empty.com").await?;
+    assert!(tech_repo
+        .list_asset_technologies(empty.id, 10, 0)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_technology_repository_upsert_refreshes_existing(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let tech_repo = factory.technology_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "example.com").await?;
+
+    let first = Technology::new(
+        asset.id,
+        "Nginx".to_string(),
+        Some("1.20".to_string()),
+        Some("WebServer".to_string()),
+    );
+    let inserted = tech_repo.upsert_technology(&first).await?;
+    assert_eq!(inserted.id, first.id);
+
+    // A rescan without a version or category keeps the stored ones
+    let rescan = Technology {
+        last_seen: first.last_seen + Duration::hours(1),
+        ..Technology::new(asset.id, "Nginx".to_string(), None, None)
+    };
+    let upserted = tech_repo.upsert_technology(&rescan).await?;
+    assert_eq!(upserted.id, inserted.id);
+    assert_eq!(upserted.version, Some("1.20".to_string()));
+    assert_eq!(upserted.category, Some("WebServer".to_string()));
+    assert!(upserted.last_seen > inserted.last_seen);
+
+    let count = tech_repo
+        .count_technologies(Some(asset.id), None, None)
+        .await?;
+    assert_eq!(count, 1);
+
+    let history = factory
+        .asset_history_repository()
+        .list_asset_history(asset.id, 10, 0)
+        .await?;
+    assert!(history.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_technology_repository_upsert_records_version_change(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let tech_repo = factory.technology_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "example.com").await?;
+
+    let original = Technology::new(
+        asset.id,
+        "WordPress".to_string(),
+        Some("5.8".to_string()),
+        Some("CMS".to_string()),
+    );
+    let inserted = tech_repo.upsert_technology(&original).await?;
+
+    let bumped = Technology::new(
+        asset.id,
+        "WordPress".to_string(),
+        Some("6.4".to_string()),
+        None,
+    );
+    let upserted = tech_repo.upsert_technology(&bumped).await?;
+    assert_eq!(upserted.id, inserted.id);
+    assert_eq!(upserted.version, Some("6.4".to_string()));
+    assert_eq!(upserted.category, Some("CMS".to_string()));
+
+    let technologies = tech_repo
+        .list_technologies(Some(asset.id), None, None, 10, 0)
+        .await?;
+    assert_eq!(technologies.len(), 1);
+
+    let history = factory
+        .asset_history_repository()
+        .list_asset_history(asset.id, 10, 0)
+        .await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].field, "technologies.WordPress.version");
+    assert_eq!(history[0].old_value, json!("5.8"));
+    assert_eq!(history[0].new_value, json!("6.4"));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_technology_repository_distribution(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let tech_repo = factory.technology_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let other_org = create_test_organization(&factory, "Other Organization").await?;
+    let asset1 = create_test_asset(&factory, org.id, AssetType::Domain, "example1.com").await?;
+    let asset2 = create_test_asset(&factory, org.id, AssetType::Domain, "example2.com").await?;
+    let asset3 = create_test_asset(&factory, org.id, AssetType::Domain, "example3.com").await?;
+    let other_asset =
+        create_test_asset(&factory, other_org.id, AssetType::Domain, "other.com").await?;
+
+    for (asset_id, name, category) in [
+        (asset1.id, "Nginx", Some("WebServer")),
+        (asset2.id, "nginx", Some("WebServer")),
+        (asset3.id, "Nginx", Some("WebServer")),
+        (asset1.id, "WordPress", Some("CMS")),
+        (asset2.id, "WordPress", Some("CMS")),
+        (asset1.id, "PHP", Some("ProgrammingLanguage")),
+        (asset3.id, "jQuery", None),
+        (other_asset.id, "Apache", Some("WebServer")),
+    ] {
+        tech_repo
+            .create_technology(&Technology::new(
+                asset_id,
+                name.to_string(),
+                None,
+                category.map(str::to_string),
+            ))
+            .await?;
+    }
+
+    let distribution = tech_repo.technology_distribution(org.id).await?;
+
+    let counts = |counts: Vec<TechnologyCount>| -> Vec<(String, usize)> {
+        counts.into_iter().map(|c| (c.name, c.count)).collect()
+    };
+    assert_eq!(
+        counts(distribution.by_name),
+        vec![
+            ("Nginx".to_string(), 3),
+            ("WordPress".to_string(), 2),
+            ("PHP".to_string(), 1),
+            ("jQuery".to_string(), 1),
+        ]
+    );
+    assert_eq!(
+        counts(distribution.by_category),
+        vec![
+            ("WebServer".to_string(), 3),
+            ("CMS".to_string(), 2),
+            ("ProgrammingLanguage".to_string(), 1),
+            ("Uncategorized".to_string(), 1),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_technology_repository_list_organization_technologies(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+
+    let factory = RepositoryFactory::new(pool);
+    let tech_repo = factory.technology_repository();
+
+    let org = create_test_organization(&factory, "Test Organization").await?;
+    let other_org = create_test_organization(&factory, "Other Organization").await?;
+    let asset1 = create_test_asset(&factory, org.id, AssetType::Domain, "example1.com").await?;
+    let asset2 = create_test_asset(&factory, org.id, AssetType::Domain, "example2.com").await?;
+    let other_asset =
+        create_test_asset(&factory, other_org.id, AssetType::Domain, "other.com").await?;
+
+    for (asset_id, name, category) in [
+        (asset1.id, "Nginx", "Web Server"),
+        (asset2.id, "nginx", "Web Server"),
+        (asset1.id, "WordPress", "CMS"),
+        (other_asset.id, "Nginx", "Web Server"),
+    ] {
+        tech_repo
+            .create_technology(&Technology::new(
+                asset_id,
+                name.to_string(),
+                None,
+                Some(category.to_string()),
+            ))
+            .await?;
+    }
+
+    let page = tech_repo
+        .list_organization_technologies(org.id, None, None, 10, 0)
+        .await?;
+    assert_eq!(page.total, 3);
+    let listed: Vec<(&str, &str)> = page
+        .items
+        .iter()
+        .map(|t| (t.technology.name.as_str(), t.asset_value.as_str()))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("Nginx", "example1.com"),
+            ("nginx", "example2.com"),
+            ("WordPress", "example1.com"),
+        ]
+    );
+
+    // Searches and categories ignore case
+    let page = tech_repo
+        .list_organization_technologies(org.id, Some("GIN".to_string()), None, 10, 0)
+        .await?;
+    assert_eq!(page.total, 2);
+    let page = tech_repo
+        .list_organization_technologies(org.id, None, Some("cms".to_string()), 10, 0)
+        .await?;
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].technology.name, "WordPress");
+
+    // The total is kept on pages past the end
+    let page = tech_repo
+        .list_organization_technologies(org.id, None, None, 10, 10)
+        .await?;
+    assert!(page.items.is_empty());
+    assert_eq!(page.total, 3);
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use backend::{
+    models::Technology,
+    services::{StaticVulnerabilityFeed, TechnologyServiceImpl},
+    Result, TechnologyService,
+};
+use infrastructure::{
+    database::migrations::Migrator,
+    repositories::RepositoryFactory,
+    utils::testing::{create_test_asset, create_test_organization},
+};
+use shared::types::{AssetType, Severity};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const FIXTURE_FEED: &str = include_str!("fixtures/vulnerability_feed.json");
+
+// Helper function to run migrations before tests
+async fn setup_database(pool: &PgPool) -> Result<()> {
+    let migrator = Migrator::new(pool.clone());
+    migrator.run_migrations().await.unwrap();
+    Ok(())
+}
+
+// Helper to build a service matching against the fixture feed
+fn create_service(factory: &RepositoryFactory) -> TechnologyServiceImpl {
+    TechnologyServiceImpl::new(factory.technology_repository(), factory.asset_repository())
+        .with_vulnerability_feed(Arc::new(
+            StaticVulnerabilityFeed::from_json(FIXTURE_FEED).unwrap(),
+        ))
+        .with_vulnerability_repository(factory.vulnerability_repository())
+}
+
+#[sqlx::test]
+async fn test_apache_2_4_49_maps_to_path_traversal_cve(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+    let factory = RepositoryFactory::new(pool.clone());
+    let service = create_service(&factory);
+
+    let org = create_test_organization(&factory, "Known Vulns Org").await?;
+    let asset =
+        create_test_asset(&factory, org.id, AssetType::Domain, "apache.example.com").await?;
+    let technology = Technology::new(
+        asset.id,
+        "Apache".to_string(),
+        Some("2.4.49".to_string()),
+        Some("Web Server".to_string()),
+    );
+
+    let known = service.find_known_vulnerabilities(&technology).await?;
+    let cves: Vec<&str> = known.iter().map(|k| k.cve_id.as_str()).collect();
+    assert_eq!(cves, vec!["CVE-2021-41773"]);
+    assert_eq!(known[0].severity, Severity::High);
+
+    // Another release and a technology without a version match nothing
+    let earlier = Technology {
+        version: Some("2.4.48".to_string()),
+        ..technology.clone()
+    };
+    assert!(service
+        .find_known_vulnerabilities(&earlier)
+        .await?
+        .is_empty());
+    let unversioned = Technology {
+        version: None,
+        ..technology.clone()
+    };
+    assert!(service
+        .find_known_vulnerabilities(&unversioned)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_known_vulnerabilities_recorded_on_asset_once(pool: PgPool) -> Result<()> {
+    setup_database(&pool).await?;
+    let factory = RepositoryFactory::new(pool.clone());
+    let service = create_service(&factory);
+
+    let org = create_test_organization(&factory, "Recorded Vulns Org").await?;
+    let asset = create_test_asset(&factory, org.id, AssetType::Domain, "httpd.example.com").await?;
+    let technology = Technology::new(
+        asset.id,
+        "Apache".to_string(),
+        Some("2.4.49".to_string()),
+        None,
+    );
+
+    let created = service.record_known_vulnerabilities(&technology).await?;
+    assert_eq!(created.len(), 1);
+    let vulnerability = &created[0];
+    assert_eq!(vulnerability.asset_id, asset.id);
+    assert_eq!(vulnerability.cve_id.as_deref(), Some("CVE-2021-41773"));
+    assert_eq!(vulnerability.title, "Apache HTTP Server path traversal");
+    assert_eq!(vulnerability.cvss_score, Some(7.5));
+    assert_eq!(vulnerability.evidence["affected_technology"], "Apache");
+
+    let stored = factory
+        .vulnerability_repository()
+        .get_vulnerability(vulnerability.id)
+        .await?;
+    assert_eq!(stored.cve_id.as_deref(), Some("CVE-2021-41773"));
+
+    // The asset already has the CVE, so running again records nothing
+    assert!(service
+        .record_known_vulnerabilities(&technology)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
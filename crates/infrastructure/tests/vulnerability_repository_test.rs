@@ -6,6 +6,7 @@ mod tests {
         utils::testing::{create_test_asset, create_test_organization, setup_test_db},
     };
     use shared::types::{AssetType, Severity, VulnerabilityStatus, ID};
+    use std::collections::HashMap;
 
     // Helper to create a test vulnerability
     async fn create_test_vulnerability(
@@ -127,7 +128,7 @@ mod tests {
 
         // List all for Asset 1
         let vulns_asset1 = vuln_repo
-            .list_vulnerabilities(Some(asset1.id), None, None, None, limit, offset)
+            .list_vulnerabilities(Some(asset1.id), None, None, None, false, limit, offset)
             .await
             .expect("Failed to list vulns for asset 1");
         assert_eq!(vulns_asset1.len(), 3);
@@ -139,6 +140,7 @@ mod tests {
                 None,
                 Some(Severity::High),
                 None,
+                false,
                 limit,
                 offset,
             )
@@ -154,6 +156,7 @@ mod tests {
                 None,
                 None,
                 Some(VulnerabilityStatus::Open),
+                false,
                 limit,
                 offset,
             )
@@ -172,6 +175,7 @@ mod tests {
                 None,
                 Some(Severity::Medium),
                 Some(VulnerabilityStatus::Open),
+                false,
                 limit,
                 offset,
             )
@@ -182,30 +186,275 @@ mod tests {
 
         // Count all for Asset 1
         let count_asset1 = vuln_repo
-            .count_vulnerabilities(Some(asset1.id), None, None, None)
+            .count_vulnerabilities(Some(asset1.id), None, None, None, false)
             .await
             .expect("Failed to count vulns for asset 1");
         assert_eq!(count_asset1, 3);
 
         // Count Open status for Asset 1
         let count_open_asset1 = vuln_repo
-            .count_vulnerabilities(Some(asset1.id), None, None, Some(VulnerabilityStatus::Open))
+            .count_vulnerabilities(
+                Some(asset1.id),
+                None,
+                None,
+                Some(VulnerabilityStatus::Open),
+                false,
+            )
             .await
             .expect("Failed to count open vulns for asset 1");
         assert_eq!(count_open_asset1, 2);
 
         // Count High severity for Asset 1
         let count_high_asset1 = vuln_repo
-            .count_vulnerabilities(Some(asset1.id), None, Some(Severity::High), None)
+            .count_vulnerabilities(Some(asset1.id), None, Some(Severity::High), None, false)
             .await
             .expect("Failed to count high vulns for asset 1");
         assert_eq!(count_high_asset1, 1);
 
         // Count all vulnerabilities across all assets (no filters)
         let count_all = vuln_repo
-            .count_vulnerabilities(None, None, None, None)
+            .count_vulnerabilities(None, None, None, None, false)
             .await
             .expect("Failed to count all vulns");
         assert_eq!(count_all, 4);
     }
+
+    #[tokio::test]
+    async fn test_vulnerability_repository_bulk_update_status_is_org_scoped() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let vuln_repo = factory.vulnerability_repository();
+
+        let org = create_test_organization(&factory, "Bulk Vuln Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Bulk Vuln Org")
+            .await
+            .unwrap();
+        let asset = create_test_asset(&factory, org.id, AssetType::Domain, "mine.example.com")
+            .await
+            .unwrap();
+        let other_asset = create_test_asset(
+            &factory,
+            other_org.id,
+            AssetType::Domain,
+            "theirs.example.com",
+        )
+        .await
+        .unwrap();
+
+        let mine1 = create_test_vulnerability(&factory, &asset, "Mine 1", Severity::High).await;
+        let mine2 = create_test_vulnerability(&factory, &asset, "Mine 2", Severity::Low).await;
+        let untouched =
+            create_test_vulnerability(&factory, &asset, "Not listed", Severity::Low).await;
+        let theirs =
+            create_test_vulnerability(&factory, &other_asset, "Theirs", Severity::High).await;
+
+        // The other organization's vulnerability is ignored
+        let updated = vuln_repo
+            .bulk_update_status(
+                org.id,
+                &[mine1.id, mine2.id, theirs.id],
+                VulnerabilityStatus::Closed,
+            )
+            .await
+            .expect("Failed to bulk update vulnerabilities");
+        assert_eq!(updated, 2);
+
+        for id in [mine1.id, mine2.id] {
+            let vuln = vuln_repo.get_vulnerability(id).await.unwrap();
+            assert_eq!(vuln.status, VulnerabilityStatus::Closed);
+            assert!(vuln.resolved_at.is_some());
+        }
+        for id in [untouched.id, theirs.id] {
+            let vuln = vuln_repo.get_vulnerability(id).await.unwrap();
+            assert_eq!(vuln.status, VulnerabilityStatus::Open);
+            assert!(vuln.resolved_at.is_none());
+        }
+
+        // Reopening clears the resolution time
+        let updated = vuln_repo
+            .bulk_update_status(org.id, &[mine1.id], VulnerabilityStatus::Open)
+            .await
+            .unwrap();
+        assert_eq!(updated, 1);
+        let reopened = vuln_repo.get_vulnerability(mine1.id).await.unwrap();
+        assert_eq!(reopened.status, VulnerabilityStatus::Open);
+        assert!(reopened.resolved_at.is_none());
+
+        // So does starting work on it again
+        let updated = vuln_repo
+            .bulk_update_status(org.id, &[mine2.id], VulnerabilityStatus::InProgress)
+            .await
+            .unwrap();
+        assert_eq!(updated, 1);
+        let in_progress = vuln_repo.get_vulnerability(mine2.id).await.unwrap();
+        assert_eq!(in_progress.status, VulnerabilityStatus::InProgress);
+        assert!(in_progress.resolved_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_vulnerability_repository_count_by_severity() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let vuln_repo = factory.vulnerability_repository();
+
+        let org = create_test_organization(&factory, "Severity Count Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Severity Count Org")
+            .await
+            .unwrap();
+        let asset = create_test_asset(&factory, org.id, AssetType::Domain, "bands.example.com")
+            .await
+            .unwrap();
+        let other_asset = create_test_asset(
+            &factory,
+            other_org.id,
+            AssetType::Domain,
+            "other-bands.example.com",
+        )
+        .await
+        .unwrap();
+
+        create_test_vulnerability(&factory, &asset, "Critical 1", Severity::Critical).await;
+        create_test_vulnerability(&factory, &asset, "High 1", Severity::High).await;
+        create_test_vulnerability(&factory, &asset, "High 2", Severity::High).await;
+        create_test_vulnerability(&factory, &asset, "Low 1", Severity::Low).await;
+        let mut closed = create_test_vulnerability(&factory, &asset, "Low 2", Severity::Low).await;
+        closed.status = VulnerabilityStatus::Closed;
+        vuln_repo.update_vulnerability(&closed).await.unwrap();
+        let deleted =
+            create_test_vulnerability(&factory, &asset, "Deleted", Severity::Medium).await;
+        vuln_repo.delete_vulnerability(deleted.id).await.unwrap();
+        create_test_vulnerability(&factory, &other_asset, "Theirs", Severity::Info).await;
+
+        // Closed vulnerabilities count, deleted ones and other organizations' don't
+        let counts = vuln_repo
+            .count_by_severity(org.id, None)
+            .await
+            .expect("Failed to count vulnerabilities by severity");
+        assert_eq!(
+            counts,
+            HashMap::from([
+                (Severity::Critical, 1),
+                (Severity::High, 2),
+                (Severity::Low, 2),
+            ])
+        );
+
+        // Unless only open ones are asked for
+        let open = vuln_repo
+            .count_by_severity(org.id, Some(VulnerabilityStatus::Open))
+            .await
+            .expect("Failed to count open vulnerabilities by severity");
+        assert_eq!(
+            open,
+            HashMap::from([
+                (Severity::Critical, 1),
+                (Severity::High, 2),
+                (Severity::Low, 1),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vulnerability_repository_counts_by_day() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool.clone());
+        let vuln_repo = factory.vulnerability_repository();
+
+        let org = create_test_organization(&factory, "Trend Vuln Org")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Trend Vuln Org")
+            .await
+            .unwrap();
+        let asset = create_test_asset(&factory, org.id, AssetType::Domain, "trend.example.com")
+            .await
+            .unwrap();
+        let other_asset = create_test_asset(
+            &factory,
+            other_org.id,
+            AssetType::Domain,
+            "trend.example.org",
+        )
+        .await
+        .unwrap();
+
+        // Vulnerabilities are stamped as first seen on creation, so backdate them
+        let now = chrono::Utc::now();
+        for (asset, title, days_ago) in [
+            (&asset, "Three days ago", 3),
+            (&asset, "Also three days ago", 3),
+            (&asset, "Yesterday", 1),
+            (&asset, "Before the window", 60),
+            (&other_asset, "Another organization's", 1),
+        ] {
+            let vuln = create_test_vulnerability(&factory, asset, title, Severity::Medium).await;
+            sqlx::query("UPDATE vulnerabilities SET first_seen = $2 WHERE id = $1")
+                .bind(vuln.id)
+                .bind(now - chrono::Duration::days(days_ago))
+                .execute(&db_pool)
+                .await
+                .unwrap();
+        }
+
+        let counts = vuln_repo
+            .vuln_counts_by_day(org.id, now - chrono::Duration::days(30))
+            .await
+            .expect("Failed to count vulnerabilities by day");
+        let day = |days_ago| (now - chrono::Duration::days(days_ago)).date_naive();
+        assert_eq!(counts, vec![(day(3), 2), (day(1), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_vulnerability_repository_list_vulnerabilities_page() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let vuln_repo = factory.vulnerability_repository();
+
+        let org = create_test_organization(&factory, "Paged Vuln Org")
+            .await
+            .unwrap();
+        let asset = create_test_asset(&factory, org.id, AssetType::Domain, "paged.example.com")
+            .await
+            .unwrap();
+        let other_asset =
+            create_test_asset(&factory, org.id, AssetType::Domain, "other.example.com")
+                .await
+                .unwrap();
+
+        for i in 0..4 {
+            create_test_vulnerability(&factory, &asset, &format!("High {}", i), Severity::High)
+                .await;
+        }
+        create_test_vulnerability(&factory, &asset, "Low", Severity::Low).await;
+        create_test_vulnerability(&factory, &other_asset, "Elsewhere", Severity::High).await;
+
+        // The total covers every matching vulnerability, not just the page
+        let page = vuln_repo
+            .list_vulnerabilities_page(Some(asset.id), None, None, None, false, 2, 0)
+            .await
+            .expect("Failed to list page of vulnerabilities");
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert!(page.items.iter().all(|v| v.asset_id == asset.id));
+
+        let beyond = vuln_repo
+            .list_vulnerabilities_page(Some(asset.id), None, None, None, false, 2, 10)
+            .await
+            .unwrap();
+        assert_eq!(beyond.total, 5);
+        assert!(beyond.items.is_empty());
+
+        // Filtering by organization covers all of its assets
+        let high = vuln_repo
+            .list_vulnerabilities_page(Some(org.id), None, Some(Severity::High), None, false, 3, 0)
+            .await
+            .unwrap();
+        assert_eq!(high.total, 5);
+        assert_eq!(high.items.len(), 3);
+        assert!(high.items.iter().all(|v| v.severity == Severity::High));
+    }
 }
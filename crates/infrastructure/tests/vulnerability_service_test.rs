@@ -0,0 +1,190 @@
+#[cfg(test)]
+mod tests {
+    use backend::{
+        models::{Asset, Vulnerability},
+        services::VulnerabilityServiceImpl,
+        VulnerabilityService,
+    };
+    use infrastructure::{
+        repositories::factory::RepositoryFactory,
+        utils::testing::{create_test_asset, create_test_organization, setup_test_db},
+    };
+    use shared::types::{AssetType, Severity, VulnerabilityStatus};
+
+    // Helper to create an open vulnerability on an asset
+    async fn create_vulnerability(
+        factory: &RepositoryFactory,
+        asset: &Asset,
+        title: &str,
+        severity: Severity,
+        cve_id: Option<&str>,
+        technology: &str,
+    ) -> Vulnerability {
+        let vuln = Vulnerability::new(
+            asset.id,
+            None, // port_id
+            title.to_string(),
+            None, // description
+            severity,
+            cve_id.map(str::to_string),
+            Some(serde_json::json!({ "affected_technology": technology })),
+            None, // remediation
+        );
+        factory
+            .vulnerability_repository()
+            .create_vulnerability(&vuln)
+            .await
+            .expect("Failed to create test vulnerability")
+    }
+
+    #[tokio::test]
+    async fn test_correlate_groups_vulnerabilities_across_assets() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let service = VulnerabilityServiceImpl::new(
+            factory.vulnerability_repository(),
+            factory.asset_repository(),
+        );
+
+        let org = create_test_organization(&factory, "Test Org Vuln Groups")
+            .await
+            .unwrap();
+        let other_org = create_test_organization(&factory, "Other Org Vuln Groups")
+            .await
+            .unwrap();
+        let web1 = create_test_asset(&factory, org.id, AssetType::Domain, "web1.groups.com")
+            .await
+            .unwrap();
+        let web2 = create_test_asset(&factory, org.id, AssetType::Domain, "web2.groups.com")
+            .await
+            .unwrap();
+        let web3 = create_test_asset(&factory, org.id, AssetType::Domain, "web3.groups.com")
+            .await
+            .unwrap();
+        let other = create_test_asset(&factory, other_org.id, AssetType::Domain, "groups.org")
+            .await
+            .unwrap();
+
+        // The same CVE on three assets, reported with differing titles and
+        // severities, and twice on one asset
+        let log4shell = Some("CVE-2021-44228");
+        create_vulnerability(
+            &factory,
+            &web1,
+            "Log4Shell",
+            Severity::High,
+            log4shell,
+            "log4j",
+        )
+        .await;
+        create_vulnerability(
+            &factory,
+            &web2,
+            "Log4j RCE",
+            Severity::Critical,
+            log4shell,
+            "log4j",
+        )
+        .await;
+        create_vulnerability(
+            &factory,
+            &web3,
+            "Log4Shell",
+            Severity::Medium,
+            log4shell,
+            "log4j",
+        )
+        .await;
+        create_vulnerability(
+            &factory,
+            &web3,
+            "Log4Shell",
+            Severity::Low,
+            log4shell,
+            "log4j",
+        )
+        .await;
+
+        // No CVE, grouped by title
+        create_vulnerability(
+            &factory,
+            &web1,
+            "Directory listing",
+            Severity::Low,
+            None,
+            "nginx",
+        )
+        .await;
+        create_vulnerability(
+            &factory,
+            &web2,
+            "Directory listing",
+            Severity::Info,
+            None,
+            "nginx",
+        )
+        .await;
+
+        // Same title on a different technology is a separate group
+        create_vulnerability(
+            &factory,
+            &web3,
+            "Directory listing",
+            Severity::Medium,
+            None,
+            "apache",
+        )
+        .await;
+
+        // Closed vulnerabilities and other organizations are left out
+        let mut closed = create_vulnerability(
+            &factory,
+            &web1,
+            "Log4Shell",
+            Severity::Critical,
+            log4shell,
+            "log4j",
+        )
+        .await;
+        closed.status = VulnerabilityStatus::Closed;
+        factory
+            .vulnerability_repository()
+            .update_vulnerability(&closed)
+            .await
+            .unwrap();
+        create_vulnerability(
+            &factory,
+            &other,
+            "Log4Shell",
+            Severity::High,
+            log4shell,
+            "log4j",
+        )
+        .await;
+
+        let groups = service.correlate(org.id).await.unwrap();
+        assert_eq!(groups.len(), 3);
+
+        // Most severe group first
+        let log4j = &groups[0];
+        assert_eq!(log4j.cve_id.as_deref(), Some("CVE-2021-44228"));
+        assert_eq!(log4j.technology.as_deref(), Some("log4j"));
+        assert_eq!(log4j.affected_asset_count, 3);
+        assert_eq!(log4j.vulnerability_count, 4);
+        assert_eq!(log4j.highest_severity, Severity::Critical);
+
+        let apache = &groups[1];
+        assert_eq!(apache.title, "Directory listing");
+        assert_eq!(apache.technology.as_deref(), Some("apache"));
+        assert_eq!(apache.affected_asset_count, 1);
+        assert_eq!(apache.highest_severity, Severity::Medium);
+
+        let nginx = &groups[2];
+        assert_eq!(nginx.title, "Directory listing");
+        assert_eq!(nginx.cve_id, None);
+        assert_eq!(nginx.technology.as_deref(), Some("nginx"));
+        assert_eq!(nginx.affected_asset_count, 2);
+        assert_eq!(nginx.vulnerability_count, 2);
+        assert_eq!(nginx.highest_severity, Severity::Low);
+    }
+}
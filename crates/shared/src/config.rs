@@ -1,23 +1,107 @@
 #[cfg(feature = "backend")]
 use dotenvy::dotenv;
 use serde::Deserialize;
-#[cfg(feature = "backend")]
-use std::env;
 use std::net::IpAddr;
+#[cfg(feature = "backend")]
+use std::{env, fs, path::Path, str::FromStr};
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Config {
     pub database_url: String,
+    /// Largest number of connections kept in the database pool
+    pub database_max_connections: u32,
+    /// Seconds to wait for a pooled connection before failing a query
+    pub database_acquire_timeout_secs: u64,
+    /// Seconds an unused connection stays open, 0 to keep it indefinitely
+    pub database_idle_timeout_secs: u64,
+    /// Attempts to reach the database at startup before giving up
+    pub database_connect_attempts: u32,
     pub redis_url: Option<String>,
     pub host: IpAddr,
     pub port: u16,
+    /// PEM certificate chain the API serves HTTPS with. HTTPS is served
+    /// when this and `tls_key_path` are set, plain HTTP otherwise.
+    pub tls_cert_path: Option<String>,
+    /// PEM private key for `tls_cert_path`
+    pub tls_key_path: Option<String>,
+    /// Largest request body the API accepts, in bytes. Larger bodies are
+    /// refused with 413.
+    pub max_request_body_bytes: usize,
+    /// Seconds a request may take before the API gives up on it with 408
+    pub request_timeout_secs: u64,
+    /// IP addresses and CIDR ranges of the reverse proxies in front of the
+    /// API. `X-Forwarded-For` is only believed on connections from them;
+    /// otherwise the client address is the connection's peer.
+    pub trusted_proxies: Vec<String>,
     pub jwt_secret: String,
     pub jwt_expiration: i64,
+    /// Failed logins from one address for one account before it is locked
+    pub login_lockout_threshold: u32,
+    /// Failed logins for one account from any address before it is locked,
+    /// which stops guessing spread over many addresses
+    pub login_account_lockout_threshold: u32,
+    /// Seconds a locked account stays locked, and how long failed logins
+    /// count towards a lockout
+    pub login_lockout_secs: u64,
     pub environment: Environment,
     pub log_level: String,
+    pub log_format: LogFormat,
     pub max_concurrent_tasks: usize,
+    pub stale_asset_threshold_days: i64,
+    /// Seconds during which an identical alert for an organization isn't
+    /// sent again. Zero sends every alert.
+    pub notification_suppression_secs: u64,
+    /// Whether the API sets security headers such as `X-Frame-Options`
+    pub security_headers: bool,
+    /// `Content-Security-Policy` sent when security headers are enabled
+    pub content_security_policy: String,
+    /// Whether the API gzip/brotli-compresses responses for clients that
+    /// accept it. Disabling it can make traffic easier to inspect.
+    pub compression: bool,
+    /// IP-to-ASN dataset used to enrich discovered IPs with their network
+    /// owner, in the iptoasn.com TSV format. No enrichment when unset.
+    pub asn_database_path: Option<String>,
+    /// Shodan API key used to enrich scanned IPs with the ports, banners
+    /// and vulnerabilities Shodan has seen. No enrichment when unset.
+    pub shodan_api_key: Option<String>,
+    /// Directory web crawl jobs store screenshots of discovered pages in.
+    /// No screenshots are taken when unset.
+    pub screenshot_dir: Option<String>,
+    /// Proxy all outbound discovery traffic goes through, as an `http://`,
+    /// `https://`, `socks5://` or `socks5h://` URL. Port scans can only be
+    /// proxied over SOCKS5. Direct connections when unset.
+    pub proxy_url: Option<String>,
+    /// Port the tasks worker serves its `/metrics` on, on `host`. Not
+    /// served when unset.
+    pub worker_metrics_port: Option<u16>,
+    /// IP addresses and CIDR ranges no scan may target, on top of cloud
+    /// metadata endpoints, which are always refused
+    pub scan_blocklist: Vec<String>,
+    /// Whether private networks and other internal addresses (loopback,
+    /// link-local) may be scanned
+    pub scan_private_networks: bool,
+    /// Where discovered entities are streamed as they're stored. Not
+    /// streamed when unset.
+    pub output_sink: Option<OutputSinkKind>,
+    /// URL the `webhook` output sink POSTs events to
+    pub output_webhook_url: Option<String>,
+    /// File the `file` output sink appends NDJSON events to
+    pub output_file_path: Option<String>,
+    /// `host:port` of the brokers the `kafka` output sink connects through
+    pub output_kafka_brokers: Vec<String>,
+    /// Topic the `kafka` output sink produces events to
+    pub output_kafka_topic: String,
 }
 
+/// URL schemes accepted for `PROXY_URL`
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// Policy for an API that serves no scripts, styles or frames
+pub const DEFAULT_CONTENT_SECURITY_POLICY: &str = "default-src 'none'; frame-ancestors 'none'";
+
+/// 10 MiB, enough for bulk imports without letting one request hog memory
+pub const DEFAULT_MAX_REQUEST_BODY_BYTES: usize = 10 * 1024 * 1024;
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Environment {
@@ -26,61 +110,610 @@ pub enum Environment {
     Test,
 }
 
+/// How log lines are written
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregators
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(ConfigError::InvalidValue("LOG_FORMAT")),
+        }
+    }
+}
+
+/// Where discovery events are streamed to
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSinkKind {
+    /// POST each event to `output_webhook_url`
+    Webhook,
+    /// Append each event to the NDJSON file at `output_file_path`
+    File,
+    /// Produce each event to `output_kafka_topic`, in builds with Kafka support
+    Kafka,
+}
+
+impl std::str::FromStr for OutputSinkKind {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "webhook" => Ok(OutputSinkKind::Webhook),
+            "file" => Ok(OutputSinkKind::File),
+            "kafka" => Ok(OutputSinkKind::Kafka),
+            _ => Err(ConfigError::InvalidValue("OUTPUT_SINK")),
+        }
+    }
+}
+
+/// Topic discovery events are produced to when none is configured
+pub const DEFAULT_OUTPUT_KAFKA_TOPIC: &str = "easm.discovery";
+
+/// Settings read by `Config::from_file`. Every setting is optional so the
+/// environment and defaults can fill in the rest.
+#[cfg(feature = "backend")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    database_url: Option<String>,
+    database_max_connections: Option<u32>,
+    database_acquire_timeout_secs: Option<u64>,
+    database_idle_timeout_secs: Option<u64>,
+    database_connect_attempts: Option<u32>,
+    redis_url: Option<String>,
+    host: Option<IpAddr>,
+    port: Option<u16>,
+    tls_cert_path: Option<String>,
+    tls_key_path: Option<String>,
+    max_request_body_bytes: Option<usize>,
+    request_timeout_secs: Option<u64>,
+    trusted_proxies: Option<Vec<String>>,
+    jwt_secret: Option<String>,
+    jwt_expiration: Option<i64>,
+    login_lockout_threshold: Option<u32>,
+    login_account_lockout_threshold: Option<u32>,
+    login_lockout_secs: Option<u64>,
+    environment: Option<Environment>,
+    log_level: Option<String>,
+    log_format: Option<LogFormat>,
+    max_concurrent_tasks: Option<usize>,
+    stale_asset_threshold_days: Option<i64>,
+    notification_suppression_secs: Option<u64>,
+    security_headers: Option<bool>,
+    content_security_policy: Option<String>,
+    compression: Option<bool>,
+    asn_database_path: Option<String>,
+    shodan_api_key: Option<String>,
+    screenshot_dir: Option<String>,
+    proxy_url: Option<String>,
+    worker_metrics_port: Option<u16>,
+    scan_blocklist: Option<Vec<String>>,
+    scan_private_networks: Option<bool>,
+    output_sink: Option<OutputSinkKind>,
+    output_webhook_url: Option<String>,
+    output_file_path: Option<String>,
+    output_kafka_brokers: Option<Vec<String>>,
+    output_kafka_topic: Option<String>,
+}
+
 #[cfg(feature = "backend")]
 impl Config {
+    /// Load the configuration from environment variables (and `.env`), then
+    /// validate it. Every missing or invalid setting is reported at once.
     pub fn from_env() -> Result<Self, ConfigError> {
+        Self::load(FileConfig::default())
+    }
+
+    /// Load the configuration from a TOML file, then apply environment
+    /// variable overrides on top as `from_env` does. Settings are named like
+    /// the fields of `Config`, e.g. `database_url` or `port`, and any left
+    /// out of the file fall back to the environment or their defaults.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| {
+            ConfigError::new(format!(
+                "Failed to read config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let file = toml::from_str(&contents).map_err(|e| {
+            ConfigError::new(format!("Invalid config file {}: {}", path.display(), e))
+        })?;
+
+        Self::load(file)
+    }
+
+    /// Build the configuration from `file`, letting environment variables
+    /// take precedence, and validate it
+    fn load(file: FileConfig) -> Result<Self, ConfigError> {
         let _ = dotenv();
 
-        let database_url =
-            env::var("DATABASE_URL").map_err(|_| ConfigError::MissingEnv("DATABASE_URL"))?;
+        let mut problems = Vec::new();
 
-        let redis_url = env::var("REDIS_URL").ok();
+        let database_url = env::var("DATABASE_URL")
+            .ok()
+            .or(file.database_url)
+            .unwrap_or_default();
 
-        let host = env::var("HOST")
-            .unwrap_or_else(|_| "127.0.0.1".to_string())
-            .parse()
-            .map_err(|_| ConfigError::InvalidValue("HOST"))?;
+        let database_max_connections = parse_env(
+            "DATABASE_MAX_CONNECTIONS",
+            file.database_max_connections.unwrap_or(10),
+            &mut problems,
+        );
 
-        let port = env::var("PORT")
-            .unwrap_or_else(|_| "3000".to_string())
-            .parse()
-            .map_err(|_| ConfigError::InvalidValue("PORT"))?;
+        let database_acquire_timeout_secs = parse_env(
+            "DATABASE_ACQUIRE_TIMEOUT_SECS",
+            file.database_acquire_timeout_secs.unwrap_or(30),
+            &mut problems,
+        );
 
-        let jwt_secret =
-            env::var("JWT_SECRET").map_err(|_| ConfigError::MissingEnv("JWT_SECRET"))?;
+        // Default: 10 minutes
+        let database_idle_timeout_secs = parse_env(
+            "DATABASE_IDLE_TIMEOUT_SECS",
+            file.database_idle_timeout_secs.unwrap_or(600),
+            &mut problems,
+        );
 
-        let jwt_expiration = env::var("JWT_EXPIRATION")
-            .unwrap_or_else(|_| "86400".to_string()) // Default: 24 hours
-            .parse()
-            .map_err(|_| ConfigError::InvalidValue("JWT_EXPIRATION"))?;
+        let database_connect_attempts = parse_env(
+            "DATABASE_CONNECT_ATTEMPTS",
+            file.database_connect_attempts.unwrap_or(5),
+            &mut problems,
+        );
 
-        let environment = match env::var("ENVIRONMENT")
-            .unwrap_or_else(|_| "development".to_string())
-            .as_str()
-        {
-            "production" => Environment::Production,
-            "test" => Environment::Test,
-            _ => Environment::Development,
+        let redis_url = env::var("REDIS_URL").ok().or(file.redis_url);
+
+        let host = parse_env(
+            "HOST",
+            file.host.unwrap_or(IpAddr::from([127, 0, 0, 1])),
+            &mut problems,
+        );
+
+        let port = parse_env("PORT", file.port.unwrap_or(3000), &mut problems);
+
+        let tls_cert_path = env::var("TLS_CERT_PATH")
+            .ok()
+            .or(file.tls_cert_path)
+            .filter(|path| !path.trim().is_empty());
+
+        let tls_key_path = env::var("TLS_KEY_PATH")
+            .ok()
+            .or(file.tls_key_path)
+            .filter(|path| !path.trim().is_empty());
+
+        let max_request_body_bytes = parse_env(
+            "MAX_REQUEST_BODY_BYTES",
+            file.max_request_body_bytes
+                .unwrap_or(DEFAULT_MAX_REQUEST_BODY_BYTES),
+            &mut problems,
+        );
+
+        let request_timeout_secs = parse_env(
+            "REQUEST_TIMEOUT_SECS",
+            file.request_timeout_secs.unwrap_or(30),
+            &mut problems,
+        );
+
+        let trusted_proxies = env::var("TRUSTED_PROXIES")
+            .ok()
+            .map(|ranges| {
+                ranges
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|range| !range.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .or(file.trusted_proxies)
+            .unwrap_or_default();
+
+        let jwt_secret = env::var("JWT_SECRET")
+            .ok()
+            .or(file.jwt_secret)
+            .unwrap_or_default();
+
+        // Default: 24 hours
+        let jwt_expiration = parse_env(
+            "JWT_EXPIRATION",
+            file.jwt_expiration.unwrap_or(86400),
+            &mut problems,
+        );
+
+        let login_lockout_threshold = parse_env(
+            "LOGIN_LOCKOUT_THRESHOLD",
+            file.login_lockout_threshold.unwrap_or(5),
+            &mut problems,
+        );
+
+        let login_account_lockout_threshold = parse_env(
+            "LOGIN_ACCOUNT_LOCKOUT_THRESHOLD",
+            file.login_account_lockout_threshold.unwrap_or(20),
+            &mut problems,
+        );
+
+        // Default: 15 minutes
+        let login_lockout_secs = parse_env(
+            "LOGIN_LOCKOUT_SECS",
+            file.login_lockout_secs.unwrap_or(900),
+            &mut problems,
+        );
+
+        let environment = match env::var("ENVIRONMENT") {
+            Ok(environment) => match environment.as_str() {
+                "production" => Environment::Production,
+                "test" => Environment::Test,
+                _ => Environment::Development,
+            },
+            Err(_) => file.environment.unwrap_or(Environment::Development),
         };
 
-        let log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
+        let log_level = env::var("LOG_LEVEL")
+            .ok()
+            .or(file.log_level)
+            .unwrap_or_else(|| "info".to_string());
+
+        let log_format = parse_env(
+            "LOG_FORMAT",
+            file.log_format.unwrap_or_default(),
+            &mut problems,
+        );
+
+        let max_concurrent_tasks = parse_env(
+            "MAX_CONCURRENT_TASKS",
+            file.max_concurrent_tasks.unwrap_or(10),
+            &mut problems,
+        );
+
+        let stale_asset_threshold_days = parse_env(
+            "STALE_ASSET_THRESHOLD_DAYS",
+            file.stale_asset_threshold_days.unwrap_or(30),
+            &mut problems,
+        );
+
+        let notification_suppression_secs = parse_env(
+            "NOTIFICATION_SUPPRESSION_SECS",
+            file.notification_suppression_secs.unwrap_or(86400),
+            &mut problems,
+        );
+
+        let security_headers = parse_env(
+            "SECURITY_HEADERS",
+            file.security_headers.unwrap_or(true),
+            &mut problems,
+        );
+
+        let content_security_policy = env::var("CONTENT_SECURITY_POLICY")
+            .ok()
+            .or(file.content_security_policy)
+            .unwrap_or_else(|| DEFAULT_CONTENT_SECURITY_POLICY.to_string());
+
+        let compression = parse_env(
+            "COMPRESSION",
+            file.compression.unwrap_or(true),
+            &mut problems,
+        );
+
+        let asn_database_path = env::var("ASN_DATABASE_PATH")
+            .ok()
+            .or(file.asn_database_path);
+
+        let shodan_api_key = env::var("SHODAN_API_KEY")
+            .ok()
+            .or(file.shodan_api_key)
+            .filter(|key| !key.trim().is_empty());
+
+        let screenshot_dir = env::var("SCREENSHOT_DIR")
+            .ok()
+            .or(file.screenshot_dir)
+            .filter(|dir| !dir.trim().is_empty());
+
+        let proxy_url = env::var("PROXY_URL")
+            .ok()
+            .or(file.proxy_url)
+            .filter(|url| !url.trim().is_empty());
+
+        let worker_metrics_port = match env::var("WORKER_METRICS_PORT") {
+            Ok(port) => port.parse().map(Some).unwrap_or_else(|_| {
+                problems.push(format!(
+                    "WORKER_METRICS_PORT has an invalid value `{}`",
+                    port
+                ));
+                None
+            }),
+            Err(_) => file.worker_metrics_port,
+        };
 
-        let max_concurrent_tasks = env::var("MAX_CONCURRENT_TASKS")
-            .unwrap_or_else(|_| "10".to_string())
-            .parse()
-            .map_err(|_| ConfigError::InvalidValue("MAX_CONCURRENT_TASKS"))?;
+        let scan_blocklist = env::var("SCAN_BLOCKLIST")
+            .ok()
+            .map(|ranges| {
+                ranges
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|range| !range.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .or(file.scan_blocklist)
+            .unwrap_or_default();
 
-        Ok(Config {
+        let scan_private_networks = parse_env(
+            "SCAN_PRIVATE_NETWORKS",
+            file.scan_private_networks.unwrap_or(false),
+            &mut problems,
+        );
+
+        let output_sink = match env::var("OUTPUT_SINK") {
+            Ok(sink) if sink.trim().is_empty() => None,
+            Ok(sink) => sink.trim().parse().map(Some).unwrap_or_else(|_| {
+                problems.push(format!("OUTPUT_SINK has an invalid value `{}`", sink));
+                None
+            }),
+            Err(_) => file.output_sink,
+        };
+
+        let output_webhook_url = env::var("OUTPUT_WEBHOOK_URL")
+            .ok()
+            .or(file.output_webhook_url)
+            .filter(|url| !url.trim().is_empty());
+
+        let output_file_path = env::var("OUTPUT_FILE_PATH")
+            .ok()
+            .or(file.output_file_path)
+            .filter(|path| !path.trim().is_empty());
+
+        let output_kafka_brokers = env::var("OUTPUT_KAFKA_BROKERS")
+            .ok()
+            .map(|brokers| {
+                brokers
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|broker| !broker.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .or(file.output_kafka_brokers)
+            .unwrap_or_default();
+
+        let output_kafka_topic = env::var("OUTPUT_KAFKA_TOPIC")
+            .ok()
+            .or(file.output_kafka_topic)
+            .unwrap_or_else(|| DEFAULT_OUTPUT_KAFKA_TOPIC.to_string());
+
+        let config = Config {
             database_url,
+            database_max_connections,
+            database_acquire_timeout_secs,
+            database_idle_timeout_secs,
+            database_connect_attempts,
             redis_url,
             host,
             port,
+            tls_cert_path,
+            tls_key_path,
+            max_request_body_bytes,
+            request_timeout_secs,
+            trusted_proxies,
             jwt_secret,
             jwt_expiration,
+            login_lockout_threshold,
+            login_account_lockout_threshold,
+            login_lockout_secs,
             environment,
             log_level,
+            log_format,
             max_concurrent_tasks,
-        })
+            stale_asset_threshold_days,
+            notification_suppression_secs,
+            security_headers,
+            content_security_policy,
+            compression,
+            asn_database_path,
+            shodan_api_key,
+            screenshot_dir,
+            proxy_url,
+            worker_metrics_port,
+            scan_blocklist,
+            scan_private_networks,
+            output_sink,
+            output_webhook_url,
+            output_file_path,
+            output_kafka_brokers,
+            output_kafka_topic,
+        };
+
+        problems.extend(config.problems());
+        if !problems.is_empty() {
+            return Err(ConfigError::Invalid(problems));
+        }
+
+        Ok(config)
+    }
+
+    /// Check that the settings are usable, listing every problem found
+    pub fn validate(&self) -> ConfigResult<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.database_url.trim().is_empty() {
+            problems.push("DATABASE_URL must be set".to_string());
+        } else if !self.database_url.starts_with("postgres://")
+            && !self.database_url.starts_with("postgresql://")
+        {
+            problems.push(
+                "DATABASE_URL must be a postgres:// or postgresql:// connection URL".to_string(),
+            );
+        }
+
+        if self.database_max_connections == 0 {
+            problems.push("DATABASE_MAX_CONNECTIONS must be at least 1".to_string());
+        }
+
+        if self.database_acquire_timeout_secs == 0 {
+            problems.push("DATABASE_ACQUIRE_TIMEOUT_SECS must be at least 1".to_string());
+        }
+
+        if self.database_connect_attempts == 0 {
+            problems.push("DATABASE_CONNECT_ATTEMPTS must be at least 1".to_string());
+        }
+
+        if self.host.is_multicast() {
+            problems.push(format!(
+                "HOST must be a unicast address to listen on, got {}",
+                self.host
+            ));
+        }
+
+        if self.port == 0 {
+            problems.push("PORT must be between 1 and 65535".to_string());
+        }
+
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            problems.push("TLS_CERT_PATH and TLS_KEY_PATH must be set together".to_string());
+        }
+
+        if self.max_request_body_bytes == 0 {
+            problems.push("MAX_REQUEST_BODY_BYTES must be at least 1".to_string());
+        }
+
+        if self.request_timeout_secs == 0 {
+            problems.push("REQUEST_TIMEOUT_SECS must be at least 1".to_string());
+        }
+
+        if self.worker_metrics_port == Some(0) {
+            problems.push("WORKER_METRICS_PORT must be between 1 and 65535".to_string());
+        }
+
+        if self.jwt_secret.is_empty() {
+            problems.push("JWT_SECRET must be set".to_string());
+        }
+
+        if self.jwt_expiration <= 0 {
+            problems.push("JWT_EXPIRATION must be a positive number of seconds".to_string());
+        }
+
+        if self.login_lockout_threshold == 0 {
+            problems.push("LOGIN_LOCKOUT_THRESHOLD must be at least 1".to_string());
+        }
+
+        if self.login_account_lockout_threshold == 0 {
+            problems.push("LOGIN_ACCOUNT_LOCKOUT_THRESHOLD must be at least 1".to_string());
+        }
+
+        if self.login_lockout_secs == 0 {
+            problems.push("LOGIN_LOCKOUT_SECS must be at least 1".to_string());
+        }
+
+        if !is_valid_log_filter(&self.log_level) {
+            problems.push(format!(
+                "LOG_LEVEL must be a level (trace, debug, info, warn, error, off) or `target=level` directives, got `{}`",
+                self.log_level
+            ));
+        }
+
+        if self.max_concurrent_tasks == 0 {
+            problems.push("MAX_CONCURRENT_TASKS must be at least 1".to_string());
+        }
+
+        if self.stale_asset_threshold_days <= 0 {
+            problems.push("STALE_ASSET_THRESHOLD_DAYS must be at least 1".to_string());
+        }
+
+        // Sent as a header value, so it must be visible ASCII on one line
+        if self.security_headers
+            && !self
+                .content_security_policy
+                .bytes()
+                .all(|b| b == b' ' || b.is_ascii_graphic())
+        {
+            problems.push(
+                "CONTENT_SECURITY_POLICY must be printable ASCII without line breaks".to_string(),
+            );
+        }
+
+        if let Some(proxy_url) = &self.proxy_url {
+            let valid = PROXY_SCHEMES.iter().any(|scheme| {
+                proxy_url
+                    .strip_prefix(scheme)
+                    .and_then(|rest| rest.strip_prefix("://"))
+                    .is_some_and(|authority| !authority.is_empty())
+            });
+            if !valid {
+                problems.push(format!(
+                    "PROXY_URL must be an http, https, socks5 or socks5h URL, got `{}`",
+                    proxy_url
+                ));
+            }
+        }
+
+        for range in &self.trusted_proxies {
+            if !is_ip_range(range) {
+                problems.push(format!(
+                    "TRUSTED_PROXIES must list IP addresses or CIDR ranges, got `{}`",
+                    range
+                ));
+            }
+        }
+
+        for range in &self.scan_blocklist {
+            if !is_ip_range(range) {
+                problems.push(format!(
+                    "SCAN_BLOCKLIST must list IP addresses or CIDR ranges, got `{}`",
+                    range
+                ));
+            }
+        }
+
+        match self.output_sink {
+            Some(OutputSinkKind::Webhook) => {
+                let valid = self.output_webhook_url.as_deref().is_some_and(|url| {
+                    ["http://", "https://"]
+                        .iter()
+                        .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme))
+                });
+                if !valid {
+                    problems.push(
+                        "OUTPUT_WEBHOOK_URL must be an http or https URL for the webhook output sink"
+                            .to_string(),
+                    );
+                }
+            }
+            Some(OutputSinkKind::File) if self.output_file_path.is_none() => {
+                problems.push("OUTPUT_FILE_PATH must be set for the file output sink".to_string());
+            }
+            Some(OutputSinkKind::Kafka) => {
+                if self.output_kafka_brokers.is_empty() {
+                    problems.push(
+                        "OUTPUT_KAFKA_BROKERS must be set for the kafka output sink".to_string(),
+                    );
+                }
+                if self.output_kafka_topic.trim().is_empty() {
+                    problems.push("OUTPUT_KAFKA_TOPIC must not be empty".to_string());
+                }
+            }
+            _ => {}
+        }
+
+        problems
     }
 
     pub fn is_development(&self) -> bool {
@@ -96,6 +729,58 @@ impl Config {
     }
 }
 
+/// Parse an environment variable, falling back to `fallback` when it is
+/// unset. A value that doesn't parse is recorded in `problems`.
+#[cfg(feature = "backend")]
+fn parse_env<T: FromStr>(name: &str, fallback: T, problems: &mut Vec<String>) -> T {
+    match env::var(name) {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            problems.push(format!("{} has an invalid value `{}`", name, value));
+            fallback
+        }),
+        Err(_) => fallback,
+    }
+}
+
+/// Whether `range` is an IP address or a CIDR range, e.g. `10.0.0.0/8`
+#[cfg(feature = "backend")]
+fn is_ip_range(range: &str) -> bool {
+    let (address, prefix) = match range.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (range, None),
+    };
+    let Ok(address) = address.trim().parse::<IpAddr>() else {
+        return false;
+    };
+    let bits = if address.is_ipv4() { 32 } else { 128 };
+    prefix.is_none_or(|prefix| {
+        prefix
+            .trim()
+            .parse::<u8>()
+            .is_ok_and(|prefix| prefix <= bits)
+    })
+}
+
+/// Whether `filter` is a tracing filter made of levels and `target=level`
+/// directives, e.g. `info` or `warn,api=debug`
+#[cfg(feature = "backend")]
+fn is_valid_log_filter(filter: &str) -> bool {
+    const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+    let mut directives = filter
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .peekable();
+    directives.peek().is_some()
+        && directives.all(|directive| {
+            let level = directive
+                .rsplit_once('=')
+                .map_or(directive, |(_, level)| level);
+            LEVELS.contains(&level.to_lowercase().as_str())
+        })
+}
+
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
 pub enum ConfigError {
     #[error("Missing environment variable: {0}")]
@@ -104,6 +789,9 @@ pub enum ConfigError {
     #[error("Invalid value for environment variable: {0}")]
     InvalidValue(&'static str),
 
+    #[error("Invalid configuration: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+
     #[error("Configuration error: {0}")]
     Custom(String),
 }
@@ -1,5 +1,9 @@
 pub mod config;
 pub mod errors;
+#[cfg(feature = "backend")]
+pub mod logging;
+#[cfg(feature = "backend")]
+pub mod metrics;
 pub mod types;
 
 pub use config::*;
@@ -0,0 +1,31 @@
+use crate::config::{Config, LogFormat};
+use tracing::Subscriber;
+use tracing_subscriber::{fmt::MakeWriter, util::SubscriberInitExt, EnvFilter};
+
+/// Build the subscriber described by the `log_level` and `log_format`
+/// settings, writing to `writer`. JSON lines carry the current span and the
+/// full list of spans the event was recorded in.
+pub fn build_subscriber<W>(config: &Config, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(&config.log_level))
+        .with_writer(writer);
+
+    match config.log_format {
+        LogFormat::Text => Box::new(builder.finish()),
+        LogFormat::Json => Box::new(
+            builder
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .finish(),
+        ),
+    }
+}
+
+/// Install the configured subscriber as the global default, logging to stdout
+pub fn init_tracing(config: &Config) {
+    build_subscriber(config, std::io::stdout).init();
+}
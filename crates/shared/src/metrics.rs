@@ -0,0 +1,26 @@
+use std::sync::LazyLock;
+
+use prometheus::{Encoder, Registry, TextEncoder};
+
+/// Content type of the Prometheus text exposition format
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(|| {
+    Registry::new_custom(Some("easm".to_string()), None).expect("valid metrics prefix")
+});
+
+/// Registry every metric the process records is registered with, exported
+/// by `/metrics`
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+/// The metrics in `registry` in the Prometheus text format
+pub fn render(registry: &Registry) -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&registry.gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+    }
+
+    String::from_utf8(buffer).unwrap_or_default()
+}
@@ -56,12 +56,14 @@ pub enum Severity {
 #[serde(rename_all = "UPPERCASE")]
 pub enum VulnerabilityStatus {
     Open,
+    /// Acknowledged and being remediated
+    InProgress,
     Closed,
     AcceptedRisk,
     FalsePositive,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(Type))]
 #[cfg_attr(
     feature = "backend",
@@ -137,6 +139,19 @@ impl std::fmt::Display for JobStatus {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(Type))]
+#[cfg_attr(
+    feature = "backend",
+    sqlx(type_name = "VARCHAR", rename_all = "UPPERCASE")
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
 #[cfg_attr(feature = "backend", derive(Type))]
 #[cfg_attr(
@@ -166,6 +181,17 @@ impl std::str::FromStr for UserRole {
     }
 }
 
+impl std::fmt::Display for UserRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserRole::Admin => write!(f, "ADMIN"),
+            UserRole::Manager => write!(f, "MANAGER"),
+            UserRole::Analyst => write!(f, "ANALYST"),
+            UserRole::ReadOnly => write!(f, "READONLY"),
+        }
+    }
+}
+
 impl UserRole {
     /// Check if this role can perform admin operations
     pub fn can_admin(&self) -> bool {
@@ -211,8 +237,9 @@ pub type ID = Uuid;
 
 pub type Timestamp = chrono::DateTime<chrono::Utc>;
 
-/// Parameters for pagination
+/// Parameters for pagination, the first page of ten when omitted
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PaginationParams {
     pub page: u32,
     pub page_size: u32,
@@ -232,3 +259,79 @@ impl PaginationParams {
         (self.page.saturating_sub(1)) * self.page_size
     }
 }
+
+/// One page of a listing along with the number of items on all pages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Items matching the listing's filters, regardless of `limit` and `offset`
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+/// Direction of a [`Sort`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Order for a listing, by one of the fields it allows sorting on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sort<F> {
+    pub field: F,
+    pub order: SortOrder,
+}
+
+impl<F> Sort<F> {
+    pub fn new(field: F, order: SortOrder) -> Self {
+        Self { field, order }
+    }
+}
+
+/// Fields vulnerability listings can be sorted on. Severity sorts by rank,
+/// from info up to critical, rather than by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VulnerabilitySortField {
+    Severity,
+    Status,
+    CreatedAt,
+}
+
+/// Fields discovery job listings can be sorted on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobSortField {
+    Status,
+    CreatedAt,
+}
+
+/// What happened to the resource in a [`ResourceEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceEventType {
+    Created,
+    Updated,
+}
+
+/// Kind of resource a [`ResourceEvent`] is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Asset,
+    Vulnerability,
+}
+
+/// Live notice that one of an organization's assets or vulnerabilities was
+/// created or updated, so open lists know to refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceEvent {
+    #[serde(rename = "type")]
+    pub event_type: ResourceEventType,
+    pub org_id: ID,
+    pub resource: ResourceKind,
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use shared::config::{
+        Config, Environment, LogFormat, DEFAULT_CONTENT_SECURITY_POLICY,
+        DEFAULT_MAX_REQUEST_BODY_BYTES,
+    };
+    use shared::logging::build_subscriber;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    // Writer that collects everything the subscriber writes
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn config(log_format: LogFormat) -> Config {
+        Config {
+            database_url: "postgres://localhost/easm".into(),
+            database_max_connections: 10,
+            database_acquire_timeout_secs: 30,
+            database_idle_timeout_secs: 600,
+            database_connect_attempts: 5,
+            redis_url: None,
+            host: "127.0.0.1".parse().unwrap(),
+            port: 3000,
+            tls_cert_path: None,
+            tls_key_path: None,
+            max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            request_timeout_secs: 30,
+            trusted_proxies: Vec::new(),
+            jwt_secret: "secret".into(),
+            jwt_expiration: 86400,
+            login_lockout_threshold: 5,
+            login_account_lockout_threshold: 20,
+            login_lockout_secs: 900,
+            environment: Environment::Test,
+            log_level: "info".into(),
+            log_format,
+            max_concurrent_tasks: 10,
+            stale_asset_threshold_days: 30,
+            notification_suppression_secs: 86400,
+            security_headers: true,
+            content_security_policy: DEFAULT_CONTENT_SECURITY_POLICY.into(),
+            compression: true,
+            asn_database_path: None,
+            shodan_api_key: None,
+            screenshot_dir: None,
+            proxy_url: None,
+            worker_metrics_port: None,
+            scan_blocklist: Vec::new(),
+            scan_private_networks: false,
+            output_sink: None,
+            output_webhook_url: None,
+            output_file_path: None,
+            output_kafka_brokers: Vec::new(),
+            output_kafka_topic: "easm.discovery".into(),
+        }
+    }
+
+    // Log one event inside a span and return what was written
+    fn log_event(log_format: LogFormat) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = build_subscriber(&config(log_format), move || writer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("scan", job_id = 42);
+            let _entered = span.enter();
+            tracing::info!(port = 443, "Port open");
+        });
+
+        let output = captured.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_json_format_writes_json_lines_with_spans() {
+        let output = log_event(LogFormat::Json);
+
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["fields"]["message"], "Port open");
+        assert_eq!(line["fields"]["port"], 443);
+        assert_eq!(line["span"]["name"], "scan");
+        assert_eq!(line["span"]["job_id"], 42);
+        assert_eq!(line["spans"][0]["name"], "scan");
+    }
+
+    #[test]
+    fn test_text_format_is_the_default() {
+        assert_eq!(LogFormat::default(), LogFormat::Text);
+
+        let output = log_event(LogFormat::Text);
+        assert!(output.contains("Port open"));
+        assert!(serde_json::from_str::<serde_json::Value>(output.trim()).is_err());
+    }
+
+    #[test]
+    fn test_log_format_parses_case_insensitively() {
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!("TEXT".parse::<LogFormat>(), Ok(LogFormat::Text));
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+}
@@ -1,29 +1,94 @@
 use anyhow::Result;
-use backend::models::{Asset, DiscoveryJob};
-use backend::services::{AssetServiceImpl, DiscoveryServiceImpl};
-use backend::traits::AssetService;
+use backend::models::{Asset, DiscoveryJob, Vulnerability};
+use backend::services::{
+    AssetServiceImpl, DiscoveryServiceImpl, NotificationServiceImpl, VulnerabilityServiceImpl,
+};
+use backend::traits::{
+    AssetService, DiscoveryJobRepository, OrganizationRepository, VulnerabilityService,
+};
 use chrono::Utc;
+use discovery::asn::AsnDatabase;
+use discovery::cancellation::{is_cancelled, CancellationRegistry, ScanCancelled};
 use discovery::dns;
+use discovery::http_client::HttpClientConfig;
+use discovery::output::{emit_result, OutputSink};
+use discovery::plan::{plan_dns_enumeration, plan_port_scan, HostResolver, ScanPlan};
 use discovery::port_scan;
 use discovery::results::DiscoveryResult;
+use discovery::scope::ScanScope;
+use discovery::shodan::ShodanClient;
+use discovery::takeover::{default_fingerprints, TakeoverChecker};
+use discovery::tasks::{DiscoveryTask, DiscoveryTaskType};
+use discovery::vulnerability::DiscoveredVulnerability;
+use discovery::whois::{RdapClient, DEFAULT_RDAP_BASE_URL};
 use infrastructure::repositories::factory::RepositoryFactory;
-use shared::types::{AssetStatus, AssetType, JobStatus, JobType};
+use shared::types::{AssetStatus, AssetType, JobStatus, JobType, Severity, VulnerabilityStatus};
 use sqlx::PgPool;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-/// Process pending discovery jobs
+use crate::metrics::WorkerMetrics;
+
+/// How often a running job checks whether it was cancelled through the API
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Upper bound on the assets searched when attaching findings to them
+const FINDING_ASSET_LIMIT: usize = 10_000;
+
+/// Process pending discovery jobs, enriching discovered IPs with their
+/// network owner when an ASN database is loaded and scanned IPs with
+/// Shodan's data when a Shodan client is configured. Outbound requests and
+/// scans go through the proxy in `http`, if any. Web crawls screenshot the
+/// pages they find into `screenshot_dir`, if set. Jobs targeting anything
+/// outside `scope` or their organization's allowlist fail without scanning.
+/// Alerts identical to one sent within `notification_suppression` are held
+/// back. Discovered entities are streamed to `output` once stored, if set.
 /// Returns the number of jobs processed
-pub async fn process_pending_jobs(pool: &PgPool) -> Result<usize> {
+#[allow(clippy::too_many_arguments)]
+pub async fn process_pending_jobs(
+    pool: &PgPool,
+    registry: &CancellationRegistry,
+    asn_database: Option<&AsnDatabase>,
+    shodan: Option<&ShodanClient>,
+    http: &HttpClientConfig,
+    screenshot_dir: Option<&str>,
+    scope: &ScanScope,
+    notification_suppression: Duration,
+    output: Option<&dyn OutputSink>,
+) -> Result<usize> {
     let repo_factory = Arc::new(RepositoryFactory::new(pool.clone()));
 
     // Create services with the appropriate repositories
     let asset_repository = repo_factory.asset_repository();
     let discovery_job_repository = repo_factory.discovery_job_repository();
+    let organization_repository = repo_factory.organization_repository();
+
+    let vulnerability_repository = repo_factory.vulnerability_repository();
 
-    let asset_service = AssetServiceImpl::new(asset_repository.clone());
+    let asset_service = AssetServiceImpl::new(
+        asset_repository.clone(),
+        repo_factory.asset_history_repository(),
+    )
+    .with_vulnerability_repository(vulnerability_repository.clone());
+    let notification_service =
+        NotificationServiceImpl::new(asset_repository.clone(), vulnerability_repository.clone())
+            .with_notification_log(
+                repo_factory.notification_log_repository(),
+                chrono::Duration::from_std(notification_suppression)?,
+            );
     let discovery_service =
-        DiscoveryServiceImpl::new(asset_repository.clone(), discovery_job_repository);
+        DiscoveryServiceImpl::new(asset_repository.clone(), discovery_job_repository.clone())
+            .with_notification_service(Arc::new(notification_service));
+    let vulnerability_service =
+        VulnerabilityServiceImpl::new(vulnerability_repository, asset_repository.clone());
+
+    let metrics = WorkerMetrics::global();
+    metrics
+        .record_queue_depth(discovery_job_repository.as_ref())
+        .await;
 
     // Log that we're checking for jobs
     tracing::info!("Checking for pending jobs...");
@@ -46,17 +111,82 @@ pub async fn process_pending_jobs(pool: &PgPool) -> Result<usize> {
     for mut job in pending_jobs {
         tracing::info!("Processing job: {} ({:?})", job.id, job.job_type);
 
+        // Refuse out-of-scope targets before anything is sent their way
+        let job_scope = match scope_for_job(organization_repository.as_ref(), scope, &job).await {
+            Ok(job_scope) => job_scope,
+            Err(e) => {
+                tracing::warn!("Refusing job {}: {}", job.id, e);
+                job.status = JobStatus::Failed;
+                job.completed_at = Some(Utc::now());
+                job.logs = Some(format!("Error: {}", e));
+                metrics.record_job(job.job_type, job.status, Duration::ZERO);
+                discovery_service.update_job(&job).await?;
+                continue;
+            }
+        };
+
+        // A dry run stops at working out what the job would scan
+        if job.dry_run() {
+            let resolver = dns::DnsEnumerator::new().await?;
+            job.started_at = Some(Utc::now());
+            job.status = match plan_job(&job, &job_scope, &resolver).await {
+                Ok(plan) => {
+                    tracing::info!("Planned job {}: {}", job.id, plan.summary());
+                    job.logs = Some(plan.summary());
+                    if let Some(configuration) = job.configuration.as_object_mut() {
+                        configuration.insert("plan".to_string(), serde_json::to_value(&plan)?);
+                    }
+                    processed += 1;
+                    JobStatus::Completed
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to plan job {}: {}", job.id, e);
+                    job.logs = Some(format!("Error: {}", e));
+                    JobStatus::Failed
+                }
+            };
+            job.completed_at = Some(Utc::now());
+            metrics.record_job(job.job_type, job.status, Duration::ZERO);
+            discovery_service.update_job(&job).await?;
+            continue;
+        }
+
         // Update job status to running
         job.status = JobStatus::Running;
         job.started_at = Some(Utc::now());
         job = discovery_service.update_job(&job).await?;
+        let started = Instant::now();
+
+        // Watch for the job being cancelled through the API while it runs
+        let cancel = registry.register(job.id);
+        let watcher = tokio::spawn(watch_for_cancellation(
+            discovery_job_repository.clone(),
+            job.id,
+            cancel.clone(),
+        ));
 
         // Process the job based on type
         let result = match job.job_type {
             JobType::DnsEnum => {
                 if let Some(target) = &job.target {
-                    tracing::info!("Running DNS enumeration for {}", target);
-                    process_dns_enumeration(&asset_service, &job, target).await
+                    scan_unless_fresh(&discovery_service, &job, target, async {
+                        tracing::info!("Running DNS enumeration for {}", target);
+                        tokio::select! {
+                            result = process_dns_enumeration(
+                                &asset_service,
+                                &vulnerability_service,
+                                &discovery_service,
+                                discovery_job_repository.as_ref(),
+                                &job,
+                                target,
+                                asn_database,
+                                http,
+                                output,
+                            ) => result,
+                            _ = cancel.cancelled() => Err(ScanCancelled.into()),
+                        }
+                    })
+                    .await
                 } else {
                     Err(anyhow::anyhow!(
                         "No target specified for DNS enumeration job"
@@ -66,14 +196,42 @@ pub async fn process_pending_jobs(pool: &PgPool) -> Result<usize> {
             JobType::PortScan => {
                 if let Some(target) = &job.target {
                     tracing::info!("Running port scan for {}", target);
-                    process_port_scan(&asset_service, &job, target).await
+                    process_port_scan(
+                        &asset_service,
+                        &discovery_service,
+                        discovery_job_repository.as_ref(),
+                        &job,
+                        target,
+                        &job_scope,
+                        &cancel,
+                        asn_database,
+                        shodan,
+                        http,
+                        output,
+                    )
+                    .await
                 } else {
                     Err(anyhow::anyhow!("No target specified for port scan job"))
                 }
             }
             JobType::WebCrawl => {
-                tracing::warn!("Web crawl jobs not implemented yet");
-                Err(anyhow::anyhow!("Web crawl jobs not implemented yet"))
+                if let Some(target) = &job.target {
+                    tracing::info!("Running web crawl for {}", target);
+                    tokio::select! {
+                        result = process_web_crawl(
+                            &repo_factory,
+                            &discovery_service,
+                            &job,
+                            target,
+                            http,
+                            screenshot_dir,
+                            output,
+                        ) => result,
+                        _ = cancel.cancelled() => Err(ScanCancelled.into()),
+                    }
+                } else {
+                    Err(anyhow::anyhow!("No target specified for web crawl job"))
+                }
             }
             JobType::CertScan => {
                 tracing::warn!("Certificate transparency jobs not implemented yet");
@@ -89,20 +247,13 @@ pub async fn process_pending_jobs(pool: &PgPool) -> Result<usize> {
             }
         };
 
+        watcher.abort();
+        registry.remove(job.id);
+
         // Update job status based on result
-        job.completed_at = Some(Utc::now());
-        job.status = match &result {
-            Ok(_) => {
-                tracing::info!("Job {} completed successfully", job.id);
-                JobStatus::Completed
-            }
-            Err(e) => {
-                tracing::error!("Job {} failed: {}", job.id, e);
-                // Add error to logs
-                job.logs = Some(format!("Error: {}", e));
-                JobStatus::Failed
-            }
-        };
+        finish_job(&mut job, &result, cancel.is_cancelled());
+
+        metrics.record_job(job.job_type, job.status, started.elapsed());
 
         // Update the job
         discovery_service.update_job(&job).await?;
@@ -112,78 +263,587 @@ pub async fn process_pending_jobs(pool: &PgPool) -> Result<usize> {
         }
     }
 
+    metrics
+        .record_queue_depth(discovery_job_repository.as_ref())
+        .await;
+
     tracing::info!("Successfully processed {} jobs", processed);
     Ok(processed)
 }
 
+/// Run `scan` unless `job` is incremental and scanned `target` within its
+/// freshness window, recording the scan once it succeeds. Failing to look
+/// up or record past scans fails the job like any other error.
+async fn scan_unless_fresh(
+    discovery_service: &DiscoveryServiceImpl,
+    job: &DiscoveryJob,
+    target: &str,
+    scan: impl std::future::Future<Output = Result<()>>,
+) -> Result<()> {
+    let targets = match discovery_service
+        .targets_to_scan(job, vec![target.to_string()])
+        .await
+    {
+        Err(e) => return Err(e.into()),
+        Ok(targets) if targets.is_empty() => {
+            tracing::info!("Skipping recently scanned {} for job {}", target, job.id);
+            return Ok(());
+        }
+        Ok(targets) => targets,
+    };
+
+    scan.await?;
+    discovery_service
+        .record_scanned_targets(job, &targets)
+        .await
+        .map_err(Into::into)
+}
+
+/// Mark `job` finished with the outcome of running it
+fn finish_job(job: &mut DiscoveryJob, result: &Result<()>, cancelled: bool) {
+    job.completed_at = Some(Utc::now());
+    job.status = match result {
+        _ if cancelled => {
+            tracing::info!("Job {} was cancelled", job.id);
+            job.logs = Some("Cancelled by user".to_string());
+            JobStatus::Cancelled
+        }
+        Err(e) if is_cancelled(e) => {
+            tracing::info!("Job {} was cancelled", job.id);
+            job.logs = Some("Cancelled by user".to_string());
+            JobStatus::Cancelled
+        }
+        Ok(_) => {
+            tracing::info!("Job {} completed successfully", job.id);
+            JobStatus::Completed
+        }
+        Err(e) => {
+            tracing::error!("Job {} failed: {}", job.id, e);
+            // Add error to logs
+            job.logs = Some(format!("Error: {}", e));
+            JobStatus::Failed
+        }
+    };
+}
+
+/// Scope for `job`: the global blocklist narrowed to its organization's
+/// allowlist. Fails if the job's target is outside it.
+async fn scope_for_job(
+    organization_repository: &dyn OrganizationRepository,
+    scope: &ScanScope,
+    job: &DiscoveryJob,
+) -> Result<ScanScope> {
+    let organization = organization_repository
+        .get_organization(job.organization_id)
+        .await?;
+    let scope = scope.clone().with_allowlist(&organization.scan_scope)?;
+    if let Some(target) = &job.target {
+        scope.check(target)?;
+    }
+    Ok(scope)
+}
+
+/// What `job` would scan within `scope`, resolving hostnames through
+/// `resolver` but sending nothing to the targets themselves
+async fn plan_job(
+    job: &DiscoveryJob,
+    scope: &ScanScope,
+    resolver: &dyn HostResolver,
+) -> Result<ScanPlan> {
+    let target = job
+        .target
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("No target specified for {:?} job", job.job_type))?;
+    match job.job_type {
+        JobType::DnsEnum => Ok(plan_dns_enumeration(target)),
+        JobType::PortScan => {
+            plan_port_scan(target, job.ports().as_deref(), scope, resolver, None).await
+        }
+        job_type => Err(anyhow::anyhow!(
+            "Dry runs are not supported for {:?} jobs",
+            job_type
+        )),
+    }
+}
+
+/// Poll the stored job and fire `cancel` once the API has marked it CANCELLED
+async fn watch_for_cancellation(
+    repository: Arc<dyn DiscoveryJobRepository>,
+    job_id: Uuid,
+    cancel: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = sleep(CANCELLATION_POLL_INTERVAL) => {}
+        }
+
+        match repository.get_job(job_id).await {
+            Ok(job) if job.status == JobStatus::Cancelled => {
+                tracing::info!("Cancellation requested for job {}", job_id);
+                cancel.cancel();
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("Failed to check cancellation for job {}: {}", job_id, e);
+            }
+        }
+    }
+}
+
 /// Process DNS enumeration discovery
+#[allow(clippy::too_many_arguments)]
 async fn process_dns_enumeration(
     asset_service: &impl AssetService,
+    vulnerability_service: &impl VulnerabilityService,
+    discovery_service: &DiscoveryServiceImpl,
+    discovery_job_repository: &dyn DiscoveryJobRepository,
     job: &DiscoveryJob,
     target: &str,
+    asn_database: Option<&AsnDatabase>,
+    http: &HttpClientConfig,
+    output: Option<&dyn OutputSink>,
 ) -> Result<()> {
     // Use the DNS enumerator
     let dns_enumerator = dns::DnsEnumerator::new().await?;
-    let results = dns_enumerator.enumerate(target).await?;
+    let mut results = dns_enumerator.enumerate(target).await?;
+
+    add_registration(&mut results, target, http).await;
+
+    if let Some(database) = asn_database {
+        results.enrich_with_asn(database);
+    }
+
+    let takeovers = check_takeover(&dns_enumerator, &results, http).await;
 
     // Process the results
-    process_discovery_results(asset_service, job.organization_id, results).await
+    persist_results(
+        asset_service,
+        discovery_service,
+        discovery_job_repository,
+        job,
+        results,
+        output,
+    )
+    .await?;
+    store_vulnerabilities(
+        asset_service,
+        vulnerability_service,
+        job.organization_id,
+        &takeovers,
+        takeover_remediation,
+    )
+    .await
+}
+
+/// Look up `target`'s registration details over RDAP. They're nice to have,
+/// so neither a client that can't be built nor a failed lookup fails the job.
+async fn add_registration(results: &mut DiscoveryResult, target: &str, http: &HttpClientConfig) {
+    let client = match RdapClient::with_config(DEFAULT_RDAP_BASE_URL, http) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Could not create RDAP client for {}: {}", target, e);
+            return;
+        }
+    };
+
+    match client.lookup(target).await {
+        Ok(info) => results.add_whois(target, info),
+        Err(e) => tracing::warn!("RDAP lookup failed for {}: {}", target, e),
+    }
+}
+
+/// Check the enumerated domains for subdomain takeover. CNAME and MX
+/// targets are other parties' hosts, so only the domains themselves are
+/// checked. Failed lookups and fetches skip the domain, and a checker that
+/// can't be built skips the check altogether.
+async fn check_takeover(
+    dns_enumerator: &dns::DnsEnumerator,
+    results: &DiscoveryResult,
+    http: &HttpClientConfig,
+) -> Vec<DiscoveredVulnerability> {
+    let checker = match TakeoverChecker::with_config(default_fingerprints(), http) {
+        Ok(checker) => checker,
+        Err(e) => {
+            tracing::warn!("Skipping takeover checks, could not create checker: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut findings = Vec::new();
+
+    for domain in results
+        .domains
+        .iter()
+        .filter(|domain| domain.source != "dns_cname" && domain.source != "dns_mx")
+    {
+        let cname = match dns_enumerator.lookup_cname(&domain.domain_name).await {
+            Ok(Some(cname)) => cname,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("{}", e);
+                continue;
+            }
+        };
+
+        match checker.check(&domain.domain_name, &cname).await {
+            Ok(Some(finding)) => findings.push(finding),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "Takeover check failed for {} (CNAME {}): {}",
+                domain.domain_name,
+                cname,
+                e
+            ),
+        }
+    }
+
+    findings
+}
+
+/// How to fix a dangling CNAME found by `check_takeover`
+fn takeover_remediation(finding: &DiscoveredVulnerability) -> Option<String> {
+    Some(format!(
+        "Remove the DNS record for {} or claim {} with the provider.",
+        finding.target, finding.matched_at
+    ))
+}
+
+/// Record scanner findings as vulnerabilities on the organization's domain
+/// assets they were found on, with `remediation` giving each one's fix. A
+/// finding already open on the asset isn't recorded again.
+async fn store_vulnerabilities(
+    asset_service: &impl AssetService,
+    vulnerability_service: &impl VulnerabilityService,
+    org_id: Uuid,
+    findings: &[DiscoveredVulnerability],
+    remediation: impl Fn(&DiscoveredVulnerability) -> Option<String>,
+) -> Result<()> {
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    let domains = asset_service
+        .list_assets(
+            Some(org_id),
+            Some(AssetType::Domain),
+            None,
+            None,
+            FINDING_ASSET_LIMIT,
+            0,
+        )
+        .await?;
+
+    for finding in findings {
+        let Some(asset) = domains
+            .iter()
+            .find(|asset| asset.value.eq_ignore_ascii_case(&finding.target))
+        else {
+            tracing::warn!("No domain asset for finding on {}", finding.target);
+            continue;
+        };
+
+        let open = vulnerability_service
+            .list_vulnerabilities(
+                Some(asset.id),
+                None,
+                None,
+                Some(VulnerabilityStatus::Open),
+                FINDING_ASSET_LIMIT,
+                0,
+            )
+            .await?;
+        if open.iter().any(|existing| existing.title == finding.name) {
+            tracing::debug!("{} already open on {}", finding.name, asset.value);
+            continue;
+        }
+
+        let vulnerability = Vulnerability::new(
+            asset.id,
+            None,
+            finding.name.clone(),
+            finding.description.clone(),
+            parse_severity(&finding.severity),
+            finding.cve_id.clone(),
+            Some(serde_json::json!({
+                "template_id": finding.template_id,
+                "matched_at": finding.matched_at,
+                "tags": finding.tags,
+                "source": finding.source,
+            })),
+            remediation(finding),
+        );
+        vulnerability_service
+            .create_vulnerability(&vulnerability)
+            .await?;
+        tracing::info!("Recorded {} on {}", finding.name, asset.value);
+    }
+
+    Ok(())
+}
+
+/// Process web crawl discovery. Pages whose content changed since the last
+/// crawl are found before the new content hashes are stored over the old.
+async fn process_web_crawl(
+    repo_factory: &RepositoryFactory,
+    discovery_service: &DiscoveryServiceImpl,
+    job: &DiscoveryJob,
+    target: &str,
+    http: &HttpClientConfig,
+    screenshot_dir: Option<&str>,
+    output: Option<&dyn OutputSink>,
+) -> Result<()> {
+    let org_id = job.organization_id;
+    let results = web_crawl_task(job, target, http, screenshot_dir)
+        .execute()
+        .await?;
+
+    let changes = discovery_service
+        .detect_content_changes(org_id, &results)
+        .await?;
+    tracing::debug!("{} pages changed for job {}", changes.len(), job.id);
+
+    store_web_crawl_results(repo_factory, job, &results).await?;
+
+    if let Some(output) = output {
+        let emitted = emit_result(output, org_id, Some(job.id), &results).await?;
+        tracing::debug!("Emitted {} discovery events for job {}", emitted, job.id);
+    }
+
+    Ok(())
+}
+
+/// Store a web crawl's results and link the assets written to its job
+async fn store_web_crawl_results(
+    repo_factory: &RepositoryFactory,
+    job: &DiscoveryJob,
+    results: &DiscoveryResult,
+) -> Result<()> {
+    let persisted = repo_factory
+        .persist_discovery_result(job.organization_id, results)
+        .await?;
+    let linked = repo_factory
+        .discovery_job_repository()
+        .link_job_to_assets(job.id, &persisted.asset_ids)
+        .await?;
+    tracing::debug!(
+        "Stored {} assets and {} technologies for job {}, {} newly linked",
+        persisted.assets,
+        persisted.technologies,
+        job.id,
+        linked
+    );
+
+    Ok(())
+}
+
+/// The built-in web app scan for a web crawl job. A bare host is crawled
+/// from its HTTPS root.
+fn web_crawl_task(
+    job: &DiscoveryJob,
+    target: &str,
+    http: &HttpClientConfig,
+    screenshot_dir: Option<&str>,
+) -> DiscoveryTask {
+    let target = if target.contains("://") {
+        target.to_string()
+    } else {
+        format!("https://{}", target)
+    };
+
+    DiscoveryTask {
+        job_id: job.id,
+        organization_id: job.organization_id,
+        task_type: DiscoveryTaskType::WebAppScan,
+        target,
+        nuclei_params: None,
+        screenshot_dir: screenshot_dir.map(str::to_string),
+        secret_allowlist: Vec::new(),
+        probe_paths: None,
+        crawl_scope: Default::default(),
+        http: http.clone(),
+    }
+}
+
+/// Map a scanner's severity label onto `Severity`, treating unknown labels
+/// as informational
+fn parse_severity(severity: &str) -> Severity {
+    match severity.to_lowercase().as_str() {
+        "critical" => Severity::Critical,
+        "high" => Severity::High,
+        "medium" => Severity::Medium,
+        "low" => Severity::Low,
+        _ => Severity::Info,
+    }
 }
 
 /// Process port scan discovery
+#[allow(clippy::too_many_arguments)]
 async fn process_port_scan(
     asset_service: &impl AssetService,
+    discovery_service: &DiscoveryServiceImpl,
+    discovery_job_repository: &dyn DiscoveryJobRepository,
     job: &DiscoveryJob,
     target: &str,
+    scope: &ScanScope,
+    cancel: &CancellationToken,
+    asn_database: Option<&AsnDatabase>,
+    shodan: Option<&ShodanClient>,
+    http: &HttpClientConfig,
+    output: Option<&dyn OutputSink>,
 ) -> Result<()> {
-    // Resolve the target to IP addresses if it's a domain
+    // Launched from a scan profile, the job lists the ports to scan
+    let ports = job.ports();
+
+    // Expand or resolve the target to IP addresses. Link-local and
+    // unspecified addresses don't lead to the host, and a domain in scope
+    // can still resolve to a blocked address.
     let dns_enumerator = dns::DnsEnumerator::new().await?;
-    let ips = dns_enumerator.resolve(target).await?;
+    let plan = plan_port_scan(target, ports.as_deref(), scope, &dns_enumerator, None).await?;
+    for skipped in &plan.skipped {
+        tracing::warn!(
+            "Skipping {} for {}: {}",
+            skipped.target,
+            target,
+            skipped.reason
+        );
+    }
 
-    if ips.is_empty() {
+    if plan.targets.is_empty() {
         return Err(anyhow::anyhow!(
             "No IP addresses found for target: {}",
             target
         ));
     }
 
-    // Run port scan on each IP
+    // Incremental jobs leave out addresses scanned within their window
+    let ips = discovery_service.targets_to_scan(job, plan.targets).await?;
+    if ips.is_empty() {
+        tracing::info!("All addresses for {} were scanned recently", target);
+        return Ok(());
+    }
+
+    // Run port scan on each IP, through the proxy if it can carry raw TCP
     let mut all_results = DiscoveryResult::new();
+    let scan_config = port_scan::PortScanConfig {
+        socks_proxy: http.socks_proxy().map(str::to_string),
+        ..port_scan::PortScanConfig::default()
+    };
+
+    for ip in &ips {
+        if cancel.is_cancelled() {
+            return Err(ScanCancelled.into());
+        }
 
-    for ip in ips {
-        let scanner = port_scan::PortScanner::new();
-        let results = scanner.scan_ip(&ip.to_string(), None).await?;
+        let scanner = port_scan::PortScanner::new()
+            .with_config(scan_config.clone())
+            .with_cancellation(cancel.clone());
+        let results = scanner.scan_ip(ip, ports.as_deref()).await?;
         all_results.merge(results);
+
+        // Passive data is nice to have, so a failed lookup doesn't fail the job
+        if let Some(shodan) = shodan {
+            if let Err(e) = shodan.enrich(ip, &mut all_results).await {
+                tracing::warn!("Shodan lookup failed for {}: {}", ip, e);
+            }
+        }
+    }
+
+    if let Some(database) = asn_database {
+        all_results.enrich_with_asn(database);
     }
 
     // Process the results
-    process_discovery_results(asset_service, job.organization_id, all_results).await
+    persist_results(
+        asset_service,
+        discovery_service,
+        discovery_job_repository,
+        job,
+        all_results,
+        output,
+    )
+    .await?;
+    discovery_service.record_scanned_targets(job, &ips).await?;
+    Ok(())
+}
+
+/// Store `results` for `job`. A scheduled rescan is first reconciled
+/// against the organization's known assets, so new, unchanged and
+/// disappeared assets are reported and new critical assets alerted on.
+async fn persist_results(
+    asset_service: &impl AssetService,
+    discovery_service: &DiscoveryServiceImpl,
+    discovery_job_repository: &dyn DiscoveryJobRepository,
+    job: &DiscoveryJob,
+    results: DiscoveryResult,
+    output: Option<&dyn OutputSink>,
+) -> Result<()> {
+    if let Some(schedule_id) = job.schedule_id() {
+        let report = discovery_service
+            .reconcile_results(job.organization_id, &results)
+            .await?;
+        tracing::info!(
+            "Rescan job {} for schedule {}: {} new, {} unchanged, {} disappeared assets",
+            job.id,
+            schedule_id,
+            report.new.len(),
+            report.unchanged.len(),
+            report.disappeared.len()
+        );
+        for asset in &report.disappeared {
+            tracing::info!(
+                "{:?} asset {} was not seen by job {}",
+                asset.asset_type,
+                asset.value,
+                job.id
+            );
+        }
+    }
+
+    process_discovery_results(
+        asset_service,
+        discovery_job_repository,
+        job,
+        results,
+        output,
+    )
+    .await
 }
 
-/// Process discovery results and create assets
+/// Process discovery results and create assets, linking each one created
+/// or updated to `job`, then stream every discovered entity to `output`
 async fn process_discovery_results(
     asset_service: &impl AssetService,
-    org_id: Uuid,
+    discovery_job_repository: &dyn DiscoveryJobRepository,
+    job: &DiscoveryJob,
     results: DiscoveryResult,
+    output: Option<&dyn OutputSink>,
 ) -> Result<()> {
+    let org_id = job.organization_id;
+    let mut stored_assets = Vec::new();
+
     // Process domains
-    for domain in results.domains {
+    for domain in &results.domains {
         let asset = Asset {
             id: Uuid::new_v4(),
             organization_id: org_id,
             asset_type: AssetType::Domain,
-            value: domain.domain_name,
+            value: domain.domain_name.clone(),
             status: AssetStatus::Active,
             first_seen: Utc::now(),
             last_seen: Utc::now(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
-            attributes: serde_json::json!({ "source": domain.source }),
+            attributes: results.domain_attributes(domain),
+            risk_score: 0.0,
         };
 
         // Create or update the asset
         match asset_service.create_asset(&asset).await {
-            Ok(_) => tracing::debug!("Created domain asset: {}", asset.value),
+            Ok(created) => {
+                tracing::debug!("Created domain asset: {}", asset.value);
+                stored_assets.push(created.id);
+            }
             Err(e) => {
                 tracing::warn!("Failed to create domain asset: {}: {}", asset.value, e);
                 return Err(anyhow::anyhow!(
@@ -196,7 +856,7 @@ async fn process_discovery_results(
     }
 
     // Process IP addresses
-    for ip in results.ip_addresses {
+    for ip in &results.ip_addresses {
         let asset = Asset {
             id: Uuid::new_v4(),
             organization_id: org_id,
@@ -207,12 +867,16 @@ async fn process_discovery_results(
             last_seen: Utc::now(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
-            attributes: serde_json::json!({ "source": ip.source }),
+            attributes: results.ip_attributes(ip.ip_address, &ip.source),
+            risk_score: 0.0,
         };
 
         // Create or update the asset
         match asset_service.create_asset(&asset).await {
-            Ok(_) => tracing::debug!("Created IP asset: {}", asset.value),
+            Ok(created) => {
+                tracing::debug!("Created IP asset: {}", asset.value);
+                stored_assets.push(created.id);
+            }
             Err(e) => {
                 tracing::warn!("Failed to create IP asset: {}: {}", asset.value, e);
                 return Err(anyhow::anyhow!(
@@ -225,7 +889,7 @@ async fn process_discovery_results(
     }
 
     // Process ports
-    for port in results.ports {
+    for port in &results.ports {
         // First, ensure the IP asset exists
         let ip_asset = Asset {
             id: Uuid::new_v4(),
@@ -237,11 +901,15 @@ async fn process_discovery_results(
             last_seen: Utc::now(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
-            attributes: serde_json::json!({ "source": port.source.clone() }),
+            attributes: results.ip_attributes(port.ip_address, &port.source),
+            risk_score: 0.0,
         };
 
         let ip_asset = match asset_service.create_asset(&ip_asset).await {
-            Ok(asset) => asset,
+            Ok(asset) => {
+                stored_assets.push(asset.id);
+                asset
+            }
             Err(e) => {
                 tracing::warn!(
                     "Failed to create IP asset for port: {}: {}",
@@ -270,13 +938,18 @@ async fn process_discovery_results(
             None => Vec::new(),
         };
 
-        // Add this port to the ports list
+        // Add this port to the ports list, replacing what was recorded for
+        // it before, e.g. by reconciling a rescan
+        ports.retain(|existing| {
+            existing["port"] != port.port || existing["protocol"] != port.protocol.as_str()
+        });
         ports.push(serde_json::json!({
             "port": port.port,
             "protocol": port.protocol,
             "service": port.service_name,
             "banner": port.banner,
-            "status": port.status
+            "status": port.port_status(),
+            "tls": port.tls_info,
         }));
 
         attributes.insert("ports".to_string(), serde_json::Value::Array(ports));
@@ -307,6 +980,24 @@ async fn process_discovery_results(
         }
     }
 
+    // Record which assets this job found, once each
+    stored_assets.sort_unstable();
+    stored_assets.dedup();
+    let linked = discovery_job_repository
+        .link_job_to_assets(job.id, &stored_assets)
+        .await?;
+    tracing::debug!(
+        "Linked {} of {} assets to job {}",
+        linked,
+        stored_assets.len(),
+        job.id
+    );
+
+    if let Some(output) = output {
+        let emitted = emit_result(output, org_id, Some(job.id), &results).await?;
+        tracing::debug!("Emitted {} discovery events for job {}", emitted, job.id);
+    }
+
     Ok(())
 }
 
@@ -315,10 +1006,17 @@ mod tests {
     use super::*; // Import items from parent module (job_processor)
     use backend::{
         errors as backend_error, // Alias to avoid conflict with anyhow::Error
-        traits::AssetRepository,
+        models::{
+            AssetHistory, AssetRelationship, JobAssetLink, Organization, RelationshipDirection,
+        },
+        traits::{
+            AssetHistoryRepository, AssetRepository, NotificationPeriod, NotificationService,
+            NotificationSettings,
+        },
         Result as BackendResult, // Use the Result alias from backend
     };
-    use discovery::results::{DiscoveredDomain, DiscoveryResult};
+    use discovery::results::{DiscoveredDomain, DiscoveredWebResource, DiscoveryResult};
+    use infrastructure::utils::testing::{create_test_organization, setup_test_db};
     use mockall::{mock, predicate::*};
     use std::sync::Arc;
 
@@ -329,26 +1027,228 @@ mod tests {
         impl AssetRepository for AssetRepository {
             // Use BackendResult and backend::Error
             async fn create_asset(&self, asset: &Asset) -> BackendResult<Asset>;
+            async fn upsert_asset(&self, asset: &Asset) -> BackendResult<Asset>;
             async fn get_asset(&self, id: Uuid) -> BackendResult<Asset>;
             async fn update_asset(&self, asset: &Asset) -> BackendResult<Asset>;
             async fn delete_asset(&self, id: Uuid) -> BackendResult<bool>;
+            async fn restore_asset(&self, id: Uuid) -> BackendResult<bool>;
             async fn list_assets(
                 &self,
-                organization_id: Option<Uuid>,
-                asset_type: Option<AssetType>,
-                status: Option<AssetStatus>,
+                filter: &backend::models::AssetFilter,
                 limit: usize,
                 offset: usize,
             ) -> BackendResult<Vec<Asset>>;
-            async fn count_assets(
+            async fn count_assets(&self, filter: &backend::models::AssetFilter) -> BackendResult<usize>;
+            async fn add_asset_tags(&self, asset_id: Uuid, tags: &[String]) -> BackendResult<Vec<String>>;
+            async fn remove_asset_tag(&self, asset_id: Uuid, tag: &str) -> BackendResult<bool>;
+            async fn list_asset_tags(&self, asset_id: Uuid) -> BackendResult<Vec<String>>;
+            async fn add_asset_relationship(&self, relationship: &AssetRelationship) -> BackendResult<bool>;
+            async fn add_relationships_bulk(&self, relationships: Vec<(Uuid, Uuid, String)>) -> BackendResult<usize>;
+            async fn remove_asset_relationship(
+                &self,
+                source_asset_id: Uuid,
+                target_asset_id: Uuid,
+                relationship_type: &str,
+            ) -> BackendResult<bool>;
+            async fn list_asset_relationships(
+                &self,
+                asset_id: Uuid,
+                direction: RelationshipDirection,
+                relationship_type: Option<String>,
+                limit: usize,
+                offset: usize,
+            ) -> BackendResult<Vec<(AssetRelationship, Asset)>>;
+            async fn bulk_update_status(
+                &self,
+                organization_id: Uuid,
+                asset_ids: &[Uuid],
+                status: AssetStatus,
+            ) -> BackendResult<usize>;
+            async fn mark_stale(
                 &self,
                 organization_id: Option<Uuid>,
-                asset_type: Option<AssetType>,
-                status: Option<AssetStatus>,
+                older_than: chrono::Duration,
             ) -> BackendResult<usize>;
+            async fn update_risk_score(&self, id: Uuid, risk_score: f32) -> BackendResult<bool>;
+            async fn list_recent_assets(&self, organization_id: Uuid, limit: usize) -> BackendResult<Vec<Asset>>;
+            async fn asset_counts_by_day(
+                &self,
+                organization_id: Uuid,
+                since: chrono::DateTime<chrono::Utc>,
+            ) -> BackendResult<Vec<(chrono::NaiveDate, usize)>>;
+        }
+    }
+
+    mock! {
+        pub AssetHistoryRepository {}
+
+        #[async_trait::async_trait]
+        impl AssetHistoryRepository for AssetHistoryRepository {
+            async fn create_history_entry(&self, entry: &AssetHistory) -> BackendResult<AssetHistory>;
+            async fn list_asset_history(
+                &self,
+                asset_id: Uuid,
+                limit: usize,
+                offset: usize,
+            ) -> BackendResult<Vec<AssetHistory>>;
+            async fn count_asset_history(&self, asset_id: Uuid) -> BackendResult<usize>;
+        }
+    }
+
+    mock! {
+        pub OrganizationRepository {}
+
+        #[async_trait::async_trait]
+        impl OrganizationRepository for OrganizationRepository {
+            async fn create_organization(&self, organization: &Organization) -> BackendResult<Organization>;
+            async fn get_organization(&self, id: Uuid) -> BackendResult<Organization>;
+            async fn get_organization_by_name(&self, name: &str) -> BackendResult<Option<Organization>>;
+            async fn update_organization(&self, organization: &Organization) -> BackendResult<Organization>;
+            async fn delete_organization(&self, id: Uuid) -> BackendResult<bool>;
+            async fn list_organizations(&self, limit: usize, offset: usize) -> BackendResult<Vec<Organization>>;
+            async fn count_organizations(&self) -> BackendResult<usize>;
+            async fn record_report_sent(
+                &self,
+                id: Uuid,
+                sent_at: chrono::DateTime<chrono::Utc>,
+            ) -> BackendResult<bool>;
         }
     }
 
+    mock! {
+        pub DiscoveryJobRepository {}
+
+        #[async_trait::async_trait]
+        impl DiscoveryJobRepository for DiscoveryJobRepository {
+            async fn create_job(&self, job: &DiscoveryJob) -> BackendResult<DiscoveryJob>;
+            async fn get_job(&self, id: Uuid) -> BackendResult<DiscoveryJob>;
+            async fn update_job(&self, job: &DiscoveryJob) -> BackendResult<DiscoveryJob>;
+            async fn delete_job(&self, id: Uuid) -> BackendResult<bool>;
+            async fn list_jobs(
+                &self,
+                filter: &backend::models::DiscoveryJobFilter,
+                sort: Option<shared::types::Sort<shared::types::JobSortField>>,
+                limit: usize,
+                offset: usize,
+            ) -> BackendResult<Vec<DiscoveryJob>>;
+            async fn count_jobs(&self, filter: &backend::models::DiscoveryJobFilter) -> BackendResult<usize>;
+            async fn list_jobs_by_status(
+                &self,
+                status: JobStatus,
+                limit: usize,
+            ) -> BackendResult<Vec<DiscoveryJob>>;
+            async fn create_job_asset_link(&self, link: &JobAssetLink) -> BackendResult<JobAssetLink>;
+            async fn link_job_to_assets(&self, job_id: Uuid, asset_ids: &[Uuid]) -> BackendResult<usize>;
+            async fn get_job_assets(&self, job_id: Uuid) -> BackendResult<Vec<Asset>>;
+            async fn get_job_summary(&self, job_id: Uuid) -> BackendResult<backend::models::JobResultSummary>;
+            async fn record_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+                scanned_at: chrono::DateTime<chrono::Utc>,
+            ) -> BackendResult<()>;
+            async fn last_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+            ) -> BackendResult<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>>;
+        }
+    }
+
+    mock! {
+        pub NotificationService {}
+
+        #[async_trait::async_trait]
+        impl NotificationService for NotificationService {
+            async fn notify_new_vulnerability(&self, vulnerability: &Vulnerability) -> BackendResult<bool>;
+            async fn notify_vulnerability_status_change(
+                &self,
+                vulnerability: &Vulnerability,
+                old_status: VulnerabilityStatus,
+            ) -> BackendResult<bool>;
+            async fn notify_new_critical_asset(&self, asset: &Asset) -> BackendResult<bool>;
+            async fn notify_content_change(&self, asset: &Asset, previous_hash: &str) -> BackendResult<bool>;
+            async fn send_summary_report(
+                &self,
+                organization_id: Uuid,
+                period: NotificationPeriod,
+            ) -> BackendResult<bool>;
+            async fn get_notification_settings(&self, organization_id: Uuid) -> BackendResult<NotificationSettings>;
+            async fn update_notification_settings(
+                &self,
+                organization_id: Uuid,
+                settings: &NotificationSettings,
+            ) -> BackendResult<NotificationSettings>;
+            async fn notify_new_vulnerabilities_batch(
+                &self,
+                vulnerabilities: &[Vulnerability],
+            ) -> BackendResult<bool>;
+        }
+    }
+
+    fn running_job(id: Uuid, status: JobStatus) -> DiscoveryJob {
+        DiscoveryJob {
+            id,
+            organization_id: Uuid::new_v4(),
+            job_type: JobType::PortScan,
+            status,
+            target: Some("127.0.0.1".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            started_at: Some(Utc::now()),
+            completed_at: None,
+            logs: None,
+            configuration: serde_json::json!({}),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watcher_cancels_token_when_job_is_cancelled() {
+        let job_id = Uuid::new_v4();
+        let mut polls = 0;
+
+        // The job is still running on the first poll and cancelled on the second
+        let mut mock_repo = MockDiscoveryJobRepository::new();
+        mock_repo
+            .expect_get_job()
+            .with(eq(job_id))
+            .times(2)
+            .returning(move |id| {
+                polls += 1;
+                let status = if polls == 1 {
+                    JobStatus::Running
+                } else {
+                    JobStatus::Cancelled
+                };
+                Ok(running_job(id, status))
+            });
+
+        let cancel = CancellationToken::new();
+        let watcher = tokio::spawn(watch_for_cancellation(
+            Arc::new(mock_repo),
+            job_id,
+            cancel.clone(),
+        ));
+
+        tokio::time::timeout(CANCELLATION_POLL_INTERVAL * 3, cancel.cancelled())
+            .await
+            .expect("watcher should cancel the token");
+        watcher.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watcher_stops_when_job_finishes() {
+        let mut mock_repo = MockDiscoveryJobRepository::new();
+        mock_repo.expect_get_job().never();
+
+        // Cancelling locally (e.g. the job finished) ends the watcher without polling
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        watch_for_cancellation(Arc::new(mock_repo), Uuid::new_v4(), cancel).await;
+    }
+
     // Helper to create a simple discovery result for testing
     async fn create_test_discovery_result(org_id: Uuid) -> Result<()> {
         // Create a mock discovery result with a single domain
@@ -361,17 +1261,40 @@ mod tests {
         // Set up the mock repository
         let mut mock_repo = MockAssetRepository::new();
         mock_repo
-            .expect_create_asset()
+            .expect_upsert_asset()
             .withf(move |asset: &Asset| {
                 asset.organization_id == org_id && asset.value == "example.com"
             })
             .times(1)
             .returning(|asset| Ok(asset.clone()));
 
-        let asset_service = AssetServiceImpl::new(Arc::new(mock_repo));
+        let asset_service = AssetServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
 
         // Process the results
-        process_discovery_results(&asset_service, org_id, results).await
+        let job = DiscoveryJob {
+            organization_id: org_id,
+            ..running_job(Uuid::new_v4(), JobStatus::Running)
+        };
+        process_discovery_results(
+            &asset_service,
+            &linking_job_repository(),
+            &job,
+            results,
+            None,
+        )
+        .await
+    }
+
+    /// Repository accepting any links to assets
+    fn linking_job_repository() -> MockDiscoveryJobRepository {
+        let mut mock_repo = MockDiscoveryJobRepository::new();
+        mock_repo
+            .expect_link_job_to_assets()
+            .returning(|_, asset_ids| Ok(asset_ids.len()));
+        mock_repo
     }
 
     #[tokio::test]
@@ -395,15 +1318,30 @@ mod tests {
         // Set up the mock repository to return an error
         let mut mock_repo = MockAssetRepository::new();
         mock_repo
-            .expect_create_asset()
+            .expect_upsert_asset()
             .withf(move |asset: &Asset| {
                 asset.organization_id == org_id && asset.value == "example.com"
             })
             .times(1)
             .returning(|_| Err(backend_error::Error::Database("Mock DB error".to_string())));
 
-        let asset_service = AssetServiceImpl::new(Arc::new(mock_repo));
-        let result = process_discovery_results(&asset_service, org_id, results).await;
+        let asset_service = AssetServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+        let job = DiscoveryJob {
+            organization_id: org_id,
+            ..running_job(Uuid::new_v4(), JobStatus::Running)
+        };
+        // Nothing was stored, so nothing is linked
+        let result = process_discovery_results(
+            &asset_service,
+            &MockDiscoveryJobRepository::new(),
+            &job,
+            results,
+            None,
+        )
+        .await;
 
         assert!(result.is_err());
         let err_string = result.unwrap_err().to_string();
@@ -411,4 +1349,522 @@ mod tests {
         // Check that the underlying Database error message is included
         assert!(err_string.contains("Mock DB error"));
     }
+
+    /// Sink keeping every event it is sent
+    #[derive(Default)]
+    struct RecordingSink {
+        events: std::sync::Mutex<Vec<discovery::output::DiscoveryEvent>>,
+    }
+
+    #[async_trait::async_trait]
+    impl OutputSink for RecordingSink {
+        async fn emit(&self, event: discovery::output::DiscoveryEvent) -> Result<()> {
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_discovery_result_emits_stored_entities() {
+        let job = running_job(Uuid::new_v4(), JobStatus::Running);
+
+        let mut results = DiscoveryResult::new();
+        results.domains.push(DiscoveredDomain {
+            domain_name: "example.com".to_string(),
+            source: "test".to_string(),
+        });
+        results.ip_addresses.push(discovery::results::DiscoveredIp {
+            ip_address: "192.0.2.1".parse().unwrap(),
+            source: "test".to_string(),
+        });
+
+        let mut mock_repo = MockAssetRepository::new();
+        mock_repo
+            .expect_upsert_asset()
+            .times(2)
+            .returning(|asset| Ok(asset.clone()));
+        let asset_service = AssetServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let sink = RecordingSink::default();
+        process_discovery_results(
+            &asset_service,
+            &linking_job_repository(),
+            &job,
+            results,
+            Some(&sink),
+        )
+        .await
+        .unwrap();
+
+        let events = sink.events.lock().unwrap();
+        let kinds: Vec<_> = events.iter().map(|event| event.kind()).collect();
+        assert_eq!(kinds, vec![Some("ip"), Some("domain")]);
+        assert!(events
+            .iter()
+            .all(|event| event.organization_id == job.organization_id
+                && event.job_id == Some(job.id)));
+    }
+
+    #[tokio::test]
+    async fn test_process_discovery_result_links_assets_to_job() {
+        let job = running_job(Uuid::new_v4(), JobStatus::Running);
+
+        let mut results = DiscoveryResult::new();
+        results.domains.push(DiscoveredDomain {
+            domain_name: "example.com".to_string(),
+            source: "test".to_string(),
+        });
+        results.ip_addresses.push(discovery::results::DiscoveredIp {
+            ip_address: "192.0.2.1".parse().unwrap(),
+            source: "test".to_string(),
+        });
+
+        // Assets are stored under the IDs the repository hands back
+        let stored = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut asset_repo = MockAssetRepository::new();
+        let recorded = stored.clone();
+        asset_repo
+            .expect_upsert_asset()
+            .times(2)
+            .returning(move |asset| {
+                let stored = Asset {
+                    id: Uuid::new_v4(),
+                    ..asset.clone()
+                };
+                recorded.lock().unwrap().push(stored.id);
+                Ok(stored)
+            });
+        let asset_service = AssetServiceImpl::new(
+            Arc::new(asset_repo),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+
+        let linked = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut job_repo = MockDiscoveryJobRepository::new();
+        let job_id = job.id;
+        let recorded = linked.clone();
+        job_repo
+            .expect_link_job_to_assets()
+            .withf(move |id, _| *id == job_id)
+            .times(1)
+            .returning(move |_, asset_ids| {
+                recorded.lock().unwrap().extend_from_slice(asset_ids);
+                Ok(asset_ids.len())
+            });
+
+        process_discovery_results(&asset_service, &job_repo, &job, results, None)
+            .await
+            .unwrap();
+
+        let mut stored = stored.lock().unwrap().clone();
+        stored.sort_unstable();
+        assert_eq!(*linked.lock().unwrap(), stored);
+    }
+
+    /// Asset repository keeping what it's given in memory
+    fn in_memory_asset_repository() -> MockAssetRepository {
+        let assets = Arc::new(std::sync::Mutex::new(Vec::<Asset>::new()));
+        let mut mock_repo = MockAssetRepository::new();
+
+        let stored = assets.clone();
+        mock_repo
+            .expect_list_assets()
+            .returning(move |filter, _, offset| {
+                Ok(stored
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|asset| filter.asset_type.is_none_or(|t| asset.asset_type == t))
+                    .skip(offset)
+                    .cloned()
+                    .collect())
+            });
+        let stored = assets.clone();
+        mock_repo.expect_create_asset().returning(move |asset| {
+            stored.lock().unwrap().push(asset.clone());
+            Ok(asset.clone())
+        });
+        let stored = assets.clone();
+        mock_repo.expect_upsert_asset().returning(move |asset| {
+            let mut assets = stored.lock().unwrap();
+            match assets
+                .iter_mut()
+                .find(|known| known.asset_type == asset.asset_type && known.value == asset.value)
+            {
+                Some(known) => {
+                    known.last_seen = asset.last_seen;
+                    Ok(known.clone())
+                }
+                None => {
+                    assets.push(asset.clone());
+                    Ok(asset.clone())
+                }
+            }
+        });
+        let stored = assets.clone();
+        mock_repo.expect_get_asset().returning(move |id| {
+            stored
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|asset| asset.id == id)
+                .cloned()
+                .ok_or_else(|| backend_error::Error::NotFound(id.to_string()))
+        });
+        let stored = assets;
+        mock_repo.expect_update_asset().returning(move |asset| {
+            let mut assets = stored.lock().unwrap();
+            if let Some(known) = assets.iter_mut().find(|known| known.id == asset.id) {
+                *known = asset.clone();
+            }
+            Ok(asset.clone())
+        });
+        mock_repo
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_rescan_alerts_on_new_critical_asset() {
+        let asset_repo: Arc<dyn AssetRepository> = Arc::new(in_memory_asset_repository());
+        let mut history_repo = MockAssetHistoryRepository::new();
+        history_repo
+            .expect_create_history_entry()
+            .returning(|entry| Ok(entry.clone()));
+        let asset_service = AssetServiceImpl::new(asset_repo.clone(), Arc::new(history_repo));
+
+        let mut notifications = MockNotificationService::new();
+        notifications
+            .expect_notify_new_critical_asset()
+            .withf(|asset| asset.value == "192.0.2.1")
+            .times(1)
+            .returning(|_| Ok(true));
+        let job_repo: Arc<dyn DiscoveryJobRepository> = Arc::new(linking_job_repository());
+        let discovery_service = DiscoveryServiceImpl::new(asset_repo.clone(), job_repo.clone())
+            .with_notification_service(Arc::new(notifications));
+
+        let job = DiscoveryJob {
+            configuration: serde_json::json!({ "schedule_id": Uuid::new_v4().to_string() }),
+            ..running_job(Uuid::new_v4(), JobStatus::Running)
+        };
+        assert!(job.schedule_id().is_some());
+
+        // The rescan finds RDP open on an address never seen before
+        let mut results = DiscoveryResult::new();
+        results.ports.push(port_scan::DiscoveredPort {
+            ip_address: "192.0.2.1".parse().unwrap(),
+            port: 3389,
+            protocol: "TCP".to_string(),
+            status: "OPEN".to_string(),
+            service_name: Some("rdp".to_string()),
+            banner: None,
+            http_status: None,
+            http_title: None,
+            tls_info: None,
+            source: "port_scan".to_string(),
+        });
+
+        persist_results(
+            &asset_service,
+            &discovery_service,
+            job_repo.as_ref(),
+            &job,
+            results,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Stored once, with the port listed once
+        let assets = asset_repo
+            .list_assets(&Default::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].open_ports(), vec![3389]);
+    }
+
+    #[tokio::test]
+    async fn test_manual_job_is_not_reconciled() {
+        let mut asset_repo = MockAssetRepository::new();
+        asset_repo.expect_list_assets().never();
+        asset_repo
+            .expect_upsert_asset()
+            .returning(|asset| Ok(asset.clone()));
+        let asset_repo: Arc<dyn AssetRepository> = Arc::new(asset_repo);
+        let asset_service = AssetServiceImpl::new(
+            asset_repo.clone(),
+            Arc::new(MockAssetHistoryRepository::new()),
+        );
+        let job_repo: Arc<dyn DiscoveryJobRepository> = Arc::new(linking_job_repository());
+        let discovery_service = DiscoveryServiceImpl::new(asset_repo, job_repo.clone());
+
+        let mut results = DiscoveryResult::new();
+        results.domains.push(DiscoveredDomain {
+            domain_name: "example.com".to_string(),
+            source: "test".to_string(),
+        });
+
+        let job = running_job(Uuid::new_v4(), JobStatus::Running);
+        persist_results(
+            &asset_service,
+            &discovery_service,
+            job_repo.as_ref(),
+            &job,
+            results,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_scan_lookup_fails_the_job() {
+        let mut job_repo = MockDiscoveryJobRepository::new();
+        job_repo
+            .expect_last_target_scans()
+            .returning(|_, _, _| Err(backend_error::Error::Database("Mock DB error".to_string())));
+        job_repo.expect_record_target_scans().never();
+        let discovery_service =
+            DiscoveryServiceImpl::new(Arc::new(MockAssetRepository::new()), Arc::new(job_repo));
+
+        let mut job = DiscoveryJob {
+            job_type: JobType::DnsEnum,
+            target: Some("example.com".to_string()),
+            configuration: serde_json::json!({ "incremental": true }),
+            ..running_job(Uuid::new_v4(), JobStatus::Running)
+        };
+        let target = job.target.clone().unwrap();
+
+        let result = scan_unless_fresh(&discovery_service, &job, &target, async {
+            panic!("the scan should not run");
+        })
+        .await;
+        finish_job(&mut job, &result, false);
+
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(job.logs.unwrap().contains("Mock DB error"));
+        assert!(job.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_completed_web_crawl_job_lists_its_assets() {
+        let (db_pool, _container) = setup_test_db().await;
+        let factory = RepositoryFactory::new(db_pool);
+        let org = create_test_organization(&factory, "Web Crawl Org")
+            .await
+            .unwrap();
+        let job_repo = factory.discovery_job_repository();
+        let mut job = job_repo
+            .create_job(&DiscoveryJob::new(
+                org.id,
+                JobType::WebCrawl,
+                Some("https://www.example.com".to_string()),
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let mut results = DiscoveryResult::new();
+        for url in ["https://www.example.com", "https://www.example.com/about"] {
+            results.web_resources.push(DiscoveredWebResource {
+                url: url.to_string(),
+                status_code: 200,
+                title: None,
+                technologies: vec!["Nginx".to_string()],
+                source: "web_crawl".to_string(),
+                screenshot_path: None,
+                content_hash: None,
+            });
+        }
+        store_web_crawl_results(&factory, &job, &results)
+            .await
+            .unwrap();
+        finish_job(&mut job, &Ok(()), false);
+        job_repo.update_job(&job).await.unwrap();
+
+        let job = job_repo.get_job(job.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        let mut linked: Vec<String> = job_repo
+            .get_job_assets(job.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|asset| asset.value)
+            .collect();
+        linked.sort();
+        assert_eq!(
+            linked,
+            vec!["https://www.example.com", "https://www.example.com/about"]
+        );
+    }
+
+    /// Repository returning an organization allowed to scan `scan_scope`
+    fn organization_repository(scan_scope: &[&str]) -> MockOrganizationRepository {
+        let scan_scope: Vec<String> = scan_scope.iter().map(|entry| entry.to_string()).collect();
+        let mut mock_repo = MockOrganizationRepository::new();
+        mock_repo.expect_get_organization().returning(move |id| {
+            let mut organization = Organization::new("Example".to_string());
+            organization.id = id;
+            organization.scan_scope = scan_scope.clone();
+            Ok(organization)
+        });
+        mock_repo
+    }
+
+    fn job_for(target: &str) -> DiscoveryJob {
+        DiscoveryJob {
+            target: Some(target.to_string()),
+            ..running_job(Uuid::new_v4(), JobStatus::Pending)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_scope_job_proceeds() {
+        let repo = organization_repository(&["example.com", "203.0.113.0/24"]);
+        let scope = ScanScope::new(&[], false).unwrap();
+
+        for target in ["example.com", "www.example.com", "203.0.113.10"] {
+            let result = scope_for_job(&repo, &scope, &job_for(target)).await;
+            assert!(result.is_ok(), "{} should be in scope", target);
+        }
+
+        let result = scope_for_job(&repo, &scope, &job_for("example.org")).await;
+        assert!(result.unwrap_err().to_string().contains("out of scope"));
+    }
+
+    #[tokio::test]
+    async fn test_cloud_metadata_job_is_refused() {
+        // Even with private networks allowed and no allowlist to get past
+        let repo = organization_repository(&[]);
+        let scope = ScanScope::new(&[], true).unwrap();
+
+        let result = scope_for_job(&repo, &scope, &job_for("169.254.169.254")).await;
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("169.254.169.254 is out of scope"));
+        assert!(error.contains("cloud metadata"));
+    }
+
+    /// HTTP settings no client can be built from: a header name can't
+    /// contain a space
+    fn unbuildable_http() -> HttpClientConfig {
+        HttpClientConfig {
+            extra_headers: [("bad header".to_string(), "x".to_string())].into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rdap_client_failure_does_not_fail_enrichment() {
+        let mut results = DiscoveryResult::new();
+
+        add_registration(&mut results, "example.com", &unbuildable_http()).await;
+
+        assert!(results.whois_for("example.com").is_none());
+    }
+
+    #[test]
+    fn test_web_crawl_task_carries_screenshot_dir_and_http() {
+        let job = job_for("example.com");
+        let http = HttpClientConfig {
+            proxy: Some("socks5h://127.0.0.1:1080".to_string()),
+            ..Default::default()
+        };
+
+        let task = web_crawl_task(&job, "example.com", &http, Some("/var/lib/easm/shots"));
+
+        assert_eq!(task.task_type, DiscoveryTaskType::WebAppScan);
+        assert_eq!(task.target, "https://example.com");
+        assert_eq!(task.screenshot_dir.as_deref(), Some("/var/lib/easm/shots"));
+        assert_eq!(task.http, http);
+        assert_eq!(task.job_id, job.id);
+
+        let task = web_crawl_task(&job, "http://example.com:8080/app", &http, None);
+        assert_eq!(task.target, "http://example.com:8080/app");
+        assert_eq!(task.screenshot_dir, None);
+    }
+
+    #[tokio::test]
+    async fn test_takeover_checker_failure_skips_the_check() {
+        let dns_enumerator = dns::DnsEnumerator::new().await.unwrap();
+        let mut results = DiscoveryResult::new();
+        results.domains.push(DiscoveredDomain {
+            domain_name: "example.com".to_string(),
+            source: "dns_enum".to_string(),
+        });
+
+        let findings = check_takeover(&dns_enumerator, &results, &unbuildable_http()).await;
+
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_takeover_remediation_names_the_record_and_target() {
+        let finding = DiscoveredVulnerability::new(
+            "shop.example.com".to_string(),
+            "Subdomain takeover".to_string(),
+            "high".to_string(),
+            "takeover-github-pages".to_string(),
+            "example.github.io".to_string(),
+        );
+
+        let remediation = takeover_remediation(&finding).unwrap();
+
+        assert!(remediation.contains("shop.example.com"));
+        assert!(remediation.contains("example.github.io"));
+    }
+
+    /// Resolver that must not be used, standing in for the network
+    struct NoLookups;
+
+    #[async_trait::async_trait]
+    impl HostResolver for NoLookups {
+        async fn resolve(&self, host: &str) -> Result<Vec<std::net::IpAddr>> {
+            panic!("dry run looked up {}", host);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_job_produces_plan_without_connecting() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let job = DiscoveryJob {
+            configuration: serde_json::json!({ "dry_run": true, "ports": [port] }),
+            ..job_for("127.0.0.1")
+        };
+        assert!(job.dry_run());
+
+        // Loopback is refused unless private networks are allowed
+        let scope = ScanScope::new(&[], false).unwrap();
+        let plan = plan_job(&job, &scope, &NoLookups).await.unwrap();
+        assert!(plan.targets.is_empty());
+        assert_eq!(plan.skipped[0].target, "127.0.0.1");
+        assert!(plan.skipped[0].reason.starts_with("private network"));
+
+        let scope = ScanScope::new(&[], true).unwrap();
+        let plan = plan_job(&job, &scope, &NoLookups).await.unwrap();
+
+        assert_eq!(plan.targets, vec!["127.0.0.1"]);
+        assert_eq!(plan.ports, vec![port]);
+        assert_eq!(plan.estimated_requests, 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), listener.accept())
+                .await
+                .is_err(),
+            "a dry run must not connect to the target"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_is_refused_for_unplanned_job_types() {
+        let job = DiscoveryJob {
+            job_type: JobType::WebCrawl,
+            ..job_for("https://example.com")
+        };
+        let scope = ScanScope::new(&[], false).unwrap();
+
+        let error = plan_job(&job, &scope, &NoLookups).await.unwrap_err();
+        assert!(error.to_string().contains("not supported"));
+    }
 }
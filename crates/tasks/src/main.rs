@@ -1,33 +1,198 @@
 use anyhow::Result;
-use infrastructure::database::Database;
-use shared::config::Config;
-use std::time::Duration;
+use discovery::asn::AsnDatabase;
+use discovery::cancellation::CancellationRegistry;
+use discovery::http_client::HttpClientConfig;
+use discovery::output::{NdjsonFileSink, OutputSink, WebhookSink};
+use discovery::scope::ScanScope;
+use discovery::shodan::ShodanClient;
+use infrastructure::database::{Database, DatabaseOptions};
+use shared::config::{Config, OutputSinkKind};
+use shared::logging::init_tracing;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 
 mod job_processor;
+mod metrics;
+mod recovery;
+mod scheduler;
+mod stale_assets;
+mod summary_reports;
+
+/// How often assets are checked for staleness
+const STALE_ASSET_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often organizations are checked for summary reports that are due
+const SUMMARY_REPORT_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
 
+    // Load configuration
+    let config = Config::from_env()?;
+
     // Initialize tracing subscriber
-    tracing_subscriber::fmt::init();
+    init_tracing(&config);
 
     tracing::info!("Starting tasks worker...");
-
-    // Load configuration
-    let config = Config::from_env()?;
     tracing::info!("Configuration loaded successfully.");
 
     // Initialize database pool
-    let db = Database::new(&config.database_url, 5).await?;
+    let db =
+        Database::connect(&config.database_url, &DatabaseOptions::from_config(&config)).await?;
     tracing::info!("Database pool initialized.");
 
+    // Jobs a previous worker was killed in the middle of will never finish
+    match recovery::reset_interrupted_jobs(&db.pool).await {
+        Ok(count) if count > 0 => tracing::warn!("Marked {} interrupted jobs failed.", count),
+        Ok(_) => tracing::debug!("No interrupted jobs found."),
+        Err(e) => tracing::error!("Error resetting interrupted jobs: {}", e),
+    }
+
+    // Enrichment is optional, so a missing or broken dataset doesn't stop the worker
+    let asn_database =
+        config
+            .asn_database_path
+            .as_deref()
+            .and_then(|path| match AsnDatabase::from_path(path) {
+                Ok(database) => {
+                    tracing::info!("Loaded {} ASN ranges from {}.", database.len(), path);
+                    Some(database)
+                }
+                Err(e) => {
+                    tracing::error!("Error loading ASN database, IPs won't be enriched: {:#}", e);
+                    None
+                }
+            });
+
+    // Everything the worker sends out goes through the configured proxy
+    let http = HttpClientConfig {
+        proxy: config.proxy_url.clone(),
+        ..HttpClientConfig::default()
+    };
+    if let Some(proxy) = &http.proxy {
+        tracing::info!("Sending discovery traffic through proxy {}.", proxy);
+        if http.socks_proxy().is_none() {
+            tracing::warn!("Port scans can only be proxied over SOCKS5 and will go out directly.");
+        }
+    }
+
+    // Targets no job may scan, whichever organization it belongs to
+    let scan_scope = ScanScope::new(&config.scan_blocklist, config.scan_private_networks)?;
+    if config.scan_private_networks {
+        tracing::info!("Scanning private networks is allowed.");
+    }
+
+    let shodan = match ShodanClient::from_api_key(config.shodan_api_key.as_deref(), &http) {
+        Ok(Some(client)) => {
+            tracing::info!("Shodan enrichment enabled.");
+            Some(client)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            tracing::error!(
+                "Error creating Shodan client, IPs won't be enriched: {:#}",
+                e
+            );
+            None
+        }
+    };
+
+    // Streaming is optional too, so a sink that can't be set up doesn't stop the worker
+    let output = match output_sink(&config).await {
+        Ok(Some(sink)) => {
+            tracing::info!(
+                "Streaming discovery events to the {:?} sink.",
+                config.output_sink
+            );
+            Some(sink)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            tracing::error!(
+                "Error setting up the output sink, discovery events won't be streamed: {:#}",
+                e
+            );
+            None
+        }
+    };
+
+    // Queue depth and job durations, for Prometheus to scrape
+    if let Some(port) = config.worker_metrics_port {
+        let addr = SocketAddr::new(config.host, port);
+        tracing::info!("Serving metrics on http://{}/metrics.", addr);
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(addr).await {
+                tracing::error!("Error serving metrics: {}", e);
+            }
+        });
+    }
+
+    // Cancellation tokens for the jobs this worker is running
+    let registry = CancellationRegistry::new();
+
+    // Stop picking up work on SIGTERM/SIGINT, letting the current batch finish
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            wait_for_shutdown_signal().await;
+            tracing::info!("Shutdown requested, finishing in-flight jobs...");
+            shutdown.cancel();
+        }
+    });
+
+    let mut last_stale_sweep: Option<Instant> = None;
+    let mut last_report_check: Option<Instant> = None;
+
     // Main worker loop
-    loop {
+    while !shutdown.is_cancelled() {
+        // Retire assets that haven't shown up in discovery for a while
+        if last_stale_sweep.is_none_or(|at| at.elapsed() >= STALE_ASSET_SWEEP_INTERVAL) {
+            match stale_assets::sweep_stale_assets(&db.pool, config.stale_asset_threshold_days)
+                .await
+            {
+                Ok(count) if count > 0 => tracing::info!("Marked {} stale assets inactive.", count),
+                Ok(_) => tracing::debug!("No stale assets found."),
+                Err(e) => tracing::error!("Error sweeping stale assets: {}", e),
+            }
+            last_stale_sweep = Some(Instant::now());
+        }
+
+        // Send each organization's summary report once per notification period
+        if last_report_check.is_none_or(|at| at.elapsed() >= SUMMARY_REPORT_CHECK_INTERVAL) {
+            match summary_reports::send_due_summary_reports(&db.pool).await {
+                Ok(count) if count > 0 => tracing::info!("Sent {} summary reports.", count),
+                Ok(_) => tracing::debug!("No summary reports due."),
+                Err(e) => tracing::error!("Error sending summary reports: {}", e),
+            }
+            last_report_check = Some(Instant::now());
+        }
+
+        // Enqueue jobs for recurring scans before picking up pending work
+        match scheduler::process_due_schedules(&db.pool).await {
+            Ok(count) if count > 0 => tracing::info!("Enqueued {} scheduled jobs.", count),
+            Ok(_) => tracing::debug!("No scheduled scans due."),
+            Err(e) => tracing::error!("Error processing scan schedules: {}", e),
+        }
+
         tracing::debug!("Checking for pending jobs...");
-        match job_processor::process_pending_jobs(&db.pool).await {
+        match job_processor::process_pending_jobs(
+            &db.pool,
+            &registry,
+            asn_database.as_ref(),
+            shodan.as_ref(),
+            &http,
+            config.screenshot_dir.as_deref(),
+            &scan_scope,
+            Duration::from_secs(config.notification_suppression_secs),
+            output.as_deref(),
+        )
+        .await
+        {
             Ok(count) => {
                 if count > 0 {
                     tracing::info!("Processed {} jobs.", count);
@@ -42,6 +207,73 @@ async fn main() -> Result<()> {
 
         // Sleep for a configurable interval before checking again
         // TODO: Make interval configurable
-        sleep(Duration::from_secs(30)).await;
+        tokio::select! {
+            _ = sleep(Duration::from_secs(30)) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+
+    tracing::info!("Tasks worker stopped.");
+    Ok(())
+}
+
+/// The sink discovery events are streamed to, if one is configured
+async fn output_sink(config: &Config) -> Result<Option<Box<dyn OutputSink>>> {
+    let sink: Box<dyn OutputSink> = match config.output_sink {
+        None => return Ok(None),
+        Some(OutputSinkKind::Webhook) => {
+            let url = config.output_webhook_url.as_deref().unwrap_or_default();
+            Box::new(WebhookSink::new(url)?)
+        }
+        Some(OutputSinkKind::File) => {
+            let path = config.output_file_path.as_deref().unwrap_or_default();
+            Box::new(NdjsonFileSink::open(path).await?)
+        }
+        #[cfg(feature = "kafka")]
+        Some(OutputSinkKind::Kafka) => Box::new(
+            discovery::output::KafkaSink::connect(
+                &config.output_kafka_brokers,
+                &config.output_kafka_topic,
+            )
+            .await?,
+        ),
+        #[cfg(not(feature = "kafka"))]
+        Some(OutputSinkKind::Kafka) => {
+            anyhow::bail!("this worker was built without Kafka support (the `kafka` feature)")
+        }
+    };
+    Ok(Some(sink))
+}
+
+/// Resolve once the process receives SIGINT (Ctrl+C) or SIGTERM
+async fn wait_for_shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
     }
 }
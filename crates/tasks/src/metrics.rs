@@ -0,0 +1,168 @@
+use std::{net::SocketAddr, sync::LazyLock, time::Duration};
+
+use anyhow::Result;
+use axum::{http::header, routing::get, Router};
+use backend::{models::DiscoveryJobFilter, traits::DiscoveryJobRepository};
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry};
+use shared::{
+    metrics::{registry, render, METRICS_CONTENT_TYPE},
+    types::{JobStatus, JobType},
+};
+
+/// Bucket bounds for job durations, in seconds, from a quick DNS lookup to
+/// a port scan of a large range
+const JOB_DURATION_BUCKETS: [f64; 10] = [
+    1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0,
+];
+
+/// Statuses counted for the job queue
+const QUEUE_STATUSES: [JobStatus; 3] = [JobStatus::Pending, JobStatus::Running, JobStatus::Failed];
+
+static WORKER_METRICS: LazyLock<WorkerMetrics> =
+    LazyLock::new(|| WorkerMetrics::new(registry()).expect("worker metrics registered once"));
+
+/// Queue depth and job outcomes recorded by the worker
+pub struct WorkerMetrics {
+    jobs: IntGaugeVec,
+    job_duration: HistogramVec,
+    jobs_processed: IntCounterVec,
+}
+
+impl WorkerMetrics {
+    /// Metrics registered with `registry`
+    pub fn new(registry: &Registry) -> prometheus::Result<Self> {
+        let jobs = IntGaugeVec::new(
+            Opts::new("discovery_jobs", "Discovery jobs by status"),
+            &["status"],
+        )?;
+        let job_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "discovery_job_duration_seconds",
+                "Time taken to run a discovery job",
+            )
+            .buckets(JOB_DURATION_BUCKETS.to_vec()),
+            &["job_type"],
+        )?;
+        let jobs_processed = IntCounterVec::new(
+            Opts::new(
+                "discovery_jobs_processed_total",
+                "Discovery jobs run by the worker",
+            ),
+            &["job_type", "status"],
+        )?;
+
+        registry.register(Box::new(jobs.clone()))?;
+        registry.register(Box::new(job_duration.clone()))?;
+        registry.register(Box::new(jobs_processed.clone()))?;
+
+        Ok(Self {
+            jobs,
+            job_duration,
+            jobs_processed,
+        })
+    }
+
+    /// Metrics in the registry exported by `/metrics`
+    pub fn global() -> &'static Self {
+        &WORKER_METRICS
+    }
+
+    /// Record the number of jobs in each queue status
+    pub async fn record_queue_depth(&self, repository: &dyn DiscoveryJobRepository) {
+        for status in QUEUE_STATUSES {
+            let filter = DiscoveryJobFilter {
+                statuses: vec![status],
+                ..Default::default()
+            };
+            match repository.count_jobs(&filter).await {
+                Ok(count) => self
+                    .jobs
+                    .with_label_values(&[status_label(status)])
+                    .set(count as i64),
+                Err(e) => tracing::warn!("Failed to count {} jobs: {}", status, e),
+            }
+        }
+    }
+
+    /// Record a job of `job_type` that ended in `status` after `duration`
+    pub fn record_job(&self, job_type: JobType, status: JobStatus, duration: Duration) {
+        let job_type = job_type_label(job_type);
+        self.job_duration
+            .with_label_values(&[job_type])
+            .observe(duration.as_secs_f64());
+        self.jobs_processed
+            .with_label_values(&[job_type, status_label(status)])
+            .inc();
+    }
+}
+
+/// Serve the worker's metrics on `addr` until the process exits
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let router = Router::new().route(
+        "/metrics",
+        get(|| async {
+            (
+                [(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)],
+                render(registry()),
+            )
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+fn job_type_label(job_type: JobType) -> &'static str {
+    match job_type {
+        JobType::DnsEnum => "dns_enum",
+        JobType::PortScan => "port_scan",
+        JobType::WebCrawl => "web_crawl",
+        JobType::CertScan => "cert_scan",
+        JobType::VulnScan => "vuln_scan",
+    }
+}
+
+fn status_label(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Pending => "pending",
+        JobStatus::Running => "running",
+        JobStatus::Completed => "completed",
+        JobStatus::Failed => "failed",
+        JobStatus::Cancelled => "cancelled",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completed_job_is_observed() {
+        let registry = Registry::new();
+        let metrics = WorkerMetrics::new(&registry).unwrap();
+
+        metrics.record_job(
+            JobType::PortScan,
+            JobStatus::Completed,
+            Duration::from_secs(42),
+        );
+
+        let histogram = metrics.job_duration.with_label_values(&["port_scan"]);
+        assert_eq!(histogram.get_sample_count(), 1);
+        assert_eq!(histogram.get_sample_sum(), 42.0);
+        assert_eq!(
+            metrics
+                .jobs_processed
+                .with_label_values(&["port_scan", "completed"])
+                .get(),
+            1
+        );
+
+        let exported = render(&registry);
+        assert!(exported.contains("discovery_job_duration_seconds_count{job_type=\"port_scan\"} 1"));
+        assert!(exported.contains(
+            "discovery_jobs_processed_total{job_type=\"port_scan\",status=\"completed\"} 1"
+        ));
+    }
+}
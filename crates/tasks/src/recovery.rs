@@ -0,0 +1,203 @@
+use anyhow::Result;
+use backend::DiscoveryJobRepository;
+use chrono::{DateTime, Utc};
+use infrastructure::repositories::RepositoryFactory;
+use shared::types::JobStatus;
+use sqlx::PgPool;
+
+/// Number of interrupted jobs fetched at a time
+const RECOVERY_BATCH_SIZE: usize = 100;
+
+/// Reason recorded on jobs a previous worker left running
+const INTERRUPTED_REASON: &str = "Interrupted: the worker stopped before the job finished";
+
+/// Fail every job left RUNNING by a worker that crashed or was killed, so
+/// they don't stay stuck in RUNNING forever. Only one worker runs at a time,
+/// so on startup no job can legitimately be running yet.
+/// Returns the number of jobs reset
+pub async fn reset_interrupted_jobs(pool: &PgPool) -> Result<usize> {
+    let repo_factory = RepositoryFactory::new(pool.clone());
+    let job_repository = repo_factory.discovery_job_repository();
+
+    fail_running_jobs(job_repository.as_ref(), Utc::now()).await
+}
+
+/// Mark every RUNNING job as FAILED at `now`, recording why in its logs
+async fn fail_running_jobs(
+    job_repository: &dyn DiscoveryJobRepository,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let mut reset = 0;
+
+    loop {
+        let jobs = job_repository
+            .list_jobs_by_status(JobStatus::Running, RECOVERY_BATCH_SIZE)
+            .await?;
+        if jobs.is_empty() {
+            break;
+        }
+
+        for mut job in jobs {
+            tracing::warn!(
+                "Job {} ({:?}) was left running by a previous worker, marking it failed",
+                job.id,
+                job.job_type
+            );
+
+            job.status = JobStatus::Failed;
+            job.completed_at = Some(now);
+            job.updated_at = now;
+            job.logs = Some(match job.logs.take() {
+                Some(logs) if !logs.is_empty() => format!("{}\n{}", logs, INTERRUPTED_REASON),
+                _ => INTERRUPTED_REASON.to_string(),
+            });
+            job_repository.update_job(&job).await?;
+
+            reset += 1;
+        }
+    }
+
+    Ok(reset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::{
+        models::{Asset, DiscoveryJob, JobAssetLink},
+        Result as BackendResult,
+    };
+    use mockall::{mock, predicate::*, Sequence};
+    use shared::types::JobType;
+    use uuid::Uuid;
+
+    mock! {
+        pub DiscoveryJobRepository {}
+
+        #[async_trait::async_trait]
+        impl DiscoveryJobRepository for DiscoveryJobRepository {
+            async fn create_job(&self, job: &DiscoveryJob) -> BackendResult<DiscoveryJob>;
+            async fn get_job(&self, id: Uuid) -> BackendResult<DiscoveryJob>;
+            async fn update_job(&self, job: &DiscoveryJob) -> BackendResult<DiscoveryJob>;
+            async fn delete_job(&self, id: Uuid) -> BackendResult<bool>;
+            async fn list_jobs(
+                &self,
+                filter: &backend::models::DiscoveryJobFilter,
+                sort: Option<shared::types::Sort<shared::types::JobSortField>>,
+                limit: usize,
+                offset: usize,
+            ) -> BackendResult<Vec<DiscoveryJob>>;
+            async fn count_jobs(&self, filter: &backend::models::DiscoveryJobFilter) -> BackendResult<usize>;
+            async fn list_jobs_by_status(
+                &self,
+                status: JobStatus,
+                limit: usize,
+            ) -> BackendResult<Vec<DiscoveryJob>>;
+            async fn create_job_asset_link(&self, link: &JobAssetLink) -> BackendResult<JobAssetLink>;
+            async fn link_job_to_assets(&self, job_id: Uuid, asset_ids: &[Uuid]) -> BackendResult<usize>;
+            async fn get_job_assets(&self, job_id: Uuid) -> BackendResult<Vec<Asset>>;
+            async fn get_job_summary(&self, job_id: Uuid) -> BackendResult<backend::models::JobResultSummary>;
+            async fn record_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+                scanned_at: chrono::DateTime<chrono::Utc>,
+            ) -> BackendResult<()>;
+            async fn last_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+            ) -> BackendResult<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>>;
+        }
+    }
+
+    fn running_job(logs: Option<&str>) -> DiscoveryJob {
+        let mut job = DiscoveryJob::new(
+            Uuid::new_v4(),
+            JobType::PortScan,
+            Some("192.0.2.10".to_string()),
+            None,
+        );
+        job.status = JobStatus::Running;
+        job.started_at = Some(Utc::now());
+        job.logs = logs.map(str::to_string);
+        job
+    }
+
+    #[tokio::test]
+    async fn test_running_jobs_are_marked_failed() {
+        let now = Utc::now();
+        let jobs = vec![running_job(None), running_job(Some("Scanned 100 ports"))];
+
+        // Failed jobs drop out of the RUNNING listing
+        let mut seq = Sequence::new();
+        let mut mock_repo = MockDiscoveryJobRepository::new();
+        mock_repo
+            .expect_list_jobs_by_status()
+            .with(eq(JobStatus::Running), eq(RECOVERY_BATCH_SIZE))
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _| Ok(jobs.clone()));
+        mock_repo
+            .expect_update_job()
+            .withf(move |job: &DiscoveryJob| {
+                job.status == JobStatus::Failed
+                    && job.completed_at == Some(now)
+                    && job
+                        .logs
+                        .as_deref()
+                        .is_some_and(|logs| logs.ends_with(INTERRUPTED_REASON))
+            })
+            .times(2)
+            .in_sequence(&mut seq)
+            .returning(|job| Ok(job.clone()));
+        mock_repo
+            .expect_list_jobs_by_status()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(vec![]));
+
+        let reset = fail_running_jobs(&mock_repo, now).await.unwrap();
+        assert_eq!(reset, 2);
+    }
+
+    #[tokio::test]
+    async fn test_existing_logs_are_kept() {
+        let job = running_job(Some("Scanned 100 ports"));
+
+        let mut listed = false;
+        let mut mock_repo = MockDiscoveryJobRepository::new();
+        mock_repo
+            .expect_list_jobs_by_status()
+            .times(2)
+            .returning(move |_, _| {
+                let jobs = if listed { vec![] } else { vec![job.clone()] };
+                listed = true;
+                Ok(jobs)
+            });
+        mock_repo
+            .expect_update_job()
+            .withf(|job: &DiscoveryJob| {
+                job.logs.as_deref()
+                    == Some(&format!("Scanned 100 ports\n{}", INTERRUPTED_REASON)[..])
+            })
+            .times(1)
+            .returning(|job| Ok(job.clone()));
+
+        assert_eq!(fail_running_jobs(&mock_repo, Utc::now()).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_running_jobs() {
+        let mut mock_repo = MockDiscoveryJobRepository::new();
+        mock_repo
+            .expect_list_jobs_by_status()
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        mock_repo.expect_update_job().never();
+
+        assert_eq!(fail_running_jobs(&mock_repo, Utc::now()).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,247 @@
+use anyhow::Result;
+use backend::{
+    models::{DiscoveryJob, ScanSchedule},
+    DiscoveryJobRepository, ScanScheduleRepository,
+};
+use chrono::{DateTime, Utc};
+use infrastructure::repositories::RepositoryFactory;
+use sqlx::PgPool;
+
+/// Enqueue jobs for every enabled schedule that has come due
+/// Returns the number of jobs enqueued
+pub async fn process_due_schedules(pool: &PgPool) -> Result<usize> {
+    let repo_factory = RepositoryFactory::new(pool.clone());
+    let schedule_repository = repo_factory.scan_schedule_repository();
+    let job_repository = repo_factory.discovery_job_repository();
+
+    enqueue_due_schedules(
+        schedule_repository.as_ref(),
+        job_repository.as_ref(),
+        Utc::now(),
+    )
+    .await
+}
+
+/// Create a pending `DiscoveryJob` for each schedule due at `now` and record
+/// the run on the schedule so it isn't enqueued again until its next slot
+async fn enqueue_due_schedules(
+    schedule_repository: &dyn ScanScheduleRepository,
+    job_repository: &dyn DiscoveryJobRepository,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let schedules = schedule_repository.list_enabled_schedules().await?;
+
+    let mut enqueued = 0;
+
+    for mut schedule in schedules {
+        match schedule.is_due(now) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                // A bad expression shouldn't stop the other schedules from running
+                tracing::warn!("Skipping schedule {}: {}", schedule.id, e);
+                continue;
+            }
+        }
+
+        let job = scheduled_job(&schedule);
+        let job = job_repository.create_job(&job).await?;
+        tracing::info!(
+            "Enqueued job {} ({:?}) for schedule {}",
+            job.id,
+            job.job_type,
+            schedule.id
+        );
+
+        schedule.last_run_at = Some(now);
+        schedule.updated_at = now;
+        schedule_repository.update_schedule(&schedule).await?;
+
+        enqueued += 1;
+    }
+
+    Ok(enqueued)
+}
+
+/// Build the job a schedule enqueues when it comes due
+fn scheduled_job(schedule: &ScanSchedule) -> DiscoveryJob {
+    let mut config = serde_json::Map::new();
+    config.insert(
+        "schedule_id".to_string(),
+        serde_json::Value::String(schedule.id.to_string()),
+    );
+
+    DiscoveryJob::new(
+        schedule.organization_id,
+        schedule.job_type,
+        Some(schedule.target.clone()),
+        Some(serde_json::Value::Object(config)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::{
+        models::{Asset, JobAssetLink},
+        Result as BackendResult,
+    };
+    use chrono::Duration;
+    use mockall::{mock, predicate::*};
+    use shared::types::{JobStatus, JobType};
+    use uuid::Uuid;
+
+    mock! {
+        pub ScanScheduleRepository {}
+
+        #[async_trait::async_trait]
+        impl ScanScheduleRepository for ScanScheduleRepository {
+            async fn create_schedule(&self, schedule: &ScanSchedule) -> BackendResult<ScanSchedule>;
+            async fn get_schedule(&self, id: Uuid) -> BackendResult<ScanSchedule>;
+            async fn update_schedule(&self, schedule: &ScanSchedule) -> BackendResult<ScanSchedule>;
+            async fn delete_schedule(&self, id: Uuid) -> BackendResult<bool>;
+            async fn list_schedules(
+                &self,
+                organization_id: Option<Uuid>,
+                limit: usize,
+                offset: usize,
+            ) -> BackendResult<Vec<ScanSchedule>>;
+            async fn count_schedules(&self, organization_id: Option<Uuid>) -> BackendResult<usize>;
+            async fn list_enabled_schedules(&self) -> BackendResult<Vec<ScanSchedule>>;
+        }
+    }
+
+    mock! {
+        pub DiscoveryJobRepository {}
+
+        #[async_trait::async_trait]
+        impl DiscoveryJobRepository for DiscoveryJobRepository {
+            async fn create_job(&self, job: &DiscoveryJob) -> BackendResult<DiscoveryJob>;
+            async fn get_job(&self, id: Uuid) -> BackendResult<DiscoveryJob>;
+            async fn update_job(&self, job: &DiscoveryJob) -> BackendResult<DiscoveryJob>;
+            async fn delete_job(&self, id: Uuid) -> BackendResult<bool>;
+            async fn list_jobs(
+                &self,
+                filter: &backend::models::DiscoveryJobFilter,
+                sort: Option<shared::types::Sort<shared::types::JobSortField>>,
+                limit: usize,
+                offset: usize,
+            ) -> BackendResult<Vec<DiscoveryJob>>;
+            async fn count_jobs(&self, filter: &backend::models::DiscoveryJobFilter) -> BackendResult<usize>;
+            async fn list_jobs_by_status(
+                &self,
+                status: JobStatus,
+                limit: usize,
+            ) -> BackendResult<Vec<DiscoveryJob>>;
+            async fn create_job_asset_link(&self, link: &JobAssetLink) -> BackendResult<JobAssetLink>;
+            async fn link_job_to_assets(&self, job_id: Uuid, asset_ids: &[Uuid]) -> BackendResult<usize>;
+            async fn get_job_assets(&self, job_id: Uuid) -> BackendResult<Vec<Asset>>;
+            async fn get_job_summary(&self, job_id: Uuid) -> BackendResult<backend::models::JobResultSummary>;
+            async fn record_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+                scanned_at: chrono::DateTime<chrono::Utc>,
+            ) -> BackendResult<()>;
+            async fn last_target_scans(
+                &self,
+                organization_id: Uuid,
+                job_type: JobType,
+                targets: &[String],
+            ) -> BackendResult<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>>;
+        }
+    }
+
+    /// Schedule that runs every minute, created two minutes before `now`
+    fn every_minute_schedule(now: DateTime<Utc>) -> ScanSchedule {
+        let mut schedule = ScanSchedule::new(
+            Uuid::new_v4(),
+            "example.com".to_string(),
+            JobType::DnsEnum,
+            "0 * * * * *".to_string(),
+        );
+        schedule.created_at = now - Duration::minutes(2);
+        schedule
+    }
+
+    #[tokio::test]
+    async fn test_due_schedule_enqueues_job() {
+        let now = Utc::now();
+        let schedule = every_minute_schedule(now);
+        let schedule_id = schedule.id;
+        let organization_id = schedule.organization_id;
+
+        let mut schedule_repo = MockScanScheduleRepository::new();
+        schedule_repo
+            .expect_list_enabled_schedules()
+            .times(1)
+            .returning(move || Ok(vec![schedule.clone()]));
+        schedule_repo
+            .expect_update_schedule()
+            .withf(move |s| s.id == schedule_id && s.last_run_at == Some(now))
+            .times(1)
+            .returning(|s| Ok(s.clone()));
+
+        let mut job_repo = MockDiscoveryJobRepository::new();
+        job_repo
+            .expect_create_job()
+            .withf(move |job| {
+                job.organization_id == organization_id
+                    && job.job_type == JobType::DnsEnum
+                    && job.status == JobStatus::Pending
+                    && job.target.as_deref() == Some("example.com")
+            })
+            .times(1)
+            .returning(|job| Ok(job.clone()));
+
+        let enqueued = enqueue_due_schedules(&schedule_repo, &job_repo, now)
+            .await
+            .unwrap();
+        assert_eq!(enqueued, 1);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_schedule_does_not_enqueue_job() {
+        let now = Utc::now();
+        let mut schedule = every_minute_schedule(now);
+        schedule.enabled = false;
+
+        let mut schedule_repo = MockScanScheduleRepository::new();
+        schedule_repo
+            .expect_list_enabled_schedules()
+            .times(1)
+            .returning(move || Ok(vec![schedule.clone()]));
+        schedule_repo.expect_update_schedule().never();
+
+        let mut job_repo = MockDiscoveryJobRepository::new();
+        job_repo.expect_create_job().never();
+
+        let enqueued = enqueue_due_schedules(&schedule_repo, &job_repo, now)
+            .await
+            .unwrap();
+        assert_eq!(enqueued, 0);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_not_due_again_until_next_slot() {
+        let now = Utc::now();
+        let mut schedule = every_minute_schedule(now);
+        schedule.last_run_at = Some(now);
+
+        let mut schedule_repo = MockScanScheduleRepository::new();
+        schedule_repo
+            .expect_list_enabled_schedules()
+            .times(1)
+            .returning(move || Ok(vec![schedule.clone()]));
+        schedule_repo.expect_update_schedule().never();
+
+        let mut job_repo = MockDiscoveryJobRepository::new();
+        job_repo.expect_create_job().never();
+
+        let enqueued = enqueue_due_schedules(&schedule_repo, &job_repo, now)
+            .await
+            .unwrap();
+        assert_eq!(enqueued, 0);
+    }
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+use chrono::Duration;
+use infrastructure::repositories::RepositoryFactory;
+use sqlx::PgPool;
+
+/// Mark assets across all organizations that haven't been seen for
+/// `threshold_days` as inactive
+/// Returns the number of assets transitioned
+pub async fn sweep_stale_assets(pool: &PgPool, threshold_days: i64) -> Result<usize> {
+    let repo_factory = RepositoryFactory::new(pool.clone());
+    let asset_repository = repo_factory.asset_repository();
+
+    let marked = asset_repository
+        .mark_stale(None, Duration::days(threshold_days))
+        .await?;
+
+    Ok(marked)
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use backend::{services::NotificationServiceImpl, NotificationService, OrganizationRepository};
+use chrono::{DateTime, Utc};
+use infrastructure::repositories::RepositoryFactory;
+use sqlx::PgPool;
+
+/// Organizations fetched per page while looking for reports to send
+const ORGANIZATION_PAGE_SIZE: usize = 100;
+
+/// Send a summary report to every organization whose notification period
+/// has passed since its last one
+/// Returns the number of reports sent
+pub async fn send_due_summary_reports(pool: &PgPool) -> Result<usize> {
+    let repo_factory = RepositoryFactory::new(pool.clone());
+    let organization_repository = repo_factory.organization_repository();
+    let notification_service = NotificationServiceImpl::new(
+        repo_factory.asset_repository(),
+        repo_factory.vulnerability_repository(),
+    );
+
+    dispatch_due_reports(
+        organization_repository.as_ref(),
+        &notification_service,
+        Utc::now(),
+    )
+    .await
+}
+
+/// Send the reports due at `now` and record when each went out, so an
+/// organization isn't sent another until its next period is up
+async fn dispatch_due_reports(
+    organization_repository: &dyn OrganizationRepository,
+    notification_service: &dyn NotificationService,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let mut sent = 0;
+    let mut offset = 0;
+
+    loop {
+        let organizations = organization_repository
+            .list_organizations(ORGANIZATION_PAGE_SIZE, offset)
+            .await?;
+        let page_len = organizations.len();
+
+        for organization in organizations {
+            if !organization.is_summary_report_due(now) {
+                continue;
+            }
+
+            match notification_service
+                .send_summary_report(organization.id, organization.notification_period)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::debug!(
+                        "Summary report for organization {} was not sent",
+                        organization.id
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    // One organization's failure shouldn't hold up the others
+                    tracing::warn!(
+                        "Error sending summary report for organization {}: {}",
+                        organization.id,
+                        e
+                    );
+                    continue;
+                }
+            }
+
+            organization_repository
+                .record_report_sent(organization.id, now)
+                .await?;
+            sent += 1;
+        }
+
+        if page_len < ORGANIZATION_PAGE_SIZE {
+            break;
+        }
+        offset += page_len;
+    }
+
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::{
+        models::{Asset, Organization, Vulnerability},
+        NotificationPeriod, NotificationSettings, Result as BackendResult,
+    };
+    use chrono::Duration;
+    use mockall::{mock, predicate::*};
+    use shared::types::VulnerabilityStatus;
+    use uuid::Uuid;
+
+    mock! {
+        pub OrganizationRepository {}
+
+        #[async_trait::async_trait]
+        impl OrganizationRepository for OrganizationRepository {
+            async fn create_organization(&self, organization: &Organization) -> BackendResult<Organization>;
+            async fn get_organization(&self, id: Uuid) -> BackendResult<Organization>;
+            async fn get_organization_by_name(&self, name: &str) -> BackendResult<Option<Organization>>;
+            async fn update_organization(&self, organization: &Organization) -> BackendResult<Organization>;
+            async fn delete_organization(&self, id: Uuid) -> BackendResult<bool>;
+            async fn list_organizations(&self, limit: usize, offset: usize) -> BackendResult<Vec<Organization>>;
+            async fn count_organizations(&self) -> BackendResult<usize>;
+            async fn record_report_sent(
+                &self,
+                id: Uuid,
+                sent_at: DateTime<Utc>,
+            ) -> BackendResult<bool>;
+        }
+    }
+
+    mock! {
+        pub NotificationService {}
+
+        #[async_trait::async_trait]
+        impl NotificationService for NotificationService {
+            async fn notify_new_vulnerability(&self, vulnerability: &Vulnerability) -> BackendResult<bool>;
+            async fn notify_vulnerability_status_change(
+                &self,
+                vulnerability: &Vulnerability,
+                old_status: VulnerabilityStatus,
+            ) -> BackendResult<bool>;
+            async fn notify_new_critical_asset(&self, asset: &Asset) -> BackendResult<bool>;
+            async fn notify_content_change(&self, asset: &Asset, previous_hash: &str) -> BackendResult<bool>;
+            async fn send_summary_report(
+                &self,
+                organization_id: Uuid,
+                period: NotificationPeriod,
+            ) -> BackendResult<bool>;
+            async fn get_notification_settings(&self, organization_id: Uuid) -> BackendResult<NotificationSettings>;
+            async fn update_notification_settings(
+                &self,
+                organization_id: Uuid,
+                settings: &NotificationSettings,
+            ) -> BackendResult<NotificationSettings>;
+            async fn notify_new_vulnerabilities_batch(
+                &self,
+                vulnerabilities: &[Vulnerability],
+            ) -> BackendResult<bool>;
+        }
+    }
+
+    /// Daily organization last sent a report `hours_ago` hours before `now`
+    fn daily_organization(name: &str, now: DateTime<Utc>, hours_ago: i64) -> Organization {
+        let mut organization = Organization::new(name.to_string());
+        organization.notification_period = NotificationPeriod::Daily;
+        organization.last_report_sent_at = Some(now - Duration::hours(hours_ago));
+        organization
+    }
+
+    #[tokio::test]
+    async fn test_daily_report_sent_only_once_a_day() {
+        let now = Utc::now();
+        let overdue = daily_organization("Overdue", now, 25);
+        let recent = daily_organization("Recent", now, 2);
+        let overdue_id = overdue.id;
+
+        let mut organization_repo = MockOrganizationRepository::new();
+        organization_repo
+            .expect_list_organizations()
+            .with(eq(ORGANIZATION_PAGE_SIZE), eq(0))
+            .times(1)
+            .returning(move |_, _| Ok(vec![overdue.clone(), recent.clone()]));
+        organization_repo
+            .expect_record_report_sent()
+            .with(eq(overdue_id), eq(now))
+            .times(1)
+            .returning(|_, _| Ok(true));
+
+        let mut notification_service = MockNotificationService::new();
+        notification_service
+            .expect_send_summary_report()
+            .with(eq(overdue_id), eq(NotificationPeriod::Daily))
+            .times(1)
+            .returning(|_, _| Ok(true));
+
+        let sent = dispatch_due_reports(&organization_repo, &notification_service, now)
+            .await
+            .unwrap();
+        assert_eq!(sent, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_report_is_retried_later() {
+        let now = Utc::now();
+        let organization = daily_organization("Failing", now, 25);
+
+        let mut organization_repo = MockOrganizationRepository::new();
+        organization_repo
+            .expect_list_organizations()
+            .times(1)
+            .returning(move |_, _| Ok(vec![organization.clone()]));
+        organization_repo.expect_record_report_sent().never();
+
+        let mut notification_service = MockNotificationService::new();
+        notification_service
+            .expect_send_summary_report()
+            .times(1)
+            .returning(|_, _| Err(backend::Error::Internal("SMTP down".to_string())));
+
+        let sent = dispatch_due_reports(&organization_repo, &notification_service, now)
+            .await
+            .unwrap();
+        assert_eq!(sent, 0);
+    }
+}
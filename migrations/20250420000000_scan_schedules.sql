@@ -0,0 +1,15 @@
+-- Recurring discovery scans, enqueued as discovery_jobs by the tasks worker
+CREATE TABLE scan_schedules (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    target TEXT NOT NULL,
+    job_type VARCHAR(50) NOT NULL,         -- e.g., 'DNSENUM', 'PORTSCAN'
+    cron_expression VARCHAR(255) NOT NULL, -- cron with seconds, e.g. '0 0 2 * * *'
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_run_at TIMESTAMPTZ,               -- When a job was last enqueued
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_scan_schedules_organization_id ON scan_schedules(organization_id);
+CREATE INDEX idx_scan_schedules_enabled ON scan_schedules(enabled);
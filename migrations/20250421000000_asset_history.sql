@@ -0,0 +1,11 @@
+-- Field-level change log for assets, written when an asset is updated
+CREATE TABLE asset_history (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    asset_id UUID NOT NULL REFERENCES assets(id) ON DELETE CASCADE,
+    field VARCHAR(255) NOT NULL, -- e.g., 'status', 'attributes.open_ports'
+    old_value JSONB,             -- NULL when the field was added
+    new_value JSONB,             -- NULL when the field was removed
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_asset_history_asset_id_changed_at ON asset_history(asset_id, changed_at);
@@ -0,0 +1,8 @@
+-- Soft delete: rows are marked with deleted_at instead of being removed
+ALTER TABLE assets ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE vulnerabilities ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- A deleted asset must not block rediscovering the same asset
+ALTER TABLE assets DROP CONSTRAINT assets_organization_id_asset_type_value_key;
+CREATE UNIQUE INDEX idx_assets_unique_live ON assets(organization_id, asset_type, value)
+    WHERE deleted_at IS NULL;
@@ -0,0 +1,9 @@
+-- Free-form tags analysts use to group assets, e.g. "prod" or "pci-scope"
+CREATE TABLE asset_tags (
+    asset_id UUID NOT NULL REFERENCES assets(id) ON DELETE CASCADE,
+    tag VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (asset_id, tag)
+);
+
+CREATE INDEX idx_asset_tags_tag ON asset_tags(tag);
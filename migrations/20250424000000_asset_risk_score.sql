@@ -0,0 +1,5 @@
+-- Risk score (0-100) derived from an asset's open vulnerabilities and
+-- exposed ports, recomputed by the application whenever either changes
+ALTER TABLE assets ADD COLUMN risk_score REAL NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_assets_risk_score ON assets(organization_id, risk_score DESC);
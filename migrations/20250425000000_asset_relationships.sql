@@ -0,0 +1,31 @@
+-- Relationships between assets, previously kept in the source asset's
+-- attributes under "relationships". The primary key serves lookups by
+-- source and the target index serves "what points at this asset".
+CREATE TABLE asset_relationships (
+    source_id UUID NOT NULL REFERENCES assets(id) ON DELETE CASCADE,
+    target_id UUID NOT NULL REFERENCES assets(id) ON DELETE CASCADE,
+    relationship_type VARCHAR(100) NOT NULL,
+    metadata JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (source_id, relationship_type, target_id)
+);
+
+CREATE INDEX idx_asset_relationships_target ON asset_relationships(target_id, relationship_type);
+
+-- Move existing relationships over, dropping ones whose target is gone
+INSERT INTO asset_relationships (source_id, target_id, relationship_type)
+SELECT DISTINCT source.id, target.id, LEFT(relationship.key, 100)
+FROM assets source
+CROSS JOIN LATERAL jsonb_each(
+    CASE WHEN jsonb_typeof(source.attributes->'relationships') = 'object'
+        THEN source.attributes->'relationships' ELSE '{}'::jsonb END
+) AS relationship
+CROSS JOIN LATERAL jsonb_array_elements_text(
+    CASE WHEN jsonb_typeof(relationship.value) = 'array'
+        THEN relationship.value ELSE '[]'::jsonb END
+) AS related(id)
+JOIN assets target ON target.id::text = related.id
+ON CONFLICT DO NOTHING;
+
+UPDATE assets SET attributes = attributes - 'relationships'
+WHERE attributes ? 'relationships';
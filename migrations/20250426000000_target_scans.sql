@@ -0,0 +1,9 @@
+-- When each target was last scanned by each kind of job, so incremental
+-- discovery can skip targets scanned recently
+CREATE TABLE target_scans (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    job_type VARCHAR(50) NOT NULL,
+    target TEXT NOT NULL,
+    scanned_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (organization_id, job_type, target)
+);
@@ -0,0 +1,15 @@
+-- Named presets of discovery methods and scan settings, on top of the
+-- built-in Quick, Standard and Deep profiles every organization gets
+CREATE TABLE scan_profiles (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    methods JSONB NOT NULL,          -- job types, e.g. ["DNSENUM", "PORTSCAN"]
+    depth INTEGER NOT NULL,
+    ports INTEGER[] NOT NULL,
+    timeout_secs INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_scan_profiles_organization_name ON scan_profiles(organization_id, LOWER(name));
@@ -0,0 +1,16 @@
+-- Who changed what through the API, and when, for compliance
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,              -- kept when the user is deleted
+    organization_id UUID,               -- from the acting user's token
+    action VARCHAR(20) NOT NULL,        -- 'CREATE', 'UPDATE' or 'DELETE'
+    resource_type VARCHAR(50) NOT NULL, -- e.g. 'asset', 'vulnerability'
+    resource_id UUID,                   -- NULL for bulk changes
+    ip_address VARCHAR(45),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX idx_audit_log_organization_id_created_at ON audit_log(organization_id, created_at);
+CREATE INDEX idx_audit_log_user_id_created_at ON audit_log(user_id, created_at);
+CREATE INDEX idx_audit_log_resource ON audit_log(resource_type, resource_id);
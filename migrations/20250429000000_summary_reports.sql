@@ -0,0 +1,4 @@
+-- How often an organization is sent a summary report, and when the last one
+-- went out, so the worker can send each report once per period
+ALTER TABLE organizations ADD COLUMN notification_period VARCHAR(20) NOT NULL DEFAULT 'DAILY';
+ALTER TABLE organizations ADD COLUMN last_report_sent_at TIMESTAMPTZ;
@@ -0,0 +1,12 @@
+-- When each alert was last sent to an organization, keyed by a fingerprint
+-- of its content, so an identical alert isn't sent again within the
+-- suppression window
+CREATE TABLE notification_log (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    event_type VARCHAR(50) NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, fingerprint)
+);
+
+CREATE INDEX idx_notification_log_sent_at ON notification_log(sent_at);
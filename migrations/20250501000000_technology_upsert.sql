@@ -0,0 +1,12 @@
+-- A technology is tracked once per asset and name, with its version updated
+-- in place when a rescan detects a different one. Duplicates left by earlier
+-- scans are collapsed into the most recently seen row first.
+DELETE FROM technologies t
+USING technologies newer
+WHERE t.asset_id = newer.asset_id
+    AND t.name = newer.name
+    AND (t.last_seen, t.id) < (newer.last_seen, newer.id);
+
+ALTER TABLE technologies DROP CONSTRAINT IF EXISTS technologies_asset_id_name_version_key;
+
+ALTER TABLE technologies ADD CONSTRAINT technologies_asset_id_name_key UNIQUE (asset_id, name);
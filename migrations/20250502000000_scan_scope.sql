@@ -0,0 +1,3 @@
+-- Domains, IPs and CIDR ranges an organization's scans are restricted to.
+-- An empty list leaves every target not globally blocked in scope.
+ALTER TABLE organizations ADD COLUMN scan_scope TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+-- Deactivated users keep their account and history but can't log in
+ALTER TABLE users ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE;
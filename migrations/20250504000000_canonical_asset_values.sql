@@ -0,0 +1,167 @@
+-- Asset values are now stored in canonical form (see `canonicalize_value`),
+-- but rows written earlier by discovery runs may not be. Live assets whose
+-- values canonicalize to the same thing are merged into the first one seen,
+-- then every live value is rewritten in canonical form.
+--
+-- The migrator splits statements on semicolons, so this file has none
+-- inside a statement.
+
+-- Mirrors `canonicalize_value`, except that IPv6 addresses are only
+-- lowercased. Values that don't parse as a URL are only trimmed.
+CREATE FUNCTION canonical_web_app_value(raw TEXT) RETURNS TEXT AS $$
+    SELECT CASE
+        WHEN parts IS NULL OR parts[2] = '' THEN btrim(raw)
+        ELSE lower(parts[1]) || '://'
+            || CASE
+                WHEN (lower(parts[1]) = 'https' AND parts[2] LIKE '%:443')
+                    OR (lower(parts[1]) = 'http' AND parts[2] LIKE '%:80')
+                    THEN regexp_replace(lower(parts[2]), ':\d+$', '')
+                ELSE lower(parts[2])
+            END
+            || CASE
+                WHEN parts[4] IS NULL THEN rtrim(parts[3], '/')
+                WHEN parts[3] = '' THEN '/'
+                ELSE parts[3]
+            END
+            || COALESCE(parts[4], '')
+    END
+    FROM (
+        SELECT regexp_match(
+            CASE
+                WHEN btrim(raw) ~ '^[A-Za-z][A-Za-z0-9+.-]*://' THEN btrim(raw)
+                ELSE 'https://' || btrim(raw)
+            END,
+            '^([^:/?#]+)://([^/?#]*)([^?#]*)(\?[^#]*)?'
+        ) AS parts
+    ) parsed
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE FUNCTION canonical_asset_value(asset_type TEXT, raw TEXT) RETURNS TEXT AS $$
+    SELECT CASE asset_type
+        WHEN 'DOMAIN' THEN COALESCE(
+            NULLIF(lower(rtrim(
+                CASE
+                    WHEN btrim(raw) LIKE '%://%'
+                        THEN substring(btrim(raw) FROM '^[^:/?#]+://(?:[^@/?#]*@)?(\[[^\]]*\]|[^:/?#]*)')
+                    ELSE btrim(raw)
+                END,
+                '.'
+            )), ''),
+            btrim(raw)
+        )
+        WHEN 'IPADDRESS' THEN lower(btrim(raw))
+        WHEN 'WEBAPP' THEN canonical_web_app_value(raw)
+        ELSE btrim(raw)
+    END
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Merges JSONB objects in aggregation order, later keys winning
+CREATE AGGREGATE merge_asset_attributes (jsonb) (
+    SFUNC = jsonb_concat,
+    STYPE = jsonb,
+    INITCOND = '{}'
+);
+
+-- Each duplicate and the asset it is merged into
+CREATE TEMP TABLE asset_merges AS
+SELECT id, keep_id
+FROM (
+    SELECT id, first_value(id) OVER (
+        PARTITION BY organization_id, asset_type, canonical_asset_value(asset_type, value)
+        ORDER BY first_seen, created_at, id
+    ) AS keep_id
+    FROM assets
+    WHERE deleted_at IS NULL
+) ranked
+WHERE id <> keep_id;
+
+-- The kept asset covers the whole time its duplicates were seen, and takes
+-- attributes from the most recently seen of them
+UPDATE assets keep
+SET first_seen = merged.first_seen,
+    last_seen = merged.last_seen,
+    attributes = merged.attributes,
+    updated_at = NOW()
+FROM (
+    SELECT COALESCE(m.keep_id, a.id) AS keep_id,
+        MIN(a.first_seen) AS first_seen,
+        MAX(a.last_seen) AS last_seen,
+        merge_asset_attributes(COALESCE(a.attributes, '{}'::jsonb) ORDER BY a.last_seen, a.id) AS attributes
+    FROM assets a
+    LEFT JOIN asset_merges m ON m.id = a.id
+    WHERE m.id IS NOT NULL OR a.id IN (SELECT keep_id FROM asset_merges)
+    GROUP BY 1
+) merged
+WHERE keep.id = merged.keep_id;
+
+-- Ports and technologies are unique per asset. Where the kept asset and a
+-- duplicate both have one, the kept asset's row wins, then the newest.
+DELETE FROM ports
+WHERE id IN (
+    SELECT id FROM (
+        SELECT p.id, row_number() OVER (
+            PARTITION BY COALESCE(m.keep_id, p.asset_id), p.port_number, p.protocol
+            ORDER BY m.id IS NULL DESC, p.last_seen DESC, p.id
+        ) AS rank
+        FROM ports p
+        LEFT JOIN asset_merges m ON m.id = p.asset_id
+    ) ranked
+    WHERE rank > 1
+);
+UPDATE ports SET asset_id = m.keep_id FROM asset_merges m WHERE ports.asset_id = m.id;
+
+DELETE FROM technologies
+WHERE id IN (
+    SELECT id FROM (
+        SELECT t.id, row_number() OVER (
+            PARTITION BY COALESCE(m.keep_id, t.asset_id), t.name
+            ORDER BY m.id IS NULL DESC, t.last_seen DESC, t.id
+        ) AS rank
+        FROM technologies t
+        LEFT JOIN asset_merges m ON m.id = t.asset_id
+    ) ranked
+    WHERE rank > 1
+);
+UPDATE technologies SET asset_id = m.keep_id FROM asset_merges m WHERE technologies.asset_id = m.id;
+
+UPDATE vulnerabilities SET asset_id = m.keep_id FROM asset_merges m WHERE vulnerabilities.asset_id = m.id;
+UPDATE asset_history SET asset_id = m.keep_id FROM asset_merges m WHERE asset_history.asset_id = m.id;
+
+-- Rows keyed on the asset are copied over. The originals are removed with
+-- the duplicates below.
+INSERT INTO job_asset_links (job_id, asset_id)
+SELECT l.job_id, m.keep_id
+FROM job_asset_links l
+JOIN asset_merges m ON m.id = l.asset_id
+ON CONFLICT DO NOTHING;
+
+INSERT INTO asset_tags (asset_id, tag, created_at)
+SELECT m.keep_id, t.tag, t.created_at
+FROM asset_tags t
+JOIN asset_merges m ON m.id = t.asset_id
+ON CONFLICT DO NOTHING;
+
+INSERT INTO asset_relationships (source_id, target_id, relationship_type, metadata, created_at)
+SELECT source_id, target_id, relationship_type, metadata, created_at
+FROM (
+    SELECT COALESCE(source.keep_id, r.source_id) AS source_id,
+        COALESCE(target.keep_id, r.target_id) AS target_id,
+        r.relationship_type, r.metadata, r.created_at
+    FROM asset_relationships r
+    LEFT JOIN asset_merges source ON source.id = r.source_id
+    LEFT JOIN asset_merges target ON target.id = r.target_id
+    WHERE source.id IS NOT NULL OR target.id IS NOT NULL
+) merged
+WHERE source_id <> target_id
+ON CONFLICT DO NOTHING;
+
+DELETE FROM assets WHERE id IN (SELECT id FROM asset_merges);
+
+UPDATE assets
+SET value = canonical_asset_value(asset_type, value)
+WHERE deleted_at IS NULL AND value <> canonical_asset_value(asset_type, value);
+
+DROP TABLE asset_merges;
+DROP AGGREGATE merge_asset_attributes (jsonb);
+DROP FUNCTION canonical_asset_value(TEXT, TEXT);
+DROP FUNCTION canonical_web_app_value(TEXT);
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod integration_tests {
-    use backend::models::{Asset, Organization, Vulnerability};
+    use backend::models::{Asset, AssetFilter, Organization, Vulnerability};
     use infrastructure::repositories::RepositoryFactory;
     use shared::{
         config::Config,
@@ -121,9 +121,12 @@ mod integration_tests {
         // List
         let assets = asset_repo
             .list_assets(
-                Some(org.id),
-                Some(AssetType::Domain),
-                Some(AssetStatus::Active),
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::Domain),
+                    status: Some(AssetStatus::Active),
+                    ..Default::default()
+                },
                 10,
                 0,
             )
@@ -205,6 +208,7 @@ mod integration_tests {
                 None,
                 Some(Severity::Medium),
                 Some(VulnerabilityStatus::Open),
+                false,
                 10,
                 0,
             )
@@ -300,14 +304,21 @@ mod integration_tests {
 
         // 4. Verify organization assets can be listed
         let assets = asset_repo
-            .list_assets(Some(org.id), None, None, 10, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
             .await
             .expect("Failed to list organization assets");
         assert_eq!(assets.len(), 2);
 
         // 5. Verify asset vulnerabilities can be queried
         let domain_vulns = vuln_repo
-            .list_vulnerabilities(Some(domain_asset.id), None, None, None, 10, 0)
+            .list_vulnerabilities(Some(domain_asset.id), None, None, None, false, 10, 0)
             .await
             .expect("Failed to list domain vulnerabilities");
         assert_eq!(domain_vulns.len(), 1);
@@ -315,7 +326,7 @@ mod integration_tests {
 
         // 6. Verify filtering works correctly
         let high_vulns = vuln_repo
-            .list_vulnerabilities(None, None, Some(Severity::High), None, 10, 0)
+            .list_vulnerabilities(None, None, Some(Severity::High), None, false, 10, 0)
             .await
             .expect("Failed to list high vulnerabilities");
         assert!(!high_vulns.is_empty());
@@ -91,6 +91,9 @@ mod end_to_end_workflow_tests {
                             } else {
                                 Some(banner.to_string())
                             },
+                            http_status: None,
+                            http_title: None,
+                            tls_info: None,
                             source: "mock_port_scan".to_string(),
                         });
                     }
@@ -140,6 +143,8 @@ mod end_to_end_workflow_tests {
                             title: Some(format!("{} - Homepage", domain)),
                             technologies: vec![], // These will be added to metadata
                             source: "mock_web_discovery".to_string(),
+                            screenshot_path: None,
+                            content_hash: None,
                         });
 
                     result.metadata.insert(web_url, tech_stack.to_string());
@@ -376,7 +381,10 @@ mod end_to_end_workflow_tests {
         let org = create_test_organization(&factory).await;
 
         // Services
-        let asset_service = AssetServiceImpl::new(factory.asset_repository());
+        let asset_service = AssetServiceImpl::new(
+            factory.asset_repository(),
+            factory.asset_history_repository(),
+        );
         let discovery_service = DiscoveryServiceImpl::new(
             factory.asset_repository(),
             factory.discovery_job_repository(),
@@ -708,13 +716,14 @@ mod end_to_end_workflow_tests {
                                     }
                                     .to_string(),
                                 ),
+                                last_seen: Utc::now(),
                                 created_at: Utc::now(),
                                 updated_at: Utc::now(),
                             };
 
                             let _ = factory
                                 .technology_repository()
-                                .create_technology(&tech)
+                                .upsert_technology(&tech)
                                 .await
                                 .expect("Failed to create technology");
                         }
@@ -739,13 +748,14 @@ mod end_to_end_workflow_tests {
                         name: tech_name,
                         version: Some(tech_version),
                         category: Some("Web Server".to_string()),
+                        last_seen: Utc::now(),
                         created_at: Utc::now(),
                         updated_at: Utc::now(),
                     };
 
                     let _ = factory
                         .technology_repository()
-                        .create_technology(&tech)
+                        .upsert_technology(&tech)
                         .await
                         .expect("Failed to create web server technology");
                 }
@@ -827,17 +837,17 @@ mod end_to_end_workflow_tests {
 
         // Verify assets exist
         let domain_count = asset_service
-            .count_assets(Some(org.id), Some(AssetType::Domain), None)
+            .count_assets(Some(org.id), Some(AssetType::Domain), None, None)
             .await
             .expect("Failed to count domain assets");
 
         let ip_count = asset_service
-            .count_assets(Some(org.id), Some(AssetType::IPAddress), None)
+            .count_assets(Some(org.id), Some(AssetType::IPAddress), None, None)
             .await
             .expect("Failed to count IP assets");
 
         let web_count = asset_service
-            .count_assets(Some(org.id), Some(AssetType::WebApp), None)
+            .count_assets(Some(org.id), Some(AssetType::WebApp), None, None)
             .await
             .expect("Failed to count web app assets");
 
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod port_integration_tests {
-    use backend::models::{Asset, Organization, Port};
+    use backend::models::{Asset, AssetFilter, Organization, Port};
     use infrastructure::repositories::RepositoryFactory;
     use shared::{
         config::Config,
@@ -222,17 +222,41 @@ mod port_integration_tests {
 
         // Verify relationships
         let domain_assets = asset_repo
-            .list_assets(Some(org.id), Some(AssetType::Domain), None, 10, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::Domain),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
             .await
             .expect("Failed to list domain assets");
 
         let ip_assets = asset_repo
-            .list_assets(Some(org.id), Some(AssetType::IPAddress), None, 10, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::IPAddress),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
             .await
             .expect("Failed to list IP assets");
 
         let web_app_assets = asset_repo
-            .list_assets(Some(org.id), Some(AssetType::WebApp), None, 10, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(org.id),
+                    asset_type: Some(AssetType::WebApp),
+                    ..Default::default()
+                },
+                10,
+                0,
+            )
             .await
             .expect("Failed to list web app assets");
 
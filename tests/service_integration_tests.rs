@@ -233,7 +233,10 @@ mod service_integration_tests {
             .expect("Failed to create organization");
 
         // Create the asset service
-        let asset_service = backend::services::AssetServiceImpl::new(asset_repo.clone());
+        let asset_service = backend::services::AssetServiceImpl::new(
+            asset_repo.clone(),
+            factory.asset_history_repository(),
+        );
 
         // Test the asset service
         let asset = Asset::new(
@@ -275,7 +278,7 @@ mod service_integration_tests {
 
         // List assets
         let assets = asset_service
-            .list_assets(Some(created_org.id), None, None, 10, 0)
+            .list_assets(Some(created_org.id), None, None, None, 10, 0)
             .await
             .expect("Failed to list assets");
 
@@ -314,7 +317,10 @@ mod service_integration_tests {
         let assets = create_test_assets(&factory, created_org.id).await;
 
         // Create the asset service
-        let asset_service = backend::services::AssetServiceImpl::new(factory.asset_repository());
+        let asset_service = backend::services::AssetServiceImpl::new(
+            factory.asset_repository(),
+            factory.asset_history_repository(),
+        );
 
         // Test relationship discovery
         let relationships = asset_service
@@ -382,7 +388,7 @@ mod service_integration_tests {
 
         // Query related assets
         let related_assets = asset_service
-            .get_related_assets(web_app.id, None)
+            .get_related_assets(web_app.id, None, 100, 0)
             .await
             .expect("Failed to get related assets");
 
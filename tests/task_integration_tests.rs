@@ -3,7 +3,7 @@ mod task_integration_tests {
     use anyhow::Result;
     use backend::traits::DiscoveryJobRepository;
     use backend::{
-        models::{Asset, DiscoveryJob, Organization},
+        models::{Asset, AssetFilter, DiscoveryJob, Organization},
         traits::AssetService,
     };
     use discovery::results::DiscoveryResult;
@@ -130,7 +130,10 @@ mod task_integration_tests {
 
         // Initialize repositories
         let asset_repo = factory.asset_repository();
-        let asset_service = backend::services::AssetServiceImpl::new(asset_repo.clone());
+        let asset_service = backend::services::AssetServiceImpl::new(
+            asset_repo.clone(),
+            factory.asset_history_repository(),
+        );
         let job_repo = factory.discovery_job_repository();
 
         // Create a discovery job
@@ -172,7 +175,15 @@ mod task_integration_tests {
 
         // Find assets by domain pattern
         let all_assets = asset_repo
-            .list_assets(Some(created_org.id), Some(AssetType::Domain), None, 20, 0)
+            .list_assets(
+                &AssetFilter {
+                    organization_id: Some(created_org.id),
+                    asset_type: Some(AssetType::Domain),
+                    ..Default::default()
+                },
+                20,
+                0,
+            )
             .await
             .expect("Failed to list assets");
 
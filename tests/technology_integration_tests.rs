@@ -65,6 +65,7 @@ mod technology_integration_tests {
                 name: name.to_string(),
                 version: Some(version.to_string()),
                 category: Some(category.to_string()),
+                last_seen: now,
                 created_at: now,
                 updated_at: now,
             };
@@ -212,6 +213,7 @@ mod technology_integration_tests {
             name: "Nginx".to_string(),
             version: Some("1.18.0".to_string()),
             category: Some("Web Server".to_string()),
+            last_seen: now,
             created_at: now,
             updated_at: now,
         };
@@ -227,6 +229,7 @@ mod technology_integration_tests {
             name: "PHP".to_string(),
             version: Some("7.4.3".to_string()),
             category: Some("Programming Language".to_string()),
+            last_seen: now,
             created_at: now,
             updated_at: now,
         };
@@ -243,6 +246,7 @@ mod technology_integration_tests {
             name: "WordPress".to_string(),
             version: Some("5.9".to_string()),
             category: Some("CMS".to_string()),
+            last_seen: now,
             created_at: now,
             updated_at: now,
         };
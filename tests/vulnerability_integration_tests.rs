@@ -139,7 +139,7 @@ mod vulnerability_integration_tests {
 
         // Test vulnerability retrieval by asset
         let web_vulns = vuln_repo
-            .list_vulnerabilities(Some(web_asset.id), None, None, None, 10, 0)
+            .list_vulnerabilities(Some(web_asset.id), None, None, None, false, 10, 0)
             .await
             .expect("Failed to get vulnerabilities by web asset");
 
@@ -147,7 +147,7 @@ mod vulnerability_integration_tests {
         assert_eq!(web_vulns[0].title, "Cross-Site Scripting (XSS)");
 
         let ip_vulns = vuln_repo
-            .list_vulnerabilities(Some(ip_asset.id), None, None, None, 10, 0)
+            .list_vulnerabilities(Some(ip_asset.id), None, None, None, false, 10, 0)
             .await
             .expect("Failed to get vulnerabilities by IP asset");
 
@@ -156,7 +156,7 @@ mod vulnerability_integration_tests {
 
         // Test vulnerability retrieval by port
         let port_vulns = vuln_repo
-            .list_vulnerabilities(None, Some(ssh_port.id), None, None, 10, 0)
+            .list_vulnerabilities(None, Some(ssh_port.id), None, None, false, 10, 0)
             .await
             .expect("Failed to get vulnerabilities by port");
 
@@ -165,7 +165,7 @@ mod vulnerability_integration_tests {
 
         // Test vulnerability retrieval by severity
         let high_vulns = vuln_repo
-            .list_vulnerabilities(None, None, Some(Severity::High), None, 10, 0)
+            .list_vulnerabilities(None, None, Some(Severity::High), None, false, 10, 0)
             .await
             .expect("Failed to get high severity vulnerabilities");
 
@@ -239,7 +239,7 @@ mod vulnerability_integration_tests {
         // Clean up any potential stale data from previous test runs
         // First, get all expired cert vulnerabilities
         let stale_vulns = vuln_repo
-            .list_vulnerabilities(None, None, None, None, 1000, 0)
+            .list_vulnerabilities(None, None, None, None, false, 1000, 0)
             .await
             .expect("Failed to get vulnerabilities");
 
@@ -392,7 +392,7 @@ mod vulnerability_integration_tests {
         // Since we don't have get_vulnerabilities_by_organization,
         // we'll list all vulnerabilities (limit high) and filter for this org
         let all_vulns = vuln_repo
-            .list_vulnerabilities(None, None, None, None, 100, 0)
+            .list_vulnerabilities(None, None, None, None, false, 100, 0)
             .await
             .expect("Failed to get all vulnerabilities");
 
@@ -414,12 +414,12 @@ mod vulnerability_integration_tests {
         // Test vulnerability statistics by severity
         // Count using organization's assets for proper filtering
         let critical_count = vuln_repo
-            .count_vulnerabilities(Some(org.id), None, Some(Severity::Critical), None)
+            .count_vulnerabilities(Some(org.id), None, Some(Severity::Critical), None, false)
             .await
             .expect("Failed to count critical vulnerabilities");
 
         let medium_count = vuln_repo
-            .count_vulnerabilities(Some(org.id), None, Some(Severity::Medium), None)
+            .count_vulnerabilities(Some(org.id), None, Some(Severity::Medium), None, false)
             .await
             .expect("Failed to count medium vulnerabilities");
 
@@ -434,7 +434,7 @@ mod vulnerability_integration_tests {
         // Instead of get_vulnerabilities_by_title
         // List all and filter by title
         let all_vulns = vuln_repo
-            .list_vulnerabilities(None, None, None, None, 100, 0)
+            .list_vulnerabilities(None, None, None, None, false, 100, 0)
             .await
             .expect("Failed to get all vulnerabilities");
 